@@ -0,0 +1,253 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Flow solver: a row which wraps onto new lines
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use crate::geom::{Coord, Rect, Size};
+
+/// Storage for [`FlowSolver`] and [`FlowSetter`]
+///
+/// Details are hidden (for internal use only).
+#[derive(Clone, Debug, Default)]
+pub struct FlowStorage {
+    // Horizontal rules of each child
+    widths: Vec<SizeRules>,
+    // Width of the last vertical solve
+    width: u32,
+    // Index of the first child of each line
+    starts: Vec<usize>,
+    // Vertical rules of each line, followed by the total
+    heights: Vec<SizeRules>,
+}
+
+impl Storage for FlowStorage {}
+
+impl FlowStorage {
+    /// Number of lines, as found by the last vertical solve
+    pub fn lines(&self) -> usize {
+        self.starts.len()
+    }
+
+    // Width given to a child within the given total width
+    fn child_width(&self, index: usize, width: u32) -> u32 {
+        let rules = self.widths[index];
+        rules.ideal_size().min(width).max(rules.min_size())
+    }
+
+    // Break children into lines within `width`
+    //
+    // Lines are filled greedily; each line has at least one child.
+    fn break_lines(&mut self, width: u32) {
+        self.width = width;
+        self.starts.clear();
+        let mut used = 0;
+        for index in 0..self.widths.len() {
+            let w = self.child_width(index, width);
+            if self.starts.is_empty() || used + w > width {
+                self.starts.push(index);
+                used = 0;
+            }
+            used += w;
+        }
+        self.heights.clear();
+        self.heights.resize(self.starts.len() + 1, SizeRules::EMPTY);
+    }
+}
+
+/// A [`RulesSolver`] for flow layouts
+///
+/// Children are placed left-to-right, starting a new line each time the width
+/// is exhausted. Each child is given its ideal width (or the whole width if
+/// less, though never less than its minimum); lines are as high as their
+/// highest child. Children must be passed in order.
+///
+/// The horizontal axis must be solved first. The minimum width is that of the
+/// widest child while the ideal width places all children on a single line.
+/// Lines are broken according to the width fixed when solving the vertical
+/// axis (or the ideal width if not fixed); [`FlowSetter`] uses these lines.
+///
+/// ```
+/// use kas_layout::geom::{Coord, Rect, Size};
+/// use kas_layout::{AxisInfo, FlowSetter, FlowSolver, FlowStorage};
+/// use kas_layout::{RulesSetter, RulesSolver, SizeRules};
+///
+/// let widths = [40, 30, 50];
+/// let no_spans = || std::iter::empty::<(usize, usize, usize)>();
+/// let mut storage = FlowStorage::default();
+///
+/// let axis = AxisInfo::new(false, None);
+/// let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+/// for (i, w) in widths.iter().enumerate() {
+///     solver.for_child(&mut storage, i, |_| SizeRules::fixed(*w));
+/// }
+/// let rules = solver.finish(&mut storage, no_spans(), no_spans());
+/// assert_eq!((rules.min_size(), rules.ideal_size()), (50, 120));
+///
+/// let axis = AxisInfo::new(true, Some(80));
+/// let mut solver = FlowSolver::new(axis, widths.len(), &mut storage);
+/// for i in 0..widths.len() {
+///     solver.for_child(&mut storage, i, |_| SizeRules::fixed(10));
+/// }
+/// let rules = solver.finish(&mut storage, no_spans(), no_spans());
+/// assert_eq!(rules.ideal_size(), 20);
+/// assert_eq!(storage.lines(), 2);
+///
+/// let rect = Rect { pos: Coord(0, 0), size: Size(80, 20) };
+/// let mut setter = FlowSetter::new(rect, widths.len(), &mut storage);
+/// let rect = setter.child_rect(2);
+/// assert_eq!((rect.pos, rect.size), (Coord(0, 10), Size(50, 10)));
+/// ```
+pub struct FlowSolver {
+    axis: AxisInfo,
+    rules: SizeRules,
+    // Horizontal: maximal minimum and sum of ideal widths
+    min: u32,
+    ideal: u32,
+    // Vertical: current line
+    line: usize,
+}
+
+impl FlowSolver {
+    /// Construct.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, len: usize, storage: &mut FlowStorage) -> Self {
+        if !axis.vertical() {
+            storage.widths.clear();
+            storage.widths.resize(len, SizeRules::EMPTY);
+            storage.starts.clear();
+            storage.heights.clear();
+        } else {
+            assert_eq!(storage.widths.len(), len);
+            let width = match axis.fixed(true) {
+                Some(width) => width,
+                None => storage.widths.iter().map(|r| r.ideal_size()).sum(),
+            };
+            storage.break_lines(width);
+        }
+
+        FlowSolver {
+            axis,
+            rules: SizeRules::EMPTY,
+            min: 0,
+            ideal: 0,
+            line: 0,
+        }
+    }
+}
+
+impl RulesSolver for FlowSolver {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        child_info: Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        if !self.axis.vertical() {
+            let rules = child_rules(self.axis);
+            storage.widths[child_info] = rules;
+            self.min = self.min.max(rules.min_size());
+            self.ideal += rules.ideal_size();
+        } else {
+            while self.line + 1 < storage.lines() && storage.starts[self.line + 1] <= child_info {
+                self.line += 1;
+            }
+            let width = storage.child_width(child_info, storage.width);
+            let rules = child_rules(AxisInfo::new(true, Some(width)));
+            let line = &mut storage.heights[self.line];
+            *line = line.max(rules);
+        }
+    }
+
+    fn finish<ColIter, RowIter>(
+        mut self,
+        storage: &mut Self::Storage,
+        _: ColIter,
+        _: RowIter,
+    ) -> SizeRules
+    where
+        ColIter: Iterator<Item = (usize, usize, usize)>,
+        RowIter: Iterator<Item = (usize, usize, usize)>,
+    {
+        if !self.axis.vertical() {
+            self.rules = SizeRules::variable(self.min, self.ideal);
+        } else {
+            let lines = storage.lines();
+            for i in 0..lines {
+                self.rules += storage.heights[i];
+            }
+            storage.heights[lines] = self.rules;
+        }
+        self.rules
+    }
+}
+
+/// A [`RulesSetter`] for flow layouts
+///
+/// See [`FlowSolver`]. Lines are as found by the last vertical solve; if the
+/// width has since changed, children are resized but not moved between lines.
+/// Extra width is left at the end of each line; extra height is distributed
+/// between lines.
+pub struct FlowSetter {
+    rects: Vec<Rect>,
+}
+
+impl FlowSetter {
+    /// Construct.
+    ///
+    /// - `rect`: the [`Rect`] within which to position children
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(rect: Rect, len: usize, storage: &mut FlowStorage) -> Self {
+        assert_eq!(storage.widths.len(), len);
+        if storage.heights.len() != storage.lines() + 1 {
+            // Vertical axis not solved
+            storage.break_lines(rect.size.0);
+        }
+
+        let lines = storage.lines();
+        let mut heights = vec![0; lines];
+        if lines > 0 {
+            SizeRules::solve_seq(&mut heights, &storage.heights, rect.size.1);
+        }
+
+        let mut rects = Vec::with_capacity(len);
+        let mut y = rect.pos.1;
+        for (line, height) in heights.iter().cloned().enumerate() {
+            let start = storage.starts[line];
+            let end = storage.starts.get(line + 1).cloned().unwrap_or(len);
+            let mut x = rect.pos.0;
+            for index in start..end {
+                let w = storage.child_width(index, rect.size.0);
+                rects.push(Rect {
+                    pos: Coord(x, y),
+                    size: Size(w, height),
+                });
+                x += w as i32;
+            }
+            y += height as i32;
+        }
+
+        FlowSetter { rects }
+    }
+}
+
+impl RulesSetter for FlowSetter {
+    type Storage = FlowStorage;
+    type ChildInfo = usize;
+
+    fn child_rect(&mut self, index: Self::ChildInfo) -> Rect {
+        self.rects[index]
+    }
+}
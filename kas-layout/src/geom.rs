@@ -5,11 +5,14 @@
 
 //! Geometry data types
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "winit")]
 use winit::dpi::{PhysicalPosition, PhysicalSize};
 
 /// An `(x, y)` coordinate.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coord(pub i32, pub i32);
 
 impl Coord {
@@ -43,7 +46,7 @@ impl From<Size> for Coord {
     }
 }
 
-impl std::ops::Add for Coord {
+impl core::ops::Add for Coord {
     type Output = Self;
 
     #[inline]
@@ -52,7 +55,7 @@ impl std::ops::Add for Coord {
     }
 }
 
-impl std::ops::Sub for Coord {
+impl core::ops::Sub for Coord {
     type Output = Self;
 
     #[inline]
@@ -61,7 +64,7 @@ impl std::ops::Sub for Coord {
     }
 }
 
-impl std::ops::Add<Size> for Coord {
+impl core::ops::Add<Size> for Coord {
     type Output = Self;
 
     #[inline]
@@ -87,7 +90,7 @@ impl From<Coord> for PhysicalPosition {
     }
 }
 
-impl std::ops::AddAssign<Size> for Coord {
+impl core::ops::AddAssign<Size> for Coord {
     #[inline]
     fn add_assign(&mut self, rhs: Size) {
         self.0 += rhs.0 as i32;
@@ -97,6 +100,7 @@ impl std::ops::AddAssign<Size> for Coord {
 
 /// A `(w, h)` size.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Size(pub u32, pub u32);
 
 impl Size {
@@ -145,7 +149,7 @@ impl From<Size> for PhysicalSize {
     }
 }
 
-impl std::ops::Add for Size {
+impl core::ops::Add for Size {
     type Output = Self;
 
     #[inline]
@@ -154,7 +158,7 @@ impl std::ops::Add for Size {
     }
 }
 
-impl std::ops::Sub for Size {
+impl core::ops::Sub for Size {
     type Output = Self;
 
     #[inline]
@@ -163,7 +167,7 @@ impl std::ops::Sub for Size {
     }
 }
 
-impl std::ops::AddAssign for Size {
+impl core::ops::AddAssign for Size {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
         self.0 += rhs.0;
@@ -171,7 +175,7 @@ impl std::ops::AddAssign for Size {
     }
 }
 
-impl std::ops::SubAssign for Size {
+impl core::ops::SubAssign for Size {
     #[inline]
     fn sub_assign(&mut self, rhs: Self) {
         self.0 -= rhs.0;
@@ -181,12 +185,19 @@ impl std::ops::SubAssign for Size {
 
 /// A rectangular region.
 #[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect {
     pub pos: Coord,
     pub size: Size,
 }
 
 impl Rect {
+    /// An empty rect at the origin
+    pub const ZERO: Rect = Rect {
+        pos: Coord::ZERO,
+        size: Size::ZERO,
+    };
+
     /// Check whether the given coordinate is contained within this rect
     #[inline]
     pub fn contains(&self, c: Coord) -> bool {
@@ -195,9 +206,26 @@ impl Rect {
             && c.1 >= self.pos.1
             && c.1 < self.pos.1 + (self.size.1 as i32)
     }
+
+    /// Calculate the intersection of two rects
+    ///
+    /// Returns `None` if the rects do not overlap (or the overlap is empty).
+    #[inline]
+    pub fn intersection(&self, rhs: &Rect) -> Option<Rect> {
+        let (l1, l2) = (self.pos, self.pos + self.size);
+        let (r1, r2) = (rhs.pos, rhs.pos + rhs.size);
+        let pos = l1.max(r1);
+        let end = l2.min(r2);
+        if end.0 > pos.0 && end.1 > pos.1 {
+            let size = Size((end.0 - pos.0) as u32, (end.1 - pos.1) as u32);
+            Some(Rect { pos, size })
+        } else {
+            None
+        }
+    }
 }
 
-impl std::ops::Add<Coord> for Rect {
+impl core::ops::Add<Coord> for Rect {
     type Output = Self;
 
     #[inline]
@@ -210,7 +238,7 @@ impl std::ops::Add<Coord> for Rect {
     }
 }
 
-impl std::ops::Sub<Coord> for Rect {
+impl core::ops::Sub<Coord> for Rect {
     type Output = Self;
 
     #[inline]
@@ -5,7 +5,7 @@
 
 //! Row / column solver
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use super::{AxisInfo, GridStorage, Margins, RowTemp, RulesSetter, RulesSolver, SizeRules};
 use crate::geom::{Coord, Rect, Size};
@@ -133,13 +133,13 @@ where
         }
         let child_rules = child_rules(self.axis);
         let rules = if !self.axis.vertical {
-            if child_info.col_span_index == std::usize::MAX {
+            if child_info.col_span_index == usize::MAX {
                 &mut storage.width_mut()[child_info.col]
             } else {
                 &mut self.col_span_rules.as_mut()[child_info.col_span_index]
             }
         } else {
-            if child_info.row_span_index == std::usize::MAX {
+            if child_info.row_span_index == usize::MAX {
                 &mut storage.height_mut()[child_info.row]
             } else {
                 &mut self.row_span_rules.as_mut()[child_info.row_span_index]
@@ -0,0 +1,132 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! KAS layout engine
+//!
+//! This crate provides the geometry types ([`geom`]), [`SizeRules`] and the
+//! row, grid, flow and overlay solvers used by KAS. It is `no_std` (requiring only `alloc`)
+//! and independent of the widget and toolkit APIs.
+//!
+//! Users of KAS should not need to depend on this crate directly: its contents
+//! are re-exported by `kas::geom` and `kas::layout`.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod geom;
+
+mod flow_solver;
+mod grid_solver;
+mod misc_solver;
+mod overlay_solver;
+mod overrides;
+mod row_solver;
+mod size_rules;
+mod solver;
+mod storage;
+
+use core::fmt;
+
+use geom::Size;
+
+pub use flow_solver::{FlowSetter, FlowSolver, FlowStorage};
+pub use grid_solver::{GridChildInfo, GridSetter, GridSolver};
+pub use misc_solver::SingleSetter;
+pub use overlay_solver::{Anchor, OverlaySetter, OverlaySolver, OverlayStorage, Placement};
+pub use overrides::LayoutOverrides;
+pub use row_solver::{RowSetter, RowSolver};
+pub use size_rules::{Margins, SizeRules};
+pub use solver::{RulesSetter, RulesSolver};
+pub use storage::{
+    DynGridStorage, DynRowStorage, FixedGridStorage, FixedRowStorage, GridStorage, RowStorage,
+    RowTemp, Storage,
+};
+
+/// Information on which axis is being resized
+///
+/// Also conveys the size of the other axis, if fixed.
+#[derive(Copy, Clone, Debug)]
+pub struct AxisInfo {
+    vertical: bool,
+    has_fixed: bool,
+    other_axis: u32,
+}
+
+impl AxisInfo {
+    /// Construct
+    ///
+    /// Usually this is only used by the layout solver, but it may also be
+    /// used for diagnostic purposes.
+    #[inline]
+    pub fn new(vertical: bool, fixed: Option<u32>) -> Self {
+        AxisInfo {
+            vertical,
+            has_fixed: fixed.is_some(),
+            other_axis: fixed.unwrap_or(0),
+        }
+    }
+
+    /// True if the current axis is vertical, false if horizontal
+    #[inline]
+    pub fn vertical(&self) -> bool {
+        self.vertical
+    }
+
+    /// Size of other axis, if fixed and `vertical == self.vertical()`.
+    #[inline]
+    pub fn fixed(&self, vertical: bool) -> Option<u32> {
+        if vertical == self.vertical && self.has_fixed {
+            Some(self.other_axis)
+        } else {
+            None
+        }
+    }
+
+    /// Extract horizontal or vertical component of a [`Size`]
+    #[inline]
+    pub fn extract_size(&self, size: Size) -> u32 {
+        if !self.vertical {
+            size.0
+        } else {
+            size.1
+        }
+    }
+}
+
+pub trait Direction: Copy + Sized + fmt::Debug + 'static {
+    fn is_vertical(self) -> bool;
+    fn is_horizontal(self) -> bool {
+        !self.is_vertical()
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Horizontal;
+impl Direction for Horizontal {
+    fn is_vertical(self) -> bool {
+        false
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Vertical;
+impl Direction for Vertical {
+    fn is_vertical(self) -> bool {
+        true
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DynDirection(bool);
+impl Direction for DynDirection {
+    fn is_vertical(self) -> bool {
+        self.0
+    }
+}
+impl DynDirection {
+    pub const HORIZONTAL: DynDirection = DynDirection(false);
+    pub const VERTICAL: DynDirection = DynDirection(true);
+}
@@ -0,0 +1,267 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Overlay solver: children stacked at anchored positions
+
+use alloc::vec::Vec;
+
+use super::{AxisInfo, RulesSetter, RulesSolver, SizeRules, Storage};
+use crate::geom::{Coord, Rect, Size};
+
+/// Placement of a child along one axis of an overlay
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Placement {
+    /// Place at the left or top
+    Begin,
+    /// Place in the centre
+    Centre,
+    /// Place at the right or bottom
+    End,
+    /// Fill the whole axis
+    Stretch,
+}
+
+/// Position of a child within an overlay
+///
+/// Along each axis, the child is placed (at its ideal size, where possible)
+/// according to a [`Placement`], then moved by `offset`. Thus an offset of
+/// `Coord(-4, -4)` with [`Placement::End`] positions a child 4 pixels from the
+/// bottom-right corner, while `Coord(4, 4)` lets it overhang the corner.
+/// With [`Placement::Stretch`], the offset instead gives a margin on both
+/// sides (negative values are treated as zero).
+///
+/// The overlay is made large enough to contain each child, except that
+/// children may overhang an edge by moving away from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Anchor {
+    /// Horizontal placement
+    pub horiz: Placement,
+    /// Vertical placement
+    pub vert: Placement,
+    /// Offset from the placed position
+    pub offset: Coord,
+}
+
+impl Anchor {
+    /// Fill the whole overlay (the usual placement of the base layer)
+    pub const FILL: Anchor = Anchor::new(Placement::Stretch, Placement::Stretch);
+    /// Top-left corner
+    pub const TOP_LEFT: Anchor = Anchor::new(Placement::Begin, Placement::Begin);
+    /// Top-right corner
+    pub const TOP_RIGHT: Anchor = Anchor::new(Placement::End, Placement::Begin);
+    /// Bottom-left corner
+    pub const BOTTOM_LEFT: Anchor = Anchor::new(Placement::Begin, Placement::End);
+    /// Bottom-right corner
+    pub const BOTTOM_RIGHT: Anchor = Anchor::new(Placement::End, Placement::End);
+    /// Centre
+    pub const CENTRE: Anchor = Anchor::new(Placement::Centre, Placement::Centre);
+
+    /// Construct, with zero offset
+    #[inline]
+    pub const fn new(horiz: Placement, vert: Placement) -> Self {
+        Anchor {
+            horiz,
+            vert,
+            offset: Coord(0, 0),
+        }
+    }
+
+    /// Set the offset (inline)
+    #[inline]
+    pub const fn with_offset(mut self, offset: Coord) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    // Placement and offset along the given axis
+    fn axis(&self, vertical: bool) -> (Placement, i32) {
+        match vertical {
+            false => (self.horiz, self.offset.0),
+            true => (self.vert, self.offset.1),
+        }
+    }
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::FILL
+    }
+}
+
+// Size required around a child along one axis
+fn extra(placement: Placement, offset: i32) -> u32 {
+    match placement {
+        Placement::Begin => offset.max(0) as u32,
+        Placement::End => (-offset).max(0) as u32,
+        Placement::Centre => 2 * offset.unsigned_abs(),
+        Placement::Stretch => 2 * offset.max(0) as u32,
+    }
+}
+
+// Position and size of a child along one axis
+fn place(pos: i32, size: u32, rules: SizeRules, placement: Placement, offset: i32) -> (i32, u32) {
+    let avail = size.saturating_sub(extra(placement, offset));
+    let s = match placement {
+        Placement::Stretch => avail,
+        _ => rules.ideal_size().min(avail).max(rules.min_size()),
+    };
+    let space = size as i32 - s as i32;
+    match placement {
+        Placement::Begin => (pos + offset, s),
+        Placement::Centre => (pos + space / 2 + offset, s),
+        Placement::End => (pos + space + offset, s),
+        Placement::Stretch => (pos + offset.max(0), s),
+    }
+}
+
+/// Storage for [`OverlaySolver`] and [`OverlaySetter`]
+///
+/// Details are hidden (for internal use only).
+#[derive(Clone, Debug, Default)]
+pub struct OverlayStorage {
+    // Horizontal and vertical rules of each child
+    rules: Vec<(SizeRules, SizeRules)>,
+}
+
+impl Storage for OverlayStorage {}
+
+/// A [`RulesSolver`] for overlays
+///
+/// Children are stacked on top of each other, each positioned within the
+/// whole area according to its [`Anchor`]. The result is the maximum of the
+/// rules of each child (including space required by offsets).
+///
+/// The child info is the pair `(index, anchor)`.
+///
+/// ```
+/// use kas_layout::geom::{Coord, Rect, Size};
+/// use kas_layout::{Anchor, AxisInfo, OverlaySetter, OverlaySolver, OverlayStorage};
+/// use kas_layout::{RulesSetter, RulesSolver, SizeRules};
+///
+/// let children = [(Anchor::FILL, 100), (Anchor::TOP_RIGHT.with_offset(Coord(-5, 5)), 20)];
+/// let no_spans = || std::iter::empty::<(usize, usize, usize)>();
+/// let mut storage = OverlayStorage::default();
+///
+/// for vertical in [false, true].iter() {
+///     let axis = AxisInfo::new(*vertical, None);
+///     let mut solver = OverlaySolver::new(axis, children.len(), &mut storage);
+///     for (i, (anchor, size)) in children.iter().enumerate() {
+///         solver.for_child(&mut storage, (i, *anchor), |_| SizeRules::fixed(*size));
+///     }
+///     let rules = solver.finish(&mut storage, no_spans(), no_spans());
+///     assert_eq!(rules.ideal_size(), 100);
+/// }
+///
+/// let rect = Rect { pos: Coord(0, 0), size: Size(100, 100) };
+/// let mut setter = OverlaySetter::new(rect, children.len(), &mut storage);
+/// let rect = setter.child_rect((1, children[1].0));
+/// assert_eq!((rect.pos, rect.size), (Coord(75, 5), Size(20, 20)));
+/// ```
+pub struct OverlaySolver {
+    axis: AxisInfo,
+    rules: SizeRules,
+}
+
+impl OverlaySolver {
+    /// Construct.
+    ///
+    /// - `axis`: `AxisInfo` instance passed into `size_rules`
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(axis: AxisInfo, len: usize, storage: &mut OverlayStorage) -> Self {
+        storage
+            .rules
+            .resize(len, (SizeRules::EMPTY, SizeRules::EMPTY));
+        OverlaySolver {
+            axis,
+            rules: SizeRules::EMPTY,
+        }
+    }
+}
+
+impl RulesSolver for OverlaySolver {
+    type Storage = OverlayStorage;
+    type ChildInfo = (usize, Anchor);
+
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        (index, anchor): Self::ChildInfo,
+        child_rules: CR,
+    ) {
+        let vertical = self.axis.vertical();
+        let mut axis = self.axis;
+        if let Some(width) = self.axis.fixed(true) {
+            // Pass the width this child will be given
+            let (placement, offset) = anchor.axis(false);
+            let (_, width) = place(0, width, storage.rules[index].0, placement, offset);
+            axis = AxisInfo::new(true, Some(width));
+        }
+        let rules = child_rules(axis);
+        match vertical {
+            false => storage.rules[index].0 = rules,
+            true => storage.rules[index].1 = rules,
+        }
+        let (placement, offset) = anchor.axis(vertical);
+        let rules = rules + SizeRules::fixed(extra(placement, offset));
+        self.rules = self.rules.max(rules);
+    }
+
+    fn finish<ColIter, RowIter>(self, _: &mut Self::Storage, _: ColIter, _: RowIter) -> SizeRules
+    where
+        ColIter: Iterator<Item = (usize, usize, usize)>,
+        RowIter: Iterator<Item = (usize, usize, usize)>,
+    {
+        self.rules
+    }
+}
+
+/// A [`RulesSetter`] for overlays
+///
+/// See [`OverlaySolver`].
+pub struct OverlaySetter {
+    rect: Rect,
+    rules: Vec<(SizeRules, SizeRules)>,
+}
+
+impl OverlaySetter {
+    /// Construct.
+    ///
+    /// - `rect`: the [`Rect`] within which to position children
+    /// - `len`: number of children
+    /// - `storage`: reference to persistent storage
+    pub fn new(rect: Rect, len: usize, storage: &mut OverlayStorage) -> Self {
+        assert_eq!(storage.rules.len(), len);
+        let rules = storage.rules.clone();
+        OverlaySetter { rect, rules }
+    }
+}
+
+impl RulesSetter for OverlaySetter {
+    type Storage = OverlayStorage;
+    type ChildInfo = (usize, Anchor);
+
+    fn child_rect(&mut self, (index, anchor): Self::ChildInfo) -> Rect {
+        let (rect, rules) = (self.rect, self.rules[index]);
+        let (x, w) = place(
+            rect.pos.0,
+            rect.size.0,
+            rules.0,
+            anchor.horiz,
+            anchor.offset.0,
+        );
+        let (y, h) = place(
+            rect.pos.1,
+            rect.size.1,
+            rules.1,
+            anchor.vert,
+            anchor.offset.1,
+        );
+        Rect {
+            pos: Coord(x, y),
+            size: Size(w, h),
+        }
+    }
+}
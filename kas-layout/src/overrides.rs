@@ -0,0 +1,146 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Persistent layout overrides
+
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::SizeRules;
+
+/// User adjustments to a sequence of sizes
+///
+/// Widgets whose parts may be resized by the user (e.g. the children of a
+/// `Splitter` or the columns of a `Table`) keep one of these. After the
+/// usual rule-based solution is found, the widget passes it to
+/// [`LayoutOverrides::apply`], which replaces it with the user's adjustments,
+/// if any.
+///
+/// Adjustments are stored as the position of the end of each part (except
+/// the last), as a fraction of the total length. Thus, when the total length
+/// changes, adjusted parts are scaled proportionally instead of being reset;
+/// and when the rules change (for example on reconfigure) the adjustments
+/// persist, subject only to new minimum sizes. Adjustments are ignored when
+/// the number of parts does not match.
+///
+/// ```
+/// use kas_layout::{LayoutOverrides, SizeRules};
+///
+/// let rules = [SizeRules::variable(10, 50); 3];
+/// let mut overrides = LayoutOverrides::default();
+/// overrides.record(&[50, 50, 100]);
+///
+/// let mut sizes = [0; 3];
+/// assert!(overrides.apply(&mut sizes, &rules, 400));
+/// assert_eq!(sizes, [100, 100, 200]);
+///
+/// // Move the first end, within the limits of its neighbours
+/// assert!(overrides.move_end(0, 0.375));
+/// overrides.apply(&mut sizes, &rules, 400);
+/// assert_eq!(sizes, [150, 50, 200]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LayoutOverrides {
+    ends: Vec<f32>,
+}
+
+impl LayoutOverrides {
+    /// Construct from a list of ends
+    ///
+    /// See [`LayoutOverrides::ends`].
+    #[inline]
+    pub fn from_ends(ends: Vec<f32>) -> Self {
+        LayoutOverrides { ends }
+    }
+
+    /// Get the ends
+    ///
+    /// This is the position of the end of each part (except the last), as a
+    /// fraction of the total length, in increasing order. It is empty unless
+    /// adjustments have been made.
+    #[inline]
+    pub fn ends(&self) -> &[f32] {
+        &self.ends
+    }
+
+    /// True if there are adjustments for `parts` parts
+    #[inline]
+    pub fn matches(&self, parts: usize) -> bool {
+        parts > 0 && self.ends.len() + 1 == parts
+    }
+
+    /// Remove all adjustments
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ends.clear();
+    }
+
+    /// Record `sizes` as the adjusted sizes
+    ///
+    /// Usually this is called with the current (solved) sizes before the
+    /// first call to [`LayoutOverrides::move_end`].
+    pub fn record(&mut self, sizes: &[u32]) {
+        let total = sizes.iter().sum::<u32>().max(1) as f32;
+        let mut end = 0;
+        self.ends = (sizes.iter().take(sizes.len().saturating_sub(1)))
+            .map(|size| {
+                end += size;
+                end as f32 / total
+            })
+            .collect();
+    }
+
+    /// Move end `index` to `frac`
+    ///
+    /// The end is kept between its neighbours. Returns true if changed.
+    pub fn move_end(&mut self, index: usize, frac: f32) -> bool {
+        if index >= self.ends.len() {
+            return false;
+        }
+        let lower = match index {
+            0 => 0.0,
+            i => self.ends[i - 1],
+        };
+        let upper = self.ends.get(index + 1).cloned().unwrap_or(1.0);
+        let frac = frac.max(lower).min(upper);
+        if frac != self.ends[index] {
+            self.ends[index] = frac;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply adjustments to a solution
+    ///
+    /// Given `out`, the solved sizes of each part, `rules` for each part, and
+    /// the `target` total size, replace `out` with the adjusted sizes, while
+    /// respecting each part's minimum size where possible. Returns false
+    /// (leaving `out` unchanged) if there are no matching adjustments.
+    pub fn apply(&self, out: &mut [u32], rules: &[SizeRules], target: u32) -> bool {
+        let n = out.len();
+        assert!(rules.len() == n);
+        if !self.matches(n) {
+            return false;
+        }
+
+        let mut pos = 0;
+        for index in 0..n - 1 {
+            let rest: u32 = rules[index + 1..].iter().map(|r| r.min_size()).sum();
+            // Round to nearest (f32::round is not available without std)
+            let end = (self.ends[index] * target as f32 + 0.5) as u32;
+            let end = end
+                .min(target.saturating_sub(rest))
+                .max(pos + rules[index].min_size())
+                .min(target);
+            out[index] = end - pos;
+            pos = end;
+        }
+        out[n - 1] = target - pos;
+        true
+    }
+}
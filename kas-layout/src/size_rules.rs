@@ -51,7 +51,7 @@ impl Margins {
 
 /// Widget sizing information
 ///
-/// Return value of [`kas::Widget::size_rules`].
+/// Return value of `kas::Widget::size_rules`.
 ///
 /// This struct conveys properties such as the minimum size and preferred size
 /// of the widgets being queried.
@@ -99,6 +99,12 @@ impl SizeRules {
         self.a
     }
 
+    /// Get the ideal size
+    #[inline]
+    pub fn ideal_size(self) -> u32 {
+        self.b
+    }
+
     /// Like `self = self.max(x - y)` but handling negative values correctly
     // TODO: switch to i32?
     pub fn set_at_least_op_sub(&mut self, x: Self, y: Self) {
@@ -229,7 +235,7 @@ impl SizeRules {
     }
 }
 
-impl std::ops::Add<SizeRules> for SizeRules {
+impl core::ops::Add<SizeRules> for SizeRules {
     type Output = Self;
 
     #[inline]
@@ -241,7 +247,7 @@ impl std::ops::Add<SizeRules> for SizeRules {
     }
 }
 
-impl std::ops::Add<u32> for SizeRules {
+impl core::ops::Add<u32> for SizeRules {
     type Output = Self;
 
     #[inline]
@@ -253,7 +259,7 @@ impl std::ops::Add<u32> for SizeRules {
     }
 }
 
-impl std::ops::AddAssign for SizeRules {
+impl core::ops::AddAssign for SizeRules {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
         *self = Self {
@@ -0,0 +1,59 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Solver traits
+
+use crate::geom::Rect;
+use crate::{AxisInfo, SizeRules};
+
+/// A [`SizeRules`] solver for layouts
+///
+/// Typically, a solver is invoked twice, once for each axis, before the
+/// corresponding [`RulesSetter`] is invoked. Within KAS, this is managed by
+/// `kas::layout::solve`.
+///
+/// Implementations require access to storage able to persist between multiple
+/// solver runs and a subsequent setter run. This storage is of type
+/// [`RulesSolver::Storage`] and is passed via reference to the constructor.
+pub trait RulesSolver {
+    /// Type of storage
+    type Storage: Clone;
+
+    /// Type required by [`RulesSolver::for_child`] (see implementation documentation)
+    type ChildInfo;
+
+    /// Called once for each child. For most layouts the order is important.
+    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
+        &mut self,
+        storage: &mut Self::Storage,
+        child_info: Self::ChildInfo,
+        child_rules: CR,
+    );
+
+    /// Called at the end to output [`SizeRules`].
+    ///
+    /// Note that this does not include margins!
+    fn finish<ColIter, RowIter>(
+        self,
+        storage: &mut Self::Storage,
+        col_spans: ColIter,
+        row_spans: RowIter,
+    ) -> SizeRules
+    where
+        ColIter: Iterator<Item = (usize, usize, usize)>,
+        RowIter: Iterator<Item = (usize, usize, usize)>;
+}
+
+/// Resolves a [`RulesSolver`] solution for each child
+pub trait RulesSetter {
+    /// Type of storage
+    type Storage: Clone;
+
+    /// Type required by [`RulesSolver::for_child`] (see implementation documentation)
+    type ChildInfo;
+
+    /// Called once for each child. For most layouts the order is important.
+    fn child_rect(&mut self, child_info: Self::ChildInfo) -> Rect;
+}
@@ -5,6 +5,8 @@
 
 //! Layout solver — storage
 
+use alloc::vec::Vec;
+
 use super::SizeRules;
 
 /// Master trait over storage types
@@ -203,6 +205,8 @@ impl GridStorage for DynGridStorage {
 }
 
 mod sealed {
+    use alloc::vec::Vec;
+
     pub trait Sealed {}
     impl<S: Clone> Sealed for super::FixedRowStorage<S> {}
     impl Sealed for super::DynRowStorage {}
@@ -23,7 +23,10 @@ pub struct Child {
 }
 
 pub struct Args {
-    pub core: Member,
+    /// The `#[core]` field, if any (not with `derive`)
+    pub core: Option<Member>,
+    /// The field and its type given by `#[widget(derive = ...)]`
+    pub derive: Option<(Member, Type)>,
     pub layout_data: Option<Member>,
     pub widget: WidgetArgs,
     pub handler: Option<HandlerArgs>,
@@ -56,8 +59,10 @@ pub fn read_attrs(ast: &mut DeriveInput) -> Result<Args> {
     let mut core = None;
     let mut layout_data = None;
     let mut children = vec![];
+    let mut field_types = vec![];
 
     for (i, field) in fields.iter_mut().enumerate() {
+        field_types.push((member(i, field.ident.clone()), field.ty.clone()));
         for attr in field.attrs.drain(..) {
             if attr.path == parse_quote! { core } {
                 if core.is_none() {
@@ -120,27 +125,48 @@ pub fn read_attrs(ast: &mut DeriveInput) -> Result<Args> {
         }
     }
 
-    if let Some(core) = core {
-        if let Some(widget) = widget {
-            Ok(Args {
-                core,
-                layout_data,
-                widget,
-                handler,
-                children,
-            })
-        } else {
-            Err(Error::new(
+    let widget: WidgetArgs = match widget {
+        Some(widget) => widget,
+        None => {
+            return Err(Error::new(
                 *span,
                 "a type deriving Widget must be annotated with the #[widget]` attribute",
             ))
         }
-    } else {
-        Err(Error::new(
-            *span,
-            "one field must be marked with #[core] when deriving Widget",
-        ))
-    }
+    };
+
+    let derive = match widget.derive {
+        None if core.is_none() => {
+            return Err(Error::new(
+                *span,
+                "one field must be marked with #[core] when deriving Widget",
+            ))
+        }
+        None => None,
+        Some(ref inner) => {
+            let err = |msg| Err(Error::new(inner.span(), msg));
+            if core.is_some() {
+                return err("a #[core] field may not be used with `derive`");
+            } else if widget.layout.is_some() {
+                return err("`layout` may not be used with `derive`");
+            } else if !children.is_empty() {
+                return err("#[widget] fields may not be used with `derive`");
+            }
+            match field_types.into_iter().find(|(m, _)| m == inner) {
+                Some(field) => Some(field),
+                None => return err("no such field"),
+            }
+        }
+    };
+
+    Ok(Args {
+        core,
+        derive,
+        layout_data,
+        widget,
+        handler,
+        children,
+    })
 }
 
 fn member(index: usize, ident: Option<Ident>) -> Member {
@@ -167,6 +193,8 @@ mod kw {
     custom_keyword!(msg);
     custom_keyword!(generics);
     custom_keyword!(frame);
+    custom_keyword!(derive);
+    custom_keyword!(custom);
 }
 
 #[derive(Debug)]
@@ -315,15 +343,24 @@ impl ToTokens for GridPos {
 pub struct WidgetArgs {
     pub layout: Option<Ident>,
     pub is_frame: bool,
+    pub derive: Option<Member>,
+    pub custom: Vec<Ident>,
 }
 
 impl Parse for WidgetArgs {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut layout = None;
         let mut is_frame = false;
+        let mut derive = None;
+        let mut custom = None;
 
         if input.is_empty() {
-            return Ok(WidgetArgs { layout, is_frame });
+            return Ok(WidgetArgs {
+                layout,
+                is_frame,
+                derive,
+                custom: vec![],
+            });
         }
 
         let content;
@@ -338,6 +375,16 @@ impl Parse for WidgetArgs {
             } else if !is_frame && lookahead.peek(kw::frame) {
                 let _: kw::frame = content.parse()?;
                 is_frame = true;
+            } else if derive.is_none() && lookahead.peek(kw::derive) {
+                let _: kw::derive = content.parse()?;
+                let _: Eq = content.parse()?;
+                derive = Some(content.parse()?);
+            } else if custom.is_none() && lookahead.peek(kw::custom) {
+                let _: kw::custom = content.parse()?;
+                let inner;
+                let _ = parenthesized!(inner in content);
+                let names = inner.parse_terminated::<Ident, Comma>(Ident::parse)?;
+                custom = Some(names.into_iter().collect());
             } else {
                 return Err(lookahead.error());
             }
@@ -348,18 +395,24 @@ impl Parse for WidgetArgs {
             let _: Comma = content.parse()?;
         }
 
-        Ok(WidgetArgs { layout, is_frame })
+        Ok(WidgetArgs {
+            layout,
+            is_frame,
+            derive,
+            custom: custom.unwrap_or_default(),
+        })
     }
 }
 
 pub struct HandlerArgs {
-    pub msg: Type,
+    /// The message type, if given
+    pub msg: Option<Type>,
     pub generics: Generics,
 }
 
 impl Parse for HandlerArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut msg = parse_quote! { kas::event::VoidMsg };
+        let mut msg = None;
         let mut generics = Generics::default();
 
         if input.is_empty() {
@@ -375,7 +428,7 @@ impl Parse for HandlerArgs {
         if content.peek(kw::msg) {
             let _: kw::msg = content.parse()?;
             let _: Eq = content.parse()?;
-            msg = content.parse()?;
+            msg = Some(content.parse()?);
 
             if content.peek(Comma) {
                 let _: Comma = content.parse()?;
@@ -414,6 +467,8 @@ pub struct MakeWidget {
     pub layout: Ident,
     // msg type
     pub msg: Type,
+    // generic parameters and where clause on the struct
+    pub generics: Generics,
     // child widgets and data fields
     pub fields: Vec<WidgetField>,
     // impl blocks on the widget
@@ -430,6 +485,9 @@ impl Parse for MakeWidget {
         let _: Semi = input.parse()?;
 
         let _: Struct = input.parse()?;
+        let mut generics: Generics = input.parse()?;
+        generics.where_clause = input.parse()?;
+
         let content;
         let _ = braced!(content in input);
         let mut fields = vec![];
@@ -467,6 +525,7 @@ impl Parse for MakeWidget {
         Ok(MakeWidget {
             layout,
             msg,
+            generics,
             fields,
             impls,
         })
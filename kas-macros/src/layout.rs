@@ -6,10 +6,11 @@
 use std::cmp::Ordering;
 
 use crate::args::{Child, WidgetAttrArgs};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, TokenStreamExt};
 use syn::parse::{Error, Result};
-use syn::{Ident, Member};
+use syn::spanned::Spanned;
+use syn::{Ident, Lit, Member};
 
 pub(crate) fn validate_layout(layout: &Ident) -> Result<()> {
     if layout == "frame"
@@ -30,6 +31,84 @@ pub(crate) fn validate_layout(layout: &Ident) -> Result<()> {
     }
 }
 
+/// Check that child attributes are consistent with the layout
+///
+/// Errors are emitted directly since they do not prevent code generation.
+fn validate_children(children: &Vec<Child>, layout: &Ident) -> Result<()> {
+    let is_grid = layout == "grid";
+
+    fn pos_args(args: &WidgetAttrArgs) -> Vec<&Lit> {
+        let list = [&args.col, &args.row, &args.cspan, &args.rspan];
+        list.iter().filter_map(|arg| arg.as_ref()).collect()
+    }
+
+    if !is_grid {
+        for child in children {
+            for lit in pos_args(&child.args) {
+                lit.span()
+                    .unwrap()
+                    .error(format!(
+                        "col, row, cspan and rspan are not used with layout '{}'",
+                        layout
+                    ))
+                    .help("use the grid layout or remove this argument")
+                    .emit();
+            }
+        }
+        return Ok(());
+    }
+
+    if children.len() > 1
+        && children
+            .iter()
+            .all(|child| child.args.col.is_none() && child.args.row.is_none())
+    {
+        layout
+            .span()
+            .unwrap()
+            .error("layout 'grid' requires children to specify their position")
+            .note("without col or row, all children are placed in the same cell")
+            .help("add `#[widget(col = .., row = ..)]` to children, or use a horizontal or vertical layout")
+            .emit();
+        return Ok(());
+    }
+
+    // Rects (col, row, cspan, rspan) of children placed so far
+    let mut cells: Vec<(u32, u32, u32, u32, Span)> = vec![];
+    for child in children {
+        let pos = child.args.as_pos()?;
+        // Point at the child's position arguments where possible
+        let span = match pos_args(&child.args).first() {
+            Some(lit) => lit.span(),
+            None => child.ident.span(),
+        };
+        if pos.2 == 0 || pos.3 == 0 {
+            span.unwrap()
+                .error("cspan and rspan must be at least 1")
+                .emit();
+        }
+        // Zero spans are reported above; treat these as 1 here
+        let (col, row) = (pos.0, pos.1);
+        let (cspan, rspan) = (pos.2.max(1), pos.3.max(1));
+        let overlaps = |c: &&(u32, u32, u32, u32, Span)| {
+            col < c.0 + c.2 && c.0 < col + cspan && row < c.1 + c.3 && c.1 < row + rspan
+        };
+        if let Some(other) = cells.iter().find(overlaps) {
+            span.unwrap()
+                .error(format!(
+                    "child placed at col = {}, row = {} (cspan = {}, rspan = {}) overlaps another child",
+                    col, row, cspan, rspan
+                ))
+                .span_note(other.4.unwrap(), "cells are occupied by this child")
+                .help("specify a different col, row, cspan or rspan")
+                .emit();
+        } else {
+            cells.push((col, row, cspan, rspan, span));
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn derive(
     children: &Vec<Child>,
     layout: &Ident,
@@ -42,10 +121,12 @@ pub(crate) fn derive(
         )
     })?;
 
+    validate_children(children, layout)?;
+
     let is_frame = layout == "frame";
     let is_single = layout == "single";
     if is_frame || is_single {
-        if !children.len() == 1 {
+        if children.len() != 1 {
             return Err(Error::new(
                 layout.span(),
                 format_args!(
@@ -70,7 +151,7 @@ pub(crate) fn derive(
                     (Size::ZERO, Size::ZERO)
                 };
                 self.#data = frame_size;
-                self.#ident.size_rules(size_handle, axis)
+                kas::layout::child_rules(&mut self.#ident, size_handle, axis)
                     + axis.extract_size(frame_size.0)
                     + axis.extract_size(frame_size.1)
             }
@@ -101,7 +182,7 @@ pub(crate) fn derive(
                 if #is_frame {
                     draw_handle.outer_frame(self.core_data().rect);
                 }
-                self.#ident.draw(draw_handle, ev_mgr);
+                kas::layout::draw_child(&self.#ident, draw_handle, ev_mgr);
             }
         };
         let ty = quote! {
@@ -211,7 +292,7 @@ impl<'a> ImplLayout<'a> {
             solver.for_child(
                 &mut self.#data,
                 #child_info,
-                |axis| child.size_rules(size_handle, axis)
+                |axis| kas::layout::child_rules(child, size_handle, axis)
             );
         });
 
@@ -223,7 +304,7 @@ impl<'a> ImplLayout<'a> {
             let c0 = self.#ident.rect().pos;
             let c1 = c0 + Coord::from(self.#ident.rect().size);
             if c0.0 <= pos1.0 && c1.0 >= pos0.0 && c0.1 <= pos1.1 && c1.1 >= pos0.1 {
-                self.#ident.draw(draw_handle, ev_mgr);
+                kas::layout::draw_child(&self.#ident, draw_handle, ev_mgr);
             }
         });
 
@@ -291,11 +372,26 @@ impl<'a> ImplLayout<'a> {
             quote! { [u32; #rows] }
         };
 
+        // Arrays implement Default only up to length 32
+        let row_storage = |len: usize| match len < 32 {
+            true => quote! { kas::layout::FixedRowStorage::<[kas::layout::SizeRules; #len + 1]> },
+            false => quote! { kas::layout::DynRowStorage },
+        };
+        let (col_storage, row_storage) = (row_storage(cols), row_storage(rows));
+        let grid_storage = if cols < 32 && rows < 32 {
+            quote! {
+                kas::layout::FixedGridStorage::<
+                    [kas::layout::SizeRules; #cols + 1],
+                    [kas::layout::SizeRules; #rows + 1],
+                >
+            }
+        } else {
+            quote! { kas::layout::DynGridStorage }
+        };
+
         let data_type = match self.layout {
             Layout::Horizontal => quote! {
-                type Data = kas::layout::FixedRowStorage::<
-                    [kas::layout::SizeRules; #cols + 1]
-                >;
+                type Data = #col_storage;
                 type Solver = kas::layout::RowSolver::<
                     #col_temp,
                     Self::Data,
@@ -307,9 +403,7 @@ impl<'a> ImplLayout<'a> {
                 >;
             },
             Layout::Vertical => quote! {
-                type Data = kas::layout::FixedRowStorage::<
-                    [kas::layout::SizeRules; #rows + 1],
-                >;
+                type Data = #row_storage;
                 type Solver = kas::layout::RowSolver::<
                     #row_temp,
                     Self::Data,
@@ -321,10 +415,7 @@ impl<'a> ImplLayout<'a> {
                 >;
             },
             Layout::Grid => quote! {
-                type Data = kas::layout::FixedGridStorage::<
-                    [kas::layout::SizeRules; #cols + 1],
-                    [kas::layout::SizeRules; #rows + 1],
-                >;
+                type Data = #grid_storage;
                 type Solver = kas::layout::GridSolver::<
                     #col_temp,
                     #row_temp,
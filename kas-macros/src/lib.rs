@@ -17,7 +17,9 @@ use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
 use syn::{parse_macro_input, parse_quote};
-use syn::{DeriveInput, FnArg, Ident, ImplItemMethod, Type, TypePath};
+use syn::{
+    DeriveInput, FnArg, GenericParam, Generics, Ident, ImplItemMethod, Member, Type, TypePath,
+};
 
 use self::args::ChildType;
 
@@ -38,7 +40,15 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let name = &ast.ident;
     let widget_name = name.to_string();
 
-    let core = args.core;
+    if let Some((ref inner, ref inner_ty)) = args.derive {
+        let toks = derive_inner(name, &ast.generics, &args, inner, inner_ty);
+        return match toks {
+            Ok(toks) => toks.into(),
+            Err(err) => err.to_compile_error().into(),
+        };
+    }
+
+    let core = args.core.as_ref().unwrap();
     let count = args.children.len();
 
     let mut get_rules = quote! {};
@@ -71,6 +81,8 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
             fn as_widget(&self) -> &dyn kas::Widget { self }
             fn as_widget_mut(&mut self) -> &mut dyn kas::Widget { self }
+            fn as_any(&self) -> &dyn std::any::Any { self }
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
 
             fn len(&self) -> usize {
                 #count
@@ -118,24 +130,9 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     }
 
     if let Some(handler) = args.handler {
-        let msg = handler.msg;
+        let msg = (handler.msg).unwrap_or_else(|| parse_quote! { kas::event::VoidMsg });
         let mut generics = ast.generics.clone();
-        if !handler.generics.params.is_empty() {
-            if !generics.params.empty_or_trailing() {
-                generics.params.push_punct(Default::default());
-            }
-            generics.params.extend(handler.generics.params.into_pairs());
-        }
-        if let Some(h_clauses) = handler.generics.where_clause {
-            if let Some(ref mut clauses) = generics.where_clause {
-                if !clauses.predicates.empty_or_trailing() {
-                    clauses.predicates.push_punct(Default::default());
-                }
-                clauses.predicates.extend(h_clauses.predicates.into_pairs());
-            } else {
-                generics.where_clause = Some(h_clauses);
-            }
-        }
+        extend_generics(&mut generics, handler.generics);
         // Note: we may have extra generic types used in where clauses, but we
         // don't want these in ty_generics.
         let (impl_generics, _, where_clause) = generics.split_for_impl();
@@ -203,6 +200,263 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     toks.into()
 }
 
+/// Derive widget traits by delegation to the field `inner`
+///
+/// Methods named by the `custom` argument call an inherent method of the
+/// same name on the deriving type instead.
+fn derive_inner(
+    name: &Ident,
+    generics: &Generics,
+    args: &args::Args,
+    inner: &Member,
+    inner_ty: &Type,
+) -> Result<TokenStream, syn::Error> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let widget_name = name.to_string();
+
+    // Forwarded methods: name, whether the receiver is mutable, parameters
+    // and return type
+    type Method = (&'static str, bool, Vec<(&'static str, Type)>, Type);
+    let widget_methods: Vec<Method> = vec![
+        ("allow_focus", false, vec![], parse_quote! { bool }),
+        (
+            "accept_drop",
+            false,
+            vec![("payload", parse_quote! { &kas::event::DragPayload })],
+            parse_quote! { bool },
+        ),
+        ("tooltip", false, vec![], parse_quote! { Option<&str> }),
+        (
+            "cursor_icon",
+            false,
+            vec![],
+            parse_quote! { kas::event::CursorIcon },
+        ),
+        (
+            "shortcuts",
+            false,
+            vec![],
+            parse_quote! { &[(kas::event::Shortcut, kas::event::Action)] },
+        ),
+        ("redraw_on_hover", false, vec![], parse_quote! { bool }),
+        (
+            "size_rules",
+            true,
+            vec![
+                (
+                    "size_handle",
+                    parse_quote! { &mut dyn kas::theme::SizeHandle },
+                ),
+                ("axis", parse_quote! { kas::layout::AxisInfo }),
+            ],
+            parse_quote! { kas::layout::SizeRules },
+        ),
+        (
+            "size_rules_both",
+            true,
+            vec![(
+                "size_handle",
+                parse_quote! { &mut dyn kas::theme::SizeHandle },
+            )],
+            parse_quote! { Option<(kas::layout::SizeRules, kas::layout::SizeRules)> },
+        ),
+        (
+            "set_rect",
+            true,
+            vec![
+                (
+                    "size_handle",
+                    parse_quote! { &mut dyn kas::theme::SizeHandle },
+                ),
+                ("rect", parse_quote! { kas::geom::Rect }),
+            ],
+            parse_quote! { () },
+        ),
+        (
+            "draw",
+            false,
+            vec![
+                (
+                    "draw_handle",
+                    parse_quote! { &mut dyn kas::theme::DrawHandle },
+                ),
+                ("ev_mgr", parse_quote! { &kas::event::Manager }),
+            ],
+            parse_quote! { () },
+        ),
+    ];
+    let handler_methods: Vec<Method> = vec![
+        ("activation_via_press", false, vec![], parse_quote! { bool }),
+        (
+            "handle_action",
+            true,
+            vec![
+                ("tk", parse_quote! { &mut dyn kas::TkWindow }),
+                ("action", parse_quote! { kas::event::Action }),
+            ],
+            parse_quote! { kas::event::Response<Self::Msg> },
+        ),
+        (
+            "handle",
+            true,
+            vec![
+                ("tk", parse_quote! { &mut dyn kas::TkWindow }),
+                ("addr", parse_quote! { kas::event::Address }),
+                ("event", parse_quote! { kas::event::Event }),
+            ],
+            parse_quote! { kas::event::Response<Self::Msg> },
+        ),
+    ];
+
+    let custom = &args.widget.custom;
+    for ident in custom {
+        let known = (widget_methods.iter())
+            .chain(handler_methods.iter())
+            .any(|m| ident == m.0);
+        if !known {
+            return Err(syn::Error::new(
+                ident.span(),
+                "not a forwarded method of Widget or Handler",
+            ));
+        }
+    }
+
+    // Generate a forwarding method; `convert` maps the result
+    let forward = |method: &Method, trait_path: TokenStream, convert: TokenStream| {
+        let (ident, is_mut, params, ret) = method;
+        let ident = Ident::new(ident, Span::call_site());
+        let names: Vec<_> = (params.iter())
+            .map(|(name, _)| Ident::new(name, Span::call_site()))
+            .collect();
+        let types = params.iter().map(|(_, ty)| ty);
+        let (receiver, inner_ref) = match is_mut {
+            false => (quote! { &self }, quote! { &self.#inner }),
+            true => (quote! { &mut self }, quote! { &mut self.#inner }),
+        };
+        let body = if custom.contains(&ident) {
+            quote! { Self::#ident(self, #(#names),*) }
+        } else {
+            quote! { #convert(<#inner_ty as #trait_path>::#ident(#inner_ref, #(#names),*)) }
+        };
+        quote! {
+            #[inline]
+            fn #ident(#receiver, #(#names: #types),*) -> #ret {
+                #body
+            }
+        }
+    };
+
+    let mut widget_fns = TokenStream::new();
+    for method in &widget_methods {
+        widget_fns.append_all(forward(method, quote! { kas::Widget }, quote! {}));
+    }
+
+    let mut toks = quote! {
+        impl #impl_generics kas::WidgetCore
+            for #name #ty_generics #where_clause
+        {
+            fn core_data(&self) -> &kas::CoreData {
+                kas::WidgetCore::core_data(&self.#inner)
+            }
+
+            fn core_data_mut(&mut self) -> &mut kas::CoreData {
+                kas::WidgetCore::core_data_mut(&mut self.#inner)
+            }
+
+            fn widget_name(&self) -> &'static str {
+                #widget_name
+            }
+
+            fn as_widget(&self) -> &dyn kas::Widget { self }
+            fn as_widget_mut(&mut self) -> &mut dyn kas::Widget { self }
+            fn as_any(&self) -> &dyn std::any::Any { self }
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any { self }
+
+            fn len(&self) -> usize {
+                kas::WidgetCore::len(&self.#inner)
+            }
+            fn get(&self, index: usize) -> Option<&dyn kas::Widget> {
+                kas::WidgetCore::get(&self.#inner, index)
+            }
+            fn get_mut(&mut self, index: usize) -> Option<&mut dyn kas::Widget> {
+                kas::WidgetCore::get_mut(&mut self.#inner, index)
+            }
+            // The inner widget shares our core, thus is not itself visited
+            fn walk(&self, f: &mut dyn FnMut(&dyn kas::Widget)) {
+                for i in 0..kas::WidgetCore::len(self) {
+                    if let Some(w) = kas::WidgetCore::get(self, i) {
+                        kas::WidgetCore::walk(w, f);
+                    }
+                }
+                f(self);
+            }
+            fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn kas::Widget)) {
+                for i in 0..kas::WidgetCore::len(self) {
+                    if let Some(w) = kas::WidgetCore::get_mut(self, i) {
+                        kas::WidgetCore::walk_mut(w, f);
+                    }
+                }
+                f(self);
+            }
+        }
+
+        impl #impl_generics kas::Widget
+            for #name #ty_generics #where_clause
+        {
+            #widget_fns
+        }
+    };
+
+    if let Some(ref handler) = args.handler {
+        let msg = match handler.msg {
+            Some(ref msg) => quote! { #msg },
+            None => quote! { <#inner_ty as kas::event::Handler>::Msg },
+        };
+        let mut generics = generics.clone();
+        extend_generics(&mut generics, handler.generics.clone());
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+        let mut handler_fns = TokenStream::new();
+        for method in &handler_methods {
+            let convert = match method.0 {
+                "activation_via_press" => quote! {},
+                _ => quote! { kas::event::Response::from },
+            };
+            handler_fns.append_all(forward(method, quote! { kas::event::Handler }, convert));
+        }
+
+        toks.append_all(quote! {
+            impl #impl_generics kas::event::Handler
+                    for #name #ty_generics #where_clause
+            {
+                type Msg = #msg;
+                #handler_fns
+            }
+        });
+    }
+
+    Ok(toks)
+}
+
+/// Append `extra` generics to `generics`
+///
+/// Lifetime parameters are kept before type and const parameters, as required.
+fn extend_generics(generics: &mut Generics, extra: Generics) {
+    let mut params: Vec<GenericParam> = generics.params.iter().cloned().collect();
+    params.extend(extra.params);
+    params.sort_by_key(|param| match param {
+        GenericParam::Lifetime(_) => 0,
+        GenericParam::Type(_) => 1,
+        GenericParam::Const(_) => 2,
+    });
+    generics.params = params.into_iter().collect();
+
+    if let Some(extra_clause) = extra.where_clause {
+        let clause = generics.make_where_clause();
+        clause.predicates.extend(extra_clause.predicates);
+    }
+}
+
 /// Macro to create a widget with anonymous type
 ///
 /// See the [`kas::macros`](../kas/macros/index.html) module documentation.
@@ -292,10 +546,8 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // debug impl
     let mut debug_fields = TokenStream::new();
 
-    // generic types on struct, without constraints:
-    let mut gen_tys = Punctuated::<_, Comma>::new();
     // generic types on struct, with constraints:
-    let mut gen_ptrs = Punctuated::<_, Comma>::new();
+    let mut gen_ptrs = Punctuated::<GenericParam, Comma>::new();
     // extra generic types and where clause for handler impl
     let mut handler_extra = Punctuated::<_, Comma>::new();
     let mut handler_clauses = Punctuated::<_, Comma>::new();
@@ -326,7 +578,6 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 name_buf.write_fmt(format_args!("MWAnon{}", index)).unwrap();
                 let ty = Ident::new(&name_buf, Span::call_site());
 
-                gen_tys.push(ty.clone());
                 if let Some(ref wattr) = attr {
                     if let Some(tyr) = gen_msg {
                         handler_clauses.push(quote! { #ty: kas::event::Handler<Msg = #tyr> });
@@ -352,12 +603,12 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
                     if let Some(mut bound) = gen_bound {
                         bound.bounds.push(parse_quote! { kas::Widget });
-                        gen_ptrs.push(quote! { #ty: #bound });
+                        gen_ptrs.push(parse_quote! { #ty: #bound });
                     } else {
-                        gen_ptrs.push(quote! { #ty: kas::Widget });
+                        gen_ptrs.push(parse_quote! { #ty: kas::Widget });
                     }
                 } else {
-                    gen_ptrs.push(quote! { #ty });
+                    gen_ptrs.push(parse_quote! { #ty });
                 }
 
                 Type::Path(TypePath {
@@ -381,6 +632,17 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         quote! { where #handler_clauses }
     };
 
+    // user-specified generics are followed by those generated above
+    let mut generics = args.generics;
+    extend_generics(
+        &mut generics,
+        Generics {
+            params: gen_ptrs,
+            ..Default::default()
+        },
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     let mut impls = quote! {};
 
     for impl_block in args.impls {
@@ -394,7 +656,7 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             quote! {}
         };
         impls.append_all(quote! {
-            impl<#gen_ptrs> #target AnonWidget<#gen_tys> {
+            impl #impl_generics #target AnonWidget #ty_generics #where_clause {
                 #contents
             }
         });
@@ -406,7 +668,7 @@ pub fn make_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         #[widget(#widget_args)]
         #[handler(msg = #msg, generics = < #handler_extra > #handler_where)]
         #[derive(Clone, Debug, kas::macros::Widget)]
-        struct AnonWidget<#gen_ptrs> {
+        struct AnonWidget #impl_generics #where_clause {
             #field_toks
         }
 
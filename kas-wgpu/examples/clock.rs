@@ -39,7 +39,7 @@ fn main() -> Result<(), kas_wgpu::Error> {
         },
     );
 
-    window.add_callback(Callback::Repeat(Duration::from_secs(1)), &|w, tk| {
+    window.add_callback(Callback::Cosmetic(Duration::from_secs(1)), &|w, tk| {
         w.on_tick(tk)
     });
 
@@ -7,10 +7,8 @@
 #![feature(proc_macro_hygiene)]
 
 use kas::class::HasText;
-use kas::event::{VoidMsg, VoidResponse};
 use kas::macros::{make_widget, VoidMsg};
 use kas::widget::{Label, TextButton, Window};
-use kas::TkWindow;
 
 #[derive(Clone, Debug, VoidMsg)]
 enum Message {
@@ -28,34 +26,21 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget] _ = TextButton::new("+", Message::Incr),
         }
     };
-    let window = Window::new(
-        "Counter",
-        make_widget! {
-            vertical => VoidMsg;
-            struct {
-                #[widget] display: Label = Label::from("0"),
-                #[widget(handler = handle_button)] buttons -> Message = buttons,
-                counter: usize = 0,
-            }
-            impl {
-                fn handle_button(&mut self, tk: &mut dyn TkWindow, msg: Message)
-                    -> VoidResponse
-                {
-                    match msg {
-                        Message::Decr => {
-                            self.counter = self.counter.saturating_sub(1);
-                            self.display.set_text(tk, self.counter.to_string());
-                        }
-                        Message::Incr => {
-                            self.counter = self.counter.saturating_add(1);
-                            self.display.set_text(tk, self.counter.to_string());
-                        }
-                    };
-                    VoidResponse::None
-                }
-            }
-        },
-    );
+    let content = make_widget! {
+        vertical => Message;
+        struct {
+            #[widget] display: Label = Label::from("0"),
+            #[widget] buttons -> Message = buttons,
+            counter: usize = 0,
+        }
+    };
+    let window = Window::new("Counter", content).on_message(|content, tk, msg| {
+        content.counter = match msg {
+            Message::Decr => content.counter.saturating_sub(1),
+            Message::Incr => content.counter.saturating_add(1),
+        };
+        content.display.set_text(tk, content.counter.to_string());
+    });
 
     let mut theme = kas_wgpu::SampleTheme::new();
     theme.set_font_size(24.0);
@@ -6,8 +6,11 @@
 //! Gallery of all widgets
 #![feature(proc_macro_hygiene)]
 
-use kas::event::{Response, VoidMsg, VoidResponse};
-use kas::layout::Horizontal;
+use std::time::Duration;
+
+use kas::draw::Colour;
+use kas::event::{Callback, Response, Shortcut, VirtualKeyCode, VoidMsg, VoidResponse};
+use kas::layout::{Anchor, Horizontal};
 use kas::macros::{make_widget, VoidMsg};
 use kas::widget::*;
 use kas::TkWindow;
@@ -16,14 +19,39 @@ use kas::TkWindow;
 enum Item {
     Button,
     Check(bool),
+    Tristate(Option<bool>),
     Edit(String),
     Scroll(u32),
+    Radio(usize),
+    Slider(i32),
+    Rating(f32),
+    Dial(f64),
+    Range(i32, i32),
+    Colour(Colour),
+    Date(Date),
+    Spin(i32),
     Popup,
+    Segment(usize),
+    Menu(&'static str),
+    Busy,
+    Search(String),
+}
+
+// RGBA texels of a simple colour gradient
+fn gradient(w: u32, h: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity((w * h * 4) as usize);
+    for y in 0..h {
+        for x in 0..w {
+            data.extend_from_slice(&[(x * 255 / w) as u8, (y * 255 / h) as u8, 160, 255]);
+        }
+    }
+    data
 }
 
 fn main() -> Result<(), kas_wgpu::Error> {
     env_logger::init();
 
+    let radio = RadioGroup::new();
     let widgets = make_widget! {
         grid => Item;
         struct {
@@ -33,7 +61,8 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget(row=1, col=1)] _ = EditBox::new("edit me")
                 .on_activate(|entry| Item::Edit(entry.to_string())),
             #[widget(row=2, col=0)] _ = Label::from("TextButton"),
-            #[widget(row=2, col=1)] _ = TextButton::new("Press me", Item::Button),
+            #[widget(row=2, col=1)] _ = TextButton::new("Press me", Item::Button)
+                .with_tooltip("Sends a message to the window"),
             #[widget(row=3, col=0)] _ = Label::from("CheckBox"),
             #[widget(row=3, col=1)] _ = CheckBox::new("Check me")
                 .on_toggle(|check| Item::Check(check)),
@@ -43,21 +72,201 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget(row=5, col=0)] _ = Label::from("ScrollBar"),
             #[widget(row=5, col=1, handler = handle_scroll)] _ =
                 ScrollBar::<Horizontal>::new().with_limits(5, 2),
+            #[widget(row=6, col=0)] _ = Label::from("Tristate"),
+            #[widget(row=6, col=1)] _ = CheckBox::new("Mixed").tristate(true).indeterminate()
+                .on_tristate(|state| Item::Tristate(state)),
+            #[widget(row=7, col=0)] _ = Label::from("RadioButton"),
+            #[widget(row=7, col=1)] _ = make_widget! {
+                horizontal => Item;
+                struct {
+                    #[widget] _ = RadioButton::new("First", &radio).state(true)
+                        .on_select(|index| Item::Radio(index)),
+                    #[widget] _ = RadioButton::new("Second", &radio)
+                        .on_select(|index| Item::Radio(index)),
+                }
+            },
             #[widget(row=8)] _ = Label::from("Child window"),
             #[widget(row=8, col = 1)] _ = TextButton::new("Open", Item::Popup),
+            #[widget(row=9, col=0)] _ = Label::from("Slider"),
+            #[widget(row=9, col=1, handler = handle_slider)] _ =
+                Slider::<i32, Horizontal>::new(-2, 2, 1).with_value(0),
+            #[widget(row=10, col=0)] _ = Label::from("Rating"),
+            #[widget(row=10, col=1, handler = handle_rating)] _ =
+                Rating::new(5).with_half_steps(true).with_value(3.5),
+            #[widget(row=11, col=0)] _ = Label::from("Switch"),
+            #[widget(row=11, col=1)] _ = Switch::new("Toggle me")
+                .on_toggle(|state| Item::Check(state)),
+            #[widget(row=12, col=0)] _ = Label::from("SegmentedControl"),
+            #[widget(row=12, col=1)] _ = SegmentedControl::new(vec!["Day", "Week", "Month"])
+                .on_select(|index| Item::Segment(index)),
+            #[widget(row=13, col=0)] _ = Label::from("Dial"),
+            #[widget(row=13, col=1, handler = handle_dial)] _ =
+                Dial::new(0.0, 1.0, 0.01)
+                    .with_value(0.5)
+                    .with_ticks(11)
+                    .with_circular_drag(true),
+            #[widget(row=14, col=0)] _ = Label::from("RangeSlider"),
+            #[widget(row=14, col=1, handler = handle_range)] _ =
+                RangeSlider::<i32, Horizontal>::new(0, 24, 1)
+                    .with_min_gap(2)
+                    .with_values(9, 17),
+            #[widget(row=15, col=0)] _ = Label::from("Image"),
+            #[widget(row=15, col=1)] _ = Image::from_rgba(kas::geom::Size(64, 32), gradient(64, 32)),
+            #[widget(row=16, col=0)] _ = Label::from("Canvas"),
+            #[widget(row=16, col=1)] _ = Canvas::new(kas::geom::Size(96, 32), |painter| {
+                use kas::draw::Vec2;
+                let size = Vec2::from(painter.size());
+                let points: Vec<Vec2> = (0..=16)
+                    .map(|i| {
+                        let x = i as f32 / 16.0;
+                        let y = 0.5 - 0.4 * (x * std::f32::consts::PI * 2.0).sin();
+                        Vec2(x * size.0, y * size.1)
+                    })
+                    .collect();
+                painter.path(&points, 2.0, Colour::new(0.2, 0.4, 0.8));
+            }),
+            #[widget(row=17, col=0)] _ = Label::from("FindBar"),
+            #[widget(row=17, col=1)] _ = FindBar::new(
+                EditBox::new("A gallery of widgets, with a bar to find words").multi_line(true),
+            ),
+            #[widget(row=18, col=0)] _ = Label::from("DiffView"),
+            #[widget(row=18, col=1)] _ = DiffView::new(
+                "one\ntwo\nthree\nfour\nfive",
+                "one\n2\nthree\nfour\nfive and six",
+            ),
+            #[widget(row=19, col=0)] _ = Label::from("ColourPicker"),
+            #[widget(row=19, col=1, handler = handle_colour)] _ =
+                ColourPicker::new(Colour::new(0.2, 0.7, 1.0)),
+            #[widget(row=20, col=0)] _ = Label::from("DatePicker"),
+            #[widget(row=20, col=1, handler = handle_date)] _ = DatePicker::new(),
+            #[widget(row=21, col=0)] _ = Label::from("SpinButton"),
+            #[widget(row=21, col=1, handler = handle_spin)] _ =
+                SpinButton::new(-10, 100, 5).with_value(20),
+            #[widget(row=22, col=0)] _ = Label::from("Expander"),
+            #[widget(row=22, col=1)] _ = Expander::new(
+                "Details",
+                Label::from("Content shown only while the section is expanded"),
+            ),
+            #[widget(row=23, col=0)] _ = Label::from("Popover"),
+            #[widget(row=23, col=1)] _ = Popover::new(
+                TextButton::new("Show", ()),
+                Label::from("Anchored to the button;\nclick outside to dismiss"),
+            ),
+            #[widget(row=24, col=0)] _ = Label::from("Toolbar"),
+            #[widget(row=24, col=1)] _ = Toolbar::new(vec![
+                TextButton::new("Cut", Item::Menu("Cut")),
+                TextButton::new("Copy", Item::Menu("Copy")),
+                TextButton::new("Paste", Item::Menu("Paste")),
+                TextButton::new("Undo", Item::Menu("Undo")),
+                TextButton::new("Redo", Item::Menu("Redo")),
+            ]),
+            #[widget(row=25, col=0)] _ = Label::from("RichLabel"),
+            #[widget(row=25, col=1)] _ = RichLabel::from_markup(
+                "[b]Bold[/b], [i]italic[/i], [colour=#c02020]red[/colour] and [size=1.4]large[/size]",
+            ),
+            #[widget(row=26, col=0)] _ = Label::from("Busy state"),
+            #[widget(row=26, col=1)] _ = TextButton::new("Work for 2s", Item::Busy),
+            #[widget(row=27, col=0)] _ = Label::from("Multi-line"),
+            #[widget(row=27, col=1)] _ = EditBox::new("A multi-line editor.\nPress Enter for a new line.")
+                .multi_line(true),
+            #[widget(row=28, col=0)] _ = Label::from("Spinner"),
+            #[widget(row=28, col=1)] _ = Spinner::new(),
+            #[widget(row=29, col=0, cspan=2)] _ = Separator::<Horizontal>::new(),
+            #[widget(row=30, col=0, cspan=2)] _ = GroupBox::new("Group box", Label::from("Content within a titled frame")),
+            #[widget(row=31, col=0)] _ = Label::from("Hyperlink"),
+            #[widget(row=31, col=1)] _ = Hyperlink::new("KAS on GitHub", "https://github.com/kas-gui/kas"),
+            #[widget(row=32, col=0)] _ = Label::from("Plot"),
+            #[widget(row=32, col=1)] _ = Plot::new()
+                .with_series(Series::bar("Rain", Colour::new(0.3, 0.5, 0.9),
+                    vec![(1.0, 4.0), (2.0, 7.5), (3.0, 3.0), (4.0, 5.5), (5.0, 2.0)]))
+                .with_series(Series::line("Temperature", Colour::new(0.9, 0.3, 0.2),
+                    vec![(1.0, 2.5), (2.0, 4.0), (3.0, 6.5), (4.0, 6.0), (5.0, 8.0)]))
+                .with_x_label("Day")
+                .with_y_label("Value"),
+            #[widget(row=33, col=0)] _ = Label::from("SearchBox"),
+            #[widget(row=33, col=1, handler = handle_search)] _ = SearchBox::new(vec![
+                "Apple", "Apricot", "Banana", "Blackberry", "Cherry", "Grape", "Lemon", "Mango",
+            ]),
+            #[widget(row=34, col=0)] _ = Label::from("CodeEdit"),
+            #[widget(row=34, col=1)] _ = CodeEdit::new(
+                "/* Greet the user */\nfn main() {\n    let name = \"world\";\n    println!(\"Hello, {}!\", name); // 1 line\n}\n"
+            ).with_highlighter(SimpleHighlighter::new()
+                .with_keywords(&["fn", "let", "if", "else", "for", "in", "return"])
+                .with_line_comment("//")
+                .with_block_comment("/*", "*/")),
+            #[widget(row=35, col=0)] _ = Label::from("FlowBox"),
+            #[widget(row=35, col=1)] _ = FlowBox::new(
+                ["red", "orange", "yellow", "green", "blue", "indigo", "violet", "ultraviolet"]
+                    .iter()
+                    .map(|name| Label::from(*name))
+                    .collect()
+            ),
+            #[widget(row=36, col=0)] _ = Label::from("Overlay"),
+            #[widget(row=36, col=1)] _ = Overlay::new()
+                .with_child(Anchor::FILL, Label::from("Notifications"))
+                .with_child(Anchor::TOP_RIGHT, Label::from("3")),
+            #[widget(row=37, col=0)] _ = Label::from("DockLayout"),
+            #[widget(row=37, col=1)] _ = DockLayout::new()
+                .with_panel("Editor", Label::from("Drag tabs to re-arrange"))
+                .with_panel("Preview", Label::from("Nothing to preview"))
+                .with_docked("Files", Label::from("src/"), DockEdge::Left),
+            #[widget(row=38, col=0)] _ = Label::from("ShortcutEdit"),
+            #[widget(row=38, col=1)] _ = ShortcutEdit::new(Some(Shortcut::new(VirtualKeyCode::F5))),
+            #[widget(row=39, col=0)] _ = Label::from("Zoom"),
+            #[widget(row=39, col=1)] _ = Zoom::new(Label::from("Scroll to zoom, drag to pan"))
+                .with_scale(1.5),
         }
         impl {
             fn handle_scroll(&mut self, _: &mut dyn TkWindow, msg: u32) -> Response<Item> {
                 Response::Msg(Item::Scroll(msg))
             }
+            fn handle_search(&mut self, _: &mut dyn TkWindow, msg: String) -> Response<Item> {
+                Response::Msg(Item::Search(msg))
+            }
+            fn handle_slider(&mut self, _: &mut dyn TkWindow, msg: i32) -> Response<Item> {
+                Response::Msg(Item::Slider(msg))
+            }
+            fn handle_rating(&mut self, _: &mut dyn TkWindow, msg: f32) -> Response<Item> {
+                Response::Msg(Item::Rating(msg))
+            }
+            fn handle_dial(&mut self, _: &mut dyn TkWindow, msg: f64) -> Response<Item> {
+                Response::Msg(Item::Dial(msg))
+            }
+            fn handle_range(&mut self, _: &mut dyn TkWindow, msg: (i32, i32)) -> Response<Item> {
+                Response::Msg(Item::Range(msg.0, msg.1))
+            }
+            fn handle_colour(&mut self, _: &mut dyn TkWindow, msg: Colour) -> Response<Item> {
+                Response::Msg(Item::Colour(msg))
+            }
+            fn handle_date(&mut self, _: &mut dyn TkWindow, msg: Date) -> Response<Item> {
+                Response::Msg(Item::Date(msg))
+            }
+            fn handle_spin(&mut self, _: &mut dyn TkWindow, msg: i32) -> Response<Item> {
+                Response::Msg(Item::Spin(msg))
+            }
         }
     };
 
-    let window = Window::new(
+    let menubar = MenuBar::new(vec![
+        Menu::new("&File")
+            .with_item(MenuItem::new("&Open window", Item::Popup))
+            .with_item(MenuItem::new("&Quit", Item::Menu("Quit"))),
+        Menu::new("&Edit")
+            .with_item(MenuItem::new("&Copy", Item::Menu("Copy")))
+            .with_item(MenuItem::new("&Paste", Item::Menu("Paste")))
+            .with_menu(
+                Menu::new("&Insert")
+                    .with_item(MenuItem::new("&Date", Item::Menu("Insert date")))
+                    .with_item(MenuItem::new("&Time", Item::Menu("Insert time"))),
+            ),
+    ]);
+
+    let mut window = Window::new(
         "Widget Gallery",
         make_widget! {
             vertical => VoidMsg;
             struct {
+                #[widget(handler = activations)] _ = menubar,
                 #[widget] _ = make_widget! {
                     frame => VoidMsg;
                     struct {
@@ -65,6 +274,7 @@ fn main() -> Result<(), kas_wgpu::Error> {
                     }
                 },
                 #[widget(handler = activations)] _ = ScrollRegion::new(widgets).with_auto_bars(true),
+                #[widget] status: StatusBar = StatusBar::new().with_section("Gallery"),
             }
             impl {
                 fn activations(&mut self, tk: &mut dyn TkWindow, item: Item)
@@ -73,10 +283,38 @@ fn main() -> Result<(), kas_wgpu::Error> {
                     match item {
                         Item::Button => println!("Clicked!"),
                         Item::Check(b) => println!("Checkbox: {}", b),
+                        Item::Tristate(s) => println!("Tristate: {:?}", s),
                         Item::Edit(s) => println!("Edited: {}", s),
+                        Item::Search(s) => println!("Search: {}", s),
                         Item::Scroll(p) => println!("ScrollBar: {}", p),
+                        Item::Radio(i) => println!("RadioButton: {}", i),
+                        Item::Slider(i) => println!("Slider: {}", i),
+                        Item::Rating(v) => println!("Rating: {}", v),
+                        Item::Dial(v) => println!("Dial: {:.2}", v),
+                        Item::Range(a, b) => println!("RangeSlider: {}..={}", a, b),
+                        Item::Colour(c) => println!("ColourPicker: {:?}", c),
+                        Item::Date(d) => println!("DatePicker: {}", d),
+                        Item::Spin(v) => println!("SpinButton: {}", v),
+                        Item::Segment(i) => println!("Segment: {}", i),
+                        Item::Menu(s) => {
+                            println!("Menu: {}", s);
+                            let text = format!("Activated: {}", s);
+                            self.status.push_message(tk, text, Duration::from_secs(3));
+                        }
+                        Item::Busy => {
+                            // Simulate asynchronous work; the window is busy
+                            // until the worker drops the handle
+                            let busy = tk.set_busy();
+                            std::thread::spawn(move || {
+                                std::thread::sleep(Duration::from_secs(2));
+                                drop(busy);
+                            });
+                        }
                         Item::Popup => {
-                            let window = MessageBox::new("Popup", "Hello!");
+                            let buttons = DialogButtons::YesNo;
+                            let window = Dialog::new("Popup", "Hello?", buttons, |r| {
+                                println!("Dialog: {:?}", r)
+                            });
                             tk.add_window(Box::new(window));
                         }
                     };
@@ -86,8 +324,15 @@ fn main() -> Result<(), kas_wgpu::Error> {
         },
     );
 
+    window.add_callback(Callback::Repeat(Duration::from_millis(500)), &|w, tk| {
+        w.status.poll(tk)
+    });
+
     let theme = kas_wgpu::SampleTheme::new();
     let mut toolkit = kas_wgpu::Toolkit::new(theme)?;
+    toolkit.set_options(&kas_wgpu::Options::from_env()?)?;
+    #[cfg(feature = "gamepad")]
+    toolkit.enable_gamepad(Default::default());
     toolkit.add(window)?;
     toolkit.run()
 }
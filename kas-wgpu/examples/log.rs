@@ -0,0 +1,36 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Log example (lines appended from a worker thread)
+
+use std::thread;
+use std::time::Duration;
+
+use kas::widget::{LogView, Window};
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let log = LogView::new(1000);
+    let theme = kas_wgpu::SampleTheme::new();
+    let mut toolkit = kas_wgpu::Toolkit::<_, ()>::new_custom(theme, None)?;
+
+    // The worker wakes the event loop after each line, thus redrawing the view
+    let proxy = toolkit.create_proxy();
+    let sender = log.sender().with_wake(move || {
+        let _ = proxy.send_event(());
+    });
+    thread::spawn(move || {
+        for n in 1.. {
+            thread::sleep(Duration::from_millis(250));
+            if !sender.push(format!("Worker: line {}", n)) {
+                break; // the view was closed
+            }
+        }
+    });
+
+    toolkit.add(Window::new("Log", log))?;
+    toolkit.run()
+}
@@ -84,7 +84,7 @@ fn make_window() -> Box<dyn kas::Window> {
 
     let mut window = Window::new("Stopwatch", stopwatch);
 
-    window.add_callback(Callback::Repeat(Duration::from_millis(16)), &|w, tk| {
+    window.add_callback(Callback::Cosmetic(Duration::from_millis(16)), &|w, tk| {
         w.on_tick(tk)
     });
 
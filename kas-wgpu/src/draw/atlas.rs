@@ -0,0 +1,450 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Texture atlas resource management
+//!
+//! Images are packed into fixed-size atlas textures. Total atlas memory is
+//! limited by a (soft) budget: when an allocation would exceed the budget, the
+//! least-recently-used images are evicted.
+
+use log::warn;
+use std::collections::HashMap;
+
+use kas::geom::{Coord, Rect, Size};
+
+/// Width and height of each atlas texture
+pub const ATLAS_SIZE: u32 = 1024;
+/// Bytes per texel (RGBA, 8 bits per channel)
+const TEXEL_BYTES: u32 = 4;
+/// Bytes used by each atlas texture
+const ATLAS_BYTES: u64 = (ATLAS_SIZE * ATLAS_SIZE * TEXEL_BYTES) as u64;
+/// Default budget: 64 MiB
+const DEFAULT_BUDGET: u64 = 64 << 20;
+/// Row alignment required on buffer-to-texture copies
+const ROW_ALIGN: u32 = 256;
+
+/// Identifier of an image allocated in an atlas
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(u32);
+
+/// Summary of texture atlas usage
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Number of atlas textures
+    pub atlases: usize,
+    /// Bytes of GPU memory used by atlas textures
+    pub allocated_bytes: u64,
+    /// Bytes of atlas space occupied by images
+    pub used_bytes: u64,
+    /// Number of images allocated
+    pub images: usize,
+    /// Number of images evicted since creation
+    pub evictions: u64,
+    /// The budget, in bytes
+    pub budget: u64,
+}
+
+/// A horizontal strip of an atlas, holding images of similar height
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Start of unused space at the end of the shelf
+    end: u32,
+    /// Free slots before `end`: `(x, width)`, sorted by `x`
+    free: Vec<(u32, u32)>,
+    items: usize,
+}
+
+impl Shelf {
+    fn alloc(&mut self, width: u32) -> Option<u32> {
+        let x = if let Some(i) = self.free.iter().position(|slot| slot.1 >= width) {
+            let slot = &mut self.free[i];
+            let x = slot.0;
+            if slot.1 == width {
+                self.free.remove(i);
+            } else {
+                slot.0 += width;
+                slot.1 -= width;
+            }
+            x
+        } else if self.end + width <= ATLAS_SIZE {
+            self.end += width;
+            self.end - width
+        } else {
+            return None;
+        };
+        self.items += 1;
+        Some(x)
+    }
+
+    fn free(&mut self, x: u32, width: u32) {
+        self.items -= 1;
+        if self.items == 0 {
+            self.end = 0;
+            self.free.clear();
+            return;
+        }
+
+        let i = self.free.iter().position(|slot| slot.0 > x);
+        let i = i.unwrap_or(self.free.len());
+        self.free.insert(i, (x, width));
+        // Merge with the next and previous slots where adjacent
+        if i + 1 < self.free.len() && x + width == self.free[i + 1].0 {
+            self.free[i].1 += self.free.remove(i + 1).1;
+        }
+        if i > 0 && self.free[i - 1].0 + self.free[i - 1].1 == x {
+            self.free[i - 1].1 += self.free.remove(i).1;
+        }
+        if let Some(last) = self.free.last() {
+            if last.0 + last.1 == self.end {
+                self.end = last.0;
+                self.free.pop();
+            }
+        }
+    }
+}
+
+/// A single atlas texture and its allocator
+struct Atlas {
+    /// The texture is created on first upload
+    texture: Option<(wgpu::Texture, wgpu::TextureView)>,
+    shelves: Vec<Shelf>,
+    items: usize,
+}
+
+impl Atlas {
+    fn new() -> Self {
+        Atlas {
+            texture: None,
+            shelves: vec![],
+            items: 0,
+        }
+    }
+
+    /// Allocate, returning shelf index and position
+    fn alloc(&mut self, size: Size) -> Option<(usize, Coord)> {
+        // Best fit: the lowest shelf tall enough with room
+        let mut best: Option<(usize, u32)> = None;
+        for (i, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height >= size.1 && best.map(|b| shelf.height < b.1).unwrap_or(true) {
+                let has_room = shelf.end + size.0 <= ATLAS_SIZE
+                    || shelf.free.iter().any(|slot| slot.1 >= size.0);
+                if has_room {
+                    best = Some((i, shelf.height));
+                }
+            }
+        }
+
+        let index = match best {
+            Some((i, _)) => i,
+            None => {
+                let y = self.shelves.last().map(|s| s.y + s.height).unwrap_or(0);
+                if y + size.1 > ATLAS_SIZE {
+                    return None;
+                }
+                self.shelves.push(Shelf {
+                    y,
+                    height: size.1,
+                    end: 0,
+                    free: vec![],
+                    items: 0,
+                });
+                self.shelves.len() - 1
+            }
+        };
+
+        let shelf = &mut self.shelves[index];
+        let x = shelf.alloc(size.0)?;
+        self.items += 1;
+        Some((index, Coord(x as i32, shelf.y as i32)))
+    }
+
+    fn free(&mut self, shelf: usize, rect: Rect) {
+        self.items -= 1;
+        self.shelves[shelf].free(rect.pos.0 as u32, rect.size.0);
+        // Trailing empty shelves may be reused at any height
+        while self.shelves.last().map(|s| s.items == 0).unwrap_or(false) {
+            self.shelves.pop();
+        }
+    }
+
+    fn texture(&mut self, device: &wgpu::Device) -> &wgpu::Texture {
+        if self.texture.is_none() {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                size: wgpu::Extent3d {
+                    width: ATLAS_SIZE,
+                    height: ATLAS_SIZE,
+                    depth: 1,
+                },
+                array_layer_count: 1,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+            let view = texture.create_default_view();
+            self.texture = Some((texture, view));
+        }
+        &self.texture.as_ref().unwrap().0
+    }
+}
+
+struct Entry {
+    atlas: usize,
+    shelf: usize,
+    rect: Rect,
+    /// Frame number of last use
+    last_used: u64,
+}
+
+/// Manager of texture atlases
+///
+/// Images are allocated with [`ResourceManager::allocate`] and their contents
+/// supplied with [`ResourceManager::write`]; uploads happen on the next
+/// render. Each frame in which an image is drawn, it should be looked up via
+/// [`ResourceManager::get`], which marks it as used.
+///
+/// When an allocation would exceed the budget, images not used in the current
+/// frame are evicted, least-recently-used first. Evicted images are no longer
+/// [`ResourceManager::contains`]; users should check this and re-allocate as
+/// required. Images used in the current frame are never evicted, thus the
+/// budget may be exceeded (with a warning).
+pub struct ResourceManager {
+    atlases: Vec<Atlas>,
+    entries: HashMap<ImageId, Entry>,
+    uploads: Vec<(ImageId, Vec<u8>)>,
+    next_id: u32,
+    frame: u64,
+    budget: u64,
+    evictions: u64,
+}
+
+impl Default for ResourceManager {
+    fn default() -> Self {
+        ResourceManager::new()
+    }
+}
+
+impl ResourceManager {
+    /// Construct, with the default budget (64 MiB)
+    pub fn new() -> Self {
+        ResourceManager {
+            atlases: vec![],
+            entries: HashMap::new(),
+            uploads: vec![],
+            next_id: 0,
+            frame: 0,
+            budget: DEFAULT_BUDGET,
+            evictions: 0,
+        }
+    }
+
+    /// Set the budget for atlas textures, in bytes
+    ///
+    /// Each atlas uses 4 MiB. Reducing the budget does not evict anything
+    /// immediately; empty atlases beyond the budget are released at the end
+    /// of the next frame.
+    #[inline]
+    pub fn set_budget(&mut self, bytes: u64) {
+        self.budget = bytes;
+    }
+
+    /// Get the budget, in bytes
+    #[inline]
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Query current usage
+    pub fn usage(&self) -> ResourceUsage {
+        let used_bytes = self
+            .entries
+            .values()
+            .map(|e| e.rect.size.0 as u64 * e.rect.size.1 as u64 * TEXEL_BYTES as u64);
+        ResourceUsage {
+            atlases: self.atlases.len(),
+            allocated_bytes: self.atlases.len() as u64 * ATLAS_BYTES,
+            used_bytes: used_bytes.sum(),
+            images: self.entries.len(),
+            evictions: self.evictions,
+            budget: self.budget,
+        }
+    }
+
+    /// Allocate space for an image of the given size
+    ///
+    /// Returns `None` if the image is larger than [`ATLAS_SIZE`] on either
+    /// axis or is empty.
+    pub fn allocate(&mut self, size: Size) -> Option<ImageId> {
+        if size.0 == 0 || size.1 == 0 || size.0 > ATLAS_SIZE || size.1 > ATLAS_SIZE {
+            return None;
+        }
+
+        let (atlas, shelf, pos) = loop {
+            if let Some(result) = self.try_alloc(size) {
+                break result;
+            }
+
+            let new_bytes = (self.atlases.len() as u64 + 1) * ATLAS_BYTES;
+            if new_bytes > self.budget && self.evict_lru() {
+                continue;
+            }
+            if new_bytes > self.budget {
+                warn!(
+                    "ResourceManager: exceeding budget of {} bytes (all images in use)",
+                    self.budget
+                );
+            }
+            self.atlases.push(Atlas::new());
+        };
+
+        let id = ImageId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+        let rect = Rect { pos, size };
+        let entry = Entry {
+            atlas,
+            shelf,
+            rect,
+            last_used: self.frame,
+        };
+        self.entries.insert(id, entry);
+        Some(id)
+    }
+
+    /// Queue the contents of an image for upload
+    ///
+    /// `data` must contain the image's texels in row-major order, 4 bytes
+    /// (RGBA, sRGB) per texel. Does nothing if `id` is not allocated.
+    pub fn write(&mut self, id: ImageId, data: Vec<u8>) {
+        if let Some(entry) = self.entries.get(&id) {
+            let size = entry.rect.size;
+            assert_eq!(data.len(), (size.0 * size.1 * TEXEL_BYTES) as usize);
+            self.uploads.push((id, data));
+        }
+    }
+
+    /// True if `id` is allocated (i.e. has not been freed or evicted)
+    #[inline]
+    pub fn contains(&self, id: ImageId) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    /// Look up an image, marking it as used in this frame
+    ///
+    /// Returns the atlas index and the image's rect within that atlas.
+    pub fn get(&mut self, id: ImageId) -> Option<(usize, Rect)> {
+        let frame = self.frame;
+        self.entries.get_mut(&id).map(|entry| {
+            entry.last_used = frame;
+            (entry.atlas, entry.rect)
+        })
+    }
+
+    /// Get the texture view of an atlas, if created
+    pub fn atlas_view(&self, atlas: usize) -> Option<&wgpu::TextureView> {
+        self.atlases
+            .get(atlas)
+            .and_then(|a| a.texture.as_ref())
+            .map(|t| &t.1)
+    }
+
+    /// Free an image
+    pub fn free(&mut self, id: ImageId) {
+        if let Some(entry) = self.entries.remove(&id) {
+            self.atlases[entry.atlas].free(entry.shelf, entry.rect);
+        }
+    }
+
+    /// Upload queued image data
+    pub(crate) fn prepare(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+        for (id, data) in std::mem::take(&mut self.uploads) {
+            let entry = match self.entries.get(&id) {
+                Some(entry) => entry,
+                None => continue, // evicted or freed before upload
+            };
+            let size = entry.rect.size;
+
+            // Rows must be padded to the required alignment
+            let row_bytes = size.0 * TEXEL_BYTES;
+            let row_pitch = row_bytes.div_ceil(ROW_ALIGN) * ROW_ALIGN;
+            let mut padded = vec![0u8; (row_pitch * size.1) as usize];
+            for (src, dst) in data
+                .chunks(row_bytes as usize)
+                .zip(padded.chunks_mut(row_pitch as usize))
+            {
+                dst[..row_bytes as usize].copy_from_slice(src);
+            }
+            let buffer = device
+                .create_buffer_mapped(padded.len(), wgpu::BufferUsage::COPY_SRC)
+                .fill_from_slice(&padded);
+
+            let texture = self.atlases[entry.atlas].texture(device);
+            encoder.copy_buffer_to_texture(
+                wgpu::BufferCopyView {
+                    buffer: &buffer,
+                    offset: 0,
+                    row_pitch,
+                    image_height: size.1,
+                },
+                wgpu::TextureCopyView {
+                    texture,
+                    mip_level: 0,
+                    array_layer: 0,
+                    origin: wgpu::Origin3d {
+                        x: entry.rect.pos.0 as f32,
+                        y: entry.rect.pos.1 as f32,
+                        z: 0.0,
+                    },
+                },
+                wgpu::Extent3d {
+                    width: size.0,
+                    height: size.1,
+                    depth: 1,
+                },
+            );
+        }
+    }
+
+    /// Advance the frame counter, releasing empty atlases beyond budget
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+        while self.atlases.len() as u64 * ATLAS_BYTES > self.budget
+            && self.atlases.last().map(|a| a.items == 0).unwrap_or(false)
+        {
+            self.atlases.pop();
+        }
+    }
+
+    fn try_alloc(&mut self, size: Size) -> Option<(usize, usize, Coord)> {
+        for (i, atlas) in self.atlases.iter_mut().enumerate() {
+            if let Some((shelf, pos)) = atlas.alloc(size) {
+                return Some((i, shelf, pos));
+            }
+        }
+        None
+    }
+
+    /// Evict the least-recently-used image not used this frame
+    ///
+    /// Returns false if there is nothing to evict.
+    fn evict_lru(&mut self) -> bool {
+        let frame = self.frame;
+        let lru = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.last_used < frame)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(id, _)| *id);
+        match lru {
+            Some(id) => {
+                self.free(id);
+                self.evictions += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
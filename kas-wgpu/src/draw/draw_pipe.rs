@@ -9,14 +9,22 @@
 
 use std::any::Any;
 use std::borrow::Cow;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
 use std::f32::consts::FRAC_PI_2;
+use std::rc::Rc;
 
-use wgpu_glyph::{GlyphBrush, GlyphBrushBuilder, GlyphCruncher, VariedSection};
+use wgpu_glyph::{
+    FontId, GlyphBrush, GlyphBrushBuilder, GlyphCruncher, GlyphPositioner, Scale, SectionGeometry,
+    VariedSection,
+};
 
-use kas::draw::{Colour, Draw, Quad, Style, Vec2};
+use kas::draw::{Colour, Draw, Quad, RgbaImage, Style, Transform, Vec2};
 use kas::geom::{Coord, Rect, Size};
 use kas::theme;
 
+use super::atlas::{ImageId, ResourceManager};
+use super::image_pipe::ImagePipe;
 use super::round_pipe::RoundPipe;
 use super::square_pipe::SquarePipe;
 
@@ -28,7 +36,12 @@ use super::square_pipe::SquarePipe;
 /// `SampleTheme` to `kas`.
 pub trait DrawText {
     /// Queues a text section/layout.
-    fn draw_text<'a, S>(&mut self, section: S)
+    ///
+    /// The `pass` number is used to determine whether the text belongs to an
+    /// overlay region (see [`Draw::add_overlay_region`]) and to apply the
+    /// pass's transform (see [`Draw::set_transform`]); text is not currently
+    /// clipped to its region.
+    fn draw_text<'a, S>(&mut self, pass: usize, section: S)
     where
         S: Into<Cow<'a, VariedSection<'a>>>;
 
@@ -47,23 +60,104 @@ pub trait DrawText {
     fn glyph_bounds<'a, S>(&mut self, section: S) -> Option<(Vec2, Vec2)>
     where
         S: Into<Cow<'a, VariedSection<'a>>>;
+
+    /// Lays out a section, returning a box and the colour of each glyph.
+    ///
+    /// Each box spans the glyph's advance width and the height of its line
+    /// (including the line gap). As with drawing, invisible glyphs (e.g.
+    /// spaces) and control characters are omitted.
+    ///
+    /// This does not use the cache.
+    fn glyph_boxes(&mut self, section: &VariedSection) -> Vec<(Vec2, Vec2, [f32; 4])>;
+
+    /// Returns the advance width of a character with the given font and scale.
+    fn char_advance(&mut self, c: char, font: FontId, scale: Scale) -> f32;
+}
+
+/// Statistics on the last frame rendered
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DrawStats {
+    /// Number of render passes (one per clip region)
+    pub passes: usize,
+    /// Number of (non-empty) draw calls, excluding text
+    pub draw_calls: usize,
+    /// Number of vertices drawn, excluding text
+    pub vertices: usize,
+}
+
+struct Shared {
+    atlases: ResourceManager,
+    // Atlas allocations of uploaded images, by RgbaImage::key
+    images: HashMap<u64, ImageId>,
+    glyph_brush: GlyphBrush<'static, ()>,
+    overlay_glyph_brush: GlyphBrush<'static, ()>,
+}
+
+/// Drawing resources shared by all windows
+///
+/// This holds the texture atlases used for images (see [`ResourceManager`])
+/// and the glyph caches used for text, thus each image or glyph is uploaded
+/// only once however many windows use it. Handles are reference counted: each
+/// [`DrawPipe`] holds one, and resources are released with the last.
+///
+/// Since windows are drawn in turn, an image last drawn by one window may be
+/// evicted to make room for another window's images; it is then uploaded
+/// again when next drawn.
+#[derive(Clone)]
+pub struct SharedResources(Rc<RefCell<Shared>>);
+
+impl SharedResources {
+    /// Construct
+    ///
+    /// Glyph caches use the fonts of the given `theme` and render to targets
+    /// of format `tex_format`.
+    pub fn new<D: theme::Theme<DrawPipe>>(
+        device: &mut wgpu::Device,
+        tex_format: wgpu::TextureFormat,
+        theme: &D,
+    ) -> Self {
+        let glyph_brush =
+            GlyphBrushBuilder::using_fonts(theme.get_fonts()).build(device, tex_format);
+        // Text in overlays is drawn after other text, thus needs its own queue
+        let overlay_glyph_brush =
+            GlyphBrushBuilder::using_fonts(theme.get_fonts()).build(device, tex_format);
+        SharedResources(Rc::new(RefCell::new(Shared {
+            atlases: ResourceManager::new(),
+            images: HashMap::new(),
+            glyph_brush,
+            overlay_glyph_brush,
+        })))
+    }
+
+    /// Number of handles (usually one per window, plus one for the toolkit)
+    #[inline]
+    pub fn handles(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
 }
 
 /// Manager of draw pipes and implementor of [`Draw`]
 pub struct DrawPipe {
     clip_regions: Vec<Rect>,
+    // Transform of each pass, applied when drawn
+    transforms: Vec<Transform>,
+    // Size of the render target; this differs from the window rect (the size
+    // of the content) while a resize snapshot is shown
+    target: Size,
+    // Pass numbers of overlay regions, in the order added
+    overlays: Vec<usize>,
+    stats: DrawStats,
+    shared: SharedResources,
     round_pipe: RoundPipe,
     square_pipe: SquarePipe,
-    glyph_brush: GlyphBrush<'static, ()>,
+    image_pipe: ImagePipe,
 }
 
 impl DrawPipe {
-    /// Construct
-    // TODO: do we want to share state across windows? With glyph_brush this is
-    // not trivial but with our "pipes" it shouldn't be difficult.
+    /// Construct, using the given `shared` resources
     pub fn new<D: theme::Theme<Self>>(
         device: &mut wgpu::Device,
-        tex_format: wgpu::TextureFormat,
+        shared: SharedResources,
         size: Size,
         theme: &D,
     ) -> Self {
@@ -75,31 +169,98 @@ impl DrawPipe {
         let f = a.0 / a.1;
         let norm = [dir.1.sin() * f, -dir.1.cos() * f, 1.0];
 
-        let glyph_brush =
-            GlyphBrushBuilder::using_fonts(theme.get_fonts()).build(device, tex_format);
-
         let region = Rect {
             pos: Coord::ZERO,
             size,
         };
         DrawPipe {
             clip_regions: vec![region],
+            transforms: vec![Transform::IDENTITY],
+            target: size,
+            overlays: vec![],
+            stats: DrawStats::default(),
+            shared,
             square_pipe: SquarePipe::new(device, size, norm),
             round_pipe: RoundPipe::new(device, size, norm),
-            glyph_brush,
+            image_pipe: ImagePipe::new(device, size),
+        }
+    }
+
+    /// Get statistics on the last frame rendered
+    #[inline]
+    pub fn stats(&self) -> DrawStats {
+        self.stats
+    }
+
+    /// Access the shared resources
+    #[inline]
+    pub fn shared(&self) -> &SharedResources {
+        &self.shared
+    }
+
+    /// Access the texture atlas manager
+    ///
+    /// This is shared by all windows (see [`SharedResources`]).
+    #[inline]
+    pub fn resources(&self) -> Ref<'_, ResourceManager> {
+        Ref::map(self.shared.0.borrow(), |shared| &shared.atlases)
+    }
+
+    /// Access the texture atlas manager mutably
+    ///
+    /// This is shared by all windows (see [`SharedResources`]).
+    #[inline]
+    pub fn resources_mut(&mut self) -> RefMut<'_, ResourceManager> {
+        RefMut::map(self.shared.0.borrow_mut(), |shared| &mut shared.atlases)
+    }
+
+    /// Upload an image to a texture atlas
+    ///
+    /// Images are identified by [`RgbaImage::key`]; an image already resident
+    /// is not uploaded again. Returns `None` if the image cannot be allocated
+    /// (see [`ResourceManager::allocate`]).
+    ///
+    /// It is not usually necessary to call this directly: [`Draw::draw_image`]
+    /// uploads images on first use.
+    pub fn upload_image(&mut self, image: &RgbaImage) -> Option<ImageId> {
+        let shared = &mut *self.shared.0.borrow_mut();
+        if let Some(id) = shared.images.get(&image.key()) {
+            if shared.atlases.contains(*id) {
+                return Some(*id);
+            }
         }
+        let id = shared.atlases.allocate(image.size())?;
+        shared.atlases.write(id, image.data().to_vec());
+        shared.images.insert(image.key(), id);
+        Some(id)
+    }
+
+    /// The first clip region, covering the whole window
+    #[inline]
+    pub(crate) fn window_rect(&self) -> Rect {
+        self.clip_regions[0]
     }
 
     /// Process window resize
     pub fn resize(&mut self, device: &wgpu::Device, size: Size) -> wgpu::CommandBuffer {
         self.clip_regions[0].size = size;
+        self.target = size;
         let mut encoder =
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 });
         self.square_pipe.resize(device, &mut encoder, size);
         self.round_pipe.resize(device, &mut encoder, size);
+        self.image_pipe.resize(device, &mut encoder, size);
         encoder.finish()
     }
 
+    /// Set the size of the render target, without resizing content
+    ///
+    /// Content is scaled to fill the target. Call [`DrawPipe::resize`] to
+    /// restore an unscaled mapping.
+    pub fn set_target_size(&mut self, size: Size) {
+        self.target = size;
+    }
+
     /// Render batched draw instructions via `rpass`
     pub fn render(
         &mut self,
@@ -109,15 +270,108 @@ impl DrawPipe {
     ) -> wgpu::CommandBuffer {
         let desc = wgpu::CommandEncoderDescriptor { todo: 0 };
         let mut encoder = device.create_command_encoder(&desc);
-        let mut load_op = wgpu::LoadOp::Clear;
+        // The first pass clears the frame; later passes load it
+        let mut clear = Some(clear_color);
+        self.stats = DrawStats {
+            passes: self.clip_regions.len(),
+            ..DrawStats::default()
+        };
+
+        let shared = self.shared.clone();
+        let shared = &mut *shared.0.borrow_mut();
+        shared.atlases.prepare(device, &mut encoder);
+
+        // We use a separate render pass for each clipped region. Overlay
+        // regions (and their text) are rendered after all other regions.
+        let overlays = std::mem::take(&mut self.overlays);
+        let passes = (0..self.clip_regions.len()).filter(|pass| !overlays.contains(pass));
+        self.render_passes(
+            device,
+            &mut encoder,
+            frame_view,
+            passes,
+            &mut clear,
+            &shared.atlases,
+        );
+
+        // Fonts use their own render pass(es).
+        let size = self.clip_regions[0].size;
+        (shared.glyph_brush)
+            .draw_queued(device, &mut encoder, frame_view, size.0, size.1)
+            .expect("glyph_brush.draw_queued");
+
+        if !overlays.is_empty() {
+            let passes = overlays.iter().cloned();
+            self.render_passes(
+                device,
+                &mut encoder,
+                frame_view,
+                passes,
+                &mut clear,
+                &shared.atlases,
+            );
+            (shared.overlay_glyph_brush)
+                .draw_queued(device, &mut encoder, frame_view, size.0, size.1)
+                .expect("glyph_brush.draw_queued");
+        }
+
+        // Keep only first clip region (which is the entire window)
+        self.clip_regions.truncate(1);
+        self.transforms.truncate(1);
+        self.overlays = overlays;
+        self.overlays.clear();
+        shared.atlases.end_frame();
+        let atlases = &shared.atlases;
+        shared.images.retain(|_, id| atlases.contains(*id));
 
-        // We use a separate render pass for each clipped region.
-        for (pass, region) in self.clip_regions.iter().enumerate() {
+        encoder.finish()
+    }
+
+    // Scissor rect of a pass on the render target
+    fn target_region(&self, pass: usize) -> Rect {
+        let region = self.clip_regions[pass];
+        let window = self.clip_regions[0].size;
+        if window == self.target {
+            return region;
+        }
+        let scale = |x: i32, axis: usize| {
+            let (from, to) = match axis {
+                0 => (window.0, self.target.0),
+                _ => (window.1, self.target.1),
+            };
+            (x.max(0) as u64 * to as u64 / from.max(1) as u64).min(to as u64) as u32
+        };
+        let x0 = scale(region.pos.0, 0);
+        let y0 = scale(region.pos.1, 1);
+        let x1 = scale(region.pos.0 + region.size.0 as i32, 0);
+        let y1 = scale(region.pos.1 + region.size.1 as i32, 1);
+        Rect {
+            pos: Coord(x0 as i32, y0 as i32),
+            size: Size(x1 - x0, y1 - y0),
+        }
+    }
+
+    // Render the given passes, in order
+    fn render_passes<I: Iterator<Item = usize>>(
+        &mut self,
+        device: &mut wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        passes: I,
+        clear: &mut Option<wgpu::Color>,
+        resources: &ResourceManager,
+    ) {
+        for pass in passes {
+            let region = self.target_region(pass);
+            let (load_op, clear_color) = match clear.take() {
+                Some(colour) => (wgpu::LoadOp::Clear, colour),
+                None => (wgpu::LoadOp::Load, wgpu::Color::BLACK),
+            };
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
                     attachment: frame_view,
                     resolve_target: None,
-                    load_op: load_op,
+                    load_op,
                     store_op: wgpu::StoreOp::Store,
                     clear_color,
                 }],
@@ -130,23 +384,17 @@ impl DrawPipe {
                 region.size.1,
             );
 
-            self.square_pipe.render(device, pass, &mut rpass);
-            self.round_pipe.render(device, pass, &mut rpass);
-            drop(rpass);
-
-            load_op = wgpu::LoadOp::Load;
+            for count in &[
+                self.square_pipe.render(device, pass, &mut rpass),
+                self.round_pipe.render(device, pass, &mut rpass),
+                self.image_pipe.render(device, pass, &mut rpass, resources),
+            ] {
+                if *count > 0 {
+                    self.stats.draw_calls += 1;
+                    self.stats.vertices += *count as usize;
+                }
+            }
         }
-
-        // Fonts use their own render pass(es).
-        let size = self.clip_regions[0].size;
-        self.glyph_brush
-            .draw_queued(device, &mut encoder, frame_view, size.0, size.1)
-            .expect("glyph_brush.draw_queued");
-
-        // Keep only first clip region (which is the entire window)
-        self.clip_regions.truncate(1);
-
-        encoder.finish()
     }
 }
 
@@ -159,18 +407,32 @@ impl Draw for DrawPipe {
     fn add_clip_region(&mut self, region: Rect) -> usize {
         let pass = self.clip_regions.len();
         self.clip_regions.push(region);
+        self.transforms.push(Transform::IDENTITY);
         pass
     }
 
+    fn add_overlay_region(&mut self, region: Rect) -> usize {
+        let pass = self.add_clip_region(region);
+        self.overlays.push(pass);
+        pass
+    }
+
+    fn set_transform(&mut self, pass: usize, transform: Transform) {
+        self.transforms[pass] = transform;
+    }
+
     #[inline]
     fn draw_quad(&mut self, pass: usize, quad: Quad, style: Style, col: Colour) {
         // TODO: support styles
         let _ = style;
+        let quad = self.transforms[pass].apply_quad(quad);
         self.square_pipe.add_quad(pass, quad, col)
     }
 
     #[inline]
     fn draw_frame(&mut self, pass: usize, outer: Quad, inner: Quad, style: Style, col: Colour) {
+        let t = self.transforms[pass];
+        let (outer, inner) = (t.apply_quad(outer), t.apply_quad(inner));
         match style {
             Style::Flat => self
                 .square_pipe
@@ -179,15 +441,59 @@ impl Draw for DrawPipe {
             Style::Round(norm) => self.round_pipe.add_frame(pass, outer, inner, norm, col),
         }
     }
+
+    #[inline]
+    fn draw_triangle(&mut self, pass: usize, a: Vec2, b: Vec2, c: Vec2, col: Colour) {
+        let t = self.transforms[pass];
+        let (a, b, c) = (t.apply(a), t.apply(b), t.apply(c));
+        self.square_pipe.add_triangle(pass, a, b, c, col)
+    }
+
+    #[inline]
+    fn draw_gradient(&mut self, pass: usize, quad: Quad, cols: [Colour; 4]) {
+        let quad = self.transforms[pass].apply_quad(quad);
+        self.square_pipe.add_gradient(pass, quad, cols)
+    }
+
+    fn draw_image(&mut self, pass: usize, quad: Quad, image: &RgbaImage) {
+        let id = match self.upload_image(image) {
+            Some(id) => id,
+            None => return,
+        };
+        let atlas_rect = self.shared.0.borrow_mut().atlases.get(id);
+        if let Some((atlas, rect)) = atlas_rect {
+            let quad = self.transforms[pass].apply_quad(quad);
+            self.image_pipe.add_quad(pass, atlas, quad, rect);
+        }
+    }
 }
 
 impl DrawText for DrawPipe {
     #[inline]
-    fn draw_text<'a, S>(&mut self, section: S)
+    fn draw_text<'a, S>(&mut self, pass: usize, section: S)
     where
         S: Into<Cow<'a, VariedSection<'a>>>,
     {
-        self.glyph_brush.queue(section)
+        let mut section = section.into();
+        let t = self.transforms[pass];
+        if !t.is_identity() {
+            let section = section.to_mut();
+            let pos = t.apply(section.screen_position.into());
+            section.screen_position = pos.into();
+            section.bounds = (Vec2::from(section.bounds) * t.scale).into();
+            for text in &mut section.text {
+                text.scale = Scale {
+                    x: text.scale.x * t.scale,
+                    y: text.scale.y * t.scale,
+                };
+            }
+        }
+        let mut shared = self.shared.0.borrow_mut();
+        if self.overlays.contains(&pass) {
+            shared.overlay_glyph_brush.queue(section)
+        } else {
+            shared.glyph_brush.queue(section)
+        }
     }
 
     #[inline]
@@ -195,8 +501,40 @@ impl DrawText for DrawPipe {
     where
         S: Into<Cow<'a, VariedSection<'a>>>,
     {
-        self.glyph_brush
+        (self.shared.0.borrow_mut().glyph_brush)
             .glyph_bounds(section)
             .map(|rect| (Vec2(rect.min.x, rect.min.y), Vec2(rect.max.x, rect.max.y)))
     }
+
+    fn glyph_boxes(&mut self, section: &VariedSection) -> Vec<(Vec2, Vec2, [f32; 4])> {
+        let shared = self.shared.0.borrow();
+        let geometry = SectionGeometry {
+            screen_position: section.screen_position,
+            bounds: section.bounds,
+        };
+        let fonts = shared.glyph_brush.fonts();
+        (section.layout)
+            .calculate_glyphs(&fonts, &geometry, &section.text)
+            .into_iter()
+            .map(|(glyph, colour, _)| {
+                let pos = glyph.position();
+                let advance = glyph.unpositioned().h_metrics().advance_width;
+                let v = match glyph.font() {
+                    Some(font) => font.v_metrics(glyph.scale()),
+                    None => return (Vec2(pos.x, pos.y), Vec2(pos.x + advance, pos.y), colour),
+                };
+                let min = Vec2(pos.x, pos.y - v.ascent);
+                let max = Vec2(pos.x + advance, pos.y - v.descent + v.line_gap);
+                (min, max, colour)
+            })
+            .collect()
+    }
+
+    fn char_advance(&mut self, c: char, font: FontId, scale: Scale) -> f32 {
+        let shared = self.shared.0.borrow();
+        match shared.glyph_brush.fonts().get(font.0) {
+            Some(font) => font.glyph(c).scaled(scale).h_metrics().advance_width,
+            None => 0.0,
+        }
+    }
 }
@@ -0,0 +1,275 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Pipeline for textured quads (images)
+
+use std::mem::size_of;
+
+use lazy_static::lazy_static;
+
+use kas::draw::{Quad, Vec2};
+use kas::geom::{Rect, Size};
+
+use super::atlas::{ResourceManager, ATLAS_SIZE};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct Vertex(Vec2, Vec2);
+
+/// A pipeline for rendering images from texture atlases
+pub struct ImagePipe {
+    bind_group: wgpu::BindGroup,
+    atlas_layout: wgpu::BindGroupLayout,
+    scale_buf: wgpu::Buffer,
+    render_pipeline: wgpu::RenderPipeline,
+    /// Vertices by pass, then by atlas
+    passes: Vec<Vec<Vec<Vertex>>>,
+}
+
+lazy_static! {
+    static ref VS_BYTES: Vec<u32> = super::read_glsl(
+        include_str!("shaders/image.vert"),
+        glsl_to_spirv::ShaderType::Vertex,
+    );
+    static ref FS_BYTES: Vec<u32> = super::read_glsl(
+        include_str!("shaders/image.frag"),
+        glsl_to_spirv::ShaderType::Fragment,
+    );
+}
+
+impl ImagePipe {
+    /// Construct
+    pub fn new(device: &wgpu::Device, size: Size) -> Self {
+        let vs_module = device.create_shader_module(&VS_BYTES);
+        let fs_module = device.create_shader_module(&FS_BYTES);
+
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, 2.0 / size.1 as f32];
+        let scale_buf = device
+            .create_buffer_mapped(
+                scale_factor.len(),
+                wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            )
+            .fill_from_slice(&scale_factor);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare_function: wgpu::CompareFunction::Always,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[
+                wgpu::BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                },
+                wgpu::BindGroupLayoutBinding {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &scale_buf,
+                        range: 0..(size_of::<Scale>() as u64),
+                    },
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        // The atlas texture is bound separately, since it varies per draw
+        let atlas_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[wgpu::BindGroupLayoutBinding {
+                binding: 0,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::SampledTexture {
+                    multisampled: false,
+                    dimension: wgpu::TextureViewDimension::D2,
+                },
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[&bind_group_layout, &atlas_layout],
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            layout: &pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor {
+                module: &vs_module,
+                entry_point: "main",
+            },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                module: &fs_module,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::None,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+            }),
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            color_states: &[wgpu::ColorStateDescriptor {
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                write_mask: wgpu::ColorWrite::ALL,
+            }],
+            depth_stencil_state: None,
+            index_format: wgpu::IndexFormat::Uint16,
+            vertex_buffers: &[wgpu::VertexBufferDescriptor {
+                stride: size_of::<Vertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::InputStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttributeDescriptor {
+                        format: wgpu::VertexFormat::Float2,
+                        offset: 0,
+                        shader_location: 0,
+                    },
+                    wgpu::VertexAttributeDescriptor {
+                        format: wgpu::VertexFormat::Float2,
+                        offset: size_of::<Vec2>() as u64,
+                        shader_location: 1,
+                    },
+                ],
+            }],
+            sample_count: 1,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        });
+
+        ImagePipe {
+            bind_group,
+            atlas_layout,
+            scale_buf,
+            render_pipeline,
+            passes: vec![],
+        }
+    }
+
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        size: Size,
+    ) {
+        type Scale = [f32; 2];
+        let scale_factor: Scale = [2.0 / size.0 as f32, 2.0 / size.1 as f32];
+        let scale_buf = device
+            .create_buffer_mapped(scale_factor.len(), wgpu::BufferUsage::COPY_SRC)
+            .fill_from_slice(&scale_factor);
+        let byte_len = size_of::<Scale>() as u64;
+
+        encoder.copy_buffer_to_buffer(&scale_buf, 0, &self.scale_buf, 0, byte_len);
+    }
+
+    /// Render queued images and clear the queue
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass,
+        resources: &ResourceManager,
+    ) -> u32 {
+        if pass >= self.passes.len() {
+            return 0;
+        }
+        let mut count = 0;
+        for (atlas, v) in self.passes[pass].iter_mut().enumerate() {
+            let view = match resources.atlas_view(atlas) {
+                Some(view) if !v.is_empty() => view,
+                _ => {
+                    v.clear();
+                    continue;
+                }
+            };
+            let atlas_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &self.atlas_layout,
+                bindings: &[wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                }],
+            });
+            let buffer = device
+                .create_buffer_mapped(v.len(), wgpu::BufferUsage::VERTEX)
+                .fill_from_slice(v);
+            let n = v.len() as u32;
+
+            rpass.set_pipeline(&self.render_pipeline);
+            rpass.set_bind_group(0, &self.bind_group, &[]);
+            rpass.set_bind_group(1, &atlas_group, &[]);
+            rpass.set_vertex_buffers(0, &[(&buffer, 0)]);
+            rpass.draw(0..n, 0..1);
+
+            v.clear();
+            count += n;
+        }
+        count
+    }
+
+    /// Add a textured rectangle, with corners `quad`, showing the region
+    /// `tex` of the given `atlas`.
+    pub fn add_quad(&mut self, pass: usize, atlas: usize, quad: Quad, tex: Rect) {
+        let (aa, bb) = (quad.0, quad.1);
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let ab = Vec2(aa.0, bb.1);
+        let ba = Vec2(bb.0, aa.1);
+
+        let scale = 1.0 / ATLAS_SIZE as f32;
+        let taa = Vec2::from(tex.pos) * scale;
+        let tbb = Vec2::from(tex.pos + tex.size) * scale;
+        let tab = Vec2(taa.0, tbb.1);
+        let tba = Vec2(tbb.0, taa.1);
+
+        if self.passes.len() <= pass {
+            // We only need one more, but no harm in adding extra
+            self.passes.resize(pass + 8, vec![]);
+        }
+        let atlases = &mut self.passes[pass];
+        if atlases.len() <= atlas {
+            atlases.resize(atlas + 1, vec![]);
+        }
+
+        #[rustfmt::skip]
+        atlases[atlas].extend_from_slice(&[
+            Vertex(aa, taa), Vertex(ba, tba), Vertex(ab, tab),
+            Vertex(ab, tab), Vertex(ba, tba), Vertex(bb, tbb),
+        ]);
+    }
+}
@@ -9,11 +9,14 @@
 //!
 //! All drawing operations are batched and do not happen immediately.
 
+mod atlas;
 mod draw_pipe;
+mod image_pipe;
 mod round_pipe;
 mod square_pipe;
 
-pub use draw_pipe::{DrawPipe, DrawText};
+pub use atlas::{ImageId, ResourceManager, ResourceUsage, ATLAS_SIZE};
+pub use draw_pipe::{DrawPipe, DrawStats, DrawText, SharedResources};
 
 pub fn read_glsl(code: &str, stage: glsl_to_spirv::ShaderType) -> Vec<u32> {
     wgpu::read_spirv(glsl_to_spirv::compile(&code, stage).unwrap()).unwrap()
@@ -37,3 +40,24 @@ impl From<kas::draw::Colour> for Rgb {
         }
     }
 }
+
+/// 4-part colour data, with (non-premultiplied) alpha
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Rgba {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl From<kas::draw::Colour> for Rgba {
+    fn from(c: kas::draw::Colour) -> Self {
+        Rgba {
+            r: c.r,
+            g: c.g,
+            b: c.b,
+            a: c.a,
+        }
+    }
+}
@@ -179,9 +179,16 @@ impl RoundPipe {
     }
 
     /// Render queued triangles and clear the queue
-    pub fn render(&mut self, device: &wgpu::Device, pass: usize, rpass: &mut wgpu::RenderPass) {
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass,
+    ) -> u32 {
         if pass >= self.passes.len() {
-            return;
+            return 0;
         }
         let v = &mut self.passes[pass];
         let buffer = device
@@ -195,6 +202,7 @@ impl RoundPipe {
         rpass.draw(0..count, 0..1);
 
         v.clear();
+        count
     }
 
     /// Bounds on input: `aa < cc < dd < bb` and `-1 ≤ norm ≤ 1`.
@@ -7,10 +7,10 @@
 #extension GL_ARB_separate_shader_objects : enable
 
 layout(location = 0) in vec2 a_Pos;
-layout(location = 1) in vec3 a_Color;
+layout(location = 1) in vec4 a_Color;
 layout(location = 2) in vec2 a_Norm;
 
-layout(location = 0) out vec3 fragColor;
+layout(location = 0) out vec4 fragColor;
 layout(location = 1) out vec2 norm2;
 
 layout(set = 0, binding = 0) uniform Locals {
@@ -13,11 +13,11 @@ use lazy_static::lazy_static;
 use kas::draw::*;
 use kas::geom::Size;
 
-use super::Rgb;
+use super::Rgba;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
-struct Vertex(Vec2, Rgb, Vec2);
+struct Vertex(Vec2, Rgba, Vec2);
 
 /// A pipeline for rendering with flat and square-corner shading
 pub struct SquarePipe {
@@ -117,8 +117,16 @@ impl SquarePipe {
             primitive_topology: wgpu::PrimitiveTopology::TriangleList,
             color_states: &[wgpu::ColorStateDescriptor {
                 format: wgpu::TextureFormat::Bgra8UnormSrgb,
-                color_blend: wgpu::BlendDescriptor::REPLACE,
-                alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                color_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha_blend: wgpu::BlendDescriptor {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
                 write_mask: wgpu::ColorWrite::ALL,
             }],
             depth_stencil_state: None,
@@ -133,13 +141,13 @@ impl SquarePipe {
                         shader_location: 0,
                     },
                     wgpu::VertexAttributeDescriptor {
-                        format: wgpu::VertexFormat::Float3,
+                        format: wgpu::VertexFormat::Float4,
                         offset: size_of::<Vec2>() as u64,
                         shader_location: 1,
                     },
                     wgpu::VertexAttributeDescriptor {
                         format: wgpu::VertexFormat::Float2,
-                        offset: (size_of::<Vec2>() + size_of::<Rgb>()) as u64,
+                        offset: (size_of::<Vec2>() + size_of::<Rgba>()) as u64,
                         shader_location: 2,
                     },
                 ],
@@ -174,9 +182,16 @@ impl SquarePipe {
     }
 
     /// Render queued triangles and clear the queue
-    pub fn render(&mut self, device: &wgpu::Device, pass: usize, rpass: &mut wgpu::RenderPass) {
+    ///
+    /// Returns the number of vertices drawn.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        pass: usize,
+        rpass: &mut wgpu::RenderPass,
+    ) -> u32 {
         if pass >= self.passes.len() {
-            return;
+            return 0;
         }
         let v = &mut self.passes[pass];
         let buffer = device
@@ -190,6 +205,7 @@ impl SquarePipe {
         rpass.draw(0..count, 0..1);
 
         v.clear();
+        count
     }
 
     /// Add a rectangle to the buffer defined by two corners, `aa` and `bb`
@@ -216,10 +232,51 @@ impl SquarePipe {
         ]);
     }
 
+    /// Add a rectangle with corner colours `cols` (top-left, top-right,
+    /// bottom-left, bottom-right)
+    ///
+    /// This is drawn as four triangles meeting at the centre, whose colour is
+    /// the mean of the corners.
+    pub fn add_gradient(&mut self, pass: usize, quad: Quad, cols: [Colour; 4]) {
+        let (aa, bb) = (quad.0, quad.1);
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+
+        let ab = Vec2(aa.0, bb.1);
+        let ba = Vec2(bb.0, aa.1);
+        let mid = (aa + bb) * 0.5;
+
+        let mean = |f: fn(&Colour) -> f32| 0.25 * cols.iter().map(f).sum::<f32>();
+        let mid_col = Colour::new(mean(|c| c.r), mean(|c| c.g), mean(|c| c.b)).into();
+        let [c_aa, c_ba, c_ab, c_bb] = cols;
+        let (c_aa, c_ba, c_ab, c_bb) = (c_aa.into(), c_ba.into(), c_ab.into(), c_bb.into());
+        let t = Vec2(0.0, 0.0);
+
+        #[rustfmt::skip]
+        self.add_vertices(pass, &[
+            Vertex(aa, c_aa, t), Vertex(ba, c_ba, t), Vertex(mid, mid_col, t),
+            Vertex(ba, c_ba, t), Vertex(bb, c_bb, t), Vertex(mid, mid_col, t),
+            Vertex(bb, c_bb, t), Vertex(ab, c_ab, t), Vertex(mid, mid_col, t),
+            Vertex(ab, c_ab, t), Vertex(aa, c_aa, t), Vertex(mid, mid_col, t),
+        ]);
+    }
+
     /// Add a frame to the buffer, defined by two outer corners, `aa` and `bb`,
     /// and two inner corners, `cc` and `dd` with colour `col`.
     ///
     /// Bounds on input: `aa < cc < dd < bb` and `-1 ≤ norm ≤ 1`.
+    /// Add a flat-shaded triangle
+    pub fn add_triangle(&mut self, pass: usize, a: Vec2, b: Vec2, c: Vec2, col: Colour) {
+        let col = col.into();
+        let t = Vec2(0.0, 0.0);
+        self.add_vertices(
+            pass,
+            &[Vertex(a, col, t), Vertex(b, col, t), Vertex(c, col, t)],
+        );
+    }
+
     pub fn add_frame(
         &mut self,
         pass: usize,
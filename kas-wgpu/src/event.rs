@@ -5,51 +5,187 @@
 
 //! Event handling
 
-use log::{debug, error, trace};
+use log::{debug, error, info, trace};
+use std::mem::{discriminant, replace};
 use std::time::Instant;
 
-use winit::event::{Event, StartCause};
+use winit::event::{ElementState, Event, StartCause, VirtualKeyCode, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoopWindowTarget};
+use winit::window::WindowId;
 
-use kas::{theme, TkAction};
+use kas::{theme, Modality, TkAction};
 
 use crate::draw::DrawPipe;
-use crate::{SharedState, Window};
+use crate::fuzz::{FuzzConfig, Fuzzer, Injection};
+use crate::inspector::Inspector;
+use crate::window::WindowRequest;
+use crate::{ProxyEvent, SharedState, Window};
 
 pub(crate) struct Loop<T: theme::Theme<DrawPipe>> {
     windows: Vec<Window<T::Window>>,
     shared: SharedState<T>,
     resumes: Vec<(Instant, usize)>,
+    inspector: Option<Inspector>,
+    inspector_key: Option<VirtualKeyCode>,
+    hud_key: Option<VirtualKeyCode>,
+    fuzzer: Option<Fuzzer>,
+    // High-frequency events, held until the end of the batch (see coalesces)
+    pending: Vec<(WindowId, WindowEvent)>,
+    // Windows to reconfigure at the end of the batch
+    reconfigures: Vec<WindowId>,
 }
 
 impl<T: theme::Theme<DrawPipe>> Loop<T> {
-    pub(crate) fn new(windows: Vec<Window<T::Window>>, shared: SharedState<T>) -> Self {
+    pub(crate) fn new(
+        windows: Vec<Window<T::Window>>,
+        shared: SharedState<T>,
+        inspector_key: Option<VirtualKeyCode>,
+        hud_key: Option<VirtualKeyCode>,
+        fuzz: Option<FuzzConfig>,
+    ) -> Self {
+        if let Some(ref config) = fuzz {
+            info!("Event fuzzing enabled with seed {:#x}", config.seed);
+        }
         Loop {
             windows,
             shared,
             resumes: vec![],
+            inspector: None,
+            inspector_key,
+            hud_key,
+            fuzzer: fuzz.map(Fuzzer::new),
+            pending: vec![],
+            reconfigures: vec![],
         }
     }
 
     pub(crate) fn handle<U>(
         &mut self,
-        event: Event<U>,
-        elwt: &EventLoopWindowTarget<U>,
+        event: Event<ProxyEvent<U>>,
+        elwt: &EventLoopWindowTarget<ProxyEvent<U>>,
+        control_flow: &mut ControlFlow,
+    ) {
+        self.handle_event(event, elwt, control_flow);
+
+        if *control_flow != ControlFlow::Exit {
+            // Wake for whichever comes first: a timer, a tooltip, a long
+            // press, a deferred layout, a busy state check or a queued event
+            let mut next = self.resumes.first().map(|item| item.0);
+            let tooltips = self.windows.iter().filter_map(|w| w.tooltip_time());
+            let long_presses = self.windows.iter().filter_map(|w| w.long_press_time());
+            let resizes = self.windows.iter().filter_map(|w| w.resize_time());
+            let busy = self.windows.iter().filter_map(|w| w.busy_time());
+            let fuzzed = self.fuzzer.as_ref().and_then(|f| f.next_time());
+            let times = tooltips.chain(long_presses).chain(resizes).chain(busy);
+            for time in times.chain(fuzzed) {
+                next = Some(next.map(|t| t.min(time)).unwrap_or(time));
+            }
+            *control_flow = match next {
+                Some(time) => ControlFlow::WaitUntil(time),
+                None => ControlFlow::Wait,
+            };
+        }
+    }
+
+    fn handle_event<U>(
+        &mut self,
+        event: Event<ProxyEvent<U>>,
+        elwt: &EventLoopWindowTarget<ProxyEvent<U>>,
         control_flow: &mut ControlFlow,
     ) {
         use Event::*;
         let (i, (action, new_windows)) = match event {
-            WindowEvent { window_id, event } => 'outer: loop {
-                for (i, window) in self.windows.iter_mut().enumerate() {
-                    if window.window.id() == window_id {
-                        break 'outer (i, window.handle_event(&mut self.shared, event));
+            WindowEvent { window_id, event } => {
+                let i = match self.windows.iter().position(|w| w.window.id() == window_id) {
+                    Some(i) => i,
+                    None => return,
+                };
+                if is_key(&event, self.inspector_key) {
+                    self.toggle_inspector(i, elwt, control_flow);
+                    return;
+                }
+                if is_key(&event, self.hud_key) {
+                    let window = &mut self.windows[i];
+                    window.hud.toggle();
+                    window.window.request_redraw();
+                    return;
+                }
+                if self.is_blocked(i, &event) {
+                    trace!("Blocked input to window {} (modal or busy)", i);
+                    return;
+                }
+                if let Some(ref mut fuzzer) = self.fuzzer {
+                    if Fuzzer::accepts(&event) {
+                        let size = self.windows[i].window.inner_size();
+                        for injection in fuzzer.push(window_id, event, size) {
+                            match injection {
+                                Injection::Resize(id, size) => {
+                                    for window in &self.windows {
+                                        if window.window.id() == id {
+                                            window.window.set_inner_size(size);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        self.release_fuzzed(elwt, control_flow);
+                        return;
                     }
                 }
-                return;
-            },
+                if coalesces(&event) {
+                    // Replace any pending event of the same kind
+                    let kind = discriminant(&event);
+                    let len = self.pending.len();
+                    self.pending
+                        .retain(|(id, ev)| *id != window_id || discriminant(ev) != kind);
+                    if self.pending.len() < len {
+                        trace!("Coalesced event for window {}", i);
+                    }
+                    self.pending.push((window_id, event));
+                    return;
+                }
+                // Pending events precede this one
+                self.flush_pending(Some(window_id), elwt, control_flow);
+                let i = match self.windows.iter().position(|w| w.window.id() == window_id) {
+                    Some(i) if *control_flow != ControlFlow::Exit => i,
+                    _ => return,
+                };
+                self.flush_reconfigure(i);
+                (i, self.windows[i].handle_event(&mut self.shared, event))
+            }
 
+            DeviceEvent {
+                event: winit::event::DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                // Raw motion is only used by a window with a cursor grab
+                let i = match self.windows.iter().position(|w| w.cursor_grab()) {
+                    Some(i) => i,
+                    None => return,
+                };
+                self.flush_reconfigure(i);
+                (i, self.windows[i].handle_motion(delta))
+            }
             DeviceEvent { .. } => return, // windows handle local input; we do not handle global input
-            UserEvent(_) => return,       // we have no handler for user events
+            UserEvent(ProxyEvent::User(_)) => {
+                // User events (e.g. sent via a proxy from another thread)
+                // wake the loop; widgets with shared state such as LogView
+                // or a loading Image read updates when next drawn.
+                for window in &self.windows {
+                    window.window.request_redraw();
+                }
+                return;
+            }
+            #[cfg(feature = "gamepad")]
+            UserEvent(ProxyEvent::Nav(command)) => {
+                // Gamepad input goes to the focused window, unless blocked
+                let i = match self.windows.iter().position(|w| w.is_focused()) {
+                    Some(i) if !self.windows[i].blocked && !self.windows[i].is_busy() => i,
+                    _ => return,
+                };
+                self.flush_reconfigure(i);
+                (i, self.windows[i].handle_nav_command(command))
+            }
 
             NewEvents(cause) => {
                 match cause {
@@ -58,13 +194,33 @@ impl<T: theme::Theme<DrawPipe>> Loop<T> {
                     } => {
                         debug!("Wakeup: timer (requested: {:?})", requested_resume);
 
-                        let item = self
-                            .resumes
-                            .first()
-                            .cloned()
-                            .unwrap_or_else(|| panic!("timer wakeup without resume"));
-                        assert_eq!(item.0, requested_resume);
+                        if self.fuzzer.is_some() {
+                            self.release_fuzzed(elwt, control_flow);
+                        }
+                        for window in &mut self.windows {
+                            window.show_tooltip();
+                            window.finish_resize();
+                            window.poll_busy();
+                        }
 
+                        // The wakeup may have been for a tooltip, long press,
+                        // deferred layout, busy state or fuzzed event instead
+                        // of a timer
+                        let now = Instant::now();
+                        let long_press = self.windows.iter().position(|w| {
+                            w.long_press_time().map(|time| time <= now).unwrap_or(false)
+                        });
+                        if let Some(i) = long_press {
+                            self.flush_reconfigure(i);
+                            let (action, new_windows) = self.windows[i].handle_long_press();
+                            self.post_event(i, action, new_windows, elwt, control_flow);
+                        }
+                        let item = match self.resumes.first() {
+                            Some(item) if item.0 == requested_resume => *item,
+                            _ => return,
+                        };
+
+                        self.flush_reconfigure(item.1);
                         let (action, resume) = self.windows[item.1].timer_resume(requested_resume);
                         if let Some(instant) = resume {
                             self.resumes[0].0 = instant;
@@ -80,6 +236,7 @@ impl<T: theme::Theme<DrawPipe>> Loop<T> {
 
                     StartCause::Init => {
                         debug!("Wakeup: init");
+                        self.update_blocked();
 
                         for (i, window) in self.windows.iter_mut().enumerate() {
                             if let Some(instant) = window.init() {
@@ -99,17 +256,45 @@ impl<T: theme::Theme<DrawPipe>> Loop<T> {
                 }
             }
 
-            EventsCleared | LoopDestroyed | Suspended | Resumed => return,
+            EventsCleared => {
+                self.flush_pending(None, elwt, control_flow);
+                for id in replace(&mut self.reconfigures, vec![]) {
+                    if let Some(window) = self.windows.iter_mut().find(|w| w.window.id() == id) {
+                        window.reconfigure();
+                    }
+                }
+                return;
+            }
+
+            LoopDestroyed | Suspended | Resumed => return,
         };
 
+        self.post_event(i, action, new_windows, elwt, control_flow);
+    }
+
+    /// Process the results of handling an event on window `i`
+    fn post_event<U>(
+        &mut self,
+        i: usize,
+        action: TkAction,
+        new_windows: Vec<Box<dyn kas::Window>>,
+        elwt: &EventLoopWindowTarget<U>,
+        control_flow: &mut ControlFlow,
+    ) {
+        let requests = self.windows[i].take_window_requests();
+
         // Create and init() any new windows.
         let mut have_new_resumes = false;
+        let have_new_windows = !new_windows.is_empty();
         for widget in new_windows {
             debug!("Adding window {}", widget.title());
             match winit::window::Window::new(elwt) {
                 Ok(window) => {
                     window.set_title(widget.title());
                     let mut win = Window::new(&mut self.shared, window, widget);
+                    if win.modality() == Modality::Window {
+                        win.modal_parent = Some(self.windows[i].window.id());
+                    }
                     if let Some(instant) = win.init() {
                         self.resumes.push((instant, self.windows.len()));
                         have_new_resumes = true;
@@ -121,6 +306,9 @@ impl<T: theme::Theme<DrawPipe>> Loop<T> {
                 }
             };
         }
+        if have_new_windows {
+            self.update_blocked();
+        }
         if have_new_resumes {
             self.resumes.sort_by_key(|item| item.0);
             if let Some(first) = self.resumes.first() {
@@ -131,24 +319,252 @@ impl<T: theme::Theme<DrawPipe>> Loop<T> {
             }
         }
 
+        if let Some(ref inspector) = self.inspector {
+            let mut update = i == inspector.target;
+            if i == inspector.window {
+                let target = &mut self.windows[inspector.target];
+                match inspector.take_action() {
+                    TkAction::None => (),
+                    TkAction::Redraw => target.window.request_redraw(),
+                    _ => target.reconfigure(),
+                }
+                update = true;
+            }
+            if update {
+                self.update_inspector();
+            }
+        }
+
         match action {
             TkAction::None => (),
             TkAction::Redraw => self.windows[i].window.request_redraw(),
-            TkAction::Reconfigure => self.windows[i].reconfigure(),
-            TkAction::Close => {
-                self.windows.remove(i);
-                if self.windows.is_empty() {
-                    *control_flow = ControlFlow::Exit;
-                } else {
-                    // update window indices in self.resumes!
-                    for resume in &mut self.resumes {
-                        if resume.1 >= i {
-                            resume.1 -= 1;
-                        }
-                    }
+            TkAction::Reconfigure => {
+                // Deferred, thus done at most once per batch of events (but
+                // before the window handles another event, other than a
+                // resize; see Loop::flush_reconfigure)
+                let id = self.windows[i].window.id();
+                if !self.reconfigures.contains(&id) {
+                    self.reconfigures.push(id);
                 }
             }
+            TkAction::Close => self.remove_window(i, control_flow),
             TkAction::CloseAll => *control_flow = ControlFlow::Exit,
         }
+
+        for request in requests {
+            if *control_flow == ControlFlow::Exit {
+                return;
+            }
+            match request {
+                WindowRequest::Activate(id) => {
+                    if let Some(window) = self.windows.iter().find(|w| w.id() == id) {
+                        // winit does not yet support focussing a window; the
+                        // best we can do is to show it
+                        window.window.set_visible(true);
+                        window.window.request_redraw();
+                    }
+                }
+                WindowRequest::Close(id) => {
+                    if let Some(index) = self.windows.iter().position(|w| w.id() == id) {
+                        self.remove_window(index, control_flow);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply any deferred reconfigure of window `i`
+    ///
+    /// Reconfiguring is deferred to the end of a batch of events (see
+    /// `post_event`), but must happen before further events reach the
+    /// window's widgets: replaced widgets have no valid identifiers until
+    /// configured.
+    fn flush_reconfigure(&mut self, i: usize) {
+        let id = self.windows[i].window.id();
+        if let Some(index) = self.reconfigures.iter().position(|r| *r == id) {
+            self.reconfigures.remove(index);
+            self.windows[i].reconfigure();
+        }
+    }
+
+    /// Handle pending (coalesced) events for `window`, or for all windows
+    fn flush_pending<U>(
+        &mut self,
+        window: Option<WindowId>,
+        elwt: &EventLoopWindowTarget<U>,
+        control_flow: &mut ControlFlow,
+    ) {
+        for (window_id, event) in replace(&mut self.pending, vec![]) {
+            if window.map(|id| id != window_id).unwrap_or(false) {
+                self.pending.push((window_id, event));
+                continue;
+            }
+            if *control_flow == ControlFlow::Exit {
+                continue;
+            }
+            // The window may have been closed while the event was pending
+            if let Some(i) = self.windows.iter().position(|w| w.window.id() == window_id) {
+                // A resize (re-)solves the layout itself, thus the reconfigure
+                // may wait; other events require configured widgets
+                match event {
+                    WindowEvent::Resized(_) => (),
+                    _ => self.flush_reconfigure(i),
+                }
+                let (action, new_windows) = self.windows[i].handle_event(&mut self.shared, event);
+                self.post_event(i, action, new_windows, elwt, control_flow);
+            }
+        }
+    }
+
+    /// Handle all queued events which are due
+    fn release_fuzzed<U>(
+        &mut self,
+        elwt: &EventLoopWindowTarget<U>,
+        control_flow: &mut ControlFlow,
+    ) {
+        let now = Instant::now();
+        while let Some((window_id, event)) = self.fuzzer.as_mut().and_then(|f| f.pop(now)) {
+            if *control_flow == ControlFlow::Exit {
+                return;
+            }
+            // The window may have been closed while the event was queued
+            if let Some(i) = self.windows.iter().position(|w| w.window.id() == window_id) {
+                if self.is_blocked(i, &event) {
+                    continue;
+                }
+                self.flush_reconfigure(i);
+                let (action, new_windows) = self.windows[i].handle_event(&mut self.shared, event);
+                self.post_event(i, action, new_windows, elwt, control_flow);
+            }
+        }
+    }
+
+    /// True if `event` is user input to window `i` while blocked by a modal
+    /// window or busy
+    fn is_blocked(&self, i: usize, event: &WindowEvent) -> bool {
+        use WindowEvent::*;
+        match event {
+            Resized(_) | Moved(_) | Destroyed | Focused(_) | CursorLeft { .. } => return false,
+            HiDpiFactorChanged(_) | RedrawRequested => return false,
+            _ => (),
+        }
+        self.windows[i].blocked || self.windows[i].is_busy()
+    }
+
+    /// Update which windows are blocked by modal windows, redrawing as needed
+    ///
+    /// A window is blocked while it has a window-modal child, or while an
+    /// application-modal window opened after it is open. Windows are kept in
+    /// the order opened.
+    fn update_blocked(&mut self) {
+        let mut app_modal = false;
+        for i in (0..self.windows.len()).rev() {
+            let id = self.windows[i].window.id();
+            let has_child = self.windows.iter().any(|w| w.modal_parent == Some(id));
+            let window = &mut self.windows[i];
+            let blocked = app_modal || has_child;
+            if window.blocked != blocked {
+                window.blocked = blocked;
+                window.window.request_redraw();
+            }
+            app_modal |= window.modality() == Modality::Application;
+        }
+    }
+
+    fn remove_window(&mut self, i: usize, control_flow: &mut ControlFlow) {
+        self.windows.remove(i).close();
+        if self.windows.is_empty() {
+            *control_flow = ControlFlow::Exit;
+            return;
+        }
+        self.update_blocked();
+
+        // update window indices in self.resumes!
+        for resume in &mut self.resumes {
+            if resume.1 >= i {
+                resume.1 -= 1;
+            }
+        }
+
+        if let Some(mut inspector) = self.inspector.take() {
+            let adjust = |index: usize| if index > i { index - 1 } else { index };
+            if inspector.window == i {
+                let target = &mut self.windows[adjust(inspector.target)];
+                target.inspected = false;
+                target.window.request_redraw();
+            } else if inspector.target == i {
+                // The inspector has nothing left to show
+                self.remove_window(adjust(inspector.window), control_flow);
+            } else {
+                inspector.window = adjust(inspector.window);
+                inspector.target = adjust(inspector.target);
+                self.inspector = Some(inspector);
+            }
+        }
+    }
+
+    /// Open an inspector on window `i`, or close the inspector if open
+    fn toggle_inspector<U>(
+        &mut self,
+        i: usize,
+        elwt: &EventLoopWindowTarget<U>,
+        control_flow: &mut ControlFlow,
+    ) {
+        if let Some(ref inspector) = self.inspector {
+            let index = inspector.window;
+            self.remove_window(index, control_flow);
+            return;
+        }
+
+        debug!("Opening inspector on window {}", i);
+        let window = match winit::window::Window::new(elwt) {
+            Ok(window) => window,
+            Err(e) => {
+                error!("Unable to create window: {}", e);
+                return;
+            }
+        };
+        let (inspector, widget) = Inspector::new(self.windows.len(), i);
+        window.set_title(widget.title());
+        let mut win = Window::new(&mut self.shared, window, widget);
+        // The inspector has no callbacks, thus will not request a resume time
+        let _ = win.init();
+        self.windows.push(win);
+        self.windows[i].inspected = true;
+        self.inspector = Some(inspector);
+        self.update_inspector();
+    }
+
+    fn update_inspector(&mut self) {
+        if let Some(ref inspector) = self.inspector {
+            let target = &mut self.windows[inspector.target];
+            inspector.set_text(target.describe());
+            target.window.request_redraw();
+            self.windows[inspector.window].reconfigure();
+        }
+    }
+}
+
+/// True if `event` may be coalesced
+///
+/// During fast mouse movement or interactive resizing, many such events may
+/// arrive in one batch. Only the last of each kind (per window) is handled,
+/// at the end of the batch or before any other event to the same window.
+fn coalesces(event: &WindowEvent) -> bool {
+    matches!(
+        event,
+        WindowEvent::CursorMoved { .. } | WindowEvent::Resized(_)
+    )
+}
+
+/// True if `event` is a press of `key`
+fn is_key(event: &WindowEvent, key: Option<VirtualKeyCode>) -> bool {
+    match event {
+        WindowEvent::KeyboardInput { input, .. } => {
+            input.state == ElementState::Pressed
+                && input.virtual_keycode.is_some()
+                && input.virtual_keycode == key
+        }
+        _ => false,
     }
 }
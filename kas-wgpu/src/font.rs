@@ -7,10 +7,18 @@
 //!
 //! Optionally, this uses font-kit to find a suitable font. Since this is a
 //! large dependency, an alternative is provided.
+//!
+//! Fonts are listed as regular, bold, italic and bold italic faces, such that
+//! the index of a face is given by [`font_index`], followed by a monospace face
+//! (see [`mono_index`]). Without font-kit, only the regular face is available;
+//! it is also used in place of the others.
 
 #[cfg(feature = "font-kit")]
 use font_kit::{
-    family_name::FamilyName, handle::Handle, properties::Properties, source::SystemSource,
+    family_name::FamilyName,
+    handle::Handle,
+    properties::{Properties, Style, Weight},
+    source::SystemSource,
 };
 
 use lazy_static::lazy_static;
@@ -28,9 +36,16 @@ struct FontCollectionBytes {
 
 #[cfg(feature = "font-kit")]
 impl FontCollectionBytes {
-    fn load() -> Self {
+    fn load(family: FamilyName, bold: bool, italic: bool) -> Self {
+        let mut properties = Properties::new();
+        if bold {
+            properties.weight(Weight::BOLD);
+        }
+        if italic {
+            properties.style(Style::Italic);
+        }
         let handle = SystemSource::new()
-            .select_best_match(&[FamilyName::SansSerif], &Properties::new())
+            .select_best_match(&[family], &properties)
             .unwrap();
         match handle {
             Handle::Path { path, font_index } => {
@@ -59,8 +74,14 @@ impl FontCollectionBytes {
 
 #[cfg(feature = "font-kit")]
 lazy_static! {
-    static ref FCB: FontCollectionBytes = FontCollectionBytes::load();
-    static ref FONT: Font<'static> = FCB.font();
+    static ref FCB: [FontCollectionBytes; 5] = [
+        FontCollectionBytes::load(FamilyName::SansSerif, false, false),
+        FontCollectionBytes::load(FamilyName::SansSerif, true, false),
+        FontCollectionBytes::load(FamilyName::SansSerif, false, true),
+        FontCollectionBytes::load(FamilyName::SansSerif, true, true),
+        FontCollectionBytes::load(FamilyName::Monospace, false, false),
+    ];
+    static ref FONTS: Vec<Font<'static>> = FCB.iter().map(|fcb| fcb.font()).collect();
 }
 
 #[cfg(not(feature = "font-kit"))]
@@ -68,9 +89,30 @@ const BYTES: &'static [u8] = include_bytes!("/usr/share/fonts/dejavu/DejaVuSerif
 
 #[cfg(not(feature = "font-kit"))]
 lazy_static! {
-    static ref FONT: Font<'static> = Font::from_bytes(BYTES).unwrap();
+    static ref FONTS: Vec<Font<'static>> = vec![Font::from_bytes(BYTES).unwrap()];
+}
+
+/// Get the list of fonts
+pub(crate) fn get_fonts() -> Vec<Font<'static>> {
+    FONTS.clone()
 }
 
-pub(crate) fn get_font() -> Font<'static> {
-    FONT.clone()
+/// Index of the face with the given style within [`get_fonts`]
+pub(crate) fn font_index(bold: bool, italic: bool) -> usize {
+    let index = bold as usize + 2 * italic as usize;
+    if index < FONTS.len() {
+        index
+    } else {
+        0
+    }
+}
+
+/// Index of the monospace face within [`get_fonts`]
+pub(crate) fn mono_index() -> usize {
+    let index = 4;
+    if index < FONTS.len() {
+        index
+    } else {
+        0
+    }
 }
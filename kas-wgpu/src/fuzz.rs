@@ -0,0 +1,237 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event fuzzing
+//!
+//! A debugging aid: input events are delayed and reordered, and synthetic
+//! events are injected, in order to shake out state-machine bugs. All random
+//! decisions derive from a seed, thus (given the same real input) a run is
+//! reproducible.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use winit::dpi::LogicalSize;
+use winit::event::{
+    DeviceId, ElementState, KeyboardInput, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+};
+use winit::window::WindowId;
+
+/// Configuration of the event fuzzer
+///
+/// See [`Toolkit::set_fuzz`](crate::Toolkit::set_fuzz).
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// Random seed
+    pub seed: u64,
+    /// Maximum delay applied to input events
+    ///
+    /// Each input event is delayed by a random time up to this value. Events
+    /// to the same window are never reordered, but events to different windows
+    /// may be.
+    pub max_delay: Duration,
+    /// Probability of injecting synthetic events, per input event
+    pub inject_rate: f32,
+    /// Inject bursts of mouse clicks
+    pub clicks: bool,
+    /// Inject keyboard focus changes
+    pub focus_churn: bool,
+    /// Inject window resize storms
+    pub resizes: bool,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig {
+            seed: 0x853c_49e6_748f_ea9b,
+            max_delay: Duration::from_millis(50),
+            inject_rate: 0.05,
+            clicks: true,
+            focus_churn: true,
+            resizes: true,
+        }
+    }
+}
+
+/// A small PRNG (xorshift64*)
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // xorshift requires a non-zero state
+        Rng(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Random value in `0.0..1.0`
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Random value in `low..high`
+    fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + (self.next() % (high - low) as u64) as u32
+    }
+}
+
+/// A synthetic action which cannot be represented as a queued event
+pub(crate) enum Injection {
+    /// Resize the window to the given size
+    Resize(WindowId, LogicalSize),
+}
+
+/// Event fuzzer state
+pub(crate) struct Fuzzer {
+    config: FuzzConfig,
+    rng: Rng,
+    /// Queued events, with release time
+    queue: VecDeque<(Instant, WindowId, WindowEvent)>,
+}
+
+// Scancode used by synthetic key events; chosen not to clash with real keys.
+const FUZZ_SCANCODE: u32 = 0xF0F0;
+
+impl Fuzzer {
+    pub fn new(config: FuzzConfig) -> Self {
+        let rng = Rng::new(config.seed);
+        Fuzzer {
+            config,
+            rng,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// True if an event should be passed through the fuzzer
+    pub fn accepts(event: &WindowEvent) -> bool {
+        use WindowEvent::*;
+        matches!(
+            event,
+            ReceivedCharacter(_)
+                | KeyboardInput { .. }
+                | CursorMoved { .. }
+                | CursorLeft { .. }
+                | MouseWheel { .. }
+                | MouseInput { .. }
+                | Touch(_)
+        )
+    }
+
+    /// Queue an event, possibly injecting others
+    ///
+    /// `size` is the current size of the target window.
+    pub fn push(
+        &mut self,
+        window: WindowId,
+        event: WindowEvent,
+        size: LogicalSize,
+    ) -> Vec<Injection> {
+        let now = Instant::now();
+        self.enqueue(now, window, event);
+
+        let mut injections = vec![];
+        if self.rng.unit() >= self.config.inject_rate {
+            return injections;
+        }
+
+        match self.rng.range(0, 3) {
+            0 if self.config.clicks => {
+                for _ in 0..self.rng.range(2, 6) {
+                    for state in &[ElementState::Pressed, ElementState::Released] {
+                        let event = WindowEvent::MouseInput {
+                            device_id: dummy_device(),
+                            state: *state,
+                            button: MouseButton::Left,
+                            modifiers: ModifiersState::default(),
+                        };
+                        self.enqueue(now, window, event);
+                    }
+                }
+            }
+            1 if self.config.focus_churn => {
+                for _ in 0..self.rng.range(1, 8) {
+                    let vkey = match self.rng.range(0, 4) {
+                        0 => VirtualKeyCode::Escape,
+                        _ => VirtualKeyCode::Tab,
+                    };
+                    for state in &[ElementState::Pressed, ElementState::Released] {
+                        let event = WindowEvent::KeyboardInput {
+                            device_id: dummy_device(),
+                            input: KeyboardInput {
+                                scancode: FUZZ_SCANCODE,
+                                state: *state,
+                                virtual_keycode: Some(vkey),
+                                modifiers: ModifiersState::default(),
+                            },
+                            is_synthetic: true,
+                        };
+                        self.enqueue(now, window, event);
+                    }
+                }
+            }
+            2 if self.config.resizes => {
+                for _ in 0..self.rng.range(2, 10) {
+                    let w = size.width * (0.5 + self.rng.unit() as f64);
+                    let h = size.height * (0.5 + self.rng.unit() as f64);
+                    injections.push(Injection::Resize(window, LogicalSize::new(w, h)));
+                }
+                // Restore the original size at the end of the storm
+                injections.push(Injection::Resize(window, size));
+            }
+            _ => (),
+        }
+        injections
+    }
+
+    fn enqueue(&mut self, now: Instant, window: WindowId, event: WindowEvent) {
+        let delay = self.config.max_delay.mul_f32(self.rng.unit());
+        let mut time = now + delay;
+
+        // Events to the same window must stay in order. Otherwise, keep the
+        // queue sorted by release time.
+        let mut index = self.queue.len();
+        for (i, item) in self.queue.iter().enumerate().rev() {
+            if item.1 == window {
+                time = time.max(item.0);
+                break;
+            }
+            if item.0 <= time {
+                break;
+            }
+            index = i;
+        }
+        self.queue.insert(index, (time, window, event));
+    }
+
+    /// Pop the next event whose release time has been reached
+    pub fn pop(&mut self, now: Instant) -> Option<(WindowId, WindowEvent)> {
+        if self
+            .queue
+            .front()
+            .map(|item| item.0 <= now)
+            .unwrap_or(false)
+        {
+            self.queue.pop_front().map(|item| (item.1, item.2))
+        } else {
+            None
+        }
+    }
+
+    /// Time at which the next event is due, if any
+    pub fn next_time(&self) -> Option<Instant> {
+        self.queue.front().map(|item| item.0)
+    }
+}
+
+fn dummy_device() -> DeviceId {
+    // Safety: the dummy id is only passed to our own event handling, which
+    // ignores the device.
+    unsafe { DeviceId::dummy() }
+}
@@ -0,0 +1,185 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Gamepad input (via `gilrs`)
+//!
+//! Gamepads are polled on a separate thread; mapped input is sent through the
+//! event loop's user-event channel, thus the UI thread sleeps as usual.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use gilrs::{Axis, Button, EventType, Gilrs};
+use kas::event::{NavCommand, NavKey};
+use log::{info, warn};
+use winit::event_loop::EventLoopProxy;
+
+use crate::ProxyEvent;
+
+/// Interval between polls for gamepad input
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+pub use gilrs::Button as GamepadButton;
+
+/// Mapping of gamepad input to navigation commands
+///
+/// By default:
+///
+/// -   the D-pad and left stick navigate ([`NavCommand::Move`])
+/// -   the South face button (A on Xbox controllers) activates
+/// -   the East face button (B on Xbox controllers) cancels
+/// -   the shoulder buttons move focus to the previous and next widget
+///
+/// ```no_run
+/// use kas::event::NavCommand;
+/// use kas_wgpu::{GamepadButton, GamepadMapping};
+///
+/// // Swap activate and cancel and disable the stick
+/// let mapping = GamepadMapping::default()
+///     .with_button(GamepadButton::East, Some(NavCommand::Activate))
+///     .with_button(GamepadButton::South, Some(NavCommand::Cancel))
+///     .with_stick(false);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GamepadMapping {
+    buttons: HashMap<Button, NavCommand>,
+    stick: bool,
+    threshold: f32,
+}
+
+impl Default for GamepadMapping {
+    fn default() -> Self {
+        let mut buttons = HashMap::new();
+        buttons.insert(Button::DPadLeft, NavCommand::Move(NavKey::Left));
+        buttons.insert(Button::DPadRight, NavCommand::Move(NavKey::Right));
+        buttons.insert(Button::DPadUp, NavCommand::Move(NavKey::Up));
+        buttons.insert(Button::DPadDown, NavCommand::Move(NavKey::Down));
+        buttons.insert(Button::South, NavCommand::Activate);
+        buttons.insert(Button::East, NavCommand::Cancel);
+        buttons.insert(Button::LeftTrigger, NavCommand::PreviousFocus);
+        buttons.insert(Button::RightTrigger, NavCommand::NextFocus);
+        GamepadMapping {
+            buttons,
+            stick: true,
+            threshold: 0.5,
+        }
+    }
+}
+
+impl GamepadMapping {
+    /// Construct a mapping without any inputs
+    pub fn empty() -> Self {
+        GamepadMapping {
+            buttons: HashMap::new(),
+            stick: false,
+            threshold: 0.5,
+        }
+    }
+
+    /// Map `button` to `command` (or to nothing, if `None`)
+    pub fn with_button(mut self, button: Button, command: Option<NavCommand>) -> Self {
+        match command {
+            Some(command) => self.buttons.insert(button, command),
+            None => self.buttons.remove(&button),
+        };
+        self
+    }
+
+    /// Enable or disable navigation with the left stick
+    pub fn with_stick(mut self, stick: bool) -> Self {
+        self.stick = stick;
+        self
+    }
+
+    /// Set the stick deflection (from 0 to 1) required to navigate
+    ///
+    /// Each navigation requires the stick to return within this threshold.
+    pub fn with_stick_threshold(mut self, threshold: f32) -> Self {
+        assert!(threshold > 0.0 && threshold < 1.0);
+        self.threshold = threshold;
+        self
+    }
+
+    /// Get the command mapped to `button`, if any
+    pub fn button(&self, button: Button) -> Option<NavCommand> {
+        self.buttons.get(&button).cloned()
+    }
+}
+
+// Translates gilrs events according to a mapping
+struct Translator {
+    mapping: GamepadMapping,
+    // Deflected direction of the left stick, per axis
+    stick: [Option<NavKey>; 2],
+}
+
+impl Translator {
+    fn translate(&mut self, event: EventType) -> Option<NavCommand> {
+        match event {
+            EventType::ButtonPressed(button, _) | EventType::ButtonRepeated(button, _) => {
+                self.mapping.button(button)
+            }
+            EventType::AxisChanged(axis, value, _) if self.mapping.stick => {
+                let (i, key) = match axis {
+                    Axis::LeftStickX if value < 0.0 => (0, NavKey::Left),
+                    Axis::LeftStickX => (0, NavKey::Right),
+                    // The Y axis points up
+                    Axis::LeftStickY if value < 0.0 => (1, NavKey::Down),
+                    Axis::LeftStickY => (1, NavKey::Up),
+                    _ => return None,
+                };
+                if value.abs() < self.mapping.threshold {
+                    self.stick[i] = None;
+                    None
+                } else if self.stick[i] != Some(key) {
+                    self.stick[i] = Some(key);
+                    Some(NavCommand::Move(key))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Poll gamepads on a new thread until the event loop closes
+pub(crate) fn spawn<U: Send + 'static>(
+    mapping: GamepadMapping,
+    proxy: EventLoopProxy<ProxyEvent<U>>,
+) {
+    let result = thread::Builder::new()
+        .name("kas-gamepad".to_string())
+        .spawn(move || {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(e) => {
+                    warn!("Gamepad support unavailable: {}", e);
+                    return;
+                }
+            };
+            for (_, gamepad) in gilrs.gamepads() {
+                info!("Found gamepad: {}", gamepad.name());
+            }
+            let mut translator = Translator {
+                mapping,
+                stick: [None, None],
+            };
+            loop {
+                while let Some(event) = gilrs.next_event() {
+                    if let Some(command) = translator.translate(event.event) {
+                        if proxy.send_event(ProxyEvent::Nav(command)).is_err() {
+                            return; // event loop closed
+                        }
+                    }
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+    if let Err(e) = result {
+        warn!("Failed to start gamepad thread: {}", e);
+    }
+}
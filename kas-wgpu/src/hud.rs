@@ -0,0 +1,142 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Performance HUD
+//!
+//! A debugging aid: an overlay drawn over window contents showing a graph of
+//! recent frame times and a few statistics on the last frame.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use kas::draw::{Colour, Draw, Quad, Style, Vec2};
+use kas::geom::{Coord, Rect, Size};
+use wgpu_glyph::{Scale, Section};
+
+use crate::draw::{DrawPipe, DrawStats, DrawText};
+
+/// Number of frames shown in the graph
+const HISTORY: usize = 60;
+/// Frame time corresponding to the full height of the graph
+const GRAPH_SCALE: Duration = Duration::from_millis(33);
+/// Frame time budget (at 60 FPS); longer frames are highlighted
+const BUDGET: Duration = Duration::from_micros(16_667);
+
+/// HUD state, per window
+#[derive(Debug, Default)]
+pub(crate) struct Hud {
+    /// If false, nothing is recorded or drawn
+    pub enabled: bool,
+    frame_times: VecDeque<Duration>,
+    stats: DrawStats,
+    layout_time: Duration,
+    /// Events handled since the last frame
+    events: u32,
+    /// Events handled before the last frame
+    last_events: u32,
+}
+
+impl Hud {
+    /// Toggle the HUD, clearing statistics
+    pub fn toggle(&mut self) {
+        *self = Hud {
+            enabled: !self.enabled,
+            ..Hud::default()
+        };
+    }
+
+    /// Count one event handled
+    #[inline]
+    pub fn count_event(&mut self) {
+        self.events += 1;
+    }
+
+    /// Record the duration of the last layout pass
+    #[inline]
+    pub fn set_layout_time(&mut self, time: Duration) {
+        self.layout_time = time;
+    }
+
+    /// Record a completed frame
+    pub fn record_frame(&mut self, time: Duration, stats: DrawStats) {
+        if self.frame_times.len() >= HISTORY {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(time);
+        self.stats = stats;
+        self.last_events = self.events;
+        self.events = 0;
+    }
+
+    /// Queue the HUD for drawing
+    ///
+    /// The HUD is drawn in its own overlay region, thus over all widgets.
+    pub fn draw(&self, draw: &mut DrawPipe, window_size: Size, dpi_factor: f32) {
+        let line_height = 16.0 * dpi_factor;
+        let margin = 4.0 * dpi_factor;
+        let bar_width = 3.0 * dpi_factor;
+        let graph_height = 48.0 * dpi_factor;
+        let lines = 5.0;
+
+        let width = 2.0 * margin + bar_width * HISTORY as f32;
+        let height = 3.0 * margin + graph_height + lines * line_height;
+        let rect = Rect {
+            pos: Coord::ZERO,
+            // The scissor rect must lie within the window
+            size: Size(
+                (width.ceil() as u32).min(window_size.0),
+                (height.ceil() as u32).min(window_size.1),
+            ),
+        };
+        let pass = draw.add_overlay_region(rect);
+
+        let back = Colour::new(0.1, 0.1, 0.1);
+        draw.draw_quad(
+            pass,
+            Quad(Vec2(0.0, 0.0), Vec2(width, height)),
+            Style::Flat,
+            back,
+        );
+
+        // Frame time graph, with a line at the budget
+        let base = margin + graph_height;
+        let scale = graph_height / GRAPH_SCALE.as_secs_f32();
+        let good = Colour::new(0.2, 0.8, 0.2);
+        let bad = Colour::new(0.9, 0.2, 0.2);
+        let mut x = margin;
+        for time in &self.frame_times {
+            let h = (time.as_secs_f32() * scale).min(graph_height);
+            let col = if *time > BUDGET { bad } else { good };
+            let quad = Quad(Vec2(x, base - h), Vec2(x + bar_width, base));
+            draw.draw_quad(pass, quad, Style::Flat, col);
+            x += bar_width;
+        }
+        let y = base - BUDGET.as_secs_f32() * scale;
+        let quad = Quad(Vec2(margin, y), Vec2(width - margin, y + 1.0));
+        draw.draw_quad(pass, quad, Style::Flat, Colour::new(0.8, 0.8, 0.8));
+
+        let last = self.frame_times.back().cloned().unwrap_or_default();
+        let text = format!(
+            "frame: {:.2} ms\nlayout: {:.2} ms\npasses: {}, draws: {}\nvertices: {}\nevents: {}",
+            last.as_secs_f64() * 1e3,
+            self.layout_time.as_secs_f64() * 1e3,
+            self.stats.passes,
+            self.stats.draw_calls,
+            self.stats.vertices,
+            self.last_events,
+        );
+        draw.draw_text(
+            pass,
+            Section {
+                text: &text,
+                screen_position: (margin, base + margin),
+                color: [1.0, 1.0, 1.0, 1.0],
+                scale: Scale::uniform(line_height * 0.85),
+                bounds: (width - 2.0 * margin, lines * line_height),
+                ..Section::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget inspector
+//!
+//! The inspector is a debugging aid: a separate window showing the widget
+//! tree of a target window, updated live.
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Write;
+use std::rc::Rc;
+
+use kas::event::{self, Response, VoidMsg, VoidResponse};
+use kas::layout::{AxisInfo, SizeRules};
+use kas::macros::{VoidMsg, Widget};
+use kas::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use kas::widget::TextButton;
+use kas::{CoreData, LayoutData, TkAction, TkWindow, Widget, WidgetId};
+
+/// Inspector state, as tracked by the event loop
+pub(crate) struct Inspector {
+    /// Index of the inspector's window
+    pub window: usize,
+    /// Index of the inspected window
+    pub target: usize,
+    state: Rc<State>,
+}
+
+impl Inspector {
+    /// Construct, returning the inspector's window widget
+    pub fn new(window: usize, target: usize) -> (Self, Box<dyn kas::Window>) {
+        let state = Rc::new(State::default());
+        let widget = Box::new(kas::widget::Window::new(
+            "Inspector",
+            InspectorWidget {
+                core: Default::default(),
+                layout_data: Default::default(),
+                view: TreeView {
+                    core: Default::default(),
+                    state: state.clone(),
+                },
+                buttons: Buttons {
+                    core: Default::default(),
+                    layout_data: Default::default(),
+                    redraw: TextButton::new("Redraw", Command::Redraw),
+                    reconfigure: TextButton::new("Reconfigure", Command::Reconfigure),
+                },
+                state: state.clone(),
+            },
+        ));
+        let inspector = Inspector {
+            window,
+            target,
+            state,
+        };
+        (inspector, widget)
+    }
+
+    /// Update the displayed description of the target window
+    pub fn set_text(&self, text: String) {
+        *self.state.text.borrow_mut() = text;
+    }
+
+    /// Take any action requested for the target window
+    pub fn take_action(&self) -> TkAction {
+        self.state.action.replace(TkAction::None)
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    text: RefCell<String>,
+    action: Cell<TkAction>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            text: RefCell::new(String::new()),
+            action: Cell::new(TkAction::None),
+        }
+    }
+}
+
+#[derive(Clone, Debug, VoidMsg)]
+enum Command {
+    Redraw,
+    Reconfigure,
+}
+
+/// Displays the (shared) description text
+#[widget]
+#[handler(msg = VoidMsg)]
+#[derive(Clone, Debug, Widget)]
+struct TreeView {
+    #[core]
+    core: CoreData,
+    state: Rc<State>,
+}
+
+impl Widget for TreeView {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let text = self.state.text.borrow();
+        size_handle.text_bound(&text, TextClass::Label, true, axis)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let props = TextProperties {
+            class: TextClass::Label,
+            multi_line: true,
+            horiz: Align::Begin,
+            vert: Align::Begin,
+        };
+        draw_handle.text(self.core.rect, &self.state.text.borrow(), props);
+    }
+}
+
+#[widget(layout = horizontal)]
+#[handler(msg = Command)]
+#[derive(Clone, Debug, Widget)]
+struct Buttons {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget]
+    redraw: TextButton<Command>,
+    #[widget]
+    reconfigure: TextButton<Command>,
+}
+
+#[widget(layout = vertical)]
+#[handler(msg = VoidMsg)]
+#[derive(Clone, Debug, Widget)]
+struct InspectorWidget {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget]
+    view: TreeView,
+    #[widget(handler = command)]
+    buttons: Buttons,
+    state: Rc<State>,
+}
+
+impl InspectorWidget {
+    fn command(&mut self, _: &mut dyn TkWindow, msg: Command) -> VoidResponse {
+        let action = match msg {
+            Command::Redraw => TkAction::Redraw,
+            Command::Reconfigure => TkAction::Reconfigure,
+        };
+        self.state.action.set(self.state.action.get().max(action));
+        Response::None
+    }
+}
+
+/// Find the hovered widget, if any
+pub(crate) fn hovered(widget: &dyn Widget, mgr: &event::Manager) -> Option<WidgetId> {
+    let mut hovered = None;
+    widget.walk(&mut |w| {
+        if hovered.is_none() && mgr.is_hovered(w.id()) {
+            hovered = Some(w.id());
+        }
+    });
+    hovered
+}
+
+/// Describe the widget tree, plus details of the hovered widget
+pub(crate) fn describe(widget: &dyn Widget, mgr: &event::Manager) -> String {
+    fn write_tree(out: &mut String, widget: &dyn Widget, mgr: &event::Manager, depth: usize) {
+        let rect = widget.rect();
+        let state = mgr.highlight_state(widget.id());
+        let _ = write!(
+            out,
+            "{}{} {} pos={:?} size={:?}",
+            "  ".repeat(depth),
+            widget.widget_name(),
+            widget.id(),
+            rect.pos,
+            rect.size,
+        );
+        if state.hover {
+            out.push_str(" [hover]");
+        }
+        if state.key_focus {
+            out.push_str(" [key focus]");
+        }
+        if state.char_focus {
+            out.push_str(" [char focus]");
+        }
+        out.push('\n');
+        for i in 0..widget.len() {
+            write_tree(out, widget.get(i).unwrap(), mgr, depth + 1);
+        }
+    }
+
+    let mut out = String::new();
+    write_tree(&mut out, widget, mgr, 0);
+
+    if let Some(id) = hovered(widget, mgr) {
+        let state = mgr.highlight_state(id);
+        widget.walk(&mut |w| {
+            if w.id() != id {
+                return;
+            }
+            let rect = w.rect();
+            let _ = write!(
+                out,
+                "\nHovered: {} {}\n  rect: {:?}\n  state: {:?}",
+                w.widget_name(),
+                id,
+                rect,
+                state,
+            );
+            #[cfg(feature = "inspect")]
+            {
+                // Rules recorded by the last layout; the widget is not re-sized
+                let rules = w.core_data().layout_rules();
+                let _ = write!(
+                    out,
+                    "\n  horizontal: {:?}\n  vertical: {:?}",
+                    rules[0], rules[1]
+                );
+            }
+            #[cfg(feature = "profiling")]
+            {
+                let profile = w.core_data().profile();
+                let _ = write!(
+                    out,
+                    "\n  draws: {}\n  events: {}\n  layout: {:?}",
+                    profile.draws(),
+                    profile.events(),
+                    profile.layout(),
+                );
+            }
+        });
+    }
+
+    out
+}
@@ -8,19 +8,35 @@
 pub mod draw;
 mod event;
 mod font;
+mod fuzz;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod hud;
+mod inspector;
+mod loader;
+mod options;
 mod theme;
 mod window;
 
-use log::info;
-use std::{error, fmt};
+use log::{info, warn};
+use std::{error, fmt, io};
 
 use winit::error::OsError;
-use winit::event_loop::EventLoop;
+use winit::event::VirtualKeyCode;
+use winit::event_loop::{EventLoop, EventLoopClosed, EventLoopProxy};
 
-use crate::draw::DrawPipe;
-use window::Window;
+use crate::draw::{DrawPipe, SharedResources};
+use options::EventRecorder;
+use window::{Window, WindowList};
 
-pub use theme::SampleTheme;
+pub use fuzz::FuzzConfig;
+#[cfg(feature = "gamepad")]
+pub use gamepad::{GamepadButton, GamepadMapping};
+pub use loader::{
+    decode_pnm, load_image, Decoder, ImageData, ImageLoader, ImageSource, LoadError, LoadId,
+};
+pub use options::{Options, OptionsError};
+pub use theme::{ContrastIssue, FocusRing, SampleTheme, ThemeColours, MIN_TEXT_CONTRAST};
 
 pub use kas;
 pub use wgpu_glyph as glyph;
@@ -30,6 +46,60 @@ struct SharedState<T> {
     device: wgpu::Device,
     queue: wgpu::Queue,
     theme: T,
+    /// Atlases and glyph caches, shared by all windows
+    resources: SharedResources,
+    window_list: WindowList,
+    next_window_id: u32,
+    /// Factor applied to the DPI factor of each window
+    scale_factor: f64,
+    /// Outline the rect of each widget (see [`Options::debug_layout`])
+    debug_layout: bool,
+    recorder: Option<EventRecorder>,
+}
+
+/// Events sent through the event loop's user-event channel
+pub(crate) enum ProxyEvent<U> {
+    /// A user event (see [`Toolkit::create_proxy`])
+    User(U),
+    /// Mapped gamepad input
+    #[cfg(feature = "gamepad")]
+    Nav(kas::event::NavCommand),
+}
+
+/// Sends user events to a [`Toolkit`]'s event loop, from any thread
+///
+/// Construct via [`Toolkit::create_proxy`].
+pub struct ToolkitProxy<U: 'static> {
+    proxy: EventLoopProxy<ProxyEvent<U>>,
+}
+
+impl<U: 'static> Clone for ToolkitProxy<U> {
+    fn clone(&self) -> Self {
+        ToolkitProxy {
+            proxy: self.proxy.clone(),
+        }
+    }
+}
+
+impl<U: 'static> fmt::Debug for ToolkitProxy<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("ToolkitProxy").finish()
+    }
+}
+
+impl<U: 'static> ToolkitProxy<U> {
+    /// Send an event, waking the event loop
+    ///
+    /// Fails (returning the event) if the event loop has closed.
+    pub fn send_event(&self, event: U) -> Result<(), EventLoopClosed<U>> {
+        self.proxy
+            .send_event(ProxyEvent::User(event))
+            .map_err(|e| match e.0 {
+                ProxyEvent::User(event) => EventLoopClosed(event),
+                #[cfg(feature = "gamepad")]
+                _ => unreachable!(),
+            })
+    }
 }
 
 /// Possible failures from constructing a [`Toolkit`]
@@ -43,6 +113,10 @@ pub enum Error {
     NoAdapter,
     /// OS error during window creation
     Window(OsError),
+    /// Invalid toolkit options
+    Options(OptionsError),
+    /// I/O error (for example, opening a file named by [`Options`])
+    Io(io::Error),
 }
 
 impl fmt::Display for Error {
@@ -50,6 +124,8 @@ impl fmt::Display for Error {
         match self {
             Error::NoAdapter => write!(f, "no suitable graphics adapter found"),
             Error::Window(e) => write!(f, "window creation error: {}", e),
+            Error::Options(e) => write!(f, "invalid options: {}", e),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
         }
     }
 }
@@ -62,11 +138,26 @@ impl From<OsError> for Error {
     }
 }
 
+impl From<OptionsError> for Error {
+    fn from(e: OptionsError) -> Self {
+        Error::Options(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
 /// Builds a toolkit over a `winit::event_loop::EventLoop`.
 pub struct Toolkit<T: kas::theme::Theme<DrawPipe>, U: 'static> {
-    el: EventLoop<U>,
+    el: EventLoop<ProxyEvent<U>>,
     windows: Vec<Window<T::Window>>,
     shared: SharedState<T>,
+    inspector_key: Option<VirtualKeyCode>,
+    hud_key: Option<VirtualKeyCode>,
+    fuzz: Option<FuzzConfig>,
 }
 
 impl<T: kas::theme::Theme<DrawPipe> + 'static> Toolkit<T, ()> {
@@ -105,13 +196,15 @@ impl<T: kas::theme::Theme<DrawPipe> + 'static, U: 'static> Toolkit<T, U> {
         };
         info!("Using graphics adapter: {}", adapter.get_info().name);
 
-        let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
+        let (mut device, queue) = adapter.request_device(&wgpu::DeviceDescriptor {
             extensions: wgpu::Extensions {
                 anisotropic_filtering: false,
             },
             limits: wgpu::Limits::default(),
         });
 
+        let resources = SharedResources::new(&mut device, window::TEX_FORMAT, &theme);
+
         Ok(Toolkit {
             el: EventLoop::with_user_event(),
             windows: vec![],
@@ -119,7 +212,24 @@ impl<T: kas::theme::Theme<DrawPipe> + 'static, U: 'static> Toolkit<T, U> {
                 device,
                 queue,
                 theme,
+                resources,
+                window_list: Default::default(),
+                next_window_id: 1,
+                scale_factor: 1.0,
+                debug_layout: false,
+                recorder: None,
+            },
+            inspector_key: if cfg!(debug_assertions) {
+                Some(VirtualKeyCode::F12)
+            } else {
+                None
+            },
+            hud_key: if cfg!(debug_assertions) {
+                Some(VirtualKeyCode::F10)
+            } else {
+                None
             },
+            fuzz: None,
         })
     }
 
@@ -141,9 +251,105 @@ impl<T: kas::theme::Theme<DrawPipe> + 'static, U: 'static> Toolkit<T, U> {
         Ok(())
     }
 
+    /// Set the key used to toggle the widget inspector
+    ///
+    /// The inspector is a separate window showing the widget tree of the
+    /// window in which the key was pressed, with details of the hovered
+    /// widget (including its size rules, with the `inspect` feature). The
+    /// hovered widget is also highlighted in the inspected window.
+    ///
+    /// By default, this is `F12` in debug builds and disabled (`None`)
+    /// otherwise.
+    pub fn set_inspector_key(&mut self, key: Option<VirtualKeyCode>) {
+        self.inspector_key = key;
+    }
+
+    /// Set the key used to toggle the performance HUD
+    ///
+    /// The HUD is an overlay on the window in which the key was pressed,
+    /// showing a graph of recent frame times, the number of render passes,
+    /// draw calls and vertices of the last frame, the duration of the last
+    /// layout pass and the number of events handled between frames.
+    ///
+    /// By default, this is `F10` in debug builds and disabled (`None`)
+    /// otherwise.
+    pub fn set_hud_key(&mut self, key: Option<VirtualKeyCode>) {
+        self.hud_key = key;
+    }
+
+    /// Enable or disable event fuzzing
+    ///
+    /// This is a debugging aid for finding bugs in event handling: input events
+    /// are randomly delayed (and sometimes reordered between windows), and
+    /// synthetic events (rapid clicks, focus changes, resizes) are injected.
+    /// The seed used is logged on start. Disabled by default.
+    pub fn set_fuzz(&mut self, config: Option<FuzzConfig>) {
+        self.fuzz = config;
+    }
+
+    /// Apply debug options
+    ///
+    /// See [`Options`]; typically these are read via [`Options::from_env`].
+    /// Options should be applied before adding windows, since the colour
+    /// scheme and scale factor are not updated for existing windows. Fails if
+    /// the event-recording file cannot be created. An unknown colour scheme is
+    /// only logged as a warning, since the available schemes depend on the
+    /// theme.
+    pub fn set_options(&mut self, options: &Options) -> Result<(), Error> {
+        if let Some(name) = options.theme.as_ref() {
+            if !self.shared.theme.set_colour_scheme(name) {
+                warn!("Theme has no colour scheme named {:?}", name);
+            }
+        }
+        self.shared.scale_factor = options.scale.unwrap_or(1.0) as f64;
+        self.shared.debug_layout = options.debug_layout;
+        self.shared.recorder = match options.record_events.as_ref() {
+            Some(path) => {
+                info!("Recording events to {}", path.display());
+                Some(EventRecorder::new(path)?)
+            }
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Create a proxy, which may be used to send user events from other threads
+    ///
+    /// Each user event causes all windows to be redrawn; this may be used to
+    /// wake the UI after updating shared state (e.g. via a
+    /// [`kas::widget::LogSender`]). See winit's documentation of
+    /// `EventLoopProxy`.
+    pub fn create_proxy(&self) -> ToolkitProxy<U> {
+        ToolkitProxy {
+            proxy: self.el.create_proxy(),
+        }
+    }
+
+    /// Enable gamepad input
+    ///
+    /// Gamepads are polled on a new thread; input is mapped to navigation
+    /// commands (see [`GamepadMapping`]), which are delivered (through the
+    /// user-event channel) to the window with input focus. See
+    /// [`kas::event::NavCommand`] for the handling of these commands.
+    ///
+    /// If gamepads are unsupported on this system, this logs a warning.
+    #[cfg(feature = "gamepad")]
+    pub fn enable_gamepad(&mut self, mapping: GamepadMapping)
+    where
+        U: Send,
+    {
+        gamepad::spawn(mapping, self.el.create_proxy());
+    }
+
     /// Run the main loop.
     pub fn run(self) -> ! {
-        let mut el = event::Loop::new(self.windows, self.shared);
+        let mut el = event::Loop::new(
+            self.windows,
+            self.shared,
+            self.inspector_key,
+            self.hud_key,
+            self.fuzz,
+        );
         self.el
             .run(move |event, elwt, control_flow| el.handle(event, elwt, control_flow))
     }
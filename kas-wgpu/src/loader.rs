@@ -0,0 +1,260 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Background image loading
+//!
+//! Reading and decoding images can be slow; doing so on the UI thread blocks
+//! event handling and drawing. An [`ImageLoader`] instead reads and decodes on
+//! a worker thread. Completed images are collected with
+//! [`ImageLoader::poll`] or sent directly to an [`Image`] widget (see
+//! [`ImageLoader::load_to`]); optionally the loader wakes the event loop (e.g.
+//! by sending a user event) whenever a result is ready.
+//!
+//! [`Image`]: kas::widget::Image
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use kas::geom::Size;
+use kas::widget::ImageSender;
+
+/// Identifier of a load request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LoadId(u64);
+
+/// Source of an image
+#[derive(Clone, Debug)]
+pub enum ImageSource {
+    /// Read from a file
+    Path(PathBuf),
+    /// Already-read (encoded) data, e.g. fetched from a URL by the caller
+    Bytes(Vec<u8>),
+}
+
+/// A decoded image, in RGBA (sRGB) format with 4 bytes per texel
+#[derive(Clone, Debug)]
+pub struct ImageData {
+    /// Size in texels
+    pub size: Size,
+    /// Texel data, row-major
+    pub data: Vec<u8>,
+}
+
+/// Image loading error
+#[derive(Debug)]
+pub enum LoadError {
+    /// Failed to read the source
+    Io(std::io::Error),
+    /// Failed to decode the image
+    Decode(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            LoadError::Io(e) => write!(f, "image read error: {}", e),
+            LoadError::Decode(e) => write!(f, "image decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<ImageData> for kas::draw::RgbaImage {
+    fn from(image: ImageData) -> Self {
+        kas::draw::RgbaImage::new(image.size, image.data)
+    }
+}
+
+/// Image decoder
+///
+/// Decodes an encoded image. The default decoder, [`decode_pnm`], supports
+/// only binary PPM/PGM; applications wanting other formats should supply a
+/// decoder (for example, wrapping the `image` crate).
+pub type Decoder = fn(&[u8]) -> Result<ImageData, String>;
+
+type Job = (LoadId, ImageSource, Option<ImageSender>);
+type Done = (LoadId, Result<ImageData, LoadError>);
+
+/// Loads images on a background thread
+///
+/// Requests are processed in order by a single worker thread. Dropping the
+/// loader stops the worker after its current job.
+pub struct ImageLoader {
+    next_id: u64,
+    jobs: Sender<Job>,
+    done: Receiver<Done>,
+}
+
+impl ImageLoader {
+    /// Construct, using the default decoder ([`decode_pnm`])
+    pub fn new() -> Self {
+        ImageLoader::with_decoder(decode_pnm, || ())
+    }
+
+    /// Construct with a custom decoder and wake-up function
+    ///
+    /// The `wake` function is called on the worker thread each time a result
+    /// is ready. With `kas_wgpu`, this is typically used to send a user event
+    /// via a proxy (see [`Toolkit::create_proxy`](crate::Toolkit::create_proxy)),
+    /// after which [`ImageLoader::poll`] should be called.
+    pub fn with_decoder<W: Fn() + Send + 'static>(decoder: Decoder, wake: W) -> Self {
+        let (jobs, job_recv) = channel::<Job>();
+        let (done_send, done) = channel::<Done>();
+        thread::spawn(move || {
+            for (id, source, target) in job_recv {
+                let result = match source {
+                    ImageSource::Path(path) => load_image(path, decoder),
+                    ImageSource::Bytes(bytes) => decoder(&bytes).map_err(LoadError::Decode),
+                };
+                match (target, result) {
+                    (Some(target), Ok(image)) => {
+                        // Fails only if the widget no longer wants the image
+                        let _ = target.send(image.into());
+                    }
+                    (_, result) => {
+                        if done_send.send((id, result)).is_err() {
+                            break; // loader dropped
+                        }
+                    }
+                }
+                wake();
+            }
+        });
+
+        ImageLoader {
+            next_id: 0,
+            jobs,
+            done,
+        }
+    }
+
+    /// Request loading of an image
+    pub fn load(&mut self, source: ImageSource) -> LoadId {
+        self.push(source, None)
+    }
+
+    /// Request loading of an image, to be shown by an [`Image`] widget
+    ///
+    /// The `target` is obtained from a placeholder [`Image::loading`] via
+    /// [`Image::sender`]. On success, the image is sent to the widget (and
+    /// not reported by [`ImageLoader::poll`]); the widget shows the image
+    /// when next drawn, thus a wake-up function which sends a user event
+    /// (causing a redraw) should be used. Errors are reported by `poll`.
+    ///
+    /// [`Image`]: kas::widget::Image
+    /// [`Image::loading`]: kas::widget::Image::loading
+    /// [`Image::sender`]: kas::widget::Image::sender
+    pub fn load_to(&mut self, source: ImageSource, target: ImageSender) -> LoadId {
+        self.push(source, Some(target))
+    }
+
+    fn push(&mut self, source: ImageSource, target: Option<ImageSender>) -> LoadId {
+        let id = LoadId(self.next_id);
+        self.next_id += 1;
+        // Send only fails if the worker panicked; in this case the request is
+        // never completed.
+        let _ = self.jobs.send((id, source, target));
+        id
+    }
+
+    /// Collect a completed image, if any
+    ///
+    /// This does not block. Call repeatedly to collect all results.
+    pub fn poll(&self) -> Option<(LoadId, Result<ImageData, LoadError>)> {
+        self.done.try_recv().ok()
+    }
+}
+
+impl Default for ImageLoader {
+    fn default() -> Self {
+        ImageLoader::new()
+    }
+}
+
+/// Read and decode an image file on the current thread
+///
+/// This blocks until complete; see [`ImageLoader`] for background loading.
+/// The result may be converted to a [`kas::draw::RgbaImage`] for display by
+/// an [`Image`](kas::widget::Image) widget.
+pub fn load_image<P: AsRef<Path>>(path: P, decoder: Decoder) -> Result<ImageData, LoadError> {
+    let bytes = fs::read(path).map_err(LoadError::Io)?;
+    decoder(&bytes).map_err(LoadError::Decode)
+}
+
+/// Decode a binary PPM (`P6`) or PGM (`P5`) image with 8-bit samples
+///
+/// ```
+/// let ppm = b"P6 2 1 255\n\xff\x00\x00\x00\x00\xff";
+/// let image = kas_wgpu::decode_pnm(&ppm[..]).unwrap();
+/// assert_eq!(image.size, kas::geom::Size(2, 1));
+/// assert_eq!(image.data, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+/// ```
+pub fn decode_pnm(bytes: &[u8]) -> Result<ImageData, String> {
+    // Header: magic, width, height and maxval, separated by whitespace, where
+    // comments start with '#' and run to the end of the line.
+    let mut pos = 0;
+    let mut fields = [0u32; 3];
+    let magic = bytes.get(0..2).ok_or("unexpected end of data")?;
+    let channels = match magic {
+        b"P5" => 1,
+        b"P6" => 3,
+        _ => return Err("unsupported format (expected binary PPM or PGM)".into()),
+    };
+    pos += 2;
+    for field in &mut fields {
+        loop {
+            match bytes.get(pos) {
+                Some(b'#') => {
+                    while bytes.get(pos).map(|b| *b != b'\n').unwrap_or(false) {
+                        pos += 1;
+                    }
+                }
+                Some(b) if b.is_ascii_whitespace() => pos += 1,
+                Some(_) => break,
+                None => return Err("unexpected end of header".into()),
+            }
+        }
+        let start = pos;
+        while bytes.get(pos).map(|b| b.is_ascii_digit()).unwrap_or(false) {
+            pos += 1;
+        }
+        *field = std::str::from_utf8(&bytes[start..pos])
+            .unwrap()
+            .parse()
+            .map_err(|_| "invalid header field")?;
+    }
+    // A single whitespace byte separates header and data
+    pos += 1;
+
+    let [width, height, maxval] = fields;
+    if maxval == 0 || maxval > 255 {
+        return Err(format!("unsupported maxval: {}", maxval));
+    }
+    let len = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|n| n.checked_mul(channels))
+        .ok_or("image dimensions too large")?;
+    let end = pos.checked_add(len).ok_or("image dimensions too large")?;
+    let samples = bytes.get(pos..end).ok_or("unexpected end of data")?;
+
+    let scale = |s: u8| (s as u32 * 255 / maxval).min(255) as u8;
+    let mut data = Vec::with_capacity(len / channels * 4);
+    for texel in samples.chunks(channels) {
+        match texel {
+            [v] => data.extend_from_slice(&[scale(*v), scale(*v), scale(*v), 255]),
+            [r, g, b] => data.extend_from_slice(&[scale(*r), scale(*g), scale(*b), 255]),
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(ImageData {
+        size: Size(width, height),
+        data,
+    })
+}
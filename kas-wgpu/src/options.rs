@@ -0,0 +1,208 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Toolkit options from the environment and command line
+//!
+//! These are diagnostic switches common to all applications; see [`Options`].
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use std::{env, error, fmt};
+
+use log::warn;
+use winit::event::WindowEvent;
+
+/// Problems found while parsing [`Options`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionsError {
+    /// A flag was given without its value
+    MissingValue(&'static str),
+    /// The scale factor is not a positive number
+    InvalidScale(String),
+    /// A value is not valid Unicode
+    NotUnicode(&'static str),
+}
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            OptionsError::MissingValue(flag) => write!(f, "missing value for {}", flag),
+            OptionsError::InvalidScale(s) => write!(f, "invalid scale factor: {:?}", s),
+            OptionsError::NotUnicode(flag) => write!(f, "value of {} is not valid Unicode", flag),
+        }
+    }
+}
+
+impl error::Error for OptionsError {}
+
+/// Toolkit debug options
+///
+/// These may be read from environment variables and from the command line
+/// (where flags override variables):
+///
+/// | Flag | Variable | Effect |
+/// | --- | --- | --- |
+/// | `--kas-theme NAME` | `KAS_THEME` | Select the theme's colour scheme |
+/// | `--kas-scale FACTOR` | `KAS_SCALE` | Multiply the DPI factor |
+/// | `--kas-debug-layout` | `KAS_DEBUG_LAYOUT=1` | Outline each widget's rect |
+/// | `--kas-record-events FILE` | `KAS_RECORD_EVENTS` | Write window events to `FILE` |
+///
+/// Values may also be given as `--kas-scale=1.5`. Other arguments are ignored,
+/// thus applications may parse their own arguments as usual (ignoring those
+/// starting `--kas-`). Apply via [`Toolkit::set_options`]:
+///
+/// ```no_run
+/// use kas_wgpu::{Options, SampleTheme, Toolkit};
+///
+/// # fn main() -> Result<(), kas_wgpu::Error> {
+/// let mut toolkit = Toolkit::new(SampleTheme::new())?;
+/// toolkit.set_options(&Options::from_env()?)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Parsing is deterministic given its input:
+///
+/// ```
+/// use kas_wgpu::Options;
+///
+/// let mut options = Options::default();
+/// options.parse_args(vec!["app", "--kas-scale=2", "--verbose", "--kas-debug-layout"]).unwrap();
+/// assert_eq!(options.scale, Some(2.0));
+/// assert!(options.debug_layout);
+/// ```
+///
+/// [`Toolkit::set_options`]: crate::Toolkit::set_options
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Options {
+    /// Name of the colour scheme
+    ///
+    /// See [`kas::theme::Theme::set_colour_scheme`].
+    pub theme: Option<String>,
+    /// Factor applied to each window's DPI factor
+    pub scale: Option<f32>,
+    /// Outline the rect of each widget
+    ///
+    /// Outlines are drawn over the window contents after each frame, in window
+    /// coordinates; widgets within scrolled regions are outlined at their
+    /// unscrolled positions.
+    pub debug_layout: bool,
+    /// Write each window event, with a time stamp, to this file
+    pub record_events: Option<PathBuf>,
+}
+
+impl Options {
+    /// Read options from environment variables, then command-line arguments
+    pub fn from_env() -> Result<Self, OptionsError> {
+        let mut options = Options::default();
+        if let Some(name) = env_var("KAS_THEME")? {
+            options.theme = Some(name);
+        }
+        if let Some(scale) = env_var("KAS_SCALE")? {
+            options.scale = Some(parse_scale(&scale)?);
+        }
+        if let Some(value) = env::var_os("KAS_DEBUG_LAYOUT") {
+            options.debug_layout = !(value.is_empty() || value == "0");
+        }
+        if let Some(path) = env::var_os("KAS_RECORD_EVENTS") {
+            options.record_events = Some(path.into());
+        }
+        options.parse_args(env::args_os())?;
+        Ok(options)
+    }
+
+    /// Apply command-line arguments
+    ///
+    /// The first item is the program name, and is skipped.
+    pub fn parse_args<I, A>(&mut self, args: I) -> Result<(), OptionsError>
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        let mut args = args.into_iter().map(|arg| arg.into()).skip(1);
+        while let Some(arg) = args.next() {
+            // Arguments which are not valid Unicode are not ours
+            let arg = match arg.to_str() {
+                Some(arg) => arg.to_string(),
+                None => continue,
+            };
+            let (flag, inline) = match arg.find('=') {
+                Some(i) => (&arg[..i], Some(OsString::from(&arg[i + 1..]))),
+                None => (&arg[..], None),
+            };
+            let mut value = |flag| {
+                let value = inline.clone().or_else(|| args.next());
+                value.ok_or(OptionsError::MissingValue(flag))
+            };
+            let string = |value: OsString, flag| {
+                value
+                    .into_string()
+                    .map_err(|_| OptionsError::NotUnicode(flag))
+            };
+            match flag {
+                "--kas-theme" => {
+                    let name = string(value("--kas-theme")?, "--kas-theme")?;
+                    self.theme = Some(name);
+                }
+                "--kas-scale" => {
+                    let scale = string(value("--kas-scale")?, "--kas-scale")?;
+                    self.scale = Some(parse_scale(&scale)?);
+                }
+                "--kas-debug-layout" => self.debug_layout = true,
+                "--kas-record-events" => {
+                    self.record_events = Some(value("--kas-record-events")?.into());
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn env_var(name: &'static str) -> Result<Option<String>, OptionsError> {
+    match env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(OptionsError::NotUnicode(name)),
+    }
+}
+
+fn parse_scale(s: &str) -> Result<f32, OptionsError> {
+    match s.trim().parse::<f32>() {
+        Ok(scale) if scale.is_finite() && scale > 0.0 => Ok(scale),
+        _ => Err(OptionsError::InvalidScale(s.to_string())),
+    }
+}
+
+/// Writes window events to a file, one per line
+pub(crate) struct EventRecorder {
+    file: LineWriter<File>,
+    start: Instant,
+}
+
+impl EventRecorder {
+    pub fn new(path: &Path) -> io::Result<Self> {
+        let file = LineWriter::new(File::create(path)?);
+        Ok(EventRecorder {
+            file,
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, window: kas::WindowId, event: &WindowEvent) {
+        if let WindowEvent::RedrawRequested = event {
+            return;
+        }
+        let time = self.start.elapsed().as_secs_f64();
+        let result = writeln!(self.file, "{:.4} {:?} {:?}", time, window, event);
+        if let Err(e) = result {
+            warn!("Failed to record event: {}", e);
+        }
+    }
+}
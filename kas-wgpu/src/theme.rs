@@ -8,48 +8,539 @@
 //! Widget size and appearance can be modified through themes.
 
 use std::any::Any;
-use std::f32;
+use std::ops::Range;
+use std::{f32, fmt};
 
-use wgpu_glyph::{Font, HorizontalAlign, Layout, Scale, Section, VerticalAlign};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use wgpu_glyph::{
+    BuiltInLineBreaker, Font, FontId, HorizontalAlign, Layout, Scale, Section, SectionText,
+    VariedSection, VerticalAlign,
+};
 
 use kas::draw::*;
 use kas::event::HighlightState;
 use kas::geom::{Coord, Rect, Size};
 use kas::layout::{AxisInfo, SizeRules};
-use kas::theme::{self, Align, TextClass, TextProperties};
+use kas::theme::{self, Align, SpanClass, SpinnerStyle, TextClass, TextProperties, TextStyle};
+use log::warn;
 
 use crate::draw::*;
+use crate::font::{font_index, mono_index};
+
+/// Colours used by [`SampleTheme`]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThemeColours {
+    /// Background colour
+    pub background: Colour,
+    /// Frame colour
+    pub frame: Colour,
+    /// Text background
+    pub text_area: Colour,
+    /// Text in text area
+    pub text: Colour,
+    /// Text on background
+    pub label_text: Colour,
+    /// Text on button
+    pub button_text: Colour,
+    /// Keyboard-navigation focus indicator
+    pub nav_focus: Colour,
+    /// Button (and other control) colour
+    pub button: Colour,
+    /// Button colour when hovered
+    pub button_highlighted: Colour,
+    /// Button colour when depressed
+    pub button_depressed: Colour,
+    /// Level meter colour in the normal zone
+    pub level_normal: Colour,
+    /// Level meter colour in the warning zone
+    pub level_warn: Colour,
+    /// Level meter colour in the danger zone
+    pub level_danger: Colour,
+    /// Text highlighted as a match (e.g. of a search)
+    pub text_match: Colour,
+    /// Text highlighted as selected (e.g. the current search match)
+    pub text_selected: Colour,
+    /// Text highlighted as inserted (e.g. in a diff)
+    pub text_inserted: Colour,
+    /// Text highlighted as deleted (e.g. in a diff)
+    pub text_deleted: Colour,
+    /// Frame of a control with invalid content
+    pub error: Colour,
+    /// Hyperlink text
+    pub link: Colour,
+    /// Hyperlink text when hovered or depressed
+    pub link_hover: Colour,
+    /// Translucent colour over a window blocked by a modal window
+    pub dim: Colour,
+}
+
+impl ThemeColours {
+    /// The default colour scheme
+    pub const fn new() -> Self {
+        ThemeColours {
+            background: Colour::grey(0.7),
+            frame: Colour::grey(0.7),
+            text_area: Colour::grey(1.0),
+            text: Colour::grey(0.0),
+            label_text: Colour::grey(0.0),
+            button_text: Colour::grey(1.0),
+            nav_focus: Colour::new(1.0, 0.7, 0.5),
+            button: Colour::new(0.2, 0.7, 1.0),
+            button_highlighted: Colour::new(0.25, 0.8, 1.0),
+            button_depressed: Colour::new(0.15, 0.525, 0.75),
+            level_normal: Colour::new(0.2, 0.8, 0.2),
+            level_warn: Colour::new(1.0, 0.8, 0.0),
+            level_danger: Colour::new(0.9, 0.1, 0.1),
+            text_match: Colour::new(0.0, 0.4, 0.9),
+            text_selected: Colour::new(0.85, 0.25, 0.0),
+            text_inserted: Colour::new(0.0, 0.55, 0.1),
+            text_deleted: Colour::new(0.8, 0.1, 0.1),
+            error: Colour::new(0.9, 0.2, 0.1),
+            link: Colour::new(0.0, 0.2, 0.8),
+            link_hover: Colour::new(0.4, 0.0, 0.8),
+            dim: Colour {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.3,
+            },
+        }
+    }
+
+    /// A high-contrast colour scheme
+    ///
+    /// All text meets WCAG level AA contrast (see
+    /// [`ThemeColours::contrast_issues`]).
+    pub const fn high_contrast() -> Self {
+        ThemeColours {
+            background: Colour::grey(1.0),
+            frame: Colour::grey(0.1),
+            text_area: Colour::grey(1.0),
+            text: Colour::grey(0.0),
+            label_text: Colour::grey(0.0),
+            button_text: Colour::grey(1.0),
+            nav_focus: Colour::new(1.0, 0.45, 0.0),
+            button: Colour::new(0.0, 0.15, 0.5),
+            button_highlighted: Colour::new(0.0, 0.18, 0.55),
+            button_depressed: Colour::new(0.0, 0.1, 0.35),
+            level_normal: Colour::new(0.0, 0.5, 0.0),
+            level_warn: Colour::new(0.8, 0.5, 0.0),
+            level_danger: Colour::new(0.7, 0.0, 0.0),
+            text_match: Colour::new(0.0, 0.15, 0.7),
+            text_selected: Colour::new(0.7, 0.0, 0.0),
+            text_inserted: Colour::new(0.0, 0.25, 0.0),
+            text_deleted: Colour::new(0.6, 0.0, 0.0),
+            error: Colour::new(0.8, 0.0, 0.0),
+            link: Colour::new(0.0, 0.0, 0.7),
+            link_hover: Colour::new(0.35, 0.0, 0.55),
+            dim: Colour {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.5,
+            },
+        }
+    }
+
+    /// A colour scheme for red-green colour-vision deficiency
+    ///
+    /// For protanopia and deuteranopia: hues which differ in meaning (e.g.
+    /// inserted and deleted text, or the zones of a level meter) are chosen
+    /// from the blue-orange axis, and also differ in lightness. All text meets
+    /// WCAG level AA contrast.
+    pub const fn red_green_safe() -> Self {
+        ThemeColours {
+            background: Colour::grey(0.7),
+            frame: Colour::grey(0.7),
+            text_area: Colour::grey(1.0),
+            text: Colour::grey(0.0),
+            label_text: Colour::grey(0.0),
+            button_text: Colour::grey(1.0),
+            nav_focus: Colour::new(0.8, 0.35, 0.0),
+            button: Colour::new(0.0, 0.15, 0.45),
+            button_highlighted: Colour::new(0.0, 0.18, 0.55),
+            button_depressed: Colour::new(0.0, 0.1, 0.3),
+            level_normal: Colour::new(0.0, 0.17, 0.45),
+            level_warn: Colour::new(0.87, 0.78, 0.05),
+            level_danger: Colour::new(0.67, 0.11, 0.0),
+            text_match: Colour::new(0.0, 0.12, 0.45),
+            text_selected: Colour::new(0.45, 0.08, 0.0),
+            text_inserted: Colour::new(0.0, 0.15, 0.5),
+            text_deleted: Colour::new(0.48, 0.07, 0.0),
+            error: Colour::new(0.8, 0.15, 0.0),
+            link: Colour::new(0.0, 0.08, 0.35),
+            link_hover: Colour::new(0.35, 0.02, 0.3),
+            dim: Colour {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.3,
+            },
+        }
+    }
+
+    /// A colour scheme for blue-yellow colour-vision deficiency
+    ///
+    /// For tritanopia: hues which differ in meaning are chosen from the
+    /// red-teal axis, and also differ in lightness. All text meets WCAG level
+    /// AA contrast.
+    pub const fn blue_yellow_safe() -> Self {
+        ThemeColours {
+            background: Colour::grey(0.7),
+            frame: Colour::grey(0.7),
+            text_area: Colour::grey(1.0),
+            text: Colour::grey(0.0),
+            label_text: Colour::grey(0.0),
+            button_text: Colour::grey(1.0),
+            nav_focus: Colour::new(0.9, 0.1, 0.2),
+            button: Colour::new(0.0, 0.2, 0.2),
+            button_highlighted: Colour::new(0.0, 0.22, 0.22),
+            button_depressed: Colour::new(0.0, 0.12, 0.12),
+            level_normal: Colour::new(0.0, 0.35, 0.3),
+            level_warn: Colour::new(1.0, 0.45, 0.55),
+            level_danger: Colour::new(0.6, 0.0, 0.0),
+            text_match: Colour::new(0.0, 0.2, 0.2),
+            text_selected: Colour::new(0.6, 0.0, 0.02),
+            text_inserted: Colour::new(0.0, 0.18, 0.18),
+            text_deleted: Colour::new(0.55, 0.0, 0.0),
+            error: Colour::new(0.8, 0.0, 0.05),
+            link: Colour::new(0.0, 0.1, 0.12),
+            link_hover: Colour::new(0.4, 0.0, 0.05),
+            dim: Colour {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.3,
+            },
+        }
+    }
+
+    /// Find text colours with insufficient contrast against their background
+    ///
+    /// Each pair of text and background colours used together by
+    /// [`SampleTheme`] is checked against [`MIN_TEXT_CONTRAST`] (WCAG level
+    /// AA for normal text). In debug builds, [`SampleTheme::set_colours`] logs
+    /// a warning for each issue found.
+    ///
+    /// ```
+    /// use kas_wgpu::ThemeColours;
+    ///
+    /// assert!(ThemeColours::high_contrast().contrast_issues().is_empty());
+    /// ```
+    pub fn contrast_issues(&self) -> Vec<ContrastIssue> {
+        let pairs = [
+            ("text", self.text, "text_area", self.text_area),
+            ("label_text", self.label_text, "background", self.background),
+            ("button_text", self.button_text, "button", self.button),
+            (
+                "button_text",
+                self.button_text,
+                "button_highlighted",
+                self.button_highlighted,
+            ),
+            (
+                "button_text",
+                self.button_text,
+                "button_depressed",
+                self.button_depressed,
+            ),
+            ("link", self.link, "background", self.background),
+            ("link_hover", self.link_hover, "background", self.background),
+            ("text_match", self.text_match, "text_area", self.text_area),
+            (
+                "text_selected",
+                self.text_selected,
+                "text_area",
+                self.text_area,
+            ),
+            (
+                "text_inserted",
+                self.text_inserted,
+                "text_area",
+                self.text_area,
+            ),
+            (
+                "text_deleted",
+                self.text_deleted,
+                "text_area",
+                self.text_area,
+            ),
+        ];
+        (pairs.iter())
+            .map(|(foreground, fg, background, bg)| ContrastIssue {
+                foreground,
+                background,
+                ratio: fg.contrast_ratio(*bg),
+            })
+            .filter(|issue| issue.ratio < MIN_TEXT_CONTRAST)
+            .collect()
+    }
+
+    /// Derive a high-contrast variant of this colour scheme
+    ///
+    /// Background colours (`background` and `text_area`) are kept. Each other
+    /// colour is made darker or lighter, where necessary, to contrast with
+    /// the colour against which it is drawn: text (including `link` and the
+    /// `text_*` span colours) to a ratio of at least 7 (WCAG level AAA),
+    /// buttons to the same ratio against `button_text`, and other colours
+    /// (frames, focus indicator and level meters) to a ratio of at least 3.
+    /// Colours already meeting these requirements are unchanged, except that
+    /// button states (normal, highlighted and depressed) are adjusted
+    /// together, preserving the differences between them.
+    ///
+    /// ```
+    /// use kas_wgpu::ThemeColours;
+    ///
+    /// let colours = ThemeColours::new().to_high_contrast();
+    /// assert!(colours.contrast_issues().is_empty());
+    /// ```
+    pub fn to_high_contrast(&self) -> Self {
+        const TEXT: f32 = 7.0;
+        const OTHER: f32 = 3.0;
+        let (bg, area) = (self.background, self.text_area);
+        let on_button = |c: Colour| c.contrast_ratio(self.button_text);
+        let states = [self.button, self.button_highlighted, self.button_depressed];
+        let least = states.iter().map(|c| on_button(*c)).fold(TEXT, f32::min);
+        let button = |c: Colour| with_contrast(c, self.button_text, on_button(c) * TEXT / least);
+        ThemeColours {
+            background: bg,
+            frame: with_contrast(self.frame, bg, OTHER),
+            text_area: area,
+            text: with_contrast(self.text, area, TEXT),
+            label_text: with_contrast(self.label_text, bg, TEXT),
+            button_text: self.button_text,
+            nav_focus: with_contrast(self.nav_focus, bg, OTHER),
+            button: button(self.button),
+            button_highlighted: button(self.button_highlighted),
+            button_depressed: button(self.button_depressed),
+            level_normal: with_contrast(self.level_normal, area, OTHER),
+            level_warn: with_contrast(self.level_warn, area, OTHER),
+            level_danger: with_contrast(self.level_danger, area, OTHER),
+            text_match: with_contrast(self.text_match, area, TEXT),
+            text_selected: with_contrast(self.text_selected, area, TEXT),
+            text_inserted: with_contrast(self.text_inserted, area, TEXT),
+            text_deleted: with_contrast(self.text_deleted, area, TEXT),
+            error: with_contrast(self.error, bg, OTHER),
+            link: with_contrast(self.link, bg, TEXT),
+            link_hover: with_contrast(self.link_hover, bg, TEXT),
+            dim: Colour {
+                a: self.dim.a.max(0.5),
+                ..self.dim
+            },
+        }
+    }
+
+    fn span_colour(&self, class: SpanClass) -> Colour {
+        match class {
+            SpanClass::Match => self.text_match,
+            SpanClass::Selected => self.text_selected,
+            SpanClass::Inserted => self.text_inserted,
+            SpanClass::Deleted => self.text_deleted,
+        }
+    }
+
+    fn nav_colour(&self, highlights: HighlightState) -> Option<Colour> {
+        if highlights.key_focus {
+            Some(self.nav_focus)
+        } else {
+            None
+        }
+    }
+
+    fn button_colour(&self, highlights: HighlightState, show: bool) -> Option<Colour> {
+        if highlights.depress {
+            Some(self.button_depressed)
+        } else if show && highlights.hover {
+            Some(self.button_highlighted)
+        } else if show {
+            Some(self.button)
+        } else {
+            None
+        }
+    }
+}
+
+// Mix `fg` towards black or white (whichever contrasts more with `bg`) until
+// its contrast ratio with `bg` is at least `ratio`, or as close as possible
+fn with_contrast(fg: Colour, bg: Colour, ratio: f32) -> Colour {
+    if fg.contrast_ratio(bg) >= ratio {
+        return fg;
+    }
+    let lum = bg.luminance();
+    let black = Colour::grey(0.0).contrast_ratio(bg) >= Colour::grey(1.0).contrast_ratio(bg);
+    // Target luminance, from the definition of the contrast ratio
+    let (target, goal) = match black {
+        true => (0.0, (lum + 0.05) / ratio - 0.05),
+        false => (1.0, ratio * (lum + 0.05) - 0.05),
+    };
+    // Luminance is linear in the components, thus also in the mix
+    let fg_lum = fg.luminance();
+    let t = ((goal - fg_lum) / (target - fg_lum)).clamp(0.0, 1.0);
+    let mix = |c: f32| c + t * (target - c);
+    Colour {
+        r: mix(fg.r),
+        g: mix(fg.g),
+        b: mix(fg.b),
+        a: fg.a,
+    }
+}
+
+impl Default for ThemeColours {
+    fn default() -> Self {
+        ThemeColours::new()
+    }
+}
+
+/// Minimum contrast ratio of text against its background
+///
+/// This is the requirement of WCAG 2 (level AA) for normal text. See
+/// [`Colour::contrast_ratio`].
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// A text colour with insufficient contrast against its background
+///
+/// See [`ThemeColours::contrast_issues`]. Colours are identified by the names
+/// of fields of [`ThemeColours`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContrastIssue {
+    /// The text colour
+    pub foreground: &'static str,
+    /// The background colour
+    pub background: &'static str,
+    /// The contrast ratio of the two
+    pub ratio: f32,
+}
+
+impl fmt::Display for ContrastIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{} on {} has contrast ratio {:.2} (minimum {})",
+            self.foreground, self.background, self.ratio, MIN_TEXT_CONTRAST
+        )
+    }
+}
+
+/// Style of the keyboard-navigation focus indicator
+///
+/// The indicator is drawn as a ring just inside the frame of the focussed
+/// element. Dimensions are in logical pixels (i.e. before DPI scaling).
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FocusRing {
+    /// Thickness of the ring
+    pub width: f32,
+    /// Distance between the element's frame and the ring
+    pub offset: f32,
+    /// Ring colour; if `None`, [`ThemeColours::nav_focus`] is used
+    pub colour: Option<Colour>,
+}
+
+impl Default for FocusRing {
+    fn default() -> Self {
+        FocusRing {
+            width: MARGIN,
+            offset: 0.0,
+            colour: None,
+        }
+    }
+}
 
 /// A simple, inflexible theme providing a sample implementation.
+///
+/// The colour schemes `default`, `high-contrast`, `red-green-safe` and
+/// `blue-yellow-safe` may be selected by name (see
+/// [`Theme::set_colour_scheme`]).
+///
+/// [`Theme::set_colour_scheme`]: kas::theme::Theme::set_colour_scheme
 #[derive(Copy, Clone, Debug, Default)]
 pub struct SampleTheme {
     font_size: f32,
+    // The scheme set by the user
+    colours: ThemeColours,
+    // The scheme in use: derived from `colours` in high-contrast mode
+    active: ThemeColours,
+    focus: FocusRing,
+    high_contrast: bool,
 }
 
 impl SampleTheme {
     /// Construct
     pub fn new() -> Self {
-        SampleTheme { font_size: 18.0 }
+        SampleTheme {
+            font_size: 18.0,
+            colours: ThemeColours::new(),
+            active: ThemeColours::new(),
+            focus: FocusRing::default(),
+            high_contrast: false,
+        }
     }
 
     /// Set font size. Default is 18.
     pub fn set_font_size(&mut self, size: f32) {
         self.font_size = size;
     }
+
+    /// Set the colour scheme
+    ///
+    /// In high-contrast mode, a variant derived from this scheme is used (see
+    /// [`SampleTheme::set_high_contrast`]).
+    ///
+    /// In debug builds, this logs a warning for each text colour with
+    /// insufficient contrast (see [`ThemeColours::contrast_issues`]).
+    pub fn set_colours(&mut self, colours: ThemeColours) {
+        if cfg!(debug_assertions) {
+            for issue in colours.contrast_issues() {
+                warn!("Theme colours: {}", issue);
+            }
+        }
+        self.colours = colours;
+        self.update_colours();
+    }
+
+    /// Set the style of the keyboard-navigation focus indicator
+    pub fn set_focus_ring(&mut self, focus: FocusRing) {
+        self.focus = focus;
+    }
+
+    /// Enable or disable high-contrast mode
+    ///
+    /// In high-contrast mode, the colour scheme in use is derived from that
+    /// set by [`SampleTheme::set_colours`] via
+    /// [`ThemeColours::to_high_contrast`], the thickness of the focus
+    /// indicator is doubled and the marks of checkboxes, radio boxes and
+    /// highlighted lines are thickened. Disabling the mode restores the scheme
+    /// as set.
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+        self.update_colours();
+    }
+
+    fn update_colours(&mut self) {
+        self.active = match self.high_contrast {
+            false => self.colours,
+            true => self.colours.to_high_contrast(),
+        };
+    }
 }
 
 #[doc(hidden)]
 pub struct SampleWindow {
-    font_size: f32,
+    theme: SampleTheme,
     font_scale: f32,
     margin: f32,
     frame_size: f32,
     button_frame: f32,
     scrollbar_size: f32,
+    focus_width: f32,
+    focus_offset: f32,
+    line_width: f32,
 }
 
-/// Inner margin; this is multiplied by the DPI factor then rounded to nearest
-/// integer, e.g. `(2.0 * 1.25).round() == 3.0`.
+/// Inner margin; this is multiplied by the DPI factor then snapped to whole
+/// pixels (see [`snap_width`]), e.g. `(2.0 * 1.25).round() == 3.0`.
 const MARGIN: f32 = 2.0;
 /// Frame size (adjusted as above)
 const FRAME_SIZE: f32 = 5.0;
@@ -58,49 +549,39 @@ const BUTTON_FRAME: f32 = 5.0;
 /// Scrollbar width & min length
 const SCROLLBAR_SIZE: f32 = 8.0;
 
-/// Background colour
-pub const BACKGROUND: Colour = Colour::grey(0.7);
-/// Frame colour
-pub const FRAME: Colour = BACKGROUND;
-/// Text background
-pub const TEXT_AREA: Colour = Colour::grey(1.0);
-
-/// Text in text area
-pub const TEXT: Colour = Colour::grey(0.0);
-/// Text on background
-pub const LABEL_TEXT: Colour = Colour::grey(0.0);
-/// Text on button
-pub const BUTTON_TEXT: Colour = Colour::grey(1.0);
-
-fn nav_colour(highlights: HighlightState) -> Option<Colour> {
-    if highlights.key_focus {
-        Some(Colour::new(1.0, 0.7, 0.5))
-    } else {
-        None
+impl SampleWindow {
+    fn new(theme: SampleTheme, dpi_factor: f32) -> Self {
+        let focus_mult = if theme.high_contrast { 2.0 } else { 1.0 };
+        let focus_width = snap_width(theme.focus.width * focus_mult * dpi_factor);
+        let focus_offset = snap_width(theme.focus.offset * dpi_factor);
+        // The focus ring is drawn within the margin, hence the margin must be
+        // large enough to contain it.
+        let margin = snap_width(MARGIN * dpi_factor).max(focus_width + focus_offset);
+        SampleWindow {
+            theme,
+            font_scale: (theme.font_size * dpi_factor).round(),
+            margin,
+            frame_size: snap_width(FRAME_SIZE * dpi_factor),
+            button_frame: snap_width(BUTTON_FRAME * dpi_factor),
+            scrollbar_size: snap_width(SCROLLBAR_SIZE * dpi_factor),
+            focus_width,
+            focus_offset,
+            // Hairlines are one (logical) pixel wide
+            line_width: snap_width(dpi_factor),
+        }
     }
-}
 
-fn button_colour(highlights: HighlightState, show: bool) -> Option<Colour> {
-    if highlights.depress {
-        Some(Colour::new(0.15, 0.525, 0.75))
-    } else if show && highlights.hover {
-        Some(Colour::new(0.25, 0.8, 1.0))
-    } else if show {
-        Some(Colour::new(0.2, 0.7, 1.0))
-    } else {
-        None
+    #[inline]
+    fn colours(&self) -> &ThemeColours {
+        &self.theme.active
     }
-}
 
-impl SampleWindow {
-    fn new(font_size: f32, dpi_factor: f32) -> Self {
-        SampleWindow {
-            font_size,
-            font_scale: (font_size * dpi_factor).round(),
-            margin: (MARGIN * dpi_factor).round(),
-            frame_size: (FRAME_SIZE * dpi_factor).round(),
-            button_frame: (BUTTON_FRAME * dpi_factor).round(),
-            scrollbar_size: (SCROLLBAR_SIZE * dpi_factor).round(),
+    // Width of the gap between a mark (e.g. a check) and its surround, given
+    // the usual width; in high-contrast mode, marks grow into half the gap
+    fn mark_gap(&self, gap: f32) -> f32 {
+        match self.theme.high_contrast {
+            false => gap,
+            true => (0.5 * gap).floor(),
         }
     }
 }
@@ -111,6 +592,68 @@ pub struct SizeHandle<'a> {
     window: &'a mut SampleWindow,
 }
 
+impl<'a> SizeHandle<'a> {
+    // Measure the size of text within the given bounds
+    fn measure_text(
+        &mut self,
+        text: &str,
+        class: TextClass,
+        multi_line: bool,
+        bounds: (f32, f32),
+    ) -> (u32, u32) {
+        let layout = match multi_line {
+            false => Layout::default_single_line(),
+            true => Layout::default_wrap(),
+        };
+        let bounds = self.draw.glyph_bounds(Section {
+            text,
+            screen_position: (0.0, 0.0),
+            scale: Scale::uniform(self.window.font_scale),
+            bounds,
+            layout,
+            font_id: class_font(class),
+            ..Section::default()
+        });
+        bounds
+            .map(|(min, max)| ((max - min).0 as u32, (max - min).1 as u32))
+            .unwrap_or((0, 0))
+    }
+
+    // Measure the size of rich text within the given bounds
+    fn measure_runs(
+        &mut self,
+        runs: &[(&str, TextStyle)],
+        multi_line: bool,
+        bounds: (f32, f32),
+    ) -> (u32, u32) {
+        let layout = match multi_line {
+            false => Layout::default_single_line(),
+            true => Layout::default_wrap(),
+        };
+        let text = run_sections(runs, self.window.font_scale, Colour::default());
+        let bounds = self.draw.glyph_bounds(VariedSection {
+            screen_position: (0.0, 0.0),
+            bounds,
+            layout,
+            text,
+            ..VariedSection::default()
+        });
+        bounds
+            .map(|(min, max)| ((max - min).0 as u32, (max - min).1 as u32))
+            .unwrap_or((0, 0))
+    }
+
+    // Size rules on each axis of text of the given size
+    fn text_rules(&self, size: (u32, u32)) -> (SizeRules, SizeRules) {
+        let line_height = self.window.font_scale as u32;
+        let min = 3 * line_height;
+        let margin = SizeRules::fixed(2 * self.window.margin as u32);
+        let w = SizeRules::variable(min, size.0.max(min));
+        let h = SizeRules::variable(line_height, size.1.max(line_height));
+        (w + margin, h + margin)
+    }
+}
+
 impl theme::Window<DrawPipe> for SampleWindow {
     type SizeHandle = SizeHandle<'static>;
 
@@ -128,7 +671,7 @@ impl theme::Window<DrawPipe> for SampleWindow {
     }
 
     fn set_dpi_factor(&mut self, factor: f32) {
-        *self = SampleWindow::new(self.font_size, factor)
+        *self = SampleWindow::new(self.theme, factor)
     }
 }
 
@@ -138,6 +681,12 @@ impl<'a> theme::SizeHandle for SizeHandle<'a> {
         (Size::uniform(f), Size::uniform(f))
     }
 
+    fn group_frame(&self) -> (Size, Size) {
+        let f = self.window.frame_size as u32;
+        let title = (self.window.font_scale + 2.0 * self.window.margin) as u32;
+        (Size(f, title.max(f)), Size::uniform(f))
+    }
+
     fn inner_margin(&self) -> Size {
         Size::uniform(self.window.margin as u32)
     }
@@ -153,50 +702,90 @@ impl<'a> theme::SizeHandle for SizeHandle<'a> {
     fn text_bound(
         &mut self,
         text: &str,
+        class: TextClass,
+        multi_line: bool,
+        axis: AxisInfo,
+    ) -> SizeRules {
+        let mut bounds = (f32::INFINITY, f32::INFINITY);
+        if let Some(size) = axis.fixed(false) {
+            bounds.1 = size as f32;
+        } else if let Some(size) = axis.fixed(true) {
+            bounds.0 = size as f32;
+        }
+        let size = self.measure_text(text, class, multi_line, bounds);
+        let (w, h) = self.text_rules(size);
+        match axis.vertical() {
+            false => w,
+            true => h,
+        }
+    }
+
+    fn text_bound_both(
+        &mut self,
+        text: &str,
+        class: TextClass,
+        multi_line: bool,
+    ) -> (SizeRules, SizeRules) {
+        let bounds = (f32::INFINITY, f32::INFINITY);
+        let size = self.measure_text(text, class, multi_line, bounds);
+        self.text_rules(size)
+    }
+
+    fn text_runs_bound(
+        &mut self,
+        runs: &[(&str, TextStyle)],
         _: TextClass,
         multi_line: bool,
         axis: AxisInfo,
     ) -> SizeRules {
-        let font_scale = self.window.font_scale;
-        let line_height = font_scale as u32;
-        let draw = &mut self.draw;
-        let mut bound = |vert: bool| -> u32 {
-            let layout = match multi_line {
-                false => Layout::default_single_line(),
-                true => Layout::default_wrap(),
+        let mut bounds = (f32::INFINITY, f32::INFINITY);
+        if let Some(size) = axis.fixed(false) {
+            bounds.1 = size as f32;
+        } else if let Some(size) = axis.fixed(true) {
+            bounds.0 = size as f32;
+        }
+        let size = self.measure_runs(runs, multi_line, bounds);
+        let (w, h) = self.text_rules(size);
+        match axis.vertical() {
+            false => w,
+            true => h,
+        }
+    }
+
+    fn text_index_nearest(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        pos: Coord,
+    ) -> usize {
+        let stops = caret_stops(self.draw, self.window, rect, text, &props);
+        let pos = Vec2::from(pos);
+        let mut best = (f32::INFINITY, f32::INFINITY, 0);
+        for stop in &stops {
+            // Prefer the nearest line, then the nearest position on it
+            let dy = if pos.1 < stop.top {
+                stop.top - pos.1
+            } else {
+                (pos.1 - stop.bottom).max(0.0)
             };
-            let mut bounds = (f32::INFINITY, f32::INFINITY);
-            if let Some(size) = axis.fixed(false) {
-                bounds.1 = size as f32;
-            } else if let Some(size) = axis.fixed(true) {
-                bounds.0 = size as f32;
+            let dx = (pos.0 - stop.x).abs();
+            if (dy, dx) < (best.0, best.1) {
+                best = (dy, dx, stop.index);
             }
+        }
+        best.2
+    }
 
-            let bounds = draw.glyph_bounds(Section {
-                text,
-                screen_position: (0.0, 0.0),
-                scale: Scale::uniform(font_scale),
-                bounds,
-                layout,
-                ..Section::default()
-            });
-
-            bounds
-                .map(|(min, max)| match vert {
-                    false => (max - min).0,
-                    true => (max - min).1,
-                } as u32)
-                .unwrap_or(0)
-        };
-
-        let inner = if !axis.vertical() {
-            let min = 3 * line_height;
-            SizeRules::variable(min, bound(false).max(min))
-        } else {
-            SizeRules::variable(line_height, bound(true).max(line_height))
-        };
-        let margin = SizeRules::fixed(2 * self.window.margin as u32);
-        inner + margin
+    fn text_caret_rect(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        index: usize,
+    ) -> Rect {
+        let stops = caret_stops(self.draw, self.window, rect, text, &props);
+        caret_rect(&stops, index)
     }
 
     fn button_surround(&self) -> (Size, Size) {
@@ -215,19 +804,69 @@ impl<'a> theme::SizeHandle for SizeHandle<'a> {
         )
     }
 
+    fn radiobox(&self) -> Size {
+        self.checkbox()
+    }
+
     fn scrollbar(&self) -> (u32, u32, u32) {
         let s = self.window.scrollbar_size as u32;
         (s, s, 2 * s)
     }
+
+    fn slider(&self) -> (u32, u32, u32) {
+        let s = self.window.scrollbar_size as u32;
+        (s, s, 5 * s)
+    }
+
+    fn rating_symbol(&self) -> Size {
+        self.checkbox()
+    }
+
+    fn switch(&self) -> Size {
+        let size = self.checkbox();
+        Size(2 * size.0, size.1)
+    }
+
+    fn dial(&self) -> Size {
+        let size = self.checkbox();
+        Size::uniform(2 * size.0.max(size.1))
+    }
+
+    fn level_meter(&self) -> (u32, u32) {
+        let (thickness, _, min_len) = self.slider();
+        (thickness / 2, min_len)
+    }
+
+    fn swatch(&self) -> Size {
+        self.switch()
+    }
+
+    fn splitter(&self) -> u32 {
+        self.slider().0 / 2
+    }
+
+    fn spinner(&self) -> Size {
+        Size::uniform((2.0 * self.window.font_scale) as u32)
+    }
+
+    fn separator(&self) -> (u32, u32) {
+        let thickness = (self.window.line_width as u32).max(1);
+        (thickness, self.window.margin as u32)
+    }
 }
 
 #[doc(hidden)]
 pub struct DrawHandle<'a> {
     draw: &'a mut DrawPipe,
     window: &'a mut SampleWindow,
+    // Clip rect, in window coordinates
     rect: Rect,
     offset: Coord,
+    // Transform from pass coordinates (widget coordinates plus offset) to
+    // window coordinates
+    transform: Transform,
     pass: usize,
+    overlay: bool,
 }
 
 impl theme::Theme<DrawPipe> for SampleTheme {
@@ -238,7 +877,7 @@ impl theme::Theme<DrawPipe> for SampleTheme {
     ///
     /// See also documentation on [`ThemeWindow::set_dpi_factor`].
     fn new_window(&self, _draw: &mut DrawPipe, dpi_factor: f32) -> Self::Window {
-        SampleWindow::new(self.font_size, dpi_factor)
+        SampleWindow::new(*self, dpi_factor)
     }
 
     unsafe fn draw_handle<'a>(
@@ -254,12 +893,14 @@ impl theme::Theme<DrawPipe> for SampleTheme {
             window: transmute::<&'a mut Self::Window, &'static mut Self::Window>(window),
             rect,
             offset: Coord::ZERO,
+            transform: Transform::IDENTITY,
             pass: 0,
+            overlay: false,
         }
     }
 
     fn get_fonts<'a>(&self) -> Vec<Font<'a>> {
-        vec![crate::font::get_font()]
+        crate::font::get_fonts()
     }
 
     fn light_direction(&self) -> (f32, f32) {
@@ -267,7 +908,258 @@ impl theme::Theme<DrawPipe> for SampleTheme {
     }
 
     fn clear_colour(&self) -> Colour {
-        BACKGROUND
+        self.active.background
+    }
+
+    fn set_colour_scheme(&mut self, name: &str) -> bool {
+        match name {
+            "default" => {
+                self.set_colours(ThemeColours::new());
+                self.set_high_contrast(false);
+            }
+            "high-contrast" => {
+                self.set_colours(ThemeColours::high_contrast());
+                self.set_high_contrast(true);
+            }
+            "red-green-safe" => self.set_colours(ThemeColours::red_green_safe()),
+            "blue-yellow-safe" => self.set_colours(ThemeColours::blue_yellow_safe()),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<'a> DrawHandle<'a> {
+    /// Add a pass clipped to `rect` (in pass coordinates) and the current
+    /// region, with the current transform
+    ///
+    /// Returns `None` if the intersection is empty.
+    fn add_pass(&mut self, rect: Rect) -> Option<(usize, Rect)> {
+        let rect = self.transform.apply_rect(rect).intersection(&self.rect)?;
+        let pass = match self.overlay {
+            false => self.draw.add_clip_region(rect),
+            true => self.draw.add_overlay_region(rect),
+        };
+        if !self.transform.is_identity() {
+            self.draw.set_transform(pass, self.transform);
+        }
+        Some((pass, rect))
+    }
+
+    /// Draw the margin inside `quad`, shrinking `quad` accordingly
+    ///
+    /// If the element has key focus, the focus ring is drawn within this
+    /// margin; the rest of the margin is drawn with colour `fill`. Returns
+    /// the width of the gap between the ring (if any) and the inner quad.
+    fn focus_ring(&mut self, quad: &mut Quad, highlights: HighlightState, fill: Colour) -> f32 {
+        let window = &self.window;
+        let ring = window
+            .colours()
+            .nav_colour(highlights)
+            .map(|col| window.theme.focus.colour.unwrap_or(col));
+        let widths = match ring {
+            Some(_) => [
+                window.focus_offset,
+                window.focus_width,
+                window.margin - window.focus_offset - window.focus_width,
+            ],
+            None => [0.0, window.margin, 0.0],
+        };
+        let cols = [fill, ring.unwrap_or(fill), fill];
+
+        for (width, col) in widths.iter().zip(cols.iter()) {
+            if *width > 0.0 {
+                let outer = *quad;
+                quad.shrink(*width);
+                self.draw
+                    .draw_frame(self.pass, outer, *quad, Style::Flat, *col);
+            }
+        }
+        match ring {
+            Some(_) => widths[2],
+            None => widths[1],
+        }
+    }
+    /// Draw a slider track: a narrow, sunken bar centred across `rect`
+    ///
+    /// Returns the inner quad of the track.
+    fn slider_track(&mut self, rect: Rect, dir: bool) -> Quad {
+        let pos0 = Vec2::from(rect.pos + self.offset);
+        let size = Vec2::from(rect.size);
+
+        let mut track = Quad(pos0, pos0 + size);
+        let inset = if !dir { size.1 } else { size.0 };
+        let inset = (0.375 * inset).floor();
+        if !dir {
+            (track.0).1 += inset;
+            (track.1).1 -= inset;
+        } else {
+            (track.0).0 += inset;
+            (track.1).0 -= inset;
+        }
+        let outer = track;
+        track.shrink(self.window.frame_size.min(inset));
+        let style = Style::Square(Vec2(0.0, -0.8));
+        let colours = self.window.colours();
+        self.draw
+            .draw_frame(self.pass, outer, track, style, colours.frame);
+        self.draw
+            .draw_quad(self.pass, track, Style::Flat, colours.text_area);
+        track
+    }
+
+    /// Draw a slider grip of length `len` at offset `pos`
+    ///
+    /// This is drawn as for a scrollbar handle.
+    fn slider_grip(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, hl: HighlightState) {
+        let pos0 = Vec2::from(rect.pos + self.offset);
+        let size = Vec2::from(rect.size);
+        let colours = self.window.colours();
+
+        let mut quad = Quad(pos0, pos0 + size);
+        let half_width = if !dir {
+            (quad.0).0 += pos as f32;
+            (quad.1).0 = (quad.0).0 + len as f32;
+            (0.5 * size.1).floor()
+        } else {
+            (quad.0).1 += pos as f32;
+            (quad.1).1 = (quad.0).1 + len as f32;
+            (0.5 * size.0).floor()
+        };
+        let outer = quad;
+        quad.shrink(half_width);
+        let style = Style::Round(Vec2(0.0, 0.6));
+        let col = colours
+            .nav_colour(hl)
+            .map(|col| self.window.theme.focus.colour.unwrap_or(col))
+            .filter(|_| !hl.depress)
+            .or_else(|| colours.button_colour(hl, true))
+            .unwrap();
+        self.draw.draw_frame(self.pass, outer, quad, style, col);
+        self.draw.draw_quad(self.pass, quad, Style::Flat, col);
+    }
+
+    fn text_colour(&self, props: &TextProperties) -> Colour {
+        match props.class {
+            TextClass::Label => self.window.colours().label_text,
+            TextClass::Button => self.window.colours().button_text,
+            TextClass::Edit | TextClass::Code => self.window.colours().text,
+        }
+    }
+
+    // Split text into parts, coloured by span
+    fn span_parts<'b>(
+        &self,
+        text: &'b str,
+        col: Colour,
+        spans: &[(Range<usize>, SpanClass)],
+    ) -> Vec<(&'b str, Colour)> {
+        let colours = self.window.colours();
+        let mut parts = Vec::with_capacity(2 * spans.len() + 3);
+        let mut pos = 0;
+        for (range, class) in spans {
+            let (start, end) = (range.start, range.end);
+            if start < pos || end < start || text.get(start..end).is_none() {
+                continue;
+            }
+            let span_col = colours.span_colour(*class);
+            parts.push((&text[pos..start], col));
+            parts.push((&text[start..end], span_col));
+            pos = end;
+        }
+        parts.push((&text[pos..], col));
+        parts
+    }
+
+    /// Draw text made of `parts`, each with its own colour
+    fn text_parts(&mut self, rect: Rect, parts: &[(&str, Colour)], props: TextProperties) {
+        let scale = Scale::uniform(self.window.font_scale);
+        let text = parts
+            .iter()
+            .map(|(text, col)| SectionText {
+                text,
+                scale,
+                color: (*col).into(),
+                font_id: class_font(props.class),
+            })
+            .collect();
+        self.text_sections(rect, text, props);
+    }
+
+    // Draw a text-insertion caret before byte `index` of `text`
+    fn caret(&mut self, rect: Rect, text: &str, props: &TextProperties, index: usize, col: Colour) {
+        let stops = caret_stops(self.draw, self.window, rect + self.offset, text, props);
+        let caret = caret_rect(&stops, index);
+        let pos = Vec2::from(caret.pos);
+        let size = Vec2(self.window.line_width, caret.size.1 as f32);
+        self.draw
+            .draw_quad(self.pass, Quad(pos, pos + size), Style::Flat, col);
+    }
+
+    fn text_sections(&mut self, rect: Rect, text: Vec<SectionText>, props: TextProperties) {
+        let (text_pos, bounds, layout) =
+            text_layout(rect + self.offset, self.window.margin, &props);
+        self.draw.draw_text(
+            self.pass,
+            VariedSection {
+                screen_position: text_pos.into(),
+                bounds: bounds.into(),
+                layout,
+                text,
+                ..VariedSection::default()
+            },
+        );
+    }
+
+    // Draw a single line of text in a frame, placed as for a tooltip
+    fn floating_label(&mut self, pos: Coord, text: &str, frame: Colour) {
+        let colours = self.window.colours();
+        let font_scale = self.window.font_scale;
+        let margin = self.window.margin;
+        let section = |pos: Vec2, col: Colour| Section {
+            text,
+            screen_position: pos.into(),
+            color: col.into(),
+            scale: Scale::uniform(font_scale),
+            layout: Layout::default_single_line(),
+            ..Section::default()
+        };
+
+        // Frame and padding are each one margin wide
+        let width = self
+            .draw
+            .glyph_bounds(section(Vec2::splat(0.0), colours.text))
+            .map(|(min, max)| (max - min).0)
+            .unwrap_or(0.0);
+        let size = Vec2(width, font_scale) + 4.0 * margin;
+
+        // Place below the cursor, or above where there is insufficient space
+        let window = self.draw.window_rect();
+        let (win_pos, win_size) = (Vec2::from(window.pos), Vec2::from(window.size));
+        let cursor = Vec2::from(pos + self.offset);
+        let mut p = cursor + Vec2(0.0, font_scale);
+        if p.1 + size.1 > win_pos.1 + win_size.1 {
+            p.1 = cursor.1 - size.1;
+        }
+        p.0 = p.0.min(win_pos.0 + win_size.0 - size.0);
+        let p = Vec2(p.0.max(win_pos.0).floor(), p.1.max(win_pos.1).floor());
+
+        let rect = Rect {
+            pos: Coord(p.0 as i32, p.1 as i32),
+            size: Size(size.0.ceil() as u32, size.1.ceil() as u32),
+        };
+        let pass = match rect.intersection(&window) {
+            Some(rect) => self.draw.add_overlay_region(rect),
+            None => return,
+        };
+        let outer = Quad(p, p + size);
+        let mut inner = outer;
+        inner.shrink(margin);
+        self.draw.draw_frame(pass, outer, inner, Style::Flat, frame);
+        self.draw
+            .draw_quad(pass, inner, Style::Flat, colours.text_area);
+        self.draw
+            .draw_text(pass, section(p + 2.0 * margin, colours.text));
     }
 }
 
@@ -278,21 +1170,82 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         offset: Coord,
         f: &mut dyn FnMut(&mut dyn theme::DrawHandle),
     ) {
-        let rect = rect + self.offset;
-        let pass = self.draw.add_clip_region(rect);
+        // Nested regions are clipped to their parent; fully hidden regions
+        // need not be drawn at all.
+        let (pass, rect) = match self.add_pass(rect + self.offset) {
+            Some(result) => result,
+            None => return,
+        };
         let mut handle = DrawHandle {
             draw: self.draw,
             window: self.window,
             rect,
             offset: self.offset - offset,
+            transform: self.transform,
             pass,
+            overlay: self.overlay,
+        };
+        f(&mut handle);
+    }
+
+    fn zoom_region(
+        &mut self,
+        rect: Rect,
+        offset: Coord,
+        scale: f32,
+        f: &mut dyn FnMut(&mut dyn theme::DrawHandle),
+    ) {
+        let rect = rect + self.offset;
+        let (pass, clip) = match self.add_pass(rect) {
+            Some(result) => result,
+            None => return,
+        };
+        // Scale about rect.pos, then translate by -offset, in pass coordinates
+        let pos = Vec2::from(rect.pos);
+        let zoom = Transform::new(scale, pos * (1.0 - scale) - Vec2::from(offset));
+        let transform = zoom.then(self.transform);
+        self.draw.set_transform(pass, transform);
+        let mut handle = DrawHandle {
+            draw: self.draw,
+            window: self.window,
+            rect: clip,
+            offset: self.offset,
+            transform,
+            pass,
+            overlay: self.overlay,
+        };
+        f(&mut handle);
+    }
+
+    fn overlay(&mut self, rect: Rect, f: &mut dyn FnMut(&mut dyn theme::DrawHandle)) {
+        let rect = self.transform.apply_rect(rect + self.offset);
+        let rect = match rect.intersection(&self.draw.window_rect()) {
+            Some(rect) => rect,
+            None => return,
+        };
+        let pass = self.draw.add_overlay_region(rect);
+        if !self.transform.is_identity() {
+            self.draw.set_transform(pass, self.transform);
+        }
+        let mut handle = DrawHandle {
+            draw: self.draw,
+            window: self.window,
+            rect,
+            offset: self.offset,
+            transform: self.transform,
+            pass,
+            overlay: true,
         };
         f(&mut handle);
     }
 
     fn target_rect(&self) -> Rect {
         // Translate to local coordinates
-        self.rect - self.offset
+        self.transform.inverse().apply_rect(self.rect) - self.offset
+    }
+
+    fn draw_device(&mut self) -> (usize, Coord, &mut dyn Draw) {
+        (self.pass, self.offset, self.draw)
     }
 
     fn outer_frame(&mut self, rect: Rect) {
@@ -302,51 +1255,200 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         let outer = quad;
         quad.shrink(self.window.frame_size);
         let style = Style::Round(Vec2(0.6, -0.6));
-        self.draw.draw_frame(self.pass, outer, quad, style, FRAME);
+        self.draw
+            .draw_frame(self.pass, outer, quad, style, self.window.colours().frame);
     }
 
-    fn text(&mut self, rect: Rect, text: &str, props: TextProperties) {
+    fn group_frame(&mut self, rect: Rect, title: &str, title_width: u32) {
+        let f = self.window.frame_size;
+        let title_height = self.window.font_scale + 2.0 * self.window.margin;
         let pos = Vec2::from(rect.pos + self.offset);
         let size = Vec2::from(rect.size);
-        let quad = Quad(pos, pos + size);
-        let bounds = size - 2.0 * self.window.margin;
 
-        let col = match props.class {
-            TextClass::Label => LABEL_TEXT,
-            TextClass::Button => BUTTON_TEXT,
-            TextClass::Edit => TEXT,
-        };
+        // The top edge is centred on the title
+        let top = pos.1 + (0.5 * (title_height - f)).max(0.0).floor();
+        let (x0, x1, y1) = (pos.0, pos.0 + size.0, pos.1 + size.1);
+        let indent = 2.0 * f;
+        let gap0 = (x0 + indent).min(x1 - f);
+        let gap1 = (gap0 + title_width as f32).min(x1 - f);
+        let col = self.window.colours().frame;
+        let edges = [
+            Quad(Vec2(x0, top), Vec2(x0 + f, y1)),
+            Quad(Vec2(x1 - f, top), Vec2(x1, y1)),
+            Quad(Vec2(x0 + f, y1 - f), Vec2(x1 - f, y1)),
+            Quad(Vec2(x0 + f, top), Vec2(gap0, top + f)),
+            Quad(Vec2(gap1, top), Vec2(x1 - f, top + f)),
+        ];
+        for quad in edges.iter() {
+            if (quad.1).0 > (quad.0).0 && (quad.1).1 > (quad.0).1 {
+                self.draw.draw_quad(self.pass, *quad, Style::Flat, col);
+            }
+        }
 
-        // TODO: support justified alignment
-        let (h_align, h_offset) = match props.horiz {
-            Align::Begin | Align::Justify => (HorizontalAlign::Left, 0.0),
-            Align::Centre => (HorizontalAlign::Center, 0.5 * bounds.0),
-            Align::End => (HorizontalAlign::Right, bounds.0),
+        let title_rect = Rect {
+            pos: rect.pos + Coord(indent as i32, 0),
+            size: Size((gap1 - gap0) as u32, title_height as u32),
         };
-        let (v_align, v_offset) = match props.vert {
-            Align::Begin | Align::Justify => (VerticalAlign::Top, 0.0),
-            Align::Centre => (VerticalAlign::Center, 0.5 * bounds.1),
-            Align::End => (VerticalAlign::Bottom, bounds.1),
+        let props = TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
         };
+        self.text(title_rect, title, props);
+    }
 
-        let text_pos = quad.0 + self.window.margin + Vec2(h_offset, v_offset);
+    fn text(&mut self, rect: Rect, text: &str, props: TextProperties) {
+        let col = self.text_colour(&props);
+        self.text_parts(rect, &[(text, col)], props);
+    }
 
-        let layout = match props.multi_line {
-            true => Layout::default_wrap(),
-            false => Layout::default_single_line(),
+    fn text_runs(&mut self, rect: Rect, runs: &[(&str, TextStyle)], props: TextProperties) {
+        let col = self.text_colour(&props);
+        let text = run_sections(runs, self.window.font_scale, col);
+        self.text_sections(rect, text, props);
+    }
+
+    fn text_spans(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+    ) {
+        let col = self.text_colour(&props);
+        let parts = self.span_parts(text, col, spans);
+        self.text_parts(rect, &parts, props);
+    }
+
+    fn text_caret(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+        caret: Option<usize>,
+    ) {
+        let col = self.text_colour(&props);
+        if let Some(caret) = caret.filter(|caret| text.is_char_boundary(*caret)) {
+            self.caret(rect, text, &props, caret, col);
         }
-        .h_align(h_align)
-        .v_align(v_align);
+        let parts = self.span_parts(text, col, spans);
+        self.text_parts(rect, &parts, props);
+    }
 
-        self.draw.draw_text(Section {
+    fn text_styled(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        styles: &[(Range<usize>, TextStyle)],
+        caret: Option<usize>,
+    ) {
+        let col = self.text_colour(&props);
+        if let Some(caret) = caret.filter(|caret| text.is_char_boundary(*caret)) {
+            self.caret(rect, text, &props, caret, col);
+        }
+        let scale = Scale::uniform(self.window.font_scale);
+        let section = |text, style: &TextStyle| SectionText {
             text,
+            scale,
+            color: style.colour.unwrap_or(col).into(),
+            font_id: match props.class {
+                TextClass::Code => class_font(props.class),
+                _ => FontId(font_index(style.bold, style.italic)),
+            },
+        };
+        let plain = TextStyle::default();
+        let mut sections = Vec::with_capacity(2 * styles.len() + 1);
+        let mut pos = 0;
+        for (range, style) in styles {
+            let (start, end) = (range.start, range.end);
+            if start < pos || end < start || text.get(start..end).is_none() {
+                continue;
+            }
+            sections.push(section(&text[pos..start], &plain));
+            sections.push(section(&text[start..end], style));
+            pos = end;
+        }
+        sections.push(section(&text[pos..], &plain));
+        self.text_sections(rect, sections, props);
+    }
+
+    fn line_highlight(&mut self, rect: Rect, class: SpanClass) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let quad = Quad(pos, pos + Vec2::from(rect.size));
+        // A pale tint of the span colour over the text area
+        let colours = self.window.colours();
+        let (bg, fg) = (colours.text_area, colours.span_colour(class));
+        let mix = |a: f32, b: f32| a + 0.2 * (b - a);
+        let col = Colour::new(mix(bg.r, fg.r), mix(bg.g, fg.g), mix(bg.b, fg.b));
+        self.draw.draw_quad(self.pass, quad, Style::Flat, col);
+        if self.window.theme.high_contrast {
+            // Edge the line with the span colour itself
+            let mut inner = quad;
+            inner.shrink(self.window.focus_width);
+            self.draw
+                .draw_frame(self.pass, quad, inner, Style::Flat, fg);
+        }
+    }
+
+    fn hyperlink(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        highlights: HighlightState,
+    ) {
+        let colours = self.window.colours();
+        let col = match highlights.hover || highlights.depress {
+            true => colours.link_hover,
+            false => colours.link,
+        };
+        let rect = rect + self.offset;
+        if let Some(ring) = colours.nav_colour(highlights) {
+            let ring = self.window.theme.focus.colour.unwrap_or(ring);
+            let outer = Quad(Vec2::from(rect.pos), Vec2::from(rect.pos + rect.size));
+            let mut inner = outer;
+            inner.shrink(self.window.focus_width);
+            self.draw
+                .draw_frame(self.pass, outer, inner, Style::Flat, ring);
+        }
+
+        let (text_pos, bounds, layout) = text_layout(rect, self.window.margin, &props);
+        let section = VariedSection {
             screen_position: text_pos.into(),
-            color: col.into(),
-            scale: Scale::uniform(self.window.font_scale),
             bounds: bounds.into(),
             layout,
-            ..Section::default()
-        });
+            text: vec![SectionText {
+                text,
+                scale: Scale::uniform(self.window.font_scale),
+                color: col.into(),
+                ..SectionText::default()
+            }],
+            ..VariedSection::default()
+        };
+
+        // Underline each line, from its first to its last visible glyph,
+        // slightly below the baseline
+        let thickness = self.window.line_width.max(1.0);
+        let offset = (0.9 * self.window.font_scale).round();
+        let mut line: Option<(f32, f32, f32)> = None;
+        let mut lines = vec![];
+        for (min, max, _) in self.draw.glyph_boxes(&section) {
+            match line {
+                Some((top, _, ref mut right)) if top == min.1 => *right = max.0,
+                _ => lines.extend(line.replace((min.1, min.0, max.0))),
+            }
+        }
+        lines.extend(line);
+        for (top, left, right) in lines {
+            let y = top + offset;
+            let quad = Quad(Vec2(left, y), Vec2(right, y + thickness));
+            self.draw.draw_quad(self.pass, quad, Style::Flat, col);
+        }
+
+        self.draw.draw_text(self.pass, section);
     }
 
     fn button(&mut self, rect: Rect, highlights: HighlightState) {
@@ -354,7 +1456,11 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         let size = Vec2::from(rect.size);
         let mut quad = Quad(pos, pos + size);
 
-        let col = button_colour(highlights, true).unwrap();
+        let col = self
+            .window
+            .colours()
+            .button_colour(highlights, true)
+            .unwrap();
 
         let outer = quad;
         quad.shrink(self.window.button_frame);
@@ -362,11 +1468,7 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         self.draw.draw_frame(self.pass, outer, quad, style, col);
 
         if highlights.key_focus {
-            let outer = quad;
-            quad.shrink(self.window.margin);
-            let col = nav_colour(highlights).unwrap();
-            self.draw
-                .draw_frame(self.pass, outer, quad, Style::Flat, col);
+            self.focus_ring(&mut quad, highlights, col);
         }
 
         self.draw.draw_quad(self.pass, quad, Style::Flat, col);
@@ -380,20 +1482,21 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         let outer = quad;
         quad.shrink(self.window.frame_size);
         let style = Style::Square(Vec2(0.0, -0.8));
-        self.draw.draw_frame(self.pass, outer, quad, style, FRAME);
+        let frame = match highlights.error {
+            true => self.window.colours().error,
+            false => self.window.colours().frame,
+        };
+        self.draw.draw_frame(self.pass, outer, quad, style, frame);
 
+        let text_area = self.window.colours().text_area;
         if highlights.key_focus {
-            let outer = quad;
-            quad.shrink(self.window.margin);
-            let col = nav_colour(highlights).unwrap();
-            self.draw
-                .draw_frame(self.pass, outer, quad, Style::Flat, col);
+            self.focus_ring(&mut quad, highlights, text_area);
         }
 
-        self.draw.draw_quad(self.pass, quad, Style::Flat, TEXT_AREA);
+        self.draw.draw_quad(self.pass, quad, Style::Flat, text_area);
     }
 
-    fn checkbox(&mut self, pos: Coord, checked: bool, highlights: HighlightState) {
+    fn checkbox(&mut self, pos: Coord, state: Option<bool>, highlights: HighlightState) {
         let pos = Vec2::from(pos + self.offset);
         let size = 2.0 * (self.window.frame_size + self.window.margin) + self.window.font_scale;
         let size = Vec2::splat(size);
@@ -402,20 +1505,83 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         let outer = quad;
         quad.shrink(self.window.frame_size);
         let style = Style::Square(Vec2(0.0, -0.8));
-        self.draw.draw_frame(self.pass, outer, quad, style, FRAME);
+        self.draw
+            .draw_frame(self.pass, outer, quad, style, self.window.colours().frame);
+
+        let checked = state.unwrap_or(false);
+        let text_area = self.window.colours().text_area;
+        if state != Some(false) || highlights.any() {
+            let gap = self.focus_ring(&mut quad, highlights, text_area);
+            quad.shrink(self.window.mark_gap(gap) - gap);
+        }
 
-        if checked || highlights.any() {
-            let outer = quad;
-            quad.shrink(self.window.margin);
-            let col = nav_colour(highlights).unwrap_or(TEXT_AREA);
+        if state.is_none() {
+            // Indeterminate: draw a horizontal bar across the middle, of a
+            // third (or in high-contrast mode a half) of the height
+            let col = self
+                .window
+                .colours()
+                .button_colour(highlights, true)
+                .unwrap();
+            let parts = if self.window.theme.high_contrast {
+                4.0
+            } else {
+                3.0
+            };
+            let inset = (((quad.1).1 - (quad.0).1) / parts).floor();
+            let mut bar = quad;
+            (bar.0).1 += inset;
+            (bar.1).1 -= inset;
             self.draw
-                .draw_frame(self.pass, outer, quad, Style::Flat, col);
+                .draw_frame(self.pass, quad, bar, Style::Flat, text_area);
+            self.draw.draw_quad(self.pass, bar, Style::Flat, col);
+            return;
         }
 
-        let col = button_colour(highlights, checked).unwrap_or(TEXT_AREA);
+        let col = self
+            .window
+            .colours()
+            .button_colour(highlights, checked)
+            .unwrap_or(text_area);
         self.draw.draw_quad(self.pass, quad, Style::Flat, col);
     }
 
+    fn radiobox(&mut self, pos: Coord, checked: bool, highlights: HighlightState) {
+        let pos = Vec2::from(pos + self.offset);
+        let size = 2.0 * (self.window.frame_size + self.window.margin) + self.window.font_scale;
+        let mut quad = Quad(pos, pos + Vec2::splat(size));
+
+        // A round frame with degenerate inner quad draws a disc
+        let centre = pos + Vec2::splat(0.5 * size);
+        let centre = Quad(centre, centre);
+        let style = Style::Round(Vec2(0.0, -0.8));
+        self.draw
+            .draw_frame(self.pass, quad, centre, style, self.window.colours().frame);
+        quad.shrink(self.window.frame_size);
+
+        let window = &self.window;
+        let flat = || Style::Round(Vec2::splat(0.0));
+        let text_area = window.colours().text_area;
+        let ring = window
+            .colours()
+            .nav_colour(highlights)
+            .map(|col| window.theme.focus.colour.unwrap_or(col));
+        let mut margin = window.margin;
+        if let Some(col) = ring {
+            let width = window.focus_offset + window.focus_width;
+            self.draw.draw_frame(self.pass, quad, centre, flat(), col);
+            quad.shrink(width);
+            margin -= width;
+        }
+        self.draw
+            .draw_frame(self.pass, quad, centre, flat(), text_area);
+        quad.shrink(self.window.mark_gap(margin));
+
+        if let Some(col) = self.window.colours().button_colour(highlights, checked) {
+            self.draw.draw_frame(self.pass, quad, centre, flat(), col);
+        }
+    }
+
     fn scrollbar(
         &mut self,
         rect: Rect,
@@ -443,8 +1609,584 @@ impl<'a> theme::DrawHandle for DrawHandle<'a> {
         let outer = quad;
         quad.shrink(half_width);
         let style = Style::Round(Vec2(0.0, 0.6));
-        let col = button_colour(highlights, true).unwrap();
+        let col = self
+            .window
+            .colours()
+            .button_colour(highlights, true)
+            .unwrap();
         self.draw.draw_frame(self.pass, outer, quad, style, col);
         self.draw.draw_quad(self.pass, quad, Style::Flat, col);
     }
+
+    fn slider(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, highlights: HighlightState) {
+        self.slider_track(rect, dir);
+        self.slider_grip(rect, dir, len, pos, highlights);
+    }
+
+    fn range_slider(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        len: u32,
+        pos: (u32, u32),
+        highlights: (HighlightState, HighlightState),
+    ) {
+        let mut track = self.slider_track(rect, dir);
+        let (a, b) = ((pos.0 + len / 2) as f32, (pos.1 + len / 2) as f32);
+        let start = Vec2::from(rect.pos + self.offset);
+        if !dir {
+            (track.0).0 = start.0 + a;
+            (track.1).0 = start.0 + b;
+        } else {
+            (track.0).1 = start.1 + a;
+            (track.1).1 = start.1 + b;
+        }
+        let col = self.window.colours().button;
+        self.draw.draw_quad(self.pass, track, Style::Flat, col);
+
+        self.slider_grip(rect, dir, len, pos.0, highlights.0);
+        self.slider_grip(rect, dir, len, pos.1, highlights.1);
+    }
+
+    fn switch(&mut self, rect: Rect, position: f32, highlights: HighlightState) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let size = Vec2::from(rect.size);
+        let mut quad = Quad(pos, pos + size);
+        let colours = self.window.colours();
+
+        // The track is a pill: a round frame around a horizontal line
+        let r = 0.5 * size.1;
+        let line = |quad: &Quad| {
+            let y = 0.5 * ((quad.0).1 + (quad.1).1);
+            Quad(Vec2((quad.0).0 + r, y), Vec2((quad.1).0 - r, y))
+        };
+        let style = Style::Round(Vec2(0.0, -0.8));
+        self.draw
+            .draw_frame(self.pass, quad, line(&quad), style, colours.frame);
+        quad.shrink(self.window.frame_size);
+
+        let flat = || Style::Round(Vec2::splat(0.0));
+        let ring = colours
+            .nav_colour(highlights)
+            .map(|col| self.window.theme.focus.colour.unwrap_or(col));
+        if let Some(col) = ring {
+            let width = self.window.focus_offset + self.window.focus_width;
+            self.draw
+                .draw_frame(self.pass, quad, line(&quad), flat(), col);
+            quad.shrink(width);
+        }
+        let track = match position >= 0.5 {
+            true => colours.button,
+            false => colours.text_area,
+        };
+        self.draw
+            .draw_frame(self.pass, quad, line(&quad), flat(), track);
+
+        // The knob is a disc, raised as for a button
+        let d = size.1;
+        let x = (pos.0 + position.clamp(0.0, 1.0) * (size.0 - d)).round();
+        let knob = Quad(Vec2(x, pos.1), Vec2(x + d, pos.1 + d));
+        let centre = Vec2(x + 0.5 * d, pos.1 + 0.5 * d);
+        let col = colours
+            .button_colour(highlights, true)
+            .unwrap_or(colours.button);
+        let col = match position >= 0.5 {
+            true => colours.text_area,
+            false => col,
+        };
+        let style = Style::Round(Vec2(0.0, 0.6));
+        self.draw
+            .draw_frame(self.pass, knob, Quad(centre, centre), style, col);
+    }
+
+    fn level_meter(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        value: f32,
+        peak: Option<f32>,
+        zones: (f32, f32),
+    ) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let mut outer = Quad(pos, pos + Vec2::from(rect.size));
+        let colours = self.window.colours();
+        let style = Style::Square(Vec2(0.0, -0.8));
+        let mut inner = outer;
+        inner.shrink(self.window.frame_size);
+        self.draw
+            .draw_frame(self.pass, outer, inner, style, colours.frame);
+        self.draw
+            .draw_quad(self.pass, inner, Style::Flat, colours.text_area);
+        outer = inner;
+
+        // Returns the portion of `outer` between fractions `a` and `b` of
+        // the length; vertical meters fill from the bottom.
+        let len = match dir {
+            false => (outer.1).0 - (outer.0).0,
+            true => (outer.1).1 - (outer.0).1,
+        };
+        let part = |a: f32, b: f32| {
+            let (a, b) = (a.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+            let mut quad = outer;
+            if dir {
+                (quad.0).1 = (outer.1).1 - b * len;
+                (quad.1).1 = (outer.1).1 - a * len;
+            } else {
+                (quad.0).0 = (outer.0).0 + a * len;
+                (quad.1).0 = (outer.0).0 + b * len;
+            }
+            quad.snap()
+        };
+
+        let zones = [
+            (0.0, zones.0, colours.level_normal),
+            (zones.0, zones.1, colours.level_warn),
+            (zones.1, 1.0, colours.level_danger),
+        ];
+        for (a, b, col) in zones.iter() {
+            if value > *a && b > a {
+                let quad = part(*a, value.min(*b));
+                self.draw.draw_quad(self.pass, quad, Style::Flat, *col);
+            }
+        }
+
+        if let Some(peak) = peak {
+            let col = zones
+                .iter()
+                .find(|(_, b, _)| peak <= *b)
+                .map(|z| z.2)
+                .unwrap_or(colours.level_danger);
+            let width = self.window.frame_size / len.max(1.0);
+            let quad = part(peak - width, peak);
+            self.draw.draw_quad(self.pass, quad, Style::Flat, col);
+        }
+    }
+
+    fn dial(&mut self, rect: Rect, value: f32, ticks: u32, highlights: HighlightState) {
+        let colours = self.window.colours();
+        let d = rect.size.0.min(rect.size.1) as f32;
+        let centre = Vec2::from(rect.pos + self.offset) + Vec2::from(rect.size) * 0.5;
+        let square = |r: f32| Quad(centre - Vec2::splat(r), centre + Vec2::splat(r));
+        let point = Quad(centre, centre);
+        let flat = || Style::Round(Vec2::splat(0.0));
+        // Angle from the top, clockwise, of a fraction of the sweep
+        let angle = |frac: f32| (frac.clamp(0.0, 1.0) - 0.5) * 1.5 * f32::consts::PI;
+
+        // Tick marks are small discs around the rim
+        let tick_r = (0.04 * d).max(1.0);
+        let rim = 0.5 * d - tick_r;
+        if ticks >= 2 {
+            for i in 0..ticks {
+                let a = angle(i as f32 / (ticks - 1) as f32);
+                let pos = centre + Vec2(a.sin(), -a.cos()) * rim;
+                let quad = Quad(pos - Vec2::splat(tick_r), pos + Vec2::splat(tick_r));
+                let pos = Quad(pos, pos);
+                self.draw
+                    .draw_frame(self.pass, quad, pos, flat(), colours.frame);
+            }
+        }
+
+        // The focus ring sits between ticks and knob
+        let mut r = rim - 2.0 * tick_r;
+        let ring = colours
+            .nav_colour(highlights)
+            .map(|col| self.window.theme.focus.colour.unwrap_or(col));
+        if let Some(col) = ring {
+            let width = self.window.focus_width;
+            self.draw
+                .draw_frame(self.pass, square(r), square(r - width), flat(), col);
+            r -= width + self.window.focus_offset;
+        }
+
+        // The knob is a raised disc with a pointer
+        let col = colours
+            .button_colour(highlights, true)
+            .unwrap_or(colours.button);
+        let style = Style::Round(Vec2(0.0, 0.6));
+        self.draw
+            .draw_frame(self.pass, square(r), point, style, col);
+
+        let a = angle(value);
+        let pos = centre + Vec2(a.sin(), -a.cos()) * (0.65 * r);
+        let pr = (0.12 * r).max(1.0);
+        let quad = Quad(pos - Vec2::splat(pr), pos + Vec2::splat(pr));
+        let style = Style::Round(Vec2(0.0, -0.6));
+        self.draw
+            .draw_frame(self.pass, quad, Quad(pos, pos), style, colours.text_area);
+    }
+
+    fn rating(
+        &mut self,
+        rect: Rect,
+        count: u32,
+        value: f32,
+        preview: Option<f32>,
+        highlights: HighlightState,
+    ) {
+        let colours = *self.window.colours();
+        let ring = colours
+            .nav_colour(highlights)
+            .map(|col| self.window.theme.focus.colour.unwrap_or(col));
+        let pos0 = Vec2::from(rect.pos + self.offset);
+        if let Some(col) = ring {
+            let outer = Quad(pos0, pos0 + Vec2::from(rect.size));
+            let mut inner = outer;
+            inner.shrink(self.window.focus_width);
+            self.draw
+                .draw_frame(self.pass, outer, inner, Style::Flat, col);
+        }
+
+        let (value, fill) = match preview {
+            Some(v) => (v, colours.button_highlighted),
+            None => (value, colours.button),
+        };
+        let size = rect.size.0 as f32 / count.max(1) as f32;
+        let flat = || Style::Round(Vec2::splat(0.0));
+        for i in 0..count {
+            let pos = pos0 + Vec2((i as f32 * size).round(), 0.0);
+            let mut quad = Quad(pos, pos + Vec2::splat(size));
+            quad.shrink(self.window.margin);
+            let centre = pos + Vec2::splat(0.5 * size);
+            let centre = Quad(centre, centre);
+
+            // Each symbol is a disc, drawn as for a radio box
+            let style = Style::Round(Vec2(0.0, -0.8));
+            self.draw
+                .draw_frame(self.pass, quad, centre, style, colours.frame);
+            quad.shrink(self.window.frame_size);
+            self.draw
+                .draw_frame(self.pass, quad, centre, flat(), colours.text_area);
+
+            let frac = (value - i as f32).clamp(0.0, 1.0);
+            if frac >= 1.0 {
+                self.draw.draw_frame(self.pass, quad, centre, flat(), fill);
+            } else if frac > 0.0 {
+                // Partial fill: draw the whole disc in a region clipped to
+                // the filled fraction.
+                let x = ((quad.0).0 + frac * ((quad.1).0 - (quad.0).0)).round() as i32;
+                let clip = Rect {
+                    pos: Coord((quad.0).0 as i32, pos.1 as i32),
+                    size: Size((x - (quad.0).0 as i32).max(0) as u32, size.ceil() as u32),
+                };
+                if let Some((pass, _)) = self.add_pass(clip) {
+                    self.draw.draw_frame(pass, quad, centre, flat(), fill);
+                }
+            }
+        }
+    }
+
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let outer = Quad(pos, pos + Vec2::from(rect.size));
+        let mut inner = outer;
+        inner.shrink(self.window.frame_size);
+        let style = Style::Square(Vec2(0.0, -0.8));
+        self.draw
+            .draw_frame(self.pass, outer, inner, style, self.window.colours().frame);
+        self.draw.draw_quad(self.pass, inner, Style::Flat, colour);
+    }
+
+    fn splitter(&mut self, rect: Rect, dir: bool, highlights: HighlightState) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let size = Vec2::from(rect.size);
+        let outer = Quad(pos, pos + size);
+
+        // A thin line along the handle, with a grip at its centre
+        let mut line = outer;
+        let mut grip = outer;
+        let (thickness, len) = match dir {
+            false => (size.0, size.1),
+            true => (size.1, size.0),
+        };
+        let line_start = (0.5 * thickness).floor();
+        let grip_len = (4.0 * thickness).min(len);
+        let grip_start = (0.5 * (len - grip_len)).floor();
+        if !dir {
+            (line.0).0 += line_start;
+            (line.1).0 = (line.0).0 + self.window.line_width;
+            (grip.0).1 += grip_start;
+            (grip.1).1 = (grip.0).1 + grip_len;
+        } else {
+            (line.0).1 += line_start;
+            (line.1).1 = (line.0).1 + self.window.line_width;
+            (grip.0).0 += grip_start;
+            (grip.1).0 = (grip.0).0 + grip_len;
+        }
+        let colours = self.window.colours();
+        self.draw
+            .draw_quad(self.pass, line, Style::Flat, colours.frame);
+
+        let mut inner = grip;
+        inner.shrink((0.5 * thickness).floor());
+        let style = Style::Round(Vec2(0.0, 0.6));
+        let col = colours.button_colour(highlights, true).unwrap();
+        self.draw.draw_frame(self.pass, grip, inner, style, col);
+        self.draw.draw_quad(self.pass, inner, Style::Flat, col);
+    }
+
+    fn separator(&mut self, rect: Rect, dir: bool) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let size = Vec2::from(rect.size);
+        let mut line = Quad(pos, pos + size);
+        let thickness = (self.window.line_width).max(1.0);
+        if dir {
+            (line.0).0 += (0.5 * (size.0 - thickness)).floor();
+            (line.1).0 = (line.0).0 + thickness;
+        } else {
+            (line.0).1 += (0.5 * (size.1 - thickness)).floor();
+            (line.1).1 = (line.0).1 + thickness;
+        }
+        let col = self.window.colours().frame;
+        self.draw.draw_quad(self.pass, line, Style::Flat, col);
+    }
+
+    fn image(&mut self, rect: Rect, image: &RgbaImage) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let quad = Quad(pos, pos + Vec2::from(rect.size));
+        self.draw.draw_image(self.pass, quad, image);
+    }
+
+    fn dim(&mut self, rect: Rect) {
+        let rect = match (rect + self.offset).intersection(&self.draw.window_rect()) {
+            Some(rect) => rect,
+            None => return,
+        };
+        let pass = self.draw.add_overlay_region(rect);
+        let pos = Vec2::from(rect.pos);
+        let quad = Quad(pos, pos + Vec2::from(rect.size));
+        let col = self.window.colours().dim;
+        self.draw.draw_quad(pass, quad, Style::Flat, col);
+    }
+
+    fn spinner(&mut self, rect: Rect, phase: f32, style: SpinnerStyle) {
+        let pos = Vec2::from(rect.pos + self.offset);
+        let rect_size = Vec2::from(rect.size);
+        let max_size = 4.0 * self.window.font_scale;
+        let size = rect_size.0.min(rect_size.1).min(max_size);
+        let centre = pos + rect_size * 0.5;
+        let base = self.window.colours().button;
+
+        if style == SpinnerStyle::Arc {
+            // An arc of three quarters of a turn, led by the phase angle and
+            // fading towards its tail
+            const SEGMENTS: usize = 24;
+            const LEN: f32 = 0.75;
+            let (inner, outer) = (0.38 * size, 0.5 * size);
+            let point = |frac: f32| {
+                let angle = 2.0 * f32::consts::PI * (phase - frac * LEN);
+                Vec2(angle.sin(), -angle.cos())
+            };
+            for i in 0..SEGMENTS {
+                let (f0, f1) = (i as f32 / SEGMENTS as f32, (i + 1) as f32 / SEGMENTS as f32);
+                let (d0, d1) = (point(f0), point(f1));
+                let col = Colour {
+                    a: 1.0 - 0.85 * f0,
+                    ..base
+                };
+                let a = centre + d0 * inner;
+                let b = centre + d0 * outer;
+                let c = centre + d1 * outer;
+                let d = centre + d1 * inner;
+                self.draw.draw_triangle(self.pass, a, b, c, col);
+                self.draw.draw_triangle(self.pass, a, c, d, col);
+            }
+            return;
+        }
+
+        // A ring of spokes; the leading spoke (at the phase angle) is opaque
+        // and those trailing it fade
+        const SPOKES: usize = 12;
+        let (inner, outer, half_width) = (0.25 * size, 0.5 * size, 0.05 * size);
+        for i in 0..SPOKES {
+            let frac = i as f32 / SPOKES as f32;
+            let angle = 2.0 * f32::consts::PI * frac;
+            let dir = Vec2(angle.sin(), -angle.cos());
+            let perp = Vec2(-dir.1, dir.0) * half_width;
+            let age = (phase - frac).rem_euclid(1.0);
+            let col = Colour {
+                a: 1.0 - 0.85 * age,
+                ..base
+            };
+            let a = centre + dir * inner + perp;
+            let b = centre + dir * outer + perp;
+            let c = centre + dir * outer - perp;
+            let d = centre + dir * inner - perp;
+            self.draw.draw_triangle(self.pass, a, b, c, col);
+            self.draw.draw_triangle(self.pass, a, c, d, col);
+        }
+    }
+
+    fn tooltip(&mut self, pos: Coord, text: &str) {
+        let frame = self.window.colours().frame;
+        self.floating_label(pos, text, frame);
+    }
+
+    fn drag_ghost(&mut self, pos: Coord, label: &str, accepted: bool) {
+        let colours = self.window.colours();
+        let frame = match accepted {
+            false => colours.frame,
+            true => colours.nav_focus,
+        };
+        self.floating_label(pos, label, frame);
+    }
+}
+
+// Sections of text for each run, given the standard font scale and colour
+// Text position, bounds and layout of text drawn within `rect`
+fn text_layout(
+    rect: Rect,
+    margin: f32,
+    props: &TextProperties,
+) -> (Vec2, Vec2, Layout<BuiltInLineBreaker>) {
+    let pos = Vec2::from(rect.pos);
+    let bounds = Vec2::from(rect.size) - 2.0 * margin;
+
+    // TODO: support justified alignment
+    let (h_align, h_offset) = match props.horiz {
+        Align::Begin | Align::Justify => (HorizontalAlign::Left, 0.0),
+        Align::Centre => (HorizontalAlign::Center, 0.5 * bounds.0),
+        Align::End => (HorizontalAlign::Right, bounds.0),
+    };
+    let (v_align, v_offset) = match props.vert {
+        Align::Begin | Align::Justify => (VerticalAlign::Top, 0.0),
+        Align::Centre => (VerticalAlign::Center, 0.5 * bounds.1),
+        Align::End => (VerticalAlign::Bottom, bounds.1),
+    };
+
+    let text_pos = (pos + margin + Vec2(h_offset, v_offset)).round();
+
+    let layout = match props.multi_line {
+        true => Layout::default_wrap(),
+        false => Layout::default_single_line(),
+    }
+    .h_align(h_align)
+    .v_align(v_align);
+
+    (text_pos, bounds, layout)
+}
+
+// A position at which a caret may be drawn within laid-out text
+#[derive(Clone, Copy, Debug)]
+struct CaretStop {
+    index: usize,
+    x: f32,
+    top: f32,
+    bottom: f32,
+}
+
+// Find the caret position before each char of `text` and at its end, when
+// drawn (as Edit text) within `rect`
+fn caret_stops(
+    draw: &mut DrawPipe,
+    window: &SampleWindow,
+    rect: Rect,
+    text: &str,
+    props: &TextProperties,
+) -> Vec<CaretStop> {
+    let (text_pos, bounds, layout) = text_layout(rect, window.margin, props);
+    let scale = Scale::uniform(window.font_scale);
+    let font_id = class_font(props.class);
+
+    // One section per char, with the byte index encoded as colour, lets us
+    // map glyphs back to the text (invisible glyphs are omitted).
+    let sections = text
+        .char_indices()
+        .map(|(i, c)| SectionText {
+            text: &text[i..i + c.len_utf8()],
+            scale,
+            color: [i as f32, 0.0, 0.0, 0.0],
+            font_id,
+        })
+        .collect();
+    let boxes = draw.glyph_boxes(&VariedSection {
+        screen_position: text_pos.into(),
+        bounds: bounds.into(),
+        layout,
+        text: sections,
+        ..VariedSection::default()
+    });
+
+    let height = match boxes.first() {
+        Some((min, max, _)) => max.1 - min.1,
+        None => window.font_scale,
+    };
+    // The first line may be empty, thus we find its top from the first glyph
+    let top = match boxes.first() {
+        Some((min, _, col)) => {
+            let lines = text[..col[0] as usize].matches('\n').count();
+            min.1 - height * lines as f32
+        }
+        None => text_pos.1,
+    };
+    let mut stop = CaretStop {
+        index: 0,
+        x: text_pos.0,
+        top,
+        bottom: top + height,
+    };
+
+    let mut stops = Vec::with_capacity(text.len() + 1);
+    let mut boxes = boxes.into_iter().peekable();
+    for (i, c) in text.char_indices() {
+        stop.index = i;
+        match boxes.peek() {
+            Some((min, max, col)) if col[0] as usize == i => {
+                // The glyph may start a new (wrapped) line
+                stop.x = min.0;
+                stop.top = min.1;
+                stop.bottom = max.1;
+                stops.push(stop);
+                stop.x = max.0;
+                boxes.next();
+            }
+            _ => {
+                stops.push(stop);
+                if c == '\n' {
+                    stop.x = text_pos.0;
+                    stop.top = stop.bottom;
+                    stop.bottom += height;
+                } else if !c.is_control() {
+                    stop.x += draw.char_advance(c, font_id, scale);
+                }
+            }
+        }
+    }
+    stop.index = text.len();
+    stops.push(stop);
+    stops
+}
+
+// The caret rect at `index`, of zero width
+fn caret_rect(stops: &[CaretStop], index: usize) -> Rect {
+    let stop = match stops.iter().rev().find(|stop| stop.index <= index) {
+        Some(stop) => *stop,
+        None => return Rect::default(),
+    };
+    Rect {
+        pos: Coord(stop.x.round() as i32, stop.top.round() as i32),
+        size: Size(0, (stop.bottom - stop.top).round() as u32),
+    }
+}
+
+// The font used for the regular face of text of the given class
+fn class_font(class: TextClass) -> FontId {
+    match class {
+        TextClass::Code => FontId(mono_index()),
+        _ => FontId(font_index(false, false)),
+    }
+}
+
+fn run_sections<'b>(
+    runs: &[(&'b str, TextStyle)],
+    font_scale: f32,
+    col: Colour,
+) -> Vec<SectionText<'b>> {
+    runs.iter()
+        .map(|(text, style)| SectionText {
+            text,
+            scale: Scale::uniform(font_scale * style.scale.unwrap_or(1.0)),
+            color: style.colour.unwrap_or(col).into(),
+            font_id: FontId(font_index(style.bold, style.italic)),
+        })
+        .collect()
 }
@@ -6,21 +6,49 @@
 //! `Window` and `WindowList` types
 
 use log::{debug, info, trace, warn};
+use std::cell::RefCell;
 use std::mem::replace;
+use std::process::Command;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 #[cfg(feature = "clipboard")]
 use clipboard::{ClipboardContext, ClipboardProvider};
 
+use kas::draw::{Colour, Draw, Quad, Style, Vec2};
 use kas::event::Callback;
 use kas::geom::{Coord, Rect, Size};
-use kas::theme::SizeHandle;
+use kas::theme::{DrawHandle, SizeHandle, SpinnerStyle};
 use kas::{event, theme, TkAction, WidgetId};
 use winit::dpi::LogicalSize;
 use winit::event::WindowEvent;
 
 use crate::draw::DrawPipe;
-use crate::SharedState;
+use crate::hud::Hud;
+use crate::{inspector, SharedState};
+
+/// Format of window surfaces
+pub(crate) const TEX_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+
+/// Time without resize events after which a deferred layout is performed
+const RESIZE_DELAY: Duration = Duration::from_millis(150);
+
+/// Time for which a window is busy before the activity indicator is shown
+const BUSY_GRACE: Duration = Duration::from_millis(400);
+/// Interval at which to check whether a busy state has ended
+const BUSY_POLL: Duration = Duration::from_millis(50);
+/// Period of one cycle of the activity indicator
+const BUSY_PERIOD: Duration = Duration::from_millis(1000);
+
+/// Identifiers and titles of open windows, shared by all windows
+pub(crate) type WindowList = Rc<RefCell<Vec<(kas::WindowId, String)>>>;
+
+/// A request concerning another window, made via [`kas::TkWindow`]
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum WindowRequest {
+    Activate(kas::WindowId),
+    Close(kas::WindowId),
+}
 
 /// Per-window data
 pub(crate) struct Window<TW> {
@@ -30,8 +58,27 @@ pub(crate) struct Window<TW> {
     surface: wgpu::Surface,
     sc_desc: wgpu::SwapChainDescriptor,
     swap_chain: wgpu::SwapChain,
-    timeouts: Vec<(usize, Instant, Option<Duration>)>,
+    /// Size for which widgets were last laid out
+    layout_size: Size,
+    /// While showing a resize snapshot, the time at which to update layout
+    resize_time: Option<Instant>,
+    /// Callback index, time, repeat period and whether cosmetic
+    timeouts: Vec<(usize, Instant, Option<Duration>, bool)>,
+    /// Cosmetic callbacks missed while idle
+    missed_callbacks: Vec<usize>,
     tk_window: TkWindow<TW>,
+    /// If true, the hovered widget is highlighted (see [`crate::inspector`])
+    pub(crate) inspected: bool,
+    /// Performance HUD (see [`crate::hud`])
+    pub(crate) hud: Hud,
+    /// If this window is window-modal, the window which opened it
+    pub(crate) modal_parent: Option<winit::window::WindowId>,
+    /// True while user input is blocked by a modal window
+    pub(crate) blocked: bool,
+    /// The mouse cursor icon last set
+    cursor_icon: event::CursorIcon,
+    /// True while the cursor is hidden and grabbed
+    cursor_grab: bool,
 }
 
 // Public functions, for use by the toolkit
@@ -50,15 +97,21 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
 
         let sc_desc = wgpu::SwapChainDescriptor {
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            format: TEX_FORMAT,
             width: size.0,
             height: size.1,
             present_mode: wgpu::PresentMode::Vsync,
         };
         let swap_chain = shared.device.create_swap_chain(&surface, &sc_desc);
 
-        let mut tk_window = TkWindow::new(shared, sc_desc.format, size, dpi_factor);
+        let scaled_dpi_factor = dpi_factor * shared.scale_factor;
+        let mut tk_window = TkWindow::new(shared, size, scaled_dpi_factor);
         tk_window.ev_mgr.configure(widget.as_widget_mut());
+        if let Some(size) = widget.initial_size(&mut tk_window) {
+            // This results in a resize event, thus a new layout
+            window.set_inner_size(LogicalSize::from_physical(size, dpi_factor));
+        }
+        (shared.window_list.borrow_mut()).push((tk_window.id, widget.title().to_string()));
 
         widget.resize(&mut tk_window, size);
 
@@ -68,11 +121,53 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
             surface,
             sc_desc,
             swap_chain,
+            layout_size: size,
+            resize_time: None,
             timeouts: vec![],
+            missed_callbacks: vec![],
             tk_window,
+            inspected: false,
+            hud: Hud::default(),
+            modal_parent: None,
+            blocked: false,
+            cursor_icon: event::CursorIcon::Default,
+            cursor_grab: false,
         }
     }
 
+    /// True if the window has input focus
+    #[cfg(feature = "gamepad")]
+    pub fn is_focused(&self) -> bool {
+        self.tk_window.ev_mgr.window_focused()
+    }
+
+    /// True while the cursor is grabbed (see [`event::Manager::cursor_grab`])
+    pub fn cursor_grab(&self) -> bool {
+        self.cursor_grab
+    }
+
+    /// The modality of the window's widget
+    pub fn modality(&self) -> kas::Modality {
+        self.widget.modality()
+    }
+
+    /// Identifier assigned to this window
+    pub fn id(&self) -> kas::WindowId {
+        self.tk_window.id
+    }
+
+    /// Take requests concerning other windows made while handling events
+    pub fn take_window_requests(&mut self) -> Vec<WindowRequest> {
+        replace(&mut self.tk_window.window_requests, vec![])
+    }
+
+    /// Notify the widget that the window is closing
+    pub fn close(&mut self) {
+        self.widget.on_close();
+        let id = self.tk_window.id;
+        (self.tk_window.window_list.borrow_mut()).retain(|item| item.0 != id);
+    }
+
     /// Called by the `Toolkit` when the event loop starts to initialise
     /// windows. Optionally returns a callback time.
     pub fn init(&mut self) -> Option<Instant> {
@@ -83,9 +178,11 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
                 Callback::Start => {
                     self.widget.trigger_callback(i, &mut self.tk_window);
                 }
-                Callback::Repeat(dur) => {
+                Callback::Repeat(dur) | Callback::Cosmetic(dur) => {
                     self.widget.trigger_callback(i, &mut self.tk_window);
-                    self.timeouts.push((i, Instant::now() + dur, Some(dur)));
+                    let cosmetic = condition == Callback::Cosmetic(dur);
+                    let time = Instant::now() + dur;
+                    self.timeouts.push((i, time, Some(dur), cosmetic));
                 }
             }
         }
@@ -95,12 +192,13 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
 
     /// Recompute layout of widgets and redraw
     pub fn reconfigure(&mut self) {
-        let size = Size(self.sc_desc.width, self.sc_desc.height);
+        let size = self.layout_size;
         debug!("Reconfiguring window (size = {:?})", size);
 
         self.tk_window.ev_mgr.configure(self.widget.as_widget_mut());
-        self.widget.resize(&mut self.tk_window, size);
+        self.do_layout(size);
         self.window.request_redraw();
+        self.update_cursor();
     }
 
     /// Handle an event
@@ -111,18 +209,58 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
         shared: &mut SharedState<T>,
         event: WindowEvent,
     ) -> (TkAction, Vec<Box<dyn kas::Window>>) {
+        if self.hud.enabled {
+            match event {
+                WindowEvent::RedrawRequested => (),
+                _ => self.hud.count_event(),
+            }
+        }
+        if let Some(recorder) = shared.recorder.as_mut() {
+            recorder.record(self.tk_window.id, &event);
+        }
+
+        let idle = self.tk_window.ev_mgr.is_idle();
+
         // Note: resize must be handled here to update self.swap_chain.
         match event {
             WindowEvent::Resized(size) => self.do_resize(shared, size),
             WindowEvent::RedrawRequested => self.do_draw(shared),
             WindowEvent::HiDpiFactorChanged(factor) => {
-                self.tk_window.set_dpi_factor(factor);
+                self.tk_window.set_dpi_factor(factor * shared.scale_factor);
                 self.do_resize(shared, self.window.inner_size());
             }
             event @ _ => {
                 event::Manager::handle_winit(&mut *self.widget, &mut self.tk_window, event)
             }
         }
+        if idle && !self.tk_window.ev_mgr.is_idle() {
+            for i in replace(&mut self.missed_callbacks, vec![]) {
+                self.widget.trigger_callback(i, &mut self.tk_window);
+            }
+        }
+        self.update_cursor();
+        let new_windows = replace(&mut self.tk_window.new_windows, vec![]);
+        (self.tk_window.pop_action(), new_windows)
+    }
+
+    /// Handle a navigation command (from a gamepad)
+    #[cfg(feature = "gamepad")]
+    pub fn handle_nav_command(
+        &mut self,
+        command: event::NavCommand,
+    ) -> (TkAction, Vec<Box<dyn kas::Window>>) {
+        event::Manager::handle_nav_command(&mut *self.widget, &mut self.tk_window, command);
+        self.update_cursor();
+        let new_windows = replace(&mut self.tk_window.new_windows, vec![]);
+        (self.tk_window.pop_action(), new_windows)
+    }
+
+    /// Handle raw mouse motion
+    ///
+    /// This is only used while a cursor grab is active.
+    pub fn handle_motion(&mut self, delta: (f64, f64)) -> (TkAction, Vec<Box<dyn kas::Window>>) {
+        event::Manager::handle_winit_motion(&mut *self.widget, &mut self.tk_window, delta);
+        self.update_cursor();
         let new_windows = replace(&mut self.tk_window.new_windows, vec![]);
         (self.tk_window.pop_action(), new_windows)
     }
@@ -133,7 +271,13 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
         while i < self.timeouts.len() {
             for timeout in &mut self.timeouts[i..] {
                 if timeout.1 == instant {
-                    self.widget.trigger_callback(timeout.0, &mut self.tk_window);
+                    if timeout.3 && self.tk_window.ev_mgr.is_idle() {
+                        if !self.missed_callbacks.contains(&timeout.0) {
+                            self.missed_callbacks.push(timeout.0);
+                        }
+                    } else {
+                        self.widget.trigger_callback(timeout.0, &mut self.tk_window);
+                    }
                     if let Some(dur) = timeout.2 {
                         while timeout.1 <= Instant::now() {
                             timeout.1 += dur;
@@ -152,6 +296,100 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
         (self.tk_window.pop_action(), self.next_resume())
     }
 
+    /// Time at which a tooltip is due, if any
+    pub(crate) fn tooltip_time(&self) -> Option<Instant> {
+        self.tk_window.ev_mgr.tooltip_time()
+    }
+
+    /// Show a tooltip, if due
+    pub(crate) fn show_tooltip(&mut self) {
+        let widget = self.widget.as_widget();
+        if self.tk_window.ev_mgr.show_tooltip(widget) {
+            self.window.request_redraw();
+        }
+    }
+
+    /// Time at which a long press is due, if any
+    pub(crate) fn long_press_time(&self) -> Option<Instant> {
+        self.tk_window.ev_mgr.long_press_time()
+    }
+
+    /// Handle a long press, if due
+    pub(crate) fn handle_long_press(&mut self) -> (TkAction, Vec<Box<dyn kas::Window>>) {
+        event::Manager::handle_long_press(&mut *self.widget, &mut self.tk_window);
+        self.update_cursor();
+        let new_windows = replace(&mut self.tk_window.new_windows, vec![]);
+        (self.tk_window.pop_action(), new_windows)
+    }
+
+    /// Time at which a deferred layout (see [`kas::Window::resize_snapshot`])
+    /// is due, if any
+    pub(crate) fn resize_time(&self) -> Option<Instant> {
+        self.resize_time
+    }
+
+    /// True while in a busy state (see [`kas::TkWindow::set_busy`])
+    pub(crate) fn is_busy(&self) -> bool {
+        self.tk_window.busy.iter().any(|busy| !busy.is_unique())
+    }
+
+    /// Time at which to check the busy state, if any
+    ///
+    /// Once the activity indicator is shown, the window is instead redrawn
+    /// continuously while busy.
+    pub(crate) fn busy_time(&self) -> Option<Instant> {
+        let shown = self.tk_window.busy_since? + BUSY_GRACE;
+        let now = Instant::now();
+        if shown > now {
+            Some(shown.min(now + BUSY_POLL))
+        } else {
+            None
+        }
+    }
+
+    /// Update the busy state, redrawing as required
+    pub(crate) fn poll_busy(&mut self) {
+        if self.busy_phase().is_some() {
+            self.window.request_redraw();
+        }
+    }
+
+    // Update the busy state, returning the phase of the activity indicator if
+    // shown. Redraws the window once the busy state ends.
+    fn busy_phase(&mut self) -> Option<f32> {
+        let is_busy = self.is_busy();
+        let tk_window = &mut self.tk_window;
+        if !is_busy {
+            tk_window.busy.clear();
+            if tk_window.busy_since.take().is_some() {
+                self.window.request_redraw();
+            }
+            return None;
+        }
+        tk_window.busy.retain(|busy| !busy.is_unique());
+        let elapsed = tk_window.busy_since?.elapsed().checked_sub(BUSY_GRACE)?;
+        let period = BUSY_PERIOD.as_secs_f32();
+        Some((elapsed.as_secs_f32() % period) / period)
+    }
+
+    /// Request a redraw if a deferred layout is due
+    ///
+    /// Layout is updated when next drawn.
+    pub(crate) fn finish_resize(&mut self) {
+        if self
+            .resize_time
+            .map(|t| t <= Instant::now())
+            .unwrap_or(false)
+        {
+            self.window.request_redraw();
+        }
+    }
+
+    /// Describe the widget tree, for the inspector
+    pub(crate) fn describe(&self) -> String {
+        inspector::describe(self.widget.as_widget(), &self.tk_window.ev_mgr)
+    }
+
     fn next_resume(&self) -> Option<Instant> {
         let mut next = None;
         for timeout in &self.timeouts {
@@ -166,20 +404,45 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
 
 // Internal functions
 impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
+    /// Show the hovered widget's cursor icon and apply any grab, if changed
+    fn update_cursor(&mut self) {
+        let icon = self.tk_window.ev_mgr.cursor_icon();
+        if icon != self.cursor_icon {
+            self.window.set_cursor_icon(icon);
+            self.cursor_icon = icon;
+        }
+        let grab = self.tk_window.ev_mgr.cursor_grab();
+        if grab != self.cursor_grab {
+            // Without confinement, the cursor is still hidden
+            if let Err(e) = self.window.set_cursor_grab(grab) {
+                warn!("Unable to set cursor grab: {}", e);
+            }
+            self.window.set_cursor_visible(!grab);
+            self.cursor_grab = grab;
+        }
+    }
+
     fn do_resize<T: theme::Theme<DrawPipe, Window = TW>>(
         &mut self,
         shared: &mut SharedState<T>,
         size: LogicalSize,
     ) {
         let size = size.to_physical(self.window.hidpi_factor()).into();
+        // Some platforms report minimisation as a resize to zero
+        if self.tk_window.ev_mgr.set_visible(size != Size::ZERO) {
+            self.window.request_redraw();
+        }
         if size == Size(self.sc_desc.width, self.sc_desc.height) {
             return;
         }
         debug!("Resizing window to size={:?}", size);
-        self.widget.resize(&mut self.tk_window, size);
-
-        let buf = self.tk_window.resize(&shared.device, size);
-        shared.queue.submit(&[buf]);
+        if self.widget.resize_snapshot() {
+            // Scale the current content until resizing pauses
+            self.tk_window.draw_pipe.set_target_size(size);
+            self.resize_time = Some(Instant::now() + RESIZE_DELAY);
+        } else {
+            self.resize_content(shared, size);
+        }
 
         self.sc_desc.width = size.0;
         self.sc_desc.height = size.1;
@@ -188,14 +451,43 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
             .create_swap_chain(&self.surface, &self.sc_desc);
     }
 
+    fn resize_content<T: theme::Theme<DrawPipe, Window = TW>>(
+        &mut self,
+        shared: &mut SharedState<T>,
+        size: Size,
+    ) {
+        self.resize_time = None;
+        self.do_layout(size);
+        let buf = self.tk_window.resize(&shared.device, size);
+        shared.queue.submit(&[buf]);
+    }
+
+    fn do_layout(&mut self, size: Size) {
+        let start = Instant::now();
+        self.layout_size = size;
+        self.tk_window.ev_mgr.set_window_size(size);
+        self.widget.resize(&mut self.tk_window, size);
+        if self.hud.enabled {
+            self.hud.set_layout_time(start.elapsed());
+        }
+    }
+
     fn do_draw<T: theme::Theme<DrawPipe, Window = TW>>(&mut self, shared: &mut SharedState<T>) {
         trace!("Drawing window");
-        let size = Size(self.sc_desc.width, self.sc_desc.height);
+        let start = Instant::now();
+        if self.resize_time.map(|t| t <= start).unwrap_or(false) {
+            let size = Size(self.sc_desc.width, self.sc_desc.height);
+            self.resize_content(shared, size);
+        }
+        let size = self.layout_size;
+        let busy_phase = self.busy_phase();
+        if self.tk_window.ev_mgr.layout_frame() {
+            self.do_layout(size);
+        }
         let rect = Rect {
             pos: Coord::ZERO,
             size,
         };
-        let frame = self.swap_chain.get_next_texture();
         let mut draw_handle = unsafe {
             shared.theme.draw_handle(
                 &mut self.tk_window.draw_pipe,
@@ -203,9 +495,76 @@ impl<TW: theme::Window<DrawPipe> + 'static> Window<TW> {
                 rect,
             )
         };
-        self.widget.draw(&mut draw_handle, &self.tk_window.ev_mgr);
+        kas::layout::draw_child(&*self.widget, &mut draw_handle, &self.tk_window.ev_mgr);
+        if let Some((label, coord, accepted)) = self.tk_window.ev_mgr.drag_ghost() {
+            draw_handle.drag_ghost(coord, label, accepted);
+        } else if let Some((id, coord)) = self.tk_window.ev_mgr.tooltip() {
+            if let Some(text) = self.widget.get_by_id(id).and_then(|w| w.tooltip()) {
+                draw_handle.tooltip(coord, text);
+            }
+        }
+        if self.blocked {
+            draw_handle.dim(rect);
+        }
+        if let Some(phase) = busy_phase {
+            draw_handle.dim(rect);
+            draw_handle.spinner(rect, phase, SpinnerStyle::Spokes);
+        }
+        drop(draw_handle);
+        if shared.debug_layout {
+            self.draw_layout_outlines();
+        }
+        if self.inspected {
+            self.draw_inspector_highlight();
+        }
+        if self.hud.enabled {
+            let dpi_factor = self.window.hidpi_factor() as f32;
+            self.hud
+                .draw(&mut self.tk_window.draw_pipe, size, dpi_factor);
+        }
+        let frame = self.swap_chain.get_next_texture();
         let buf = self.tk_window.render(shared, &frame.view);
         shared.queue.submit(&[buf]);
+        let busy = busy_phase.is_some() && !self.tk_window.ev_mgr.is_idle();
+        if self.tk_window.ev_mgr.animation_frame() || busy {
+            self.window.request_redraw();
+        }
+        if self.hud.enabled {
+            let stats = self.tk_window.draw_pipe.stats();
+            self.hud.record_frame(start.elapsed(), stats);
+        }
+    }
+}
+
+impl<TW> Window<TW> {
+    fn draw_layout_outlines(&mut self) {
+        let draw_pipe = &mut self.tk_window.draw_pipe;
+        let col = Colour::new(0.0, 0.8, 0.8);
+        self.widget.as_widget().walk(&mut |widget| {
+            let rect = widget.rect();
+            let pos = Vec2::from(rect.pos);
+            let outer = Quad(pos, pos + Vec2::from(rect.size));
+            let mut inner = outer;
+            inner.shrink(1.0);
+            draw_pipe.draw_frame(0, outer, inner, Style::Flat, col);
+        });
+    }
+
+    fn draw_inspector_highlight(&mut self) {
+        let ev_mgr = &self.tk_window.ev_mgr;
+        let widget = self.widget.as_widget();
+        let rect = match inspector::hovered(widget, ev_mgr).and_then(|id| widget.get_by_id(id)) {
+            Some(w) => w.rect(),
+            None => return,
+        };
+        let pos = Vec2::from(rect.pos);
+        let outer = Quad(pos, pos + Vec2::from(rect.size));
+        let mut inner = outer;
+        inner.shrink(2.0);
+        let col = Colour::new(1.0, 0.0, 1.0);
+        self.tk_window
+            .draw_pipe
+            .draw_frame(0, outer, inner, Style::Flat, col);
     }
 }
 
@@ -218,12 +577,16 @@ pub(crate) struct TkWindow<TW> {
     pub(crate) ev_mgr: event::Manager,
     theme_window: TW,
     new_windows: Vec<Box<dyn kas::Window>>,
+    id: kas::WindowId,
+    window_list: WindowList,
+    window_requests: Vec<WindowRequest>,
+    busy: Vec<kas::Busy>,
+    busy_since: Option<Instant>,
 }
 
 impl<TW: theme::Window<DrawPipe> + 'static> TkWindow<TW> {
     pub fn new<T: theme::Theme<DrawPipe, Window = TW>>(
         shared: &mut SharedState<T>,
-        tex_format: wgpu::TextureFormat,
         size: Size,
         dpi_factor: f64,
     ) -> Self {
@@ -236,8 +599,11 @@ impl<TW: theme::Window<DrawPipe> + 'static> TkWindow<TW> {
             }
         };
 
-        let mut draw_pipe = DrawPipe::new(&mut shared.device, tex_format, size, &shared.theme);
+        let resources = shared.resources.clone();
+        let mut draw_pipe = DrawPipe::new(&mut shared.device, resources, size, &shared.theme);
         let theme_window = shared.theme.new_window(&mut draw_pipe, dpi_factor as f32);
+        let id = kas::WindowId::new(shared.next_window_id);
+        shared.next_window_id += 1;
 
         TkWindow {
             #[cfg(feature = "clipboard")]
@@ -247,6 +613,11 @@ impl<TW: theme::Window<DrawPipe> + 'static> TkWindow<TW> {
             ev_mgr: event::Manager::new(dpi_factor),
             theme_window,
             new_windows: vec![],
+            id,
+            window_list: shared.window_list.clone(),
+            window_requests: vec![],
+            busy: vec![],
+            busy_since: None,
         }
     }
 
@@ -290,14 +661,38 @@ impl<TW: theme::Window<DrawPipe>> kas::TkWindow for TkWindow<TW> {
         self.new_windows.push(widget);
     }
 
+    #[inline]
+    fn window_id(&self) -> kas::WindowId {
+        self.id
+    }
+
+    fn windows(&self) -> Vec<(kas::WindowId, String)> {
+        self.window_list.borrow().clone()
+    }
+
+    fn activate_window(&mut self, id: kas::WindowId) {
+        self.window_requests.push(WindowRequest::Activate(id));
+    }
+
+    fn close_window(&mut self, id: kas::WindowId) {
+        self.window_requests.push(WindowRequest::Close(id));
+    }
+
+    fn set_busy(&mut self) -> kas::Busy {
+        let busy = kas::Busy::new();
+        self.busy.push(busy.clone());
+        if self.busy_since.is_none() {
+            self.busy_since = Some(Instant::now());
+        }
+        busy
+    }
+
     fn data(&self) -> &event::Manager {
         &self.ev_mgr
     }
 
-    fn update_data(&mut self, f: &mut dyn FnMut(&mut event::Manager) -> bool) {
-        if f(&mut self.ev_mgr) {
-            self.send_action(TkAction::Redraw);
-        }
+    fn data_mut(&mut self) -> event::ManagerMut<'_> {
+        event::ManagerMut::new(&mut self.ev_mgr, &mut self.action)
     }
 
     fn with_size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
@@ -349,6 +744,24 @@ impl<TW: theme::Window<DrawPipe>> kas::TkWindow for TkWindow<TW> {
                 .unwrap_or_else(|e| warn!("Failed to set clipboard contents: {:?}", e))
         });
     }
+
+    fn open_url(&mut self, url: &str) {
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            // The empty string is the title of the new console window
+            command.args(&["/C", "start", ""]);
+            command
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = Command::new("open");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let mut command = Command::new("xdg-open");
+
+        if let Err(e) = command.arg(url).spawn() {
+            warn!("Failed to open {:?}: {}", url, e);
+        }
+    }
 }
 
 fn to_wgpu_color(c: kas::draw::Colour) -> wgpu::Color {
@@ -5,6 +5,9 @@
 
 //! Class-specific widget traits
 
+use std::ops::Range;
+
+use crate::theme::SpanClass;
 use crate::TkWindow;
 
 /// Functionality for widgets which can be toggled or selected: check boxes,
@@ -52,6 +55,19 @@ pub trait Editable: HasText {
     fn set_editable(&mut self, editable: bool);
 }
 
+/// Functionality for text widgets able to highlight spans of their text
+///
+/// This is used, for example, by [`FindBar`](crate::widget::FindBar) to
+/// highlight search matches.
+pub trait HasSpans: HasText {
+    /// Set highlighted spans, replacing any previous spans
+    ///
+    /// Each span is a byte range of the widget's text; see
+    /// [`DrawHandle::text_spans`](crate::theme::DrawHandle::text_spans).
+    /// Spans are cleared when the text is edited or replaced.
+    fn set_spans(&mut self, tk: &mut dyn TkWindow, spans: Vec<(Range<usize>, SpanClass)>);
+}
+
 /// Summation of [`HasBool`] and [`HasText`] traits.
 ///
 /// Used because Rust doesn't (yet) support multi-trait objects.
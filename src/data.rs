@@ -5,12 +5,21 @@
 
 //! Data types
 
+use std::borrow::Cow;
+#[cfg(feature = "profiling")]
+use std::cell::Cell;
 use std::fmt;
 use std::num::NonZeroU32;
+#[cfg(feature = "profiling")]
+use std::time::Duration;
 use std::u32;
 
 use crate::event::VirtualKeyCode;
 use crate::geom::Rect;
+use crate::layout::SizeRules;
+
+/// Maximum number of shortcut keys per widget
+pub(crate) const MAX_KEYS: usize = 4;
 
 /// Widget identifier
 ///
@@ -59,10 +68,37 @@ pub struct CoreData {
     pub rect: Rect,
     pub id: WidgetId,
     // variable-length list; None may not preceed Some(_)
-    keys: [Option<VirtualKeyCode>; 4],
+    keys: [Option<VirtualKeyCode>; MAX_KEYS],
+    tooltip: Option<Cow<'static, str>>,
+    // ideal width and vertical rules from size_rules_both, kept between
+    // passes by layout::child_rules
+    pub(crate) rules_cache: Option<(u32, SizeRules)>,
+    #[cfg(feature = "profiling")]
+    profile: Profile,
+    // rules found on each axis during the last layout (see layout_rules)
+    #[cfg(feature = "inspect")]
+    pub(crate) layout_rules: [Option<SizeRules>; 2],
 }
 
 impl CoreData {
+    /// Construct
+    ///
+    /// This is equivalent to `CoreData::default()`, but usable in `const`
+    /// contexts.
+    pub const fn new() -> Self {
+        CoreData {
+            rect: Rect::ZERO,
+            id: WidgetId::LAST,
+            keys: [None; MAX_KEYS],
+            tooltip: None,
+            rules_cache: None,
+            #[cfg(feature = "profiling")]
+            profile: Profile::new(),
+            #[cfg(feature = "inspect")]
+            layout_rules: [None, None],
+        }
+    }
+
     /// Set shortcut keys
     pub fn set_keys(&mut self, keys: &[VirtualKeyCode]) {
         if keys.len() > self.keys.len() {
@@ -88,4 +124,213 @@ impl CoreData {
             .fuse()
             .map(|x| x.unwrap())
     }
+
+    /// Set or clear the tooltip
+    ///
+    /// This is returned by the default implementation of [`Widget::tooltip`].
+    ///
+    /// [`Widget::tooltip`]: crate::Widget::tooltip
+    #[inline]
+    pub fn set_tooltip(&mut self, tooltip: Option<Cow<'static, str>>) {
+        self.tooltip = tooltip;
+    }
+
+    /// Get the tooltip
+    #[inline]
+    pub fn tooltip(&self) -> Option<&str> {
+        self.tooltip.as_deref()
+    }
+
+    /// Get the rules found during the last layout
+    ///
+    /// Returns the horizontal and vertical [`SizeRules`] found for this
+    /// widget by the last call to [`layout::child_rules`] (or
+    /// [`layout::solve`], for the root widget) on each axis, or `None` where
+    /// the widget has not been sized on that axis.
+    ///
+    /// Requires the `inspect` feature (enabled by `strict`).
+    ///
+    /// [`layout::child_rules`]: crate::layout::child_rules
+    /// [`layout::solve`]: crate::layout::solve
+    #[cfg(feature = "inspect")]
+    #[inline]
+    pub fn layout_rules(&self) -> [Option<SizeRules>; 2] {
+        self.layout_rules
+    }
+
+    /// Get profiling counters
+    ///
+    /// Requires the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    #[inline]
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+}
+
+/// Per-widget profiling counters
+///
+/// These are collected only with the `profiling` feature, and are accessed
+/// via [`CoreData::profile`]. Counters are kept by the layout and event
+/// dispatch code (see [`layout::child_rules`], [`layout::draw_child`] and
+/// [`Manager::dispatch`]), thus are only as accurate as widgets' use of these.
+///
+/// [`layout::child_rules`]: crate::layout::child_rules
+/// [`layout::draw_child`]: crate::layout::draw_child
+/// [`Manager::dispatch`]: crate::event::Manager::dispatch
+#[cfg(feature = "profiling")]
+#[derive(Clone, Default, Debug)]
+pub struct Profile {
+    draws: Cell<u64>,
+    events: Cell<u64>,
+    layout: Cell<Duration>,
+}
+
+#[cfg(feature = "profiling")]
+impl Profile {
+    const fn new() -> Self {
+        Profile {
+            draws: Cell::new(0),
+            events: Cell::new(0),
+            layout: Cell::new(Duration::from_secs(0)),
+        }
+    }
+
+    /// Number of times the widget was drawn
+    #[inline]
+    pub fn draws(&self) -> u64 {
+        self.draws.get()
+    }
+
+    /// Number of events addressed to the widget
+    ///
+    /// Events addressed by coordinate are counted against the hovered widget.
+    #[inline]
+    pub fn events(&self) -> u64 {
+        self.events.get()
+    }
+
+    /// Duration of the last layout
+    ///
+    /// This is the time spent finding the widget's size rules (on both axes,
+    /// including those of its children) during the last layout.
+    #[inline]
+    pub fn layout(&self) -> Duration {
+        self.layout.get()
+    }
+
+    /// Reset all counters
+    pub fn reset(&self) {
+        self.draws.set(0);
+        self.events.set(0);
+        self.layout.set(Duration::default());
+    }
+
+    pub(crate) fn record_draw(&self) {
+        self.draws.set(self.draws.get() + 1);
+    }
+
+    pub(crate) fn record_event(&self) {
+        self.events.set(self.events.get() + 1);
+    }
+
+    pub(crate) fn record_layout(&self, vertical: bool, elapsed: Duration) {
+        // Layout always starts with the horizontal axis
+        match vertical {
+            false => self.layout.set(elapsed),
+            true => self.layout.set(self.layout.get() + elapsed),
+        }
+    }
+}
+
+/// A list of data items, as viewed by list-like widgets
+///
+/// This is the model used by [`ListView`](crate::widget::ListView) and
+/// [`IconView`](crate::widget::IconView). It is implemented for `Vec<T>`; users
+/// may implement it for other collections (or for data computed on demand).
+pub trait ListData: fmt::Debug {
+    /// Type of list items
+    type Item;
+
+    /// Number of items
+    fn len(&self) -> usize;
+
+    /// True if there are no items
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an item, if `index < self.len()`
+    fn get(&self, index: usize) -> Option<&Self::Item>;
+}
+
+impl<T: fmt::Debug> ListData for Vec<T> {
+    type Item = T;
+
+    #[inline]
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+}
+
+/// A table of text cells, as viewed by table widgets
+///
+/// This is the model used by [`Table`](crate::widget::Table). Cells are
+/// addressed by `(row, column)` index; each row also has a key identifying it
+/// independently of its position (e.g. a database key). Columns may have
+/// headers. It is implemented for `Vec<Vec<T>>`, where the key is the row
+/// index, the first row determines the number of columns and there are no
+/// headers.
+pub trait TableData: fmt::Debug {
+    /// Type of row keys
+    type Key: Clone + fmt::Debug;
+
+    /// Number of rows
+    fn rows(&self) -> usize;
+
+    /// Number of columns
+    fn cols(&self) -> usize;
+
+    /// Key of the given `row`
+    fn key(&self, row: usize) -> Self::Key;
+
+    /// Get the text of a cell, if it exists
+    fn cell(&self, row: usize, col: usize) -> Option<&str>;
+
+    /// Get the header of a column, if any
+    ///
+    /// Table widgets show a row of headers if any column has a header. By
+    /// default, there are no headers.
+    fn header(&self, _col: usize) -> Option<&str> {
+        None
+    }
+}
+
+impl<T: AsRef<str> + fmt::Debug> TableData for Vec<Vec<T>> {
+    type Key = usize;
+
+    #[inline]
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn cols(&self) -> usize {
+        self.first().map(|row| row.len()).unwrap_or(0)
+    }
+
+    #[inline]
+    fn key(&self, row: usize) -> usize {
+        row
+    }
+
+    #[inline]
+    fn cell(&self, row: usize, col: usize) -> Option<&str> {
+        self.get(row).and_then(|r| r.get(col)).map(|s| s.as_ref())
+    }
 }
@@ -5,8 +5,14 @@
 
 //! Colour type and theming
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::VoidMsg;
+
 /// Standard colour description
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Colour {
     pub r: f32,
     pub g: f32,
@@ -14,6 +20,12 @@ pub struct Colour {
     pub a: f32,
 }
 
+impl From<VoidMsg> for Colour {
+    fn from(_: VoidMsg) -> Self {
+        unreachable!()
+    }
+}
+
 impl Colour {
     /// Constructor
     pub const fn new(r: f32, g: f32, b: f32) -> Self {
@@ -24,6 +36,32 @@ impl Colour {
     pub const fn grey(s: f32) -> Self {
         Colour::new(s, s, s)
     }
+
+    /// Relative luminance, as defined by WCAG 2
+    ///
+    /// Components are taken to be linear (as they are when drawn to an sRGB
+    /// target); alpha is ignored. The result is `0.0` for black and `1.0` for
+    /// white.
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Contrast ratio of two colours, as defined by WCAG 2
+    ///
+    /// The result ranges from `1.0` (identical luminance) to `21.0` (black on
+    /// white), and is symmetric. WCAG level AA requires a ratio of at least
+    /// 4.5 for normal text and 3 for large text; level AAA requires 7.
+    ///
+    /// ```
+    /// use kas::draw::Colour;
+    ///
+    /// let ratio = Colour::grey(0.0).contrast_ratio(Colour::grey(1.0));
+    /// assert!((ratio - 21.0).abs() < 1e-4);
+    /// ```
+    pub fn contrast_ratio(self, other: Colour) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
 }
 
 impl From<Colour> for [f32; 4] {
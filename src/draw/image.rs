@@ -0,0 +1,69 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image data
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::geom::Size;
+
+static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+
+/// A raster image, in RGBA (sRGB) format with 4 bytes per texel
+///
+/// Image data is shared: cloning is cheap. Each image has a unique key (shared
+/// by clones), allowing the toolkit to upload the image once and reuse the
+/// result (see [`Draw::draw_image`](super::Draw::draw_image)).
+#[derive(Clone)]
+pub struct RgbaImage {
+    key: u64,
+    size: Size,
+    data: Arc<[u8]>,
+}
+
+impl RgbaImage {
+    /// Construct from texel data
+    ///
+    /// `data` must contain the texels of an image of the given `size` in
+    /// row-major order, thus have length `4 * size.0 * size.1`.
+    pub fn new(size: Size, data: Vec<u8>) -> Self {
+        assert_eq!(data.len(), 4 * size.0 as usize * size.1 as usize);
+        RgbaImage {
+            key: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+            size,
+            data: data.into(),
+        }
+    }
+
+    /// A key, unique to this image and its clones
+    #[inline]
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Size in texels
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Texel data, row-major
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl fmt::Debug for RgbaImage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RgbaImage {{ key: {}, size: {:?}, ... }}",
+            self.key, self.size
+        )
+    }
+}
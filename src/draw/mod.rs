@@ -12,11 +12,18 @@
 //! [`Style`] of drawing may batch operations independently of other styles or
 //! may share batching with another style. Roughly speaking, later [`Style`]s
 //! are drawn later, but draw order is implementation defined.
+//!
+//! Coordinates are in device (physical) pixels. At fractional scale factors,
+//! edges which do not lie on pixel boundaries appear blurred and very thin
+//! lines may vanish; [`Quad::snap`] and [`snap_width`] align edges and line
+//! widths to whole pixels.
 
 mod colour;
+mod image;
 mod traits;
 mod vector;
 
 pub use colour::Colour;
+pub use image::RgbaImage;
 pub use traits::{Draw, Style};
-pub use vector::{Quad, Vec2};
+pub use vector::{snap_width, Quad, Transform, Vec2};
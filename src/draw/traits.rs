@@ -7,7 +7,7 @@
 
 use std::any::Any;
 
-use super::{Colour, Quad, Vec2};
+use super::{Colour, Quad, RgbaImage, Transform, Vec2};
 use kas::geom::Rect;
 
 /// Style of drawing
@@ -50,6 +50,21 @@ pub trait Draw {
     /// Returns the pass number for this clip region.
     fn add_clip_region(&mut self, region: Rect) -> usize;
 
+    /// Add an overlay region
+    ///
+    /// This is a clip region (see [`Draw::add_clip_region`]) which is drawn
+    /// after (on top of) all non-overlay regions, e.g. for pop-up menus.
+    /// Overlay regions are drawn in the order added.
+    fn add_overlay_region(&mut self, region: Rect) -> usize;
+
+    /// Set the transform of a pass
+    ///
+    /// All coordinates drawn in the given `pass` are mapped through
+    /// `transform` (this does not affect the pass's region, which is in
+    /// window coordinates). Passes are initially untransformed. Line and
+    /// frame widths scale with the content.
+    fn set_transform(&mut self, pass: usize, transform: Transform);
+
     /// Add a rectangle to the draw buffer.
     ///
     /// The `pass` number indicates in which pass this is drawn. In general,
@@ -68,4 +83,34 @@ pub trait Draw {
     /// Expected componentwise bounds on input:
     /// `outer.0 < inner.0 < inner.1 < outer.1` and `-1 ≤ norm ≤ 1`.
     fn draw_frame(&mut self, pass: usize, outer: Quad, inner: Quad, style: Style, col: Colour);
+
+    /// Add a flat-shaded triangle with corners `a`, `b`, `c` to the draw
+    /// buffer.
+    ///
+    /// The `pass` number is used as in [`Draw::draw_quad`]. Corners may be
+    /// given in either winding order.
+    fn draw_triangle(&mut self, pass: usize, a: Vec2, b: Vec2, c: Vec2, col: Colour);
+
+    /// Add a rectangle filled with a gradient to the draw buffer.
+    ///
+    /// The colours of the four corners are given in the order top-left,
+    /// top-right, bottom-left, bottom-right, and interpolated across the
+    /// rectangle. Interpolation is exact for linear gradients (where opposite
+    /// sides have equal colour differences); otherwise it may be approximate,
+    /// hence such gradients should be drawn in several smaller parts.
+    ///
+    /// The `pass` number is used as in [`Draw::draw_quad`].
+    ///
+    /// Expected componentwise bounds on input: `quad.0 < quad.1`.
+    fn draw_gradient(&mut self, pass: usize, quad: Quad, cols: [Colour; 4]);
+
+    /// Add an image to the draw buffer, scaled to fill `quad`.
+    ///
+    /// The `pass` number is used as in [`Draw::draw_quad`]. The image is
+    /// uploaded to the graphics device on first use (and again if evicted);
+    /// identity is determined by [`RgbaImage::key`]. Images may not be drawn
+    /// if too large for the implementation.
+    ///
+    /// Expected componentwise bounds on input: `quad.0 < quad.1`.
+    fn draw_image(&mut self, pass: usize, quad: Quad, image: &RgbaImage);
 }
@@ -5,10 +5,11 @@
 
 //! Vector types
 //!
-//! For drawing operations, all dimensions use the `f32` type.
+//! For drawing operations, all dimensions use the `f32` type. The unit is
+//! the device (physical) pixel, thus whole numbers lie on pixel boundaries.
 
-use kas::geom::{Coord, Size};
-use std::ops::{Add, Neg, Sub};
+use kas::geom::{Coord, Rect, Size};
+use std::ops::{Add, Mul, Neg, Sub};
 
 /// Axis-aligned 2D cuboid, specified via two corners
 ///
@@ -25,6 +26,144 @@ impl Quad {
         self.0 = self.0 + value;
         self.1 = self.1 - value;
     }
+
+    /// Snap edges to device pixels
+    ///
+    /// Each corner is rounded to the nearest pixel boundary, except that on
+    /// each axis a quad of positive size remains at least one pixel in size
+    /// (thus hairlines do not disappear). See also [`snap_width`].
+    ///
+    /// ```
+    /// use kas::draw::{Quad, Vec2};
+    ///
+    /// let quad = Quad(Vec2(1.4, 2.5), Vec2(1.6, 7.25)).snap();
+    /// assert_eq!(quad, Quad(Vec2(1.0, 3.0), Vec2(2.0, 7.0)));
+    /// ```
+    pub fn snap(self) -> Self {
+        let (a, b) = (self.0.round(), self.1.round());
+        let snap_end = |a: f32, b: f32, low: f32, high: f32| match high > low && b <= a {
+            true => a + 1.0,
+            false => b,
+        };
+        let b = Vec2(
+            snap_end(a.0, b.0, (self.0).0, (self.1).0),
+            snap_end(a.1, b.1, (self.0).1, (self.1).1),
+        );
+        Quad(a, b)
+    }
+}
+
+/// Snap a line width to device pixels
+///
+/// Dimensions expected to be crisp (e.g. frame and line widths) should be a
+/// whole number of pixels; this rounds `width` to the nearest pixel, except
+/// that where `width > 0` the result is at least one pixel. For example,
+/// a 1-pixel line at a scale factor of 1.5 is drawn 2 pixels wide.
+#[inline]
+pub fn snap_width(width: f32) -> f32 {
+    match width > 0.0 {
+        true => width.round().max(1.0),
+        false => 0.0,
+    }
+}
+
+/// A transform: uniform scale followed by translation
+///
+/// This maps a point `p` to `p * scale + offset`. The `scale` is expected to
+/// be positive.
+///
+/// ```
+/// use kas::draw::{Transform, Vec2};
+///
+/// let zoom = Transform::new(2.0, Vec2(10.0, 0.0));
+/// assert_eq!(zoom.apply(Vec2(1.0, 2.0)), Vec2(12.0, 4.0));
+/// assert_eq!(zoom.inverse().apply(Vec2(12.0, 4.0)), Vec2(1.0, 2.0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    /// Scale factor
+    pub scale: f32,
+    /// Translation, applied after scaling
+    pub offset: Vec2,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The identity transform
+    pub const IDENTITY: Transform = Transform {
+        scale: 1.0,
+        offset: Vec2::splat(0.0),
+    };
+
+    /// Construct
+    #[inline]
+    pub fn new(scale: f32, offset: Vec2) -> Self {
+        Transform { scale, offset }
+    }
+
+    /// True if this is the identity transform
+    #[inline]
+    pub fn is_identity(self) -> bool {
+        self == Transform::IDENTITY
+    }
+
+    /// The transform equivalent to applying `self` then `next`
+    #[inline]
+    pub fn then(self, next: Transform) -> Self {
+        Transform {
+            scale: self.scale * next.scale,
+            offset: self.offset * next.scale + next.offset,
+        }
+    }
+
+    /// The inverse transform
+    #[inline]
+    pub fn inverse(self) -> Self {
+        let scale = 1.0 / self.scale;
+        Transform {
+            scale,
+            offset: -self.offset * scale,
+        }
+    }
+
+    /// Transform a point
+    #[inline]
+    pub fn apply(self, p: Vec2) -> Vec2 {
+        p * self.scale + self.offset
+    }
+
+    /// Transform a quad
+    #[inline]
+    pub fn apply_quad(self, quad: Quad) -> Quad {
+        Quad(self.apply(quad.0), self.apply(quad.1))
+    }
+
+    /// Transform a coordinate, rounding to the nearest pixel
+    #[inline]
+    pub fn apply_coord(self, coord: Coord) -> Coord {
+        let p = self.apply(Vec2::from(coord)).round();
+        Coord(p.0 as i32, p.1 as i32)
+    }
+
+    /// Transform a rect
+    ///
+    /// The result is rounded outwards to whole pixels, thus it covers the
+    /// transformed rect.
+    pub fn apply_rect(self, rect: Rect) -> Rect {
+        let a = self.apply(Vec2::from(rect.pos));
+        let b = self.apply(Vec2::from(rect.pos) + Vec2::from(rect.size));
+        let (x0, y0) = (a.0.floor() as i32, a.1.floor() as i32);
+        let (x1, y1) = (b.0.ceil() as i32, b.1.ceil() as i32);
+        Rect {
+            pos: Coord(x0, y0),
+            size: Size((x1 - x0).max(0) as u32, (y1 - y0).max(0) as u32),
+        }
+    }
 }
 
 /// 2D vector
@@ -47,6 +186,12 @@ impl Vec2 {
         Vec2(value, value)
     }
 
+    /// Round each component to the nearest integer (pixel boundary)
+    #[inline]
+    pub fn round(self) -> Self {
+        Vec2(self.0.round(), self.1.round())
+    }
+
     /// For each component, return `±1` with the same sign as `self`.
     #[inline]
     pub fn sign(self) -> Self {
@@ -119,6 +264,14 @@ impl Sub<f32> for Vec2 {
     }
 }
 
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+    #[inline]
+    fn mul(self, rhs: f32) -> Self::Output {
+        Vec2(self.0 * rhs, self.1 * rhs)
+    }
+}
+
 impl From<(f32, f32)> for Vec2 {
     #[inline]
     fn from(arg: (f32, f32)) -> Self {
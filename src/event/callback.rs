@@ -18,7 +18,14 @@ pub enum Callback {
     /// Call once immediately on start.
     Start,
     /// Call on start and repeatedly with the given period. Precise timing is not guaranteed.
-    // Note: do we want to auto-suspend timeouts for minimised windows? Perhaps
-    // make this optional?
     Repeat(Duration),
+    /// As [`Callback::Repeat`], but suspended while the window is idle
+    ///
+    /// Use this for callbacks which only affect the window's appearance, for
+    /// example updating a clock. No calls are made while the window is idle
+    /// (see [`Manager::is_idle`]); if any were missed, one call is made when
+    /// the window becomes active.
+    ///
+    /// [`Manager::is_idle`]: super::Manager::is_idle
+    Cosmetic(Duration),
 }
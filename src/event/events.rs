@@ -5,10 +5,15 @@
 
 //! Event handling: events
 
-use super::MouseButton;
+use std::any::Any;
+use std::fmt;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::{ElementState, MouseButton, Shortcut, VirtualKeyCode};
 
 use crate::geom::Coord;
-use crate::WidgetId;
+use crate::{TkAction, WidgetId, WindowId};
 
 /// Delivery address of an [`Event`]
 #[derive(Clone, Copy, Debug)]
@@ -26,13 +31,124 @@ pub enum Action {
     ReceivedCharacter(char),
     /// A mouse or touchpad scroll event
     Scroll(ScrollDelta),
+    /// A navigation key was pressed while the widget has key focus
+    ///
+    /// This is sent to the widget with character focus, if any, allowing an
+    /// editor to move its caret.
+    NavKey(NavKey),
+    /// Switch to the next tab (previous if `true`), from Ctrl+Tab (with Shift)
+    ///
+    /// This is sent to the widget with key focus (otherwise, the hovered
+    /// widget); a tab container should handle this when unhandled by its
+    /// descendants.
+    NextTab(bool),
+    /// The Tab key was pressed while the widget has key focus
+    ///
+    /// A widget with multiple focusable parts (e.g. the handles of a
+    /// [`RangeSlider`]) may handle this to move focus between its parts. When
+    /// unhandled, key focus moves to the next widget.
+    ///
+    /// [`RangeSlider`]: crate::widget::RangeSlider
+    NextFocus,
+    /// Shift+Tab was pressed while the widget has key focus
+    ///
+    /// As [`Action::NextFocus`], but in reverse: when unhandled, key focus
+    /// moves to the previous widget.
+    PreviousFocus,
+    /// The edit key (F2) was pressed while the widget has key focus
+    ///
+    /// Widgets with editable items (e.g. a [`Table`]) may handle this to begin
+    /// editing the focused item.
+    ///
+    /// [`Table`]: crate::widget::Table
+    BeginEdit,
+    /// The copy shortcut was pressed while the widget has key focus
+    ///
+    /// This is only sent when no widget has character focus; otherwise the
+    /// shortcut is received as a character.
+    Copy,
+    /// The cut shortcut was pressed while the widget has key focus
+    ///
+    /// This is sent as for [`Action::Copy`].
+    Cut,
+    /// The paste shortcut was pressed while the widget has key focus
+    ///
+    /// This is sent as for [`Action::Copy`].
+    Paste,
+    /// A keyboard shortcut bound to this widget was pressed
+    ///
+    /// This is sent for shortcuts declared by [`Widget::shortcuts`] and may
+    /// be used with [`ManagerMut::add_shortcut`]. Typically a widget handles
+    /// this by returning a message.
+    ///
+    /// [`Widget::shortcuts`]: crate::Widget::shortcuts
+    /// [`ManagerMut::add_shortcut`]: super::ManagerMut::add_shortcut
+    Shortcut(Shortcut),
+    /// The widget lost character focus
+    ///
+    /// The parameter is `true` if the user cancelled input (pressed Escape);
+    /// otherwise focus moved elsewhere, for example due to a mouse click.
+    LostCharFocus(bool),
+    /// A drag left the widget (or was cancelled) without dropping
+    ///
+    /// This is sent to a drop target after one or more [`Event::DragHover`]
+    /// events.
+    DragLeave,
+    /// A short touch without movement
+    ///
+    /// This and the following touch gestures are sent with
+    /// [`Address::Coord`] (the start of the touch), thus reach the widget under
+    /// the gesture, and may be handled by any parent when unhandled. They are
+    /// recognised in addition to the press events of single touches, thus a
+    /// tap also activates a button as usual.
+    Tap,
+    /// A touch held in place
+    ///
+    /// This may be used to open a context menu. Releasing the touch ends the
+    /// press as usual, but is not a tap.
+    LongPress,
+    /// A quick single-finger movement, by the given displacement
+    Swipe(Coord),
+    /// A two-finger pinch, scaling by the given factor
+    ///
+    /// The factor is relative to the previous pinch event: greater than 1
+    /// when the fingers move apart. The address is the centre of the
+    /// fingers. For example, a [`Zoom`] view zooms about this point.
+    ///
+    /// Two-finger movement without pinching is sent as [`Action::Scroll`]
+    /// instead, with a [`ScrollDelta::PixelDelta`]. Touches of two-finger
+    /// gestures do not generate press events; a press already in progress is
+    /// cancelled.
+    ///
+    /// [`Zoom`]: crate::widget::Zoom
+    Pinch(f32),
 }
 
 /// Low-level events addressed to a widget by [`WidgetId`] or coordinate.
 #[derive(Clone, Debug)]
 pub enum Event {
     Action(Action),
+    /// Query the widget under the mouse cursor
+    ///
+    /// This is sent with [`Address::Coord`](super::Address::Coord) each time
+    /// the cursor moves; the handling widget becomes the hover target.
+    /// While a pop-up is open, it is instead sent to the pop-up owner with
+    /// [`Address::Id`](super::Address::Id); see
+    /// [`ManagerMut::set_popup`](super::ManagerMut::set_popup).
+    ///
+    /// This is also used to find the widget at a coordinate for other
+    /// purposes (e.g. drag targets), thus should not be used to track the
+    /// cursor; see [`Event::CursorMove`] instead.
     Identify,
+    /// The mouse cursor moved over this widget
+    ///
+    /// This is sent with [`Address::Id`](super::Address::Id) to the hovered
+    /// widget each time the cursor moves, after [`Event::Identify`], and may
+    /// be used to track the cursor position while hovered. It is not sent
+    /// while a mouse grab is active ([`Event::PressMove`] is sent instead).
+    CursorMove {
+        coord: Coord,
+    },
     /// A mouse button was pressed or touch event started
     PressStart {
         source: PressSource,
@@ -57,6 +173,109 @@ pub enum Event {
         end_id: Option<WidgetId>,
         coord: Coord,
     },
+    /// A key was pressed or released
+    ///
+    /// This raw event is received only by the widget holding the raw key grab
+    /// (see [`ManagerMut::request_raw_keys`]), regardless of focus and before
+    /// translation into actions. `scancode` identifies the physical key while
+    /// `vkey` is its symbol under the current keyboard layout, if any. If a
+    /// press is handled, no action is sent for it; if unhandled, the key is
+    /// translated as usual. Text input is still delivered separately, as
+    /// [`Action::ReceivedCharacter`].
+    ///
+    /// [`ManagerMut::request_raw_keys`]: super::ManagerMut::request_raw_keys
+    Key {
+        scancode: u32,
+        vkey: Option<VirtualKeyCode>,
+        state: ElementState,
+    },
+    /// A drag moved over this widget
+    ///
+    /// This is sent to the drop target (the innermost widget under the
+    /// drag for which [`Widget::accept_drop`] is true) whenever the drag
+    /// moves; see [`ManagerMut::start_drag`]. When the drag leaves the widget
+    /// without dropping, [`Action::DragLeave`] is sent.
+    ///
+    /// [`Widget::accept_drop`]: crate::Widget::accept_drop
+    /// [`ManagerMut::start_drag`]: super::ManagerMut::start_drag
+    DragHover {
+        payload: DragPayload,
+        coord: Coord,
+    },
+    /// A drag was dropped on this widget
+    ///
+    /// This is sent to the drop target, as for [`Event::DragHover`], before
+    /// the drag's source receives [`Event::PressEnd`].
+    Drop {
+        payload: DragPayload,
+        coord: Coord,
+    },
+}
+
+/// The payload of a drag-and-drop operation
+///
+/// This is a cheaply cloneable, type-erased value; drop targets test its
+/// type via [`DragPayload::downcast_ref`].
+///
+/// ```
+/// use kas::event::DragPayload;
+///
+/// let payload = DragPayload::new(3usize);
+/// assert!(payload.is::<usize>());
+/// assert_eq!(payload.downcast_ref::<usize>(), Some(&3));
+/// assert_eq!(payload.downcast_ref::<String>(), None);
+/// ```
+#[derive(Clone)]
+pub struct DragPayload(Rc<dyn Any>);
+
+impl DragPayload {
+    /// Construct from a value
+    #[inline]
+    pub fn new<T: Any>(value: T) -> Self {
+        DragPayload(Rc::new(value))
+    }
+
+    /// True if the payload is of type `T`
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /// Get the payload as type `T`, if it is of that type
+    #[inline]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+/// The payload of a drag of files from outside the application
+///
+/// Files dragged onto a window (e.g. from a file manager) are delivered as a
+/// drag with a [`DragPayload`] of this type. Since the windowing system does
+/// not report the position of such drags on all platforms, the target is
+/// found from the last known mouse position.
+///
+/// ```
+/// use kas::event::{DragPayload, FileDrag};
+///
+/// // A widget accepting only images might have:
+/// fn accept_drop(payload: &DragPayload) -> bool {
+///     payload
+///         .downcast_ref::<FileDrag>()
+///         .map(|files| files.paths.iter().all(|p| p.extension() == Some("png".as_ref())))
+///         .unwrap_or(false)
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDrag {
+    /// Paths of the dragged files
+    pub paths: Vec<PathBuf>,
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DragPayload {{ .. }}")
+    }
 }
 
 /// Source of `EventChild::Press`
@@ -79,6 +298,105 @@ impl PressSource {
     }
 }
 
+/// Mode of a press grab
+///
+/// See [`ManagerMut::request_press_grab_mode`].
+///
+/// [`ManagerMut::request_press_grab_mode`]: super::ManagerMut::request_press_grab_mode
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GrabMode {
+    /// Deliver [`Event::PressMove`] with the cursor position
+    #[default]
+    Grab,
+    /// Hide and confine the cursor, delivering relative motion
+    ///
+    /// This suits fine-adjustment controls, since dragging is not limited by
+    /// the edges of the window or screen. Where the platform reports raw
+    /// mouse motion, [`Event::PressMove`] is sent with this as `delta` and
+    /// without moving `coord` from the press position; otherwise this acts as
+    /// [`GrabMode::Grab`] (with the cursor hidden). Widgets should thus use
+    /// `delta` and not `coord`.
+    ///
+    /// This applies to mouse grabs only; touch grabs behave as
+    /// [`GrabMode::Grab`].
+    Relative,
+}
+
+/// Type used by [`Action::NavKey`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NavKey {
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/// A navigation command from a gamepad, remote control or similar device
+///
+/// Toolkits map device input to these commands, which are handled by
+/// [`Manager::handle_nav_command`](super::Manager::handle_nav_command).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NavCommand {
+    /// Navigate in a direction
+    ///
+    /// This is first sent as [`Action::NavKey`] to the widget with key focus
+    /// (allowing e.g. a slider to adjust its value); when unhandled, key focus
+    /// moves to the nearest widget in that direction.
+    Move(NavKey),
+    /// Activate the widget with key focus (as with the Return key)
+    Activate,
+    /// Cancel editing or close a pop-up (as with the Escape key)
+    ///
+    /// Unlike Escape, this does not clear key focus.
+    Cancel,
+    /// Move key focus to the next widget (as with the Tab key)
+    NextFocus,
+    /// Move key focus to the previous widget (as with Shift+Tab)
+    PreviousFocus,
+}
+
+/// An action deferred until handling of the current event completes
+///
+/// See [`TkWindow::defer`]. Deferred actions are processed in order; those
+/// deferred while processing are processed afterwards.
+///
+/// [`TkWindow::defer`]: crate::TkWindow::defer
+#[derive(Clone, Debug)]
+pub enum Deferred {
+    /// Send an event to a widget (see [`TkWindow::send`])
+    ///
+    /// [`TkWindow::send`]: crate::TkWindow::send
+    Send(WidgetId, Event),
+    /// Give key focus to a widget, or clear key focus
+    ///
+    /// This is ignored if the widget does not allow focus.
+    SetKeyFocus(Option<WidgetId>),
+    /// Move key focus to the next widget (as with the Tab key)
+    NextFocus,
+    /// Move key focus to the previous widget (as with Shift+Tab)
+    PreviousFocus,
+    /// Give character focus to a widget, or clear character focus
+    ///
+    /// The widget losing character focus, if any, receives
+    /// [`Action::LostCharFocus`].
+    SetCharFocus(Option<WidgetId>),
+    /// A toolkit action (see [`TkWindow::send_action`])
+    ///
+    /// For example, [`TkAction::Reconfigure`] after sending events which may
+    /// replace widgets.
+    ///
+    /// [`TkWindow::send_action`]: crate::TkWindow::send_action
+    Toolkit(TkAction),
+    /// Close a window (see [`TkWindow::close_window`])
+    ///
+    /// [`TkWindow::close_window`]: crate::TkWindow::close_window
+    CloseWindow(WindowId),
+}
+
 /// Type used by [`Action::Scroll`]
 #[derive(Clone, Copy, Debug)]
 pub enum ScrollDelta {
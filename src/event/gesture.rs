@@ -0,0 +1,205 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Touch gesture recognition
+
+use std::time::{Duration, Instant};
+
+use super::{Action, ScrollDelta};
+use crate::geom::Coord;
+
+// Maximum duration of a tap or swipe
+const TAP_TIME: Duration = Duration::from_millis(300);
+// Duration after which a stationary touch is a long press
+const LONG_PRESS_TIME: Duration = Duration::from_millis(500);
+// Maximum movement of a tap or long press (logical pixels)
+const TAP_DISTANCE: f32 = 10.0;
+// Minimum movement of a swipe (logical pixels)
+const SWIPE_DISTANCE: f32 = 50.0;
+// Movement after which a two-finger gesture is classified (logical pixels)
+const CLASSIFY_DISTANCE: f32 = 10.0;
+
+fn distance(a: Coord, b: Coord) -> f32 {
+    let d = b - a;
+    ((d.0 as f32).powi(2) + (d.1 as f32).powi(2)).sqrt()
+}
+
+#[derive(Clone, Debug)]
+struct Touch {
+    id: u64,
+    start: Coord,
+    coord: Coord,
+    time: Instant,
+    // true once moved beyond TAP_DISTANCE
+    moved: bool,
+}
+
+// Kind of a two-finger gesture, once classified
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Scroll,
+    Pinch,
+}
+
+/// Recognition of gestures from touch events
+///
+/// A single touch may be a tap, long press or swipe; these are recognised in
+/// addition to the usual press events. Once a second touch starts, both
+/// touches form a two-finger gesture (scroll or pinch) until all touches end.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Gestures {
+    // Tracked touches (at most two)
+    touches: Vec<Touch>,
+    // true while a two-finger gesture is in progress
+    multi: bool,
+    kind: Option<Kind>,
+    // Centre and separation of the two touches when the gesture started and
+    // when last reported
+    origin: (Coord, f32),
+    last: (Coord, f32),
+    long_press_sent: bool,
+}
+
+impl Gestures {
+    /// True while a two-finger gesture is in progress
+    ///
+    /// Touches of such a gesture do not generate press events.
+    pub fn is_multi(&self) -> bool {
+        self.multi
+    }
+
+    // Centre and separation of the two touches
+    fn pair(&self) -> (Coord, f32) {
+        let (a, b) = (self.touches[0].coord, self.touches[1].coord);
+        let centre = Coord((a.0 + b.0) / 2, (a.1 + b.1) / 2);
+        (centre, distance(a, b))
+    }
+
+    /// A touch started
+    ///
+    /// Returns true if this touch starts (or joins) a two-finger gesture; in
+    /// this case, any existing press should be cancelled.
+    pub fn start(&mut self, id: u64, coord: Coord, time: Instant) -> bool {
+        if self.multi {
+            return true;
+        }
+        self.touches.push(Touch {
+            id,
+            start: coord,
+            coord,
+            time,
+            moved: false,
+        });
+        if self.touches.len() == 1 {
+            self.long_press_sent = false;
+            return false;
+        }
+        self.multi = true;
+        self.kind = None;
+        self.origin = self.pair();
+        self.last = self.origin;
+        true
+    }
+
+    /// A touch moved
+    ///
+    /// Returns the action of a two-finger gesture, if any, and the coordinate
+    /// at which it applies.
+    pub fn moved(&mut self, id: u64, coord: Coord, dpi_factor: f32) -> Option<(Coord, Action)> {
+        let touch = self.touches.iter_mut().find(|t| t.id == id)?;
+        touch.coord = coord;
+        if distance(touch.start, coord) > TAP_DISTANCE * dpi_factor {
+            touch.moved = true;
+        }
+        if !self.multi || self.touches.len() < 2 {
+            return None;
+        }
+
+        let (centre, separation) = self.pair();
+        if self.kind.is_none() {
+            let (c0, s0) = self.origin;
+            let threshold = CLASSIFY_DISTANCE * dpi_factor;
+            if (separation - s0).abs() > threshold {
+                self.kind = Some(Kind::Pinch);
+            } else if distance(c0, centre) > threshold {
+                self.kind = Some(Kind::Scroll);
+            }
+        }
+        let action = match self.kind {
+            Some(Kind::Scroll) if centre != self.last.0 => {
+                Action::Scroll(ScrollDelta::PixelDelta(centre - self.last.0))
+            }
+            Some(Kind::Pinch) if separation != self.last.1 && self.last.1 > 0.0 => {
+                Action::Pinch(separation / self.last.1)
+            }
+            _ => return None,
+        };
+        self.last = (centre, separation);
+        Some((centre, action))
+    }
+
+    /// A touch ended
+    ///
+    /// Returns the resulting tap or swipe, if any, and the coordinate at which
+    /// it applies.
+    pub fn end(
+        &mut self,
+        id: u64,
+        coord: Coord,
+        time: Instant,
+        dpi_factor: f32,
+    ) -> Option<(Coord, Action)> {
+        let index = self.touches.iter().position(|t| t.id == id)?;
+        let touch = self.touches.remove(index);
+        if self.multi {
+            if self.touches.is_empty() {
+                self.multi = false;
+            }
+            return None;
+        }
+        if self.long_press_sent || time - touch.time > TAP_TIME {
+            return None;
+        }
+        let d = distance(touch.start, coord);
+        if !touch.moved && d <= TAP_DISTANCE * dpi_factor {
+            Some((touch.start, Action::Tap))
+        } else if d >= SWIPE_DISTANCE * dpi_factor {
+            Some((touch.start, Action::Swipe(coord - touch.start)))
+        } else {
+            None
+        }
+    }
+
+    /// A touch was cancelled
+    pub fn cancel(&mut self, id: u64) {
+        self.touches.retain(|t| t.id != id);
+        if self.touches.is_empty() {
+            self.multi = false;
+        }
+    }
+
+    /// Get the time at which a long press is due, if any
+    pub fn long_press_time(&self) -> Option<Instant> {
+        match self.touches.as_slice() {
+            [touch] if !self.multi && !touch.moved && !self.long_press_sent => {
+                Some(touch.time + LONG_PRESS_TIME)
+            }
+            _ => None,
+        }
+    }
+
+    /// Recognise a long press, if due
+    ///
+    /// Returns the coordinate of the touch.
+    pub fn long_press(&mut self, time: Instant) -> Option<Coord> {
+        match self.long_press_time() {
+            Some(due) if due <= time => {
+                self.long_press_sent = true;
+                Some(self.touches[0].start)
+            }
+            _ => None,
+        }
+    }
+}
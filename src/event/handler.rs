@@ -5,7 +5,9 @@
 
 //! Event handling - handler
 
-use crate::event::{Action, Address, Event, Manager, Response};
+use std::any::Any;
+
+use crate::event::{Action, Address, CursorIcon, Event, Manager, Response};
 use crate::geom::Rect;
 use crate::layout::{AxisInfo, SizeRules};
 use crate::theme::{DrawHandle, SizeHandle};
@@ -66,7 +68,7 @@ pub trait Handler: Widget {
 
 // These implementations are somewhat redundant with Box<dyn Widget>.
 // TODO: do we want to keep both?
-impl<M> Handler for Box<dyn Handler<Msg = M>> {
+impl<M: 'static> Handler for Box<dyn Handler<Msg = M>> {
     type Msg = M;
 
     #[inline]
@@ -90,11 +92,23 @@ impl<M> Handler for Box<dyn Handler<Msg = M>> {
     }
 }
 
-impl<M> Widget for Box<dyn Handler<Msg = M>> {
+impl<M: 'static> Widget for Box<dyn Handler<Msg = M>> {
     fn allow_focus(&self) -> bool {
         self.as_ref().allow_focus()
     }
 
+    fn accept_drop(&self, payload: &super::DragPayload) -> bool {
+        self.as_ref().accept_drop(payload)
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.as_ref().tooltip()
+    }
+
+    fn cursor_icon(&self) -> CursorIcon {
+        self.as_ref().cursor_icon()
+    }
+
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         self.as_mut().size_rules(size_handle, axis)
     }
@@ -108,7 +122,7 @@ impl<M> Widget for Box<dyn Handler<Msg = M>> {
     }
 }
 
-impl<M> WidgetCore for Box<dyn Handler<Msg = M>> {
+impl<M: 'static> WidgetCore for Box<dyn Handler<Msg = M>> {
     #[inline]
     fn core_data(&self) -> &CoreData {
         self.as_ref().core_data()
@@ -131,6 +145,14 @@ impl<M> WidgetCore for Box<dyn Handler<Msg = M>> {
     fn as_widget_mut(&mut self) -> &mut dyn Widget {
         self.as_mut().as_widget_mut()
     }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self.as_ref().as_any()
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.as_mut().as_any_mut()
+    }
 
     #[inline]
     fn len(&self) -> usize {
@@ -155,7 +177,7 @@ impl<M> WidgetCore for Box<dyn Handler<Msg = M>> {
     }
 }
 
-impl<M> Clone for Box<dyn Handler<Msg = M>> {
+impl<M: 'static> Clone for Box<dyn Handler<Msg = M>> {
     fn clone(&self) -> Self {
         #[cfg(feature = "nightly")]
         unsafe {
@@ -187,9 +209,8 @@ impl Manager {
             Event::Action(action) => widget.handle_action(tk, action),
             Event::Identify => Response::Identify(widget.id()),
             Event::PressStart { source, coord } if activable && source.is_primary() => {
-                tk.update_data(&mut |data| {
-                    data.request_press_grab(source, widget.as_widget(), coord)
-                });
+                tk.data_mut()
+                    .request_press_grab(source, widget.as_widget(), coord);
                 Response::None
             }
             Event::PressMove { .. } if activable => {
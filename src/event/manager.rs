@@ -5,11 +5,29 @@
 
 //! Event manager
 
-use std::collections::{hash_map::Entry, HashMap};
+use std::cell::{Cell, RefCell};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
+use log::warn;
+
+#[cfg(feature = "winit")]
+use super::gesture::Gestures;
 use super::*;
-use crate::geom::Coord;
-use crate::{Widget, WidgetId};
+use crate::data::MAX_KEYS;
+use crate::geom::{Coord, Rect, Size};
+use crate::{TkAction, TkWindow, Widget, WidgetId};
+
+/// Time the mouse must rest over a widget before its tooltip is shown
+const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+
+/// Maximum number of deferred actions processed per event
+///
+/// See [`TkWindow::defer`]. Further actions are dropped (with a warning),
+/// since these likely result from a loop.
+pub const MAX_DEFERRED: usize = 64;
 
 /// Highlighting state of a widget
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
@@ -30,13 +48,29 @@ pub struct HighlightState {
     ///
     /// If true, this likely implies `key_focus` is also true.
     pub char_focus: bool,
+    /// The widget's content is invalid (e.g. unparsable input). This is set
+    /// by the widget itself, never by the event manager.
+    pub error: bool,
+}
+
+/// A conflict between accelerator keys
+///
+/// See [`Manager::accel_conflicts`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccelConflict {
+    /// The key
+    pub key: VirtualKeyCode,
+    /// The widget activated by the key
+    pub active: WidgetId,
+    /// The widget whose binding is shadowed
+    pub shadowed: WidgetId,
 }
 
 impl HighlightState {
     /// True if any part of the state is true
     #[inline]
     pub fn any(self) -> bool {
-        self.hover || self.depress || self.key_focus || self.char_focus
+        self.hover || self.depress || self.key_focus || self.char_focus || self.error
     }
 }
 
@@ -47,21 +81,92 @@ struct PressEvent {
     last_coord: Coord,
 }
 
+// State of a drag-and-drop operation
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "winit"), allow(dead_code))]
+struct DragState {
+    // None for a drag of files from outside the application
+    source: Option<PressSource>,
+    payload: DragPayload,
+    label: String,
+    coord: Coord,
+    target: Option<WidgetId>,
+}
+
+/// Event monitor
+///
+/// A monitor observes each event dispatched to the root of a window, along
+/// with the identifier of the target widget (if known) and the resulting
+/// response. See [`Manager::set_monitor`].
+pub type Monitor = dyn FnMut(Option<WidgetId>, &Event, &VoidResponse);
+
+#[derive(Clone, Default)]
+struct MonitorSlot(Option<Rc<RefCell<Box<Monitor>>>>);
+
+impl fmt::Debug for MonitorSlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "Some(<monitor>)"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
 /// Window event manager
 ///
 /// Encapsulation of per-window event state plus supporting methods.
 #[derive(Clone, Debug)]
 pub struct Manager {
     dpi_factor: f64,
+    window_size: Size,
     char_focus: Option<WidgetId>,
     key_focus: Option<WidgetId>,
+    // widget receiving raw key events; see ManagerMut::request_raw_keys
+    raw_keys: Option<WidgetId>,
     hover: Option<WidgetId>,
+    // widgets to redraw on hover change; see Widget::redraw_on_hover
+    hover_redraw: HashSet<WidgetId>,
+    // widgets with a non-default cursor icon; see Widget::cursor_icon
+    cursor_icons: HashMap<WidgetId, CursorIcon>,
+    popup: Option<WidgetId>,
     key_events: Vec<(u32, WidgetId)>,
     last_mouse_coord: Coord,
+    modifiers: ModifiersState,
     mouse_grab: Option<(WidgetId, MouseButton)>,
+    mouse_grab_mode: GrabMode,
+    // Press coordinate of a relative grab, and whether raw motion was received
+    relative_grab: (Coord, bool),
     // TODO: would a VecMap be faster?
     touch_grab: HashMap<u64, PressEvent>,
+    drag: Option<DragState>,
+    // files of a FileDrag already dropped, whose DroppedFile events are ignored
+    #[cfg(feature = "winit")]
+    dropped_files: Vec<std::path::PathBuf>,
+    #[cfg(feature = "winit")]
+    gestures: Gestures,
     accel_keys: HashMap<VirtualKeyCode, WidgetId>,
+    accel_conflicts: Vec<AccelConflict>,
+    // keys set by ManagerMut::set_accel_keys, applied to widgets on configure
+    accel_overrides: HashMap<WidgetId, Vec<VirtualKeyCode>>,
+    // bindings from ManagerMut::add_shortcut, including standard bindings
+    shortcuts: HashMap<Shortcut, (ShortcutTarget, Action)>,
+    // bindings from Widget::shortcuts, collected on configure
+    widget_shortcuts: HashMap<Shortcut, (WidgetId, Action)>,
+    // true after a key press was handled as a shortcut, until the next
+    // character or key press
+    #[cfg(feature = "winit")]
+    shortcut_char: bool,
+    // actions from TkWindow::defer, processed once not handling an event
+    deferred: VecDeque<Deferred>,
+    handling: bool,
+    monitor: MonitorSlot,
+    animation_end: Option<Instant>,
+    layout_animation: bool,
+    frame_requested: Cell<bool>,
+    focused: bool,
+    visible: bool,
+    hover_since: Option<Instant>,
+    tooltip: Option<(WidgetId, Coord)>,
 }
 
 impl Manager {
@@ -74,14 +179,43 @@ impl Manager {
     pub fn new(dpi_factor: f64) -> Self {
         Manager {
             dpi_factor,
+            window_size: Size::ZERO,
             char_focus: None,
             key_focus: None,
+            raw_keys: None,
             hover: None,
+            hover_redraw: HashSet::new(),
+            cursor_icons: HashMap::new(),
+            popup: None,
             key_events: Vec::with_capacity(4),
             last_mouse_coord: Coord::ZERO,
+            modifiers: ModifiersState::default(),
             mouse_grab: None,
+            mouse_grab_mode: GrabMode::Grab,
+            relative_grab: (Coord::ZERO, false),
             touch_grab: HashMap::new(),
+            drag: None,
+            #[cfg(feature = "winit")]
+            dropped_files: vec![],
+            #[cfg(feature = "winit")]
+            gestures: Gestures::default(),
             accel_keys: HashMap::new(),
+            accel_conflicts: vec![],
+            accel_overrides: HashMap::new(),
+            shortcuts: standard_shortcuts(),
+            widget_shortcuts: HashMap::new(),
+            #[cfg(feature = "winit")]
+            shortcut_char: false,
+            deferred: VecDeque::new(),
+            handling: false,
+            monitor: MonitorSlot::default(),
+            animation_end: None,
+            layout_animation: false,
+            frame_requested: Cell::new(false),
+            focused: true,
+            visible: true,
+            hover_since: None,
+            tooltip: None,
         }
     }
 
@@ -96,19 +230,68 @@ impl Manager {
         let mut id = WidgetId::FIRST;
 
         self.accel_keys.clear();
+        self.accel_conflicts.clear();
+        self.widget_shortcuts.clear();
+        self.hover_redraw.clear();
+        self.cursor_icons.clear();
         widget.walk_mut(&mut |widget| {
             map.insert(widget.id(), id);
+            if let Some(keys) = self.accel_overrides.remove(&widget.id()) {
+                widget.core_data_mut().set_keys(&keys);
+            }
             widget.core_data_mut().id = id;
+            if widget.redraw_on_hover() {
+                self.hover_redraw.insert(id);
+            }
+            let icon = widget.cursor_icon();
+            if icon != CursorIcon::Default {
+                self.cursor_icons.insert(id, icon);
+            }
 
+            // Where a key is used by several widgets, the last takes precedence
             for key in widget.core_data().keys() {
-                self.accel_keys.insert(key, id);
+                match self.accel_keys.insert(key, id) {
+                    Some(shadowed) if shadowed != id => {
+                        warn!("Accelerator key {:?}: {} shadows {}", key, id, shadowed);
+                        self.accel_conflicts.push(AccelConflict {
+                            key,
+                            active: id,
+                            shadowed,
+                        });
+                    }
+                    _ => (),
+                }
+            }
+            for (shortcut, action) in widget.shortcuts() {
+                let binding = (id, action.clone());
+                if let Some((shadowed, _)) = self.widget_shortcuts.insert(*shortcut, binding) {
+                    warn!("Shortcut {}: {} shadows {}", shortcut, id, shadowed);
+                }
             }
             id = id.next();
         });
+        self.shortcuts.retain(|_, (target, _)| match target {
+            ShortcutTarget::Focus => true,
+            ShortcutTarget::Widget(id) => match map.get(id) {
+                Some(new_id) => {
+                    *id = *new_id;
+                    true
+                }
+                None => false,
+            },
+        });
 
         self.char_focus = self.char_focus.and_then(|id| map.get(&id).cloned());
         self.key_focus = self.key_focus.and_then(|id| map.get(&id).cloned());
+        self.raw_keys = self.raw_keys.and_then(|id| map.get(&id).cloned());
         self.hover = self.hover.and_then(|id| map.get(&id).cloned());
+        self.popup = self.popup.and_then(|id| map.get(&id).cloned());
+        if let Some(drag) = self.drag.as_mut() {
+            drag.target = drag.target.and_then(|id| map.get(&id).cloned());
+        }
+        self.tooltip = self
+            .tooltip
+            .and_then(|(id, coord)| map.get(&id).map(|id| (*id, coord)));
         for event in &mut self.key_events {
             event.1 = map.get(&event.1).cloned().unwrap();
         }
@@ -128,13 +311,352 @@ impl Manager {
         self.dpi_factor = dpi_factor;
     }
 
+    /// Set an event monitor
+    ///
+    /// The monitor is called for each event dispatched to the window's root
+    /// widget (after handling), and may be used for logging, testing or other
+    /// debugging purposes. Any existing monitor is replaced.
+    ///
+    /// The monitor is passed the identifier of the target widget: for events
+    /// delivered by coordinate, this is the widget found at that coordinate
+    /// (the result of an [`Event::Identify`] query), not the hovered widget.
+    ///
+    /// Note that the monitor may not access the event manager.
+    pub fn set_monitor<F>(&mut self, f: F)
+    where
+        F: FnMut(Option<WidgetId>, &Event, &VoidResponse) + 'static,
+    {
+        self.monitor = MonitorSlot(Some(Rc::new(RefCell::new(Box::new(f)))));
+    }
+
+    /// Remove the event monitor, if any
+    #[inline]
+    pub fn clear_monitor(&mut self) {
+        self.monitor = MonitorSlot(None);
+    }
+
+    /// Dispatch an event to the root widget of a window
+    ///
+    /// (For toolkit use.)
+    ///
+    /// This calls [`Handler::handle`] and the event monitor, if any, then
+    /// processes deferred actions (see [`TkWindow::defer`]) unless called
+    /// while handling another event (e.g. from [`Manager::handle_winit`]).
+    /// With the `profiling`
+    /// feature, the event is counted against the target widget. With the
+    /// `strict` feature, this panics if `addr` is the id of no configured
+    /// widget.
+    pub fn dispatch<W>(
+        widget: &mut W,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> VoidResponse
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        #[cfg(feature = "strict")]
+        {
+            if let Address::Id(id) = addr {
+                crate::layout::strict::check_event_target(widget.as_widget(), id);
+            }
+        }
+        let monitor = tk.data().monitor.0.clone();
+        let is_identify = matches!(event, Event::Identify);
+        // Resolve the widget handling an event delivered by coordinate (only
+        // when reported); this is the result of an Identify query
+        let mut id = match addr {
+            Address::Id(id) => Some(id),
+            Address::Coord(_) if is_identify => None,
+            Address::Coord(_) if monitor.is_some() || cfg!(feature = "profiling") => {
+                match widget.handle(tk, addr, Event::Identify) {
+                    Response::Identify(id) => Some(id),
+                    _ => None,
+                }
+            }
+            Address::Coord(_) => None,
+        };
+
+        let ev = monitor.as_ref().map(|_| event.clone());
+        let r = widget.handle(tk, addr, event);
+        if let (true, Response::Identify(w_id)) = (is_identify, &r) {
+            id = Some(*w_id);
+        }
+
+        #[cfg(feature = "profiling")]
+        {
+            let target = id.and_then(|id| widget.get_by_id(id));
+            let target = target.unwrap_or(widget.as_widget());
+            target.core_data().profile().record_event();
+        }
+        if let (Some(monitor), Some(ev)) = (monitor, ev) {
+            (*monitor.borrow_mut())(id, &ev, &r);
+        }
+
+        if !tk.data().handling {
+            Manager::process_deferred(widget, tk);
+        }
+        r
+    }
+
+    // Mark the start of handling an event; deferred actions are processed
+    // by Manager::process_deferred at the end
+    fn begin_handling(tk: &mut dyn TkWindow) {
+        tk.data_mut().update(|data| {
+            data.handling = true;
+            false
+        });
+    }
+
+    // Process deferred actions, including any deferred meanwhile
+    fn process_deferred<W>(widget: &mut W, tk: &mut dyn TkWindow)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        Manager::begin_handling(tk);
+        let mut count = 0;
+        loop {
+            let mut next = None;
+            tk.data_mut().update(|data| {
+                next = data.deferred.pop_front();
+                false
+            });
+            let action = match next {
+                Some(action) => action,
+                None => break,
+            };
+            if count == MAX_DEFERRED {
+                let n = 1 + tk.data().deferred.len();
+                warn!("Dropping {} deferred actions: limit reached (loop?)", n);
+                tk.data_mut().update(|data| {
+                    data.deferred.clear();
+                    false
+                });
+                break;
+            }
+            count += 1;
+            Manager::apply_deferred(widget, tk, action);
+        }
+        tk.data_mut().update(|data| {
+            data.handling = false;
+            false
+        });
+    }
+
+    fn apply_deferred<W>(widget: &mut W, tk: &mut dyn TkWindow, action: Deferred)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let known = |widget: &W, id: WidgetId| {
+            let found = widget.get_by_id(id).is_some();
+            if !found {
+                warn!("Deferred action for unknown widget {}: {:?}", id, action);
+            }
+            found
+        };
+        match action {
+            Deferred::Send(id, ref event) => {
+                if known(widget, id) {
+                    let ev = event.clone();
+                    let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                }
+            }
+            Deferred::SetKeyFocus(Some(id)) => {
+                if known(widget, id) && widget.get_by_id(id).unwrap().allow_focus() {
+                    tk.data_mut().update(|data| {
+                        data.key_focus = Some(id);
+                        true
+                    });
+                }
+            }
+            Deferred::SetKeyFocus(None) => {
+                tk.data_mut().update(|data| data.key_focus.take().is_some());
+            }
+            Deferred::NextFocus => {
+                tk.data_mut().update(|data| data.next_key_focus(widget.as_widget_mut()));
+            }
+            Deferred::PreviousFocus => {
+                tk.data_mut().update(|data| data.prev_key_focus(widget.as_widget_mut()));
+            }
+            Deferred::SetCharFocus(target) => {
+                if target.map(|id| known(widget, id)).unwrap_or(true) {
+                    let old = tk.data().char_focus;
+                    match target {
+                        Some(id) => tk.data_mut().set_char_focus(id),
+                        None => tk.data_mut().clear_char_focus(),
+                    }
+                    match old {
+                        Some(id) if old != target => {
+                            let ev = Event::Action(Action::LostCharFocus(false));
+                            let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            Deferred::Toolkit(action) => tk.send_action(action),
+            Deferred::CloseWindow(id) => tk.close_window(id),
+        }
+    }
+
+    // Update a drag from `source` (if any) to `coord`, notifying targets
+    #[cfg(feature = "winit")]
+    fn update_drag<W>(
+        widget: &mut W,
+        tk: &mut dyn TkWindow,
+        source: Option<PressSource>,
+        coord: Coord,
+    ) where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let (payload, old_target) = match tk.data().drag.as_ref() {
+            Some(drag) if drag.source == source => (drag.payload.clone(), drag.target),
+            _ => return,
+        };
+        let target = match Manager::dispatch(widget, tk, Address::Coord(coord), Event::Identify) {
+            Response::Identify(id) => find_drop_target(widget.as_widget(), id, &payload),
+            _ => None,
+        };
+        tk.data_mut().update(|data| {
+            let drag = data.drag.as_mut().unwrap();
+            drag.coord = coord;
+            drag.target = target;
+            true
+        });
+
+        if let Some(id) = old_target.filter(|id| target != Some(*id)) {
+            let ev = Event::Action(Action::DragLeave);
+            let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+        }
+        if let Some(id) = target {
+            let ev = Event::DragHover { payload, coord };
+            let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+        }
+    }
+
+    // Add a file hovered over the window to the file drag, starting one if
+    // no drag is in progress
+    #[cfg(feature = "winit")]
+    fn hover_file<W>(widget: &mut W, tk: &mut dyn TkWindow, path: std::path::PathBuf)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let mut paths = match tk.data().drag.as_ref() {
+            None => vec![],
+            Some(drag) => match drag.payload.downcast_ref::<FileDrag>() {
+                Some(files) if drag.source.is_none() => files.paths.clone(),
+                _ => return,
+            },
+        };
+        paths.push(path);
+        let coord = tk.data().last_mouse_coord;
+        tk.data_mut().update(|data| {
+            let target = data.drag.as_ref().and_then(|drag| drag.target);
+            data.drag = Some(DragState {
+                source: None,
+                payload: DragPayload::new(FileDrag { paths: paths.clone() }),
+                label: String::new(),
+                coord,
+                target,
+            });
+            false
+        });
+        Manager::update_drag(widget, tk, None, coord);
+    }
+
+    // Drop a file onto the window
+    //
+    // Where a file drag is in progress, all of its files are dropped at once
+    // (the DroppedFile events for the other files are then ignored).
+    #[cfg(feature = "winit")]
+    fn drop_file<W>(widget: &mut W, tk: &mut dyn TkWindow, path: std::path::PathBuf)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        if let Some(index) = tk.data().dropped_files.iter().position(|p| *p == path) {
+            tk.data_mut().update(|data| {
+                data.dropped_files.remove(index);
+                false
+            });
+            return;
+        }
+        let hovered = tk.data().drag.as_ref().and_then(|drag| match drag.source {
+            None => drag.payload.downcast_ref::<FileDrag>(),
+            Some(_) => None,
+        });
+        let hovered = hovered.filter(|files| files.paths.contains(&path));
+        match hovered.map(|files| files.paths.clone()) {
+            Some(mut paths) => {
+                paths.retain(|p| *p != path);
+                tk.data_mut().update(|data| {
+                    data.dropped_files = paths.clone();
+                    false
+                });
+            }
+            None => {
+                // Without a preceding hover event, drop this file alone
+                let coord = tk.data().last_mouse_coord;
+                let mut path = Some(path);
+                tk.data_mut().update(|data| {
+                    let paths = vec![path.take().unwrap()];
+                    data.drag = Some(DragState {
+                        source: None,
+                        payload: DragPayload::new(FileDrag { paths }),
+                        label: String::new(),
+                        coord,
+                        target: None,
+                    });
+                    false
+                });
+                Manager::update_drag(widget, tk, None, coord);
+            }
+        }
+        Manager::end_drag(widget, tk, None, false);
+    }
+
+    // End a drag from `source` (if any), dropping unless `cancel`
+    #[cfg(feature = "winit")]
+    fn end_drag<W>(
+        widget: &mut W,
+        tk: &mut dyn TkWindow,
+        source: Option<PressSource>,
+        cancel: bool,
+    ) where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        match tk.data().drag.as_ref() {
+            Some(drag) if drag.source == source => (),
+            _ => return,
+        }
+        let mut drag = None;
+        tk.data_mut().update(|data| {
+            drag = data.drag.take();
+            true
+        });
+        let drag = drag.unwrap();
+        if let Some(id) = drag.target {
+            let ev = match cancel {
+                false => Event::Drop {
+                    payload: drag.payload,
+                    coord: drag.coord,
+                },
+                true => Event::Action(Action::DragLeave),
+            };
+            let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+        }
+    }
+
     /// Get the complete highlight state
+    ///
+    /// While the window is unfocused, widgets are not highlighted as hovered
+    /// (though [`Manager::is_hovered`] is still accurate).
     pub fn highlight_state(&self, w_id: WidgetId) -> HighlightState {
         HighlightState {
-            hover: self.is_hovered(w_id),
+            hover: self.focused && self.is_hovered(w_id),
             depress: self.is_depressed(w_id),
             key_focus: self.key_focus(w_id),
             char_focus: self.char_focus(w_id),
+            error: false,
         }
     }
 
@@ -164,6 +686,15 @@ impl Manager {
         false
     }
 
+    /// Get the mouse cursor icon to show
+    ///
+    /// This is that of the hovered widget (see [`Widget::cursor_icon`]).
+    #[inline]
+    pub fn cursor_icon(&self) -> CursorIcon {
+        let icon = self.hover.and_then(|id| self.cursor_icons.get(&id));
+        icon.cloned().unwrap_or_default()
+    }
+
     /// Check whether the given widget is visually depressed
     #[inline]
     pub fn is_depressed(&self, w_id: WidgetId) -> bool {
@@ -185,24 +716,515 @@ impl Manager {
         false
     }
 
-    #[cfg(feature = "winit")]
-    fn set_hover(&mut self, w_id: Option<WidgetId>) -> bool {
-        if self.hover != w_id {
-            self.hover = w_id;
-            return true;
+    /// Set the hovered widget, returning the widgets to redraw
+    ///
+    /// This only decides whether a redraw is needed; the toolkit may still
+    /// repaint the whole window (see [`Widget::redraw_on_hover`]).
+    #[cfg(feature = "winit")]
+    fn set_hover(&mut self, w_id: Option<WidgetId>) -> [Option<WidgetId>; 2] {
+        if self.hover == w_id {
+            return [None, None];
+        }
+        let old = std::mem::replace(&mut self.hover, w_id);
+        let redraw = |id: Option<WidgetId>| id.filter(|id| self.hover_redraw.contains(id));
+        [redraw(old), redraw(w_id)]
+    }
+
+    /// Update the hovered widget, redrawing where required
+    #[cfg(feature = "winit")]
+    fn update_hover(tk: &mut dyn TkWindow, w_id: Option<WidgetId>) {
+        let mut redraw = [None, None];
+        tk.data_mut().update(|data| {
+            redraw = data.set_hover(w_id);
+            false
+        });
+        for id in redraw.iter().filter_map(|id| *id) {
+            tk.redraw(id);
+        }
+    }
+
+    #[inline]
+    pub fn last_mouse_coord(&self) -> Coord {
+        self.last_mouse_coord
+    }
+
+    /// Get the state of keyboard modifiers (shift, ctrl, etc.)
+    ///
+    /// This is updated on each keyboard and mouse event.
+    #[inline]
+    pub fn modifiers(&self) -> ModifiersState {
+        self.modifiers
+    }
+
+    /// Set the window size
+    ///
+    /// (For toolkit use.) This should be called whenever the window's layout
+    /// is solved.
+    #[inline]
+    pub fn set_window_size(&mut self, size: Size) {
+        self.window_size = size;
+    }
+
+    /// Get the window size
+    ///
+    /// Widgets drawing overlays (e.g. [`Popover`]) may use this to keep them
+    /// within the window.
+    ///
+    /// [`Popover`]: crate::widget::Popover
+    #[inline]
+    pub fn window_size(&self) -> Size {
+        self.window_size
+    }
+
+    /// Get the current pop-up owner, if any
+    #[inline]
+    pub fn popup(&self) -> Option<WidgetId> {
+        self.popup
+    }
+
+    /// Request another animation frame
+    ///
+    /// Unlike [`ManagerMut::request_animation`], this may be called while
+    /// drawing: the window is redrawn once more after the current frame.
+    /// Widgets animating indefinitely (e.g. [`Spinner`]) should call this each
+    /// time they are drawn; their animation then stops while they are not
+    /// drawn, for example when hidden.
+    ///
+    /// [`Spinner`]: crate::widget::Spinner
+    #[inline]
+    pub fn request_frame(&self) {
+        self.frame_requested.set(true);
+    }
+
+    /// True if the window has input focus
+    ///
+    /// The window is redrawn when this changes. See also [`Manager::is_idle`].
+    #[inline]
+    pub fn window_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// True if the window is idle: without input focus or not visible
+    ///
+    /// While idle, animation frames are not drawn (animations resume when the
+    /// window becomes active), cosmetic timers
+    /// ([`Callback::Cosmetic`](super::Callback::Cosmetic)) are suspended,
+    /// hover highlighting is disabled and tooltips are not shown. (The hovered
+    /// widget is still tracked, e.g. for mouse-wheel scrolling.) Widgets may also
+    /// use this to suspend other work which only affects appearance.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        !self.focused || !self.visible
+    }
+
+    /// Set whether the window is visible
+    ///
+    /// (For toolkit use.) The toolkit should call this when the window is
+    /// minimised or restored, where known. Returns true if the visibility
+    /// changed, in which case the window should be redrawn.
+    pub fn set_visible(&mut self, visible: bool) -> bool {
+        let changed = visible != self.visible;
+        self.visible = visible;
+        changed
+    }
+
+    /// Check whether layout should be solved before drawing
+    ///
+    /// (For toolkit use.) This should be called before drawing; if it returns
+    /// true, the window's layout should be solved again.
+    pub fn layout_frame(&mut self) -> bool {
+        let layout = self.layout_animation;
+        if self.animation_end.is_none() {
+            self.layout_animation = false;
+        }
+        layout
+    }
+
+    /// Check whether another animation frame is required
+    ///
+    /// (For toolkit use.) This should be called after drawing; if it returns
+    /// true, the window should be redrawn again.
+    pub fn animation_frame(&mut self) -> bool {
+        let requested = self.frame_requested.replace(false);
+        if self.is_idle() {
+            // Animations compute their state from the time, thus pause
+            return false;
+        } else if requested {
+            return true;
+        }
+        match self.animation_end {
+            Some(end) if end > Instant::now() => true,
+            Some(_) => {
+                // Draw once more after the end, so that the final
+                // state is shown.
+                self.animation_end = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the widget whose tooltip is shown, if any
+    ///
+    /// Also returns the mouse position at which the tooltip was opened. The
+    /// toolkit should draw the text of [`Widget::tooltip`] near this position.
+    #[inline]
+    pub fn tooltip(&self) -> Option<(WidgetId, Coord)> {
+        self.tooltip
+    }
+
+    /// Get the time at which a tooltip is due, if any
+    ///
+    /// (For toolkit use.) When this time is reached, the toolkit should call
+    /// [`Manager::show_tooltip`].
+    pub fn tooltip_time(&self) -> Option<Instant> {
+        let blocked = self.tooltip.is_some() || self.popup.is_some() || self.mouse_grab.is_some();
+        if blocked || self.is_idle() {
+            return None;
+        }
+        self.hover_since.map(|time| time + TOOLTIP_DELAY)
+    }
+
+    /// Show the hovered widget's tooltip, if due
+    ///
+    /// (For toolkit use.) Returns true if a tooltip was opened, in which case
+    /// the window should be redrawn.
+    pub fn show_tooltip(&mut self, widget: &dyn Widget) -> bool {
+        match self.tooltip_time() {
+            Some(time) if time <= Instant::now() => (),
+            _ => return false,
+        }
+        self.hover_since = None;
+        let id = match self.hover {
+            Some(id) => id,
+            None => return false,
+        };
+        match widget.get_by_id(id).and_then(|w| w.tooltip()) {
+            Some(_) => {
+                self.tooltip = Some((id, self.last_mouse_coord));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn hide_tooltip(&mut self) -> bool {
+        self.hover_since = None;
+        self.tooltip.take().is_some()
+    }
+
+    #[cfg(feature = "winit")]
+    fn restart_tooltip_timer(&mut self) -> bool {
+        self.hover_since = self.hover.map(|_| Instant::now());
+        false
+    }
+
+    #[cfg(feature = "winit")]
+    fn set_modifiers(&mut self, modifiers: ModifiersState) -> bool {
+        self.modifiers = modifiers;
+        false
+    }
+
+    #[cfg(feature = "winit")]
+    fn set_last_mouse_coord(&mut self, coord: Coord) -> bool {
+        self.last_mouse_coord = coord;
+        false
+    }
+
+    #[cfg(feature = "winit")]
+    fn mouse_grab(&self) -> Option<(WidgetId, MouseButton)> {
+        self.mouse_grab
+    }
+
+    #[cfg(feature = "winit")]
+    fn end_mouse_grab(&mut self, button: MouseButton) -> bool {
+        if self.mouse_grab.map(|g| g.1 == button).unwrap_or(false) {
+            self.mouse_grab = None;
+            self.mouse_grab_mode = GrabMode::Grab;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True while a drag-and-drop operation is in progress
+    #[inline]
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// True if `w_id` is the target of the current drag, if any
+    ///
+    /// Drop targets may use this to highlight themselves.
+    #[inline]
+    pub fn is_drop_target(&self, w_id: WidgetId) -> bool {
+        self.drag.as_ref().map(|d| d.target == Some(w_id)).unwrap_or(false)
+    }
+
+    /// Get the label, position and acceptance of the current drag, if any
+    ///
+    /// The toolkit should draw this via [`DrawHandle::drag_ghost`] over all
+    /// other content. (For toolkit use.)
+    ///
+    /// This is `None` for drags of files from outside the application, since
+    /// the windowing system draws these.
+    ///
+    /// [`DrawHandle::drag_ghost`]: crate::theme::DrawHandle::drag_ghost
+    pub fn drag_ghost(&self) -> Option<(&str, Coord, bool)> {
+        let drag = self.drag.as_ref().filter(|drag| drag.source.is_some())?;
+        Some((&drag.label, drag.coord, drag.target.is_some()))
+    }
+
+    /// True if the cursor should be hidden and confined
+    ///
+    /// This is the case during a mouse grab with [`GrabMode::Relative`].
+    /// (For toolkit use.)
+    #[inline]
+    pub fn cursor_grab(&self) -> bool {
+        self.mouse_grab.is_some() && self.mouse_grab_mode == GrabMode::Relative
+    }
+
+    /// Get the widget bound to an accelerator key, if any
+    #[inline]
+    pub fn accel_binding(&self, key: VirtualKeyCode) -> Option<WidgetId> {
+        self.accel_keys.get(&key).cloned()
+    }
+
+    /// List active accelerator key bindings, ordered by key
+    pub fn accel_bindings(&self) -> Vec<(VirtualKeyCode, WidgetId)> {
+        let mut bindings: Vec<_> = self.accel_keys.iter().map(|(k, id)| (*k, *id)).collect();
+        bindings.sort();
+        bindings
+    }
+
+    /// List accelerator key conflicts
+    ///
+    /// Accelerator keys are bound when the window is configured, in widget
+    /// order (children before their parent). Where multiple widgets use the
+    /// same key, the last widget shadows the others; each shadowed binding is
+    /// listed here (and logged as a warning).
+    #[inline]
+    pub fn accel_conflicts(&self) -> &[AccelConflict] {
+        &self.accel_conflicts
+    }
+
+    /// Get the binding of a keyboard shortcut, if any
+    pub fn shortcut_binding(&self, shortcut: Shortcut) -> Option<(ShortcutTarget, Action)> {
+        match self.widget_shortcuts.get(&shortcut) {
+            Some((id, action)) => Some((ShortcutTarget::Widget(*id), action.clone())),
+            None => self.shortcuts.get(&shortcut).cloned(),
+        }
+    }
+
+    /// Get whether this widget receives raw key events
+    ///
+    /// See [`ManagerMut::request_raw_keys`].
+    #[inline]
+    pub fn raw_keys(&self, w_id: WidgetId) -> bool {
+        self.raw_keys == Some(w_id)
+    }
+
+    #[cfg(feature = "winit")]
+    fn set_raw_motion(&mut self) -> bool {
+        self.relative_grab.1 = true;
+        false
+    }
+
+    #[cfg(feature = "winit")]
+    fn touch_grab(&self, touch_id: u64) -> Option<PressEvent> {
+        self.touch_grab.get(&touch_id).cloned()
+    }
+
+    #[cfg(feature = "winit")]
+    fn update_touch_coord(&mut self, touch_id: u64, coord: Coord) -> bool {
+        if let Some(v) = self.touch_grab.get_mut(&touch_id) {
+            v.last_coord = coord;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[cfg(feature = "winit")]
+    fn end_touch_grab(&mut self, touch_id: u64) -> bool {
+        self.touch_grab.remove(&touch_id).is_some()
+    }
+
+    // Move key focus to the next focusable widget, wrapping to the first
+    //
+    // The walk visits widgets in order of identifier.
+    fn next_key_focus(&mut self, widget: &mut dyn Widget) -> bool {
+        let start = self.key_focus;
+        let (mut first, mut found) = (None, None);
+        widget.walk(&mut |w| {
+            if w.allow_focus() {
+                first = first.or(Some(w.id()));
+                if found.is_none() && start.map(|id| w.id() > id).unwrap_or(true) {
+                    found = Some(w.id());
+                }
+            }
+        });
+        self.key_focus = found.or(first);
+        start != self.key_focus
+    }
+
+    // Move key focus to the previous focusable widget, wrapping to the last
+    fn prev_key_focus(&mut self, widget: &mut dyn Widget) -> bool {
+        let start = self.key_focus;
+        let (mut last, mut found) = (None, None);
+        widget.walk(&mut |w| {
+            if w.allow_focus() {
+                last = Some(w.id());
+                if start.map(|id| w.id() < id).unwrap_or(true) {
+                    found = Some(w.id());
+                }
+            }
+        });
+        self.key_focus = found.or(last);
+        start != self.key_focus
+    }
+
+    // Move key focus to the nearest focusable widget in direction `key`
+    //
+    // Returns false if there is no such widget.
+    fn spatial_key_focus(&mut self, widget: &dyn Widget, key: NavKey) -> bool {
+        let current = match self.key_focus.and_then(|id| widget.get_by_id(id)) {
+            Some(w) => w.rect(),
+            None => return false,
+        };
+        let centre = |r: Rect| (2 * r.pos.0 + r.size.0 as i32, 2 * r.pos.1 + r.size.1 as i32);
+        let c = centre(current);
+        let mut best: Option<(i64, WidgetId)> = None;
+        widget.walk(&mut |w| {
+            let rect = w.rect();
+            if !w.allow_focus() || Some(w.id()) == self.key_focus || rect.size.0 == 0 {
+                return;
+            }
+            let p = centre(rect);
+            let (along, across) = match key {
+                NavKey::Left => (c.0 - p.0, p.1 - c.1),
+                NavKey::Right => (p.0 - c.0, p.1 - c.1),
+                NavKey::Up => (c.1 - p.1, p.0 - c.0),
+                NavKey::Down => (p.1 - c.1, p.0 - c.0),
+                _ => return,
+            };
+            if along <= 0 {
+                return;
+            }
+            // Prefer widgets in line with the current widget
+            let score = along as i64 + 2 * (across as i64).abs();
+            if best.map(|b| score < b.0).unwrap_or(true) {
+                best = Some((score, w.id()));
+            }
+        });
+        match best {
+            Some((_, id)) => {
+                self.key_focus = Some(id);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Mutable access to the event manager
+///
+/// This is obtained from [`TkWindow::data_mut`]. Each method records the
+/// effect of its change on the window, scheduling the minimal action required
+/// (if any): for example, opening a pop-up requires a redraw while binding a
+/// shortcut requires none. Read access to the [`Manager`] is available via
+/// deref.
+///
+/// Since the handle holds a mutable borrow on the [`TkWindow`], it must be
+/// dropped before the window is used again; in particular, updates cannot be
+/// nested. Usually the handle is used for a single call:
+/// ```ignore
+/// tk.data_mut().request_press_grab(source, self, coord);
+/// ```
+pub struct ManagerMut<'a> {
+    mgr: &'a mut Manager,
+    action: &'a mut TkAction,
+}
+
+impl<'a> std::ops::Deref for ManagerMut<'a> {
+    type Target = Manager;
+    fn deref(&self) -> &Manager {
+        self.mgr
+    }
+}
+
+impl<'a> ManagerMut<'a> {
+    /// Construct
+    ///
+    /// (For toolkit use.) Required actions are merged into `action` (as by
+    /// [`TkWindow::send_action`]), which the toolkit should apply after event
+    /// handling.
+    #[inline]
+    pub fn new(mgr: &'a mut Manager, action: &'a mut TkAction) -> Self {
+        ManagerMut { mgr, action }
+    }
+
+    fn send_action(&mut self, action: TkAction) {
+        *self.action = (*self.action).max(action);
+    }
+
+    // Update state with a closure, which returns true if a redraw is required
+    pub(crate) fn update<F: FnOnce(&mut Manager) -> bool>(&mut self, f: F) {
+        if f(self.mgr) {
+            self.send_action(TkAction::Redraw);
         }
-        false
     }
 
-    #[inline]
-    pub fn last_mouse_coord(&self) -> Coord {
-        self.last_mouse_coord
+    // Queue an action; see TkWindow::defer
+    pub(crate) fn defer(&mut self, action: Deferred) {
+        self.mgr.deferred.push_back(action);
     }
 
-    #[cfg(feature = "winit")]
-    fn set_last_mouse_coord(&mut self, coord: Coord) -> bool {
-        self.last_mouse_coord = coord;
-        false
+    /// Set or clear the pop-up owner
+    ///
+    /// A pop-up (e.g. a drop-down menu) may extend beyond its owner's rect,
+    /// where coordinate-based event routing cannot find it. While a pop-up is
+    /// open, hover queries ([`Event::Identify`]) are therefore addressed to the
+    /// owner by id (the cursor position is available from
+    /// [`Manager::last_mouse_coord`]), and mouse presses go to the owner, which
+    /// should close the pop-up on a press outside. Received characters are
+    /// also sent to the owner (when no widget has character focus), and the
+    /// owner receives key focus. Pressing Escape closes the pop-up.
+    ///
+    /// The window is redrawn.
+    pub fn set_popup(&mut self, w_id: Option<WidgetId>) {
+        let mgr = &mut *self.mgr;
+        mgr.popup = w_id;
+        mgr.key_focus = w_id.or(mgr.key_focus);
+        mgr.char_focus = None;
+        self.send_action(TkAction::Redraw);
+    }
+
+    /// Request animation frames
+    ///
+    /// The window is redrawn repeatedly (as often as the toolkit is able) for
+    /// at least `duration` from now, starting immediately. Animated widgets
+    /// should compute their state from the current time when drawing.
+    pub fn request_animation(&mut self, duration: Duration) {
+        let end = Instant::now() + duration;
+        if self.mgr.animation_end.map(|t| t < end).unwrap_or(true) {
+            self.mgr.animation_end = Some(end);
+        }
+        self.send_action(TkAction::Redraw);
+    }
+
+    /// Request animation frames with layout
+    ///
+    /// As [`ManagerMut::request_animation`], except that the window's layout
+    /// is also solved before each frame (and once after the end). This allows
+    /// widgets to animate their size (e.g. [`Expander`]) by computing
+    /// [`Widget::size_rules`] from the current time.
+    ///
+    /// [`Expander`]: crate::widget::Expander
+    /// [`Widget::size_rules`]: crate::Widget::size_rules
+    pub fn request_layout_animation(&mut self, duration: Duration) {
+        self.mgr.layout_animation = true;
+        self.request_animation(duration);
     }
 
     /// Request a mouse grab on the given input source
@@ -216,31 +1238,51 @@ impl Manager {
     /// affects parents intercepting [`Response::Unhandled`] events).
     ///
     /// In the case that multiple widgets attempt to grab the same source, only
-    /// the first will be successful.
+    /// the first will be successful. Returns true if granted, in which case
+    /// the window is redrawn (showing the widget as depressed).
     pub fn request_press_grab(
         &mut self,
         source: PressSource,
         widget: &dyn Widget,
         coord: Coord,
     ) -> bool {
+        self.request_press_grab_mode(source, widget, coord, GrabMode::Grab)
+    }
+
+    /// Request a mouse grab with the given mode
+    ///
+    /// This is [`ManagerMut::request_press_grab`] except that, for mouse grabs,
+    /// `mode` may request relative motion: see [`GrabMode::Relative`].
+    pub fn request_press_grab_mode(
+        &mut self,
+        source: PressSource,
+        widget: &dyn Widget,
+        coord: Coord,
+        mode: GrabMode,
+    ) -> bool {
+        let mgr = &mut *self.mgr;
         let w_id = widget.id();
+        let focus = (mgr.key_focus, mgr.char_focus);
         if widget.allow_focus() {
-            if self.key_focus.is_some() {
-                self.key_focus = Some(w_id);
+            if mgr.key_focus.is_some() {
+                mgr.key_focus = Some(w_id);
             }
-            self.char_focus = None;
+            mgr.char_focus = None;
         }
+        let focus_changed = focus != (mgr.key_focus, mgr.char_focus);
 
-        match source {
+        let granted = match source {
             PressSource::Mouse(button) => {
-                if self.mouse_grab.is_none() {
-                    self.mouse_grab = Some((w_id, button));
+                if mgr.mouse_grab.is_none() {
+                    mgr.mouse_grab = Some((w_id, button));
+                    mgr.mouse_grab_mode = mode;
+                    mgr.relative_grab = (coord, false);
                     true
                 } else {
                     false
                 }
             }
-            PressSource::Touch(touch_id) => match self.touch_grab.entry(touch_id) {
+            PressSource::Touch(touch_id) => match mgr.touch_grab.entry(touch_id) {
                 Entry::Occupied(_) => false,
                 Entry::Vacant(v) => {
                     v.insert(PressEvent {
@@ -251,70 +1293,165 @@ impl Manager {
                     true
                 }
             },
+        };
+        if granted || focus_changed {
+            self.send_action(TkAction::Redraw);
         }
+        granted
     }
 
-    #[cfg(feature = "winit")]
-    fn mouse_grab(&self) -> Option<(WidgetId, MouseButton)> {
-        self.mouse_grab
+    /// Start a drag-and-drop operation
+    ///
+    /// This requires that widget `w_id` holds the press grab for `source`
+    /// (see [`ManagerMut::request_press_grab`]), and is typically called on
+    /// [`Event::PressMove`] once the press has moved some distance. Returns
+    /// false (and does nothing) if this is not the case or a drag is already
+    /// in progress.
+    ///
+    /// While the press continues, the innermost widget under the press for
+    /// which [`Widget::accept_drop`] is true becomes the drop target and
+    /// receives [`Event::DragHover`] events. On release, the target receives
+    /// [`Event::Drop`]; the source then receives [`Event::PressEnd`] as
+    /// usual. The `label` is drawn near the press as a "ghost".
+    pub fn start_drag(
+        &mut self,
+        source: PressSource,
+        w_id: WidgetId,
+        payload: DragPayload,
+        label: String,
+    ) -> bool {
+        let mgr = &mut *self.mgr;
+        if mgr.drag.is_some() {
+            return false;
+        }
+        let coord = match source {
+            PressSource::Mouse(button) if mgr.mouse_grab == Some((w_id, button)) => {
+                mgr.last_mouse_coord
+            }
+            PressSource::Touch(touch_id) => match mgr.touch_grab.get(&touch_id) {
+                Some(grab) if grab.start_id == w_id => grab.last_coord,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        mgr.drag = Some(DragState {
+            source: Some(source),
+            payload,
+            label,
+            coord,
+            target: None,
+        });
+        self.send_action(TkAction::Redraw);
+        true
     }
 
-    #[cfg(feature = "winit")]
-    fn end_mouse_grab(&mut self, button: MouseButton) -> bool {
-        if self.mouse_grab.map(|g| g.1 == button).unwrap_or(false) {
-            self.mouse_grab = None;
-            true
-        } else {
-            false
+    /// Change the accelerator keys of a widget
+    ///
+    /// Existing bindings of `w_id` are replaced by `keys`, effective
+    /// immediately (the widget's [`CoreData`](crate::CoreData) is updated when
+    /// the window is next configured). If any key is bound to another widget
+    /// (see [`Manager::accel_binding`]), nothing is changed and false is
+    /// returned. The window is not reconfigured, but is redrawn.
+    ///
+    /// Panics if more than four keys are given.
+    pub fn set_accel_keys(&mut self, w_id: WidgetId, keys: &[VirtualKeyCode]) -> bool {
+        assert!(
+            keys.len() <= MAX_KEYS,
+            "ManagerMut::set_accel_keys: found {} keys; max supported is {}",
+            keys.len(),
+            MAX_KEYS,
+        );
+        let mgr = &mut *self.mgr;
+        let taken = |key| match mgr.accel_keys.get(key) {
+            Some(id) => *id != w_id,
+            None => false,
+        };
+        if keys.iter().any(taken) {
+            return false;
         }
+        mgr.accel_keys.retain(|_, id| *id != w_id);
+        for key in keys {
+            mgr.accel_keys.insert(*key, w_id);
+        }
+        mgr.accel_overrides.insert(w_id, keys.to_vec());
+        self.send_action(TkAction::Redraw);
+        true
     }
 
-    #[cfg(feature = "winit")]
-    fn touch_grab(&self, touch_id: u64) -> Option<PressEvent> {
-        self.touch_grab.get(&touch_id).cloned()
+    /// Bind a keyboard shortcut
+    ///
+    /// When `shortcut` is pressed, `action` is sent to the `target` widget.
+    /// For example, [`Action::Activate`] activates a button, while a widget
+    /// may handle [`Action::Shortcut`] by returning a message. Shortcuts are
+    /// matched before other key handling (but after raw keys; see
+    /// [`ManagerMut::request_raw_keys`]).
+    ///
+    /// Bindings to a widget are removed when the widget is removed from the
+    /// window. Standard bindings (Ctrl+C, Ctrl+X and Ctrl+V to copy, cut and
+    /// paste, and Tab to move key focus) are present by default and may be
+    /// replaced; bindings declared by widgets (see [`Widget::shortcuts`])
+    /// take precedence.
+    ///
+    /// Returns the replaced binding, if any.
+    pub fn add_shortcut(
+        &mut self,
+        shortcut: Shortcut,
+        target: ShortcutTarget,
+        action: Action,
+    ) -> Option<(ShortcutTarget, Action)> {
+        self.mgr.shortcuts.insert(shortcut, (target, action))
     }
 
-    #[cfg(feature = "winit")]
-    fn update_touch_coord(&mut self, touch_id: u64, coord: Coord) -> bool {
-        if let Some(v) = self.touch_grab.get_mut(&touch_id) {
-            v.last_coord = coord;
+    /// Remove a keyboard shortcut binding
+    ///
+    /// This removes the binding made via [`ManagerMut::add_shortcut`] (or a
+    /// standard binding), returning it, if any. Bindings declared by widgets
+    /// are unaffected.
+    pub fn remove_shortcut(&mut self, shortcut: Shortcut) -> Option<(ShortcutTarget, Action)> {
+        self.mgr.shortcuts.remove(&shortcut)
+    }
+
+    /// Request raw key events
+    ///
+    /// While granted, the widget receives [`Event::Key`] for each key press
+    /// and release, whatever the focus. This suits widgets interpreting keys
+    /// directly, such as a game viewport or a field capturing a key binding.
+    /// Only one widget may hold this grab; returns true if granted (or
+    /// already held by `w_id`). The grab is held until released via
+    /// [`ManagerMut::release_raw_keys`].
+    pub fn request_raw_keys(&mut self, w_id: WidgetId) -> bool {
+        match self.mgr.raw_keys {
+            Some(id) => id == w_id,
+            None => {
+                self.mgr.raw_keys = Some(w_id);
+                true
+            }
+        }
+    }
+
+    /// Release raw key events
+    ///
+    /// Returns true if `w_id` held the grab.
+    pub fn release_raw_keys(&mut self, w_id: WidgetId) -> bool {
+        if self.mgr.raw_keys == Some(w_id) {
+            self.mgr.raw_keys = None;
             true
         } else {
             false
         }
     }
 
-    #[cfg(feature = "winit")]
-    fn end_touch_grab(&mut self, touch_id: u64) -> bool {
-        self.touch_grab.remove(&touch_id).is_some()
-    }
-
-    #[cfg(feature = "winit")]
-    fn next_key_focus(&mut self, widget: &mut dyn Widget) -> bool {
-        let start = self.key_focus;
-        let mut id = start.map(|id| id.next()).unwrap_or(WidgetId::FIRST);
-        let end = widget.id();
-        while id <= end {
-            if widget
-                .get_by_id(id)
-                .map(|w| w.allow_focus())
-                .unwrap_or(false)
-            {
-                self.key_focus = Some(id);
-                return start != Some(id);
-            }
-            id = id.next();
+    pub(crate) fn set_char_focus(&mut self, id: WidgetId) {
+        if self.mgr.key_focus.is_some() {
+            self.mgr.key_focus = Some(id);
         }
-        self.key_focus = None;
-        start != None
+        self.mgr.char_focus = Some(id);
+        self.send_action(TkAction::Redraw);
     }
 
-    pub(crate) fn set_char_focus(&mut self, id: WidgetId) -> bool {
-        if self.key_focus.is_some() {
-            self.key_focus = Some(id);
-        }
-        self.char_focus = Some(id);
-        true
+    pub(crate) fn clear_char_focus(&mut self) {
+        self.mgr.char_focus = None;
+        self.send_action(TkAction::Redraw);
     }
 }
 
@@ -336,6 +1473,73 @@ impl Manager {
         use log::trace;
         use winit::event::{ElementState, MouseScrollDelta, TouchPhase, WindowEvent::*};
         trace!("Event: {:?}", event);
+        Manager::begin_handling(tk);
+
+        match &event {
+            KeyboardInput { input, .. } => {
+                let modifiers = input.modifiers;
+                tk.data_mut().update(|data| data.set_modifiers(modifiers));
+            }
+            CursorMoved { modifiers, .. }
+            | MouseWheel { modifiers, .. }
+            | MouseInput { modifiers, .. } => {
+                let modifiers = *modifiers;
+                tk.data_mut().update(|data| data.set_modifiers(modifiers));
+            }
+            _ => (),
+        }
+
+        // Input dismisses any tooltip; cursor motion restarts the timer below
+        match &event {
+            CursorMoved { .. }
+            | CursorLeft { .. }
+            | KeyboardInput { .. }
+            | MouseWheel { .. }
+            | MouseInput { .. }
+            | Touch(_) => {
+                tk.data_mut().update(|data| data.hide_tooltip());
+            }
+            _ => (),
+        }
+
+        // The raw key grab sees keys before translation, which is skipped for
+        // handled presses
+        let raw_key = match (&event, tk.data().raw_keys) {
+            (KeyboardInput { input, .. }, Some(id)) => {
+                let ev = Event::Key {
+                    scancode: input.scancode,
+                    vkey: input.virtual_keycode,
+                    state: input.state,
+                };
+                match Manager::dispatch(widget, tk, Address::Id(id), ev) {
+                    Response::Unhandled(_) => None,
+                    r @ _ => Some(r),
+                }
+            }
+            _ => None,
+        };
+
+        // Shortcuts are matched before translation
+        let shortcut = match &event {
+            KeyboardInput { input, .. } if input.state == ElementState::Pressed => {
+                tk.data_mut().update(|data| {
+                    data.shortcut_char = false;
+                    false
+                });
+                match (raw_key.is_some(), input.virtual_keycode) {
+                    (false, Some(vkey)) => {
+                        let shortcut = Shortcut::new(vkey).with_modifiers(input.modifiers);
+                        Manager::handle_shortcut(widget, tk, input.scancode, shortcut)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        // If character focus is lost, the widget is notified below
+        let char_focus = tk.data().char_focus;
+        let mut cancelled = false;
 
         let response = match event {
             // Resized(size) [handled by toolkit]
@@ -345,43 +1549,97 @@ impl Manager {
                 Response::None
             }
             // Destroyed
-            // DroppedFile(PathBuf),
-            // HoveredFile(PathBuf),
-            // HoveredFileCancelled,
+            DroppedFile(path) => {
+                Manager::drop_file(widget, tk, path);
+                Response::None
+            }
+            HoveredFile(path) => {
+                Manager::hover_file(widget, tk, path);
+                Response::None
+            }
+            HoveredFileCancelled => {
+                Manager::end_drag(widget, tk, None, true);
+                Response::None
+            }
+            ReceivedCharacter(_) if tk.data().shortcut_char => {
+                // The character of a handled shortcut
+                tk.data_mut().update(|data| {
+                    data.shortcut_char = false;
+                    false
+                });
+                Response::None
+            }
             ReceivedCharacter(c) if c != '\u{1b}' /* escape */ => {
-                if let Some(id) = tk.data().char_focus {
+                if let Some(id) = tk.data().char_focus.or(tk.data().popup) {
                     let ev = Event::Action(Action::ReceivedCharacter(c));
-                    widget.handle(tk, Address::Id(id), ev)
+                    Manager::dispatch(widget, tk, Address::Id(id), ev)
                 } else {
                     Response::None
                 }
             }
-            // Focused(bool),
+            Focused(focused) => {
+                tk.data_mut().update(|data| {
+                    data.focused = focused;
+                    data.hide_tooltip();
+                    true
+                });
+                // Hover highlighting (see highlight_state) changes with focus
+                Response::None
+            }
+            KeyboardInput { input, .. }
+                if input.state == ElementState::Pressed && raw_key.is_some() =>
+            {
+                raw_key.unwrap()
+            }
+            KeyboardInput { input, .. }
+                if input.state == ElementState::Pressed
+                    && input.virtual_keycode == Some(VirtualKeyCode::Escape)
+                    && tk.data().drag.is_some() =>
+            {
+                // Escape cancels a drag; the press itself continues
+                let source = tk.data().drag.as_ref().unwrap().source;
+                Manager::end_drag(widget, tk, source, true);
+                Response::None
+            }
+            KeyboardInput { .. } if shortcut.is_some() => shortcut.unwrap(),
             KeyboardInput { input, .. } => {
                 let char_focus = tk.data().char_focus.is_some();
                 match (input.scancode, input.state, input.virtual_keycode) {
                     (_, ElementState::Pressed, Some(vkey)) if char_focus => match vkey {
                         VirtualKeyCode::Escape => {
-                            tk.update_data(&mut |data| {
+                            tk.data_mut().update(|data| {
                                 data.char_focus = None;
                                 true
                             });
+                            cancelled = true;
                             Response::None
                         }
-                        _ => Response::None,
+                        vkey @ _ => {
+                            // Navigation keys may move an editing caret
+                            let nav = nav_key(vkey).and_then(|key| tk.data().char_focus.map(|id| (key, id)));
+                            if let Some((key, id)) = nav {
+                                let ev = Event::Action(Action::NavKey(key));
+                                Manager::dispatch(widget, tk, Address::Id(id), ev)
+                            } else { Response::None }
+                        }
                     },
                     (scancode, ElementState::Pressed, Some(vkey)) if !char_focus => match vkey {
-                        VirtualKeyCode::Tab => {
-                            tk.update_data(&mut |data| data.next_key_focus(widget.as_widget_mut()));
-                            Response::None
+                        VirtualKeyCode::Tab if input.modifiers.ctrl => {
+                            let id = tk.data().key_focus.or(tk.data().hover);
+                            if let Some(id) = id {
+                                let ev = Event::Action(Action::NextTab(input.modifiers.shift));
+                                Manager::dispatch(widget, tk, Address::Id(id), ev)
+                            } else { Response::None }
                         }
                         VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
-                            if let Some(id) = tk.data().key_focus {
+                            // Without key focus, activate any default widget
+                            let accel = tk.data().accel_keys.get(&VirtualKeyCode::Return).cloned();
+                            if let Some(id) = tk.data().key_focus.or(accel) {
                                 let ev = Event::Action(Action::Activate);
-                                let r =  widget.handle(tk, Address::Id(id), ev);
+                                let r =  Manager::dispatch(widget, tk, Address::Id(id), ev);
 
                                 // Add to key_events for visual feedback
-                                tk.update_data(&mut |data| {
+                                tk.data_mut().update(|data| {
                                     for item in &data.key_events {
                                         if item.1 == id {
                                             return false;
@@ -394,22 +1652,38 @@ impl Manager {
                             } else { Response::None }
                         }
                         VirtualKeyCode::Escape => {
-                            tk.update_data(&mut |data| {
-                                if data.key_focus.is_some() {
+                            let mut cancel = None;
+                            tk.data_mut().update(|data| {
+                                if data.popup.is_some() {
+                                    data.popup = None;
+                                    true
+                                } else if data.key_focus.is_some() {
                                     data.key_focus = None;
                                     true
                                 } else {
+                                    cancel = data.accel_keys.get(&VirtualKeyCode::Escape).cloned();
                                     false
                                 }
                             });
-                            Response::None
+                            // With nothing else to cancel, activate any cancel widget
+                            if let Some(id) = cancel {
+                                let ev = Event::Action(Action::Activate);
+                                Manager::dispatch(widget, tk, Address::Id(id), ev)
+                            } else { Response::None }
                         }
                         vkey @ _ => {
-                            if let Some(id) = tk.data().accel_keys.get(&vkey).cloned() {
+                            let nav = nav_key(vkey).and_then(|key| tk.data().key_focus.map(|id| (key, id)));
+                            if let Some((key, id)) = nav {
+                                let ev = Event::Action(Action::NavKey(key));
+                                Manager::dispatch(widget, tk, Address::Id(id), ev)
+                            } else if let (VirtualKeyCode::F2, Some(id)) = (vkey, tk.data().key_focus) {
+                                let ev = Event::Action(Action::BeginEdit);
+                                Manager::dispatch(widget, tk, Address::Id(id), ev)
+                            } else if let Some(id) = tk.data().accel_keys.get(&vkey).cloned() {
                                 let ev = Event::Action(Action::Activate);
-                                let r =  widget.handle(tk, Address::Id(id), ev);
+                                let r =  Manager::dispatch(widget, tk, Address::Id(id), ev);
 
-                                tk.update_data(&mut |data| {
+                                tk.data_mut().update(|data| {
                                     for item in &data.key_events {
                                         if item.1 == id {
                                             return false;
@@ -423,7 +1697,7 @@ impl Manager {
                         }
                     },
                     (scancode, ElementState::Released, _) => {
-                        tk.update_data(&mut |data| {
+                        tk.data_mut().update(|data| {
                             let r = 'outer: loop {
                                 for (i, item) in data.key_events.iter().enumerate() {
                                     // We must match scancode not vkey since the
@@ -447,41 +1721,61 @@ impl Manager {
                 ..
             } => {
                 let coord = position.to_physical(tk.data().dpi_factor).into();
+                let delta = coord - tk.data().last_mouse_coord();
+                tk.data_mut().update(|data| data.set_last_mouse_coord(coord));
 
-                // Update hovered widget
-                let w_id = match widget.handle(tk, Address::Coord(coord), Event::Identify) {
+                // Update hovered widget; an open pop-up captures the cursor.
+                // Hover is tracked while unfocused since it routes the mouse
+                // wheel, but tooltips are not shown.
+                let addr = match tk.data().popup {
+                    Some(id) => Address::Id(id),
+                    None => Address::Coord(coord),
+                };
+                let w_id = match Manager::dispatch(widget, tk, addr, Event::Identify) {
                     Response::Identify(w_id) => Some(w_id),
                     _ => None,
                 };
-                tk.update_data(&mut |data| data.set_hover(w_id));
+                Manager::update_hover(tk, w_id);
+                if tk.data().focused {
+                    tk.data_mut().update(|data| data.restart_tooltip_timer());
+                }
+                if let (Some(id), None) = (w_id, tk.data().mouse_grab()) {
+                    let ev = Event::CursorMove { coord };
+                    let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                }
 
-                let r = if let Some((grab_id, button)) = tk.data().mouse_grab() {
+                // Relative grabs with raw motion are served by handle_winit_motion
+                let raw = tk.data().cursor_grab() && tk.data().relative_grab.1;
+                if let (Some((grab_id, button)), false) = (tk.data().mouse_grab(), raw) {
                     let source = PressSource::Mouse(button);
-                    let delta = coord - tk.data().last_mouse_coord();
                     let ev = Event::PressMove { source, coord, delta };
-                    widget.handle(tk, Address::Id(grab_id), ev)
+                    let r = Manager::dispatch(widget, tk, Address::Id(grab_id), ev);
+                    Manager::update_drag(widget, tk, Some(source), coord);
+                    r
                 } else {
+                    // Some platforms report motion while dragging files
+                    Manager::update_drag(widget, tk, None, coord);
                     // We don't forward move events without a grab
                     Response::None
-                };
-
-                tk.update_data(&mut |data| data.set_last_mouse_coord(coord));
-                r
+                }
             }
             // CursorEntered { .. },
             CursorLeft { .. } => {
-                tk.update_data(&mut |data| data.set_hover(None));
+                Manager::update_hover(tk, None);
                 Response::None
             }
             MouseWheel { delta, phase, modifiers, .. } => {
-                let _ = (phase, modifiers); // TODO: do we have a use for these?
+                let _ = phase; // TODO: do we have a use for this?
                 let action = Action::Scroll(match delta {
+                    // By convention, shift+wheel scrolls horizontally
+                    MouseScrollDelta::LineDelta(x, y) if modifiers.shift && x == 0.0 =>
+                        ScrollDelta::LineDelta(-y, 0.0),
                     MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
                     MouseScrollDelta::PixelDelta(logical_position) =>
                         ScrollDelta::PixelDelta(logical_position.to_physical(tk.data().dpi_factor).into()),
                 });
                 if let Some(id) = tk.data().hover {
-                    widget.handle(tk, Address::Id(id), Event::Action(action))
+                    Manager::dispatch(widget, tk, Address::Id(id), Event::Action(action))
                 } else {
                     Response::None
                 }
@@ -493,6 +1787,9 @@ impl Manager {
             } => {
                 let coord = tk.data().last_mouse_coord();
                 let source = PressSource::Mouse(button);
+                if state == ElementState::Released {
+                    Manager::end_drag(widget, tk, Some(source), false);
+                }
 
                 let r = if let Some((grab_id, _)) = tk.data().mouse_grab() {
                     // Mouse grab active: send events there
@@ -507,7 +1804,7 @@ impl Manager {
                             coord,
                         },
                     };
-                    widget.handle(tk, Address::Id(grab_id), ev)
+                    Manager::dispatch(widget, tk, Address::Id(grab_id), ev)
                 } else if let Some(id) = tk.data().hover {
                     // No mouse grab, but we have a hover target
                     let ev = match state {
@@ -519,14 +1816,14 @@ impl Manager {
                             coord,
                         },
                     };
-                    widget.handle(tk, Address::Id(id), ev)
+                    Manager::dispatch(widget, tk, Address::Id(id), ev)
                 } else {
                     // This happens when there is no widget and on click-release
                     // when the cursor is no longer over the window.
                     Response::None
                 };
                 if state == ElementState::Released {
-                    tk.update_data(&mut |data| data.end_mouse_grab(button));
+                    tk.data_mut().update(|data| data.end_mouse_grab(button));
                 }
                 r
             }
@@ -536,47 +1833,96 @@ impl Manager {
             Touch(touch) => {
                 let source = PressSource::Touch(touch.id);
                 let coord = touch.location.to_physical(tk.data().dpi_factor).into();
+                let dpi_factor = tk.data().dpi_factor as f32;
+                // Touches of a two-finger gesture do not generate press events
+                let multi = tk.data().gestures.is_multi();
                 match touch.phase {
                     TouchPhase::Started => {
-                        let ev = Event::PressStart { source, coord };
-                        widget.handle(tk, Address::Coord(coord), ev)
+                        let mut starts_multi = false;
+                        let now = Instant::now();
+                        tk.data_mut().update(|data| {
+                            starts_multi = data.gestures.start(touch.id, coord, now);
+                            false
+                        });
+                        if !starts_multi {
+                            let ev = Event::PressStart { source, coord };
+                            Manager::dispatch(widget, tk, Address::Coord(coord), ev)
+                        } else {
+                            if !multi {
+                                Manager::cancel_touch_grabs(widget, tk);
+                            }
+                            Response::None
+                        }
                     }
                     TouchPhase::Moved => {
-                        if let Some(PressEvent { start_id, last_coord, .. }) = tk.data().touch_grab(touch.id) {
+                        let mut gesture = None;
+                        tk.data_mut().update(|data| {
+                            gesture = data.gestures.moved(touch.id, coord, dpi_factor);
+                            false
+                        });
+                        if let Some((coord, action)) = gesture {
+                            let ev = Event::Action(action);
+                            Manager::dispatch(widget, tk, Address::Coord(coord), ev)
+                        } else if let Some(grab) = tk.data().touch_grab(touch.id) {
+                            let PressEvent { start_id, last_coord, .. } = grab;
                             let action = Event::PressMove {
                                 source,
                                 coord,
                                 delta: coord - last_coord,
                             };
-                            let r = widget.handle(tk, Address::Id(start_id), action);
-                            tk.update_data(&mut |data| data.update_touch_coord(touch.id, coord));
+                            let r = Manager::dispatch(widget, tk, Address::Id(start_id), action);
+                            tk.data_mut().update(|data| data.update_touch_coord(touch.id, coord));
+                            Manager::update_drag(widget, tk, Some(source), coord);
                             r
                         } else {
                             Response::None
                         }
                     }
                     TouchPhase::Ended => {
-                        if let Some(PressEvent { start_id, cur_id, .. }) = tk.data().touch_grab(touch.id) {
-                            let action = Event::PressEnd {
-                                source,
-                                start_id: Some(start_id),
-                                end_id: Some(cur_id),
-                                coord,
-                            };
-                            let r = widget.handle(tk, Address::Id(start_id), action);
-                            tk.update_data(&mut |data| data.end_touch_grab(touch.id));
-                            r
+                        let mut gesture = None;
+                        let now = Instant::now();
+                        tk.data_mut().update(|data| {
+                            gesture = data.gestures.end(touch.id, coord, now, dpi_factor);
+                            false
+                        });
+                        let r = if multi {
+                            Response::None
                         } else {
-                            let action = Event::PressEnd {
-                                source,
-                                start_id: None,
-                                end_id: None,
-                                coord,
-                            };
-                            widget.handle(tk, Address::Coord(coord), action)
+                            Manager::end_drag(widget, tk, Some(source), false);
+                            if let Some(grab) = tk.data().touch_grab(touch.id) {
+                                let PressEvent { start_id, cur_id, .. } = grab;
+                                let action = Event::PressEnd {
+                                    source,
+                                    start_id: Some(start_id),
+                                    end_id: Some(cur_id),
+                                    coord,
+                                };
+                                let addr = Address::Id(start_id);
+                                let r = Manager::dispatch(widget, tk, addr, action);
+                                tk.data_mut().update(|data| data.end_touch_grab(touch.id));
+                                r
+                            } else {
+                                let action = Event::PressEnd {
+                                    source,
+                                    start_id: None,
+                                    end_id: None,
+                                    coord,
+                                };
+                                Manager::dispatch(widget, tk, Address::Coord(coord), action)
+                            }
+                        };
+                        if let Some((coord, action)) = gesture {
+                            let ev = Event::Action(action);
+                            let _ = Manager::dispatch(widget, tk, Address::Coord(coord), ev);
                         }
+                        r
                     }
                     TouchPhase::Cancelled => {
+                        tk.data_mut().update(|data| {
+                            data.gestures.cancel(touch.id);
+                            false
+                        });
+                        Manager::end_drag(widget, tk, Some(source), true);
                         if let Some(PressEvent { start_id, .. }) = tk.data().touch_grab(touch.id) {
                             let action = Event::PressEnd {
                                 source,
@@ -584,8 +1930,8 @@ impl Manager {
                                 end_id: None,
                                 coord,
                             };
-                            let r = widget.handle(tk, Address::Id(start_id), action);
-                            tk.update_data(&mut |data| data.end_touch_grab(touch.id));
+                            let r = Manager::dispatch(widget, tk, Address::Id(start_id), action);
+                            tk.data_mut().update(|data| data.end_touch_grab(touch.id));
                             r
                         } else {
                             Response::None
@@ -604,5 +1950,260 @@ impl Manager {
             }
             Response::Msg(_) => unreachable!(),
         };
+
+        if let Some(id) = char_focus {
+            if tk.data().char_focus != Some(id) {
+                let ev = Event::Action(Action::LostCharFocus(cancelled));
+                let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+            }
+        }
+
+        Manager::process_deferred(widget, tk);
+    }
+}
+
+#[cfg(feature = "winit")]
+impl Manager {
+    /// Handle raw mouse motion (`winit::event::DeviceEvent::MouseMotion`)
+    ///
+    /// This is only required during a [`Manager::cursor_grab`], and is
+    /// otherwise ignored. (For toolkit use.)
+    pub fn handle_winit_motion<W>(widget: &mut W, tk: &mut dyn crate::TkWindow, delta: (f64, f64))
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let grab = match tk.data().mouse_grab() {
+            Some(grab) if tk.data().cursor_grab() => grab,
+            _ => return,
+        };
+        tk.data_mut().update(|data| data.set_raw_motion());
+        let delta = Coord(delta.0.round() as i32, delta.1.round() as i32);
+        if delta != Coord::ZERO {
+            let source = PressSource::Mouse(grab.1);
+            let coord = tk.data().relative_grab.0;
+            let ev = Event::PressMove {
+                source,
+                coord,
+                delta,
+            };
+            let _ = Manager::dispatch(widget, tk, Address::Id(grab.0), ev);
+        }
+    }
+
+    /// Get the time at which a long press is due, if any
+    ///
+    /// (For toolkit use.) When this time is reached, the toolkit should call
+    /// [`Manager::handle_long_press`].
+    pub fn long_press_time(&self) -> Option<Instant> {
+        self.gestures.long_press_time()
+    }
+
+    /// Send [`Action::LongPress`], if due
+    ///
+    /// (For toolkit use.)
+    pub fn handle_long_press<W>(widget: &mut W, tk: &mut dyn TkWindow)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let mut coord = None;
+        let now = Instant::now();
+        tk.data_mut().update(|data| {
+            coord = data.gestures.long_press(now);
+            false
+        });
+        if let Some(coord) = coord {
+            let ev = Event::Action(Action::LongPress);
+            let _ = Manager::dispatch(widget, tk, Address::Coord(coord), ev);
+        }
+    }
+
+    // Cancel all touch presses (on start of a two-finger gesture)
+    fn cancel_touch_grabs<W>(widget: &mut W, tk: &mut dyn TkWindow)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let grabs = tk.data().touch_grab.iter();
+        let mut grabs: Vec<_> = grabs.map(|(id, grab)| (*id, grab.clone())).collect();
+        grabs.sort_by_key(|grab| grab.0);
+        for (touch_id, grab) in grabs {
+            let source = PressSource::Touch(touch_id);
+            Manager::end_drag(widget, tk, Some(source), true);
+            let ev = Event::PressEnd {
+                source,
+                start_id: Some(grab.start_id),
+                end_id: None,
+                coord: grab.last_coord,
+            };
+            let _ = Manager::dispatch(widget, tk, Address::Id(grab.start_id), ev);
+            tk.data_mut().update(|data| data.end_touch_grab(touch_id));
+        }
+    }
+
+    // Handle a key press bound as a shortcut, if any
+    #[cfg(feature = "winit")]
+    fn handle_shortcut<W>(
+        widget: &mut W,
+        tk: &mut dyn TkWindow,
+        scancode: u32,
+        shortcut: Shortcut,
+    ) -> Option<VoidResponse>
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        let (id, action) = match tk.data().shortcut_binding(shortcut)? {
+            (ShortcutTarget::Widget(id), action) => (Some(id), action),
+            (ShortcutTarget::Focus, _) if tk.data().char_focus.is_some() => return None,
+            (ShortcutTarget::Focus, action) => (tk.data().key_focus, action),
+        };
+        tk.data_mut().update(|data| {
+            data.shortcut_char = true;
+            false
+        });
+
+        let r = match id {
+            Some(id) => {
+                let activate = matches!(action, Action::Activate);
+                let r = Manager::dispatch(widget, tk, Address::Id(id), Event::Action(action));
+                if activate {
+                    // Add to key_events for visual feedback
+                    tk.data_mut().update(|data| {
+                        for item in &data.key_events {
+                            if item.1 == id {
+                                return false;
+                            }
+                        }
+                        data.key_events.push((scancode, id));
+                        true
+                    });
+                }
+                r
+            }
+            None => Response::unhandled_action(action),
+        };
+        Some(match r {
+            // The focused widget may move focus internally
+            Response::Unhandled(Event::Action(Action::NextFocus)) => {
+                tk.data_mut().update(|data| data.next_key_focus(widget.as_widget_mut()));
+                Response::None
+            }
+            Response::Unhandled(Event::Action(Action::PreviousFocus)) => {
+                tk.data_mut().update(|data| data.prev_key_focus(widget.as_widget_mut()));
+                Response::None
+            }
+            r @ _ => r,
+        })
     }
 }
+
+impl Manager {
+    /// Handle a navigation command (for toolkit use)
+    ///
+    /// See [`NavCommand`]. Toolkits may map gamepad (or remote control) input
+    /// to these commands, allowing UIs to be used without keyboard or mouse.
+    pub fn handle_nav_command<W>(widget: &mut W, tk: &mut dyn TkWindow, command: NavCommand)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        Manager::begin_handling(tk);
+        Manager::handle_nav_command_inner(widget, tk, command);
+        Manager::process_deferred(widget, tk);
+    }
+
+    fn handle_nav_command_inner<W>(widget: &mut W, tk: &mut dyn TkWindow, command: NavCommand)
+    where
+        W: Widget + Handler<Msg = VoidMsg> + ?Sized,
+    {
+        tk.data_mut().update(|data| data.hide_tooltip());
+        let focus = tk.data().char_focus.or(tk.data().key_focus);
+        match command {
+            NavCommand::Move(key) => {
+                let id = match focus {
+                    Some(id) => id,
+                    None => {
+                        tk.data_mut().update(|data| data.next_key_focus(widget.as_widget_mut()));
+                        return;
+                    }
+                };
+                let ev = Event::Action(Action::NavKey(key));
+                let r = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                let editing = tk.data().char_focus.is_some();
+                if let (Response::Unhandled(_), false) = (r, editing) {
+                    tk.data_mut().update(|data| data.spatial_key_focus(widget.as_widget(), key));
+                }
+            }
+            NavCommand::Activate => {
+                let accel = tk.data().accel_keys.get(&VirtualKeyCode::Return).cloned();
+                if let Some(id) = tk.data().key_focus.or(accel) {
+                    let ev = Event::Action(Action::Activate);
+                    let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                }
+            }
+            NavCommand::Cancel => {
+                let mut cancel = None;
+                let mut lost_char_focus = None;
+                tk.data_mut().update(|data| {
+                    if let Some(id) = data.char_focus.take() {
+                        lost_char_focus = Some(id);
+                        true
+                    } else if data.popup.is_some() {
+                        data.popup = None;
+                        true
+                    } else {
+                        cancel = data.accel_keys.get(&VirtualKeyCode::Escape).cloned();
+                        false
+                    }
+                });
+                if let Some(id) = lost_char_focus {
+                    let ev = Event::Action(Action::LostCharFocus(true));
+                    let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                } else if let Some(id) = cancel {
+                    let ev = Event::Action(Action::Activate);
+                    let _ = Manager::dispatch(widget, tk, Address::Id(id), ev);
+                }
+            }
+            NavCommand::NextFocus => {
+                tk.data_mut().update(|data| data.next_key_focus(widget.as_widget_mut()));
+            }
+            NavCommand::PreviousFocus => {
+                tk.data_mut().update(|data| data.prev_key_focus(widget.as_widget_mut()));
+            }
+        }
+    }
+}
+
+// Find the innermost widget containing `id` (or `id` itself) which accepts
+// a drop of `payload`
+#[cfg(feature = "winit")]
+fn find_drop_target(widget: &dyn Widget, id: WidgetId, payload: &DragPayload) -> Option<WidgetId> {
+    let mut found = None;
+    if id < widget.id() {
+        for i in 0..widget.len() {
+            let child = widget.get(i).unwrap();
+            if id <= child.id() {
+                found = find_drop_target(child, id, payload);
+                break;
+            }
+        }
+    }
+    match widget.accept_drop(payload) {
+        true => found.or(Some(widget.id())),
+        false => found,
+    }
+}
+
+/// Translate navigation keys
+#[cfg(feature = "winit")]
+fn nav_key(vkey: VirtualKeyCode) -> Option<NavKey> {
+    use VirtualKeyCode as VK;
+    Some(match vkey {
+        VK::Left => NavKey::Left,
+        VK::Right => NavKey::Right,
+        VK::Up => NavKey::Up,
+        VK::Down => NavKey::Down,
+        VK::PageUp => NavKey::PageUp,
+        VK::PageDown => NavKey::PageDown,
+        VK::Home => NavKey::Home,
+        VK::End => NavKey::End,
+        _ => return None,
+    })
+}
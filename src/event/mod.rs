@@ -52,29 +52,66 @@
 //! Highlighting information can be obtained directly in the `draw` method, and
 //! press events provide information on their start and end widget.
 //!
+//! ## Keyboard events
+//!
+//! Keys are normally translated into an [`Action`] for the widget with key or
+//! character focus (or with a matching accelerator key). Key combinations
+//! bound as shortcuts (see [`ManagerMut::add_shortcut`] and
+//! [`Widget::shortcuts`]) are matched first. Widgets interpreting
+//! keys themselves, such as a game viewport, may instead request raw
+//! [`Event::Key`] events via [`ManagerMut::request_raw_keys`].
+//!
+//! Tab and Shift+Tab cycle key focus through widgets for which
+//! [`Widget::allow_focus`] is true, in the order of the widget tree (usually
+//! the layout order); the widget with key focus is highlighted and receives
+//! keyboard actions such as [`Action::Activate`] (Return or Space) and
+//! [`Action::NavKey`]. While some widget has key focus, clicking another
+//! focusable widget moves key focus to it.
+//!
+//! ## Drag and drop
+//!
+//! A widget holding a press grab may start a drag with a typed
+//! [`DragPayload`] via [`ManagerMut::start_drag`]. Widgets accepting the
+//! payload (see [`Widget::accept_drop`]) receive [`Event::DragHover`] while
+//! the drag is over them, then [`Event::Drop`] or [`Action::DragLeave`].
+//! Files dragged onto the window from outside the application are delivered
+//! likewise, with a [`FileDrag`] payload.
+//!
 //! [`WidgetId`]: crate::WidgetId
+//! [`Widget::accept_drop`]: crate::Widget::accept_drop
+//! [`Widget::shortcuts`]: crate::Widget::shortcuts
+//! [`Widget::allow_focus`]: crate::Widget::allow_focus
 
 mod callback;
 #[cfg(not(feature = "winit"))]
 mod enums;
 mod events;
+#[cfg(feature = "winit")]
+mod gesture;
 mod handler;
 mod manager;
 mod response;
+mod shortcut;
 
 use std::fmt::Debug;
 // use std::path::PathBuf;
 
 #[cfg(feature = "winit")]
-pub use winit::event::{MouseButton, VirtualKeyCode};
+pub use winit::event::{ElementState, ModifiersState, MouseButton, VirtualKeyCode};
+#[cfg(feature = "winit")]
+pub use winit::window::CursorIcon;
 
 pub use callback::Callback;
 #[cfg(not(feature = "winit"))]
-pub use enums::{MouseButton, VirtualKeyCode};
+pub use enums::{CursorIcon, ElementState, ModifiersState, MouseButton, VirtualKeyCode};
 pub use events::*;
 pub use handler::Handler;
-pub use manager::{HighlightState, Manager};
+pub use manager::{AccelConflict, HighlightState, Manager, ManagerMut, Monitor, MAX_DEFERRED};
 pub use response::Response;
+#[cfg(feature = "widget")]
+pub(crate) use shortcut::modifiers_prefix;
+pub(crate) use shortcut::standard_shortcuts;
+pub use shortcut::{Shortcut, ShortcutTarget};
 
 /// A void message
 ///
@@ -0,0 +1,147 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Keyboard shortcuts
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Action, ModifiersState, VirtualKeyCode};
+use crate::WidgetId;
+
+/// A key combination: a key plus modifiers
+///
+/// This is displayed in the usual form, e.g. `Ctrl+Shift+S`.
+///
+/// ```
+/// use kas::event::{ModifiersState, Shortcut, VirtualKeyCode};
+///
+/// let mut modifiers = ModifiersState::default();
+/// modifiers.ctrl = true;
+/// let save = Shortcut::new(VirtualKeyCode::S).with_modifiers(modifiers);
+/// assert_eq!(save.to_string(), "Ctrl+S");
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Shortcut {
+    /// Modifiers which must be held
+    pub modifiers: ModifiersState,
+    /// The key
+    pub key: VirtualKeyCode,
+}
+
+impl Shortcut {
+    /// Construct, without modifiers
+    #[inline]
+    pub fn new(key: VirtualKeyCode) -> Self {
+        Shortcut {
+            modifiers: ModifiersState::default(),
+            key,
+        }
+    }
+
+    /// Construct, with the Ctrl modifier
+    #[inline]
+    pub fn ctrl(key: VirtualKeyCode) -> Self {
+        let mut shortcut = Shortcut::new(key);
+        shortcut.modifiers.ctrl = true;
+        shortcut
+    }
+
+    /// Set the modifiers (inline)
+    #[inline]
+    pub fn with_modifiers(mut self, modifiers: ModifiersState) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// True if `key` is a modifier key (Shift, Ctrl, Alt or Logo)
+    pub fn is_modifier_key(key: VirtualKeyCode) -> bool {
+        use VirtualKeyCode::*;
+        matches!(
+            key,
+            LShift | RShift | LControl | RControl | LAlt | RAlt | LWin | RWin
+        )
+    }
+}
+
+/// The target of a shortcut binding
+///
+/// See [`ManagerMut::add_shortcut`](super::ManagerMut::add_shortcut).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutTarget {
+    /// The widget with key focus
+    ///
+    /// Such bindings are ignored while a widget has character focus, which
+    /// receives the key as a character instead (e.g. Ctrl+C as `'\u{03}'`).
+    /// Without key focus, the action is handled as if unhandled by a widget;
+    /// e.g. [`Action::NextFocus`] moves key focus to the first widget.
+    Focus,
+    /// The given widget
+    Widget(WidgetId),
+}
+
+// Bindings present by default
+pub(crate) fn standard_shortcuts() -> HashMap<Shortcut, (ShortcutTarget, Action)> {
+    let shift = ModifiersState {
+        shift: true,
+        ..Default::default()
+    };
+    let bindings = vec![
+        (Shortcut::ctrl(VirtualKeyCode::C), Action::Copy),
+        (Shortcut::ctrl(VirtualKeyCode::X), Action::Cut),
+        (Shortcut::ctrl(VirtualKeyCode::V), Action::Paste),
+        (Shortcut::new(VirtualKeyCode::Tab), Action::NextFocus),
+        (
+            Shortcut::new(VirtualKeyCode::Tab).with_modifiers(shift),
+            Action::PreviousFocus,
+        ),
+    ];
+    bindings
+        .into_iter()
+        .map(|(shortcut, action)| (shortcut, (ShortcutTarget::Focus, action)))
+        .collect()
+}
+
+// Names of held modifiers, each followed by `+`
+pub(crate) fn modifiers_prefix(modifiers: ModifiersState) -> String {
+    let mut s = String::new();
+    let names = [
+        (modifiers.ctrl, "Ctrl+"),
+        (modifiers.alt, "Alt+"),
+        (modifiers.shift, "Shift+"),
+        (modifiers.logo, "Logo+"),
+    ];
+    for (_, name) in names.iter().filter(|n| n.0) {
+        s.push_str(name);
+    }
+    s
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use VirtualKeyCode::*;
+        write!(f, "{}", modifiers_prefix(self.modifiers))?;
+        let name = match self.key {
+            Key1 => "1",
+            Key2 => "2",
+            Key3 => "3",
+            Key4 => "4",
+            Key5 => "5",
+            Key6 => "6",
+            Key7 => "7",
+            Key8 => "8",
+            Key9 => "9",
+            Key0 => "0",
+            Back => "Backspace",
+            Return => "Enter",
+            key => return write!(f, "{:?}", key),
+        };
+        write!(f, "{}", name)
+    }
+}
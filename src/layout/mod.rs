@@ -6,103 +6,21 @@
 //! Layout solver
 //!
 //! This is only of interest if building a custom widget with children.
+//!
+//! The solvers and [`SizeRules`] are provided by the `no_std` `kas-layout`
+//! crate and re-exported here.
 
-mod grid_solver;
-mod misc_solver;
 mod row_solver;
-mod size_rules;
 mod sizer;
-mod storage;
-
-use kas::geom::Size;
-
-pub use grid_solver::{GridChildInfo, GridSetter, GridSolver};
-pub use misc_solver::SingleSetter;
-pub use row_solver::{RowPositionSolver, RowSetter, RowSolver};
-pub use size_rules::{Margins, SizeRules};
-pub use sizer::{solve, RulesSetter, RulesSolver};
-pub use storage::{
-    DynGridStorage, DynRowStorage, FixedGridStorage, FixedRowStorage, GridStorage, RowStorage,
-    RowTemp, Storage,
+#[cfg(feature = "strict")]
+pub(crate) mod strict;
+
+pub use kas_layout::{
+    Anchor, AxisInfo, Direction, DynDirection, DynGridStorage, DynRowStorage, FixedGridStorage,
+    FixedRowStorage, FlowSetter, FlowSolver, FlowStorage, GridChildInfo, GridSetter, GridSolver,
+    GridStorage, Horizontal, LayoutOverrides, Margins, OverlaySetter, OverlaySolver,
+    OverlayStorage, Placement, RowSetter, RowSolver, RowStorage, RowTemp, RulesSetter, RulesSolver,
+    SingleSetter, SizeRules, Storage, Vertical,
 };
-
-/// Information on which axis is being resized
-///
-/// Also conveys the size of the other axis, if fixed.
-#[derive(Copy, Clone, Debug)]
-pub struct AxisInfo {
-    vertical: bool,
-    has_fixed: bool,
-    other_axis: u32,
-}
-
-impl AxisInfo {
-    fn new(vertical: bool, fixed: Option<u32>) -> Self {
-        AxisInfo {
-            vertical: vertical,
-            has_fixed: fixed.is_some(),
-            other_axis: fixed.unwrap_or(0),
-        }
-    }
-
-    /// True if the current axis is vertical, false if horizontal
-    #[inline]
-    pub fn vertical(&self) -> bool {
-        self.vertical
-    }
-
-    /// Size of other axis, if fixed and `vertical == self.vertical()`.
-    #[inline]
-    pub fn fixed(&self, vertical: bool) -> Option<u32> {
-        if vertical == self.vertical && self.has_fixed {
-            Some(self.other_axis)
-        } else {
-            None
-        }
-    }
-
-    /// Extract horizontal or vertical component of a [`Size`]
-    #[inline]
-    pub fn extract_size(&self, size: Size) -> u32 {
-        if !self.vertical {
-            size.0
-        } else {
-            size.1
-        }
-    }
-}
-
-pub trait Direction: Copy + Sized + std::fmt::Debug {
-    fn is_vertical(self) -> bool;
-    fn is_horizontal(self) -> bool {
-        !self.is_vertical()
-    }
-}
-
-#[derive(Copy, Clone, Default, Debug)]
-pub struct Horizontal;
-impl Direction for Horizontal {
-    fn is_vertical(self) -> bool {
-        false
-    }
-}
-
-#[derive(Copy, Clone, Default, Debug)]
-pub struct Vertical;
-impl Direction for Vertical {
-    fn is_vertical(self) -> bool {
-        true
-    }
-}
-
-#[derive(Copy, Clone, Debug)]
-pub struct DynDirection(bool);
-impl Direction for DynDirection {
-    fn is_vertical(self) -> bool {
-        self.0
-    }
-}
-impl DynDirection {
-    pub const HORIZONTAL: DynDirection = DynDirection(false);
-    pub const VERTICAL: DynDirection = DynDirection(true);
-}
+pub use row_solver::RowPositionSolver;
+pub use sizer::{child_rules, draw_child, solve};
@@ -7,70 +7,108 @@
 
 use log::trace;
 use std::fmt;
+#[cfg(feature = "profiling")]
+use std::time::Instant;
 
 use super::{AxisInfo, SizeRules};
+use crate::event;
 use crate::geom::{Coord, Rect, Size};
+use crate::theme::{DrawHandle, SizeHandle};
 use crate::{TkWindow, Widget};
 
-/// A [`SizeRules`] solver for layouts
+/// Get the [`SizeRules`] of a child widget
 ///
-/// Typically, a solver is invoked twice, once for each axis, before the
-/// corresponding [`RulesSetter`] is invoked. This is managed by [`solve`].
-///
-/// Implementations require access to storage able to persist between multiple
-/// solver runs and a subsequent setter run. This storage is of type
-/// [`RulesSolver::Storage`] and is passed via reference to the constructor.
-pub trait RulesSolver {
-    /// Type of storage
-    type Storage: Clone;
-
-    /// Type required by [`RulesSolver::for_child`] (see implementation documentation)
-    type ChildInfo;
-
-    /// Called once for each child. For most layouts the order is important.
-    fn for_child<CR: FnOnce(AxisInfo) -> SizeRules>(
-        &mut self,
-        storage: &mut Self::Storage,
-        child_info: Self::ChildInfo,
-        child_rules: CR,
-    );
-
-    /// Called at the end to output [`SizeRules`].
-    ///
-    /// Note that this does not include margins!
-    fn finish<ColIter, RowIter>(
-        self,
-        storage: &mut Self::Storage,
-        col_spans: ColIter,
-        row_spans: RowIter,
-    ) -> SizeRules
-    where
-        ColIter: Iterator<Item = (usize, usize, usize)>,
-        RowIter: Iterator<Item = (usize, usize, usize)>;
+/// Layout widgets should use this in place of [`Widget::size_rules`] to
+/// query each child. Where the child supports [`Widget::size_rules_both`],
+/// the horizontal query measures both axes in one pass and the vertical rules
+/// are kept for the following vertical query. These are used unless that
+/// query fixes a width less than the child's ideal width (in which case
+/// contents such as text may wrap, thus the child is queried again).
+pub fn child_rules<W: Widget + ?Sized>(
+    child: &mut W,
+    size_handle: &mut dyn SizeHandle,
+    axis: AxisInfo,
+) -> SizeRules {
+    #[cfg(feature = "profiling")]
+    let start = Instant::now();
+    let rules = child_rules_impl(child, size_handle, axis);
+    #[cfg(feature = "profiling")]
+    (child.core_data().profile()).record_layout(axis.vertical(), start.elapsed());
+    #[cfg(feature = "inspect")]
+    {
+        child.core_data_mut().layout_rules[axis.vertical() as usize] = Some(rules);
+    }
+    rules
 }
 
-/// Resolves a [`RulesSolver`] solution for each child
-pub trait RulesSetter {
-    /// Type of storage
-    type Storage: Clone;
-
-    /// Type required by [`RulesSolver::for_child`] (see implementation documentation)
-    type ChildInfo;
+fn child_rules_impl<W: Widget + ?Sized>(
+    child: &mut W,
+    size_handle: &mut dyn SizeHandle,
+    axis: AxisInfo,
+) -> SizeRules {
+    if !axis.vertical() {
+        let both = match axis.fixed(false) {
+            None => child.size_rules_both(size_handle),
+            Some(_) => None,
+        };
+        child.core_data_mut().rules_cache = both.map(|(w, h)| (w.ideal_size(), h));
+        if let Some((rules, _)) = both {
+            return rules;
+        }
+    } else if let Some((ideal, h)) = child.core_data_mut().rules_cache.take() {
+        if axis.fixed(true).map(|width| width >= ideal) != Some(false) {
+            return h;
+        }
+    }
+    child.size_rules(size_handle, axis)
+}
 
-    /// Called once for each child. For most layouts the order is important.
-    fn child_rect(&mut self, child_info: Self::ChildInfo) -> Rect;
+/// Draw a child widget
+///
+/// Parent widgets should use this in place of calling [`Widget::draw`]
+/// directly on children. With the `profiling` feature, this counts draws.
+/// With the `strict` feature, this panics if the child draws outside of its
+/// rect (other than within clip regions and overlays).
+#[inline]
+pub fn draw_child<W: Widget + ?Sized>(
+    child: &W,
+    draw_handle: &mut dyn DrawHandle,
+    ev_mgr: &event::Manager,
+) {
+    #[cfg(feature = "profiling")]
+    child.core_data().profile().record_draw();
+    #[cfg(feature = "strict")]
+    let draw_handle = &mut super::strict::StrictDrawHandle::new(draw_handle, child.as_widget());
+    child.draw(draw_handle, ev_mgr);
 }
 
 /// Solve `widget` for `SizeRules` on both axes, horizontal first.
+///
+/// With the `strict` feature, this panics if any widget was not sized on both
+/// axes (see [`child_rules`]) or, where `size` is at least the minimum size
+/// of `widget`, if any widget is assigned a non-empty rect smaller than its
+/// minimum size.
 pub fn solve<L: Widget>(widget: &mut L, tk: &mut dyn TkWindow, size: Size) {
     tk.with_size_handle(&mut |size_handle| {
+        #[cfg(feature = "strict")]
+        super::strict::reset_rules(widget);
         // We call size_rules not because we want the result, but because our
         // spec requires that we do so before calling set_rect.
+        #[cfg(feature = "profiling")]
+        let start = Instant::now();
         let w = widget.size_rules(size_handle, AxisInfo::new(false, None));
         let h = widget.size_rules(size_handle, AxisInfo::new(true, Some(size.0)));
+        #[cfg(feature = "profiling")]
+        (widget.core_data().profile()).record_layout(false, start.elapsed());
+        #[cfg(feature = "inspect")]
+        {
+            widget.core_data_mut().layout_rules = [Some(w), Some(h)];
+        }
 
         let pos = Coord(0, 0);
         widget.set_rect(size_handle, Rect { pos, size });
+        #[cfg(feature = "strict")]
+        super::strict::check_layout(widget);
 
         trace!(
             "Layout solution for size={:?} has rules {:?}, {:?} and hierarchy:{}",
@@ -0,0 +1,335 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Strict mode: runtime checks of the widget protocol
+//!
+//! With the `strict` feature, the following are checked, with a panic on
+//! violation:
+//!
+//! -   every widget is sized on both axes (via [`child_rules`]) during each
+//!     layout, before [`solve`] completes
+//! -   where the window is at least its minimum size, every widget not
+//!     hidden (i.e. given an empty rect) is assigned a size no smaller than
+//!     the minimum reported for each axis
+//! -   widgets drawn via [`draw_child`] draw only within their assigned rect
+//!     (except within clip regions and overlays, which are not checked)
+//! -   events dispatched by [`Manager::dispatch`] are addressed to the id of a
+//!     configured widget
+//!
+//! [`child_rules`]: super::child_rules
+//! [`solve`]: super::solve
+//! [`draw_child`]: super::draw_child
+//! [`Manager::dispatch`]: crate::event::Manager::dispatch
+
+use std::ops::Range;
+
+use crate::draw::{Colour, Draw, RgbaImage};
+use crate::event::HighlightState;
+use crate::geom::{Coord, Rect};
+use crate::theme::{DrawHandle, SpanClass, SpinnerStyle, TextProperties, TextStyle};
+use crate::Widget;
+
+/// Forget rules found by any previous layout
+pub(crate) fn reset_rules(widget: &mut dyn Widget) {
+    widget.walk_mut(&mut |w| w.core_data_mut().layout_rules = [None, None]);
+}
+
+/// Check the sizes assigned by a layout
+pub(crate) fn check_layout(widget: &dyn Widget) {
+    let root = widget.core_data();
+    let root_fits = match root.layout_rules {
+        [Some(w), Some(h)] => root.rect.size.0 >= w.min_size() && root.rect.size.1 >= h.min_size(),
+        _ => false,
+    };
+    widget.walk(&mut |w| {
+        let core = w.core_data();
+        let (rules, size) = match core.layout_rules {
+            [Some(w), Some(h)] => ((w, h), core.rect.size),
+            _ => panic!(
+                "strict: widget {} {} was not sized on both axes before set_rect \
+                 (parents must use layout::child_rules)",
+                w.widget_name(),
+                w.id(),
+            ),
+        };
+        // Widgets given an empty rect are hidden, thus not checked
+        let hidden = size.0 == 0 || size.1 == 0;
+        let too_small = size.0 < rules.0.min_size() || size.1 < rules.1.min_size();
+        if root_fits && !hidden && too_small {
+            panic!(
+                "strict: widget {} {} was assigned size {:?}, less than its minimum ({}, {})",
+                w.widget_name(),
+                w.id(),
+                size,
+                rules.0.min_size(),
+                rules.1.min_size(),
+            );
+        }
+    });
+}
+
+/// Check that `id` is that of a configured widget
+pub(crate) fn check_event_target(widget: &dyn Widget, id: crate::WidgetId) {
+    if widget.get_by_id(id).is_none() {
+        panic!(
+            "strict: event addressed to {}, which is not a configured widget",
+            id
+        );
+    }
+}
+
+/// A [`DrawHandle`] checking that drawing is within the widget's rect
+pub(crate) struct StrictDrawHandle<'a> {
+    inner: &'a mut dyn DrawHandle,
+    widget: &'a dyn Widget,
+}
+
+impl<'a> StrictDrawHandle<'a> {
+    pub fn new(inner: &'a mut dyn DrawHandle, widget: &'a dyn Widget) -> Self {
+        StrictDrawHandle { inner, widget }
+    }
+
+    fn check(&self, what: &str, rect: Rect) {
+        let bounds = self.widget.rect();
+        let inside = rect.pos.0 >= bounds.pos.0
+            && rect.pos.1 >= bounds.pos.1
+            && rect.pos.0 + rect.size.0 as i32 <= bounds.pos.0 + bounds.size.0 as i32
+            && rect.pos.1 + rect.size.1 as i32 <= bounds.pos.1 + bounds.size.1 as i32;
+        if !inside {
+            panic!(
+                "strict: widget {} {} draws {} at {:?}, outside its rect {:?}",
+                self.widget.widget_name(),
+                self.widget.id(),
+                what,
+                rect,
+                bounds,
+            );
+        }
+    }
+
+    fn check_pos(&self, what: &str, pos: Coord) {
+        let size = Default::default();
+        self.check(what, Rect { pos, size });
+    }
+}
+
+impl<'a> DrawHandle for StrictDrawHandle<'a> {
+    fn clip_region(&mut self, rect: Rect, offset: Coord, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.check("a clip region", rect);
+        // Content is drawn in translated coordinates; children are checked
+        // again by draw_child.
+        self.inner.clip_region(rect, offset, f)
+    }
+
+    fn zoom_region(
+        &mut self,
+        rect: Rect,
+        offset: Coord,
+        scale: f32,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.check("a zoom region", rect);
+        self.inner.zoom_region(rect, offset, scale, f)
+    }
+
+    fn overlay(&mut self, rect: Rect, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        // Overlays (pop-ups) may be anywhere
+        self.inner.overlay(rect, f)
+    }
+
+    fn target_rect(&self) -> Rect {
+        self.inner.target_rect()
+    }
+
+    fn draw_device(&mut self) -> (usize, Coord, &mut dyn Draw) {
+        self.inner.draw_device()
+    }
+
+    fn outer_frame(&mut self, rect: Rect) {
+        self.check("a frame", rect);
+        self.inner.outer_frame(rect)
+    }
+
+    fn group_frame(&mut self, rect: Rect, title: &str, title_width: u32) {
+        self.check("a group frame", rect);
+        self.inner.group_frame(rect, title, title_width)
+    }
+
+    fn text(&mut self, rect: Rect, text: &str, props: TextProperties) {
+        self.check("text", rect);
+        self.inner.text(rect, text, props)
+    }
+
+    fn text_runs(&mut self, rect: Rect, runs: &[(&str, TextStyle)], props: TextProperties) {
+        self.check("text", rect);
+        self.inner.text_runs(rect, runs, props)
+    }
+
+    fn text_spans(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+    ) {
+        self.check("text", rect);
+        self.inner.text_spans(rect, text, props, spans)
+    }
+
+    fn text_caret(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+        caret: Option<usize>,
+    ) {
+        self.check("text", rect);
+        self.inner.text_caret(rect, text, props, spans, caret)
+    }
+
+    fn text_styled(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        styles: &[(Range<usize>, TextStyle)],
+        caret: Option<usize>,
+    ) {
+        self.check("text", rect);
+        self.inner.text_styled(rect, text, props, styles, caret)
+    }
+
+    fn line_highlight(&mut self, rect: Rect, class: SpanClass) {
+        self.check("a line highlight", rect);
+        self.inner.line_highlight(rect, class)
+    }
+
+    fn hyperlink(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        highlights: HighlightState,
+    ) {
+        self.check("a hyperlink", rect);
+        self.inner.hyperlink(rect, text, props, highlights)
+    }
+
+    fn button(&mut self, rect: Rect, highlights: HighlightState) {
+        self.check("a button", rect);
+        self.inner.button(rect, highlights)
+    }
+
+    fn edit_box(&mut self, rect: Rect, highlights: HighlightState) {
+        self.check("an edit box", rect);
+        self.inner.edit_box(rect, highlights)
+    }
+
+    fn checkbox(&mut self, pos: Coord, state: Option<bool>, highlights: HighlightState) {
+        self.check_pos("a checkbox", pos);
+        self.inner.checkbox(pos, state, highlights)
+    }
+
+    fn radiobox(&mut self, pos: Coord, checked: bool, highlights: HighlightState) {
+        self.check_pos("a radiobox", pos);
+        self.inner.radiobox(pos, checked, highlights)
+    }
+
+    fn scrollbar(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, highlights: HighlightState) {
+        self.check("a scrollbar", rect);
+        self.inner.scrollbar(rect, dir, len, pos, highlights)
+    }
+
+    fn slider(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, highlights: HighlightState) {
+        self.check("a slider", rect);
+        self.inner.slider(rect, dir, len, pos, highlights)
+    }
+
+    fn range_slider(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        len: u32,
+        pos: (u32, u32),
+        highlights: (HighlightState, HighlightState),
+    ) {
+        self.check("a slider", rect);
+        self.inner.range_slider(rect, dir, len, pos, highlights)
+    }
+
+    fn rating(
+        &mut self,
+        rect: Rect,
+        count: u32,
+        value: f32,
+        preview: Option<f32>,
+        highlights: HighlightState,
+    ) {
+        self.check("a rating", rect);
+        self.inner.rating(rect, count, value, preview, highlights)
+    }
+
+    fn switch(&mut self, rect: Rect, position: f32, highlights: HighlightState) {
+        self.check("a switch", rect);
+        self.inner.switch(rect, position, highlights)
+    }
+
+    fn dial(&mut self, rect: Rect, value: f32, ticks: u32, highlights: HighlightState) {
+        self.check("a dial", rect);
+        self.inner.dial(rect, value, ticks, highlights)
+    }
+
+    fn level_meter(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        value: f32,
+        peak: Option<f32>,
+        zones: (f32, f32),
+    ) {
+        self.check("a level meter", rect);
+        self.inner.level_meter(rect, dir, value, peak, zones)
+    }
+
+    fn swatch(&mut self, rect: Rect, colour: Colour) {
+        self.check("a swatch", rect);
+        self.inner.swatch(rect, colour)
+    }
+
+    fn splitter(&mut self, rect: Rect, dir: bool, highlights: HighlightState) {
+        self.check("a splitter", rect);
+        self.inner.splitter(rect, dir, highlights)
+    }
+
+    fn separator(&mut self, rect: Rect, dir: bool) {
+        self.check("a separator", rect);
+        self.inner.separator(rect, dir)
+    }
+
+    fn image(&mut self, rect: Rect, image: &RgbaImage) {
+        self.check("an image", rect);
+        self.inner.image(rect, image)
+    }
+
+    fn tooltip(&mut self, pos: Coord, text: &str) {
+        // Tooltips are drawn over other content, near the mouse
+        self.inner.tooltip(pos, text)
+    }
+
+    fn drag_ghost(&mut self, pos: Coord, label: &str, accepted: bool) {
+        // As with tooltips, drawn over other content
+        self.inner.drag_ghost(pos, label, accepted)
+    }
+
+    fn dim(&mut self, rect: Rect) {
+        self.inner.dim(rect)
+    }
+
+    fn spinner(&mut self, rect: Rect, phase: f32, style: SpinnerStyle) {
+        self.check("a spinner", rect);
+        self.inner.spinner(rect, phase, style)
+    }
+}
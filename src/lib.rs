@@ -19,7 +19,23 @@
 //!
 //! -   system interfaces (window creation and event capture)
 //! -   widget rendering and sizing
+//!
+//! ## Optional features
+//!
+//! The following are enabled by default, but may be disabled by users
+//! embedding only the widget model, event handling and layout engine:
+//!
+//! -   `macros`: the procedural macros, re-exported by [`macros`]
+//! -   `theme`: the [`theme::Theme`] and [`theme::Window`] traits (used by
+//!     toolkits) and the `rusttype` dependency
+//! -   `widget`: the standard [`widget`] library (requires `macros`)
+//!
+//! The `serde` feature (not enabled by default) provides `Serialize` and
+//! `Deserialize` impls for geometry types, [`draw::Colour`], input enums
+//! (e.g. [`event::VirtualKeyCode`]) and widget state such as
+//! [`layout::LayoutOverrides`].
 
+#[cfg(feature = "macros")]
 extern crate kas_macros;
 extern crate self as kas; // required for reliable self-reference in kas_macros
 
@@ -32,12 +48,16 @@ mod traits;
 pub mod class;
 pub mod draw;
 pub mod event;
-pub mod geom;
 pub mod layout;
+pub mod test;
 pub mod theme;
+#[cfg(feature = "widget")]
 pub mod widget;
 
+pub use kas_layout::geom;
+
 // macro re-exports
+#[cfg(feature = "macros")]
 pub mod macros;
 
 // export most important members directly for convenience and less redundancy:
@@ -58,6 +58,10 @@
 //! The `#[widget(..)]` attribute on the struct supports the following arguments:
 //!
 //! -   `layout = ...` (optional) — see below
+//! -   `derive = ...` (optional) — delegate to a field; see
+//!     [below](#delegation)
+//! -   `custom(...)` (optional, with `derive` only) — see
+//!     [below](#delegation)
 //!
 //! If the `layout` argument is missing, the [`Widget`] trait must be
 //! implemented manually. If present, this trait will be implemented depending
@@ -82,14 +86,35 @@
 //! If there is a `#[handler]` attribute on the struct, then the [`Handler`]
 //! trait will be implemented. This attribute accepts the following arguments:
 //!
-//! -   `msg = ...` — the [`Handler::Msg`] associated type; defaults to `()`
+//! -   `msg = ...` — the [`Handler::Msg`] associated type; defaults to
+//!     [`VoidMsg`] (except [with `derive`](#delegation))
 //! -   `generics = < X, Y, ... > where CONDS` — see below
 //!
 //! Commonly the [`Handler`] implementation requires extra bounds on generic
 //! types, and sometimes also additional type parameters; the `generics`
 //! argument allows this. This argument is optional and if present must be the
 //! last argument. Note that the generic types and bounds given are *added to*
-//! the generics defined on the struct itself.
+//! the generics (including lifetimes and where clause) defined on the struct
+//! itself, which are used on all generated implementations.
+//!
+//! ### Delegation
+//!
+//! A widget may wrap another (e.g. as a newtype) with `derive = field`, where
+//! `field` names (or, for tuple structs, indexes) the inner widget. The
+//! wrapper then shares the inner widget's [`CoreData`] (thus has no `#[core]`
+//! field) and its children, and every method of [`Widget`] is forwarded to the
+//! inner widget. With a `#[handler]` attribute, [`Handler`] is also forwarded;
+//! here the `msg` type defaults to that of the inner widget and, if given,
+//! must support conversion via `From` from that type. Without a `#[handler]`
+//! attribute, [`Handler`] must be implemented manually.
+//!
+//! Forwarded methods may be overridden selectively by listing their names in
+//! `custom(...)`: each listed method instead calls an inherent method of the
+//! same name and signature, which must be defined on the wrapper. Both
+//! [`Widget`] and [`Handler`] methods may be listed; note that the inner
+//! widget's `handle` method calls its own `handle_action`.
+//!
+//! `layout` and `#[widget]` fields may not be used with `derive`.
 //!
 //! ### Fields
 //!
@@ -156,6 +181,29 @@
 //! }
 //! ```
 //!
+//! A newtype over a [`TextButton`], adding a tooltip:
+//!
+//! ```
+//! use kas::macros::Widget;
+//! use kas::widget::TextButton;
+//!
+//! #[derive(Clone, Debug, Widget)]
+//! #[widget(derive = button, custom(tooltip))]
+//! #[handler]
+//! struct HelpButton<M: Clone + std::fmt::Debug + 'static> {
+//!     button: TextButton<M>,
+//!     help: String,
+//! }
+//!
+//! impl<M: Clone + std::fmt::Debug + 'static> HelpButton<M> {
+//!     fn tooltip(&self) -> Option<&str> {
+//!         Some(&self.help)
+//!     }
+//! }
+//! ```
+//!
+//! [`TextButton`]: crate::widget::TextButton
+//!
 //!
 //! ## The `make_widget` macro
 //!
@@ -167,7 +215,7 @@
 //! Syntax should match the following Backus-Naur Form:
 //!
 //! ```bnf
-//! <input>     ::= <layout> "=>" <msg> ";" <fields> ";" <funcs>
+//! <input>     ::= <layout> "=>" <msg> ";" "struct" <generics> "{" <fields> "}" <funcs>
 //! <layout>    ::= "frame" | "single" | "horizontal" | "vertical" | "grid"
 //! <msg>  ::= <type>
 //! <fields>    ::= "" | <field> | <field> "," <fields>
@@ -179,9 +227,12 @@
 //! <w_args>    ::= <w_arg> | <w_arg> "," <w_args>
 //! <w_arg>     ::= <pos_arg> "=" <lit> | "handler" = <ident>
 //! <pos_arg>   ::= "col" | "row" | "cspan" | "rspan"
+//! <generics>  ::= "" | "<" <params> ">" | "<" <params> ">" "where" <clauses>
 //! <funcs>     ::= "" | <func> <funcs>
 //! ```
-//! where `<type>` is a type expression, `<expr>` is a (value) expression,
+//! where `<type>` is a type expression, `<params>` is a list of generic
+//! parameters (lifetimes, types and consts), `<clauses>` is a list of where
+//! clauses, `<expr>` is a (value) expression,
 //! `<ident>` is an identifier, `<lit>` is a literal, `<path>` is a path,
 //! `<bound>` is a trait object bound, and
 //! `<func>` is a Rust method definition. `""` is the empty string (i.e. nothing).
@@ -198,6 +249,12 @@
 //!
 //! The `layout` specifier is as [above](#type-attributes).
 //!
+//! Generic parameters and a where clause may be given after `struct`. These
+//! are required when field types refer to lifetimes or type parameters from
+//! the surrounding scope, since the anonymous struct cannot capture these
+//! implicitly. They are applied to the struct and to all generated
+//! implementations, including the `<funcs>`.
+//!
 //! Fields may have an identifier or may be anonymous (via usage of `_`). This
 //! is often convenient for child widgets which don't need to be referred to.
 //!
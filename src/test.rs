@@ -0,0 +1,788 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Conformance tests for widget implementations
+//!
+//! [`check_widget`] exercises a widget through the cycles a toolkit would
+//! (configure, size, layout, draw and event handling), using a mock toolkit,
+//! and reports any violations of the widget protocol found. This is intended
+//! for use in the tests of custom widgets:
+//!
+//! ```
+//! use kas::test::check_widget;
+//! use kas::widget::Label;
+//!
+//! let mut label = Label::from("Hello");
+//! assert!(check_widget(&mut label).is_ok());
+//! ```
+//!
+//! Checks cannot be exhaustive: a widget passing these is not necessarily
+//! correct, but one failing is not.
+
+use std::any::Any;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::draw::{Colour, Draw, Quad, RgbaImage, Style, Transform, Vec2};
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, MouseButton, PressSource, Response,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{
+    DrawHandle, SizeHandle, SpanClass, SpinnerStyle, TextClass, TextProperties, TextStyle,
+};
+use crate::{Busy, TkAction, TkWindow, Widget, WidgetId, WindowId};
+
+/// A violation of the widget protocol, as found by [`check_widget`]
+#[derive(Clone, Debug)]
+pub struct Violation {
+    /// Name and identifier of the offending widget
+    pub widget: String,
+    /// Description of the violation
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}: {}", self.widget, self.message)
+    }
+}
+
+/// Check a widget for violations of the widget protocol
+///
+/// The widget (and its children) are configured, sized and laid out at both
+/// their ideal and minimum sizes, drawn, then sent a selection of events: an
+/// activation to each widget; identification, cursor motion, a press and a
+/// release at the centre of each widget. Any actions requested (reconfigure
+/// or redraw) are performed. The following are reported as violations:
+///
+/// -   a panic in any method
+/// -   inconsistent child enumeration: [`WidgetCore::walk`] must visit each
+///     child (as reported by [`WidgetCore::len`] and [`WidgetCore::get`])
+///     before the widget itself, and [`WidgetCore::get_by_id`] must find
+///     every widget
+/// -   non-deterministic size rules, or results of
+///     [`Widget::size_rules_both`] differing from those of
+///     [`Widget::size_rules`]
+/// -   [`Widget::set_rect`] not storing the assigned rect
+/// -   drawing outside of the widget's rect (other than within clip regions,
+///     zoom regions and overlays)
+/// -   identification of an unknown widget in response to an event
+///
+/// [`WidgetCore::walk`]: crate::WidgetCore::walk
+/// [`WidgetCore::len`]: crate::WidgetCore::len
+/// [`WidgetCore::get`]: crate::WidgetCore::get
+/// [`WidgetCore::get_by_id`]: crate::WidgetCore::get_by_id
+pub fn check_widget<W: Widget + Handler>(widget: &mut W) -> Result<(), Vec<Violation>> {
+    let mut checker = Checker {
+        tk: MockWindow::new(),
+        violations: vec![],
+    };
+    checker.run(widget);
+    match checker.violations.is_empty() {
+        true => Ok(()),
+        false => Err(checker.violations),
+    }
+}
+
+fn describe(widget: &dyn Widget) -> String {
+    format!("{} {}", widget.widget_name(), widget.id())
+}
+
+struct Checker {
+    tk: MockWindow,
+    violations: Vec<Violation>,
+}
+
+impl Checker {
+    fn report(&mut self, widget: &dyn Widget, message: String) {
+        let widget = describe(widget);
+        self.violations.push(Violation { widget, message });
+    }
+
+    // Run f, reporting any panic against the widget named
+    fn guard<F: FnOnce(&mut Self)>(&mut self, widget: String, what: &str, f: F) -> bool {
+        match panic::catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(()) => true,
+            Err(payload) => {
+                let msg = match payload.downcast_ref::<&str>() {
+                    Some(s) => s.to_string(),
+                    None => match payload.downcast_ref::<String>() {
+                        Some(s) => s.clone(),
+                        None => "(unknown panic payload)".to_string(),
+                    },
+                };
+                let message = format!("panicked during {}: {}", what, msg);
+                self.violations.push(Violation { widget, message });
+                false
+            }
+        }
+    }
+
+    fn run<W: Widget + Handler>(&mut self, widget: &mut W) {
+        let _ = self.cycle(widget) && self.events(widget);
+    }
+
+    // Configure, size, lay out and draw; returns false after a panic
+    fn cycle<W: Widget + Handler>(&mut self, widget: &mut W) -> bool {
+        let name = describe(widget.as_widget());
+        let ok = self.guard(name.clone(), "configure", |c| {
+            c.tk.mgr.configure(widget.as_widget_mut());
+        });
+        if !ok {
+            return false;
+        }
+        self.check_children(widget.as_widget());
+
+        let mut rules = (SizeRules::EMPTY, SizeRules::EMPTY);
+        let ok = self.guard(name.clone(), "size_rules", |c| {
+            rules = c.check_size_rules(widget);
+        });
+        if !ok {
+            return false;
+        }
+
+        let ideal = Size(rules.0.ideal_size(), rules.1.ideal_size());
+        let min = Size(rules.0.min_size(), rules.1.min_size());
+        for size in [min, ideal].iter().cloned() {
+            let ok = self.guard(name.clone(), "layout", |c| {
+                c.tk.mgr.set_window_size(size);
+                layout::solve(widget, &mut c.tk, size);
+            });
+            if !ok {
+                return false;
+            }
+            let rect = widget.rect();
+            if rect.pos != Coord::ZERO || rect.size != size {
+                let msg = format!("set_rect assigned size {:?} but rect is {:?}", size, rect);
+                self.report(widget.as_widget(), msg);
+            }
+            if !self.draw(widget) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn check_children(&mut self, widget: &dyn Widget) {
+        let mut walked = vec![];
+        widget.walk(&mut |w| walked.push(w.id()));
+
+        let mut count = 0;
+        let mut violations = vec![];
+        visit(widget, &mut count, &mut violations);
+        fn visit(w: &dyn Widget, count: &mut usize, out: &mut Vec<(String, String)>) {
+            *count += 1;
+            let len = w.len();
+            for i in 0..len {
+                match w.get(i) {
+                    Some(child) => {
+                        if child.id() >= w.id() {
+                            let msg = format!(
+                                "child {} has id {}, not less than that of its parent \
+                                 (walk must visit children first)",
+                                i,
+                                child.id()
+                            );
+                            out.push((describe(w), msg));
+                        }
+                        visit(child, count, out);
+                    }
+                    None => {
+                        let msg = format!("get({}) returned None with len() = {}", i, len);
+                        out.push((describe(w), msg));
+                    }
+                }
+            }
+            if w.get(len).is_some() {
+                out.push((
+                    describe(w),
+                    format!("get({}) returned Some with len() = {}", len, len),
+                ));
+            }
+        }
+        for (widget, message) in violations {
+            self.violations.push(Violation { widget, message });
+        }
+
+        if count != walked.len() {
+            let msg = format!(
+                "walk visited {} widgets but len/get enumerate {}",
+                walked.len(),
+                count
+            );
+            self.report(widget, msg);
+        }
+        for id in walked {
+            if widget.get_by_id(id).map(|w| w.id()) != Some(id) {
+                self.report(widget, format!("get_by_id({}) did not find the widget", id));
+            }
+        }
+    }
+
+    fn check_size_rules(&mut self, widget: &mut dyn Widget) -> (SizeRules, SizeRules) {
+        let mut sh = MockSizeHandle;
+        let same = |a: SizeRules, b: SizeRules| {
+            a.min_size() == b.min_size() && a.ideal_size() == b.ideal_size()
+        };
+
+        let w = widget.size_rules(&mut sh, AxisInfo::new(false, None));
+        let h = widget.size_rules(&mut sh, AxisInfo::new(true, Some(w.ideal_size())));
+        let w2 = widget.size_rules(&mut sh, AxisInfo::new(false, None));
+        let h2 = widget.size_rules(&mut sh, AxisInfo::new(true, Some(w.ideal_size())));
+        if !same(w, w2) || !same(h, h2) {
+            let msg = format!(
+                "size_rules is not deterministic: found {:?}, {:?} then {:?}, {:?}",
+                w, h, w2, h2
+            );
+            self.report(widget.as_widget(), msg);
+        }
+
+        if let Some((bw, bh)) = widget.size_rules_both(&mut sh) {
+            let h_free = widget.size_rules(&mut sh, AxisInfo::new(true, None));
+            if !same(bw, w) || !same(bh, h_free) {
+                let msg = format!(
+                    "size_rules_both returned {:?}, {:?} but size_rules {:?}, {:?}",
+                    bw, bh, w, h_free
+                );
+                self.report(widget.as_widget(), msg);
+            }
+        }
+        (w, h)
+    }
+
+    fn draw<W: Widget>(&mut self, widget: &W) -> bool {
+        let mut outside = vec![];
+        let mut draw = MockDraw;
+        let ok = self.guard(describe(widget.as_widget()), "draw", |c| {
+            let mut handle = MockDrawHandle {
+                draw: &mut draw,
+                bounds: widget.rect(),
+                depth: 0,
+                outside: &mut outside,
+            };
+            layout::draw_child(widget, &mut handle, &c.tk.mgr);
+        });
+        for (what, rect) in outside {
+            let msg = format!(
+                "draws {} at {:?}, outside its rect {:?}",
+                what,
+                rect,
+                widget.rect()
+            );
+            self.report(widget.as_widget(), msg);
+        }
+        ok
+    }
+
+    // Send events; returns false after a panic
+    fn events<W: Widget + Handler>(&mut self, widget: &mut W) -> bool {
+        let mut targets = vec![];
+        widget.walk(&mut |w| targets.push((w.id(), w.rect())));
+
+        for (id, rect) in targets {
+            let centre = rect.pos + Coord(rect.size.0 as i32 / 2, rect.size.1 as i32 / 2);
+            let source = PressSource::Mouse(MouseButton::Left);
+            let events = [
+                (Address::Id(id), Event::Action(Action::Activate)),
+                (Address::Coord(centre), Event::Identify),
+                (Address::Id(id), Event::CursorMove { coord: centre }),
+                (
+                    Address::Coord(centre),
+                    Event::PressStart {
+                        source,
+                        coord: centre,
+                    },
+                ),
+                (
+                    Address::Coord(centre),
+                    Event::PressEnd {
+                        source,
+                        start_id: Some(id),
+                        end_id: Some(id),
+                        coord: centre,
+                    },
+                ),
+            ];
+            for (addr, event) in events.iter().cloned() {
+                let mut identified = None;
+                let what = format!("handling of {:?} addressed to {:?}", event, addr);
+                let ok = self.guard(describe(widget.as_widget()), &what, |c| {
+                    if let Response::Identify(id) = widget.handle(&mut c.tk, addr, event) {
+                        identified = Some(id);
+                    }
+                });
+                if !ok {
+                    return false;
+                }
+                if let Some(id) = identified {
+                    if widget.get_by_id(id).is_none() {
+                        let msg = format!("identified unknown widget {} for {:?}", id, addr);
+                        self.report(widget.as_widget(), msg);
+                    }
+                }
+
+                match std::mem::replace(&mut self.tk.action, TkAction::None) {
+                    TkAction::Reconfigure if !self.cycle(widget) => return false,
+                    TkAction::Redraw if !self.draw(widget) => return false,
+                    _ => (),
+                }
+            }
+        }
+        true
+    }
+}
+
+/// A mock toolkit window
+struct MockWindow {
+    mgr: event::Manager,
+    action: TkAction,
+    clipboard: Option<String>,
+}
+
+impl MockWindow {
+    fn new() -> Self {
+        MockWindow {
+            mgr: event::Manager::new(1.0),
+            action: TkAction::None,
+            clipboard: None,
+        }
+    }
+}
+
+impl TkWindow for MockWindow {
+    fn add_window(&mut self, _: Box<dyn crate::Window>) {}
+
+    fn window_id(&self) -> WindowId {
+        WindowId::new(0)
+    }
+
+    fn windows(&self) -> Vec<(WindowId, String)> {
+        vec![(WindowId::new(0), String::new())]
+    }
+
+    fn activate_window(&mut self, _: WindowId) {}
+
+    fn close_window(&mut self, _: WindowId) {}
+
+    fn set_busy(&mut self) -> Busy {
+        Busy::new()
+    }
+
+    fn data(&self) -> &event::Manager {
+        &self.mgr
+    }
+
+    fn data_mut(&mut self) -> event::ManagerMut<'_> {
+        event::ManagerMut::new(&mut self.mgr, &mut self.action)
+    }
+
+    fn with_size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        f(&mut MockSizeHandle);
+    }
+
+    fn redraw(&mut self, _: WidgetId) {
+        self.send_action(TkAction::Redraw);
+    }
+
+    fn send_action(&mut self, action: TkAction) {
+        self.action = self.action.max(action);
+    }
+
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard(&mut self, content: String) {
+        self.clipboard = Some(content);
+    }
+
+    fn open_url(&mut self, _url: &str) {}
+}
+
+// Mock metrics: fixed-width characters
+const CHAR_WIDTH: u32 = 8;
+const LINE_HEIGHT: u32 = 16;
+const MARGIN: u32 = 2;
+const FRAME: u32 = 4;
+
+/// A mock size handle, with fixed metrics
+struct MockSizeHandle;
+
+impl MockSizeHandle {
+    fn text_size(text: &str, multi_line: bool) -> Size {
+        let lines = match multi_line {
+            false => 1,
+            true => text.split('\n').count() as u32,
+        };
+        let width = text.split('\n').map(|line| line.chars().count()).max();
+        let width = width.unwrap_or(0) as u32 * CHAR_WIDTH;
+        Size(width, lines * LINE_HEIGHT)
+    }
+
+    fn text_rules(size: Size, axis: AxisInfo) -> SizeRules {
+        let margin = SizeRules::fixed(2 * MARGIN);
+        margin
+            + match axis.vertical() {
+                false => SizeRules::variable(3 * LINE_HEIGHT, size.0.max(3 * LINE_HEIGHT)),
+                true => SizeRules::variable(LINE_HEIGHT, size.1.max(LINE_HEIGHT)),
+            }
+    }
+}
+
+impl SizeHandle for MockSizeHandle {
+    fn outer_frame(&self) -> (Size, Size) {
+        (Size::uniform(FRAME), Size::uniform(FRAME))
+    }
+
+    fn group_frame(&self) -> (Size, Size) {
+        (Size(FRAME, LINE_HEIGHT + 2 * MARGIN), Size::uniform(FRAME))
+    }
+
+    fn inner_margin(&self) -> Size {
+        Size::uniform(MARGIN)
+    }
+
+    fn outer_margin(&self) -> Size {
+        Size::uniform(MARGIN)
+    }
+
+    fn line_height(&self, _: TextClass) -> u32 {
+        LINE_HEIGHT
+    }
+
+    fn text_bound(
+        &mut self,
+        text: &str,
+        _: TextClass,
+        multi_line: bool,
+        axis: AxisInfo,
+    ) -> SizeRules {
+        Self::text_rules(Self::text_size(text, multi_line), axis)
+    }
+
+    fn text_runs_bound(
+        &mut self,
+        runs: &[(&str, TextStyle)],
+        _: TextClass,
+        multi_line: bool,
+        axis: AxisInfo,
+    ) -> SizeRules {
+        let text: String = runs.iter().map(|run| run.0).collect();
+        Self::text_rules(Self::text_size(&text, multi_line), axis)
+    }
+
+    fn text_index_nearest(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        _: TextProperties,
+        pos: Coord,
+    ) -> usize {
+        let n = ((pos.0 - rect.pos.0).max(0) as u32 / CHAR_WIDTH) as usize;
+        text.char_indices()
+            .nth(n)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len())
+    }
+
+    fn text_caret_rect(&mut self, rect: Rect, text: &str, _: TextProperties, index: usize) -> Rect {
+        let n = text[..index.min(text.len())].chars().count() as u32;
+        Rect {
+            pos: rect.pos + Coord((MARGIN + n * CHAR_WIDTH) as i32, MARGIN as i32),
+            size: Size(0, LINE_HEIGHT),
+        }
+    }
+
+    fn button_surround(&self) -> (Size, Size) {
+        let s = Size::uniform(FRAME + MARGIN);
+        (s, s)
+    }
+
+    fn edit_surround(&self) -> (Size, Size) {
+        let s = Size::uniform(FRAME + MARGIN);
+        (s, s)
+    }
+
+    fn checkbox(&self) -> Size {
+        Size::uniform(LINE_HEIGHT)
+    }
+
+    fn radiobox(&self) -> Size {
+        Size::uniform(LINE_HEIGHT)
+    }
+
+    fn scrollbar(&self) -> (u32, u32, u32) {
+        (LINE_HEIGHT / 2, LINE_HEIGHT, 2 * LINE_HEIGHT)
+    }
+
+    fn slider(&self) -> (u32, u32, u32) {
+        (LINE_HEIGHT, LINE_HEIGHT / 2, 5 * LINE_HEIGHT)
+    }
+
+    fn rating_symbol(&self) -> Size {
+        Size::uniform(LINE_HEIGHT)
+    }
+
+    fn switch(&self) -> Size {
+        Size(2 * LINE_HEIGHT, LINE_HEIGHT)
+    }
+
+    fn dial(&self) -> Size {
+        Size::uniform(2 * LINE_HEIGHT)
+    }
+
+    fn level_meter(&self) -> (u32, u32) {
+        (LINE_HEIGHT / 2, 5 * LINE_HEIGHT)
+    }
+
+    fn swatch(&self) -> Size {
+        Size(2 * LINE_HEIGHT, LINE_HEIGHT)
+    }
+
+    fn splitter(&self) -> u32 {
+        LINE_HEIGHT / 2
+    }
+
+    fn spinner(&self) -> Size {
+        Size::uniform(2 * LINE_HEIGHT)
+    }
+
+    fn separator(&self) -> (u32, u32) {
+        (1, MARGIN)
+    }
+}
+
+/// A mock draw device, discarding everything
+struct MockDraw;
+
+impl Draw for MockDraw {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn add_clip_region(&mut self, _: Rect) -> usize {
+        0
+    }
+
+    fn add_overlay_region(&mut self, _: Rect) -> usize {
+        0
+    }
+
+    fn set_transform(&mut self, _: usize, _: Transform) {}
+
+    fn draw_quad(&mut self, _: usize, _: Quad, _: Style, _: Colour) {}
+
+    fn draw_frame(&mut self, _: usize, _: Quad, _: Quad, _: Style, _: Colour) {}
+
+    fn draw_triangle(&mut self, _: usize, _: Vec2, _: Vec2, _: Vec2, _: Colour) {}
+
+    fn draw_gradient(&mut self, _: usize, _: Quad, _: [Colour; 4]) {}
+
+    fn draw_image(&mut self, _: usize, _: Quad, _: &RgbaImage) {}
+}
+
+/// A mock draw handle, recording drawing outside of `bounds`
+///
+/// Content of clip regions and overlays is not checked.
+struct MockDrawHandle<'a> {
+    draw: &'a mut MockDraw,
+    bounds: Rect,
+    depth: usize,
+    outside: &'a mut Vec<(&'static str, Rect)>,
+}
+
+impl<'a> MockDrawHandle<'a> {
+    fn check(&mut self, what: &'static str, rect: Rect) {
+        let b = self.bounds;
+        let inside = rect.pos.0 >= b.pos.0
+            && rect.pos.1 >= b.pos.1
+            && rect.pos.0 + rect.size.0 as i32 <= b.pos.0 + b.size.0 as i32
+            && rect.pos.1 + rect.size.1 as i32 <= b.pos.1 + b.size.1 as i32;
+        if self.depth == 0 && !inside {
+            self.outside.push((what, rect));
+        }
+    }
+
+    fn check_pos(&mut self, what: &'static str, pos: Coord) {
+        let size = Size::ZERO;
+        self.check(what, Rect { pos, size });
+    }
+
+    fn nested(&mut self, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        let mut handle = MockDrawHandle {
+            draw: self.draw,
+            bounds: self.bounds,
+            depth: self.depth + 1,
+            outside: self.outside,
+        };
+        f(&mut handle);
+    }
+}
+
+impl<'a> DrawHandle for MockDrawHandle<'a> {
+    fn clip_region(&mut self, rect: Rect, _: Coord, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.check("a clip region", rect);
+        self.nested(f);
+    }
+
+    fn zoom_region(
+        &mut self,
+        rect: Rect,
+        _: Coord,
+        _: f32,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    ) {
+        self.check("a zoom region", rect);
+        self.nested(f);
+    }
+
+    fn overlay(&mut self, _: Rect, f: &mut dyn FnMut(&mut dyn DrawHandle)) {
+        self.nested(f);
+    }
+
+    fn target_rect(&self) -> Rect {
+        self.bounds
+    }
+
+    fn draw_device(&mut self) -> (usize, Coord, &mut dyn Draw) {
+        (0, Coord::ZERO, self.draw)
+    }
+
+    fn outer_frame(&mut self, rect: Rect) {
+        self.check("a frame", rect);
+    }
+
+    fn group_frame(&mut self, rect: Rect, _: &str, _: u32) {
+        self.check("a group frame", rect);
+    }
+
+    fn text(&mut self, rect: Rect, _: &str, _: TextProperties) {
+        self.check("text", rect);
+    }
+
+    fn text_runs(&mut self, rect: Rect, _: &[(&str, TextStyle)], _: TextProperties) {
+        self.check("text", rect);
+    }
+
+    fn text_spans(
+        &mut self,
+        rect: Rect,
+        _: &str,
+        _: TextProperties,
+        _: &[(Range<usize>, SpanClass)],
+    ) {
+        self.check("text", rect);
+    }
+
+    fn text_caret(
+        &mut self,
+        rect: Rect,
+        _: &str,
+        _: TextProperties,
+        _: &[(Range<usize>, SpanClass)],
+        _: Option<usize>,
+    ) {
+        self.check("text", rect);
+    }
+
+    fn text_styled(
+        &mut self,
+        rect: Rect,
+        _: &str,
+        _: TextProperties,
+        _: &[(Range<usize>, TextStyle)],
+        _: Option<usize>,
+    ) {
+        self.check("text", rect);
+    }
+
+    fn line_highlight(&mut self, rect: Rect, _: SpanClass) {
+        self.check("a line highlight", rect);
+    }
+
+    fn hyperlink(&mut self, rect: Rect, _: &str, _: TextProperties, _: HighlightState) {
+        self.check("a hyperlink", rect);
+    }
+
+    fn button(&mut self, rect: Rect, _: HighlightState) {
+        self.check("a button", rect);
+    }
+
+    fn edit_box(&mut self, rect: Rect, _: HighlightState) {
+        self.check("an edit box", rect);
+    }
+
+    fn checkbox(&mut self, pos: Coord, _: Option<bool>, _: HighlightState) {
+        self.check_pos("a checkbox", pos);
+    }
+
+    fn radiobox(&mut self, pos: Coord, _: bool, _: HighlightState) {
+        self.check_pos("a radiobox", pos);
+    }
+
+    fn scrollbar(&mut self, rect: Rect, _: bool, _: u32, _: u32, _: HighlightState) {
+        self.check("a scrollbar", rect);
+    }
+
+    fn slider(&mut self, rect: Rect, _: bool, _: u32, _: u32, _: HighlightState) {
+        self.check("a slider", rect);
+    }
+
+    fn range_slider(
+        &mut self,
+        rect: Rect,
+        _: bool,
+        _: u32,
+        _: (u32, u32),
+        _: (HighlightState, HighlightState),
+    ) {
+        self.check("a slider", rect);
+    }
+
+    fn rating(&mut self, rect: Rect, _: u32, _: f32, _: Option<f32>, _: HighlightState) {
+        self.check("a rating", rect);
+    }
+
+    fn switch(&mut self, rect: Rect, _: f32, _: HighlightState) {
+        self.check("a switch", rect);
+    }
+
+    fn dial(&mut self, rect: Rect, _: f32, _: u32, _: HighlightState) {
+        self.check("a dial", rect);
+    }
+
+    fn level_meter(&mut self, rect: Rect, _: bool, _: f32, _: Option<f32>, _: (f32, f32)) {
+        self.check("a level meter", rect);
+    }
+
+    fn swatch(&mut self, rect: Rect, _: Colour) {
+        self.check("a swatch", rect);
+    }
+
+    fn splitter(&mut self, rect: Rect, _: bool, _: HighlightState) {
+        self.check("a splitter", rect);
+    }
+
+    fn separator(&mut self, rect: Rect, _: bool) {
+        self.check("a separator", rect);
+    }
+
+    fn image(&mut self, rect: Rect, _: &RgbaImage) {
+        self.check("an image", rect);
+    }
+
+    fn tooltip(&mut self, _: Coord, _: &str) {}
+
+    fn drag_ghost(&mut self, _: Coord, _: &str, _: bool) {}
+
+    fn dim(&mut self, _: Rect) {}
+
+    fn spinner(&mut self, rect: Rect, _: f32, _: SpinnerStyle) {
+        self.check("a spinner", rect);
+    }
+}
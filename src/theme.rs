@@ -16,13 +16,19 @@
 //! and drawing information for widgets. Widgets are provided implementations of
 //! these traits within calls to the appropriate [`Widget`] methods.
 //!
+//! Without the `theme` feature, only the widget-facing parts ([`SizeHandle`],
+//! [`DrawHandle`] and associated types) are available.
+//!
 //! [`Widget`]: crate::Widget
 
+#[cfg(feature = "theme")]
 use std::any::Any;
+use std::ops::Range;
 
+#[cfg(feature = "theme")]
 use rusttype::Font;
 
-use kas::draw::Colour;
+use kas::draw::{Colour, Draw, RgbaImage};
 use kas::event::HighlightState;
 use kas::geom::{Coord, Rect, Size};
 use kas::layout::{AxisInfo, SizeRules};
@@ -40,6 +46,7 @@ pub enum Align {
 }
 
 /// Class of text drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextClass {
     /// Label text is drawn over the background colour
     Label,
@@ -47,6 +54,51 @@ pub enum TextClass {
     Button,
     /// Class of text drawn in an edit (entry) box
     Edit,
+    /// Source code, drawn as edit text but in a monospace font (where
+    /// available)
+    Code,
+}
+
+/// Class of a highlighted span of text
+///
+/// See [`DrawHandle::text_spans`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpanClass {
+    /// A match, for example of a search
+    Match,
+    /// The current or selected item, for example the current search match
+    Selected,
+    /// Inserted content, for example in a diff
+    Inserted,
+    /// Deleted content, for example in a diff
+    Deleted,
+}
+
+/// Style of an activity indicator
+///
+/// See [`DrawHandle::spinner`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SpinnerStyle {
+    /// A ring of spokes, fading behind the leading spoke
+    #[default]
+    Spokes,
+    /// A rotating arc
+    Arc,
+}
+
+/// Style of a run of rich text
+///
+/// The default style is that of plain text. See [`DrawHandle::text_runs`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextStyle {
+    /// Use a bold face
+    pub bold: bool,
+    /// Use an italic face
+    pub italic: bool,
+    /// Colour, if not the theme's colour for the [`TextClass`]
+    pub colour: Option<Colour>,
+    /// Size relative to the standard font size, if not `1.0`
+    pub scale: Option<f32>,
 }
 
 /// Text alignment, class, etc.
@@ -64,10 +116,13 @@ pub struct TextProperties {
 
 /// A *theme* provides widget sizing and drawing implementations.
 ///
+/// Requires the `theme` feature.
+///
 /// The theme is generic over some `Draw` type.
 ///
 /// Objects of this type are copied within each window's data structure. For
 /// large resources (e.g. fonts and icons) consider using external storage.
+#[cfg(feature = "theme")]
 pub trait Theme<Draw> {
     /// The associated [`Window`] implementation.
     type Window: Window<Draw> + 'static;
@@ -112,7 +167,9 @@ pub trait Theme<Draw> {
     /// methods.
     ///
     /// Corresponding `FontId`s may be created from the index into this list.
-    /// The first font in the list will be the default font.
+    /// The first font in the list will be the default font. Themes supporting
+    /// bold and italic [`TextStyle`]s should list the regular, bold, italic
+    /// and bold italic faces first, in that order.
     ///
     /// TODO: this part of the API is dependent on `rusttype::Font`. We should
     /// build an abstraction over this, or possibly just pass the font bytes
@@ -133,6 +190,16 @@ pub trait Theme<Draw> {
 
     /// Background colour
     fn clear_colour(&self) -> Colour;
+
+    /// Select a colour scheme by name
+    ///
+    /// Returns false if the theme has no scheme of this name, in which case
+    /// the theme is unchanged. Windows constructed before this call may not be
+    /// affected. The default implementation supports no schemes.
+    fn set_colour_scheme(&mut self, name: &str) -> bool {
+        let _ = name;
+        false
+    }
 }
 
 /// Per-window storage for the theme
@@ -141,6 +208,9 @@ pub trait Theme<Draw> {
 ///
 /// The main reason for this separation is to allow proper handling of
 /// multi-window applications across screens with differing DPIs.
+///
+/// Requires the `theme` feature.
+#[cfg(feature = "theme")]
 pub trait Window<Draw> {
     /// The associated [`SizeHandle`] implementation.
     type SizeHandle: SizeHandle;
@@ -180,6 +250,13 @@ pub trait SizeHandle {
     /// Returns `(top_left, bottom_right)` dimensions as two `Size`s.
     fn outer_frame(&self) -> (Size, Size);
 
+    /// Size of a group frame around child widget(s)
+    ///
+    /// Returns `(top_left, bottom_right)` dimensions as two `Size`s, as
+    /// [`SizeHandle::outer_frame`]. The top includes space for a title of
+    /// class [`TextClass::Label`] (see [`DrawHandle::group_frame`]).
+    fn group_frame(&self) -> (Size, Size);
+
     /// The margin around content within a widget
     ///
     /// This area may be used to draw focus indicators.
@@ -205,6 +282,57 @@ pub trait SizeHandle {
         axis: AxisInfo,
     ) -> SizeRules;
 
+    /// Get text label size bounds on both axes
+    ///
+    /// This is equivalent to calling [`SizeHandle::text_bound`] on each axis
+    /// without a fixed dimension, but may measure the text only once.
+    fn text_bound_both(
+        &mut self,
+        text: &str,
+        class: TextClass,
+        multi_line: bool,
+    ) -> (SizeRules, SizeRules) {
+        let w = self.text_bound(text, class, multi_line, AxisInfo::new(false, None));
+        let h = self.text_bound(text, class, multi_line, AxisInfo::new(true, None));
+        (w, h)
+    }
+
+    /// Get a size bound for rich text
+    ///
+    /// Sizing requirements of [`DrawHandle::text_runs`]. The `runs` are
+    /// concatenated.
+    fn text_runs_bound(
+        &mut self,
+        runs: &[(&str, TextStyle)],
+        class: TextClass,
+        multi_line: bool,
+        axis: AxisInfo,
+    ) -> SizeRules;
+
+    /// Find the caret position nearest to `pos`
+    ///
+    /// The `text` is laid out as by [`DrawHandle::text`] within `rect`. The
+    /// result is a byte index of `text` on a `char` boundary.
+    fn text_index_nearest(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        pos: Coord,
+    ) -> usize;
+
+    /// Find the position of a caret at byte `index` of `text`
+    ///
+    /// The `text` is laid out as by [`DrawHandle::text`] within `rect`. The
+    /// result has zero width and the height of the caret's line.
+    fn text_caret_rect(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        index: usize,
+    ) -> Rect;
+
     /// Size of the sides of a button.
     ///
     /// Includes each side (as in `outer_frame`), minus the content area (to be added separately).
@@ -220,6 +348,11 @@ pub trait SizeHandle {
     /// This element is not scalable (except by DPI).
     fn checkbox(&self) -> Size;
 
+    /// Size of the element drawn by [`DrawHandle::radiobox`].
+    ///
+    /// This element is not scalable (except by DPI).
+    fn radiobox(&self) -> Size;
+
     /// Dimensions for a scrollbar
     ///
     /// Returns three components:
@@ -232,6 +365,43 @@ pub trait SizeHandle {
     /// `min_handle_len` (so that some movement is always possible).
     /// It is required that `min_len >= min_handle_len`.
     fn scrollbar(&self) -> (u32, u32, u32);
+
+    /// Dimensions for a slider
+    ///
+    /// Returns three components:
+    ///
+    /// -   `thickness`: slider width (for vertical sliders)
+    /// -   `grip_len`: length of the grip
+    /// -   `min_len`: minimum length for the whole slider
+    ///
+    /// It is required that `min_len >= grip_len`.
+    fn slider(&self) -> (u32, u32, u32);
+
+    /// Size of a single symbol of a rating widget
+    fn rating_symbol(&self) -> Size;
+
+    /// Size of a switch (including its knob)
+    fn switch(&self) -> Size;
+
+    /// Size of a dial (including tick marks)
+    fn dial(&self) -> Size;
+
+    /// Dimensions of a level meter: `(thickness, min_len)`
+    fn level_meter(&self) -> (u32, u32);
+
+    /// Size of a colour swatch
+    fn swatch(&self) -> Size;
+
+    /// Thickness of a splitter handle
+    fn splitter(&self) -> u32;
+
+    /// Size of an activity indicator (see [`DrawHandle::spinner`])
+    fn spinner(&self) -> Size;
+
+    /// Dimensions of a separator: `(thickness, margin)`
+    ///
+    /// The margin is applied on each side of the line, across its length.
+    fn separator(&self) -> (u32, u32);
 }
 
 /// Handle passed to objects during draw and sizing operations
@@ -243,28 +413,152 @@ pub trait DrawHandle {
     ///
     /// All content drawn by the new region is clipped to the given `rect`
     /// (in the current coordinate space, i.e. not translated by `offset`).
+    /// Regions may be nested, in which case the new region is additionally
+    /// clipped to the current one; if the intersection is empty, `f` is not
+    /// called.
     fn clip_region(&mut self, rect: Rect, offset: Coord, f: &mut dyn FnMut(&mut dyn DrawHandle));
 
+    /// Construct a new draw-handle on a scaled region and pass to a callback.
+    ///
+    /// This is as [`DrawHandle::clip_region`], except that content is also
+    /// magnified by `scale` about `rect.pos`: a point `p` drawn by `f` appears
+    /// at `rect.pos + (p - rect.pos) * scale - offset`. The size of text and
+    /// of all other features scales likewise. Scaled regions may be nested.
+    fn zoom_region(
+        &mut self,
+        rect: Rect,
+        offset: Coord,
+        scale: f32,
+        f: &mut dyn FnMut(&mut dyn DrawHandle),
+    );
+
+    /// Construct a new draw-handle on an overlay region and pass to a callback.
+    ///
+    /// Content drawn in an overlay appears over all other (non-overlay)
+    /// content, and is clipped to the given `rect` and the window, but not to
+    /// any parent region. This may be used to draw pop-ups such as menus which
+    /// extend beyond their owner's rect. Regions nested within an overlay are
+    /// also overlays.
+    ///
+    /// Coordinates are unchanged (i.e. the current offset still applies).
+    /// If `rect` lies entirely outside the window, `f` is not called.
+    fn overlay(&mut self, rect: Rect, f: &mut dyn FnMut(&mut dyn DrawHandle));
+
     /// Target area for drawing
     ///
     /// This is the `Rect` passed to [`Theme::draw_handle`] or
-    /// [`DrawHandle::clip_region`], minus any offsets.
+    /// [`DrawHandle::clip_region`] (after clipping to any parent region),
+    /// minus any offsets and in the current (possibly scaled) coordinates.
     fn target_rect(&self) -> Rect;
 
+    /// Access the low-level drawing API
+    ///
+    /// Returns the current pass number, the offset from widget coordinates to
+    /// those used by [`Draw`] (add to a widget coordinate), and the [`Draw`]
+    /// implementation. This allows drawing of arbitrary shapes within the
+    /// current region; most widgets should use the themed methods instead.
+    ///
+    /// Coordinates are in device pixels; use [`Quad::snap`] to keep edges
+    /// crisp at fractional scale factors.
+    ///
+    /// [`Quad::snap`]: crate::draw::Quad::snap
+    fn draw_device(&mut self) -> (usize, Coord, &mut dyn Draw);
+
     /// Draw a frame in the given [`Rect`]
     ///
     /// The frame dimensions should equal those of [`SizeHandle::outer_frame`].
     fn outer_frame(&mut self, rect: Rect);
 
+    /// Draw a group frame with a title in the given [`Rect`]
+    ///
+    /// The frame dimensions should equal those of [`SizeHandle::group_frame`].
+    /// The title is drawn overlapping the top edge of the frame, indented by
+    /// twice the frame's left width, with the edge broken behind it;
+    /// `title_width` is the width reserved for the title, including margins
+    /// (as measured by [`SizeHandle::text_bound`]).
+    fn group_frame(&mut self, rect: Rect, title: &str, title_width: u32);
+
     /// Draw some text using the standard font
     ///
     /// The dimensions required for this text may be queried with [`SizeHandle::text_bound`].
     fn text(&mut self, rect: Rect, text: &str, props: TextProperties);
 
+    /// Draw rich text, made of runs each with its own style
+    ///
+    /// As [`DrawHandle::text`], except that the `runs` are concatenated, each
+    /// drawn with its given style. The dimensions required may be queried with
+    /// [`SizeHandle::text_runs_bound`].
+    fn text_runs(&mut self, rect: Rect, runs: &[(&str, TextStyle)], props: TextProperties);
+
+    /// Draw some text, with highlighted spans
+    ///
+    /// As [`DrawHandle::text`], except that each span of `text` (given as a
+    /// byte range) is highlighted according to its class. Spans should be in
+    /// order and not overlap; spans not on `char` boundaries are ignored.
+    fn text_spans(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+    );
+
+    /// Draw editable text, with highlighted spans and a caret
+    ///
+    /// As [`DrawHandle::text_spans`], except that if `caret` is not `None`, a
+    /// text-insertion caret is drawn before that byte of `text`, at the
+    /// position given by [`SizeHandle::text_caret_rect`].
+    fn text_caret(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        spans: &[(Range<usize>, SpanClass)],
+        caret: Option<usize>,
+    );
+
+    /// Draw editable text, with styled spans and a caret
+    ///
+    /// As [`DrawHandle::text_caret`], except that each span of `text` (given
+    /// as a byte range) is drawn with its given style, for example a colour
+    /// assigned by a syntax highlighter. Themes may ignore the bold and italic
+    /// properties for [`TextClass::Code`] in order to keep a monospace face;
+    /// the `scale` property is ignored. Spans should be in order and not
+    /// overlap; spans not on `char` boundaries are ignored.
+    fn text_styled(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        styles: &[(Range<usize>, TextStyle)],
+        caret: Option<usize>,
+    );
+
+    /// Draw a highlighted line background
+    ///
+    /// This marks a line of text within `rect` according to its class, for
+    /// example a line inserted in a diff. It should be drawn before the text.
+    fn line_highlight(&mut self, rect: Rect, class: SpanClass);
+
+    /// Draw a hyperlink
+    ///
+    /// As [`DrawHandle::text`], except that the text is drawn underlined, in
+    /// a link colour which changes when hovered or depressed. Where the link
+    /// has keyboard focus, an indicator is drawn within the margin of `rect`.
+    fn hyperlink(
+        &mut self,
+        rect: Rect,
+        text: &str,
+        props: TextProperties,
+        highlights: HighlightState,
+    );
+
     /// Draw button sides, background and margin-area highlight
     fn button(&mut self, rect: Rect, highlights: HighlightState);
 
     /// Draw edit box sides, background and margin-area highlight
+    ///
+    /// If `highlights.error` is set, the box should be visibly marked.
     fn edit_box(&mut self, rect: Rect, highlights: HighlightState);
 
     /// Draw UI element: checkbox
@@ -274,8 +568,18 @@ pub trait DrawHandle {
     /// part of this element.
     ///
     /// Size is fixed as [`SizeHandle::checkbox`], thus only the `pos`
+    /// and state are needed here. The `state` is `Some(checked)`, or `None`
+    /// for an indeterminate (tristate) checkbox.
+    fn checkbox(&mut self, pos: Coord, state: Option<bool>, highlights: HighlightState);
+
+    /// Draw UI element: radiobox
+    ///
+    /// This is similar in appearance to a checkbox, but is usually round. It
+    /// represents one of a group of mutually exclusive options.
+    ///
+    /// Size is fixed as [`SizeHandle::radiobox`], thus only the `pos`
     /// and state are needed here.
-    fn checkbox(&mut self, pos: Coord, checked: bool, highlights: HighlightState);
+    fn radiobox(&mut self, pos: Coord, checked: bool, highlights: HighlightState);
 
     /// Draw UI element: scrollbar
     ///
@@ -285,4 +589,152 @@ pub trait DrawHandle {
     /// -   `pos`: offset of handle from start in pixels
     /// -   `highlights`: highlighting information
     fn scrollbar(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, highlights: HighlightState);
+
+    /// Draw UI element: slider
+    ///
+    /// The slider consists of a track along the length of `rect` and a grip
+    /// positioned on the track.
+    ///
+    /// -   `rect`: target area
+    /// -   `dir`: true for a vertical slider, false for horizontal
+    /// -   `len`: length of grip in pixels
+    /// -   `pos`: offset of grip from start in pixels
+    /// -   `highlights`: highlighting information
+    fn slider(&mut self, rect: Rect, dir: bool, len: u32, pos: u32, highlights: HighlightState);
+
+    /// Draw UI element: range slider
+    ///
+    /// As for [`DrawHandle::slider`], but with two grips; the part of the
+    /// track between the grips is highlighted.
+    ///
+    /// -   `rect`: target area
+    /// -   `dir`: true for a vertical slider, false for horizontal
+    /// -   `len`: length of each grip in pixels
+    /// -   `pos`: offsets of the lower and upper grips from start in pixels
+    /// -   `highlights`: highlighting information for each grip
+    fn range_slider(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        len: u32,
+        pos: (u32, u32),
+        highlights: (HighlightState, HighlightState),
+    );
+
+    /// Draw UI element: rating
+    ///
+    /// A rating is a row of `count` symbols, each of size
+    /// [`SizeHandle::rating_symbol`], filled according to a value.
+    ///
+    /// -   `rect`: target area
+    /// -   `count`: number of symbols
+    /// -   `value`: number of filled symbols; fractional values fill the last
+    ///     symbol partially
+    /// -   `preview`: if not `None`, a value to draw instead of `value` (with
+    ///     distinct styling), as shown while hovering
+    /// -   `highlights`: highlighting information
+    fn rating(
+        &mut self,
+        rect: Rect,
+        count: u32,
+        value: f32,
+        preview: Option<f32>,
+        highlights: HighlightState,
+    );
+
+    /// Draw UI element: switch
+    ///
+    /// A switch is a track with a knob, which is at the start of the track
+    /// when off and at the end when on.
+    ///
+    /// -   `rect`: target area, of size [`SizeHandle::switch`]
+    /// -   `position`: position of the knob, from 0 (off) to 1 (on);
+    ///     intermediate values are used while animating or dragging
+    /// -   `highlights`: highlighting information
+    fn switch(&mut self, rect: Rect, position: f32, highlights: HighlightState);
+
+    /// Draw UI element: dial
+    ///
+    /// A dial is a round knob with a pointer, sweeping through 270° from
+    /// lower-left (minimum) to lower-right (maximum), surrounded by tick marks.
+    ///
+    /// -   `rect`: target area, at least of size [`SizeHandle::dial`]; the
+    ///     dial is centred within this
+    /// -   `value`: position of the pointer, from 0 (minimum) to 1 (maximum)
+    /// -   `ticks`: number of tick marks (evenly spaced over the sweep, if at
+    ///     least two)
+    /// -   `highlights`: highlighting information
+    fn dial(&mut self, rect: Rect, value: f32, ticks: u32, highlights: HighlightState);
+
+    /// Draw UI element: level meter
+    ///
+    /// -   `rect`: target area
+    /// -   `dir`: direction of meter (false: horizontal, filling from the left;
+    ///     true: vertical, filling from the bottom)
+    /// -   `value`: current level, from 0 to 1
+    /// -   `peak`: peak-hold level (drawn as a thin mark), if any
+    /// -   `zones`: start of the warning and danger zones, as fractions of
+    ///     the range; the meter is coloured according to zone
+    fn level_meter(
+        &mut self,
+        rect: Rect,
+        dir: bool,
+        value: f32,
+        peak: Option<f32>,
+        zones: (f32, f32),
+    );
+
+    /// Draw a colour swatch: a framed sample of `colour`
+    fn swatch(&mut self, rect: Rect, colour: Colour);
+
+    /// Draw UI element: splitter handle
+    ///
+    /// This is a grip filling `rect`, between two panes of a splitter.
+    ///
+    /// -   `rect`: target area
+    /// -   `dir`: true if panes are arranged vertically (thus the handle is
+    ///     horizontal), false if arranged horizontally
+    /// -   `highlights`: highlighting information
+    fn splitter(&mut self, rect: Rect, dir: bool, highlights: HighlightState);
+
+    /// Draw UI element: separator
+    ///
+    /// This is a thin rule centred within `rect`, along its length.
+    ///
+    /// -   `rect`: target area, including margins
+    /// -   `dir`: true for a vertical rule, false for a horizontal one
+    fn separator(&mut self, rect: Rect, dir: bool);
+
+    /// Draw an image, scaled to fill `rect`
+    ///
+    /// See [`Draw::draw_image`](kas::draw::Draw::draw_image).
+    fn image(&mut self, rect: Rect, image: &RgbaImage);
+
+    /// Draw a tooltip
+    ///
+    /// A tooltip is a small pop-up containing a single line of `text`, drawn
+    /// over all other content near (usually just below) the mouse position
+    /// `pos`, and placed so as to fit within the window where possible.
+    fn tooltip(&mut self, pos: Coord, text: &str);
+
+    /// Draw the "ghost" of a drag-and-drop operation
+    ///
+    /// This is drawn like a tooltip, with the drag's `label`, near the drag
+    /// position `pos`. `accepted` is true when over a drop target, in which
+    /// case the ghost should be highlighted.
+    ///
+    /// See [`Manager::drag_ghost`](kas::event::Manager::drag_ghost).
+    fn drag_ghost(&mut self, pos: Coord, label: &str, accepted: bool);
+
+    /// Dim a region
+    ///
+    /// This is drawn over all other content, for example to mark a window
+    /// whose input is blocked by a modal window.
+    fn dim(&mut self, rect: Rect);
+
+    /// Draw an indeterminate activity indicator
+    ///
+    /// The indicator is drawn centred within `rect`, no larger than `rect`.
+    /// It is animated by `phase`, which cycles through `0.0..1.0`.
+    fn spinner(&mut self, rect: Rect, phase: f32, style: SpinnerStyle);
 }
@@ -14,9 +14,81 @@
 //!
 //! [winit]: https://github.com/rust-windowing/winit
 
+use std::fmt;
+use std::sync::Arc;
+
 use crate::theme::SizeHandle;
 use crate::{event, WidgetId};
 
+/// Window identifier
+///
+/// Each window is assigned a unique identifier by the toolkit when created.
+/// Identifiers are not reused while the toolkit runs.
+#[derive(Debug, Clone, Copy, Hash, Ord, PartialOrd, PartialEq, Eq)]
+pub struct WindowId(u32);
+
+impl WindowId {
+    /// Construct from a `u32`
+    ///
+    /// This is intended for use by toolkits.
+    #[inline]
+    pub fn new(n: u32) -> Self {
+        WindowId(n)
+    }
+
+    /// Get identifier as a `u32`
+    #[inline]
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for WindowId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "window #{}", self.0)
+    }
+}
+
+/// Modality of a window
+///
+/// See [`Window::modality`](crate::Window::modality).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Modality {
+    /// The window does not block others
+    #[default]
+    None,
+    /// The window blocks user input to the window which opened it
+    Window,
+    /// The window blocks user input to all windows opened before it
+    Application,
+}
+
+/// A handle on the busy state of a window
+///
+/// Obtained from [`TkWindow::set_busy`]. The window remains busy while any
+/// clone of this handle (other than the toolkit's own) exists. Handles may be
+/// sent to other threads, for example to a worker performing the task which
+/// the window awaits; drop them once the task completes.
+#[derive(Clone, Debug, Default)]
+pub struct Busy(Arc<()>);
+
+impl Busy {
+    /// Construct a new busy state
+    ///
+    /// This is intended for use by toolkits.
+    pub fn new() -> Self {
+        Busy::default()
+    }
+
+    /// True if no other handle on this state remains
+    ///
+    /// This is intended for use by toolkits: a window is no longer busy once
+    /// its own handle is unique.
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.0) == 1
+    }
+}
+
 /// Toolkit actions needed after event handling, if any.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum TkAction {
@@ -55,18 +127,55 @@ pub trait TkWindow {
     /// processing, albeit without error handling.
     fn add_window(&mut self, widget: Box<dyn kas::Window>);
 
+    /// Get the identifier of this window
+    fn window_id(&self) -> WindowId;
+
+    /// List open windows
+    ///
+    /// This returns the identifier and title of each open window, in the order
+    /// opened. Windows added via [`TkWindow::add_window`] are listed once
+    /// created, after event handling.
+    fn windows(&self) -> Vec<(WindowId, String)>;
+
+    /// Request that a window be activated
+    ///
+    /// The window is shown (if hidden) and brought to the front where the
+    /// windowing system supports this. The request is handled after event
+    /// handling; unknown identifiers are ignored.
+    fn activate_window(&mut self, id: WindowId);
+
+    /// Request that a window be closed
+    ///
+    /// This has the same effect as [`TkAction::Close`] sent by that window,
+    /// but may be used from any window. The request is handled after event
+    /// handling; unknown identifiers are ignored.
+    fn close_window(&mut self, id: WindowId);
+
+    /// Put this window into a busy state
+    ///
+    /// While busy, user input to the window is ignored, for example to
+    /// prevent submitting a form twice while awaiting the result. If the
+    /// window remains busy for longer than a short grace period, an activity
+    /// indicator is drawn over it. The busy state ends once all clones of the
+    /// returned handle are dropped (see [`Busy`]); this may be called multiple
+    /// times, in which case the window is busy until all states end.
+    fn set_busy(&mut self) -> Busy;
+
     /// Read access to the event manager state
     fn data(&self) -> &event::Manager;
 
-    /// Update event manager data with a closure
+    /// Mutable access to the event manager state
     ///
-    /// The closure should return true if this update may require a redraw.
-    fn update_data(&mut self, f: &mut dyn FnMut(&mut event::Manager) -> bool);
+    /// The returned handle schedules any redraw or reconfigure required by
+    /// changes made through it; see [`event::ManagerMut`].
+    fn data_mut(&mut self) -> event::ManagerMut<'_>;
 
     /// Construct a [`SizeHandle`] and call the closure on it
     fn with_size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle));
 
     /// Notify that a widget must be redrawn
+    ///
+    /// Toolkits may redraw the whole window (as does `kas_wgpu`).
     fn redraw(&mut self, id: WidgetId);
 
     /// Notify that a toolkit action should happen
@@ -78,6 +187,40 @@ pub trait TkWindow {
     /// affect the UI after a reconfigure action.
     fn send_action(&mut self, action: TkAction);
 
+    /// Send an event to the widget with the given identifier
+    ///
+    /// The event is queued and delivered (as with [`Address::Id`]) once the
+    /// current event has been handled, thus the target may be any widget of
+    /// this window, including the sender. For example, a toolbar button's
+    /// handler may send [`Action::Activate`] to a search field to give it
+    /// character focus. The target's response is discarded. Events sent to
+    /// an unknown identifier are dropped with a warning.
+    ///
+    /// This is equivalent to deferring [`Deferred::Send`] (see
+    /// [`TkWindow::defer`]).
+    ///
+    /// [`Address::Id`]: crate::event::Address::Id
+    /// [`Action::Activate`]: crate::event::Action::Activate
+    /// [`Deferred::Send`]: crate::event::Deferred::Send
+    fn send(&mut self, id: WidgetId, event: event::Event) {
+        self.defer(event::Deferred::Send(id, event));
+    }
+
+    /// Defer an action until the current event has been handled
+    ///
+    /// Some actions, such as moving focus or sending further events, are
+    /// better not applied in the middle of event dispatch; deferred actions
+    /// are applied in order once handling of the current event completes.
+    ///
+    /// To prevent loops (e.g. two widgets sending events to each other), at
+    /// most [`MAX_DEFERRED`] actions are processed per event; further
+    /// actions are dropped with a warning.
+    ///
+    /// [`MAX_DEFERRED`]: crate::event::MAX_DEFERRED
+    fn defer(&mut self, action: event::Deferred) {
+        self.data_mut().defer(action);
+    }
+
     /// Attempt to get clipboard contents
     ///
     /// In case of failure, paste actions will simply fail. The implementation
@@ -86,4 +229,11 @@ pub trait TkWindow {
 
     /// Attempt to set clipboard contents
     fn set_clipboard(&mut self, content: String);
+
+    /// Open a URL (or path) with the system's default handler
+    ///
+    /// For example, a web address would be opened in a browser. This does not
+    /// wait for the handler. In case of failure, the implementation may wish
+    /// to log an appropriate warning message.
+    fn open_url(&mut self, url: &str);
 }
@@ -5,13 +5,14 @@
 
 //! Widget traits
 
+use std::any::Any;
 use std::fmt;
 
-use crate::event::{self, Callback, Handler, VoidMsg};
+use crate::event::{self, Callback, CursorIcon, Handler, VoidMsg};
 use crate::geom::{Rect, Size};
 use crate::layout::{self, AxisInfo, SizeRules};
 use crate::theme::{DrawHandle, SizeHandle};
-use crate::toolkit::TkWindow;
+use crate::toolkit::{Modality, TkWindow};
 use crate::{CoreData, WidgetId};
 
 pub trait CloneTo {
@@ -33,7 +34,10 @@ impl<T: Clone + Sized> CloneTo for T {
 ///
 /// It is not recommended to implement this manually since breaking changes may
 /// be readily introduced (although not in patch releases).
-pub trait WidgetCore: fmt::Debug {
+///
+/// Widgets must be `'static` (i.e. may not contain borrowed data); this allows
+/// downcasting (see [`WidgetCore::as_any`]).
+pub trait WidgetCore: Any + fmt::Debug {
     /// Get direct access to the [`CoreData`] providing property storage.
     fn core_data(&self) -> &CoreData;
 
@@ -63,9 +67,24 @@ pub trait WidgetCore: fmt::Debug {
     /// Erase type
     fn as_widget_mut(&mut self) -> &mut dyn Widget;
 
+    /// Erase type, for downcasting
+    ///
+    /// Usually it is more convenient to use `<dyn Widget>::downcast_ref`.
+    fn as_any(&self) -> &dyn Any;
+    /// Erase type, for downcasting
+    ///
+    /// Usually it is more convenient to use `<dyn Widget>::downcast_mut`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
     /// Get the number of child widgets
     fn len(&self) -> usize;
 
+    /// True if there are no child widgets
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Get a reference to a child widget by index, or `None` if the index is
     /// out of bounds.
     ///
@@ -101,6 +120,26 @@ pub trait WidgetCore: fmt::Debug {
         None
     }
 
+    /// Find a child widget by identifier, mutably
+    ///
+    /// This requires that the widget tree has already been configured by
+    /// [`crate::event::Manager::configure`].
+    ///
+    /// Warning: as with [`WidgetCore::get_mut`], changes made directly may
+    /// require a reconfigure or redraw.
+    fn get_by_id_mut(&mut self, id: WidgetId) -> Option<&mut dyn Widget> {
+        if id == self.id() {
+            return Some(self.as_widget_mut());
+        } else if id < self.id() {
+            for i in 0..self.len() {
+                if self.get(i).map(|w| id <= w.id()).unwrap_or(false) {
+                    return self.get_mut(i).and_then(|w| w.get_by_id_mut(id));
+                }
+            }
+        }
+        None
+    }
+
     /// Walk through all widgets, calling `f` once on each.
     ///
     /// This walk is iterative (nonconcurrent), depth-first, and always calls
@@ -146,6 +185,14 @@ impl WidgetCore for Box<dyn Widget> {
     fn as_widget_mut(&mut self) -> &mut dyn Widget {
         self.as_mut().as_widget_mut()
     }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self.as_ref().as_any()
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.as_mut().as_any_mut()
+    }
 
     #[inline]
     fn len(&self) -> usize {
@@ -202,6 +249,73 @@ pub trait Widget: WidgetCore {
         false
     }
 
+    /// Does this widget accept a drop of `payload`?
+    ///
+    /// Drop targets receive [`event::Event::DragHover`] and
+    /// [`event::Event::Drop`] events for accepted drags (see
+    /// [`event::ManagerMut::start_drag`]). By default, nothing is accepted.
+    fn accept_drop(&self, _payload: &event::DragPayload) -> bool {
+        false
+    }
+
+    /// Get the widget's tooltip, if any
+    ///
+    /// When the mouse rests over a widget for a short time, the toolkit shows
+    /// this text in a small pop-up, dismissed when the mouse moves.
+    ///
+    /// The default implementation returns the tooltip stored in the widget's
+    /// [`CoreData`] (see [`CoreData::set_tooltip`]).
+    fn tooltip(&self) -> Option<&str> {
+        self.core_data().tooltip()
+    }
+
+    /// Get the mouse cursor icon shown while over this widget
+    ///
+    /// The default implementation returns [`CursorIcon::Default`].
+    ///
+    /// This is read when the window is configured.
+    #[inline]
+    fn cursor_icon(&self) -> CursorIcon {
+        CursorIcon::Default
+    }
+
+    /// Get the widget's keyboard shortcuts
+    ///
+    /// Each shortcut, when pressed, sends the paired action to this widget
+    /// (e.g. [`Action::Activate`] or [`Action::Shortcut`]), whether or not
+    /// the widget has focus. These take precedence over bindings made via
+    /// [`ManagerMut::add_shortcut`]; where several widgets declare the same
+    /// shortcut, the last takes precedence. The default implementation
+    /// returns an empty list.
+    ///
+    /// This is read when the window is configured.
+    ///
+    /// [`Action::Activate`]: event::Action::Activate
+    /// [`Action::Shortcut`]: event::Action::Shortcut
+    /// [`ManagerMut::add_shortcut`]: event::ManagerMut::add_shortcut
+    #[inline]
+    fn shortcuts(&self) -> &[(event::Shortcut, event::Action)] {
+        &[]
+    }
+
+    /// Does this widget need redrawing when hovered or unhovered?
+    ///
+    /// When the mouse moves onto or off a widget returning true, the widget
+    /// is redrawn (via [`TkWindow::redraw`]); other hover changes cause no
+    /// redraw. The default implementation returns [`Widget::allow_focus`],
+    /// since interactive widgets usually have a hover highlight while static
+    /// ones (e.g. labels) do not.
+    ///
+    /// This only filters which hover changes trigger a redraw: toolkits (e.g.
+    /// `kas_wgpu`) may not support partial redraws, in which case the whole
+    /// window is still repainted whenever any widget requires a redraw.
+    ///
+    /// This is read when the window is configured.
+    #[inline]
+    fn redraw_on_hover(&self) -> bool {
+        self.allow_focus()
+    }
+
     /// Get size rules for the given axis.
     ///
     /// This method takes `&mut self` to allow local caching of child widget
@@ -213,6 +327,19 @@ pub trait Widget: WidgetCore {
     /// current widget configuration.
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules;
 
+    /// Get size rules for both axes in a single pass
+    ///
+    /// This is optional: the default implementation returns `None`. Widgets
+    /// whose rules on both axes derive from the same measurement (e.g. of a
+    /// text) may implement this to avoid measuring twice; the result must
+    /// equal that of `size_rules` on each axis without a fixed dimension.
+    ///
+    /// Layout solvers use this via [`layout::child_rules`].
+    #[inline]
+    fn size_rules_both(&mut self, _: &mut dyn SizeHandle) -> Option<(SizeRules, SizeRules)> {
+        None
+    }
+
     /// Adjust to the given size.
     ///
     /// For many widgets this operation is trivial and the default
@@ -233,15 +360,82 @@ pub trait Widget: WidgetCore {
     fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager);
 }
 
+impl dyn Widget {
+    /// True if the widget is of type `T`
+    #[inline]
+    pub fn is<T: Widget>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Downcast to a concrete widget type
+    ///
+    /// Together with [`WidgetCore::get_by_id`], this allows access to a
+    /// widget's concrete type from any ancestor, e.g. the window's content:
+    /// ```
+    /// use kas::class::HasText;
+    /// use kas::event::Manager;
+    /// use kas::widget::{Label, Row};
+    /// use kas::{Widget, WidgetCore};
+    ///
+    /// let mut row = Row::new(vec![Label::from("a"), Label::from("b")]);
+    /// Manager::new(1.0).configure(&mut row);
+    /// let id = row.get(1).unwrap().id();
+    ///
+    /// let widget: &dyn Widget = row.get_by_id(id).unwrap();
+    /// let label = widget.downcast_ref::<Label>().unwrap();
+    /// assert_eq!(label.get_text(), "b");
+    /// ```
+    #[inline]
+    pub fn downcast_ref<T: Widget>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Downcast to a concrete widget type, mutably
+    ///
+    /// See [`WidgetCore::get_by_id_mut`]. Changes made directly may require a
+    /// reconfigure or redraw (see [`TkWindow::send_action`]).
+    #[inline]
+    pub fn downcast_mut<T: Widget>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
 impl Widget for Box<dyn Widget> {
     fn allow_focus(&self) -> bool {
         self.as_ref().allow_focus()
     }
 
+    fn accept_drop(&self, payload: &event::DragPayload) -> bool {
+        self.as_ref().accept_drop(payload)
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.as_ref().tooltip()
+    }
+
+    fn cursor_icon(&self) -> event::CursorIcon {
+        self.as_ref().cursor_icon()
+    }
+
+    fn shortcuts(&self) -> &[(event::Shortcut, event::Action)] {
+        self.as_ref().shortcuts()
+    }
+
+    fn redraw_on_hover(&self) -> bool {
+        self.as_ref().redraw_on_hover()
+    }
+
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         self.as_mut().size_rules(size_handle, axis)
     }
 
+    fn size_rules_both(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+    ) -> Option<(SizeRules, SizeRules)> {
+        self.as_mut().size_rules_both(size_handle)
+    }
+
     fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
         self.as_mut().set_rect(size_handle, rect);
     }
@@ -296,6 +490,16 @@ pub trait Window: Widget + Handler<Msg = VoidMsg> {
     /// Get the window title
     fn title(&self) -> &str;
 
+    /// The initial size of the window
+    ///
+    /// This is called by the toolkit once, when the window is created, after
+    /// configuration. The window is given this size if possible.
+    ///
+    /// By default, this returns `None`: the toolkit chooses a size.
+    fn initial_size(&mut self, _tk: &mut dyn TkWindow) -> Option<Size> {
+        None
+    }
+
     /// Adjust the size of the window, repositioning widgets.
     fn resize(&mut self, tk: &mut dyn TkWindow, size: Size);
 
@@ -307,4 +511,40 @@ pub trait Window: Widget + Handler<Msg = VoidMsg> {
 
     /// Trigger a callback (see `iter_callbacks`).
     fn trigger_callback(&mut self, index: usize, tk: &mut dyn TkWindow);
+
+    /// The modality of this window
+    ///
+    /// While a window-modal window is open, the toolkit blocks user input to
+    /// the window which opened it (its parent); windows added directly to the
+    /// toolkit have no parent. While an application-modal window is open, the
+    /// toolkit blocks user input to all windows opened before it. Blocked
+    /// windows are dimmed (see [`DrawHandle::dim`]).
+    ///
+    /// By default, windows are not modal.
+    fn modality(&self) -> Modality {
+        Modality::None
+    }
+
+    /// Whether to show a snapshot while resizing
+    ///
+    /// If true, the toolkit may defer layout during rapid (interactive)
+    /// resizing of the window, instead scaling the content laid out for the
+    /// previous size, until resizing pauses. This trades momentary blurriness
+    /// for responsiveness where layout is expensive. Input is not scaled: it
+    /// uses the previous layout until layout is updated.
+    ///
+    /// By default, this is false.
+    fn resize_snapshot(&self) -> bool {
+        false
+    }
+
+    /// Notification that the window is closing
+    ///
+    /// This is called by the toolkit immediately before the window is
+    /// removed, whether closed by the window itself (via `TkAction::Close`)
+    /// or by the user (e.g. via the window manager). It is not called when the
+    /// toolkit exits.
+    ///
+    /// By default, this does nothing.
+    fn on_close(&mut self) {}
 }
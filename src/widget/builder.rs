@@ -0,0 +1,64 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget builders
+
+use std::borrow::Cow;
+
+use crate::event::VirtualKeyCode;
+use crate::Widget;
+
+/// A builder for a widget of type `W`
+///
+/// Builders are usually constructed via a `builder` method on the widget
+/// type, e.g. [`Label::builder`]. Options common to all widgets (accelerator
+/// keys and tooltip) are available on every builder; other options are
+/// specific to each widget type. Call [`Builder::build`] to finish.
+///
+/// ```
+/// use kas::widget::{Label, TextButton};
+///
+/// let label = Label::builder().text("Some text").wrap(false).build();
+/// let button = TextButton::builder(())
+///     .label("Save")
+///     .tooltip("Save the document")
+///     .build();
+/// ```
+///
+/// [`Label::builder`]: crate::widget::Label::builder
+#[derive(Clone, Debug, Default)]
+pub struct Builder<W>(pub(crate) W);
+
+impl<W: Widget> Builder<W> {
+    /// Construct a builder from a widget
+    ///
+    /// All options start from the state of `widget`.
+    #[inline]
+    pub fn new(widget: W) -> Self {
+        Builder(widget)
+    }
+
+    /// Set accelerator keys
+    ///
+    /// See [`CoreData::set_keys`](crate::CoreData::set_keys).
+    pub fn keys(mut self, keys: &[VirtualKeyCode]) -> Self {
+        self.0.core_data_mut().set_keys(keys);
+        self
+    }
+
+    /// Set the tooltip
+    ///
+    /// A `&'static str` tooltip is borrowed, not copied.
+    pub fn tooltip<S: Into<Cow<'static, str>>>(mut self, tooltip: S) -> Self {
+        self.0.core_data_mut().set_tooltip(Some(tooltip.into()));
+        self
+    }
+
+    /// Build the widget
+    #[inline]
+    pub fn build(self) -> W {
+        self.0
+    }
+}
@@ -5,6 +5,7 @@
 
 //! Push-buttons
 
+use std::borrow::Cow;
 use std::fmt::Debug;
 
 use crate::class::HasText;
@@ -12,21 +13,22 @@ use crate::event::{self, Action, Handler, Response, VirtualKeyCode};
 use crate::layout::{AxisInfo, SizeRules};
 use crate::macros::Widget;
 use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::widget::Builder;
 use crate::{CoreData, TkWindow, Widget, WidgetCore};
 use kas::geom::Rect;
 
 /// A push-button with a text label
 #[widget]
 #[derive(Clone, Debug, Default, Widget)]
-pub struct TextButton<M: Clone + Debug> {
+pub struct TextButton<M: Clone + Debug + 'static> {
     #[core]
     core: CoreData,
     text_rect: Rect,
-    label: String,
+    label: Cow<'static, str>,
     msg: M,
 }
 
-impl<M: Clone + Debug> Widget for TextButton<M> {
+impl<M: Clone + Debug + 'static> Widget for TextButton<M> {
     fn allow_focus(&self) -> bool {
         true
     }
@@ -37,6 +39,16 @@ impl<M: Clone + Debug> Widget for TextButton<M> {
             + size_handle.text_bound(&self.label, TextClass::Button, false, axis)
     }
 
+    fn size_rules_both(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+    ) -> Option<(SizeRules, SizeRules)> {
+        let sides = size_handle.button_surround();
+        let sides = sides.0 + sides.1;
+        let (w, h) = size_handle.text_bound_both(&self.label, TextClass::Button, false);
+        Some((w + sides.0, h + sides.1))
+    }
+
     fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
         let sides = size_handle.button_surround();
         self.text_rect = Rect {
@@ -58,14 +70,16 @@ impl<M: Clone + Debug> Widget for TextButton<M> {
     }
 }
 
-impl<M: Clone + Debug> TextButton<M> {
+impl<M: Clone + Debug + 'static> TextButton<M> {
     /// Construct a button with a given `label` and `msg`
     ///
     /// The message `msg` is returned to the parent widget on activation. Any
     /// type supporting `Clone` is valid, though it is recommended to use a
     /// simple `Copy` type (e.g. an enum). Click actions must be implemented on
     /// the parent (or other ancestor).
-    pub fn new<S: Into<String>>(label: S, msg: M) -> Self {
+    ///
+    /// A `&'static str` label is borrowed, not copied.
+    pub fn new<S: Into<Cow<'static, str>>>(label: S, msg: M) -> Self {
         TextButton {
             core: Default::default(),
             text_rect: Default::default(),
@@ -74,12 +88,43 @@ impl<M: Clone + Debug> TextButton<M> {
         }
     }
 
+    /// Construct a button with a static `label` and `msg`
+    ///
+    /// This is equivalent to [`TextButton::new`], but usable in `const`
+    /// contexts.
+    pub const fn new_static(label: &'static str, msg: M) -> Self {
+        TextButton {
+            core: CoreData::new(),
+            text_rect: Rect::ZERO,
+            label: Cow::Borrowed(label),
+            msg,
+        }
+    }
+
+    /// Construct a builder with the given `msg` and an empty label
+    ///
+    /// See [`TextButton::new`].
+    pub fn builder(msg: M) -> Builder<Self> {
+        Builder::new(TextButton::new("", msg))
+    }
+
     /// Set accelerator keys (chain style)
     pub fn with_keys(mut self, keys: &[VirtualKeyCode]) -> Self {
         self.set_keys(keys);
         self
     }
 
+    /// Set the tooltip (chain style)
+    pub fn with_tooltip<S: Into<String>>(mut self, tooltip: S) -> Self {
+        self.set_tooltip(Some(tooltip.into()));
+        self
+    }
+
+    /// Set or clear the tooltip
+    pub fn set_tooltip(&mut self, tooltip: Option<String>) {
+        self.core.set_tooltip(tooltip.map(Cow::Owned));
+    }
+
     /// Replace the message value
     pub fn set_msg(&mut self, msg: M) {
         self.msg = msg;
@@ -91,18 +136,28 @@ impl<M: Clone + Debug> TextButton<M> {
     }
 }
 
-impl<M: Clone + Debug> HasText for TextButton<M> {
+impl<M: Clone + Debug + 'static> Builder<TextButton<M>> {
+    /// Set the label
+    ///
+    /// A `&'static str` label is borrowed, not copied.
+    pub fn label<S: Into<Cow<'static, str>>>(mut self, label: S) -> Self {
+        self.0.label = label.into();
+        self
+    }
+}
+
+impl<M: Clone + Debug + 'static> HasText for TextButton<M> {
     fn get_text(&self) -> &str {
         &self.label
     }
 
     fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
-        self.label = text;
+        self.label = Cow::Owned(text);
         tk.redraw(self.id());
     }
 }
 
-impl<M: Clone + Debug> Handler for TextButton<M> {
+impl<M: Clone + Debug + 'static> Handler for TextButton<M> {
     type Msg = M;
 
     #[inline]
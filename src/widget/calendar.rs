@@ -0,0 +1,764 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Calendar and date picker
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::class::HasText;
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, PressSource, Response,
+    ScrollDelta,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::{VoidMsg, Widget};
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::widget::EditBox;
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// A date in the (proleptic) Gregorian calendar
+///
+/// Dates are ordered chronologically and formatted (via `Display`) in ISO
+/// 8601 format, as parsed by [`Date::parse`].
+///
+/// ```
+/// use kas::widget::Date;
+///
+/// let date = Date::new(2020, 2, 28).unwrap();
+/// assert_eq!(date.add_days(1).to_string(), "2020-02-29");
+/// assert_eq!(date.add_days(2), Date::new(2020, 3, 1).unwrap());
+/// assert_eq!(Date::new(2020, 1, 31).unwrap().add_months(1), Date::new(2020, 2, 29).unwrap());
+/// assert_eq!(date.weekday(), 4); // a Friday
+/// assert_eq!(Date::parse("2020-02-28"), Some(date));
+/// assert_eq!(Date::parse("2019-02-29"), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, VoidMsg)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Construct, if valid
+    ///
+    /// The `month` is in the range 1-12 and `day` in the range 1 to
+    /// [`Date::days_in_month`].
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > Date::days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// The current date (UTC)
+    pub fn today() -> Self {
+        let secs = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        Date::from_days(secs.div_euclid(86400))
+    }
+
+    /// The number of days in a month
+    pub fn days_in_month(year: i32, month: u8) -> u8 {
+        match month {
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Parse a date in ISO 8601 format: `YYYY-MM-DD`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        // Allow a sign on the year only
+        let split = text.get(1..)?.find('-')? + 1;
+        let year = text[..split].parse().ok()?;
+        let mut parts = text[split + 1..].split('-');
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Date::new(year, month, day)
+    }
+
+    /// The year
+    #[inline]
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// The month, in the range 1-12
+    #[inline]
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, starting from 1
+    #[inline]
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    /// The day of the week, from 0 (Monday) to 6 (Sunday)
+    pub fn weekday(self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as u8
+    }
+
+    /// The date `n` days later (or earlier, if negative)
+    pub fn add_days(self, n: i64) -> Self {
+        Date::from_days(self.to_days() + n)
+    }
+
+    /// The date `n` months later (or earlier, if negative)
+    ///
+    /// The day is reduced to fit the resulting month if necessary.
+    pub fn add_months(self, n: i32) -> Self {
+        let months = self.year as i64 * 12 + (self.month - 1) as i64 + n as i64;
+        let year = months.div_euclid(12) as i32;
+        let month = months.rem_euclid(12) as u8 + 1;
+        let day = self.day.min(Date::days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    // Days since 1970-01-01 (see http://howardhinnant.github.io/date_algorithms.html)
+    fn to_days(self) -> i64 {
+        let (m, d) = (self.month as i64, self.day as i64);
+        let y = self.year as i64 - (m <= 2) as i64;
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * (m + if m > 2 { -3 } else { 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    fn from_days(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Part of a calendar
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Hit {
+    Previous,
+    Next,
+    Day(Date),
+}
+
+/// A month calendar, for selecting a date
+///
+/// One month is shown as a grid of days, in weeks starting on Monday, under
+/// a title with buttons to show the previous or next month.
+///
+/// Clicking a day selects it. With key focus, the arrow keys move a cursor
+/// between days (and the page up/down keys between months); the enter key
+/// selects the day under the cursor. The shown month is always that of the
+/// cursor. Scrolling over the calendar also changes the month.
+///
+/// Each selection is reported with the selected [`Date`] as a message. See
+/// also [`DatePicker`].
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Calendar {
+    #[core]
+    core: CoreData,
+    cell: Size,
+    origin: Coord,
+    cursor: Date,
+    selected: Option<Date>,
+    hover: Option<Hit>,
+    press: Option<(PressSource, Option<Hit>)>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar::new()
+    }
+}
+
+impl Calendar {
+    /// Construct, showing the current month, without a selection
+    pub fn new() -> Self {
+        Calendar {
+            core: Default::default(),
+            cell: Size::ZERO,
+            origin: Coord::ZERO,
+            cursor: Date::today(),
+            selected: None,
+            hover: None,
+            press: None,
+        }
+    }
+
+    /// Set the selected date (inline)
+    #[inline]
+    pub fn with_date(mut self, date: Date) -> Self {
+        self.cursor = date;
+        self.selected = Some(date);
+        self
+    }
+
+    /// Get the selected date
+    #[inline]
+    pub fn date(&self) -> Option<Date> {
+        self.selected
+    }
+
+    /// Set the selected date
+    ///
+    /// The date's month is shown. No message is sent.
+    pub fn set_date(&mut self, tk: &mut dyn TkWindow, date: Option<Date>) {
+        self.selected = date;
+        if let Some(date) = date {
+            self.cursor = date;
+        }
+        tk.redraw(self.id());
+    }
+
+    /// Get the cursor date
+    ///
+    /// This determines the shown month.
+    #[inline]
+    pub fn cursor(&self) -> Date {
+        self.cursor
+    }
+
+    // Date of the first (top-left) cell
+    fn first_cell(&self) -> Date {
+        let first = Date::new(self.cursor.year, self.cursor.month, 1).unwrap();
+        first.add_days(-(first.weekday() as i64))
+    }
+
+    // Rect of the cell at `(col, row)` of the grid (including title rows)
+    fn cell_rect(&self, col: u32, row: u32) -> Rect {
+        let pos = Coord((col * self.cell.0) as i32, (row * self.cell.1) as i32);
+        Rect {
+            pos: self.origin + pos,
+            size: self.cell,
+        }
+    }
+
+    fn hit(&self, coord: Coord) -> Option<Hit> {
+        if self.cell.0 == 0 || self.cell.1 == 0 {
+            return None;
+        }
+        let rel = coord - self.origin;
+        if rel.0 < 0 || rel.1 < 0 {
+            return None;
+        }
+        let col = rel.0 as u32 / self.cell.0;
+        let row = rel.1 as u32 / self.cell.1;
+        match (col, row) {
+            (0, 0) => Some(Hit::Previous),
+            (6, 0) => Some(Hit::Next),
+            (col, row) if col < 7 && (2..8).contains(&row) => {
+                let date = self.first_cell().add_days(((row - 2) * 7 + col) as i64);
+                Some(Hit::Day(date)).filter(|_| date.month == self.cursor.month)
+            }
+            _ => None,
+        }
+    }
+
+    fn set_cursor(&mut self, tk: &mut dyn TkWindow, date: Date) {
+        if date != self.cursor {
+            self.cursor = date;
+            tk.redraw(self.id());
+        }
+    }
+
+    fn select(&mut self, tk: &mut dyn TkWindow, date: Date) -> Response<Date> {
+        self.cursor = date;
+        self.selected = Some(date);
+        tk.redraw(self.id());
+        Response::Msg(date)
+    }
+
+    // Draw, optionally showing the cursor regardless of key focus
+    fn draw_calendar(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager, cursor: bool) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let cursor = cursor || highlights.key_focus;
+        let hover = match highlights.hover {
+            true => self.hover,
+            false => None,
+        };
+        let props = |class| TextProperties {
+            class,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+
+        for (col, hit, text) in [(0, Hit::Previous, "‹"), (6, Hit::Next, "›")].iter() {
+            let rect = self.cell_rect(*col, 0);
+            if hover == Some(*hit) {
+                let hl = HighlightState {
+                    hover: true,
+                    ..HighlightState::default()
+                };
+                draw_handle.button(rect, hl);
+            }
+            draw_handle.text(rect, text, props(TextClass::Label));
+        }
+        let title = format!(
+            "{} {}",
+            MONTHS[self.cursor.month as usize - 1],
+            self.cursor.year
+        );
+        let rect = Rect {
+            pos: self.cell_rect(1, 0).pos,
+            size: Size(5 * self.cell.0, self.cell.1),
+        };
+        draw_handle.text(rect, &title, props(TextClass::Label));
+        for (col, name) in WEEKDAYS.iter().enumerate() {
+            draw_handle.text(self.cell_rect(col as u32, 1), name, props(TextClass::Label));
+        }
+
+        let first = self.first_cell();
+        for index in 0..42 {
+            let date = first.add_days(index as i64);
+            if date.month != self.cursor.month {
+                continue;
+            }
+            let rect = self.cell_rect(index % 7, 2 + index / 7);
+            let hl = HighlightState {
+                hover: hover == Some(Hit::Day(date)),
+                depress: self.selected == Some(date),
+                key_focus: cursor && self.cursor == date,
+                char_focus: false,
+                error: false,
+            };
+            if hl != HighlightState::default() {
+                draw_handle.button(rect, hl);
+            }
+            let class = match hl.depress {
+                true => TextClass::Button,
+                false => TextClass::Label,
+            };
+            draw_handle.text(rect, &date.day.to_string(), props(class));
+        }
+    }
+}
+
+impl Widget for Calendar {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let margin = size_handle.inner_margin();
+        if !axis.vertical() {
+            let width = (WEEKDAYS.iter().chain(["00"].iter()))
+                .map(|text| {
+                    let rules = size_handle.text_bound(text, TextClass::Label, false, axis);
+                    rules.ideal_size()
+                })
+                .max()
+                .unwrap_or(0);
+            let height = size_handle.line_height(TextClass::Label);
+            self.cell.0 = width.max(height) + 2 * margin.0;
+            SizeRules::fixed(7 * self.cell.0)
+        } else {
+            self.cell.1 = size_handle.line_height(TextClass::Label) + 2 * margin.1;
+            SizeRules::fixed(8 * self.cell.1)
+        }
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        // We centre the grid within rect
+        let size = Size(7 * self.cell.0, 8 * self.cell.1);
+        let extra = Coord::from(rect.size) - Coord::from(size);
+        self.origin = rect.pos + Coord(extra.0.max(0) / 2, extra.1.max(0) / 2);
+        self.core.rect = rect;
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        self.draw_calendar(draw_handle, ev_mgr, false);
+    }
+}
+
+impl Handler for Calendar {
+    type Msg = Date;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<Date> {
+        match action {
+            Action::Activate => self.select(tk, self.cursor),
+            Action::NavKey(key) => {
+                let date = self.cursor;
+                let date = match key {
+                    NavKey::Left => date.add_days(-1),
+                    NavKey::Right => date.add_days(1),
+                    NavKey::Up => date.add_days(-7),
+                    NavKey::Down => date.add_days(7),
+                    NavKey::PageUp => date.add_months(-1),
+                    NavKey::PageDown => date.add_months(1),
+                    NavKey::Home => Date { day: 1, ..date },
+                    NavKey::End => Date {
+                        day: Date::days_in_month(date.year, date.month),
+                        ..date
+                    },
+                };
+                self.set_cursor(tk, date);
+                Response::None
+            }
+            Action::Scroll(delta) => {
+                let y = match delta {
+                    ScrollDelta::LineDelta(_, y) => y,
+                    ScrollDelta::PixelDelta(d) => d.1 as f32,
+                };
+                match y {
+                    y if y > 0.0 => self.set_cursor(tk, self.cursor.add_months(-1)),
+                    y if y < 0.0 => self.set_cursor(tk, self.cursor.add_months(1)),
+                    _ => return Response::unhandled_action(Action::Scroll(delta)),
+                }
+                Response::None
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<Date> {
+        match event {
+            Event::Identify => {
+                let coord = match addr {
+                    Address::Coord(coord) => coord,
+                    Address::Id(_) => tk.data().last_mouse_coord(),
+                };
+                let hover = self.hit(coord);
+                if hover != self.hover {
+                    self.hover = hover;
+                    tk.redraw(self.id());
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.hit(coord)));
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.hit(coord),
+                    false => None,
+                };
+                match start {
+                    Some(hit) if end == start => match hit {
+                        Hit::Previous => self.set_cursor(tk, self.cursor.add_months(-1)),
+                        Hit::Next => self.set_cursor(tk, self.cursor.add_months(1)),
+                        Hit::Day(date) => return self.select(tk, date),
+                    },
+                    _ => (),
+                }
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
+
+type DateBox = EditBox<fn(&str) -> Option<Date>>;
+
+/// A date entry field with a pop-up [`Calendar`]
+///
+/// A date may be typed into the field in ISO 8601 format (`YYYY-MM-DD`) and
+/// confirmed with the enter key; invalid text is reverted. Alternatively,
+/// clicking the button beside the field (or pressing the down arrow key while
+/// the field has key focus) opens a calendar as a pop-up (see
+/// [`ManagerMut::set_popup`]). Selecting a day in the calendar, by mouse or
+/// keyboard, closes it; so does clicking outside the calendar or the Escape
+/// key.
+///
+/// Each new date is reported as a message.
+///
+/// [`ManagerMut::set_popup`]: crate::event::ManagerMut::set_popup
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct DatePicker {
+    #[core]
+    core: CoreData,
+    #[widget]
+    edit: DateBox,
+    #[widget]
+    calendar: Calendar,
+    button_rect: Rect,
+    popup_size: Size,
+    open: bool,
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        DatePicker::new()
+    }
+}
+
+impl DatePicker {
+    /// Construct, without a date
+    pub fn new() -> Self {
+        let edit: DateBox = EditBox::new("").on_activate(Date::parse);
+        DatePicker {
+            core: Default::default(),
+            edit,
+            calendar: Calendar::new(),
+            button_rect: Rect::default(),
+            popup_size: Size::ZERO,
+            open: false,
+        }
+    }
+
+    /// Set the date (inline)
+    pub fn with_date(mut self, date: Date) -> Self {
+        self.edit = EditBox::new(date.to_string()).on_activate(Date::parse);
+        self.calendar = self.calendar.with_date(date);
+        self
+    }
+
+    /// Get the date
+    #[inline]
+    pub fn date(&self) -> Option<Date> {
+        self.calendar.date()
+    }
+
+    /// Set the date
+    ///
+    /// No message is sent.
+    pub fn set_date(&mut self, tk: &mut dyn TkWindow, date: Option<Date>) {
+        let text = date.map(|d| d.to_string()).unwrap_or_default();
+        self.edit.set_string(tk, text);
+        self.calendar.set_date(tk, date);
+    }
+
+    /// True while the calendar is open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn open(&mut self, tk: &mut dyn TkWindow) {
+        self.open = true;
+        let id = self.id();
+        tk.data_mut().set_popup(Some(id));
+    }
+
+    fn close(&mut self, tk: &mut dyn TkWindow) {
+        self.open = false;
+        if tk.data().popup() == Some(self.id()) {
+            tk.data_mut().set_popup(None);
+        }
+    }
+
+    fn edit_response(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        r: Response<Option<Date>>,
+    ) -> Response<Date> {
+        match r {
+            Response::Msg(Some(date)) => {
+                self.calendar.set_date(tk, Some(date));
+                Response::Msg(date)
+            }
+            Response::Msg(None) => {
+                // Revert invalid text
+                let date = self.calendar.date();
+                self.set_date(tk, date);
+                Response::None
+            }
+            Response::Unhandled(Event::Action(Action::NavKey(NavKey::Down))) => {
+                self.open(tk);
+                Response::None
+            }
+            Response::None => Response::None,
+            Response::Identify(id) => Response::Identify(id),
+            Response::Unhandled(e) => Response::Unhandled(e),
+        }
+    }
+
+    fn calendar_response(&mut self, tk: &mut dyn TkWindow, r: Response<Date>) -> Response<Date> {
+        if let Response::Msg(date) = r {
+            self.edit.set_string(tk, date.to_string());
+            self.close(tk);
+        }
+        r
+    }
+}
+
+impl Widget for DatePicker {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let popup = layout::child_rules(&mut self.calendar, size_handle, axis);
+        let rules = layout::child_rules(&mut self.edit, size_handle, axis);
+        let margin = size_handle.inner_margin();
+        let button = size_handle.line_height(TextClass::Button);
+        if !axis.vertical() {
+            self.popup_size.0 = popup.ideal_size();
+            self.button_rect.size.0 = button + 2 * margin.0;
+            rules + self.button_rect.size.0
+        } else {
+            self.popup_size.1 = popup.ideal_size();
+            rules.max(SizeRules::fixed(button + 2 * margin.1))
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let button_width = self.button_rect.size.0.min(rect.size.0);
+        let edit_size = Size(rect.size.0 - button_width, rect.size.1);
+        self.edit.set_rect(
+            size_handle,
+            Rect {
+                pos: rect.pos,
+                size: edit_size,
+            },
+        );
+        self.button_rect = Rect {
+            pos: rect.pos + Coord(edit_size.0 as i32, 0),
+            size: Size(button_width, rect.size.1),
+        };
+        // The calendar opens below the field
+        let pos = rect.pos + Coord(0, rect.size.1 as i32);
+        let size = self.popup_size;
+        self.calendar.set_rect(size_handle, Rect { pos, size });
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        layout::draw_child(&self.edit, draw_handle, ev_mgr);
+        let open = self.open && ev_mgr.popup() == Some(self.id());
+        let hl = HighlightState {
+            depress: open,
+            ..ev_mgr.highlight_state(self.id())
+        };
+        draw_handle.button(self.button_rect, hl);
+        let props = TextProperties {
+            class: TextClass::Button,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+        draw_handle.text(self.button_rect, "▾", props);
+
+        if open {
+            let rect = self.calendar.rect();
+            draw_handle.overlay(rect, &mut |draw_handle| {
+                draw_handle.edit_box(rect, HighlightState::default());
+                self.calendar.draw_calendar(draw_handle, ev_mgr, true);
+            });
+        }
+    }
+}
+
+impl Handler for DatePicker {
+    type Msg = Date;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<Date> {
+        match action {
+            // Keyboard input goes to the pop-up's owner
+            Action::Activate | Action::NavKey(_) if self.open => {
+                let addr = Address::Id(self.calendar.id());
+                let r = self.calendar.handle(tk, addr, Event::Action(action));
+                self.calendar_response(tk, r)
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<Date> {
+        if self.open && tk.data().popup() != Some(self.id()) {
+            // The pop-up was closed externally (e.g. via Escape)
+            self.open = false;
+        }
+
+        match addr {
+            Address::Id(id) if id <= self.edit.id() => {
+                let r = self.edit.handle(tk, addr, event);
+                return self.edit_response(tk, r);
+            }
+            Address::Id(id) if id <= self.calendar.id() => {
+                let r = self.calendar.handle(tk, addr, event);
+                return self.calendar_response(tk, r);
+            }
+            Address::Coord(coord) if self.edit.rect().contains(coord) => {
+                let r = self.edit.handle(tk, addr, event);
+                return self.edit_response(tk, r);
+            }
+            _ => (),
+        }
+
+        match event {
+            Event::Identify => {
+                let coord = match addr {
+                    Address::Coord(coord) => coord,
+                    Address::Id(_) => tk.data().last_mouse_coord(),
+                };
+                if self.open && self.calendar.rect().contains(coord) {
+                    // Presses within the calendar are sent directly to it
+                    let addr = Address::Coord(coord);
+                    return self.calendar.handle(tk, addr, event);
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                if self.open {
+                    self.close(tk);
+                } else if self.button_rect.contains(coord) {
+                    self.open(tk);
+                }
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Canvas for custom drawing
+
+use std::fmt::{self, Debug};
+
+use crate::draw::{Colour, Quad, Style, Vec2};
+use crate::event::{self, Handler, VoidMsg};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Drawing API passed to a [`Canvas`] draw function
+///
+/// All coordinates are relative to the top-left corner of the canvas, and all
+/// drawing is clipped to the canvas. Shapes are drawn in the order given,
+/// except that text is drawn after all shapes.
+pub struct Painter<'a> {
+    handle: &'a mut dyn DrawHandle,
+    rect: Rect,
+}
+
+impl<'a> Painter<'a> {
+    /// Construct, drawing relative to `rect`
+    pub(crate) fn new(handle: &'a mut dyn DrawHandle, rect: Rect) -> Self {
+        Painter { handle, rect }
+    }
+
+    /// Size of the canvas
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.rect.size
+    }
+
+    /// Fill a rectangle
+    pub fn fill_rect(&mut self, rect: Rect, col: Colour) {
+        let (pass, offset, draw) = self.handle.draw_device();
+        let pos = Vec2::from(self.rect.pos + rect.pos + offset);
+        let quad = Quad(pos, pos + Vec2::from(rect.size));
+        draw.draw_quad(pass, quad, Style::Flat, col);
+    }
+
+    /// Fill a triangle
+    pub fn fill_triangle(&mut self, a: Vec2, b: Vec2, c: Vec2, col: Colour) {
+        let (pass, offset, draw) = self.handle.draw_device();
+        let origin = Vec2::from(self.rect.pos + offset);
+        draw.draw_triangle(pass, origin + a, origin + b, origin + c, col);
+    }
+
+    /// Fill a convex polygon
+    ///
+    /// The polygon is drawn as a fan of triangles from the first point, thus
+    /// non-convex polygons may not be drawn correctly. Nothing is drawn given
+    /// fewer than three points.
+    pub fn fill_polygon(&mut self, points: &[Vec2], col: Colour) {
+        if let Some((first, rest)) = points.split_first() {
+            for pair in rest.windows(2) {
+                self.fill_triangle(*first, pair[0], pair[1], col);
+            }
+        }
+    }
+
+    /// Draw a straight line from `a` to `b` with the given `width`
+    ///
+    /// Lines have square (not extended) ends.
+    pub fn line(&mut self, a: Vec2, b: Vec2, width: f32, col: Colour) {
+        let d = b - a;
+        let len = (d.0 * d.0 + d.1 * d.1).sqrt();
+        if len == 0.0 {
+            return;
+        }
+        let n = Vec2(-d.1, d.0) * (0.5 * width / len);
+        self.fill_triangle(a + n, a - n, b + n, col);
+        self.fill_triangle(b + n, a - n, b - n, col);
+    }
+
+    /// Draw a path of straight lines through each point in turn
+    ///
+    /// Segments are not joined smoothly; with wide lines, small gaps may be
+    /// visible at sharp corners.
+    pub fn path(&mut self, points: &[Vec2], width: f32, col: Colour) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], width, col);
+        }
+    }
+
+    /// Draw text within `rect`
+    pub fn text(&mut self, rect: Rect, text: &str, props: TextProperties) {
+        let rect = Rect {
+            pos: self.rect.pos + rect.pos,
+            size: rect.size,
+        };
+        self.handle.text(rect, text, props);
+    }
+}
+
+/// A widget for custom drawing
+///
+/// The draw function is called with a [`Painter`] each time the canvas is
+/// drawn. Any state used by the draw function should be captured by the
+/// closure, for example via `Rc<RefCell<..>>`; after changing such state,
+/// call [`Canvas::redraw`].
+///
+/// The canvas has the given ideal size but may be made smaller or larger.
+#[widget]
+#[derive(Clone, Widget)]
+pub struct Canvas<F: Fn(&mut Painter) + 'static> {
+    #[core]
+    core: CoreData,
+    size: Size,
+    draw_fn: F,
+}
+
+impl<F: Fn(&mut Painter)> Debug for Canvas<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Canvas {{ core: {:?}, size: {:?}, ... }}",
+            self.core, self.size
+        )
+    }
+}
+
+impl<F: Fn(&mut Painter)> Canvas<F> {
+    /// Construct, with the given ideal size and draw function
+    pub fn new(size: Size, draw_fn: F) -> Self {
+        Canvas {
+            core: Default::default(),
+            size,
+            draw_fn,
+        }
+    }
+
+    /// Request a redraw
+    ///
+    /// This should be called whenever state used by the draw function
+    /// changes.
+    #[inline]
+    pub fn redraw(&self, tk: &mut dyn TkWindow) {
+        tk.redraw(self.id());
+    }
+}
+
+impl<F: Fn(&mut Painter)> Widget for Canvas<F> {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let ideal = axis.extract_size(self.size);
+        SizeRules::variable(0, ideal)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let rect = self.core.rect;
+        let draw_fn = &self.draw_fn;
+        draw_handle.clip_region(rect, Coord::ZERO, &mut |handle| {
+            let mut painter = Painter { handle, rect };
+            draw_fn(&mut painter);
+        });
+    }
+}
+
+impl<F: Fn(&mut Painter)> Handler for Canvas<F> {
+    type Msg = VoidMsg;
+}
@@ -5,6 +5,7 @@
 
 //! Toggle widgets
 
+use std::borrow::Cow;
 use std::fmt::{self, Debug};
 
 use crate::class::{HasBool, HasText};
@@ -12,10 +13,15 @@ use crate::event::{self, Action, Handler, Response, VoidMsg};
 use crate::layout::{AxisInfo, SizeRules};
 use crate::macros::Widget;
 use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::widget::Builder;
 use crate::{CoreData, TkWindow, Widget, WidgetCore};
 use kas::geom::{Coord, Rect};
 
 /// A checkable box with optional label
+///
+/// Optionally, the checkbox may support a third, *indeterminate* state (see
+/// [`CheckBox::tristate`]). This is commonly used to represent a mixed state,
+/// for example where a checkbox controls a group of other checkboxes.
 #[widget]
 #[derive(Clone, Default, Widget)]
 pub struct CheckBox<OT: 'static> {
@@ -23,17 +29,27 @@ pub struct CheckBox<OT: 'static> {
     core: CoreData,
     box_pos: Coord,
     text_pos_x: i32,
-    label: String,
+    label: Cow<'static, str>,
     state: bool,
+    tristate: bool,
+    indeterminate: bool,
     on_toggle: OT,
 }
 
+/// Event handler of a [`CheckBox`] receiving the tristate
+///
+/// Construct via [`CheckBox::on_tristate`].
+#[derive(Clone, Copy, Default)]
+pub struct OnTristate<F>(F);
+
 impl<H> Debug for CheckBox<H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
             "CheckBox {{ core: {:?}, state: {:?}, label: {:?}, ... }}",
-            self.core, self.state, self.label
+            self.core,
+            self.get_tristate(),
+            self.label
         )
     }
 }
@@ -69,9 +85,11 @@ impl<OT: 'static> Widget for CheckBox<OT> {
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
         let highlights = ev_mgr.highlight_state(self.id());
-        draw_handle.checkbox(self.box_pos, self.state, highlights);
+        draw_handle.checkbox(self.box_pos, self.get_tristate(), highlights);
         let mut text_rect = self.core.rect;
         text_rect.pos.0 = self.text_pos_x;
+        let indent = (self.text_pos_x - self.core.rect.pos.0) as u32;
+        text_rect.size.0 = text_rect.size.0.saturating_sub(indent);
         if !self.label.is_empty() {
             let props = TextProperties {
                 class: TextClass::Label,
@@ -94,13 +112,17 @@ impl<M, OT: Fn(bool) -> M> CheckBox<OT> {
     ///
     /// The closure `f` is called with the new state of the checkbox when
     /// toggled, and the result of `f` is returned from the event handler.
-    pub fn new_on<S: Into<String>>(label: S, f: OT) -> Self {
+    ///
+    /// A `&'static str` label is borrowed, not copied.
+    pub fn new_on<S: Into<Cow<'static, str>>>(label: S, f: OT) -> Self {
         CheckBox {
             core: Default::default(),
             box_pos: Default::default(),
             text_pos_x: 0,
             label: label.into(),
             state: false,
+            tristate: false,
+            indeterminate: false,
             on_toggle: f,
         }
     }
@@ -110,14 +132,16 @@ impl CheckBox<()> {
     /// Construct a checkbox with a given `label`.
     ///
     /// CheckBox labels are optional; if no label is desired, use an empty
-    /// string.
-    pub fn new<S: Into<String>>(label: S) -> Self {
+    /// string. A `&'static str` label is borrowed, not copied.
+    pub fn new<S: Into<Cow<'static, str>>>(label: S) -> Self {
         CheckBox {
             core: Default::default(),
             box_pos: Default::default(),
             text_pos_x: 0,
             label: label.into(),
             state: false,
+            tristate: false,
+            indeterminate: false,
             on_toggle: (),
         }
     }
@@ -127,28 +151,172 @@ impl CheckBox<()> {
     /// The closure `f` is called with the new state of the checkbox when
     /// toggled, and the result of `f` is returned from the event handler.
     pub fn on_toggle<M, OT: Fn(bool) -> M>(self, f: OT) -> CheckBox<OT> {
+        self.on_toggle_impl(f)
+    }
+
+    /// Set the event handler to be called on toggle, receiving the tristate
+    ///
+    /// The closure `f` is called with the new state of the checkbox when
+    /// toggled, as returned by [`CheckBox::get_tristate`] (thus `None` when
+    /// indeterminate), and the result of `f` is returned from the event
+    /// handler. This is intended for use with tristate mode; see
+    /// [`CheckBox::set_tristate`].
+    pub fn on_tristate<M, F: Fn(Option<bool>) -> M>(self, f: F) -> CheckBox<OnTristate<F>> {
+        self.on_toggle_impl(OnTristate(f))
+    }
+
+    // Replace the handler, without bounds
+    fn on_toggle_impl<OT>(self, f: OT) -> CheckBox<OT> {
         CheckBox {
             core: self.core,
             box_pos: self.box_pos,
             text_pos_x: self.text_pos_x,
             label: self.label,
             state: self.state,
+            tristate: self.tristate,
+            indeterminate: self.indeterminate,
             on_toggle: f,
         }
     }
+
+    /// Construct a checkbox with a static `label`
+    ///
+    /// This is equivalent to [`CheckBox::new`], but usable in `const`
+    /// contexts.
+    pub const fn new_static(label: &'static str) -> Self {
+        CheckBox {
+            core: CoreData::new(),
+            box_pos: Coord::ZERO,
+            text_pos_x: 0,
+            label: Cow::Borrowed(label),
+            state: false,
+            tristate: false,
+            indeterminate: false,
+            on_toggle: (),
+        }
+    }
+
+    /// Construct a builder, starting from an unlabelled, unchecked box
+    pub fn builder() -> Builder<Self> {
+        Builder::new(CheckBox::new(""))
+    }
+}
+
+impl Builder<CheckBox<()>> {
+    /// Set the event handler to be called on toggle
+    ///
+    /// See [`CheckBox::on_toggle`].
+    pub fn on_toggle<M, OT: Fn(bool) -> M>(self, f: OT) -> Builder<CheckBox<OT>> {
+        Builder(self.0.on_toggle(f))
+    }
+
+    /// Set the event handler to be called on toggle, receiving the tristate
+    ///
+    /// See [`CheckBox::on_tristate`].
+    pub fn on_tristate<M, F: Fn(Option<bool>) -> M>(
+        self,
+        f: F,
+    ) -> Builder<CheckBox<OnTristate<F>>> {
+        Builder(self.0.on_tristate(f))
+    }
+}
+
+impl<OT: 'static> Builder<CheckBox<OT>> {
+    /// Set the label
+    ///
+    /// A `&'static str` label is borrowed, not copied.
+    pub fn label<S: Into<Cow<'static, str>>>(mut self, label: S) -> Self {
+        self.0.label = label.into();
+        self
+    }
+
+    /// Set the initial state
+    pub fn state(self, state: bool) -> Self {
+        Builder(self.0.state(state))
+    }
+
+    /// Enable or disable tristate mode
+    ///
+    /// See [`CheckBox::set_tristate`].
+    pub fn tristate(self, tristate: bool) -> Self {
+        Builder(self.0.tristate(tristate))
+    }
+
+    /// Set the indeterminate state
+    pub fn indeterminate(self) -> Self {
+        Builder(self.0.indeterminate())
+    }
 }
 
 impl<OT: 'static> CheckBox<OT> {
     /// Set the initial state of the checkbox.
     pub fn set_state(&mut self, state: bool) {
         self.state = state;
+        self.indeterminate = false;
     }
 
     /// Set the initial state of the checkbox.
     pub fn state(mut self, state: bool) -> Self {
-        self.state = state;
+        self.set_state(state);
         self
     }
+
+    /// Enable or disable tristate mode.
+    ///
+    /// In tristate mode, activation cycles through the states unchecked,
+    /// checked and indeterminate. The `on_toggle` handler is called with
+    /// `false` when the new state is indeterminate; to distinguish this, use
+    /// a handler set by [`CheckBox::on_tristate`] instead.
+    pub fn set_tristate(&mut self, tristate: bool) {
+        self.tristate = tristate;
+    }
+
+    /// Enable or disable tristate mode.
+    ///
+    /// See [`CheckBox::set_tristate`].
+    pub fn tristate(mut self, tristate: bool) -> Self {
+        self.tristate = tristate;
+        self
+    }
+
+    /// Set the indeterminate state.
+    ///
+    /// This is allowed even when not in tristate mode; in this case the next
+    /// activation sets the checkbox to checked.
+    pub fn set_indeterminate(&mut self) {
+        self.state = false;
+        self.indeterminate = true;
+    }
+
+    /// Set the indeterminate state.
+    pub fn indeterminate(mut self) -> Self {
+        self.set_indeterminate();
+        self
+    }
+
+    /// Get the state, including indeterminate state.
+    ///
+    /// Returns `Some(checked)` or `None` if indeterminate.
+    pub fn get_tristate(&self) -> Option<bool> {
+        match self.indeterminate {
+            false => Some(self.state),
+            true => None,
+        }
+    }
+
+    // Advance state on activation
+    fn toggle(&mut self) {
+        if self.indeterminate {
+            // Tristate cycles back to unchecked; otherwise see set_indeterminate
+            self.indeterminate = false;
+            self.state = !self.tristate;
+        } else if self.state && self.tristate {
+            self.indeterminate = true;
+            self.state = false;
+        } else {
+            self.state = !self.state;
+        }
+    }
 }
 
 impl<H> HasBool for CheckBox<H> {
@@ -156,8 +324,9 @@ impl<H> HasBool for CheckBox<H> {
         self.state
     }
 
-    fn set_bool(&mut self, _tk: &mut dyn TkWindow, state: bool) {
-        self.state = state;
+    fn set_bool(&mut self, tk: &mut dyn TkWindow, state: bool) {
+        self.set_state(state);
+        tk.redraw(self.id());
     }
 }
 
@@ -167,7 +336,7 @@ impl<H> HasText for CheckBox<H> {
     }
 
     fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
-        self.label = text;
+        self.label = Cow::Owned(text);
         tk.redraw(self.id());
     }
 }
@@ -183,7 +352,7 @@ impl Handler for CheckBox<()> {
     fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
         match action {
             Action::Activate => {
-                self.state = !self.state;
+                self.toggle();
                 tk.redraw(self.id());
                 Response::None
             }
@@ -203,7 +372,7 @@ impl<M, H: Fn(bool) -> M> Handler for CheckBox<H> {
     fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<M> {
         match action {
             Action::Activate => {
-                self.state = !self.state;
+                self.toggle();
                 tk.redraw(self.id());
                 ((self.on_toggle)(self.state)).into()
             }
@@ -211,3 +380,23 @@ impl<M, H: Fn(bool) -> M> Handler for CheckBox<H> {
         }
     }
 }
+
+impl<M, F: Fn(Option<bool>) -> M> Handler for CheckBox<OnTristate<F>> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<M> {
+        match action {
+            Action::Activate => {
+                self.toggle();
+                tk.redraw(self.id());
+                ((self.on_toggle.0)(self.get_tristate())).into()
+            }
+            a @ _ => Response::unhandled_action(a),
+        }
+    }
+}
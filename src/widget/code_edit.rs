@@ -0,0 +1,807 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Source code editor
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::ops::Range;
+
+use super::text::LastEdit;
+use crate::class::{Editable, HasText};
+use crate::draw::Colour;
+use crate::event::{
+    self, Action, Address, Deferred, Event, Handler, Manager, NavKey, Response, ScrollDelta,
+    VoidMsg,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties, TextStyle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Number of columns between tab stops
+const TAB_WIDTH: usize = 4;
+
+/// Minimum width of the text area, in lines
+const MIN_WIDTH_LINES: u32 = 8;
+
+/// Maximum ideal width of the text area, in lines
+const MAX_WIDTH_LINES: u32 = 40;
+
+/// Maximum ideal height, in rows
+const MAX_IDEAL_ROWS: u32 = 16;
+
+fn is_control(c: char) -> bool {
+    c < '\u{20}' || ('\u{7f}'..='\u{9f}').contains(&c)
+}
+
+/// Assigns text styles to lines of source code
+///
+/// Lines are highlighted in order, from the first; `State` is passed from each
+/// line to the next, allowing constructs spanning several lines (for example
+/// block comments). Each span is a byte range of `line` with the style to draw
+/// it with; spans should be in order and should not overlap. Unstyled text is
+/// drawn in the theme's colour.
+///
+/// ```
+/// use kas::widget::{Highlighter, SimpleHighlighter};
+///
+/// let highlighter = SimpleHighlighter::new()
+///     .with_keywords(&["fn", "let"])
+///     .with_line_comment("//");
+/// let mut state = Default::default();
+/// let spans = highlighter.highlight_line("let x = 1; // one", &mut state);
+/// let ranges: Vec<_> = spans.into_iter().map(|(range, _)| range).collect();
+/// assert_eq!(ranges, vec![0..3, 8..9, 11..17]);
+/// ```
+pub trait Highlighter: Clone + Debug {
+    /// State passed between lines
+    type State: Default;
+
+    /// Find the styled spans of one line (without line break)
+    fn highlight_line(&self, line: &str, state: &mut Self::State)
+        -> Vec<(Range<usize>, TextStyle)>;
+}
+
+/// A [`Highlighter`] which leaves all text unstyled
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlainText;
+
+impl Highlighter for PlainText {
+    type State = ();
+
+    fn highlight_line(&self, _: &str, _: &mut ()) -> Vec<(Range<usize>, TextStyle)> {
+        vec![]
+    }
+}
+
+/// A simple, configurable [`Highlighter`]
+///
+/// This recognises keywords, numbers, double-quoted strings (with backslash
+/// escapes), line comments and block comments. It is not aware of any
+/// particular language's grammar, but is adequate for many C-like languages.
+#[derive(Clone, Debug)]
+pub struct SimpleHighlighter {
+    keywords: Vec<Cow<'static, str>>,
+    line_comment: Option<Cow<'static, str>>,
+    block_comment: Option<(Cow<'static, str>, Cow<'static, str>)>,
+    /// Colour of keywords
+    pub keyword: Colour,
+    /// Colour of numeric literals
+    pub number: Colour,
+    /// Colour of string literals
+    pub string: Colour,
+    /// Colour of comments
+    pub comment: Colour,
+}
+
+impl Default for SimpleHighlighter {
+    fn default() -> Self {
+        SimpleHighlighter::new()
+    }
+}
+
+impl SimpleHighlighter {
+    /// Construct, with no keywords or comments
+    pub fn new() -> Self {
+        SimpleHighlighter {
+            keywords: vec![],
+            line_comment: None,
+            block_comment: None,
+            keyword: Colour::new(0.55, 0.25, 0.75),
+            number: Colour::new(0.8, 0.4, 0.1),
+            string: Colour::new(0.2, 0.55, 0.2),
+            comment: Colour::grey(0.5),
+        }
+    }
+
+    /// Set the keywords
+    pub fn with_keywords(mut self, keywords: &[&'static str]) -> Self {
+        self.keywords = keywords.iter().map(|k| Cow::Borrowed(*k)).collect();
+        self
+    }
+
+    /// Set the line comment prefix (for example `//`)
+    pub fn with_line_comment<S: Into<Cow<'static, str>>>(mut self, prefix: S) -> Self {
+        self.line_comment = Some(prefix.into());
+        self
+    }
+
+    /// Set the block comment delimiters (for example `/*` and `*/`)
+    pub fn with_block_comment<S: Into<Cow<'static, str>>, E: Into<Cow<'static, str>>>(
+        mut self,
+        start: S,
+        end: E,
+    ) -> Self {
+        self.block_comment = Some((start.into(), end.into()));
+        self
+    }
+
+    fn style(colour: Colour) -> TextStyle {
+        TextStyle {
+            colour: Some(colour),
+            ..TextStyle::default()
+        }
+    }
+}
+
+impl Highlighter for SimpleHighlighter {
+    /// True while within a block comment
+    type State = bool;
+
+    fn highlight_line(&self, line: &str, in_comment: &mut bool) -> Vec<(Range<usize>, TextStyle)> {
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut spans = vec![];
+        let mut i = 0;
+        while i < line.len() {
+            let rest = &line[i..];
+            if let (true, Some((_, end))) = (*in_comment, self.block_comment.as_ref()) {
+                let len = match rest.find(end.as_ref()) {
+                    Some(j) => {
+                        *in_comment = false;
+                        j + end.len()
+                    }
+                    None => rest.len(),
+                };
+                spans.push((i..i + len, Self::style(self.comment)));
+                i += len;
+                continue;
+            }
+            if let Some(prefix) = self.line_comment.as_ref() {
+                if rest.starts_with(prefix.as_ref()) {
+                    spans.push((i..line.len(), Self::style(self.comment)));
+                    break;
+                }
+            }
+            if let Some((start, _)) = self.block_comment.as_ref() {
+                if rest.starts_with(start.as_ref()) {
+                    *in_comment = true;
+                    spans.push((i..i + start.len(), Self::style(self.comment)));
+                    i += start.len();
+                    continue;
+                }
+            }
+
+            let c = rest.chars().next().unwrap();
+            let len = if c == '"' {
+                let mut escaped = false;
+                let end = rest[1..].char_indices().find(|(_, c)| {
+                    let end = !escaped && *c == '"';
+                    escaped = !escaped && *c == '\\';
+                    end
+                });
+                let len = end.map(|(j, _)| j + 2).unwrap_or(rest.len());
+                spans.push((i..i + len, Self::style(self.string)));
+                len
+            } else if is_ident(c) {
+                let len = rest.find(|c| !is_ident(c)).unwrap_or(rest.len());
+                let word = &rest[..len];
+                if c.is_ascii_digit() {
+                    spans.push((i..i + len, Self::style(self.number)));
+                } else if self.keywords.iter().any(|k| k == word) {
+                    spans.push((i..i + len, Self::style(self.keyword)));
+                }
+                len
+            } else {
+                c.len_utf8()
+            };
+            i += len;
+        }
+        spans
+    }
+}
+
+/// An editor for source code
+///
+/// Text is drawn in a monospace font (where the theme provides one) with line
+/// numbers in a left gutter. Each line is styled by a [`Highlighter`] (by
+/// default, [`PlainText`]); the whole text is highlighted again after each
+/// edit. Long lines are not wrapped; instead the text area scrolls in both
+/// directions, following the caret. Only visible lines are drawn.
+///
+/// Editing is as in a multi-line [`EditBox`], except that the Tab key inserts
+/// spaces up to the next tab stop, a line break keeps the indentation of the
+/// current line, and the Home key moves to the first non-blank character of
+/// the line (or, if already there, to the start of the line).
+///
+/// [`EditBox`]: crate::widget::EditBox
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct CodeEdit<H: Highlighter + 'static = PlainText> {
+    #[core]
+    core: CoreData,
+    highlighter: H,
+    editable: bool,
+    text: String,
+    // Byte index of the start of each line
+    line_starts: Vec<usize>,
+    // Styled spans of each line, relative to its start
+    styles: Vec<Vec<(Range<usize>, TextStyle)>>,
+    caret: usize,
+    // Column kept when moving between lines
+    goal_column: Option<usize>,
+    old_state: Option<String>,
+    last_edit: LastEdit,
+    text_rect: Rect,
+    gutter_width: u32,
+    row_height: u32,
+    content_size: Size,
+    offset: Coord,
+    scroll_rate: f32,
+}
+
+impl CodeEdit<PlainText> {
+    /// Construct an editor with the given text, without highlighting
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        let mut edit = CodeEdit {
+            core: Default::default(),
+            highlighter: PlainText,
+            editable: true,
+            text: text.into(),
+            line_starts: vec![],
+            styles: vec![],
+            caret: 0,
+            goal_column: None,
+            old_state: None,
+            last_edit: LastEdit::None,
+            text_rect: Rect::default(),
+            gutter_width: 0,
+            row_height: 0,
+            content_size: Size::ZERO,
+            offset: Coord::ZERO,
+            scroll_rate: 0.0,
+        };
+        edit.update_lines();
+        edit
+    }
+}
+
+impl<H: Highlighter + 'static> CodeEdit<H> {
+    /// Set the highlighter
+    pub fn with_highlighter<H2: Highlighter>(self, highlighter: H2) -> CodeEdit<H2> {
+        let mut edit = CodeEdit {
+            core: self.core,
+            highlighter,
+            editable: self.editable,
+            text: self.text,
+            line_starts: self.line_starts,
+            styles: vec![],
+            caret: self.caret,
+            goal_column: self.goal_column,
+            old_state: self.old_state,
+            last_edit: self.last_edit,
+            text_rect: self.text_rect,
+            gutter_width: self.gutter_width,
+            row_height: self.row_height,
+            content_size: self.content_size,
+            offset: self.offset,
+            scroll_rate: self.scroll_rate,
+        };
+        edit.update_lines();
+        edit
+    }
+
+    /// Set whether the text is editable (inline)
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Access the highlighter
+    #[inline]
+    pub fn highlighter(&self) -> &H {
+        &self.highlighter
+    }
+
+    /// Get the number of lines
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Get a line of text (without line break)
+    ///
+    /// Panics if `index >= self.line_count()`.
+    pub fn line(&self, index: usize) -> &str {
+        let start = self.line_starts[index];
+        let end = match self.line_starts.get(index + 1) {
+            Some(next) => next - 1,
+            None => self.text.len(),
+        };
+        &self.text[start..end]
+    }
+
+    /// Get the caret position (a byte index into the text)
+    #[inline]
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Get the caret's line and column (in `char`s), both from zero
+    pub fn caret_position(&self) -> (usize, usize) {
+        let line = self.line_of(self.caret);
+        (line, self.column(line, self.caret))
+    }
+
+    /// Set the caret position
+    ///
+    /// The `index` is clamped to the text length and rounded down to a `char`
+    /// boundary. This scrolls as necessary to show the caret.
+    pub fn set_caret(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        self.goal_column = None;
+        self.move_caret(tk, index);
+    }
+
+    fn move_caret(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        let mut index = index.min(self.text.len());
+        while !self.text.is_char_boundary(index) {
+            index -= 1;
+        }
+        self.caret = index;
+        self.last_edit = LastEdit::None;
+        self.show_caret(tk);
+    }
+
+    // Find line starts and highlight all lines
+    fn update_lines(&mut self) {
+        self.line_starts = Some(0)
+            .into_iter()
+            .chain(self.text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut state = H::State::default();
+        let styles = (0..self.line_count())
+            .map(|i| self.highlighter.highlight_line(self.line(i), &mut state))
+            .collect();
+        self.styles = styles;
+    }
+
+    // Line containing byte `index`
+    fn line_of(&self, index: usize) -> usize {
+        match self.line_starts.binary_search(&index) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        }
+    }
+
+    // Column (in chars) of byte `index` within `line`
+    fn column(&self, line: usize, index: usize) -> usize {
+        self.text[self.line_starts[line]..index].chars().count()
+    }
+
+    // Byte index of `column` (in chars) within `line`, clamped to its end
+    fn index_of_column(&self, line: usize, column: usize) -> usize {
+        let text = self.line(line);
+        let offset = (text.char_indices().nth(column))
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        self.line_starts[line] + offset
+    }
+
+    fn props(horiz: Align) -> TextProperties {
+        TextProperties {
+            class: TextClass::Code,
+            multi_line: false,
+            horiz,
+            vert: Align::Centre,
+        }
+    }
+
+    // Rect of `line` in the text area, before scrolling
+    fn row_rect(&self, line: usize) -> Rect {
+        let y = line as u32 * self.row_height;
+        Rect {
+            pos: self.text_rect.pos + Coord(0, y as i32),
+            size: Size(self.content_size.0, self.row_height),
+        }
+    }
+
+    // Number of rows fully visible
+    fn rows(&self) -> usize {
+        match self.row_height {
+            0 => 0,
+            h => (self.text_rect.size.1 / h) as usize,
+        }
+    }
+
+    // Range of visible lines
+    fn visible(&self) -> Range<usize> {
+        let h = self.row_height;
+        if h == 0 {
+            return 0..0;
+        }
+        let first = self.offset.1 as u32 / h;
+        let last = (self.offset.1 as u32 + self.text_rect.size.1).div_ceil(h);
+        let end = (last as usize).min(self.line_count());
+        (first as usize).min(end)..end
+    }
+
+    // Update the gutter, content size and scroll offset, optionally scrolling
+    // to show the caret
+    fn update_view(&mut self, size_handle: &mut dyn SizeHandle, show_caret: bool) {
+        let rect = self.core.rect;
+        let sides = size_handle.edit_surround();
+        let inner = Rect {
+            pos: rect.pos + sides.0,
+            size: rect.size - (sides.0 + sides.1),
+        };
+        let margin = size_handle.inner_margin();
+        let line_height = size_handle.line_height(TextClass::Code);
+        self.row_height = line_height + margin.1;
+
+        let axis = AxisInfo::new(false, None);
+        let digits = "0".repeat(self.line_count().to_string().len());
+        let rules = size_handle.text_bound(&digits, TextClass::Code, false, axis);
+        self.gutter_width = rules.ideal_size().min(inner.size.0);
+        self.text_rect = Rect {
+            pos: inner.pos + Coord(self.gutter_width as i32, 0),
+            size: Size(inner.size.0 - self.gutter_width, inner.size.1),
+        };
+
+        // In a monospace font, the line with the most chars is the widest
+        let view = self.text_rect.size;
+        let longest = (0..self.line_count())
+            .max_by_key(|i| self.line(*i).chars().count())
+            .unwrap_or(0);
+        let rules = size_handle.text_bound(self.line(longest), TextClass::Code, false, axis);
+        // Leave space for the caret after the longest line
+        let width = view.0.max(rules.ideal_size() + line_height);
+        let height = view.1.max(self.line_count() as u32 * self.row_height);
+        self.content_size = Size(width, height);
+
+        let mut offset = self.offset;
+        if show_caret {
+            let line = self.line_of(self.caret);
+            let row = self.row_rect(line);
+            let index = self.caret - self.line_starts[line];
+            let props = Self::props(Align::Begin);
+            let caret = size_handle.text_caret_rect(row, self.line(line), props, index);
+            let x = caret.pos.0 - row.pos.0;
+            let (x0, x1) = (x - margin.0 as i32, x + margin.0 as i32);
+            let y0 = row.pos.1 - self.text_rect.pos.1;
+            let y1 = y0 + self.row_height as i32;
+            offset.0 = offset.0.min(x0).max(x1 - view.0 as i32);
+            offset.1 = offset.1.min(y0).max(y1 - view.1 as i32);
+        }
+        self.offset = self.clamp_offset(offset);
+    }
+
+    fn clamp_offset(&self, offset: Coord) -> Coord {
+        let max = Coord::from(self.content_size) - Coord::from(self.text_rect.size);
+        Coord(offset.0.min(max.0).max(0), offset.1.min(max.1).max(0))
+    }
+
+    fn show_caret(&mut self, tk: &mut dyn TkWindow) {
+        tk.with_size_handle(&mut |size_handle| self.update_view(size_handle, true));
+        tk.redraw(self.id());
+    }
+
+    fn set_caret_from_coord(&mut self, tk: &mut dyn TkWindow, coord: Coord) {
+        if self.row_height == 0 {
+            return;
+        }
+        let coord = coord + self.offset;
+        let y = (coord.1 - self.text_rect.pos.1).max(0) as u32;
+        let line = ((y / self.row_height) as usize).min(self.line_count() - 1);
+        let mut index = self.line_starts[line];
+        tk.with_size_handle(&mut |size_handle| {
+            let row = self.row_rect(line);
+            let props = Self::props(Align::Begin);
+            index += size_handle.text_index_nearest(row, self.line(line), props, coord);
+        });
+        self.set_caret(tk, index);
+    }
+
+    // Move the caret, returning false if the key is not used
+    fn nav_key(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> bool {
+        let line = self.line_of(self.caret);
+        let last = self.line_count() - 1;
+        let page = self.rows().saturating_sub(1).max(1);
+        let target = match key {
+            NavKey::Up => line.saturating_sub(1),
+            NavKey::Down => (line + 1).min(last),
+            NavKey::PageUp => line.saturating_sub(page),
+            NavKey::PageDown => (line + page).min(last),
+            _ => {
+                let start = self.line_starts[line];
+                let index = match key {
+                    NavKey::Left => (self.text[..self.caret].char_indices().next_back())
+                        .map(|(i, _)| i)
+                        .unwrap_or(0),
+                    NavKey::Right => (self.text[self.caret..].chars().next())
+                        .map(|c| self.caret + c.len_utf8())
+                        .unwrap_or(self.caret),
+                    NavKey::Home => {
+                        let text = self.line(line);
+                        let indent = text.len() - text.trim_start().len();
+                        match self.caret == start + indent {
+                            true => start,
+                            false => start + indent,
+                        }
+                    }
+                    _ => start + self.line(line).len(),
+                };
+                self.set_caret(tk, index);
+                return true;
+            }
+        };
+        let column = match self.goal_column {
+            Some(column) => column,
+            None => self.column(line, self.caret),
+        };
+        self.goal_column = Some(column);
+        let index = self.index_of_column(target, column);
+        self.move_caret(tk, index);
+        true
+    }
+
+    // Scroll, returning false if already at the limit
+    fn scroll(&mut self, tk: &mut dyn TkWindow, delta: ScrollDelta) -> bool {
+        let d = match delta {
+            ScrollDelta::LineDelta(x, y) => Coord(
+                (-self.scroll_rate * x) as i32,
+                (self.scroll_rate * y) as i32,
+            ),
+            ScrollDelta::PixelDelta(d) => d,
+        };
+        let offset = self.clamp_offset(self.offset - d);
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        tk.redraw(self.id());
+        true
+    }
+
+    // Insert `s` at the caret
+    fn insert(&mut self, s: &str, edit: LastEdit) {
+        if self.last_edit != edit {
+            self.old_state = Some(self.text.clone());
+            self.last_edit = edit;
+        }
+        self.text.insert_str(self.caret, s);
+        self.caret += s.len();
+    }
+
+    // Remove `range` of the text, leaving the caret at its start
+    fn remove(&mut self, range: Range<usize>, edit: LastEdit) {
+        if range.start == range.end {
+            return;
+        }
+        if self.last_edit != edit {
+            self.old_state = Some(self.text.clone());
+            self.last_edit = edit;
+        }
+        self.caret = range.start;
+        self.text.replace_range(range, "");
+    }
+
+    fn received_char(&mut self, tk: &mut dyn TkWindow, c: char) {
+        if c == '\u{03}' {
+            // we don't yet have selection support, so just copy everything
+            tk.set_clipboard(self.text.clone());
+            return;
+        }
+        if !self.editable {
+            return;
+        }
+
+        if is_control(c) {
+            match c {
+                '\u{08}' /* backspace */ => {
+                    let start = (self.text[..self.caret].char_indices().next_back())
+                        .map(|(i, _)| i)
+                        .unwrap_or(0);
+                    self.remove(start..self.caret, LastEdit::Backspace);
+                }
+                '\u{09}' /* tab */ => {
+                    let column = self.column(self.line_of(self.caret), self.caret);
+                    let spaces = " ".repeat(TAB_WIDTH - column % TAB_WIDTH);
+                    self.insert(&spaces, LastEdit::Insert);
+                }
+                '\u{0A}' /* line feed */ | '\u{0D}' /* carriage return (\r) */ => {
+                    // Keep the indentation of the current line
+                    let start = self.line_starts[self.line_of(self.caret)];
+                    let text = &self.text[start..self.caret];
+                    let indent = &text[..text.len() - text.trim_start().len()];
+                    let text = format!("\n{}", indent);
+                    self.insert(&text, LastEdit::Insert);
+                }
+                '\u{16}' /* paste */ => {
+                    if let Some(content) = tk.get_clipboard() {
+                        // We cut the content short on control characters
+                        // other than line breaks and tabs, and replace tabs
+                        // with spaces.
+                        let mut text = String::with_capacity(content.len());
+                        for c in content.chars() {
+                            match c {
+                                '\n' => text.push(c),
+                                '\r' => (),
+                                '\t' => text.push_str(&" ".repeat(TAB_WIDTH)),
+                                c if is_control(c) => break,
+                                c => text.push(c),
+                            }
+                        }
+                        self.insert(&text, LastEdit::Paste);
+                    }
+                }
+                '\u{1A}' /* undo and redo */ => {
+                    // NOTE: undo *and* redo shortcuts map to this control char
+                    if let Some(state) = self.old_state.as_mut() {
+                        std::mem::swap(state, &mut self.text);
+                        self.caret = self.text.len();
+                        self.last_edit = LastEdit::None;
+                    }
+                }
+                '\u{7f}' /* delete */ => {
+                    let end = (self.text[self.caret..].chars().next())
+                        .map(|c| self.caret + c.len_utf8())
+                        .unwrap_or(self.caret);
+                    self.remove(self.caret..end, LastEdit::Delete);
+                }
+                _ => return,
+            };
+        } else {
+            let mut buf = [0; 4];
+            self.insert(c.encode_utf8(&mut buf), LastEdit::Insert);
+        }
+        self.goal_column = None;
+        self.update_lines();
+        self.show_caret(tk);
+    }
+}
+
+impl<H: Highlighter + 'static> Widget for CodeEdit<H> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.edit_surround();
+        let line_height = size_handle.line_height(TextClass::Code);
+        let row_height = line_height + size_handle.inner_margin().1;
+        let frame = SizeRules::fixed(axis.extract_size(sides.0 + sides.1));
+        if !axis.vertical() {
+            let digits = "0".repeat(self.line_count().to_string().len());
+            let gutter = size_handle.text_bound(&digits, TextClass::Code, false, axis);
+            let longest = (0..self.line_count())
+                .max_by_key(|i| self.line(*i).chars().count())
+                .unwrap_or(0);
+            let rules = size_handle.text_bound(self.line(longest), TextClass::Code, false, axis);
+            let (min, max) = (MIN_WIDTH_LINES * line_height, MAX_WIDTH_LINES * line_height);
+            let ideal = rules.ideal_size().max(min).min(max);
+            frame + gutter + SizeRules::variable(min, ideal)
+        } else {
+            let rows = (self.line_count() as u32).clamp(4, MAX_IDEAL_ROWS);
+            frame + SizeRules::variable(row_height, rows * row_height)
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.scroll_rate = 3.0 * size_handle.line_height(TextClass::Code) as f32;
+        self.update_view(size_handle, false);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        draw_handle.edit_box(self.core.rect, highlights);
+        let caret_line = match highlights.char_focus {
+            true => Some(self.line_of(self.caret)),
+            false => None,
+        };
+
+        // The gutter scrolls with the text, but only vertically
+        let gutter = Rect {
+            pos: self.text_rect.pos - Coord(self.gutter_width as i32, 0),
+            size: Size(self.gutter_width, self.text_rect.size.1),
+        };
+        let offset = Coord(0, self.offset.1);
+        draw_handle.clip_region(gutter, offset, &mut |draw_handle| {
+            for line in self.visible() {
+                let rect = Rect {
+                    pos: Coord(gutter.pos.0, self.row_rect(line).pos.1),
+                    size: Size(self.gutter_width, self.row_height),
+                };
+                let number = (line + 1).to_string();
+                draw_handle.text(rect, &number, Self::props(Align::End));
+            }
+        });
+
+        draw_handle.clip_region(self.text_rect, self.offset, &mut |draw_handle| {
+            for line in self.visible() {
+                let caret = match caret_line == Some(line) {
+                    true => Some(self.caret - self.line_starts[line]),
+                    false => None,
+                };
+                draw_handle.text_styled(
+                    self.row_rect(line),
+                    self.line(line),
+                    Self::props(Align::Begin),
+                    &self.styles[line],
+                    caret,
+                );
+            }
+        });
+    }
+}
+
+impl<H: Highlighter + 'static> HasText for CodeEdit<H> {
+    fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.text = text;
+        self.caret = 0;
+        self.goal_column = None;
+        self.update_lines();
+        self.show_caret(tk);
+    }
+}
+
+impl<H: Highlighter + 'static> Editable for CodeEdit<H> {
+    fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    fn set_editable(&mut self, editable: bool) {
+        self.editable = editable;
+    }
+}
+
+impl<H: Highlighter + 'static> Handler for CodeEdit<H> {
+    type Msg = VoidMsg;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
+        match action {
+            Action::Activate => {
+                tk.defer(Deferred::SetCharFocus(Some(self.id())));
+                Response::None
+            }
+            Action::ReceivedCharacter(c) => {
+                self.received_char(tk, c);
+                Response::None
+            }
+            Action::NavKey(key) if self.nav_key(tk, key) => Response::None,
+            Action::Scroll(delta) if self.scroll(tk, delta) => Response::None,
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        if let Event::PressStart { source, coord } = event {
+            if source.is_primary() {
+                self.set_caret_from_coord(tk, coord);
+            }
+        }
+        Manager::handle_generic(self, tk, event)
+    }
+}
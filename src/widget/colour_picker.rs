@@ -0,0 +1,380 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Colour picker
+
+use super::property_grid::{format_colour, parse_colour, Swatch};
+use crate::class::HasText;
+use crate::draw::{Colour, Quad, Style, Vec2};
+use crate::event::{self, Action, Address, Event, Handler, Manager, NavKey, PressSource, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, Horizontal, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle, TextClass};
+use crate::widget::{EditBox, Label, Slider};
+use crate::{CoreData, LayoutData, TkWindow, Widget, WidgetCore};
+
+/// Number of rows in which each strip of the hue/saturation plane is drawn
+///
+/// Each strip is a bilinear gradient, which is only approximated by
+/// [`crate::draw::Draw::draw_gradient`]; smaller parts reduce the error.
+const PLANE_ROWS: u32 = 8;
+
+/// Convert hue (degrees), saturation and value to a colour
+fn hsv_to_colour(h: f32, s: f32, v: f32) -> Colour {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let f = h - h.floor();
+    let (p, q, t) = (v * (1.0 - s), v * (1.0 - s * f), v * (1.0 - s * (1.0 - f)));
+    let (r, g, b) = match h as u32 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    };
+    Colour::new(r, g, b)
+}
+
+/// Convert a colour to hue (degrees), saturation and value
+///
+/// Hue is undefined for greys (and saturation for black), in which case
+/// `hue` (and `sat`) are returned unchanged.
+fn colour_to_hsv(c: Colour, hue: f32, sat: f32) -> (f32, f32, f32) {
+    let max = c.r.max(c.g).max(c.b);
+    let min = c.r.min(c.g).min(c.b);
+    let d = max - min;
+    if max <= 0.0 {
+        return (hue, sat, 0.0);
+    }
+    if d <= 0.0 {
+        return (hue, 0.0, max);
+    }
+    let h = if max == c.r {
+        ((c.g - c.b) / d).rem_euclid(6.0)
+    } else if max == c.g {
+        (c.b - c.r) / d + 2.0
+    } else {
+        (c.r - c.g) / d + 4.0
+    };
+    (60.0 * h, d / max, max)
+}
+
+fn to_byte(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Hue/saturation plane: hue increases to the right, saturation upwards
+#[widget]
+#[derive(Clone, Debug, Widget)]
+struct HsPlane {
+    #[core]
+    core: CoreData,
+    hue: f32,
+    sat: f32,
+    value: f32,
+    frame: Size,
+    line_height: u32,
+    press: Option<PressSource>,
+}
+
+impl HsPlane {
+    fn new(hue: f32, sat: f32, value: f32) -> Self {
+        HsPlane {
+            core: Default::default(),
+            hue,
+            sat,
+            value,
+            frame: Size::ZERO,
+            line_height: 0,
+            press: None,
+        }
+    }
+
+    fn set_hsv(&mut self, tk: &mut dyn TkWindow, hue: f32, sat: f32, value: f32) {
+        self.hue = hue;
+        self.sat = sat;
+        self.value = value;
+        tk.redraw(self.id());
+    }
+
+    // The gradient area, within the frame
+    fn inner(&self) -> Rect {
+        let rect = self.core.rect;
+        Rect {
+            pos: rect.pos + Coord::from(self.frame),
+            size: rect.size - (self.frame + self.frame).min(rect.size),
+        }
+    }
+
+    fn set_coord(&mut self, tk: &mut dyn TkWindow, coord: Coord) -> Response<(f32, f32)> {
+        let inner = self.inner();
+        if inner.size.0 == 0 || inner.size.1 == 0 {
+            return Response::None;
+        }
+        let rel = coord - inner.pos;
+        let x = (rel.0 as f32 / inner.size.0 as f32).clamp(0.0, 1.0);
+        let y = (rel.1 as f32 / inner.size.1 as f32).clamp(0.0, 1.0);
+        self.set_hs(tk, 360.0 * x, 1.0 - y)
+    }
+
+    fn set_hs(&mut self, tk: &mut dyn TkWindow, hue: f32, sat: f32) -> Response<(f32, f32)> {
+        let (hue, sat) = (hue.clamp(0.0, 360.0), sat.clamp(0.0, 1.0));
+        if (hue, sat) == (self.hue, self.sat) {
+            return Response::None;
+        }
+        self.hue = hue;
+        self.sat = sat;
+        tk.redraw(self.id());
+        Response::Msg((hue, sat))
+    }
+}
+
+impl Widget for HsPlane {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.frame = size_handle.edit_surround().0;
+        self.line_height = size_handle.line_height(TextClass::Label);
+        let frame = axis.extract_size(self.frame + self.frame);
+        SizeRules::variable(4 * self.line_height, 8 * self.line_height) + SizeRules::fixed(frame)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        draw_handle.edit_box(self.core.rect, ev_mgr.highlight_state(self.id()));
+
+        let inner = self.inner();
+        let (pass, offset, draw) = draw_handle.draw_device();
+        let pos = Vec2::from(inner.pos + offset);
+        let size = Vec2::from(inner.size);
+        let grey = Colour::grey(self.value);
+        // Colour is linear in hue over each sixth, and linear in saturation
+        for strip in 0..6 {
+            let (x0, x1) = (strip as f32 / 6.0, (strip + 1) as f32 / 6.0);
+            let (c0, c1) = (
+                hsv_to_colour(60.0 * strip as f32, 1.0, self.value),
+                hsv_to_colour(60.0 * (strip + 1) as f32, 1.0, self.value),
+            );
+            for row in 0..PLANE_ROWS {
+                let (y0, y1) = (
+                    row as f32 / PLANE_ROWS as f32,
+                    (row + 1) as f32 / PLANE_ROWS as f32,
+                );
+                let mix = |c: Colour, y: f32| {
+                    let s = 1.0 - y;
+                    Colour::new(
+                        grey.r + s * (c.r - grey.r),
+                        grey.g + s * (c.g - grey.g),
+                        grey.b + s * (c.b - grey.b),
+                    )
+                };
+                let quad = Quad(
+                    pos + Vec2(x0 * size.0, y0 * size.1),
+                    pos + Vec2(x1 * size.0, y1 * size.1),
+                );
+                let cols = [mix(c0, y0), mix(c1, y0), mix(c0, y1), mix(c1, y1)];
+                draw.draw_gradient(pass, quad, cols);
+            }
+        }
+
+        // Marker: a small square frame, contrasting with the colour
+        let centre = pos + Vec2(self.hue / 360.0 * size.0, (1.0 - self.sat) * size.1);
+        let half = (self.line_height / 4).max(2) as f32;
+        let outer = Quad(centre - Vec2::splat(half), centre + Vec2::splat(half));
+        let mut inner = outer;
+        inner.shrink((half / 2.0).floor());
+        let col = match self.value > 0.5 {
+            true => Colour::grey(0.0),
+            false => Colour::grey(1.0),
+        };
+        draw.draw_frame(pass, outer, inner, Style::Flat, col);
+    }
+}
+
+impl Handler for HsPlane {
+    type Msg = (f32, f32);
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<Self::Msg> {
+        let (h, s) = (self.hue, self.sat);
+        match action {
+            Action::NavKey(NavKey::Left) => self.set_hs(tk, h - 5.0, s),
+            Action::NavKey(NavKey::Right) => self.set_hs(tk, h + 5.0, s),
+            Action::NavKey(NavKey::Up) => self.set_hs(tk, h, s + 0.05),
+            Action::NavKey(NavKey::Down) => self.set_hs(tk, h, s - 0.05),
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some(source);
+                self.set_coord(tk, coord)
+            }
+            Event::PressMove { source, coord, .. } if self.press == Some(source) => {
+                self.set_coord(tk, coord)
+            }
+            Event::PressEnd { source, .. } if self.press == Some(source) => {
+                self.press = None;
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
+
+type ChannelSlider = Slider<u8, Horizontal>;
+
+/// A colour picker
+///
+/// The colour may be chosen on a plane of hue (horizontal) and saturation
+/// (vertical), together with a slider for value (brightness); or with sliders
+/// for each of the red, green and blue channels; or by entering a hex code
+/// (`#rrggbb`) followed by the enter key. A swatch shows the result.
+///
+/// Each change is reported as a message with the new colour.
+#[widget(layout = grid)]
+#[handler(msg = Colour)]
+#[derive(Clone, Debug, Widget)]
+pub struct ColourPicker {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget(row = 0, col = 0, rspan = 5, handler = handle_plane)]
+    plane: HsPlane,
+    #[widget(row = 0, col = 1)]
+    value_label: Label,
+    #[widget(row = 0, col = 2, cspan = 2, handler = handle_value)]
+    value_slider: ChannelSlider,
+    #[widget(row = 1, col = 1)]
+    red_label: Label,
+    #[widget(row = 1, col = 2, cspan = 2, handler = handle_red)]
+    red: ChannelSlider,
+    #[widget(row = 2, col = 1)]
+    green_label: Label,
+    #[widget(row = 2, col = 2, cspan = 2, handler = handle_green)]
+    green: ChannelSlider,
+    #[widget(row = 3, col = 1)]
+    blue_label: Label,
+    #[widget(row = 3, col = 2, cspan = 2, handler = handle_blue)]
+    blue: ChannelSlider,
+    #[widget(row = 4, col = 1)]
+    hex_label: Label,
+    #[widget(row = 4, col = 2, handler = handle_hex)]
+    hex: EditBox<fn(&str) -> Option<Colour>>,
+    #[widget(row = 4, col = 3)]
+    swatch: Swatch<Colour>,
+    colour: Colour,
+    hsv: (f32, f32, f32),
+}
+
+impl ColourPicker {
+    /// Construct, with the given initial colour
+    pub fn new(colour: Colour) -> Self {
+        let (h, s, v) = colour_to_hsv(colour, 0.0, 0.0);
+        let slider = |x: f32| ChannelSlider::new(0, 255, 1).with_value(to_byte(x));
+        ColourPicker {
+            core: Default::default(),
+            layout_data: Default::default(),
+            plane: HsPlane::new(h, s, v),
+            value_label: Label::new("V"),
+            value_slider: slider(v),
+            red_label: Label::new("R"),
+            red: slider(colour.r),
+            green_label: Label::new("G"),
+            green: slider(colour.g),
+            blue_label: Label::new("B"),
+            blue: slider(colour.b),
+            hex_label: Label::new("Hex"),
+            hex: EditBox::new(format_colour(colour)).on_activate(parse_colour as fn(&str) -> _),
+            swatch: Swatch::new(colour),
+            colour,
+            hsv: (h, s, v),
+        }
+    }
+
+    /// Get the colour
+    #[inline]
+    pub fn colour(&self) -> Colour {
+        self.colour
+    }
+
+    /// Set the colour
+    ///
+    /// This does not send a message.
+    pub fn set_colour(&mut self, tk: &mut dyn TkWindow, colour: Colour) {
+        let (h, s) = (self.hsv.0, self.hsv.1);
+        self.hsv = colour_to_hsv(colour, h, s);
+        self.colour = colour;
+        self.update(tk, true, true);
+    }
+
+    // Update children from self.colour and self.hsv, optionally including
+    // the RGB sliders and the HSV controls
+    fn update(&mut self, tk: &mut dyn TkWindow, rgb: bool, hsv: bool) {
+        let c = self.colour;
+        if rgb {
+            self.red.set_value(tk, to_byte(c.r));
+            self.green.set_value(tk, to_byte(c.g));
+            self.blue.set_value(tk, to_byte(c.b));
+        }
+        if hsv {
+            let (h, s, v) = self.hsv;
+            self.plane.set_hsv(tk, h, s, v);
+            self.value_slider.set_value(tk, to_byte(v));
+        }
+        self.hex.set_string(tk, format_colour(c));
+        self.swatch.set_colour(tk, c);
+    }
+
+    fn set_hsv(&mut self, tk: &mut dyn TkWindow, hsv: (f32, f32, f32)) -> Response<Colour> {
+        self.hsv = hsv;
+        self.colour = hsv_to_colour(hsv.0, hsv.1, hsv.2);
+        self.update(tk, true, false);
+        Response::Msg(self.colour)
+    }
+
+    fn set_rgb(&mut self, tk: &mut dyn TkWindow, colour: Colour) -> Response<Colour> {
+        self.set_colour(tk, colour);
+        Response::Msg(colour)
+    }
+
+    fn handle_plane(&mut self, tk: &mut dyn TkWindow, msg: (f32, f32)) -> Response<Colour> {
+        let v = self.hsv.2;
+        self.set_hsv(tk, (msg.0, msg.1, v))
+    }
+
+    fn handle_value(&mut self, tk: &mut dyn TkWindow, msg: u8) -> Response<Colour> {
+        let (h, s, v) = (self.hsv.0, self.hsv.1, msg as f32 / 255.0);
+        self.plane.set_hsv(tk, h, s, v);
+        self.set_hsv(tk, (h, s, v))
+    }
+
+    fn handle_red(&mut self, tk: &mut dyn TkWindow, msg: u8) -> Response<Colour> {
+        let c = self.colour;
+        self.set_rgb(tk, Colour::new(msg as f32 / 255.0, c.g, c.b))
+    }
+
+    fn handle_green(&mut self, tk: &mut dyn TkWindow, msg: u8) -> Response<Colour> {
+        let c = self.colour;
+        self.set_rgb(tk, Colour::new(c.r, msg as f32 / 255.0, c.b))
+    }
+
+    fn handle_blue(&mut self, tk: &mut dyn TkWindow, msg: u8) -> Response<Colour> {
+        let c = self.colour;
+        self.set_rgb(tk, Colour::new(c.r, c.g, msg as f32 / 255.0))
+    }
+
+    fn handle_hex(&mut self, tk: &mut dyn TkWindow, msg: Option<Colour>) -> Response<Colour> {
+        match msg {
+            Some(colour) => self.set_rgb(tk, colour),
+            None => Response::None,
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! CSV / TSV preview
+
+use std::io::{self, BufRead};
+
+use super::{Table, TableMsg};
+use crate::event::Response;
+use crate::macros::Widget;
+use crate::{CoreData, LayoutData, TableData, TkWindow};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParseState {
+    FieldStart,
+    Unquoted,
+    Quoted,
+    // A quote within a quoted field: either an escape or the closing quote
+    QuotedQuote,
+}
+
+/// Tabular data parsed from CSV or TSV text
+///
+/// Text is parsed incrementally: it may be pushed in chunks of any size (see
+/// [`CsvData::push_str`]), thus large files may be loaded progressively. Only
+/// complete records (rows) are visible; call [`CsvData::finish`] after the
+/// last chunk to complete the final record.
+///
+/// Fields may be quoted, in which case they may contain the delimiter, line
+/// breaks and (doubled) quotes, as in RFC 4180. Empty lines are skipped.
+/// All cell text is stored in a single buffer, with a few bytes of overhead
+/// per cell.
+///
+/// ```
+/// use kas::widget::CsvData;
+/// use kas::TableData;
+///
+/// let mut data = CsvData::new(',');
+/// data.push_str("name,note\nAda,\"says \"\"hi\"\"\"\nBo");
+/// assert_eq!(data.rows(), 2);
+/// data.push_str("b,\"two\nlines\"");
+/// data.finish();
+/// assert_eq!(data.rows(), 3);
+/// assert_eq!(data.cell(1, 1), Some("says \"hi\""));
+/// assert_eq!(data.cell(2, 0), Some("Bob"));
+/// assert_eq!(data.cell(2, 1), Some("two\nlines"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CsvData {
+    delimiter: char,
+    state: ParseState,
+    in_record: bool,
+    text: String,
+    // end of each cell's text
+    cell_ends: Vec<usize>,
+    // index of the first cell of each complete record
+    records: Vec<usize>,
+    // index of the first cell of the incomplete record
+    pending: usize,
+    cols: usize,
+}
+
+impl CsvData {
+    /// Construct, empty, with the given field `delimiter`
+    ///
+    /// Typically the delimiter is `','` (CSV) or `'\t'` (TSV).
+    pub fn new(delimiter: char) -> Self {
+        CsvData {
+            delimiter,
+            state: ParseState::FieldStart,
+            in_record: false,
+            text: String::new(),
+            cell_ends: vec![],
+            records: vec![],
+            pending: 0,
+            cols: 0,
+        }
+    }
+
+    /// Construct from complete text
+    pub fn from_str(text: &str, delimiter: char) -> Self {
+        let mut data = CsvData::new(delimiter);
+        data.push_str(text);
+        data.finish();
+        data
+    }
+
+    /// Read complete text from `reader`
+    ///
+    /// The reader is read one line at a time.
+    pub fn from_reader<R: BufRead>(mut reader: R, delimiter: char) -> io::Result<Self> {
+        let mut data = CsvData::new(delimiter);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            data.push_str(&line);
+            line.clear();
+        }
+        data.finish();
+        Ok(data)
+    }
+
+    /// The field delimiter
+    #[inline]
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    /// Parse a chunk of text
+    ///
+    /// Chunks may split records or fields at any point.
+    pub fn push_str(&mut self, chunk: &str) {
+        for c in chunk.chars() {
+            self.push_char(c);
+        }
+    }
+
+    /// Complete the final record, if any
+    ///
+    /// This should be called after the last chunk. Pushing more text after
+    /// this starts a new record.
+    pub fn finish(&mut self) {
+        if self.in_record {
+            self.end_field();
+            self.end_record();
+        }
+    }
+
+    fn push_char(&mut self, c: char) {
+        let line_break = c == '\n' || c == '\r';
+        match self.state {
+            ParseState::FieldStart if line_break => self.finish(),
+            ParseState::FieldStart if c == '"' => self.state = ParseState::Quoted,
+            ParseState::FieldStart if c == self.delimiter => self.end_field(),
+            ParseState::FieldStart => {
+                self.text.push(c);
+                self.state = ParseState::Unquoted;
+            }
+            ParseState::Unquoted if line_break => self.finish(),
+            ParseState::Unquoted if c == self.delimiter => self.end_field(),
+            ParseState::Unquoted => self.text.push(c),
+            ParseState::Quoted if c == '"' => self.state = ParseState::QuotedQuote,
+            ParseState::Quoted => self.text.push(c),
+            ParseState::QuotedQuote if c == '"' => {
+                self.text.push(c);
+                self.state = ParseState::Quoted;
+            }
+            ParseState::QuotedQuote if line_break => self.finish(),
+            ParseState::QuotedQuote if c == self.delimiter => self.end_field(),
+            ParseState::QuotedQuote => {
+                // Malformed input: accept text after the closing quote
+                self.text.push(c);
+                self.state = ParseState::Unquoted;
+            }
+        }
+        if !line_break {
+            self.in_record = true;
+        }
+    }
+
+    fn end_field(&mut self) {
+        self.cell_ends.push(self.text.len());
+        self.state = ParseState::FieldStart;
+    }
+
+    fn end_record(&mut self) {
+        self.cols = self.cols.max(self.cell_ends.len() - self.pending);
+        self.records.push(self.pending);
+        self.pending = self.cell_ends.len();
+        self.in_record = false;
+    }
+}
+
+impl TableData for CsvData {
+    type Key = usize;
+
+    #[inline]
+    fn rows(&self) -> usize {
+        self.records.len()
+    }
+
+    #[inline]
+    fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline]
+    fn key(&self, row: usize) -> usize {
+        row
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Option<&str> {
+        let first = *self.records.get(row)?;
+        let end = self.records.get(row + 1).cloned().unwrap_or(self.pending);
+        let index = first + col;
+        if index >= end {
+            return None;
+        }
+        let start = match index {
+            0 => 0,
+            i => self.cell_ends[i - 1],
+        };
+        Some(&self.text[start..self.cell_ends[index]])
+    }
+}
+
+/// A preview of CSV or TSV data
+///
+/// This is a read-only [`Table`] over [`CsvData`]; see [`Table`] for details
+/// of sizing, selection and copying. Large files may be shown progressively:
+/// construct with [`CsvView::new`], then pass each chunk of text to
+/// [`CsvView::push_str`] as it becomes available (for example, from a loader
+/// thread), and finally call [`CsvView::finish`].
+#[widget(layout = single)]
+#[handler(msg = TableMsg<usize>)]
+#[derive(Clone, Debug, Widget)]
+pub struct CsvView {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget(handler = handle_msg)]
+    table: Table<CsvData>,
+}
+
+impl CsvView {
+    /// Construct, empty, with the given field `delimiter`
+    pub fn new(delimiter: char) -> Self {
+        CsvView::from_data(CsvData::new(delimiter))
+    }
+
+    /// Construct from complete text
+    pub fn from_str(text: &str, delimiter: char) -> Self {
+        CsvView::from_data(CsvData::from_str(text, delimiter))
+    }
+
+    /// Construct from parsed data
+    pub fn from_data(data: CsvData) -> Self {
+        CsvView {
+            core: Default::default(),
+            layout_data: Default::default(),
+            table: Table::new(data),
+        }
+    }
+
+    /// Access the data
+    #[inline]
+    pub fn data(&self) -> &CsvData {
+        self.table.data()
+    }
+
+    /// Access the table
+    #[inline]
+    pub fn table(&self) -> &Table<CsvData> {
+        &self.table
+    }
+
+    /// Replace the data
+    pub fn set_data(&mut self, tk: &mut dyn TkWindow, data: CsvData) {
+        self.table.set_data(tk, data);
+    }
+
+    /// Parse and show a chunk of text
+    ///
+    /// See [`CsvData::push_str`] and [`Table::append_data`].
+    pub fn push_str(&mut self, tk: &mut dyn TkWindow, chunk: &str) {
+        self.table.append_data(tk, |data| data.push_str(chunk));
+    }
+
+    /// Complete the final record
+    ///
+    /// See [`CsvData::finish`].
+    pub fn finish(&mut self, tk: &mut dyn TkWindow) {
+        self.table.append_data(tk, |data| data.finish());
+    }
+
+    fn handle_msg(
+        &mut self,
+        _: &mut dyn TkWindow,
+        msg: TableMsg<usize>,
+    ) -> Response<TableMsg<usize>> {
+        msg.into()
+    }
+}
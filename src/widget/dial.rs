@@ -0,0 +1,285 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Dial` control
+
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+use crate::event::{
+    self, Action, Address, Event, GrabMode, Handler, Manager, NavKey, PressSource, Response,
+    ScrollDelta,
+};
+use crate::geom::Coord;
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::widget::slider::fraction;
+use crate::widget::SliderType;
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Drag distance (in pixels) covering the full range
+const DRAG_LEN: f64 = 200.0;
+/// Factor by which drag motion is slowed while Shift is held
+const FINE_FACTOR: f64 = 10.0;
+/// Angle (in radians) swept by the pointer over the full range
+const SWEEP: f64 = 1.5 * PI;
+
+/// A dial (rotary knob)
+///
+/// As with a [`Slider`], dials allow user input of a value from a fixed range,
+/// `min..=max`, in increments of `step`.
+///
+/// The value is adjusted by dragging, with the mouse wheel or with the arrow
+/// keys (as for [`Slider`]). By default, dragging is vertical (up to
+/// increase); the cursor is hidden meanwhile and, where the platform supports
+/// relative motion, dragging is not limited by the edges of the screen. With
+/// [`Dial::with_circular_drag`], the value instead follows the motion of the
+/// pointer around the dial (clockwise to increase). While
+/// Shift is held, dragging adjusts the value more finely. Each change is
+/// reported as a message with the new value.
+///
+/// [`Slider`]: crate::widget::Slider
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Dial<T: SliderType> {
+    #[core]
+    core: CoreData,
+    range: (T, T),
+    step: T,
+    value: T,
+    ticks: u32,
+    circular: bool,
+    dead_zone: f32,
+    // Press source, last coordinate and unrounded value while dragging
+    press: Option<(PressSource, Coord, f64)>,
+}
+
+impl<T: SliderType> Dial<T> {
+    /// Construct a dial
+    ///
+    /// Values vary between `min` and `max` in increments of `step`. The
+    /// initial value is `min`. By default, no tick marks are drawn.
+    pub fn new(min: T, max: T, step: T) -> Self {
+        assert!(min <= max);
+        assert!(step > T::default());
+        Dial {
+            core: Default::default(),
+            range: (min, max),
+            step,
+            value: min,
+            ticks: 0,
+            circular: false,
+            dead_zone: 0.25,
+            press: None,
+        }
+    }
+
+    /// Set the initial value
+    #[inline]
+    pub fn with_value(mut self, value: T) -> Self {
+        self.value = self.clamp(value.to_f64());
+        self
+    }
+
+    /// Set the number of tick marks
+    ///
+    /// Ticks are evenly spaced around the dial, with the first at `min` and
+    /// the last at `max`. Fewer than two ticks are not drawn.
+    #[inline]
+    pub fn with_ticks(mut self, ticks: u32) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// Enable or disable circular dragging
+    ///
+    /// When enabled, dragging moves the value through the angle swept by the
+    /// pointer around the centre of the dial (one full sweep of the dial's
+    /// pointer covers the range). Motion need not start on the dial's
+    /// pointer, and the value never jumps across the gap between `max` and
+    /// `min`: it simply stops at the ends.
+    #[inline]
+    pub fn with_circular_drag(mut self, circular: bool) -> Self {
+        self.circular = circular;
+        self
+    }
+
+    /// Set the dead zone used by circular dragging
+    ///
+    /// Pointer motion at a distance from the centre less than `radius` (as a
+    /// fraction of the dial's radius) is ignored, since small movements near
+    /// the centre would otherwise cause large changes. The default is `0.25`.
+    #[inline]
+    pub fn with_dead_zone(mut self, radius: f32) -> Self {
+        self.dead_zone = radius.max(0.0);
+        self
+    }
+
+    /// Get the current value
+    #[inline]
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Set the value
+    ///
+    /// The value is clamped to the range and rounded to the nearest step.
+    pub fn set_value(&mut self, tk: &mut dyn TkWindow, value: T) {
+        self.set_and_changed(tk, value.to_f64());
+    }
+
+    // clamp to range and round to a step
+    fn clamp(&self, value: f64) -> T {
+        let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+        let step = self.step.to_f64();
+        let steps = ((value.max(min) - min) / step).round();
+        T::from_f64((min + steps * step).min(max))
+    }
+
+    // fraction of the range
+    fn fraction(&self) -> f64 {
+        fraction(self.value, self.range)
+    }
+
+    // size of the range (computed in f64 since this may overflow T)
+    fn span(&self) -> f64 {
+        self.range.1.to_f64() - self.range.0.to_f64()
+    }
+
+    // true if not equal to old value
+    fn set_and_changed(&mut self, tk: &mut dyn TkWindow, value: f64) -> bool {
+        let value = self.clamp(value);
+        if value != self.value {
+            self.value = value;
+            tk.redraw(self.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    // change in value for pointer motion from `last` to `coord` (by `delta`)
+    fn drag_delta(&self, last: Coord, coord: Coord, delta: Coord) -> f64 {
+        let span = self.span();
+        if !self.circular {
+            // Relative motion: only delta is meaningful
+            return -delta.1 as f64 * span / DRAG_LEN;
+        }
+
+        let rect = self.core.rect;
+        let centre = rect.pos + Coord(rect.size.0 as i32 / 2, rect.size.1 as i32 / 2);
+        let a = last - centre;
+        let b = coord - centre;
+        let radius = 0.5 * rect.size.0.min(rect.size.1) as f64;
+        let dead = (self.dead_zone as f64 * radius).powi(2);
+        let len2 = |v: Coord| (v.0 as f64).powi(2) + (v.1 as f64).powi(2);
+        if len2(a) < dead || len2(b) < dead {
+            return 0.0;
+        }
+
+        // Signed angle from a to b; with the y axis pointing down, positive
+        // angles are clockwise.
+        let cross = a.0 as f64 * b.1 as f64 - a.1 as f64 * b.0 as f64;
+        let dot = a.0 as f64 * b.0 as f64 + a.1 as f64 * b.1 as f64;
+        cross.atan2(dot) * span / SWEEP
+    }
+
+    fn changed(&self, changed: bool) -> Response<T> {
+        match changed {
+            true => Response::Msg(self.value),
+            false => Response::None,
+        }
+    }
+}
+
+impl<T: SliderType> Widget for Dial<T> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        SizeRules::fixed(axis.extract_size(size_handle.dial()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let hl = ev_mgr.highlight_state(self.id());
+        let value = self.fraction() as f32;
+        draw_handle.dial(self.core.rect, value, self.ticks, hl);
+    }
+}
+
+impl<T: SliderType> Handler for Dial<T> {
+    type Msg = T;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<T> {
+        let step = self.step.to_f64();
+        let value = self.value.to_f64();
+        let changed = match action {
+            Action::NavKey(key) => {
+                let value = match key {
+                    NavKey::Left | NavKey::Down => value - step,
+                    NavKey::Right | NavKey::Up => value + step,
+                    NavKey::PageUp => value + 10.0 * step,
+                    NavKey::PageDown => value - 10.0 * step,
+                    NavKey::Home => self.range.0.to_f64(),
+                    NavKey::End => self.range.1.to_f64(),
+                };
+                self.set_and_changed(tk, value)
+            }
+            Action::Scroll(delta) => {
+                // With Shift, the wheel scrolls horizontally (with reversed
+                // sign); we use either axis.
+                let delta = match delta {
+                    ScrollDelta::LineDelta(x, y) => (y - x) as f64 * step,
+                    ScrollDelta::PixelDelta(coord) => {
+                        (coord.1 - coord.0) as f64 * self.span() / DRAG_LEN
+                    }
+                };
+                self.set_and_changed(tk, value + delta)
+            }
+            a => return Response::unhandled_action(a),
+        };
+        self.changed(changed)
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<T> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                let mode = match self.circular {
+                    false => GrabMode::Relative,
+                    true => GrabMode::Grab,
+                };
+                tk.data_mut()
+                    .request_press_grab_mode(source, self, coord, mode);
+                self.press = Some((source, coord, self.value.to_f64()));
+                Response::None
+            }
+            Event::PressMove {
+                source,
+                coord,
+                delta,
+            } => match self.press {
+                Some((s, last, value)) if s == source => {
+                    let mut delta = self.drag_delta(last, coord, delta);
+                    if tk.data().modifiers().shift {
+                        delta /= FINE_FACTOR;
+                    }
+                    let value = value + delta;
+                    let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+                    self.press = Some((s, coord, value.max(min).min(max)));
+                    let changed = self.set_and_changed(tk, value);
+                    self.changed(changed)
+                }
+                _ => Response::Unhandled(event),
+            },
+            Event::PressEnd { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                self.press = None;
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
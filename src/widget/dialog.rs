@@ -8,12 +8,14 @@
 //! KAS dialog boxes are pre-configured windows, usually allowing some
 //! customisation.
 
-use crate::event::{Callback, Response, VoidMsg};
+use std::fmt::{self, Debug};
+
+use crate::event::{Callback, Response, VirtualKeyCode, VoidMsg};
 use crate::geom::Size;
 use crate::layout;
 use crate::macros::{VoidMsg, Widget};
-use crate::widget::{Label, TextButton};
-use crate::{CoreData, TkAction, TkWindow, Window};
+use crate::widget::{Label, Row, TextButton};
+use crate::{CoreData, LayoutData, Modality, TkAction, TkWindow, Window};
 
 #[derive(Clone, Debug, VoidMsg)]
 enum DialogButton {
@@ -21,6 +23,10 @@ enum DialogButton {
 }
 
 /// A simple message box.
+///
+/// The message box is window-modal by default (see [`Window::modality`]) and
+/// has a single "Ok" button, also chosen by Enter or Escape. See also
+/// [`Dialog`].
 #[widget(layout = vertical)]
 #[handler]
 #[derive(Clone, Debug, Widget)]
@@ -30,6 +36,7 @@ pub struct MessageBox {
     #[layout_data]
     layout_data: <Self as kas::LayoutData>::Data,
     title: String,
+    modality: Modality,
     #[widget]
     label: Label,
     #[widget(handler = handle_button)]
@@ -42,11 +49,21 @@ impl MessageBox {
             core: Default::default(),
             layout_data: Default::default(),
             title: title.to_string(),
+            modality: Modality::Window,
             label: Label::new(message),
-            button: TextButton::new("Ok", DialogButton::Close),
+            button: TextButton::new("Ok", DialogButton::Close)
+                .with_keys(&[VirtualKeyCode::Return, VirtualKeyCode::Escape]),
         }
     }
 
+    /// Set the modality (chain style)
+    ///
+    /// By default, the message box is window-modal.
+    pub fn with_modality(mut self, modality: Modality) -> Self {
+        self.modality = modality;
+        self
+    }
+
     fn handle_button(&mut self, tk: &mut dyn TkWindow, msg: DialogButton) -> Response<VoidMsg> {
         match msg {
             DialogButton::Close => tk.send_action(TkAction::Close),
@@ -69,4 +86,253 @@ impl Window for MessageBox {
         Vec::new()
     }
     fn trigger_callback(&mut self, _index: usize, _tk: &mut dyn TkWindow) {}
+
+    fn modality(&self) -> Modality {
+        self.modality
+    }
+}
+
+/// Standard sets of dialog buttons
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogButtons {
+    /// A single "Ok" button
+    Ok,
+    /// "Ok" and "Cancel" buttons
+    OkCancel,
+    /// "Yes" and "No" buttons
+    YesNo,
+}
+
+impl DialogButtons {
+    /// The responses of each button, in display order
+    pub fn responses(self) -> &'static [DialogResponse] {
+        match self {
+            DialogButtons::Ok => &[DialogResponse::Ok],
+            DialogButtons::OkCancel => &[DialogResponse::Ok, DialogResponse::Cancel],
+            DialogButtons::YesNo => &[DialogResponse::Yes, DialogResponse::No],
+        }
+    }
+
+    /// The response given when the dialog is closed without using a button
+    ///
+    /// This is the last button: "Ok", "Cancel" or "No".
+    pub fn cancel_response(self) -> DialogResponse {
+        *self.responses().last().unwrap()
+    }
+}
+
+/// The button chosen in a [`Dialog`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, VoidMsg)]
+pub enum DialogResponse {
+    /// The "Ok" button
+    Ok,
+    /// The "Cancel" button
+    Cancel,
+    /// The "Yes" button
+    Yes,
+    /// The "No" button
+    No,
+}
+
+impl DialogResponse {
+    /// The button label
+    pub fn label(self) -> &'static str {
+        match self {
+            DialogResponse::Ok => "Ok",
+            DialogResponse::Cancel => "Cancel",
+            DialogResponse::Yes => "Yes",
+            DialogResponse::No => "No",
+        }
+    }
+}
+
+/// Order of affirmative (e.g. "Ok") and other buttons in a dialog
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonOrder {
+    /// Affirmative button on the left, as on Windows
+    AffirmativeFirst,
+    /// Affirmative button on the right, as on macOS and GNOME
+    AffirmativeLast,
+}
+
+impl ButtonOrder {
+    /// The conventional order on the target platform
+    pub fn platform() -> Self {
+        if cfg!(target_os = "windows") {
+            ButtonOrder::AffirmativeFirst
+        } else {
+            ButtonOrder::AffirmativeLast
+        }
+    }
+}
+
+impl Default for ButtonOrder {
+    fn default() -> Self {
+        ButtonOrder::platform()
+    }
+}
+
+/// A row of standard dialog buttons
+///
+/// Buttons are shown in the order given by a [`ButtonOrder`] (by default, the
+/// platform convention). Clicking a button returns its [`DialogResponse`] as
+/// a message. Additionally, Enter chooses the affirmative (first) button of
+/// the [`DialogButtons`] and Escape the [`DialogButtons::cancel_response`],
+/// unless some other widget handles these keys (for example, Enter activates
+/// the widget with key focus and Escape first closes any pop-up).
+#[widget(layout = single)]
+#[handler(msg = DialogResponse)]
+#[derive(Clone, Debug, Widget)]
+pub struct DialogButtonBar {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget]
+    buttons: Row<TextButton<DialogResponse>>,
+}
+
+impl DialogButtonBar {
+    /// Construct, in the platform order
+    pub fn new(buttons: DialogButtons) -> Self {
+        DialogButtonBar::with_order(buttons, ButtonOrder::platform())
+    }
+
+    /// Construct, with the given order
+    pub fn with_order(buttons: DialogButtons, order: ButtonOrder) -> Self {
+        let responses = buttons.responses();
+        let default = responses[0];
+        let cancel = buttons.cancel_response();
+        let mut row: Vec<_> = (responses.iter())
+            .map(|r| {
+                let mut keys = vec![];
+                if *r == default {
+                    keys.push(VirtualKeyCode::Return);
+                }
+                if *r == cancel {
+                    keys.push(VirtualKeyCode::Escape);
+                }
+                TextButton::new(r.label(), *r).with_keys(&keys)
+            })
+            .collect();
+        if order == ButtonOrder::AffirmativeLast {
+            row.reverse();
+        }
+        DialogButtonBar {
+            core: Default::default(),
+            layout_data: Default::default(),
+            buttons: Row::new(row),
+        }
+    }
+}
+
+/// A modal dialog box with a message and a standard set of buttons
+///
+/// By default, the dialog blocks user input to its parent window (the window
+/// which added it via [`TkWindow::add_window`]) until closed; see
+/// [`Dialog::with_modality`]. Buttons are given by a [`DialogButtonBar`], thus
+/// Enter chooses the affirmative button and Escape cancels. Pressing a button closes
+/// the dialog. When the dialog closes, `on_response` is called with the
+/// chosen button, or with [`DialogButtons::cancel_response`] if the dialog
+/// was closed by other means (e.g. via the window manager).
+///
+/// Since the dialog is a separate window, `on_response` cannot access the
+/// parent's widgets directly; shared state (for example via
+/// `Rc<Cell<Option<DialogResponse>>>`) may be used instead.
+#[widget(layout = vertical)]
+#[handler]
+#[derive(Clone, Widget)]
+pub struct Dialog<F: Fn(DialogResponse) + 'static> {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as kas::LayoutData>::Data,
+    title: String,
+    modality: Modality,
+    #[widget]
+    label: Label,
+    #[widget(handler = handle_button)]
+    buttons: DialogButtonBar,
+    response: DialogResponse,
+    on_response: F,
+}
+
+impl<F: Fn(DialogResponse)> Debug for Dialog<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Dialog {{ core: {:?}, layout_data: {:?}, title: {:?}, modality: {:?}, \
+            label: {:?}, buttons: {:?}, response: {:?}, ... }}",
+            self.core,
+            self.layout_data,
+            self.title,
+            self.modality,
+            self.label,
+            self.buttons,
+            self.response
+        )
+    }
+}
+
+impl<F: Fn(DialogResponse)> Dialog<F> {
+    /// Construct
+    ///
+    /// The `on_response` closure is called exactly once, when the dialog
+    /// closes.
+    pub fn new<T: ToString, M: ToString>(
+        title: T,
+        message: M,
+        buttons: DialogButtons,
+        on_response: F,
+    ) -> Self {
+        Dialog {
+            core: Default::default(),
+            layout_data: Default::default(),
+            title: title.to_string(),
+            modality: Modality::Window,
+            label: Label::new(message),
+            buttons: DialogButtonBar::new(buttons),
+            response: buttons.cancel_response(),
+            on_response,
+        }
+    }
+
+    /// Set the modality (chain style)
+    ///
+    /// By default, the dialog is window-modal. Use [`Modality::Application`]
+    /// to block input to all other windows.
+    pub fn with_modality(mut self, modality: Modality) -> Self {
+        self.modality = modality;
+        self
+    }
+
+    fn handle_button(&mut self, tk: &mut dyn TkWindow, msg: DialogResponse) -> Response<VoidMsg> {
+        self.response = msg;
+        tk.send_action(TkAction::Close);
+        Response::None
+    }
+}
+
+impl<F: Fn(DialogResponse)> Window for Dialog<F> {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn resize(&mut self, tk: &mut dyn TkWindow, size: Size) {
+        layout::solve(self, tk, size);
+    }
+
+    // doesn't support callbacks, so doesn't need to do anything here
+    fn callbacks(&self) -> Vec<(usize, Callback)> {
+        Vec::new()
+    }
+    fn trigger_callback(&mut self, _index: usize, _tk: &mut dyn TkWindow) {}
+
+    fn modality(&self) -> Modality {
+        self.modality
+    }
+
+    fn on_close(&mut self) {
+        (self.on_response)(self.response);
+    }
 }
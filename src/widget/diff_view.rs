@@ -0,0 +1,736 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Text diff view
+
+use std::ops::Range;
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, PressSource, Response,
+    ScrollDelta, VoidMsg,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, SpanClass, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// Number of lines sampled when measuring line widths
+const SAMPLE_LINES: usize = 64;
+
+/// Maximum ideal line width, in lines
+const MAX_LINE_LINES: u32 = 40;
+
+/// A run of lines in a [`Diff`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffOp {
+    /// `len` lines common to both texts, starting at line `old` of the old
+    /// text and line `new` of the new text
+    Equal {
+        /// Index of the first line in the old text
+        old: usize,
+        /// Index of the first line in the new text
+        new: usize,
+        /// Number of lines
+        len: usize,
+    },
+    /// Lines `old` of the old text are replaced by lines `new` of the new
+    /// text; either range may be empty
+    Change {
+        /// Range of lines in the old text
+        old: Range<usize>,
+        /// Range of lines in the new text
+        new: Range<usize>,
+    },
+}
+
+/// A line-based diff of two texts
+///
+/// The diff is minimal (it has as few inserted and deleted lines as
+/// possible), as computed by Myers' algorithm. This takes time proportional
+/// to `(N + M) * D` and memory proportional to `D * D`, where `N` and `M`
+/// are the numbers of lines and `D` the number of differing lines; thus it is
+/// fast for similar texts.
+///
+/// ```
+/// use kas::widget::{Diff, DiffOp};
+///
+/// let diff = Diff::new("a\nb\nc\nd", "a\nB\nc\nd\ne");
+/// assert_eq!(diff.ops(), &[
+///     DiffOp::Equal { old: 0, new: 0, len: 1 },
+///     DiffOp::Change { old: 1..2, new: 1..2 },
+///     DiffOp::Equal { old: 2, new: 2, len: 2 },
+///     DiffOp::Change { old: 4..4, new: 4..5 },
+/// ]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Diff {
+    old: Vec<String>,
+    new: Vec<String>,
+    ops: Vec<DiffOp>,
+}
+
+impl Diff {
+    /// Compare `old` and `new`, line by line
+    pub fn new(old: &str, new: &str) -> Self {
+        let old: Vec<String> = old.lines().map(|line| line.to_string()).collect();
+        let new: Vec<String> = new.lines().map(|line| line.to_string()).collect();
+        let mut ops = vec![];
+        let (mut x, mut y) = (0, 0);
+        for (ox, oy, len) in matches(&old, &new) {
+            if ox > x || oy > y {
+                ops.push(DiffOp::Change {
+                    old: x..ox,
+                    new: y..oy,
+                });
+            }
+            ops.push(DiffOp::Equal {
+                old: ox,
+                new: oy,
+                len,
+            });
+            x = ox + len;
+            y = oy + len;
+        }
+        if old.len() > x || new.len() > y {
+            ops.push(DiffOp::Change {
+                old: x..old.len(),
+                new: y..new.len(),
+            });
+        }
+        Diff { old, new, ops }
+    }
+
+    /// Lines of the old text
+    #[inline]
+    pub fn old_lines(&self) -> &[String] {
+        &self.old
+    }
+
+    /// Lines of the new text
+    #[inline]
+    pub fn new_lines(&self) -> &[String] {
+        &self.new
+    }
+
+    /// Runs of equal and changed lines, in order
+    #[inline]
+    pub fn ops(&self) -> &[DiffOp] {
+        &self.ops
+    }
+
+    /// True if the texts have equal lines
+    pub fn is_equal(&self) -> bool {
+        self.ops.iter().all(|op| match op {
+            DiffOp::Equal { .. } => true,
+            DiffOp::Change { .. } => false,
+        })
+    }
+}
+
+// Compute a minimal set of matching runs `(x, y, len)` between `a` and `b`,
+// in order, using Myers' O(ND) algorithm.
+fn matches<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    // v[k] is the furthest x reached on diagonal k = x - y; trace[d] is a
+    // copy of v for diagonals -d..=d at the start of round d.
+    let max = n + m;
+    let index = |k: isize| (k + max + 1) as usize;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace: Vec<Vec<isize>> = vec![];
+    'outer: for d in 0..=max {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = match k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                true => v[index(k + 1)],
+                false => v[index(k - 1)] + 1,
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'outer;
+            }
+        }
+    }
+
+    // Walk back through the trace, collecting diagonal moves
+    let mut runs = vec![];
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let v = &trace[d as usize];
+        let get = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = match k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            true => k + 1,
+            false => k - 1,
+        };
+        let (prev_x, prev_y) = match d {
+            0 => (0, 0),
+            _ => (get(prev_k), get(prev_k) - prev_k),
+        };
+        // The diagonal run starts after the single insert or delete
+        let (start_x, start_y) = match d {
+            0 => (0, 0),
+            _ if prev_k == k + 1 => (prev_x, prev_y + 1),
+            _ => (prev_x + 1, prev_y),
+        };
+        if x > start_x {
+            let len = (x - start_x) as usize;
+            runs.push((start_x as usize, start_y as usize, len));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    runs.reverse();
+    runs
+}
+
+// Byte ranges of changed text within a line
+type Spans = Vec<Range<usize>>;
+
+// Spans of changed text in a pair of similar lines, or None if the lines
+// have too little in common for the spans to be useful
+fn line_spans(old: &str, new: &str) -> Option<(Spans, Spans)> {
+    let a: Vec<(usize, char)> = old.char_indices().collect();
+    let b: Vec<(usize, char)> = new.char_indices().collect();
+    let a_chars: Vec<char> = a.iter().map(|c| c.1).collect();
+    let b_chars: Vec<char> = b.iter().map(|c| c.1).collect();
+    let runs = matches(&a_chars, &b_chars);
+    let common: usize = runs.iter().map(|run| run.2).sum();
+    if 2 * common < a.len().max(b.len()) {
+        return None;
+    }
+
+    let byte = |chars: &[(usize, char)], text: &str, i: usize| match chars.get(i) {
+        Some(c) => c.0,
+        None => text.len(),
+    };
+    let (mut old_spans, mut new_spans) = (vec![], vec![]);
+    let (mut x, mut y) = (0, 0);
+    let ends = runs.iter().cloned().chain(Some((a.len(), b.len(), 0)));
+    for (rx, ry, len) in ends {
+        if rx > x {
+            old_spans.push(byte(&a, old, x)..byte(&a, old, rx));
+        }
+        if ry > y {
+            new_spans.push(byte(&b, new, y)..byte(&b, new, ry));
+        }
+        x = rx + len;
+        y = ry + len;
+    }
+    Some((old_spans, new_spans))
+}
+
+/// Display mode of a [`DiffView`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffMode {
+    /// Old and new texts in two panes, with changed lines side-by-side
+    SideBySide,
+    /// A single pane, with deleted lines shown above inserted lines
+    Unified,
+}
+
+// A displayed row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DiffRow {
+    // Line indices in the old and new texts
+    Line(Option<usize>, Option<usize>),
+    // A collapsed run of `len` unchanged lines, from op `op`
+    Fold { op: usize, len: usize },
+}
+
+/// A view of the differences between two texts
+///
+/// Texts are compared line by line (see [`Diff`]). Deleted lines are marked
+/// in the old text and inserted lines in the new text; where a changed line
+/// closely resembles its replacement, the changed parts of each are also
+/// highlighted.
+///
+/// In [`DiffMode::SideBySide`] mode (the default), the texts are shown in two
+/// panes, aligned such that unchanged lines are side-by-side; as a result
+/// both panes always scroll together. In [`DiffMode::Unified`] mode, a single
+/// pane shows both texts.
+///
+/// Long runs of unchanged lines are collapsed, leaving only a few lines of
+/// context around each change (see [`DiffView::with_context`]). Clicking a
+/// collapsed run expands it.
+///
+/// As with [`Table`], only visible rows are drawn. The view is scrolled with
+/// the mouse wheel or the arrow, page and home/end keys.
+///
+/// [`Table`]: crate::widget::Table
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct DiffView {
+    #[core]
+    core: CoreData,
+    diff: Diff,
+    mode: DiffMode,
+    context: usize,
+    expanded: Vec<bool>,
+    rows: Vec<DiffRow>,
+    old_spans: Vec<Vec<(Range<usize>, SpanClass)>>,
+    new_spans: Vec<Vec<(Range<usize>, SpanClass)>>,
+    old_changed: Vec<bool>,
+    new_changed: Vec<bool>,
+    row_height: u32,
+    margin: u32,
+    number_width: u32,
+    offset: u32,
+    max_offset: u32,
+    press: Option<(PressSource, Option<usize>)>,
+}
+
+impl DiffView {
+    /// Construct, comparing `old` and `new`
+    ///
+    /// By default, three lines of context are shown around each change.
+    pub fn new(old: &str, new: &str) -> Self {
+        let mut view = DiffView {
+            core: Default::default(),
+            diff: Diff::default(),
+            mode: DiffMode::SideBySide,
+            context: 3,
+            expanded: vec![],
+            rows: vec![],
+            old_spans: vec![],
+            new_spans: vec![],
+            old_changed: vec![],
+            new_changed: vec![],
+            row_height: 0,
+            margin: 0,
+            number_width: 0,
+            offset: 0,
+            max_offset: 0,
+            press: None,
+        };
+        view.set_diff(Diff::new(old, new));
+        view
+    }
+
+    /// Set the display mode
+    #[inline]
+    pub fn with_mode(mut self, mode: DiffMode) -> Self {
+        self.mode = mode;
+        self.update_rows();
+        self
+    }
+
+    /// Set the number of lines of context shown around each change
+    #[inline]
+    pub fn with_context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self.update_rows();
+        self
+    }
+
+    /// Get the display mode
+    #[inline]
+    pub fn mode(&self) -> DiffMode {
+        self.mode
+    }
+
+    /// Set the display mode
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_mode(&mut self, tk: &mut dyn TkWindow, mode: DiffMode) {
+        self.mode = mode;
+        self.update_rows();
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Get the number of lines of context
+    #[inline]
+    pub fn context(&self) -> usize {
+        self.context
+    }
+
+    /// Set the number of lines of context
+    ///
+    /// This collapses all expanded runs of unchanged lines.
+    pub fn set_context(&mut self, tk: &mut dyn TkWindow, lines: usize) {
+        self.context = lines;
+        self.expanded.iter_mut().for_each(|e| *e = false);
+        self.update_rows();
+        self.update_offset();
+        tk.redraw(self.id());
+    }
+
+    /// Expand all collapsed runs of unchanged lines
+    pub fn expand_all(&mut self, tk: &mut dyn TkWindow) {
+        self.expanded.iter_mut().for_each(|e| *e = true);
+        self.update_rows();
+        self.update_offset();
+        tk.redraw(self.id());
+    }
+
+    /// Access the diff
+    #[inline]
+    pub fn diff(&self) -> &Diff {
+        &self.diff
+    }
+
+    /// Compare new texts
+    ///
+    /// The view is scrolled to the top.
+    pub fn set_texts(&mut self, tk: &mut dyn TkWindow, old: &str, new: &str) {
+        self.set_diff(Diff::new(old, new));
+        self.offset = 0;
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    fn set_diff(&mut self, diff: Diff) {
+        let (old_len, new_len) = (diff.old.len(), diff.new.len());
+        self.old_spans = vec![vec![]; old_len];
+        self.new_spans = vec![vec![]; new_len];
+        self.old_changed = vec![false; old_len];
+        self.new_changed = vec![false; new_len];
+        for op in &diff.ops {
+            if let DiffOp::Change { old, new } = op {
+                old.clone().for_each(|i| self.old_changed[i] = true);
+                new.clone().for_each(|i| self.new_changed[i] = true);
+                // Pair lines in order to find intra-line changes
+                for (i, j) in old.clone().zip(new.clone()) {
+                    if let Some((a, b)) = line_spans(&diff.old[i], &diff.new[j]) {
+                        let class = |class| move |range| (range, class);
+                        self.old_spans[i] = a.into_iter().map(class(SpanClass::Deleted)).collect();
+                        self.new_spans[j] = b.into_iter().map(class(SpanClass::Inserted)).collect();
+                    }
+                }
+            }
+        }
+        self.expanded = vec![false; diff.ops.len()];
+        self.diff = diff;
+        self.update_rows();
+    }
+
+    fn update_rows(&mut self) {
+        let mut rows = vec![];
+        let last = self.diff.ops.len().saturating_sub(1);
+        for (index, op) in self.diff.ops.iter().enumerate() {
+            match *op {
+                DiffOp::Equal { old, new, len } => {
+                    let head = if index == 0 { 0 } else { self.context };
+                    let tail = if index == last { 0 } else { self.context };
+                    let line = |i| DiffRow::Line(Some(old + i), Some(new + i));
+                    // Folding a single line would save nothing
+                    if self.expanded[index] || len <= head + tail + 1 {
+                        rows.extend((0..len).map(line));
+                    } else {
+                        rows.extend((0..head).map(line));
+                        let op = index;
+                        rows.push(DiffRow::Fold {
+                            op,
+                            len: len - head - tail,
+                        });
+                        rows.extend((len - tail..len).map(line));
+                    }
+                }
+                DiffOp::Change { ref old, ref new } => match self.mode {
+                    DiffMode::SideBySide => {
+                        let len = old.len().max(new.len());
+                        rows.extend((0..len).map(|i| {
+                            let line = |r: &Range<usize>| Some(r.start + i).filter(|i| *i < r.end);
+                            DiffRow::Line(line(old), line(new))
+                        }));
+                    }
+                    DiffMode::Unified => {
+                        rows.extend(old.clone().map(|i| DiffRow::Line(Some(i), None)));
+                        rows.extend(new.clone().map(|i| DiffRow::Line(None, Some(i))));
+                    }
+                },
+            }
+        }
+        self.rows = rows;
+    }
+
+    fn update_offset(&mut self) {
+        let content_height = self.rows.len() as u32 * self.row_height;
+        self.max_offset = content_height.saturating_sub(self.core.rect.size.1);
+        self.offset = self.offset.min(self.max_offset);
+    }
+
+    // Range of visible rows
+    fn visible(&self) -> (usize, usize) {
+        let h = self.row_height;
+        if h == 0 {
+            return (0, 0);
+        }
+        let first = (self.offset / h) as usize;
+        let last = (self.offset + self.core.rect.size.1).div_ceil(h) as usize;
+        let end = last.min(self.rows.len());
+        (first.min(end), end)
+    }
+
+    // Row at `coord`, if any
+    fn row_at(&self, coord: Coord) -> Option<usize> {
+        if !self.core.rect.contains(coord) || self.row_height == 0 {
+            return None;
+        }
+        let y = (coord.1 - self.core.rect.pos.1) as u32 + self.offset;
+        Some((y / self.row_height) as usize).filter(|row| *row < self.rows.len())
+    }
+
+    // Set offset, returning true if changed
+    fn set_offset(&mut self, offset: u32) -> bool {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Rect of `row`, in (unscrolled) content coordinates, within `pane`
+    fn row_rect(&self, pane: Rect, row: usize) -> Rect {
+        let y = row as u32 * self.row_height;
+        Rect {
+            pos: pane.pos + Coord(0, y as i32),
+            size: Size(pane.size.0, self.row_height),
+        }
+    }
+
+    // Draw one line of one text within `rect`, with its line numbers
+    fn draw_line(
+        &self,
+        draw_handle: &mut dyn DrawHandle,
+        rect: Rect,
+        numbers: &[Option<usize>],
+        is_old: bool,
+        index: usize,
+    ) {
+        let (text, spans, changed, class) = match is_old {
+            true => (
+                &self.diff.old[index],
+                &self.old_spans[index],
+                self.old_changed[index],
+                SpanClass::Deleted,
+            ),
+            false => (
+                &self.diff.new[index],
+                &self.new_spans[index],
+                self.new_changed[index],
+                SpanClass::Inserted,
+            ),
+        };
+        if changed {
+            draw_handle.line_highlight(rect, class);
+        }
+
+        let props = |horiz| TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz,
+            vert: Align::Centre,
+        };
+        let mut rect = rect;
+        for number in numbers {
+            let width = (self.number_width + 2 * self.margin).min(rect.size.0);
+            if let Some(n) = number {
+                let rect = Rect {
+                    pos: rect.pos,
+                    size: Size(width, rect.size.1),
+                };
+                draw_handle.text(rect, &(n + 1).to_string(), props(Align::End));
+            }
+            rect.pos.0 += width as i32;
+            rect.size.0 -= width;
+        }
+        // Text is clipped by the pane
+        match spans.is_empty() {
+            true => draw_handle.text(rect, text, props(Align::Begin)),
+            false => draw_handle.text_spans(rect, text, props(Align::Begin), spans),
+        }
+    }
+
+    // Draw the lines of one pane; `side` selects the old or new text in
+    // side-by-side mode
+    fn draw_pane(&self, draw_handle: &mut dyn DrawHandle, pane: Rect, side: Option<bool>) {
+        let (start, end) = self.visible();
+        for row in start..end {
+            let rect = self.row_rect(pane, row);
+            let (old, new) = match self.rows[row] {
+                DiffRow::Line(old, new) => (old, new),
+                DiffRow::Fold { .. } => continue,
+            };
+            match (side, old, new) {
+                (Some(true), Some(i), _) => self.draw_line(draw_handle, rect, &[old], true, i),
+                (Some(false), _, Some(j)) => self.draw_line(draw_handle, rect, &[new], false, j),
+                (Some(_), _, _) => (),
+                (None, Some(i), Some(_)) | (None, Some(i), None) => {
+                    self.draw_line(draw_handle, rect, &[old, new], true, i)
+                }
+                (None, None, Some(j)) => self.draw_line(draw_handle, rect, &[old, new], false, j),
+                (None, None, None) => (),
+            }
+        }
+    }
+}
+
+impl Widget for DiffView {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.row_height = line_height + 2 * self.margin;
+
+        if !axis.vertical() {
+            let lines = self.diff.old.len().max(self.diff.new.len());
+            let digits = lines.max(1).to_string().len();
+            let digits = "0".repeat(digits);
+            let rules = size_handle.text_bound(&digits, TextClass::Label, false, axis);
+            self.number_width = rules.ideal_size();
+
+            // We only measure a sample of lines, since measuring all would be
+            // O(n)
+            let measure = |lines: &[String], size_handle: &mut dyn SizeHandle| {
+                let step = lines.len() / SAMPLE_LINES + 1;
+                (lines.iter().step_by(step))
+                    .map(|line| {
+                        let rules = size_handle.text_bound(line, TextClass::Label, false, axis);
+                        rules.ideal_size()
+                    })
+                    .max()
+                    .unwrap_or(0)
+            };
+            let old = measure(&self.diff.old, size_handle);
+            let new = measure(&self.diff.new, size_handle);
+            let (min, max) = (4 * line_height, MAX_LINE_LINES * line_height);
+            let number = self.number_width + 2 * self.margin;
+            let ideal = match self.mode {
+                DiffMode::SideBySide => 2 * (number + old.max(new).max(min).min(max)),
+                DiffMode::Unified => 2 * number + old.max(new).max(min).min(max),
+            };
+            SizeRules::variable(2 * (number + min), ideal + 2 * self.margin)
+        } else {
+            let rows = (self.rows.len() as u32).clamp(1, 16);
+            SizeRules::variable(self.row_height, rows * self.row_height)
+        }
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.update_offset();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let offset = Coord(0, self.offset as i32);
+        let rect = self.core.rect;
+        draw_handle.clip_region(rect, offset, &mut |draw_handle| {
+            match self.mode {
+                DiffMode::SideBySide => {
+                    let half = rect.size.0 / 2;
+                    let left = Rect {
+                        pos: rect.pos,
+                        size: Size(half, rect.size.1),
+                    };
+                    let right = Rect {
+                        pos: rect.pos + Coord(half as i32, 0),
+                        size: Size(rect.size.0 - half, rect.size.1),
+                    };
+                    // Both panes share one offset, thus scroll together
+                    let mut draw_left =
+                        |handle: &mut dyn DrawHandle| self.draw_pane(handle, left, Some(true));
+                    let mut draw_right =
+                        |handle: &mut dyn DrawHandle| self.draw_pane(handle, right, Some(false));
+                    let visible = |pane: Rect| Rect {
+                        pos: pane.pos + offset,
+                        size: pane.size,
+                    };
+                    draw_handle.clip_region(visible(left), Coord::ZERO, &mut draw_left);
+                    draw_handle.clip_region(visible(right), Coord::ZERO, &mut draw_right);
+                }
+                DiffMode::Unified => self.draw_pane(draw_handle, rect, None),
+            }
+
+            let (start, end) = self.visible();
+            for row in start..end {
+                if let DiffRow::Fold { len, .. } = self.rows[row] {
+                    let rect = self.row_rect(rect, row);
+                    draw_handle.button(rect, HighlightState::default());
+                    let props = TextProperties {
+                        class: TextClass::Button,
+                        multi_line: false,
+                        horiz: Align::Centre,
+                        vert: Align::Centre,
+                    };
+                    let text = format!("{} unchanged lines", len);
+                    draw_handle.text(rect, &text, props);
+                }
+            }
+        });
+    }
+}
+
+impl Handler for DiffView {
+    type Msg = VoidMsg;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
+        let page = self.core.rect.size.1.saturating_sub(self.row_height) as i64;
+        let d = match action {
+            Action::Scroll(ScrollDelta::LineDelta(_, y)) => {
+                -(3.0 * y * self.row_height as f32) as i64
+            }
+            Action::Scroll(ScrollDelta::PixelDelta(d)) => -d.1 as i64,
+            Action::NavKey(NavKey::Up) => -(self.row_height as i64),
+            Action::NavKey(NavKey::Down) => self.row_height as i64,
+            Action::NavKey(NavKey::PageUp) => -page,
+            Action::NavKey(NavKey::PageDown) => page,
+            Action::NavKey(NavKey::Home) => -(self.offset as i64),
+            Action::NavKey(NavKey::End) => self.max_offset as i64,
+            a => return Response::unhandled_action(a),
+        };
+        let offset = (self.offset as i64 + d).max(0) as u32;
+        if self.set_offset(offset) {
+            tk.redraw(self.id());
+            Response::None
+        } else {
+            Response::unhandled_action(action)
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.row_at(coord)));
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.row_at(coord),
+                    false => None,
+                };
+                if let Some(row) = start.filter(|_| start == end) {
+                    if let DiffRow::Fold { op, .. } = self.rows[row] {
+                        self.expanded[op] = true;
+                        self.update_rows();
+                        self.update_offset();
+                        tk.redraw(self.id());
+                    }
+                }
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -0,0 +1,1095 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Dockable panel layout
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::mem;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::{Address, Event, Handler, HighlightState, Manager, PressSource, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// An edge of a [`DockLayout`] or of a group of panels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl DockEdge {
+    // True if docking at this edge places one node above the other
+    #[inline]
+    fn is_vertical(self) -> bool {
+        match self {
+            DockEdge::Left | DockEdge::Right => false,
+            DockEdge::Top | DockEdge::Bottom => true,
+        }
+    }
+
+    // True if the docked node comes first
+    #[inline]
+    fn is_first(self) -> bool {
+        match self {
+            DockEdge::Left | DockEdge::Top => true,
+            DockEdge::Right | DockEdge::Bottom => false,
+        }
+    }
+}
+
+/// Arrangement of the panels of a [`DockLayout`]
+///
+/// This is a tree of splits with groups of tabbed panels at the leaves.
+/// Panels are identified by index, in the order they were added to the
+/// layout.
+///
+/// The arrangement may be read via [`DockLayout::arrangement`] and restored
+/// with [`DockLayout::with_arrangement`], for example to persist it between
+/// sessions (with the `serde` feature, this type supports serialisation).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DockNode {
+    /// A group of panels, one of which is shown, with a bar of tabs
+    Tabs {
+        /// Indices of panels, in tab order
+        panels: Vec<usize>,
+        /// Index (within `panels`) of the shown panel
+        active: usize,
+    },
+    /// Two nodes separated by a draggable handle
+    Split {
+        /// If true, `first` is above `second`, otherwise to its left
+        vertical: bool,
+        /// Length given to `first`, as a fraction of the length available
+        split: f32,
+        first: Box<DockNode>,
+        second: Box<DockNode>,
+    },
+}
+
+impl Default for DockNode {
+    fn default() -> Self {
+        DockNode::Tabs {
+            panels: vec![],
+            active: 0,
+        }
+    }
+}
+
+impl DockNode {
+    // Number of nodes, including self
+    fn count(&self) -> usize {
+        match self {
+            DockNode::Tabs { .. } => 1,
+            DockNode::Split { first, second, .. } => 1 + first.count() + second.count(),
+        }
+    }
+
+    // True if this is a group without panels
+    fn is_empty(&self) -> bool {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.is_empty(),
+            DockNode::Split { .. } => false,
+        }
+    }
+
+    // Get a node by pre-order index
+    fn node(&self, mut index: usize) -> Option<&DockNode> {
+        let mut node = self;
+        loop {
+            if index == 0 {
+                return Some(node);
+            }
+            index -= 1;
+            match node {
+                DockNode::Tabs { .. } => return None,
+                DockNode::Split { first, second, .. } => {
+                    let n = first.count();
+                    if index < n {
+                        node = first;
+                    } else {
+                        index -= n;
+                        node = second;
+                    }
+                }
+            }
+        }
+    }
+
+    // Get a node mutably by pre-order index
+    fn node_mut(&mut self, mut index: usize) -> Option<&mut DockNode> {
+        let mut node = self;
+        loop {
+            if index == 0 {
+                return Some(node);
+            }
+            index -= 1;
+            match node {
+                DockNode::Tabs { .. } => return None,
+                DockNode::Split { first, second, .. } => {
+                    let n = first.count();
+                    if index < n {
+                        node = first;
+                    } else {
+                        index -= n;
+                        node = second;
+                    }
+                }
+            }
+        }
+    }
+
+    // Pre-order index of the group containing `panel`
+    fn group_of(&self, panel: usize) -> Option<usize> {
+        match self {
+            DockNode::Tabs { panels, .. } => match panels.contains(&panel) {
+                true => Some(0),
+                false => None,
+            },
+            DockNode::Split { first, second, .. } => first
+                .group_of(panel)
+                .map(|i| 1 + i)
+                .or_else(|| second.group_of(panel).map(|i| 1 + first.count() + i)),
+        }
+    }
+
+    // Panels of the first group
+    fn first_group(&mut self) -> &mut Vec<usize> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels,
+            DockNode::Split { first, .. } => first.first_group(),
+        }
+    }
+
+    // Remove `panel`, then any empty groups
+    fn remove_panel(&mut self, panel: usize) {
+        if let Some(group) = self.group_of(panel) {
+            if let Some(DockNode::Tabs { panels, active }) = self.node_mut(group) {
+                let index = panels.iter().position(|p| *p == panel).unwrap();
+                panels.remove(index);
+                if index < *active || *active >= panels.len() {
+                    *active = active.saturating_sub(1);
+                }
+            }
+            self.prune();
+        }
+    }
+
+    // Replace splits with an empty side by the other side
+    fn prune(&mut self) {
+        let replacement = match self {
+            DockNode::Tabs { .. } => None,
+            DockNode::Split { first, second, .. } => {
+                first.prune();
+                second.prune();
+                if first.is_empty() {
+                    Some(mem::take(&mut **second))
+                } else if second.is_empty() {
+                    Some(mem::take(&mut **first))
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(node) = replacement {
+            *self = node;
+        }
+    }
+
+    // Dock `panel` (in a new group) at `edge` of this node
+    fn dock(&mut self, panel: usize, edge: DockEdge) {
+        if let DockNode::Tabs { panels, active } = self {
+            if panels.is_empty() {
+                panels.push(panel);
+                *active = 0;
+                return;
+            }
+        }
+        let old = mem::take(self);
+        let new = DockNode::Tabs {
+            panels: vec![panel],
+            active: 0,
+        };
+        let (first, second, split) = match edge.is_first() {
+            true => (new, old, 0.25),
+            false => (old, new, 0.75),
+        };
+        *self = DockNode::Split {
+            vertical: edge.is_vertical(),
+            split,
+            first: Box::new(first),
+            second: Box::new(second),
+        };
+    }
+
+    // Make a valid arrangement of `len` panels
+    //
+    // Out-of-range and repeated panels are removed, empty groups are pruned
+    // and missing panels are added to the first group.
+    fn normalise(&mut self, len: usize) {
+        fn retain(node: &mut DockNode, seen: &mut [bool]) {
+            match node {
+                DockNode::Tabs { panels, active } => {
+                    panels.retain(|p| match seen.get_mut(*p) {
+                        Some(seen) if !*seen => {
+                            *seen = true;
+                            true
+                        }
+                        _ => false,
+                    });
+                    *active = (*active).min(panels.len().saturating_sub(1));
+                }
+                DockNode::Split {
+                    split,
+                    first,
+                    second,
+                    ..
+                } => {
+                    *split = match split.is_nan() {
+                        true => 0.5,
+                        false => split.clamp(0.0, 1.0),
+                    };
+                    retain(first, seen);
+                    retain(second, seen);
+                }
+            }
+        }
+
+        let mut seen = vec![false; len];
+        retain(self, &mut seen);
+        self.prune();
+        let missing = (0..len).filter(|p| !seen[*p]);
+        self.first_group().extend(missing);
+    }
+}
+
+// Lengths of the two sides of a split
+fn split_lens(len: u32, split: f32, first: SizeRules, second: SizeRules) -> (u32, u32) {
+    let a = (split * len as f32).round() as u32;
+    let a = a
+        .min(len.saturating_sub(second.min_size()))
+        .max(first.min_size().min(len));
+    (a, len - a)
+}
+
+// Layout data of a node
+#[derive(Clone, Copy, Debug, Default)]
+struct NodeData {
+    // Horizontal and vertical rules
+    rules: [SizeRules; 2],
+    rect: Rect,
+}
+
+// Sizes common to all nodes
+#[derive(Clone, Debug, Default)]
+struct Metrics {
+    // Width of the tab of each panel
+    tab_widths: Vec<u32>,
+    tab_height: u32,
+    handle_size: u32,
+    surround: (Size, Size),
+}
+
+impl Metrics {
+    fn page_rect(&self, rect: Rect) -> Rect {
+        Rect {
+            pos: rect.pos + Coord(0, self.tab_height as i32),
+            size: Size(rect.size.0, rect.size.1.saturating_sub(self.tab_height)),
+        }
+    }
+
+    fn tab_rect(&self, rect: Rect, panels: &[usize], tab: usize) -> Rect {
+        let x: u32 = panels[0..tab].iter().map(|p| self.tab_widths[*p]).sum();
+        Rect {
+            pos: rect.pos + Coord(x as i32, 0),
+            size: Size(self.tab_widths[panels[tab]], self.tab_height),
+        }
+    }
+}
+
+// Solve rules of `node` (at pre-order `index`) and its descendants
+fn solve_rules<W: Widget>(
+    node: &DockNode,
+    index: usize,
+    panels: &mut [W],
+    nodes: &mut [NodeData],
+    metrics: &Metrics,
+    size_handle: &mut dyn SizeHandle,
+    axis: AxisInfo,
+) -> SizeRules {
+    let rules = match node {
+        DockNode::Tabs { panels: group, .. } => {
+            let mut rules = SizeRules::EMPTY;
+            for panel in group {
+                let child = &mut panels[*panel];
+                rules = rules.max(layout::child_rules(child, size_handle, axis));
+            }
+            match axis.vertical() {
+                false => {
+                    let tabs = group.iter().map(|p| metrics.tab_widths[*p]).sum();
+                    rules.max(SizeRules::fixed(tabs))
+                }
+                true => SizeRules::fixed(metrics.tab_height) + rules,
+            }
+        }
+        DockNode::Split {
+            vertical,
+            first,
+            second,
+            ..
+        } => {
+            let index2 = index + 1 + first.count();
+            let a = solve_rules(first, index + 1, panels, nodes, metrics, size_handle, axis);
+            let b = solve_rules(second, index2, panels, nodes, metrics, size_handle, axis);
+            match axis.vertical() == *vertical {
+                true => a + b + metrics.handle_size,
+                false => a.max(b),
+            }
+        }
+    };
+    nodes[index].rules[axis.vertical() as usize] = rules;
+    rules
+}
+
+// Set rects of `node` (at pre-order `index`) and its descendants
+fn set_rects<W: Widget>(
+    node: &DockNode,
+    index: usize,
+    rect: Rect,
+    panels: &mut [W],
+    nodes: &mut [NodeData],
+    metrics: &Metrics,
+    size_handle: &mut dyn SizeHandle,
+) {
+    nodes[index].rect = rect;
+    match node {
+        DockNode::Tabs { panels: group, .. } => {
+            let page = metrics.page_rect(rect);
+            for panel in group {
+                panels[*panel].set_rect(size_handle, page);
+            }
+        }
+        DockNode::Split {
+            vertical,
+            split,
+            first,
+            second,
+        } => {
+            let index2 = index + 1 + first.count();
+            let axis = *vertical as usize;
+            let (rules1, rules2) = (nodes[index + 1].rules[axis], nodes[index2].rules[axis]);
+            let handle = metrics.handle_size;
+            let (rect1, rect2) = match vertical {
+                false => {
+                    let len = rect.size.0.saturating_sub(handle);
+                    let (a, b) = split_lens(len, *split, rules1, rules2);
+                    let rect2 = Rect {
+                        pos: rect.pos + Coord((a + handle) as i32, 0),
+                        size: Size(b, rect.size.1),
+                    };
+                    (
+                        Rect {
+                            pos: rect.pos,
+                            size: Size(a, rect.size.1),
+                        },
+                        rect2,
+                    )
+                }
+                true => {
+                    let len = rect.size.1.saturating_sub(handle);
+                    let (a, b) = split_lens(len, *split, rules1, rules2);
+                    let rect2 = Rect {
+                        pos: rect.pos + Coord(0, (a + handle) as i32),
+                        size: Size(rect.size.0, b),
+                    };
+                    (
+                        Rect {
+                            pos: rect.pos,
+                            size: Size(rect.size.0, a),
+                        },
+                        rect2,
+                    )
+                }
+            };
+            set_rects(first, index + 1, rect1, panels, nodes, metrics, size_handle);
+            set_rects(second, index2, rect2, panels, nodes, metrics, size_handle);
+        }
+    }
+}
+
+// The part of the layout under the mouse
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    // The shown page of a group: its panel
+    Page(usize),
+    // A tab: the group's pre-order index and tab index
+    Tab(usize, usize),
+    // A split's handle: the split's pre-order index
+    Handle(usize),
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Press {
+    // Dragging a handle: split index and press offset from the handle
+    Handle(PressSource, usize, i32),
+    // Pressed a tab: group index and tab index
+    Tab(PressSource, usize, usize),
+}
+
+impl Press {
+    fn source(self) -> PressSource {
+        match self {
+            Press::Handle(source, ..) | Press::Tab(source, ..) => source,
+        }
+    }
+}
+
+/// A layout of panels which may be docked at edges, tabbed together and
+/// resized
+///
+/// Panels are arranged in groups, each showing one panel at a time with a bar
+/// of tabs. Groups are separated by handles which may be dragged to resize
+/// them. A tab may be dragged onto another group: dropping it on the tab bar
+/// or the middle of a group adds the panel to that group, while dropping it
+/// near an edge of a group docks the panel at that edge. Panels may also be
+/// arranged programmatically, e.g. with [`DockLayout::dock`].
+///
+/// The arrangement is described by a [`DockNode`]; see
+/// [`DockLayout::arrangement`] and [`DockLayout::with_arrangement`].
+///
+/// ```
+/// use kas::widget::{DockEdge, DockLayout, Label};
+///
+/// let dock = DockLayout::new()
+///     .with_panel("Editor", Label::from("main.rs"))
+///     .with_docked("Files", Label::from("src/"), DockEdge::Left)
+///     .with_docked("Output", Label::from("Finished"), DockEdge::Bottom);
+/// assert_eq!(dock.len(), 3);
+///
+/// // The arrangement is plain data and may be copied to another layout
+/// let arrangement = dock.arrangement().clone();
+/// let restored = DockLayout::new()
+///     .with_panel("Editor", Label::from("main.rs"))
+///     .with_panel("Files", Label::from("src/"))
+///     .with_panel("Output", Label::from("Finished"))
+///     .with_arrangement(arrangement.clone());
+/// assert_eq!(restored.arrangement(), &arrangement);
+/// ```
+///
+/// Panels may be added but not removed (which would renumber them).
+/// Configuring, resizing, drawing and event handling are all O(n) in the
+/// number of panels.
+#[derive(Clone)]
+pub struct DockLayout<W: Widget> {
+    core: CoreData,
+    titles: Vec<String>,
+    panels: Vec<W>,
+    root: DockNode,
+    // Data of each node, in pre-order
+    nodes: Vec<NodeData>,
+    metrics: Metrics,
+    press: Option<Press>,
+    // Hovered handle
+    hover: Option<usize>,
+}
+
+impl<W: Widget> Debug for DockLayout<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DockLayout {{ core: {:?}, titles: {:?}, panels: {:?}, root: {:?}, ... }}",
+            self.core, self.titles, self.panels, self.root,
+        )
+    }
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget> WidgetCore for DockLayout<W> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "DockLayout"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.panels.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.panels.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.panels.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.panels {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.panels {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Widget for DockLayout<W> {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let metrics = &mut self.metrics;
+        metrics.handle_size = size_handle.splitter();
+        metrics.surround = size_handle.button_surround();
+        let surround = metrics.surround.0 + metrics.surround.1;
+        if !axis.vertical() {
+            metrics.tab_widths.clear();
+            for title in &self.titles {
+                let rules = size_handle.text_bound(title, TextClass::Button, false, axis);
+                metrics.tab_widths.push(rules.min_size() + surround.0);
+            }
+        } else {
+            metrics.tab_height = size_handle.line_height(TextClass::Button) + surround.1;
+        }
+
+        self.nodes.resize(self.root.count(), NodeData::default());
+        let (panels, nodes) = (&mut self.panels, &mut self.nodes);
+        solve_rules(&self.root, 0, panels, nodes, metrics, size_handle, axis)
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.layout(size_handle);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let props = || TextProperties {
+            class: TextClass::Button,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+        let (first, last) = self.metrics.surround;
+
+        for (index, data) in self.nodes.iter().enumerate() {
+            match self.root.node(index) {
+                Some(DockNode::Tabs { panels, active }) => {
+                    for (tab, panel) in panels.iter().enumerate() {
+                        let rect = self.metrics.tab_rect(data.rect, panels, tab);
+                        let pressed = match self.press {
+                            Some(Press::Tab(_, i, t)) => i == index && t == tab,
+                            _ => false,
+                        };
+                        let hl = HighlightState {
+                            depress: tab == *active || pressed,
+                            ..HighlightState::default()
+                        };
+                        draw_handle.button(rect, hl);
+                        let text_rect = Rect {
+                            pos: rect.pos + first,
+                            size: rect.size - (first + last).min(rect.size),
+                        };
+                        draw_handle.text(text_rect, &self.titles[*panel], props());
+                    }
+                    if let Some(panel) = panels.get(*active) {
+                        layout::draw_child(&self.panels[*panel], draw_handle, ev_mgr);
+                    }
+                }
+                Some(DockNode::Split { vertical, .. }) => {
+                    let dragged = match self.press {
+                        Some(Press::Handle(_, i, _)) => i == index,
+                        _ => false,
+                    };
+                    let hl = HighlightState {
+                        hover: highlights.hover && self.hover == Some(index),
+                        depress: dragged,
+                        ..HighlightState::default()
+                    };
+                    draw_handle.splitter(self.handle_rect(index), *vertical, hl);
+                }
+                None => (),
+            }
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for DockLayout<W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        let target = match addr {
+            Address::Id(id) => {
+                for child in &mut self.panels {
+                    if id <= child.id() {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+                debug_assert!(id == self.id(), "Handler::handle: bad WidgetId");
+                Target::None
+            }
+            Address::Coord(coord) => match self.target_at(coord) {
+                Target::Page(panel) => return self.panels[panel].handle(tk, addr, event),
+                target => target,
+            },
+        };
+
+        match event {
+            Event::Identify => {
+                let hover = match target {
+                    Target::Handle(index) => Some(index),
+                    _ => None,
+                };
+                if hover != self.hover {
+                    self.hover = hover;
+                    tk.redraw(self.id());
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                let press = match target {
+                    Target::Handle(index) => {
+                        let offset = self.main(index, coord - self.handle_rect(index).pos);
+                        Press::Handle(source, index, offset)
+                    }
+                    Target::Tab(index, tab) => Press::Tab(source, index, tab),
+                    _ => return Response::Unhandled(Event::PressStart { source, coord }),
+                };
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some(press);
+                tk.redraw(self.id());
+                Response::None
+            }
+            Event::PressMove { source, coord, .. } => match self.press {
+                Some(Press::Handle(s, index, offset)) if s == source => {
+                    self.drag_handle(tk, index, coord, offset);
+                    Response::None
+                }
+                Some(Press::Tab(s, ..)) if s == source => Response::None,
+                _ => Response::Unhandled(event),
+            },
+            Event::PressEnd { source, coord, .. }
+                if self.press.map(|p| p.source()) == Some(source) =>
+            {
+                if let Some(Press::Tab(_, index, tab)) = self.press.take() {
+                    self.drop_tab(tk, index, tab, coord);
+                }
+                tk.redraw(self.id());
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
+
+impl<W: Widget> Default for DockLayout<W> {
+    fn default() -> Self {
+        DockLayout::new()
+    }
+}
+
+impl<W: Widget> DockLayout<W> {
+    /// Construct an empty layout
+    pub fn new() -> Self {
+        DockLayout {
+            core: Default::default(),
+            titles: vec![],
+            panels: vec![],
+            root: DockNode::default(),
+            nodes: vec![],
+            metrics: Metrics::default(),
+            press: None,
+            hover: None,
+        }
+    }
+
+    /// Add a panel to the first group (inline)
+    pub fn with_panel<S: Into<String>>(mut self, title: S, panel: W) -> Self {
+        let index = self.add(title.into(), panel);
+        self.root.first_group().push(index);
+        self
+    }
+
+    /// Add a panel, docked at `edge` of the layout (inline)
+    pub fn with_docked<S: Into<String>>(mut self, title: S, panel: W, edge: DockEdge) -> Self {
+        let index = self.add(title.into(), panel);
+        self.root.dock(index, edge);
+        self
+    }
+
+    /// Set the arrangement (inline)
+    ///
+    /// See [`DockLayout::arrangement`]. Panels are matched by index: missing
+    /// panels are added to the first group while unknown panels are ignored.
+    pub fn with_arrangement(mut self, arrangement: DockNode) -> Self {
+        self.root = arrangement;
+        self.root.normalise(self.panels.len());
+        self
+    }
+
+    /// Get the arrangement
+    ///
+    /// This describes the groups, tab order, shown panels and the position of
+    /// each handle; it may be restored with [`DockLayout::with_arrangement`].
+    #[inline]
+    pub fn arrangement(&self) -> &DockNode {
+        &self.root
+    }
+
+    /// Set the arrangement
+    ///
+    /// See [`DockLayout::with_arrangement`].
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_arrangement(&mut self, tk: &mut dyn TkWindow, arrangement: DockNode) {
+        self.root = arrangement;
+        self.root.normalise(self.panels.len());
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Get the number of panels
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.panels.len()
+    }
+
+    /// True if there are no panels
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.panels.is_empty()
+    }
+
+    /// Add a panel to the first group, returning its index
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push<S: Into<String>>(&mut self, tk: &mut dyn TkWindow, title: S, panel: W) -> usize {
+        let index = self.add(title.into(), panel);
+        self.root.first_group().push(index);
+        tk.send_action(TkAction::Reconfigure);
+        index
+    }
+
+    /// Access a panel
+    #[inline]
+    pub fn panel(&self, index: usize) -> Option<&W> {
+        self.panels.get(index)
+    }
+
+    /// Access a panel mutably
+    #[inline]
+    pub fn panel_mut(&mut self, index: usize) -> Option<&mut W> {
+        self.panels.get_mut(index)
+    }
+
+    /// Get the title of a panel
+    #[inline]
+    pub fn title(&self, index: usize) -> Option<&str> {
+        self.titles.get(index).map(|s| s.as_str())
+    }
+
+    /// Dock `panel` at `edge` of the layout
+    ///
+    /// Panics if `panel` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn dock(&mut self, tk: &mut dyn TkWindow, panel: usize, edge: DockEdge) {
+        assert!(panel < self.panels.len());
+        self.root.remove_panel(panel);
+        self.root.dock(panel, edge);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Dock `panel` at `edge` of the group containing `target`
+    ///
+    /// Panics if either index is out of bounds. Does nothing if
+    /// `panel == target`.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn dock_beside(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        panel: usize,
+        target: usize,
+        edge: DockEdge,
+    ) {
+        assert!(panel < self.panels.len() && target < self.panels.len());
+        if panel != target {
+            self.root.remove_panel(panel);
+            let group = self.root.group_of(target).unwrap();
+            self.root.node_mut(group).unwrap().dock(panel, edge);
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+
+    /// Move `panel` into the group containing `target`, after it
+    ///
+    /// The panel is shown. Panics if either index is out of bounds. Does
+    /// nothing if `panel == target`.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn tab_with(&mut self, tk: &mut dyn TkWindow, panel: usize, target: usize) {
+        assert!(panel < self.panels.len() && target < self.panels.len());
+        self.insert_tab(tk, panel, target, false);
+    }
+
+    /// Show `panel` within its group
+    ///
+    /// Panics if `panel` is out of bounds.
+    pub fn activate(&mut self, tk: &mut dyn TkWindow, panel: usize) {
+        let group = self.root.group_of(panel).unwrap();
+        if let Some(DockNode::Tabs { panels, active }) = self.root.node_mut(group) {
+            let index = panels.iter().position(|p| *p == panel).unwrap();
+            if index != *active {
+                *active = index;
+                tk.send_action(TkAction::Redraw);
+            }
+        }
+    }
+
+    fn add(&mut self, title: String, panel: W) -> usize {
+        self.titles.push(title);
+        self.panels.push(panel);
+        self.panels.len() - 1
+    }
+
+    // Move `panel` into the group of `target`, before or after it
+    fn insert_tab(&mut self, tk: &mut dyn TkWindow, panel: usize, target: usize, before: bool) {
+        if panel == target {
+            return;
+        }
+        self.root.remove_panel(panel);
+        let group = self.root.group_of(target).unwrap();
+        if let Some(DockNode::Tabs { panels, active }) = self.root.node_mut(group) {
+            let index = panels.iter().position(|p| *p == target).unwrap();
+            let index = index + !before as usize;
+            panels.insert(index, panel);
+            *active = index;
+        }
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    // Drop tab `tab` of group `group` at `coord`
+    fn drop_tab(&mut self, tk: &mut dyn TkWindow, group: usize, tab: usize, coord: Coord) {
+        let panels = match self.root.node(group) {
+            Some(DockNode::Tabs { panels, .. }) => panels.clone(),
+            _ => return,
+        };
+        let panel = panels[tab];
+        match self.target_at(coord) {
+            Target::Tab(g, t) if g == group && t == tab => self.activate(tk, panel),
+            Target::Tab(g, t) => {
+                if let Some(DockNode::Tabs { panels, .. }) = self.root.node(g) {
+                    let target = panels[t];
+                    self.insert_tab(tk, panel, target, true);
+                }
+            }
+            Target::Page(target) => {
+                let g = self.root.group_of(target).unwrap();
+                // When dropped on its own group, dock beside another panel
+                let target = match panels.iter().find(|p| **p != panel) {
+                    Some(other) if g == group => *other,
+                    _ if g == group => return,
+                    _ => target,
+                };
+                let page = self.metrics.page_rect(self.nodes[g].rect);
+                match drop_edge(page, coord) {
+                    Some(edge) => self.dock_beside(tk, panel, target, edge),
+                    None if g != group => self.insert_tab(tk, panel, target, false),
+                    None => (),
+                }
+            }
+            Target::Handle(_) | Target::None => (),
+        }
+    }
+
+    // Position of `coord` along the axis of the split at `index`
+    fn main(&self, index: usize, coord: Coord) -> i32 {
+        match self.root.node(index) {
+            Some(DockNode::Split { vertical: true, .. }) => coord.1,
+            _ => coord.0,
+        }
+    }
+
+    // Rect of the handle of the split at `index`
+    fn handle_rect(&self, index: usize) -> Rect {
+        let vertical = match self.root.node(index) {
+            Some(DockNode::Split { vertical, .. }) => *vertical,
+            _ => return Rect::default(),
+        };
+        let rect = self.nodes[index + 1].rect;
+        let handle = self.metrics.handle_size;
+        match vertical {
+            false => Rect {
+                pos: rect.pos + Coord(rect.size.0 as i32, 0),
+                size: Size(handle, rect.size.1),
+            },
+            true => Rect {
+                pos: rect.pos + Coord(0, rect.size.1 as i32),
+                size: Size(rect.size.0, handle),
+            },
+        }
+    }
+
+    fn target_at(&self, coord: Coord) -> Target {
+        let mut index = 0;
+        while let Some(data) = self.nodes.get(index) {
+            if !data.rect.contains(coord) {
+                break;
+            }
+            match self.root.node(index) {
+                Some(DockNode::Tabs { panels, active }) => {
+                    for tab in 0..panels.len() {
+                        if self
+                            .metrics
+                            .tab_rect(data.rect, panels, tab)
+                            .contains(coord)
+                        {
+                            return Target::Tab(index, tab);
+                        }
+                    }
+                    if self.metrics.page_rect(data.rect).contains(coord) {
+                        if let Some(panel) = panels.get(*active) {
+                            return Target::Page(*panel);
+                        }
+                    }
+                    break;
+                }
+                Some(DockNode::Split { first, .. }) => {
+                    if self.handle_rect(index).contains(coord) {
+                        return Target::Handle(index);
+                    }
+                    index = match self.nodes[index + 1].rect.contains(coord) {
+                        true => index + 1,
+                        false => index + 1 + first.count(),
+                    };
+                }
+                None => break,
+            }
+        }
+        Target::None
+    }
+
+    // Drag the handle of the split at `index` such that it starts at
+    // `coord - offset` (along the split's axis)
+    fn drag_handle(&mut self, tk: &mut dyn TkWindow, index: usize, coord: Coord, offset: i32) {
+        let data = self.nodes[index];
+        let start = self.main(index, data.rect.pos);
+        let pos = self.main(index, coord) - offset - start;
+        let handle = self.metrics.handle_size;
+        if let Some(DockNode::Split {
+            vertical,
+            split,
+            first,
+            ..
+        }) = self.root.node_mut(index)
+        {
+            let axis = *vertical as usize;
+            let index2 = index + 1 + first.count();
+            let len = match vertical {
+                false => data.rect.size.0,
+                true => data.rect.size.1,
+            };
+            let len = len.saturating_sub(handle);
+            let rules = (
+                self.nodes[index + 1].rules[axis],
+                self.nodes[index2].rules[axis],
+            );
+            let frac = pos.max(0) as f32 / len.max(1) as f32;
+            let (a, _) = split_lens(len, frac, rules.0, rules.1);
+            let frac = a as f32 / len.max(1) as f32;
+            if frac != *split {
+                *split = frac;
+                tk.with_size_handle(&mut |size_handle| self.layout(size_handle));
+                tk.redraw(self.id());
+            }
+        }
+    }
+
+    // Set rects of all nodes
+    fn layout(&mut self, size_handle: &mut dyn SizeHandle) {
+        if self.nodes.len() != self.root.count() {
+            return;
+        }
+        let (panels, nodes) = (&mut self.panels, &mut self.nodes);
+        let rect = self.core.rect;
+        set_rects(
+            &self.root,
+            0,
+            rect,
+            panels,
+            nodes,
+            &self.metrics,
+            size_handle,
+        );
+    }
+}
+
+// Edge of `rect` near `coord`, if within a quarter of the size
+fn drop_edge(rect: Rect, coord: Coord) -> Option<DockEdge> {
+    let rel = coord - rect.pos;
+    let (w, h) = (rect.size.0.max(1) as f32, rect.size.1.max(1) as f32);
+    let (x, y) = (rel.0 as f32 / w, rel.1 as f32 / h);
+    let edges = [
+        (x, DockEdge::Left),
+        (1.0 - x, DockEdge::Right),
+        (y, DockEdge::Top),
+        (1.0 - y, DockEdge::Bottom),
+    ];
+    let (dist, edge) =
+        edges
+            .iter()
+            .cloned()
+            .fold((1.0, DockEdge::Left), |a, b| if b.0 < a.0 { b } else { a });
+    match dist < 0.25 {
+        true => Some(edge),
+        false => None,
+    }
+}
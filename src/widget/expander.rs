@@ -0,0 +1,221 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Collapsible section
+
+use std::time::{Duration, Instant};
+
+use crate::class::HasText;
+use crate::event::{self, Action, Address, Event, Handler, Manager, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Duration of the expand/collapse animation
+const ANIMATION: Duration = Duration::from_millis(200);
+
+/// A collapsible section
+///
+/// This shows a header with a `label`, and below it a child widget which is
+/// only shown while expanded. Clicking the header (or activating it with the
+/// keyboard) expands or collapses the section; the change in height is
+/// animated, with the window's layout updated on each frame.
+///
+/// While collapsed, the child does not receive mouse input, but its size is
+/// still considered horizontally (so that the width does not change on
+/// expansion).
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Expander<W: Widget> {
+    #[core]
+    core: CoreData,
+    #[widget]
+    child: W,
+    label: String,
+    header: Rect,
+    child_height: u32,
+    expanded: bool,
+    // Animation start time and initial fraction shown
+    animation: Option<(Instant, f32)>,
+}
+
+impl<W: Widget> Expander<W> {
+    /// Construct a collapsed section with the given header `label`
+    pub fn new<S: Into<String>>(label: S, child: W) -> Self {
+        Expander {
+            core: Default::default(),
+            child,
+            label: label.into(),
+            header: Rect::default(),
+            child_height: 0,
+            expanded: false,
+            animation: None,
+        }
+    }
+
+    /// Set the initial state (chain style)
+    #[inline]
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// True if expanded (or expanding)
+    #[inline]
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Expand or collapse, with animation
+    pub fn set_expanded(&mut self, tk: &mut dyn TkWindow, expanded: bool) {
+        if expanded != self.expanded {
+            self.animation = Some((Instant::now(), self.fraction()));
+            self.expanded = expanded;
+            tk.data_mut().request_layout_animation(ANIMATION);
+        }
+    }
+
+    /// Access the child widget
+    #[inline]
+    pub fn child(&self) -> &W {
+        &self.child
+    }
+
+    /// Access the child widget mutably
+    #[inline]
+    pub fn child_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+
+    // Fraction of the child shown, from 0 (collapsed) to 1 (expanded)
+    fn fraction(&self) -> f32 {
+        let target = if self.expanded { 1.0 } else { 0.0 };
+        match self.animation {
+            Some((start, from)) => {
+                let t = start.elapsed().as_secs_f32() / ANIMATION.as_secs_f32();
+                if t >= 1.0 {
+                    target
+                } else {
+                    from + t * (target - from)
+                }
+            }
+            None => target,
+        }
+    }
+
+    // The part of the child which is shown
+    fn shown_rect(&self) -> Rect {
+        let mut rect = self.child.rect();
+        rect.size.1 = self.core.rect.size.1.saturating_sub(self.header.size.1);
+        rect
+    }
+}
+
+impl<W: Widget> Widget for Expander<W> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        let header = SizeRules::fixed(axis.extract_size(sides.0 + sides.1))
+            + size_handle.text_bound(&self.label, TextClass::Button, false, axis);
+        let child = layout::child_rules(&mut self.child, size_handle, axis);
+        if !axis.vertical() {
+            // Reserve a square for the arrow preceding the label
+            let arrow = size_handle.line_height(TextClass::Button) + (sides.0 + sides.1).1;
+            (header + arrow).max(child)
+        } else {
+            self.header.size.1 = header.ideal_size();
+            self.child_height = child.ideal_size();
+            match self.fraction() {
+                f if f >= 1.0 => header + child,
+                f => header + SizeRules::fixed((f * child.ideal_size() as f32).round() as u32),
+            }
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let header_height = self.header.size.1.min(rect.size.1);
+        self.header = Rect {
+            pos: rect.pos,
+            size: Size(rect.size.0, header_height),
+        };
+        // While animating, the child keeps its full size and is clipped
+        let height = (rect.size.1 - header_height).max(self.child_height);
+        let pos = rect.pos + Coord(0, header_height as i32);
+        let size = Size(rect.size.0, height);
+        self.child.set_rect(size_handle, Rect { pos, size });
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        draw_handle.button(self.header, ev_mgr.highlight_state(self.id()));
+        let props = |horiz| TextProperties {
+            class: TextClass::Button,
+            multi_line: false,
+            horiz,
+            vert: Align::Centre,
+        };
+        let mut rect = self.header;
+        rect.size.0 = rect.size.0.min(rect.size.1);
+        let arrow = if self.expanded { "▾" } else { "▸" };
+        draw_handle.text(rect, arrow, props(Align::Centre));
+        rect.pos.0 += rect.size.0 as i32;
+        rect.size.0 = self.header.size.0 - rect.size.0;
+        draw_handle.text(rect, &self.label, props(Align::Begin));
+
+        let shown = self.shown_rect();
+        if shown.size.1 > 0 {
+            draw_handle.clip_region(shown, Coord::ZERO, &mut |draw_handle| {
+                layout::draw_child(&self.child, draw_handle, ev_mgr)
+            });
+        }
+    }
+}
+
+impl<W: Widget> HasText for Expander<W> {
+    fn get_text(&self) -> &str {
+        &self.label
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.label = text;
+        tk.redraw(self.id());
+    }
+}
+
+impl<M, W: Widget + Handler<Msg = M>> Handler for Expander<W> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<M> {
+        match action {
+            Action::Activate => {
+                self.set_expanded(tk, !self.expanded);
+                Response::None
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<M> {
+        let to_child = match addr {
+            Address::Id(id) => id <= self.child.id(),
+            Address::Coord(coord) => self.expanded && self.shown_rect().contains(coord),
+        };
+        if to_child {
+            self.child.handle(tk, addr, event)
+        } else {
+            Manager::handle_generic(self, tk, event)
+        }
+    }
+}
@@ -0,0 +1,316 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Find-and-replace bar
+
+use std::ops::Range;
+
+use crate::class::{HasSpans, HasText};
+use crate::event::{Action, Address, Event, Handler, Response, VoidMsg};
+use crate::macros::Widget;
+use crate::theme::SpanClass;
+use crate::widget::{EditBox, Label, TextButton};
+use crate::{CoreData, LayoutData, TkWindow, Widget, WidgetCore};
+
+#[derive(Clone, Copy, Debug)]
+enum FindMsg {
+    Previous,
+    Next,
+    Replace,
+    ReplaceAll,
+}
+
+type FindBox = EditBox<fn(&str) -> FindMsg>;
+
+/// A find-and-replace bar attached to a text widget
+///
+/// The bar is shown above its target, which may be any widget supporting
+/// [`HasSpans`] (for example a multi-line [`EditBox`]). Searching is
+/// incremental: matches of the query are highlighted as it is typed, and one
+/// match is current. The enter key in the query box or the "Next" and
+/// "Previous" buttons move between matches. "Replace" replaces the current
+/// match (enter in the replacement box does the same); "Replace all" replaces
+/// every match. Replacement edits the target via [`HasText::set_string`].
+///
+/// Searching is case-sensitive and matches do not overlap.
+#[widget(layout = grid)]
+#[derive(Clone, Debug, Widget)]
+pub struct FindBar<W: HasSpans + Widget + 'static> {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as LayoutData>::Data,
+    #[widget(row = 0, col = 0)]
+    find_label: Label,
+    #[widget(row = 0, col = 1)]
+    find: FindBox,
+    #[widget(row = 0, col = 2)]
+    previous: TextButton<FindMsg>,
+    #[widget(row = 0, col = 3)]
+    next: TextButton<FindMsg>,
+    #[widget(row = 0, col = 4)]
+    status: Label,
+    #[widget(row = 1, col = 0)]
+    replace_label: Label,
+    #[widget(row = 1, col = 1)]
+    replacement: FindBox,
+    #[widget(row = 1, col = 2)]
+    replace: TextButton<FindMsg>,
+    #[widget(row = 1, col = 3, cspan = 2)]
+    replace_all: TextButton<FindMsg>,
+    #[widget(row = 2, col = 0, cspan = 5)]
+    target: W,
+    matches: Vec<Range<usize>>,
+    current: Option<usize>,
+}
+
+impl<W: HasSpans + Widget> FindBar<W> {
+    /// Construct, attached to `target`
+    pub fn new(target: W) -> Self {
+        let find: FindBox = EditBox::new("").on_activate(|_| FindMsg::Next);
+        let replacement: FindBox = EditBox::new("").on_activate(|_| FindMsg::Replace);
+        FindBar {
+            core: Default::default(),
+            layout_data: Default::default(),
+            find_label: Label::new("Find"),
+            find,
+            previous: TextButton::new("Previous", FindMsg::Previous),
+            next: TextButton::new("Next", FindMsg::Next),
+            // This text reserves space for the match count
+            status: Label::new("No matches"),
+            replace_label: Label::new("Replace"),
+            replacement,
+            replace: TextButton::new("Replace", FindMsg::Replace),
+            replace_all: TextButton::new("Replace all", FindMsg::ReplaceAll),
+            target,
+            matches: vec![],
+            current: None,
+        }
+    }
+
+    /// Access the target widget
+    #[inline]
+    pub fn target(&self) -> &W {
+        &self.target
+    }
+
+    /// Access the target widget mutably
+    ///
+    /// After changing the target's text, call [`FindBar::search`] to update
+    /// matches.
+    #[inline]
+    pub fn target_mut(&mut self) -> &mut W {
+        &mut self.target
+    }
+
+    /// Get the query
+    #[inline]
+    pub fn query(&self) -> &str {
+        self.find.get_text()
+    }
+
+    /// Set the query and search
+    pub fn set_query(&mut self, tk: &mut dyn TkWindow, query: String) {
+        self.find.set_string(tk, query);
+        self.search(tk);
+    }
+
+    /// Get the replacement text
+    #[inline]
+    pub fn replacement(&self) -> &str {
+        self.replacement.get_text()
+    }
+
+    /// Set the replacement text
+    pub fn set_replacement(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.replacement.set_string(tk, text);
+    }
+
+    /// Get the byte ranges of all matches in the target's text
+    #[inline]
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// Get the index of the current match, if any
+    #[inline]
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Search the target's text for the query, updating highlights
+    ///
+    /// The current match keeps its index where possible.
+    pub fn search(&mut self, tk: &mut dyn TkWindow) {
+        let query = self.find.get_text();
+        self.matches = match query.is_empty() {
+            true => vec![],
+            false => (self.target.get_text().match_indices(query))
+                .map(|(start, s)| start..start + s.len())
+                .collect(),
+        };
+        let len = self.matches.len();
+        self.current = match len {
+            0 => None,
+            _ => Some(self.current.unwrap_or(0).min(len - 1)),
+        };
+        self.update(tk);
+    }
+
+    /// Make the next match current, wrapping around at the end
+    pub fn find_next(&mut self, tk: &mut dyn TkWindow) {
+        let len = self.matches.len();
+        if len > 0 {
+            self.current = Some(self.current.map(|i| (i + 1) % len).unwrap_or(0));
+            self.update(tk);
+        }
+    }
+
+    /// Make the previous match current, wrapping around at the start
+    pub fn find_previous(&mut self, tk: &mut dyn TkWindow) {
+        let len = self.matches.len();
+        if len > 0 {
+            self.current = Some(self.current.map(|i| (i + len - 1) % len).unwrap_or(0));
+            self.update(tk);
+        }
+    }
+
+    /// Replace the current match with the replacement text
+    ///
+    /// The following match (if any) becomes current.
+    pub fn replace(&mut self, tk: &mut dyn TkWindow) {
+        let range = match self.current {
+            Some(index) => self.matches[index].clone(),
+            None => return,
+        };
+        let text = self.target.get_text();
+        let mut edited = String::with_capacity(text.len());
+        edited.push_str(&text[..range.start]);
+        edited.push_str(self.replacement.get_text());
+        edited.push_str(&text[range.end..]);
+        self.target.set_string(tk, edited);
+        self.search(tk);
+    }
+
+    /// Replace all matches with the replacement text
+    ///
+    /// Returns the number of replacements.
+    pub fn replace_all(&mut self, tk: &mut dyn TkWindow) -> usize {
+        let count = self.matches.len();
+        if count > 0 {
+            let text = self.target.get_text();
+            let edited = text.replace(self.find.get_text(), self.replacement.get_text());
+            self.target.set_string(tk, edited);
+            self.current = None;
+            self.search(tk);
+        }
+        count
+    }
+
+    // Update highlights and status
+    fn update(&mut self, tk: &mut dyn TkWindow) {
+        let current = self.current;
+        let spans = (self.matches.iter().enumerate())
+            .map(|(index, range)| match Some(index) == current {
+                true => (range.clone(), SpanClass::Selected),
+                false => (range.clone(), SpanClass::Match),
+            })
+            .collect();
+        self.target.set_spans(tk, spans);
+
+        let status = match (current, self.matches.len()) {
+            (Some(index), len) => format!("{} of {}", index + 1, len),
+            (None, _) if self.find.get_text().is_empty() => String::new(),
+            (None, _) => "No matches".to_string(),
+        };
+        self.status.set_string(tk, status);
+    }
+
+    fn handle_msg(&mut self, tk: &mut dyn TkWindow, r: Response<FindMsg>) -> Response<VoidMsg> {
+        match r.try_into() {
+            Ok(r) => r,
+            Err(msg) => {
+                match msg {
+                    FindMsg::Previous => self.find_previous(tk),
+                    FindMsg::Next => self.find_next(tk),
+                    FindMsg::Replace => self.replace(tk),
+                    FindMsg::ReplaceAll => {
+                        self.replace_all(tk);
+                    }
+                }
+                Response::None
+            }
+        }
+    }
+}
+
+impl<W: HasSpans + Widget + Handler<Msg = VoidMsg>> Handler for FindBar<W> {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<VoidMsg> {
+        // Route to a child, in the order children are numbered
+        let index = {
+            let children: [&dyn Widget; 10] = [
+                self.find_label.as_widget(),
+                self.find.as_widget(),
+                self.previous.as_widget(),
+                self.next.as_widget(),
+                self.status.as_widget(),
+                self.replace_label.as_widget(),
+                self.replacement.as_widget(),
+                self.replace.as_widget(),
+                self.replace_all.as_widget(),
+                self.target.as_widget(),
+            ];
+            match addr {
+                Address::Id(id) => children.iter().position(|w| id <= w.id()),
+                Address::Coord(coord) => children.iter().position(|w| w.rect().contains(coord)),
+            }
+        };
+
+        let edit = matches!(event, Event::Action(Action::ReceivedCharacter(_)));
+        match index {
+            Some(0) => self.find_label.handle(tk, addr, event),
+            Some(1) => {
+                let r = self.find.handle(tk, addr, event);
+                if edit {
+                    self.search(tk);
+                }
+                self.handle_msg(tk, r)
+            }
+            Some(2) => {
+                let r = self.previous.handle(tk, addr, event);
+                self.handle_msg(tk, r)
+            }
+            Some(3) => {
+                let r = self.next.handle(tk, addr, event);
+                self.handle_msg(tk, r)
+            }
+            Some(4) => self.status.handle(tk, addr, event),
+            Some(5) => self.replace_label.handle(tk, addr, event),
+            Some(6) => {
+                let r = self.replacement.handle(tk, addr, event);
+                self.handle_msg(tk, r)
+            }
+            Some(7) => {
+                let r = self.replace.handle(tk, addr, event);
+                self.handle_msg(tk, r)
+            }
+            Some(8) => {
+                let r = self.replace_all.handle(tk, addr, event);
+                self.handle_msg(tk, r)
+            }
+            Some(9) => {
+                let r = self.target.handle(tk, addr, event);
+                if edit {
+                    // Editing clears highlights; restore them
+                    self.search(tk);
+                }
+                r
+            }
+            _ => Response::Unhandled(event),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Flow box: a row which wraps
+
+use std::any::Any;
+use std::iter;
+
+use crate::event::{Address, Event, Handler, Manager, Response};
+use crate::layout::{self, AxisInfo, RulesSetter, RulesSolver, SizeRules};
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+use kas::geom::Rect;
+
+/// A flow box of boxed widgets
+///
+/// This is parameterised over handler message type.
+///
+/// See documentation of [`FlowBox`] type.
+pub type BoxFlowBox<M> = FlowBox<Box<dyn Handler<Msg = M>>>;
+
+/// A row of widgets which wraps onto new lines
+///
+/// Children are placed left-to-right, starting a new line whenever the next
+/// child does not fit the remaining width (compare flexbox with wrapping).
+/// Each child is given its ideal width where possible; each line is as high
+/// as its highest child.
+///
+/// The minimum width is that of the widest child, thus a window may be
+/// narrowed until each child is on its own line.
+///
+/// Like [`List`], this is essentially a [`Vec`] which also implements the
+/// [`Widget`] trait. Configuring, resizing, drawing and event handling are all
+/// O(n) in the number of children.
+///
+/// [`List`]: crate::widget::List
+#[derive(Clone, Default, Debug)]
+pub struct FlowBox<W: Widget> {
+    core: CoreData,
+    widgets: Vec<W>,
+    data: layout::FlowStorage,
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget> WidgetCore for FlowBox<W> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "FlowBox"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.widgets.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.widgets {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.widgets {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Widget for FlowBox<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut solver = layout::FlowSolver::new(axis, self.widgets.len(), &mut self.data);
+        for (n, child) in self.widgets.iter_mut().enumerate() {
+            solver.for_child(&mut self.data, n, |axis| {
+                layout::child_rules(child, size_handle, axis)
+            });
+        }
+        solver.finish(&mut self.data, iter::empty(), iter::empty())
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let mut setter = layout::FlowSetter::new(rect, self.widgets.len(), &mut self.data);
+
+        for (n, child) in self.widgets.iter_mut().enumerate() {
+            child.set_rect(size_handle, setter.child_rect(n));
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let target = draw_handle.target_rect();
+        for child in &self.widgets {
+            let rect = child.rect();
+            if rect.pos.1 < target.pos.1 + target.size.1 as i32
+                && target.pos.1 < rect.pos.1 + rect.size.1 as i32
+            {
+                layout::draw_child(child, draw_handle, ev_mgr);
+            }
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for FlowBox<W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match addr {
+            Address::Id(id) => {
+                for child in &mut self.widgets {
+                    if id <= child.id() {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+                debug_assert!(id == self.id(), "Handler::handle: bad WidgetId");
+            }
+            Address::Coord(coord) => {
+                for child in &mut self.widgets {
+                    if child.rect().contains(coord) {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+            }
+        }
+        Response::Unhandled(event)
+    }
+}
+
+impl<W: Widget> FlowBox<W> {
+    /// Construct a new instance
+    pub fn new(widgets: Vec<W>) -> Self {
+        FlowBox {
+            core: Default::default(),
+            widgets,
+            data: Default::default(),
+        }
+    }
+
+    /// True if there are no child widgets
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of child widgets
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Returns the number of lines, as of the last resize
+    pub fn lines(&self) -> usize {
+        self.data.lines()
+    }
+
+    /// Remove all child widgets
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if any widget is
+    /// removed.
+    pub fn clear(&mut self, tk: &mut dyn TkWindow) {
+        if !self.widgets.is_empty() {
+            tk.send_action(TkAction::Reconfigure);
+        }
+        self.widgets.clear();
+    }
+
+    /// Append a child widget
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push(&mut self, tk: &mut dyn TkWindow, widget: W) {
+        self.widgets.push(widget);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Remove the last child widget
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if any widget is
+    /// removed.
+    pub fn pop(&mut self, tk: &mut dyn TkWindow) -> Option<W> {
+        if !self.widgets.is_empty() {
+            tk.send_action(TkAction::Reconfigure);
+        }
+        self.widgets.pop()
+    }
+
+    /// Inserts a child widget position `index`
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn insert(&mut self, tk: &mut dyn TkWindow, index: usize, widget: W) {
+        self.widgets.insert(index, widget);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Removes the child widget at position `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn remove(&mut self, tk: &mut dyn TkWindow, index: usize) -> W {
+        let r = self.widgets.remove(index);
+        tk.send_action(TkAction::Reconfigure);
+        r
+    }
+
+    /// Append child widgets from an iterator
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if any widgets
+    /// are added.
+    pub fn extend<T: IntoIterator<Item = W>>(&mut self, tk: &mut dyn TkWindow, iter: T) {
+        let len = self.widgets.len();
+        self.widgets.extend(iter);
+        if len != self.widgets.len() {
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+}
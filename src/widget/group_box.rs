@@ -0,0 +1,121 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Titled group frame
+
+use crate::class::HasText;
+use crate::event::{self, Address, Event, Handler, Manager, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle, TextClass};
+use crate::{CoreData, TkAction, TkWindow, Widget};
+
+/// A group box: a frame around a child widget, with a title
+///
+/// This is like a `frame` layout (see [`make_widget`]), except that a title
+/// label is drawn overlapping the top edge of the frame. The group box is at
+/// least wide enough to show the whole title.
+///
+/// Messages from the child are returned to the parent.
+///
+/// [`make_widget`]: crate::macros::make_widget
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct GroupBox<W: Widget> {
+    #[core]
+    core: CoreData,
+    #[widget]
+    child: W,
+    title: String,
+    title_width: u32,
+    frame: (Size, Size),
+}
+
+impl<W: Widget> GroupBox<W> {
+    /// Construct, with the given `title` and `child`
+    pub fn new<S: Into<String>>(title: S, child: W) -> Self {
+        GroupBox {
+            core: Default::default(),
+            child,
+            title: title.into(),
+            title_width: 0,
+            frame: (Size::ZERO, Size::ZERO),
+        }
+    }
+
+    /// Access the child widget
+    #[inline]
+    pub fn child(&self) -> &W {
+        &self.child
+    }
+
+    /// Access the child widget mutably
+    #[inline]
+    pub fn child_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+}
+
+impl<W: Widget> Widget for GroupBox<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.frame = size_handle.group_frame();
+        let (tl, br) = self.frame;
+        let frame = axis.extract_size(tl) + axis.extract_size(br);
+        let rules = layout::child_rules(&mut self.child, size_handle, axis) + frame;
+        if !axis.vertical() {
+            // The title is indented by twice the left frame width
+            let title = size_handle.text_bound(&self.title, TextClass::Label, false, axis);
+            self.title_width = title.ideal_size();
+            let sides = SizeRules::fixed(2 * tl.0 + br.0);
+            rules.max(title + sides)
+        } else {
+            rules
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let (tl, br) = self.frame;
+        let pos = rect.pos + Coord::from(tl);
+        let size = Size(
+            rect.size.0.saturating_sub(tl.0 + br.0),
+            rect.size.1.saturating_sub(tl.1 + br.1),
+        );
+        self.child.set_rect(size_handle, Rect { pos, size });
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        draw_handle.group_frame(self.core.rect, &self.title, self.title_width);
+        layout::draw_child(&self.child, draw_handle, ev_mgr);
+    }
+}
+
+impl<W: Widget> HasText for GroupBox<W> {
+    fn get_text(&self) -> &str {
+        &self.title
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.title = text;
+        tk.send_action(TkAction::Reconfigure);
+    }
+}
+
+impl<M, W: Widget + Handler<Msg = M>> Handler for GroupBox<W> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<M> {
+        let to_child = match addr {
+            Address::Id(id) => id <= self.child.id(),
+            Address::Coord(coord) => self.child.rect().contains(coord),
+        };
+        if to_child {
+            self.child.handle(tk, addr, event)
+        } else {
+            Manager::handle_generic(self, tk, event)
+        }
+    }
+}
@@ -0,0 +1,177 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Hyperlink label
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug};
+
+use crate::class::HasText;
+use crate::event::{self, Action, CursorIcon, Handler, Response, VoidMsg};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// A label styled as a link
+///
+/// The text is drawn underlined in a link colour, and the mouse cursor shows
+/// a hand while over the link. The URL is shown as the link's tooltip.
+///
+/// By default, activating the link opens its URL with the system's default
+/// handler (see [`TkWindow::open_url`]). Alternatively, a handler may be set
+/// with [`Hyperlink::on_activate`], in which case the URL is not opened.
+#[widget]
+#[derive(Clone, Default, Widget)]
+pub struct Hyperlink<OA: 'static> {
+    #[core]
+    core: CoreData,
+    text: Cow<'static, str>,
+    url: Cow<'static, str>,
+    on_activate: OA,
+}
+
+impl<H> Debug for Hyperlink<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Hyperlink {{ core: {:?}, text: {:?}, url: {:?}, ... }}",
+            self.core, self.text, self.url
+        )
+    }
+}
+
+impl<OA: 'static> Widget for Hyperlink<OA> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        Some(&self.url)
+    }
+
+    fn cursor_icon(&self) -> CursorIcon {
+        CursorIcon::Hand
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        size_handle.text_bound(&self.text, TextClass::Label, false, axis)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let props = TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        let highlights = ev_mgr.highlight_state(self.id());
+        draw_handle.hyperlink(self.core.rect, &self.text, props, highlights);
+    }
+}
+
+impl Hyperlink<()> {
+    /// Construct a link with the given `text`, opening `url` when activated
+    ///
+    /// A `&'static str` text or URL is borrowed, not copied.
+    pub fn new<T, U>(text: T, url: U) -> Self
+    where
+        T: Into<Cow<'static, str>>,
+        U: Into<Cow<'static, str>>,
+    {
+        Hyperlink {
+            core: Default::default(),
+            text: text.into(),
+            url: url.into(),
+            on_activate: (),
+        }
+    }
+
+    /// Construct a link with static `text` and `url`
+    ///
+    /// This is equivalent to [`Hyperlink::new`], but usable in `const`
+    /// contexts.
+    pub const fn new_static(text: &'static str, url: &'static str) -> Self {
+        Hyperlink {
+            core: CoreData::new(),
+            text: Cow::Borrowed(text),
+            url: Cow::Borrowed(url),
+            on_activate: (),
+        }
+    }
+
+    /// Set the event handler to be called on activation.
+    ///
+    /// The closure `f` is called with the link's URL when activated, and the
+    /// result of `f` is returned from the event handler. The URL is not
+    /// opened.
+    pub fn on_activate<M, OA: Fn(&str) -> M>(self, f: OA) -> Hyperlink<OA> {
+        Hyperlink {
+            core: self.core,
+            text: self.text,
+            url: self.url,
+            on_activate: f,
+        }
+    }
+}
+
+impl<OA: 'static> Hyperlink<OA> {
+    /// Get the URL
+    #[inline]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Set the URL
+    pub fn set_url<U: Into<Cow<'static, str>>>(&mut self, url: U) {
+        self.url = url.into();
+    }
+}
+
+impl<H> HasText for Hyperlink<H> {
+    fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.text = Cow::Owned(text);
+        tk.send_action(TkAction::Reconfigure);
+    }
+}
+
+impl Handler for Hyperlink<()> {
+    type Msg = VoidMsg;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
+        match action {
+            Action::Activate => {
+                tk.open_url(&self.url);
+                Response::None
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+}
+
+impl<M, H: Fn(&str) -> M> Handler for Hyperlink<H> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, _: &mut dyn TkWindow, action: Action) -> Response<M> {
+        match action {
+            Action::Activate => ((self.on_activate)(&self.url)).into(),
+            a => Response::unhandled_action(a),
+        }
+    }
+}
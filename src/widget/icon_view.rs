@@ -0,0 +1,440 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Icon (thumbnail grid) view
+
+use std::fmt::Debug;
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, PressSource, Response,
+    ScrollDelta,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, ListData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// Provider of thumbnails for an [`IconView`]
+///
+/// Thumbnails are requested lazily: [`ThumbnailProvider::request`] is called
+/// when a cell becomes visible and [`ThumbnailProvider::release`] when it is
+/// scrolled out of view. Loading may happen asynchronously; until
+/// [`ThumbnailProvider::draw`] returns true a placeholder is drawn. When a
+/// thumbnail becomes ready, the provider's owner should cause a redraw (e.g.
+/// via `TkAction::Redraw`).
+///
+/// The unit type `()` implements this trait, providing no thumbnails.
+pub trait ThumbnailProvider: Debug {
+    /// Request the thumbnail for item `index`
+    fn request(&mut self, index: usize);
+
+    /// Item `index` is no longer visible
+    ///
+    /// The thumbnail may be discarded.
+    fn release(&mut self, index: usize) {
+        let _ = index;
+    }
+
+    /// Draw the thumbnail for item `index` into `rect`
+    ///
+    /// Returns false if the thumbnail is not (yet) available.
+    fn draw(&self, index: usize, draw_handle: &mut dyn DrawHandle, rect: Rect) -> bool;
+}
+
+impl ThumbnailProvider for () {
+    fn request(&mut self, _: usize) {}
+
+    fn draw(&self, _: usize, _: &mut dyn DrawHandle, _: Rect) -> bool {
+        false
+    }
+}
+
+/// A grid of icons (thumbnails) with labels
+///
+/// Items of a [`ListData`] model are shown in fixed-size cells, laid out in
+/// rows which wrap to the available width. Only visible cells are drawn, and
+/// thumbnails for these are requested lazily from a [`ThumbnailProvider`].
+///
+/// The view scrolls vertically with the mouse wheel. With key focus, arrow
+/// keys move the selection; activating an item (by click or with the enter
+/// key) yields its index as a message.
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct IconView<D: ListData + 'static, P: ThumbnailProvider + 'static>
+where
+    D::Item: AsRef<str>,
+{
+    #[core]
+    core: CoreData,
+    data: D,
+    provider: P,
+    thumb_lines: u32,
+    thumb_size: u32,
+    line_height: u32,
+    margin: u32,
+    cell: Size,
+    cols: usize,
+    offset: u32,
+    max_offset: u32,
+    visible: (usize, usize),
+    selected: Option<usize>,
+    press: Option<(PressSource, Option<usize>)>,
+}
+
+impl<D: ListData> IconView<D, ()>
+where
+    D::Item: AsRef<str>,
+{
+    /// Construct a view over `data`, without thumbnails
+    pub fn new(data: D) -> Self {
+        IconView {
+            core: Default::default(),
+            data,
+            provider: (),
+            thumb_lines: 4,
+            thumb_size: 0,
+            line_height: 0,
+            margin: 0,
+            cell: Size::ZERO,
+            cols: 1,
+            offset: 0,
+            max_offset: 0,
+            visible: (0, 0),
+            selected: None,
+            press: None,
+        }
+    }
+
+    /// Set the thumbnail provider
+    pub fn with_provider<P: ThumbnailProvider>(self, provider: P) -> IconView<D, P> {
+        IconView {
+            core: self.core,
+            data: self.data,
+            provider,
+            thumb_lines: self.thumb_lines,
+            thumb_size: self.thumb_size,
+            line_height: self.line_height,
+            margin: self.margin,
+            cell: self.cell,
+            cols: self.cols,
+            offset: self.offset,
+            max_offset: self.max_offset,
+            visible: self.visible,
+            selected: self.selected,
+            press: self.press,
+        }
+    }
+}
+
+impl<D: ListData, P: ThumbnailProvider> IconView<D, P>
+where
+    D::Item: AsRef<str>,
+{
+    /// Set the thumbnail size, in multiples of the text line height
+    ///
+    /// The default is 4.
+    #[inline]
+    pub fn with_thumb_lines(mut self, lines: u32) -> Self {
+        self.thumb_lines = lines.max(1);
+        self
+    }
+
+    /// Access the data
+    #[inline]
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    /// Replace the data
+    ///
+    /// The selection is cleared and the view scrolled to the top.
+    pub fn set_data(&mut self, tk: &mut dyn TkWindow, data: D) {
+        for i in self.visible.0..self.visible.1 {
+            self.provider.release(i);
+        }
+        self.visible = (0, 0);
+        self.data = data;
+        self.selected = None;
+        self.offset = 0;
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Access the thumbnail provider
+    #[inline]
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Access the thumbnail provider mutably
+    #[inline]
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+
+    /// Get the selected index, if any
+    #[inline]
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Set the selected index
+    pub fn set_selected(&mut self, tk: &mut dyn TkWindow, index: Option<usize>) {
+        self.selected = index.filter(|i| *i < self.data.len());
+        if let Some(i) = self.selected {
+            self.ensure_visible(i);
+        }
+        tk.redraw(self.id());
+    }
+
+    // Cell rect, in (unscrolled) content coordinates
+    fn cell_rect(&self, index: usize) -> Rect {
+        let col = (index % self.cols) as u32;
+        let row = (index / self.cols) as u32;
+        let pos =
+            self.core.rect.pos + Coord((col * self.cell.0) as i32, (row * self.cell.1) as i32);
+        Rect {
+            pos,
+            size: self.cell,
+        }
+    }
+
+    // Index of the cell at `coord`, if any
+    fn cell_at(&self, coord: Coord) -> Option<usize> {
+        if !self.core.rect.contains(coord) || self.cell.0 == 0 || self.cell.1 == 0 {
+            return None;
+        }
+        let x = (coord.0 - self.core.rect.pos.0) as u32 / self.cell.0;
+        let y = (coord.1 - self.core.rect.pos.1) as u32 + self.offset;
+        let col = x as usize;
+        let index = (y / self.cell.1) as usize * self.cols + col;
+        if col < self.cols && index < self.data.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn update_visible(&mut self) {
+        let ch = self.cell.1;
+        let (start, end) = match self.offset.checked_div(ch) {
+            None => (0, 0),
+            Some(first_row) => {
+                let last_row = (self.offset + self.core.rect.size.1).div_ceil(ch) as usize;
+                let end = (last_row * self.cols).min(self.data.len());
+                ((first_row as usize * self.cols).min(end), end)
+            }
+        };
+
+        let old = self.visible;
+        for i in old.0..old.1 {
+            if i < start || i >= end {
+                self.provider.release(i);
+            }
+        }
+        for i in start..end {
+            if i < old.0 || i >= old.1 {
+                self.provider.request(i);
+            }
+        }
+        self.visible = (start, end);
+    }
+
+    // Set offset, returning true if changed
+    fn set_offset(&mut self, offset: u32) -> bool {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            self.update_visible();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ensure_visible(&mut self, index: usize) {
+        let top = (index / self.cols) as u32 * self.cell.1;
+        let height = self.core.rect.size.1;
+        if top < self.offset {
+            self.set_offset(top);
+        } else if top + self.cell.1 > self.offset + height {
+            self.set_offset(top + self.cell.1 - height.min(top + self.cell.1));
+        }
+    }
+
+    fn navigate(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> bool {
+        let len = self.data.len() as i64;
+        if len == 0 {
+            return false;
+        }
+        let cols = self.cols as i64;
+        let page_rows = (self.core.rect.size.1 / self.cell.1.max(1)).max(1) as i64;
+        let current = self.selected.map(|i| i as i64);
+        let index = match (key, current) {
+            (NavKey::Home, _) => 0,
+            (NavKey::End, _) => len - 1,
+            (_, None) => 0,
+            (NavKey::Left, Some(i)) => i - 1,
+            (NavKey::Right, Some(i)) => i + 1,
+            (NavKey::Up, Some(i)) => i - cols,
+            (NavKey::Down, Some(i)) => i + cols,
+            (NavKey::PageUp, Some(i)) => i - page_rows * cols,
+            (NavKey::PageDown, Some(i)) => i + page_rows * cols,
+        };
+        let index = index.max(0).min(len - 1) as usize;
+        self.set_selected(tk, Some(index));
+        true
+    }
+}
+
+impl<D: ListData, P: ThumbnailProvider> Widget for IconView<D, P>
+where
+    D::Item: AsRef<str>,
+{
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.thumb_size = self.thumb_lines * self.line_height;
+        self.cell = Size(
+            self.thumb_size + 2 * self.margin,
+            self.thumb_size + self.line_height + 3 * self.margin,
+        );
+
+        if !axis.vertical() {
+            SizeRules::variable(self.cell.0, 4 * self.cell.0)
+        } else {
+            let cols = axis
+                .fixed(false)
+                .map(|w| w / self.cell.0)
+                .unwrap_or(4)
+                .max(1);
+            let rows = (self.data.len() as u32).div_ceil(cols);
+            SizeRules::variable(self.cell.1, rows.clamp(1, 4) * self.cell.1)
+        }
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.cols = ((rect.size.0 / self.cell.0.max(1)) as usize).max(1);
+        let rows = self.data.len().div_ceil(self.cols);
+        let content_height = rows as u32 * self.cell.1;
+        self.max_offset = content_height.saturating_sub(rect.size.1);
+        self.offset = self.offset.min(self.max_offset);
+        self.update_visible();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let offset = Coord(0, self.offset as i32);
+        draw_handle.clip_region(self.core.rect, offset, &mut |draw_handle| {
+            for index in self.visible.0..self.visible.1 {
+                let cell = self.cell_rect(index);
+                if self.selected == Some(index) {
+                    let hl = HighlightState {
+                        key_focus: highlights.key_focus,
+                        ..HighlightState::default()
+                    };
+                    draw_handle.button(cell, hl);
+                }
+
+                let x = (cell.size.0 - self.thumb_size) / 2;
+                let thumb = Rect {
+                    pos: cell.pos + Coord(x as i32, self.margin as i32),
+                    size: Size::uniform(self.thumb_size),
+                };
+                if !self.provider.draw(index, draw_handle, thumb) {
+                    draw_handle.edit_box(thumb, HighlightState::default());
+                }
+
+                let y = 2 * self.margin + self.thumb_size;
+                let label = Rect {
+                    pos: cell.pos + Coord(self.margin as i32, y as i32),
+                    size: Size(cell.size.0 - 2 * self.margin, self.line_height),
+                };
+                if let Some(item) = self.data.get(index) {
+                    let props = TextProperties {
+                        class: TextClass::Label,
+                        multi_line: false,
+                        horiz: Align::Centre,
+                        vert: Align::Begin,
+                    };
+                    draw_handle.text(label, item.as_ref(), props);
+                }
+            }
+        });
+    }
+}
+
+impl<D: ListData, P: ThumbnailProvider> Handler for IconView<D, P>
+where
+    D::Item: AsRef<str>,
+{
+    type Msg = usize;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<usize> {
+        match action {
+            Action::Activate => match self.selected {
+                Some(index) => Response::Msg(index),
+                None => Response::None,
+            },
+            Action::Scroll(delta) => {
+                let d = match delta {
+                    ScrollDelta::LineDelta(_, y) => -(3.0 * y * self.line_height as f32) as i64,
+                    ScrollDelta::PixelDelta(d) => -d.1 as i64,
+                };
+                let offset = (self.offset as i64 + d).max(0) as u32;
+                if self.set_offset(offset) {
+                    tk.redraw(self.id());
+                    Response::None
+                } else {
+                    Response::unhandled_action(Action::Scroll(delta))
+                }
+            }
+            Action::NavKey(key) => match self.navigate(tk, key) {
+                true => Response::None,
+                false => Response::unhandled_action(Action::NavKey(key)),
+            },
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<usize> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.cell_at(coord)));
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.cell_at(coord),
+                    false => None,
+                };
+                match start {
+                    Some(index) if end == start => {
+                        self.selected = Some(index);
+                        tk.redraw(self.id());
+                        Response::Msg(index)
+                    }
+                    _ => Response::None,
+                }
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Image display
+
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::draw::RgbaImage;
+use crate::event::{self, Handler, VoidMsg};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkAction, TkWindow, Widget};
+
+/// Image scaling mode, used by [`Image`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScaleMode {
+    /// Scale to fit within the available space, preserving aspect ratio
+    #[default]
+    Fit,
+    /// Scale to cover the available space, preserving aspect ratio
+    ///
+    /// Parts of the image outside the widget's rect are not drawn.
+    Fill,
+    /// Scale to exactly the available space, ignoring aspect ratio
+    Stretch,
+    /// Scale by the largest integer factor which fits (at least 1)
+    ///
+    /// This preserves sharp edges, e.g. for pixel art.
+    Integer,
+}
+
+/// A raster image
+///
+/// The image is centred within the widget's rect and scaled according to a
+/// [`ScaleMode`]. The ideal size is that of the image (in pixels); except in
+/// [`ScaleMode::Integer`] mode, the image may be shrunk below this.
+///
+/// Images may be constructed from raw RGBA data via [`RgbaImage::new`], or
+/// from an image decoded by the toolkit (e.g. from a file). An image loaded
+/// in the background may be shown once ready via [`Image::loading`].
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Image {
+    #[core]
+    core: CoreData,
+    image: RefCell<RgbaImage>,
+    // Size used for layout
+    size: Size,
+    mode: ScaleMode,
+    // Slot receiving the image, while loading; shared by clones
+    pending: RefCell<Option<Arc<Mutex<Option<RgbaImage>>>>>,
+}
+
+fn lock(slot: &Mutex<Option<RgbaImage>>) -> MutexGuard<'_, Option<RgbaImage>> {
+    // A panic while locked cannot leave the slot inconsistent
+    slot.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Image {
+    /// Construct, with [`ScaleMode::Fit`]
+    pub fn new(image: RgbaImage) -> Self {
+        Image {
+            core: Default::default(),
+            size: image.size(),
+            image: RefCell::new(image),
+            mode: ScaleMode::default(),
+            pending: RefCell::new(None),
+        }
+    }
+
+    /// Construct a placeholder, to be replaced by an image sent later
+    ///
+    /// The widget is sized as for an image of the given `size` (which need
+    /// not match that of the image sent) and a frame is drawn until the image
+    /// is received via an [`ImageSender`] (see [`Image::sender`]). With
+    /// `kas_wgpu`, an image may be loaded and sent by an `ImageLoader`.
+    ///
+    /// ```
+    /// use kas::draw::RgbaImage;
+    /// use kas::geom::Size;
+    /// use kas::widget::Image;
+    ///
+    /// let image = Image::loading(Size(64, 64));
+    /// let sender = image.sender();
+    /// assert!(image.is_loading());
+    /// std::thread::spawn(move || sender.send(RgbaImage::new(Size(1, 1), vec![0; 4])))
+    ///     .join()
+    ///     .unwrap();
+    /// assert_eq!(image.image().map(|image| image.size()), Some(Size(1, 1)));
+    /// ```
+    pub fn loading(size: Size) -> Self {
+        Image {
+            core: Default::default(),
+            image: RefCell::new(RgbaImage::new(Size::ZERO, vec![])),
+            size,
+            mode: ScaleMode::default(),
+            pending: RefCell::new(Some(Arc::new(Mutex::new(None)))),
+        }
+    }
+
+    /// Get a sender for the image being loaded
+    ///
+    /// If this image is not loading (see [`Image::loading`]), the sender is
+    /// not connected to any widget.
+    pub fn sender(&self) -> ImageSender {
+        ImageSender {
+            slot: match self.pending.borrow().as_ref() {
+                Some(slot) => Arc::downgrade(slot),
+                None => Weak::new(),
+            },
+        }
+    }
+
+    /// True if the image is being loaded and has not yet been received
+    pub fn is_loading(&self) -> bool {
+        self.receive()
+    }
+
+    // Move a received image into place and end loading; true if still loading
+    fn receive(&self) -> bool {
+        let mut pending = self.pending.borrow_mut();
+        if let Some(slot) = pending.as_ref() {
+            match lock(slot).clone() {
+                Some(image) => *self.image.borrow_mut() = image,
+                None => return true,
+            }
+        }
+        *pending = None;
+        false
+    }
+
+    /// Construct from RGBA texels
+    ///
+    /// See [`RgbaImage::new`].
+    pub fn from_rgba(size: Size, data: Vec<u8>) -> Self {
+        Image::new(RgbaImage::new(size, data))
+    }
+
+    /// Set the scaling mode (inline)
+    #[inline]
+    pub fn with_mode(mut self, mode: ScaleMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the scaling mode
+    #[inline]
+    pub fn mode(&self) -> ScaleMode {
+        self.mode
+    }
+
+    /// Set the scaling mode
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_mode(&mut self, tk: &mut dyn TkWindow, mode: ScaleMode) {
+        self.mode = mode;
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Get the image
+    ///
+    /// Returns `None` while loading (see [`Image::is_loading`]). This is cheap
+    /// since image data is shared.
+    pub fn image(&self) -> Option<RgbaImage> {
+        match self.receive() {
+            true => None,
+            false => Some(self.image.borrow().clone()),
+        }
+    }
+
+    /// Replace the image
+    ///
+    /// This also ends loading, if in progress: any image sent later is
+    /// ignored. Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_image(&mut self, tk: &mut dyn TkWindow, image: RgbaImage) {
+        self.size = image.size();
+        *self.image.get_mut() = image;
+        *self.pending.get_mut() = None;
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    // Target rect of `image`, scaled (may exceed the widget's rect)
+    fn image_rect(&self, image: &RgbaImage) -> Rect {
+        let rect = self.core.rect;
+        let size = image.size();
+        if self.mode == ScaleMode::Stretch || size.0 == 0 || size.1 == 0 {
+            return rect;
+        }
+        let x = rect.size.0 as f32 / size.0 as f32;
+        let y = rect.size.1 as f32 / size.1 as f32;
+        let scale = match self.mode {
+            ScaleMode::Fit => x.min(y),
+            ScaleMode::Fill => x.max(y),
+            ScaleMode::Stretch => unreachable!(),
+            ScaleMode::Integer => x.min(y).floor().max(1.0),
+        };
+        let scaled = Size(
+            (size.0 as f32 * scale).round() as u32,
+            (size.1 as f32 * scale).round() as u32,
+        );
+        let offset = Coord(
+            (rect.size.0 as i32 - scaled.0 as i32) / 2,
+            (rect.size.1 as i32 - scaled.1 as i32) / 2,
+        );
+        Rect {
+            pos: rect.pos + offset,
+            size: scaled,
+        }
+    }
+}
+
+impl Widget for Image {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let ideal = axis.extract_size(self.size);
+        match self.mode {
+            ScaleMode::Integer => SizeRules::variable(ideal, ideal),
+            _ => SizeRules::variable(0, ideal),
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let outer = self.core.rect;
+        let image = match self.image() {
+            Some(image) => image,
+            None => {
+                // Placeholder, while loading
+                draw_handle.outer_frame(outer);
+                return;
+            }
+        };
+        let image = &image;
+        let rect = self.image_rect(image);
+        // The image is centred, so fits if no larger than the widget
+        if rect.size.0 <= outer.size.0 && rect.size.1 <= outer.size.1 {
+            draw_handle.image(rect, image);
+        } else {
+            draw_handle.clip_region(outer, Coord::ZERO, &mut |handle| {
+                handle.image(rect, image);
+            });
+        }
+    }
+}
+
+impl Handler for Image {
+    type Msg = VoidMsg;
+}
+
+/// Sends an image to an [`Image`] from any thread
+///
+/// Construct via [`Image::sender`]. Since other threads may not access the
+/// UI, the image is shown on the next redraw of the widget; with `kas_wgpu`,
+/// this may be caused by sending a user event via an event loop proxy, which
+/// redraws all windows.
+#[derive(Clone, Debug)]
+pub struct ImageSender {
+    slot: Weak<Mutex<Option<RgbaImage>>>,
+}
+
+impl ImageSender {
+    /// Send the image
+    ///
+    /// Returns false if the widget no longer exists or is not loading.
+    pub fn send(&self, image: RgbaImage) -> bool {
+        match self.slot.upgrade() {
+            Some(slot) => {
+                *lock(&slot) = Some(image);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Level meter
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::event::{self, Handler, VoidMsg};
+use crate::layout::{AxisInfo, Direction, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// A level shared between threads
+///
+/// A source may be cloned and sent to another thread (e.g. a background audio
+/// task), which sets the level with [`LevelSource::set`]. A [`LevelMeter`]
+/// using this source reads the level each time [`LevelMeter::poll`] is
+/// called, for example from a [`Callback::Repeat`] window callback.
+///
+/// [`Callback::Repeat`]: crate::event::Callback::Repeat
+#[derive(Clone, Debug, Default)]
+pub struct LevelSource(Arc<AtomicU32>);
+
+impl LevelSource {
+    /// Construct, with level zero
+    pub fn new() -> Self {
+        LevelSource::default()
+    }
+
+    /// Set the level
+    #[inline]
+    pub fn set(&self, level: f32) {
+        self.0.store(level.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Get the level
+    #[inline]
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// A level meter
+///
+/// This displays a rapidly changing level in the range `0..=1` (e.g. an audio
+/// signal level), coloured according to zone: normal, warning and danger
+/// (see [`LevelMeter::with_zones`]). The highest recent level is marked until
+/// it has been held for the peak-hold duration.
+///
+/// The level may be set directly via [`LevelMeter::set_value`] or read from a
+/// [`LevelSource`]. Only changes which affect the display cause a redraw.
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct LevelMeter<D: Direction> {
+    #[core]
+    core: CoreData,
+    direction: D,
+    value: f32,
+    peak: Option<(f32, Instant)>,
+    hold: Duration,
+    zones: (f32, f32),
+    source: Option<LevelSource>,
+}
+
+impl<D: Direction + Default> LevelMeter<D> {
+    /// Construct a level meter
+    ///
+    /// By default, the warning zone starts at `0.7`, the danger zone at `0.9`
+    /// and peaks are held for 1.5 seconds.
+    #[inline]
+    pub fn new() -> Self {
+        LevelMeter::new_with_direction(D::default())
+    }
+}
+
+impl<D: Direction> LevelMeter<D> {
+    /// Construct a level meter with the given direction
+    ///
+    /// See [`LevelMeter::new`].
+    pub fn new_with_direction(direction: D) -> Self {
+        LevelMeter {
+            core: Default::default(),
+            direction,
+            value: 0.0,
+            peak: None,
+            hold: Duration::from_millis(1500),
+            zones: (0.7, 0.9),
+            source: None,
+        }
+    }
+
+    /// Set the start of the warning and danger zones
+    ///
+    /// Requires `0 <= warn <= danger <= 1`.
+    #[inline]
+    pub fn with_zones(mut self, warn: f32, danger: f32) -> Self {
+        assert!(0.0 <= warn && warn <= danger && danger <= 1.0);
+        self.zones = (warn, danger);
+        self
+    }
+
+    /// Set the peak-hold duration
+    ///
+    /// A zero duration disables the peak indicator.
+    #[inline]
+    pub fn with_peak_hold(mut self, hold: Duration) -> Self {
+        self.hold = hold;
+        self
+    }
+
+    /// Read levels from the given source
+    ///
+    /// See [`LevelMeter::poll`].
+    #[inline]
+    pub fn with_source(mut self, source: LevelSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Get the current level
+    #[inline]
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Get the held peak level, if any
+    #[inline]
+    pub fn peak(&self) -> Option<f32> {
+        self.peak.map(|p| p.0)
+    }
+
+    /// Set the level
+    ///
+    /// The value is clamped to the range `0..=1`. This also updates the peak
+    /// indicator.
+    pub fn set_value(&mut self, tk: &mut dyn TkWindow, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        let old = (self.value, self.peak());
+        self.value = value;
+
+        if self.hold == Duration::default() {
+            self.peak = None;
+        } else {
+            let now = Instant::now();
+            self.peak = match self.peak {
+                Some((peak, time)) if peak > value && now - time < self.hold => Some((peak, time)),
+                Some(_) | None if value > 0.0 => Some((value, now)),
+                _ => None,
+            };
+        }
+
+        if (self.value, self.peak()) != old {
+            tk.redraw(self.id());
+        }
+    }
+
+    /// Update the level from the source, if any
+    ///
+    /// This should be called regularly when using a [`LevelSource`]; no
+    /// redraw occurs unless the level (or peak) changes.
+    pub fn poll(&mut self, tk: &mut dyn TkWindow) {
+        if let Some(value) = self.source.as_ref().map(|s| s.get()) {
+            self.set_value(tk, value);
+        }
+    }
+}
+
+impl<D: Direction> Widget for LevelMeter<D> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let (thickness, min_len) = size_handle.level_meter();
+        if self.direction.is_vertical() == axis.vertical() {
+            SizeRules::variable(min_len, min_len)
+        } else {
+            SizeRules::fixed(thickness)
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let dir = self.direction.is_vertical();
+        let rect = self.core.rect;
+        draw_handle.level_meter(rect, dir, self.value, self.peak(), self.zones);
+    }
+}
+
+impl<D: Direction> Handler for LevelMeter<D> {
+    type Msg = VoidMsg;
+}
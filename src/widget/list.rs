@@ -5,6 +5,7 @@
 
 //! Dynamic widgets
 
+use std::any::Any;
 use std::iter;
 
 use crate::event::{Address, Event, Handler, Manager, Response};
@@ -76,6 +77,7 @@ pub struct List<D: Direction, W: Widget> {
     widgets: Vec<W>,
     data: layout::DynRowStorage,
     direction: D,
+    uniform: bool,
 }
 
 // We implement this manually, because the derive implementation cannot handle
@@ -103,6 +105,14 @@ impl<D: Direction, W: Widget> WidgetCore for List<D, W> {
     fn as_widget_mut(&mut self) -> &mut dyn Widget {
         self
     }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 
     #[inline]
     fn len(&self) -> usize {
@@ -137,10 +147,11 @@ impl<D: Direction, W: Widget> Widget for List<D, W> {
             axis,
             (self.direction, self.widgets.len()),
             &mut self.data,
-        );
+        )
+        .with_uniform(self.uniform);
         for (n, child) in self.widgets.iter_mut().enumerate() {
             solver.for_child(&mut self.data, n, |axis| {
-                child.size_rules(size_handle, axis)
+                layout::child_rules(child, size_handle, axis)
             });
         }
         solver.finish(&mut self.data, iter::empty(), iter::empty())
@@ -163,7 +174,7 @@ impl<D: Direction, W: Widget> Widget for List<D, W> {
     fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
         let solver = RowPositionSolver::new(self.direction);
         solver.for_children(&self.widgets, draw_handle.target_rect(), |w| {
-            w.draw(draw_handle, ev_mgr)
+            layout::draw_child(w, draw_handle, ev_mgr)
         });
     }
 }
@@ -209,6 +220,7 @@ impl<D: Direction + Default, W: Widget> List<D, W> {
             widgets,
             data: Default::default(),
             direction: Default::default(),
+            uniform: false,
         }
     }
 }
@@ -221,9 +233,38 @@ impl<D: Direction, W: Widget> List<D, W> {
             widgets,
             data: Default::default(),
             direction,
+            uniform: false,
         }
     }
 
+    /// Set uniform mode (inline)
+    ///
+    /// In uniform mode, all children are given equal size along the list's
+    /// direction (that of the largest child).
+    #[inline]
+    pub fn with_uniform(mut self, uniform: bool) -> Self {
+        self.uniform = uniform;
+        self
+    }
+
+    /// Set uniform mode
+    ///
+    /// See [`List::with_uniform`].
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if changed.
+    pub fn set_uniform(&mut self, tk: &mut dyn TkWindow, uniform: bool) {
+        if uniform != self.uniform {
+            self.uniform = uniform;
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+
+    /// True if in uniform mode
+    #[inline]
+    pub fn is_uniform(&self) -> bool {
+        self.uniform
+    }
+
     /// True if there are no child widgets
     pub fn is_empty(&self) -> bool {
         self.widgets.is_empty()
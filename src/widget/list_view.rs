@@ -0,0 +1,390 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Virtualised list view
+
+use std::collections::BTreeSet;
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, PressSource, Response,
+    ScrollDelta,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, ListData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// Selection mode of a [`ListView`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// At most one item is selected; clicking an item selects it
+    #[default]
+    Single,
+    /// Any number of items may be selected; clicking an item toggles it
+    Multiple,
+}
+
+/// Message type of [`ListView`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListViewMsg {
+    /// An item was selected
+    Select(usize),
+    /// An item was deselected (only in [`SelectionMode::Multiple`])
+    Deselect(usize),
+    /// An item was activated (enter key in [`SelectionMode::Single`])
+    Activate(usize),
+}
+
+/// A scrollable list of text items
+///
+/// Items of a [`ListData`] model are shown one per row. Only rows intersecting
+/// the visible region are drawn or hit-tested; thus cost of drawing and event
+/// handling is independent of the number of items, and very long lists remain
+/// fast.
+///
+/// The view scrolls vertically with the mouse wheel. Selection follows the
+/// [`SelectionMode`]; each change is reported with a [`ListViewMsg`]. With key
+/// focus, the arrow, page and home/end keys move the cursor (and, in single
+/// mode, the selection); in multiple mode the enter key toggles the item at
+/// the cursor.
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct ListView<D: ListData + 'static>
+where
+    D::Item: AsRef<str>,
+{
+    #[core]
+    core: CoreData,
+    data: D,
+    mode: SelectionMode,
+    selection: BTreeSet<usize>,
+    cursor: Option<usize>,
+    row_height: u32,
+    margin: u32,
+    offset: u32,
+    max_offset: u32,
+    press: Option<(PressSource, Option<usize>)>,
+}
+
+impl<D: ListData> ListView<D>
+where
+    D::Item: AsRef<str>,
+{
+    /// Construct a view over `data`
+    pub fn new(data: D) -> Self {
+        ListView {
+            core: Default::default(),
+            data,
+            mode: SelectionMode::default(),
+            selection: Default::default(),
+            cursor: None,
+            row_height: 0,
+            margin: 0,
+            offset: 0,
+            max_offset: 0,
+            press: None,
+        }
+    }
+
+    /// Set the selection mode
+    #[inline]
+    pub fn with_mode(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the selection mode
+    ///
+    /// The selection is cleared.
+    pub fn set_mode(&mut self, tk: &mut dyn TkWindow, mode: SelectionMode) {
+        self.mode = mode;
+        self.selection.clear();
+        tk.redraw(self.id());
+    }
+
+    /// Access the data
+    #[inline]
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    /// Replace the data
+    ///
+    /// The selection is cleared and the view scrolled to the top.
+    pub fn set_data(&mut self, tk: &mut dyn TkWindow, data: D) {
+        self.data = data;
+        self.selection.clear();
+        self.cursor = None;
+        self.offset = 0;
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Iterate over selected indices, in increasing order
+    #[inline]
+    pub fn selection(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selection.iter().cloned()
+    }
+
+    /// True if item `index` is selected
+    #[inline]
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.contains(&index)
+    }
+
+    /// Select or deselect an item
+    ///
+    /// In single mode, selecting an item deselects all others.
+    pub fn set_selected(&mut self, tk: &mut dyn TkWindow, index: usize, selected: bool) {
+        if index >= self.data.len() {
+            return;
+        }
+        if selected {
+            if self.mode == SelectionMode::Single {
+                self.selection.clear();
+            }
+            self.selection.insert(index);
+        } else {
+            self.selection.remove(&index);
+        }
+        tk.redraw(self.id());
+    }
+
+    /// Clear the selection
+    pub fn clear_selection(&mut self, tk: &mut dyn TkWindow) {
+        self.selection.clear();
+        tk.redraw(self.id());
+    }
+
+    // Range of visible rows
+    fn visible(&self) -> (usize, usize) {
+        let h = self.row_height;
+        if h == 0 {
+            return (0, 0);
+        }
+        let first = (self.offset / h) as usize;
+        let last = (self.offset + self.core.rect.size.1).div_ceil(h) as usize;
+        let end = last.min(self.data.len());
+        (first.min(end), end)
+    }
+
+    // Row rect, in (unscrolled) content coordinates
+    fn row_rect(&self, index: usize) -> Rect {
+        let y = index as u32 * self.row_height;
+        Rect {
+            pos: self.core.rect.pos + Coord(0, y as i32),
+            size: Size(self.core.rect.size.0, self.row_height),
+        }
+    }
+
+    // Index of the row at `coord`, if any
+    fn row_at(&self, coord: Coord) -> Option<usize> {
+        if !self.core.rect.contains(coord) || self.row_height == 0 {
+            return None;
+        }
+        let y = (coord.1 - self.core.rect.pos.1) as u32 + self.offset;
+        Some((y / self.row_height) as usize).filter(|i| *i < self.data.len())
+    }
+
+    // Set offset, returning true if changed
+    fn set_offset(&mut self, offset: u32) -> bool {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ensure_visible(&mut self, index: usize) {
+        let top = index as u32 * self.row_height;
+        let height = self.core.rect.size.1;
+        if top < self.offset {
+            self.set_offset(top);
+        } else if top + self.row_height > self.offset + height {
+            self.set_offset(top + self.row_height - height.min(top + self.row_height));
+        }
+    }
+
+    // Select (single mode) or toggle (multiple mode) an item
+    fn click(&mut self, tk: &mut dyn TkWindow, index: usize) -> Response<ListViewMsg> {
+        self.cursor = Some(index);
+        tk.redraw(self.id());
+        match self.mode {
+            SelectionMode::Single => {
+                if self.is_selected(index) {
+                    return Response::None;
+                }
+                self.selection.clear();
+                self.selection.insert(index);
+                Response::Msg(ListViewMsg::Select(index))
+            }
+            SelectionMode::Multiple => match self.selection.remove(&index) {
+                true => Response::Msg(ListViewMsg::Deselect(index)),
+                false => {
+                    self.selection.insert(index);
+                    Response::Msg(ListViewMsg::Select(index))
+                }
+            },
+        }
+    }
+
+    fn navigate(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> Response<ListViewMsg> {
+        let len = self.data.len() as i64;
+        if len == 0 {
+            return Response::unhandled_action(Action::NavKey(key));
+        }
+        let page = (self.core.rect.size.1 / self.row_height.max(1)).max(1) as i64;
+        let index = match (key, self.cursor.map(|i| i as i64)) {
+            (NavKey::Home, _) => 0,
+            (NavKey::End, _) => len - 1,
+            (NavKey::Left, _) | (NavKey::Right, _) => {
+                return Response::unhandled_action(Action::NavKey(key));
+            }
+            (_, None) => 0,
+            (NavKey::Up, Some(i)) => i - 1,
+            (NavKey::Down, Some(i)) => i + 1,
+            (NavKey::PageUp, Some(i)) => i - page,
+            (NavKey::PageDown, Some(i)) => i + page,
+        };
+        let index = index.max(0).min(len - 1) as usize;
+        self.ensure_visible(index);
+        match self.mode {
+            SelectionMode::Single => self.click(tk, index),
+            SelectionMode::Multiple => {
+                self.cursor = Some(index);
+                tk.redraw(self.id());
+                Response::None
+            }
+        }
+    }
+}
+
+impl<D: ListData> Widget for ListView<D>
+where
+    D::Item: AsRef<str>,
+{
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.row_height = line_height + 2 * self.margin;
+
+        // We do not measure items: this would be O(n) in the number of items.
+        if !axis.vertical() {
+            SizeRules::variable(6 * line_height, 12 * line_height)
+        } else {
+            let rows = (self.data.len() as u32).clamp(1, 8);
+            SizeRules::variable(self.row_height, rows * self.row_height)
+        }
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let content_height = self.data.len() as u32 * self.row_height;
+        self.max_offset = content_height.saturating_sub(rect.size.1);
+        self.offset = self.offset.min(self.max_offset);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let offset = Coord(0, self.offset as i32);
+        let (start, end) = self.visible();
+        draw_handle.clip_region(self.core.rect, offset, &mut |draw_handle| {
+            for index in start..end {
+                let rect = self.row_rect(index);
+                let hl = HighlightState {
+                    key_focus: highlights.key_focus && self.cursor == Some(index),
+                    ..HighlightState::default()
+                };
+                if self.is_selected(index) {
+                    draw_handle.button(rect, hl);
+                } else if hl.key_focus {
+                    draw_handle.edit_box(rect, hl);
+                }
+
+                let text_rect = Rect {
+                    pos: rect.pos + Coord(self.margin as i32, self.margin as i32),
+                    size: rect.size - Size::uniform(2 * self.margin).min(rect.size),
+                };
+                if let Some(item) = self.data.get(index) {
+                    let props = TextProperties {
+                        class: TextClass::Label,
+                        multi_line: false,
+                        horiz: Align::Begin,
+                        vert: Align::Centre,
+                    };
+                    draw_handle.text(text_rect, item.as_ref(), props);
+                }
+            }
+        });
+    }
+}
+
+impl<D: ListData> Handler for ListView<D>
+where
+    D::Item: AsRef<str>,
+{
+    type Msg = ListViewMsg;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<ListViewMsg> {
+        match action {
+            Action::Activate => match (self.mode, self.cursor) {
+                (SelectionMode::Single, Some(index)) => Response::Msg(ListViewMsg::Activate(index)),
+                (SelectionMode::Multiple, Some(index)) => self.click(tk, index),
+                (_, None) => Response::None,
+            },
+            Action::Scroll(delta) => {
+                let d = match delta {
+                    ScrollDelta::LineDelta(_, y) => -(3.0 * y * self.row_height as f32) as i64,
+                    ScrollDelta::PixelDelta(d) => -d.1 as i64,
+                };
+                let offset = (self.offset as i64 + d).max(0) as u32;
+                if self.set_offset(offset) {
+                    tk.redraw(self.id());
+                    Response::None
+                } else {
+                    Response::unhandled_action(Action::Scroll(delta))
+                }
+            }
+            Action::NavKey(key) => self.navigate(tk, key),
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<ListViewMsg> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.row_at(coord)));
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.row_at(coord),
+                    false => None,
+                };
+                match start {
+                    Some(index) if end == start => self.click(tk, index),
+                    _ => Response::None,
+                }
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Log view
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::event::{self, Action, Handler, NavKey, Response, ScrollDelta, VoidMsg};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Ideal width, in lines
+const IDEAL_WIDTH_LINES: u32 = 40;
+
+/// Ideal height, in lines
+const IDEAL_ROWS: u32 = 8;
+
+// Lines, as a ring buffer
+#[derive(Clone, Debug, Default)]
+struct LogBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    // Number of lines ever pushed; the index of a line is its position in
+    // this sequence
+    pushed: u64,
+}
+
+impl LogBuffer {
+    fn push(&mut self, text: &str) {
+        for line in text.lines() {
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.to_string());
+            self.pushed += 1;
+        }
+    }
+
+    // Index of the oldest retained line
+    fn oldest(&self) -> u64 {
+        self.pushed - self.lines.len() as u64
+    }
+
+    fn get(&self, index: u64) -> Option<&String> {
+        let i = index.checked_sub(self.oldest())?;
+        self.lines.get(i as usize)
+    }
+}
+
+fn lock(buffer: &Mutex<LogBuffer>) -> MutexGuard<'_, LogBuffer> {
+    // A panic while locked cannot leave the buffer inconsistent
+    buffer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A view over a log, optimised for appending lines
+///
+/// Lines are kept in a ring buffer of fixed capacity: once full, each new line
+/// replaces the oldest. Only visible lines are drawn, thus the view remains
+/// fast with a large capacity.
+///
+/// The view follows the end of the log (scrolling as lines are added) unless
+/// the user has scrolled up, in which case the visible lines are kept until
+/// the user scrolls back to the end (or until they are replaced).
+///
+/// Lines may be added from other threads via a [`LogSender`]:
+///
+/// ```
+/// use kas::widget::LogView;
+///
+/// let view = LogView::new(1000);
+/// let sender = view.sender();
+/// std::thread::spawn(move || sender.push("Hello from a worker"))
+///     .join()
+///     .unwrap();
+/// assert_eq!(view.lines(), vec!["Hello from a worker".to_string()]);
+/// ```
+///
+/// Cloning a view copies its lines; senders of the original are not connected
+/// to the clone.
+#[widget]
+#[derive(Widget)]
+pub struct LogView {
+    #[core]
+    core: CoreData,
+    buffer: Arc<Mutex<LogBuffer>>,
+    row_height: u32,
+    margin: u32,
+    // Index of the top line while scrolled up, or None to follow the end
+    anchor: Option<u64>,
+}
+
+impl fmt::Debug for LogView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("LogView")
+            .field("core", &self.core)
+            .field("buffer", &*lock(&self.buffer))
+            .field("anchor", &self.anchor)
+            .finish()
+    }
+}
+
+impl Clone for LogView {
+    fn clone(&self) -> Self {
+        LogView {
+            core: self.core.clone(),
+            buffer: Arc::new(Mutex::new(lock(&self.buffer).clone())),
+            row_height: self.row_height,
+            margin: self.margin,
+            anchor: self.anchor,
+        }
+    }
+}
+
+impl LogView {
+    /// Construct an empty view, retaining at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        let buffer = LogBuffer {
+            capacity,
+            ..Default::default()
+        };
+        LogView {
+            core: Default::default(),
+            buffer: Arc::new(Mutex::new(buffer)),
+            row_height: 0,
+            margin: 0,
+            anchor: None,
+        }
+    }
+
+    /// Get the maximum number of lines retained
+    pub fn capacity(&self) -> usize {
+        lock(&self.buffer).capacity
+    }
+
+    /// Get the number of lines retained
+    pub fn line_count(&self) -> usize {
+        lock(&self.buffer).lines.len()
+    }
+
+    /// Get a copy of the lines retained, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        lock(&self.buffer).lines.iter().cloned().collect()
+    }
+
+    /// Append text
+    ///
+    /// Each line of `text` is added as a separate line of the log.
+    pub fn push<S: AsRef<str>>(&mut self, tk: &mut dyn TkWindow, text: S) {
+        lock(&self.buffer).push(text.as_ref());
+        tk.redraw(self.id());
+    }
+
+    /// Remove all lines
+    pub fn clear(&mut self, tk: &mut dyn TkWindow) {
+        lock(&self.buffer).lines.clear();
+        self.anchor = None;
+        tk.redraw(self.id());
+    }
+
+    /// True if the view follows the end of the log
+    ///
+    /// This is false while the user has scrolled up.
+    #[inline]
+    pub fn is_following(&self) -> bool {
+        self.anchor.is_none()
+    }
+
+    /// Scroll to the end of the log and follow new lines
+    pub fn scroll_to_end(&mut self, tk: &mut dyn TkWindow) {
+        if self.anchor.take().is_some() {
+            tk.redraw(self.id());
+        }
+    }
+
+    /// Construct a sender, for appending lines from another thread
+    pub fn sender(&self) -> LogSender {
+        LogSender {
+            buffer: Arc::downgrade(&self.buffer),
+            wake: None,
+        }
+    }
+
+    // Number of rows fully visible
+    fn rows(&self) -> u64 {
+        match self.row_height {
+            0 => 0,
+            h => (self.core.rect.size.1 / h) as u64,
+        }
+    }
+
+    // Index of the top visible line
+    fn top(&self, buffer: &LogBuffer) -> u64 {
+        let end = buffer.pushed.saturating_sub(self.rows());
+        let end = end.max(buffer.oldest());
+        match self.anchor {
+            None => end,
+            Some(top) => top.max(buffer.oldest()).min(end),
+        }
+    }
+
+    // Scroll by `d` lines, returning true if the view changed
+    fn scroll_by(&mut self, d: i64) -> bool {
+        let buffer = lock(&self.buffer);
+        let top = self.top(&buffer);
+        let end = buffer.pushed.saturating_sub(self.rows());
+        let end = end.max(buffer.oldest());
+        let new_top = (top as i64 + d).max(buffer.oldest() as i64) as u64;
+        let anchor = match new_top >= end {
+            true => None,
+            false => Some(new_top),
+        };
+        drop(buffer);
+        let changed = anchor != self.anchor;
+        self.anchor = anchor;
+        changed
+    }
+}
+
+impl Widget for LogView {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.row_height = line_height + self.margin;
+        if !axis.vertical() {
+            let min = 4 * line_height + 2 * self.margin;
+            SizeRules::variable(min, IDEAL_WIDTH_LINES * line_height)
+        } else {
+            let extra = self.margin;
+            SizeRules::variable(
+                self.row_height + extra,
+                IDEAL_ROWS * self.row_height + extra,
+            )
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let rect = self.core.rect;
+        let props = || TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        let buffer = lock(&self.buffer);
+        let top = self.top(&buffer);
+        draw_handle.clip_region(rect, Coord::ZERO, &mut |draw_handle| {
+            // Include a partially visible row, if any
+            for i in 0..=self.rows() {
+                let line = match buffer.get(top + i) {
+                    Some(line) => line,
+                    None => break,
+                };
+                let y = self.margin / 2 + i as u32 * self.row_height;
+                let row = Rect {
+                    pos: rect.pos + Coord(self.margin as i32, y as i32),
+                    size: Size(rect.size.0.saturating_sub(2 * self.margin), self.row_height),
+                };
+                draw_handle.text(row, line, props());
+            }
+        });
+    }
+}
+
+impl Handler for LogView {
+    type Msg = VoidMsg;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
+        let page = self.rows().saturating_sub(1).max(1) as i64;
+        let d = match action {
+            Action::Scroll(ScrollDelta::LineDelta(_, y)) => -(3.0 * y).round() as i64,
+            Action::Scroll(ScrollDelta::PixelDelta(d)) => match self.row_height {
+                0 => 0,
+                h => -(d.1 / h as i32) as i64,
+            },
+            Action::NavKey(NavKey::Up) => -1,
+            Action::NavKey(NavKey::Down) => 1,
+            Action::NavKey(NavKey::PageUp) => -page,
+            Action::NavKey(NavKey::PageDown) => page,
+            Action::NavKey(NavKey::Home) => i64::MIN / 2,
+            Action::NavKey(NavKey::End) => i64::MAX / 2,
+            a => return Response::unhandled_action(a),
+        };
+        if self.scroll_by(d) {
+            tk.redraw(self.id());
+            Response::None
+        } else {
+            Response::unhandled_action(action)
+        }
+    }
+}
+
+/// Appends lines to a [`LogView`] from any thread
+///
+/// Construct via [`LogView::sender`]. Since other threads may not access the
+/// UI, new lines are shown on the next redraw of the view. To cause this,
+/// supply a wake-up function via [`LogSender::with_wake`]. With `kas_wgpu`,
+/// this typically sends a user event via an event loop proxy, which redraws
+/// all windows.
+pub struct LogSender {
+    buffer: Weak<Mutex<LogBuffer>>,
+    wake: Option<Box<dyn Fn() + Send>>,
+}
+
+impl fmt::Debug for LogSender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("LogSender")
+            .field("wake", &self.wake.is_some())
+            .finish()
+    }
+}
+
+impl LogSender {
+    /// Set a wake-up function
+    ///
+    /// This is called after each [`LogSender::push`].
+    pub fn with_wake<W: Fn() + Send + 'static>(mut self, wake: W) -> Self {
+        self.wake = Some(Box::new(wake));
+        self
+    }
+
+    /// Append text
+    ///
+    /// Each line of `text` is added as a separate line of the log. Returns
+    /// false (without calling the wake-up function) if the view no longer
+    /// exists.
+    pub fn push<S: AsRef<str>>(&self, text: S) -> bool {
+        let buffer = match self.buffer.upgrade() {
+            Some(buffer) => buffer,
+            None => return false,
+        };
+        lock(&buffer).push(text.as_ref());
+        if let Some(wake) = self.wake.as_ref() {
+            wake();
+        }
+        true
+    }
+}
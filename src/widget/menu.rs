@@ -0,0 +1,622 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Menus
+
+use std::fmt::Debug;
+
+use crate::event::{self, Action, Address, Event, Handler, HighlightState, NavKey, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+// Split a label such as "&File" into its display text and mnemonic
+//
+// A mnemonic is marked by a preceding `&`; `&&` is a literal `&`.
+fn split_mnemonic(label: &str) -> (String, Option<char>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => match chars.next() {
+                Some('&') => text.push('&'),
+                Some(c) => {
+                    if mnemonic.is_none() {
+                        mnemonic = c.to_lowercase().next();
+                    }
+                    text.push(c);
+                }
+                None => (),
+            },
+            c => text.push(c),
+        }
+    }
+    (text, mnemonic)
+}
+
+/// An activatable item within a [`Menu`]
+///
+/// The label may contain a mnemonic, marked by a preceding `&` (e.g.
+/// `"&Open"`); use `&&` for a literal `&`. While the menu is open, typing the
+/// mnemonic activates the item.
+///
+/// On activation, a clone of the item's message is returned from the
+/// [`MenuBar`]'s event handler.
+#[derive(Clone, Debug)]
+pub struct MenuItem<M> {
+    label: String,
+    mnemonic: Option<char>,
+    msg: M,
+}
+
+impl<M> MenuItem<M> {
+    /// Construct an item with a given `label` and `msg`
+    pub fn new<S: AsRef<str>>(label: S, msg: M) -> Self {
+        let (label, mnemonic) = split_mnemonic(label.as_ref());
+        MenuItem {
+            label,
+            mnemonic,
+            msg,
+        }
+    }
+
+    /// Get the label (without mnemonic marker)
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Get the mnemonic, if any (in lower case)
+    #[inline]
+    pub fn mnemonic(&self) -> Option<char> {
+        self.mnemonic
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Entry<M> {
+    Item(MenuItem<M>),
+    Menu(Menu<M>),
+}
+
+impl<M> Entry<M> {
+    fn label(&self) -> &str {
+        match self {
+            Entry::Item(item) => &item.label,
+            Entry::Menu(menu) => &menu.label,
+        }
+    }
+
+    fn mnemonic(&self) -> Option<char> {
+        match self {
+            Entry::Item(item) => item.mnemonic,
+            Entry::Menu(menu) => menu.mnemonic,
+        }
+    }
+}
+
+/// A drop-down menu or submenu
+///
+/// A menu has a label (which may contain a mnemonic, as for [`MenuItem`]) and
+/// a list of entries, each of which is either an item or a submenu. Menus are
+/// shown by a [`MenuBar`].
+#[derive(Clone, Debug)]
+pub struct Menu<M> {
+    label: String,
+    mnemonic: Option<char>,
+    entries: Vec<Entry<M>>,
+    // Pop-up rect (including frame) and position and size of the first entry
+    rect: Rect,
+    entry_pos: Coord,
+    entry_size: Size,
+}
+
+impl<M> Menu<M> {
+    /// Construct an empty menu with a given `label`
+    pub fn new<S: AsRef<str>>(label: S) -> Self {
+        let (label, mnemonic) = split_mnemonic(label.as_ref());
+        Menu {
+            label,
+            mnemonic,
+            entries: vec![],
+            rect: Rect::default(),
+            entry_pos: Coord::ZERO,
+            entry_size: Size::ZERO,
+        }
+    }
+
+    /// Add an item (inline)
+    pub fn with_item(mut self, item: MenuItem<M>) -> Self {
+        self.push_item(item);
+        self
+    }
+
+    /// Add a submenu (inline)
+    pub fn with_menu(mut self, menu: Menu<M>) -> Self {
+        self.push_menu(menu);
+        self
+    }
+
+    /// Add an item
+    pub fn push_item(&mut self, item: MenuItem<M>) {
+        self.entries.push(Entry::Item(item));
+    }
+
+    /// Add a submenu
+    pub fn push_menu(&mut self, menu: Menu<M>) {
+        self.entries.push(Entry::Menu(menu));
+    }
+
+    /// Get the label (without mnemonic marker)
+    #[inline]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Get the mnemonic, if any (in lower case)
+    #[inline]
+    pub fn mnemonic(&self) -> Option<char> {
+        self.mnemonic
+    }
+
+    /// Number of entries (items and submenus)
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if there are no entries
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Lay out the pop-up at `pos`, and all submenus beside their entries
+    fn set_rects(&mut self, size_handle: &mut dyn SizeHandle, pos: Coord, entry_height: u32) {
+        let (frame0, frame1) = size_handle.edit_surround();
+        let axis = AxisInfo::new(false, None);
+        let mut width = 0;
+        for entry in &self.entries {
+            let rules = size_handle.text_bound(entry.label(), TextClass::Label, false, axis);
+            width = width.max(rules.min_size());
+        }
+        // Reserve space for text margins (see `draw`) and submenu indicator
+        width += 2 * (entry_height / 4) + entry_height;
+
+        self.entry_pos = pos + frame0;
+        self.entry_size = Size(width, entry_height);
+        let inner = Size(width, entry_height * self.entries.len() as u32);
+        self.rect = Rect {
+            pos,
+            size: inner + frame0 + frame1,
+        };
+
+        let sub_x = self.rect.pos.0 + self.rect.size.0 as i32;
+        for index in 0..self.entries.len() {
+            let y = self.entry_rect(index).pos.1 - frame0.1 as i32;
+            if let Entry::Menu(menu) = &mut self.entries[index] {
+                menu.set_rects(size_handle, Coord(sub_x, y), entry_height);
+            }
+        }
+    }
+
+    fn entry_rect(&self, index: usize) -> Rect {
+        let y = (index as u32 * self.entry_size.1) as i32;
+        Rect {
+            pos: self.entry_pos + Coord(0, y),
+            size: self.entry_size,
+        }
+    }
+
+    fn entry_at(&self, coord: Coord) -> Option<usize> {
+        (0..self.entries.len()).find(|index| self.entry_rect(*index).contains(coord))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, highlight: Option<usize>) {
+        draw_handle.edit_box(self.rect, HighlightState::default());
+        let margin = Coord((self.entry_size.1 as i32) / 4, 0);
+        let props = |horiz| TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz,
+            vert: Align::Centre,
+        };
+        for (index, entry) in self.entries.iter().enumerate() {
+            let rect = self.entry_rect(index);
+            if highlight == Some(index) {
+                let hl = HighlightState {
+                    hover: true,
+                    ..HighlightState::default()
+                };
+                draw_handle.button(rect, hl);
+            }
+            let text_rect = Rect {
+                pos: rect.pos + margin,
+                size: Size(rect.size.0.saturating_sub(2 * margin.0 as u32), rect.size.1),
+            };
+            draw_handle.text(text_rect, entry.label(), props(Align::Begin));
+            if let Entry::Menu(_) = entry {
+                draw_handle.text(text_rect, "›", props(Align::End));
+            }
+        }
+    }
+}
+
+// Result of a hit test
+enum Hit {
+    Title(usize),
+    Entry(usize, usize),
+    Popup,
+    None,
+}
+
+/// A horizontal bar of drop-down menus
+///
+/// Clicking a menu title opens its menu as a pop-up, drawn over other widgets
+/// (see [`ManagerMut::set_popup`]). While a menu is open, moving the cursor
+/// over another title opens that menu instead, hovering a submenu opens it, and
+/// releasing the mouse button over an item activates it; clicking outside
+/// the menus closes them, as does the Escape key.
+///
+/// With key focus, the menu bar may also be used with the keyboard: the arrow
+/// keys move between titles and entries (opening submenus to the right) and
+/// Enter opens a menu or activates an entry. While a menu is open, typing the
+/// mnemonic of an entry (see [`MenuItem`]) activates it.
+///
+/// On activation of an item, a clone of its message is returned from the
+/// event handler and all menus are closed.
+///
+/// [`ManagerMut::set_popup`]: crate::event::ManagerMut::set_popup
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct MenuBar<M: Clone + Debug + 'static> {
+    #[core]
+    core: CoreData,
+    menus: Vec<Menu<M>>,
+    titles: Vec<Rect>,
+    // Path of open menus: a top-level index, then indices of open submenus
+    open: Vec<usize>,
+    // Highlighted entry of the deepest open menu, or title when closed
+    cursor: Option<usize>,
+}
+
+impl<M: Clone + Debug + 'static> Widget for MenuBar<M> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let margin = size_handle.inner_margin();
+        if axis.vertical() {
+            let height = size_handle.line_height(TextClass::Label) + 2 * margin.1;
+            return SizeRules::fixed(height);
+        }
+        let mut rules = SizeRules::EMPTY;
+        for menu in &self.menus {
+            rules += SizeRules::fixed(4 * margin.0);
+            rules += size_handle.text_bound(&menu.label, TextClass::Label, false, axis);
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        let margin = size_handle.inner_margin();
+        let entry_height = size_handle.line_height(TextClass::Label) + 2 * margin.1;
+        let axis = AxisInfo::new(false, None);
+        let bottom = rect.pos.1 + rect.size.1 as i32;
+        let mut x = rect.pos.0;
+        self.titles.clear();
+        for menu in &mut self.menus {
+            let rules = size_handle.text_bound(&menu.label, TextClass::Label, false, axis);
+            let width = rules.min_size() + 4 * margin.0;
+            self.titles.push(Rect {
+                pos: Coord(x, rect.pos.1),
+                size: Size(width, rect.size.1),
+            });
+            menu.set_rects(size_handle, Coord(x, bottom), entry_height);
+            x += width as i32;
+        }
+        self.core_data_mut().rect = rect;
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let open = ev_mgr.popup() == Some(self.id()) && !self.open.is_empty();
+        let props = || TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+        for (index, rect) in self.titles.iter().enumerate() {
+            let is_open = open && self.open[0] == index;
+            let cursor = !open && self.cursor == Some(index);
+            if is_open || (cursor && (highlights.hover || highlights.key_focus)) {
+                let hl = HighlightState {
+                    hover: cursor && highlights.hover,
+                    depress: is_open,
+                    key_focus: cursor && highlights.key_focus,
+                    char_focus: false,
+                    error: false,
+                };
+                draw_handle.button(*rect, hl);
+            }
+            draw_handle.text(*rect, &self.menus[index].label, props());
+        }
+
+        if open {
+            let deepest = self.open.len() - 1;
+            for depth in 0..=deepest {
+                let menu = self.menu_at(depth);
+                let highlight = match depth == deepest {
+                    true => self.cursor,
+                    false => Some(self.open[depth + 1]),
+                };
+                draw_handle.overlay(menu.rect, &mut |draw_handle| {
+                    menu.draw(draw_handle, highlight);
+                });
+            }
+        }
+    }
+}
+
+impl<M: Clone + Debug + 'static> MenuBar<M> {
+    /// Construct a menu bar over the given menus
+    pub fn new(menus: Vec<Menu<M>>) -> Self {
+        MenuBar {
+            core: Default::default(),
+            menus,
+            titles: vec![],
+            open: vec![],
+            cursor: None,
+        }
+    }
+
+    /// Add a menu (inline)
+    pub fn with_menu(mut self, menu: Menu<M>) -> Self {
+        self.menus.push(menu);
+        self
+    }
+
+    /// Add a menu
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push_menu(&mut self, tk: &mut dyn TkWindow, menu: Menu<M>) {
+        self.menus.push(menu);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Number of (top-level) menus
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.menus.len()
+    }
+
+    /// True if there are no menus
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.menus.is_empty()
+    }
+
+    // Get the open menu at the given depth
+    fn menu_at(&self, depth: usize) -> &Menu<M> {
+        let mut menu = &self.menus[self.open[0]];
+        for index in &self.open[1..=depth] {
+            menu = match &menu.entries[*index] {
+                Entry::Menu(m) => m,
+                Entry::Item(_) => unreachable!(),
+            };
+        }
+        menu
+    }
+
+    fn find(&self, coord: Coord) -> Hit {
+        if let Some(index) = self.titles.iter().position(|rect| rect.contains(coord)) {
+            return Hit::Title(index);
+        }
+        for depth in (0..self.open.len()).rev() {
+            let menu = self.menu_at(depth);
+            if let Some(index) = menu.entry_at(coord) {
+                return Hit::Entry(depth, index);
+            } else if menu.rect.contains(coord) {
+                return Hit::Popup;
+            }
+        }
+        Hit::None
+    }
+
+    // Open a top-level menu, optionally highlighting its first entry
+    fn open_menu(&mut self, tk: &mut dyn TkWindow, index: usize, highlight: bool) {
+        self.open = vec![index];
+        self.cursor = match highlight && !self.menus[index].is_empty() {
+            true => Some(0),
+            false => None,
+        };
+        let id = self.id();
+        tk.data_mut().set_popup(Some(id));
+    }
+
+    fn close(&mut self, tk: &mut dyn TkWindow) {
+        self.cursor = self.open.first().cloned();
+        self.open.clear();
+        if tk.data().popup() == Some(self.id()) {
+            tk.data_mut().set_popup(None);
+        }
+    }
+
+    // Activate an entry: emit an item's message or open a submenu
+    fn activate(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        depth: usize,
+        index: usize,
+        highlight: bool,
+    ) -> Response<M> {
+        let msg = match &self.menu_at(depth).entries[index] {
+            Entry::Item(item) => Some(item.msg.clone()),
+            Entry::Menu(menu) => {
+                let empty = menu.is_empty();
+                self.open.truncate(depth + 1);
+                self.open.push(index);
+                self.cursor = match highlight && !empty {
+                    true => Some(0),
+                    false => None,
+                };
+                None
+            }
+        };
+        match msg {
+            Some(msg) => {
+                self.close(tk);
+                Response::Msg(msg)
+            }
+            None => {
+                tk.redraw(self.id());
+                Response::None
+            }
+        }
+    }
+
+    fn handle_nav(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> Response<M> {
+        let n = self.menus.len();
+        if n == 0 {
+            return Response::Unhandled(Event::Action(Action::NavKey(key)));
+        }
+        if self.open.is_empty() {
+            let cursor = self.cursor.unwrap_or(0);
+            self.cursor = Some(match key {
+                NavKey::Left => (cursor + n - 1) % n,
+                NavKey::Right => (cursor + 1) % n,
+                NavKey::Home => 0,
+                NavKey::End => n - 1,
+                NavKey::Down => {
+                    self.open_menu(tk, cursor, true);
+                    return Response::None;
+                }
+                key => return Response::Unhandled(Event::Action(Action::NavKey(key))),
+            });
+            tk.redraw(self.id());
+            return Response::None;
+        }
+
+        let deepest = self.open.len() - 1;
+        let len = self.menu_at(deepest).len();
+        match key {
+            NavKey::Up | NavKey::Down | NavKey::Home | NavKey::End if len > 0 => {
+                self.cursor = Some(match (key, self.cursor) {
+                    (NavKey::Up, Some(c)) => (c + len - 1) % len,
+                    (NavKey::Down, Some(c)) => (c + 1) % len,
+                    (NavKey::Up, None) | (NavKey::End, _) => len - 1,
+                    _ => 0,
+                });
+            }
+            NavKey::Right => match self.cursor {
+                Some(c) => match self.menu_at(deepest).entries[c] {
+                    Entry::Menu(_) => return self.activate(tk, deepest, c, true),
+                    Entry::Item(_) => self.open_menu(tk, (self.open[0] + 1) % n, true),
+                },
+                None => self.open_menu(tk, (self.open[0] + 1) % n, true),
+            },
+            NavKey::Left if deepest > 0 => self.cursor = self.open.pop(),
+            NavKey::Left => self.open_menu(tk, (self.open[0] + n - 1) % n, true),
+            _ => (),
+        }
+        tk.redraw(self.id());
+        Response::None
+    }
+
+    fn handle_char(&mut self, tk: &mut dyn TkWindow, c: char) -> Response<M> {
+        let c = c.to_lowercase().next();
+        if self.open.is_empty() || c.is_none() {
+            return Response::None;
+        }
+        let deepest = self.open.len() - 1;
+        let menu = self.menu_at(deepest);
+        if let Some(index) = menu.entries.iter().position(|e| e.mnemonic() == c) {
+            return self.activate(tk, deepest, index, true);
+        }
+        if deepest == 0 {
+            if let Some(index) = self.menus.iter().position(|m| m.mnemonic == c) {
+                self.open_menu(tk, index, true);
+            }
+        }
+        Response::None
+    }
+}
+
+impl<M: Clone + Debug + 'static> Handler for MenuBar<M> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<M> {
+        if !self.open.is_empty() && tk.data().popup() != Some(self.id()) {
+            // The pop-up was closed externally (e.g. via Escape)
+            self.open.clear();
+        }
+
+        match event {
+            Event::Action(Action::Activate) => {
+                if self.open.is_empty() {
+                    let index = self.cursor.unwrap_or(0);
+                    if index < self.menus.len() {
+                        self.open_menu(tk, index, true);
+                    }
+                } else if let Some(index) = self.cursor {
+                    let deepest = self.open.len() - 1;
+                    return self.activate(tk, deepest, index, true);
+                }
+                Response::None
+            }
+            Event::Action(Action::NavKey(key)) => self.handle_nav(tk, key),
+            Event::Action(Action::ReceivedCharacter(c)) => self.handle_char(tk, c),
+            Event::Action(a) => Response::unhandled_action(a),
+            Event::Identify => {
+                let coord = match addr {
+                    Address::Coord(coord) => coord,
+                    Address::Id(_) => tk.data().last_mouse_coord(),
+                };
+                let (open, cursor) = (self.open.clone(), self.cursor);
+                match self.find(coord) {
+                    Hit::Title(index) if self.open.is_empty() => self.cursor = Some(index),
+                    Hit::Title(index) if self.open[0] != index => {
+                        self.open = vec![index];
+                        self.cursor = None;
+                    }
+                    Hit::Entry(depth, index) => {
+                        self.open.truncate(depth + 1);
+                        self.cursor = Some(index);
+                        if let Entry::Menu(_) = self.menu_at(depth).entries[index] {
+                            self.open.push(index);
+                            self.cursor = None;
+                        }
+                    }
+                    _ => (),
+                }
+                if self.open != open || self.cursor != cursor {
+                    tk.redraw(self.id());
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                match self.find(coord) {
+                    Hit::Title(index) if self.open.first() == Some(&index) => self.close(tk),
+                    Hit::Title(index) => self.open_menu(tk, index, false),
+                    Hit::Entry(..) | Hit::Popup => (),
+                    Hit::None => self.close(tk),
+                }
+                Response::None
+            }
+            Event::PressEnd { source, coord, .. } if source.is_primary() => {
+                match self.find(coord) {
+                    Hit::Entry(depth, index) => self.activate(tk, depth, index, false),
+                    _ => Response::None,
+                }
+            }
+            e => Response::Unhandled(e),
+        }
+    }
+}
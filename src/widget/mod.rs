@@ -7,21 +7,132 @@
 //!
 //! KAS provides these common widget types for convenience.
 //! All these widgets can be implemented in user-code.
+//!
+//! ## Static construction
+//!
+//! Some widgets may be constructed in `const` contexts, borrowing static
+//! text; these do not allocate until their content is changed:
+//!
+//! -   [`Label::new_static`]
+//! -   [`TextButton::new_static`]
+//! -   [`CheckBox::new_static`]
+//! -   [`Hyperlink::new_static`]
+//! -   [`Separator::new_with_direction`]
+//!
+//! Custom widgets may do likewise using [`CoreData::new`].
+//!
+//! ```
+//! use kas::layout::Horizontal;
+//! use kas::widget::{Label, Separator};
+//!
+//! const TITLE: Label = Label::new_static("Title");
+//! const RULE: Separator<Horizontal> = Separator::new_with_direction(Horizontal);
+//!
+//! let (title, rule) = (TITLE, RULE);
+//! ```
+//!
+//! [`CoreData::new`]: crate::CoreData::new
 
+mod builder;
 mod button;
+mod calendar;
+mod canvas;
 mod checkbox;
+mod code_edit;
+mod colour_picker;
+mod csv_view;
+mod dial;
 mod dialog;
+mod diff_view;
+mod dock;
+mod expander;
+mod find_bar;
+mod flow_box;
+mod group_box;
+mod hyperlink;
+mod icon_view;
+mod image;
+mod level_meter;
 mod list;
+mod list_view;
+mod log_view;
+mod menu;
+mod overlay;
+mod plot;
+mod popover;
+mod property_grid;
+mod radio;
+mod range_slider;
+mod rating;
+mod rich_label;
 mod scroll;
 mod scrollbar;
+mod search_box;
+mod segmented;
+mod separator;
+mod shortcut_edit;
+mod side_nav;
+mod slider;
+mod spin_button;
+mod spinner;
+mod splitter;
+mod status_bar;
+mod switch;
+mod tabbed_stack;
+mod table;
 mod text;
+mod toolbar;
 mod window;
+mod zoom;
 
+pub use builder::Builder;
 pub use button::TextButton;
-pub use checkbox::CheckBox;
-pub use dialog::MessageBox;
+pub use calendar::{Calendar, Date, DatePicker};
+pub use canvas::{Canvas, Painter};
+pub use checkbox::{CheckBox, OnTristate};
+pub use code_edit::{CodeEdit, Highlighter, PlainText, SimpleHighlighter};
+pub use colour_picker::ColourPicker;
+pub use csv_view::{CsvData, CsvView};
+pub use dial::Dial;
+pub use dialog::{ButtonOrder, Dialog, DialogButtonBar, DialogButtons, DialogResponse, MessageBox};
+pub use diff_view::{Diff, DiffMode, DiffOp, DiffView};
+pub use dock::{DockEdge, DockLayout, DockNode};
+pub use expander::Expander;
+pub use find_bar::FindBar;
+pub use flow_box::{BoxFlowBox, FlowBox};
+pub use group_box::GroupBox;
+pub use hyperlink::Hyperlink;
+pub use icon_view::{IconView, ThumbnailProvider};
+pub use image::{Image, ImageSender, ScaleMode};
+pub use level_meter::{LevelMeter, LevelSource};
 pub use list::{BoxColumn, BoxList, BoxRow, Column, List, Row};
+pub use list_view::{ListView, ListViewMsg, SelectionMode};
+pub use log_view::{LogSender, LogView};
+pub use menu::{Menu, MenuBar, MenuItem};
+pub use overlay::{BoxOverlay, Overlay};
+pub use plot::{Plot, Series, SeriesKind};
+pub use popover::Popover;
+pub use property_grid::{PropertyGrid, PropertySource, PropertyValue};
+pub use radio::{RadioButton, RadioGroup};
+pub use range_slider::RangeSlider;
+pub use rating::Rating;
+pub use rich_label::RichLabel;
 pub use scroll::ScrollRegion;
 pub use scrollbar::ScrollBar;
+pub use search_box::SearchBox;
+pub use segmented::SegmentedControl;
+pub use separator::Separator;
+pub use shortcut_edit::ShortcutEdit;
+pub use side_nav::SideNav;
+pub use slider::{Slider, SliderType};
+pub use spin_button::{SpinButton, SpinType};
+pub use spinner::Spinner;
+pub use splitter::{ColumnSplitter, RowSplitter, Splitter};
+pub use status_bar::StatusBar;
+pub use switch::{Switch, ToggleSwitch};
+pub use tabbed_stack::{TabPosition, TabbedStack};
+pub use table::{Table, TableMsg};
 pub use text::{EditBox, Label};
+pub use toolbar::Toolbar;
 pub use window::Window;
+pub use zoom::Zoom;
@@ -0,0 +1,247 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Overlay: stacked children
+
+use std::any::Any;
+use std::iter;
+
+use crate::event::{Address, Event, Handler, Manager, Response};
+use crate::layout::{self, Anchor, AxisInfo, RulesSetter, RulesSolver, SizeRules};
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+use kas::geom::Rect;
+
+/// An overlay of boxed widgets
+///
+/// This is parameterised over handler message type.
+///
+/// See documentation of [`Overlay`] type.
+pub type BoxOverlay<M> = Overlay<Box<dyn Handler<Msg = M>>>;
+
+/// A stack of widgets, each positioned by an [`Anchor`]
+///
+/// All children share the overlay's area: later children are drawn over
+/// (and receive mouse events before) earlier ones. Typically the first child
+/// fills the area ([`Anchor::FILL`]) while others float at a corner or centre,
+/// for example a badge over an icon or a floating action button over a view.
+///
+/// ```
+/// use kas::geom::Coord;
+/// use kas::layout::Anchor;
+/// use kas::widget::{Label, Overlay};
+///
+/// let overlay = Overlay::new()
+///     .with_child(Anchor::FILL, Label::from("Inbox"))
+///     .with_child(Anchor::TOP_RIGHT.with_offset(Coord(-2, 2)), Label::from("3"));
+/// assert_eq!(overlay.len(), 2);
+/// ```
+///
+/// Children with offsets towards the outside of an edge overhang the overlay;
+/// the parent must ensure there is space to draw these.
+///
+/// Configuring, resizing, drawing and event handling are all O(n) in the
+/// number of children.
+#[derive(Clone, Default, Debug)]
+pub struct Overlay<W: Widget> {
+    core: CoreData,
+    widgets: Vec<(Anchor, W)>,
+    data: layout::OverlayStorage,
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget> WidgetCore for Overlay<W> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "Overlay"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.widgets.get(index).map(|w| w.1.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.widgets.get_mut(index).map(|w| w.1.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.widgets {
+            child.1.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.widgets {
+            child.1.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Widget for Overlay<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut solver = layout::OverlaySolver::new(axis, self.widgets.len(), &mut self.data);
+        for (n, (anchor, child)) in self.widgets.iter_mut().enumerate() {
+            solver.for_child(&mut self.data, (n, *anchor), |axis| {
+                layout::child_rules(child, size_handle, axis)
+            });
+        }
+        solver.finish(&mut self.data, iter::empty(), iter::empty())
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let mut setter = layout::OverlaySetter::new(rect, self.widgets.len(), &mut self.data);
+
+        for (n, (anchor, child)) in self.widgets.iter_mut().enumerate() {
+            child.set_rect(size_handle, setter.child_rect((n, *anchor)));
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        for (_, child) in &self.widgets {
+            layout::draw_child(child, draw_handle, ev_mgr);
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for Overlay<W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match addr {
+            Address::Id(id) => {
+                for (_, child) in &mut self.widgets {
+                    if id <= child.id() {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+                debug_assert!(id == self.id(), "Handler::handle: bad WidgetId");
+            }
+            Address::Coord(coord) => {
+                // The top-most child takes precedence
+                for (_, child) in self.widgets.iter_mut().rev() {
+                    if child.rect().contains(coord) {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+            }
+        }
+        Response::Unhandled(event)
+    }
+}
+
+impl<W: Widget> Overlay<W> {
+    /// Construct an empty overlay
+    pub fn new() -> Self {
+        Overlay {
+            core: Default::default(),
+            widgets: vec![],
+            data: Default::default(),
+        }
+    }
+
+    /// Add a child on top (inline)
+    pub fn with_child(mut self, anchor: Anchor, widget: W) -> Self {
+        self.widgets.push((anchor, widget));
+        self
+    }
+
+    /// True if there are no child widgets
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Returns the number of child widgets
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// Get the anchor of the child at `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn anchor(&self, index: usize) -> Anchor {
+        self.widgets[index].0
+    }
+
+    /// Set the anchor of the child at `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if changed.
+    pub fn set_anchor(&mut self, tk: &mut dyn TkWindow, index: usize, anchor: Anchor) {
+        if anchor != self.widgets[index].0 {
+            self.widgets[index].0 = anchor;
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+
+    /// Add a child on top
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push(&mut self, tk: &mut dyn TkWindow, anchor: Anchor, widget: W) {
+        self.widgets.push((anchor, widget));
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Remove the top child
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if any widget is
+    /// removed.
+    pub fn pop(&mut self, tk: &mut dyn TkWindow) -> Option<W> {
+        if !self.widgets.is_empty() {
+            tk.send_action(TkAction::Reconfigure);
+        }
+        self.widgets.pop().map(|w| w.1)
+    }
+
+    /// Removes the child widget at position `index`
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn remove(&mut self, tk: &mut dyn TkWindow, index: usize) -> W {
+        let r = self.widgets.remove(index);
+        tk.send_action(TkAction::Reconfigure);
+        r.1
+    }
+}
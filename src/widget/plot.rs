@@ -0,0 +1,554 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Plot widget
+
+use super::Painter;
+use crate::draw::{Colour, Vec2};
+use crate::event::{self, Address, Event, Handler, Manager, Response, VoidMsg};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+// Number of ticks aimed for on each axis
+const TICKS: f32 = 5.0;
+// Width of bars relative to the spacing between points
+const BAR_FILL: f32 = 0.8;
+// Sample tick label, used to reserve space
+const TICK_SAMPLE: &str = "-000.00";
+
+/// Type of a [`Series`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeriesKind {
+    /// Points joined by straight lines
+    Line,
+    /// A vertical bar from zero to each point
+    Bar,
+}
+
+/// A data series for a [`Plot`]
+#[derive(Clone, Debug)]
+pub struct Series {
+    /// Name, shown in the hover readout
+    pub name: String,
+    /// Type of series
+    pub kind: SeriesKind,
+    /// Colour of lines or bars
+    pub colour: Colour,
+    /// Data points, `(x, y)`, in order of increasing `x`
+    pub points: Vec<(f32, f32)>,
+}
+
+impl Series {
+    /// Construct a line series
+    pub fn line<S: Into<String>>(name: S, colour: Colour, points: Vec<(f32, f32)>) -> Self {
+        let name = name.into();
+        let kind = SeriesKind::Line;
+        Series {
+            name,
+            kind,
+            colour,
+            points,
+        }
+    }
+
+    /// Construct a bar series
+    pub fn bar<S: Into<String>>(name: S, colour: Colour, points: Vec<(f32, f32)>) -> Self {
+        let name = name.into();
+        let kind = SeriesKind::Bar;
+        Series {
+            name,
+            kind,
+            colour,
+            points,
+        }
+    }
+}
+
+// Range of values on an axis with its tick step
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Scale {
+    min: f32,
+    max: f32,
+    step: f32,
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Scale::auto(0.0, 1.0)
+    }
+}
+
+impl Scale {
+    // Find a scale covering `min..max` with "nice" ticks
+    fn auto(min: f32, max: f32) -> Self {
+        let (min, max) = match (min.is_finite() && max.is_finite(), max - min) {
+            (false, _) => (0.0, 1.0),
+            (true, 0.0) => (min - 1.0, max + 1.0),
+            _ => (min, max),
+        };
+        let step = nice_step((max - min) / TICKS);
+        Scale {
+            min: (min / step).floor() * step,
+            max: (max / step).ceil() * step,
+            step,
+        }
+    }
+
+    // Use a fixed range, with "nice" ticks
+    fn fixed(min: f32, max: f32) -> Self {
+        let step = nice_step((max - min).abs().max(f32::EPSILON) / TICKS);
+        Scale { min, max, step }
+    }
+
+    // Iterate over tick values
+    fn ticks(self) -> impl Iterator<Item = f32> {
+        let first = (self.min / self.step).ceil() as i64;
+        let last = (self.max / self.step).floor() as i64;
+        (first..=last).map(move |i| i as f32 * self.step)
+    }
+
+    // Format a tick label
+    fn label(self, value: f32) -> String {
+        let decimals = (-self.step.log10().floor()).max(0.0) as usize;
+        // Avoid displaying "-0"
+        let value = if value.abs() < 0.5 * self.step {
+            0.0
+        } else {
+            value
+        };
+        format!("{:.*}", decimals, value)
+    }
+
+    // Map `value` to the fraction `0..=1` of the axis
+    fn frac(self, value: f32) -> f32 {
+        (value - self.min) / (self.max - self.min)
+    }
+}
+
+// Round `step` up to 1, 2 or 5 times a power of 10
+fn nice_step(step: f32) -> f32 {
+    let mag = 10f32.powf(step.log10().floor());
+    let norm = step / mag;
+    let nice = if norm <= 1.0 {
+        1.0
+    } else if norm <= 2.0 {
+        2.0
+    } else if norm <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * mag
+}
+
+fn label_props(horiz: Align, vert: Align) -> TextProperties {
+    TextProperties {
+        class: TextClass::Label,
+        multi_line: false,
+        horiz,
+        vert,
+    }
+}
+
+/// A chart of line and bar series
+///
+/// Each [`Series`] is drawn over a pair of axes with tick labels and optional
+/// axis labels. By default the axes are scaled to fit all data (including
+/// zero where there are bar series); use [`Plot::with_x_range`] and
+/// [`Plot::with_y_range`] to fix the ranges instead.
+///
+/// While the mouse hovers near a data point, the point is highlighted and its
+/// series name and value are shown in a readout. Use [`Plot::hovered`] to find
+/// which point this is.
+///
+/// Bar series share the space around each `x` value, in order. For best
+/// results they should use the same, evenly-spaced, `x` values.
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Plot {
+    #[core]
+    core: CoreData,
+    series: Vec<Series>,
+    x_label: String,
+    y_label: String,
+    x_range: Option<(f32, f32)>,
+    y_range: Option<(f32, f32)>,
+    x_scale: Scale,
+    y_scale: Scale,
+    tick_size: Size,
+    area: Rect,
+    hover: Option<(usize, usize)>,
+}
+
+impl Plot {
+    /// Construct an empty plot
+    pub fn new() -> Self {
+        Plot::default()
+    }
+
+    /// Add a series (chain style)
+    pub fn with_series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self.update_scales();
+        self
+    }
+
+    /// Set the label of the x axis (chain style)
+    pub fn with_x_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.x_label = label.into();
+        self
+    }
+
+    /// Set the label of the y axis (chain style)
+    pub fn with_y_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.y_label = label.into();
+        self
+    }
+
+    /// Fix the range of the x axis (chain style)
+    pub fn with_x_range(mut self, min: f32, max: f32) -> Self {
+        self.x_range = Some((min, max));
+        self.update_scales();
+        self
+    }
+
+    /// Fix the range of the y axis (chain style)
+    pub fn with_y_range(mut self, min: f32, max: f32) -> Self {
+        self.y_range = Some((min, max));
+        self.update_scales();
+        self
+    }
+
+    /// Access the list of series
+    #[inline]
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Append a series
+    ///
+    /// The plot is redrawn, rescaling axes as necessary.
+    pub fn push_series(&mut self, tk: &mut dyn TkWindow, series: Series) {
+        self.series.push(series);
+        self.changed(tk);
+    }
+
+    /// Replace the points of series `index`
+    ///
+    /// The plot is redrawn, rescaling axes as necessary.
+    pub fn set_points(&mut self, tk: &mut dyn TkWindow, index: usize, points: Vec<(f32, f32)>) {
+        self.series[index].points = points;
+        self.changed(tk);
+    }
+
+    /// Remove all series
+    pub fn clear(&mut self, tk: &mut dyn TkWindow) {
+        self.series.clear();
+        self.changed(tk);
+    }
+
+    /// Get the hovered data point, if any
+    ///
+    /// Returns `(series_index, point_index)`.
+    #[inline]
+    pub fn hovered(&self) -> Option<(usize, usize)> {
+        self.hover
+    }
+
+    fn changed(&mut self, tk: &mut dyn TkWindow) {
+        self.hover = None;
+        self.update_scales();
+        tk.redraw(self.id());
+    }
+
+    fn update_scales(&mut self) {
+        let (mut x0, mut x1) = (f32::INFINITY, f32::NEG_INFINITY);
+        let (mut y0, mut y1) = (f32::INFINITY, f32::NEG_INFINITY);
+        for series in &self.series {
+            for &(x, y) in &series.points {
+                x0 = x0.min(x);
+                x1 = x1.max(x);
+                y0 = y0.min(y);
+                y1 = y1.max(y);
+            }
+        }
+        if self.bar_count() > 0 {
+            // Bars extend half the spacing either side and start from zero
+            let half = 0.5 * self.bar_spacing();
+            x0 -= half;
+            x1 += half;
+            y0 = y0.min(0.0);
+            y1 = y1.max(0.0);
+        }
+        self.x_scale = match self.x_range {
+            Some((min, max)) => Scale::fixed(min, max),
+            None => Scale::auto(x0, x1),
+        };
+        self.y_scale = match self.y_range {
+            Some((min, max)) => Scale::fixed(min, max),
+            None => Scale::auto(y0, y1),
+        };
+    }
+
+    fn axis_width(&self) -> u32 {
+        (self.tick_size.1 / 16).max(1)
+    }
+
+    // Number of lines of text used by axis labels
+    fn axis_label_lines(&self) -> u32 {
+        (!self.x_label.is_empty()) as u32 + (!self.y_label.is_empty()) as u32
+    }
+
+    fn bar_count(&self) -> usize {
+        let iter = self.series.iter();
+        iter.filter(|s| s.kind == SeriesKind::Bar).count()
+    }
+
+    // Smallest spacing between consecutive x values of bar series
+    fn bar_spacing(&self) -> f32 {
+        let mut spacing = f32::INFINITY;
+        for series in &self.series {
+            if series.kind == SeriesKind::Bar {
+                for pair in series.points.windows(2) {
+                    let d = pair[1].0 - pair[0].0;
+                    if d > 0.0 {
+                        spacing = spacing.min(d);
+                    }
+                }
+            }
+        }
+        if spacing.is_finite() {
+            spacing
+        } else {
+            1.0
+        }
+    }
+
+    // Position of a datum, relative to the plot area
+    fn map(&self, x: f32, y: f32) -> Vec2 {
+        let size = Vec2::from(self.area.size);
+        let x = self.x_scale.frac(x) * size.0;
+        let y = (1.0 - self.y_scale.frac(y)) * size.1;
+        Vec2(x, y)
+    }
+
+    // Horizontal offset and width of bars of series `index`, in data units
+    fn bar_geometry(&self, index: usize) -> (f32, f32) {
+        let count = self.bar_count();
+        let n = (self.series[..index].iter())
+            .filter(|s| s.kind == SeriesKind::Bar)
+            .count();
+        let width = BAR_FILL * self.bar_spacing() / count as f32;
+        let offset = (n as f32 - 0.5 * (count - 1) as f32) * width;
+        (offset, width)
+    }
+
+    // Position of the marker of a datum, relative to the plot area
+    fn marker(&self, series: usize, (x, y): (f32, f32)) -> Vec2 {
+        match self.series[series].kind {
+            SeriesKind::Line => self.map(x, y),
+            SeriesKind::Bar => self.map(x + self.bar_geometry(series).0, y),
+        }
+    }
+
+    // Find the data point nearest `coord` (within a tolerance)
+    fn point_at(&self, coord: Coord) -> Option<(usize, usize)> {
+        let pos = Vec2::from(coord - self.area.pos);
+        let tolerance = self.tick_size.1 as f32;
+        let mut best = None;
+        let mut best_dist = tolerance * tolerance;
+        for (s, series) in self.series.iter().enumerate() {
+            for (i, point) in series.points.iter().enumerate() {
+                let d = self.marker(s, *point) - pos;
+                let dist = d.0 * d.0 + d.1 * d.1;
+                if dist <= best_dist {
+                    best = Some((s, i));
+                    best_dist = dist;
+                }
+            }
+        }
+        best
+    }
+
+    fn draw_series(&self, painter: &mut Painter) {
+        let line_width = (self.tick_size.1 as f32 / 8.0).max(1.0);
+        for (s, series) in self.series.iter().enumerate() {
+            match series.kind {
+                SeriesKind::Line => {
+                    let points: Vec<Vec2> =
+                        (series.points.iter()).map(|p| self.map(p.0, p.1)).collect();
+                    painter.path(&points, line_width, series.colour);
+                }
+                SeriesKind::Bar => {
+                    let (offset, width) = self.bar_geometry(s);
+                    let base = self.y_scale.min.max(0.0).min(self.y_scale.max);
+                    for &(x, y) in &series.points {
+                        let a = self.map(x + offset - 0.5 * width, y);
+                        let b = self.map(x + offset + 0.5 * width, base);
+                        let pos = Coord(a.0.min(b.0) as i32, a.1.min(b.1) as i32);
+                        let d = b - a;
+                        let size = Size(d.0.abs() as u32, d.1.abs() as u32);
+                        painter.fill_rect(Rect { pos, size }, series.colour);
+                    }
+                }
+            }
+        }
+
+        if let Some((s, i)) = self.hover {
+            let series = &self.series[s];
+            let centre = self.marker(s, series.points[i]);
+            let half = (2.0 * line_width).round();
+            let pos = Coord((centre.0 - half) as i32, (centre.1 - half) as i32);
+            let size = Size::uniform(2 * half as u32);
+            painter.fill_rect(Rect { pos, size }, series.colour);
+        }
+    }
+}
+
+impl Widget for Plot {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let tick = size_handle.text_bound(TICK_SAMPLE, TextClass::Label, false, axis);
+        let tick = tick.ideal_size();
+        let (minor, major) = match axis.vertical() {
+            false => {
+                self.tick_size.0 = tick;
+                (tick * 3 / 2, 4 * tick)
+            }
+            true => {
+                self.tick_size.1 = tick;
+                let labels = 2 + self.axis_label_lines();
+                (tick * labels, 12 * tick)
+            }
+        };
+        SizeRules::variable(minor + major / 2, minor + major)
+    }
+
+    fn set_rect(&mut self, _: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let tick = self.tick_size;
+        // Left: y tick labels. Right: half an x tick label.
+        // Top: half a y tick label or the y-axis label (right of the ticks).
+        // Bottom: x tick labels and the x-axis label.
+        let left = tick.0;
+        let right = tick.0 / 2;
+        let top = match self.y_label.is_empty() {
+            true => tick.1 / 2,
+            false => tick.1,
+        };
+        let bottom = match self.x_label.is_empty() {
+            true => tick.1,
+            false => 2 * tick.1,
+        } + self.axis_width();
+        let pos = rect.pos + Coord(left as i32, top as i32);
+        let size = Size(
+            rect.size.0.saturating_sub(left + right),
+            rect.size.1.saturating_sub(top + bottom),
+        );
+        self.area = Rect { pos, size };
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let area = self.area;
+        let tick = self.tick_size;
+
+        // Axes
+        let thickness = self.axis_width();
+        let y_axis = Rect {
+            pos: area.pos - Coord(thickness as i32, 0),
+            size: Size(thickness, area.size.1),
+        };
+        draw_handle.separator(y_axis, true);
+        let x_axis = Rect {
+            pos: area.pos + Coord(0, area.size.1 as i32),
+            size: Size(area.size.0, thickness),
+        };
+        draw_handle.separator(x_axis, false);
+
+        // Tick labels
+        for y in self.y_scale.ticks() {
+            let centre = area.pos.1 + self.map(0.0, y).1 as i32;
+            let rect = Rect {
+                pos: Coord(self.core.rect.pos.0, centre - tick.1 as i32 / 2),
+                size: Size(tick.0 - thickness, tick.1),
+            };
+            let props = label_props(Align::End, Align::Centre);
+            draw_handle.text(rect, &self.y_scale.label(y), props);
+        }
+        for x in self.x_scale.ticks() {
+            let centre = area.pos.0 + self.map(x, 0.0).0 as i32;
+            let rect = Rect {
+                pos: Coord(centre - tick.0 as i32 / 2, x_axis.pos.1 + thickness as i32),
+                size: Size(tick.0, tick.1),
+            };
+            let props = label_props(Align::Centre, Align::Begin);
+            draw_handle.text(rect, &self.x_scale.label(x), props);
+        }
+
+        // Axis labels
+        if !self.x_label.is_empty() {
+            let rect = Rect {
+                pos: Coord(
+                    area.pos.0,
+                    area.pos.1 + (area.size.1 + thickness + tick.1) as i32,
+                ),
+                size: Size(area.size.0, tick.1),
+            };
+            let props = label_props(Align::Centre, Align::Begin);
+            draw_handle.text(rect, &self.x_label, props);
+        }
+        if !self.y_label.is_empty() {
+            let rect = Rect {
+                pos: Coord(area.pos.0, self.core.rect.pos.1),
+                size: Size(area.size.0, tick.1),
+            };
+            let props = label_props(Align::Begin, Align::Begin);
+            draw_handle.text(rect, &self.y_label, props);
+        }
+
+        // Data
+        draw_handle.clip_region(area, Coord::ZERO, &mut |handle| {
+            let mut painter = Painter::new(handle, area);
+            self.draw_series(&mut painter);
+        });
+
+        // Hover readout
+        let hover = self
+            .hover
+            .filter(|_| ev_mgr.highlight_state(self.id()).hover);
+        if let Some((s, i)) = hover {
+            let series = &self.series[s];
+            let (x, y) = series.points[i];
+            let text = format!("{}: ({}, {})", series.name, x, y);
+            let marker = self.marker(s, (x, y));
+            let pos = area.pos + Coord(marker.0 as i32, marker.1 as i32);
+            draw_handle.tooltip(pos, &text);
+        }
+    }
+}
+
+impl Handler for Plot {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        match event {
+            Event::CursorMove { coord } => {
+                let hover = self.point_at(coord);
+                if hover != self.hover {
+                    self.hover = hover;
+                    tk.redraw(self.id());
+                }
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Pop-over anchored to a trigger widget
+
+use crate::event::{self, Address, Event, Handler, HighlightState, Manager, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// A pop-over, showing content anchored to a trigger widget
+///
+/// The `trigger` (e.g. a [`TextButton`]) is shown in place. Any message from
+/// it opens (or closes) the pop-over, which shows `content` in an overlay
+/// below the trigger. Where there is not enough room below, the content is
+/// shown above the trigger instead; it is also moved left as necessary to fit
+/// within the window.
+///
+/// While open, the pop-over captures mouse input. It is dismissed by a click
+/// outside the content or by the escape key. Messages from the content are
+/// returned to the parent; the pop-over remains open.
+///
+/// [`TextButton`]: crate::widget::TextButton
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Popover<T: Widget, C: Widget> {
+    #[core]
+    core: CoreData,
+    #[widget]
+    trigger: T,
+    #[widget]
+    content: C,
+    content_size: Size,
+    open: bool,
+}
+
+impl<T: Widget, C: Widget> Popover<T, C> {
+    /// Construct, with the given `trigger` and `content`
+    pub fn new(trigger: T, content: C) -> Self {
+        Popover {
+            core: Default::default(),
+            trigger,
+            content,
+            content_size: Size::ZERO,
+            open: false,
+        }
+    }
+
+    /// Access the trigger widget
+    #[inline]
+    pub fn trigger(&self) -> &T {
+        &self.trigger
+    }
+
+    /// Access the content widget
+    #[inline]
+    pub fn content(&self) -> &C {
+        &self.content
+    }
+
+    /// Access the content widget mutably
+    #[inline]
+    pub fn content_mut(&mut self) -> &mut C {
+        &mut self.content
+    }
+
+    /// True while open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open or close the pop-over
+    pub fn set_open(&mut self, tk: &mut dyn TkWindow, open: bool) {
+        self.open = open;
+        let id = self.id();
+        if open {
+            tk.data_mut().set_popup(Some(id));
+        } else if tk.data().popup() == Some(id) {
+            tk.data_mut().set_popup(None);
+        }
+    }
+
+    // Translation from the content's layout position (below the trigger) to
+    // where it is shown, given the window size
+    fn offset(&self, window: Size) -> Coord {
+        let trigger = self.trigger.rect();
+        let content = self.content.rect();
+        let mut offset = Coord::ZERO;
+        let bottom = content.pos.1 + content.size.1 as i32;
+        if bottom > window.1 as i32 && trigger.pos.1 >= content.size.1 as i32 {
+            // Flip to above the trigger
+            offset.1 = -(trigger.size.1 as i32 + content.size.1 as i32);
+        }
+        let right = content.pos.0 + content.size.0 as i32;
+        if right > window.0 as i32 {
+            offset.0 = (window.0 as i32 - right).max(-content.pos.0);
+        }
+        offset
+    }
+}
+
+impl<T: Widget, C: Widget> Widget for Popover<T, C> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        // The content is not constrained by our size
+        let axis_info = AxisInfo::new(axis.vertical(), None);
+        let content = layout::child_rules(&mut self.content, size_handle, axis_info);
+        if !axis.vertical() {
+            self.content_size.0 = content.ideal_size();
+        } else {
+            self.content_size.1 = content.ideal_size();
+        }
+        layout::child_rules(&mut self.trigger, size_handle, axis)
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.trigger.set_rect(size_handle, rect);
+        // The content is positioned below the trigger; see offset
+        let pos = rect.pos + Coord(0, rect.size.1 as i32);
+        let size = self.content_size;
+        self.content.set_rect(size_handle, Rect { pos, size });
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        layout::draw_child(&self.trigger, draw_handle, ev_mgr);
+        if self.open && ev_mgr.popup() == Some(self.id()) {
+            let offset = self.offset(ev_mgr.window_size());
+            let mut rect = self.content.rect();
+            rect.pos = rect.pos + offset;
+            draw_handle.overlay(rect, &mut |draw_handle| {
+                draw_handle.edit_box(rect, HighlightState::default());
+                let offset = Coord(-offset.0, -offset.1);
+                draw_handle.clip_region(rect, offset, &mut |draw_handle| {
+                    layout::draw_child(&self.content, draw_handle, ev_mgr)
+                });
+            });
+        }
+    }
+}
+
+// Translate the coordinates of an event by `d`
+fn translate(event: Event, d: Coord) -> Event {
+    match event {
+        Event::PressStart { source, coord } => Event::PressStart {
+            source,
+            coord: coord + d,
+        },
+        Event::PressMove {
+            source,
+            coord,
+            delta,
+        } => Event::PressMove {
+            source,
+            coord: coord + d,
+            delta,
+        },
+        Event::PressEnd {
+            source,
+            start_id,
+            end_id,
+            coord,
+        } => Event::PressEnd {
+            source,
+            start_id,
+            end_id,
+            coord: coord + d,
+        },
+        Event::CursorMove { coord } => Event::CursorMove { coord: coord + d },
+        Event::DragHover { payload, coord } => Event::DragHover {
+            payload,
+            coord: coord + d,
+        },
+        Event::Drop { payload, coord } => Event::Drop {
+            payload,
+            coord: coord + d,
+        },
+        e => e,
+    }
+}
+
+impl<M, T: Widget + Handler, C: Widget + Handler<Msg = M>> Handler for Popover<T, C> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<M> {
+        if self.open && tk.data().popup() != Some(self.id()) {
+            // The pop-up was closed externally (e.g. via Escape)
+            self.open = false;
+        }
+
+        let offset = self.offset(tk.data().window_size());
+        let mut shown = self.content.rect();
+        shown.pos = shown.pos + offset;
+        // Content coordinates are screen coordinates plus d
+        let d = Coord(-offset.0, -offset.1);
+
+        let to_trigger = match addr {
+            Address::Id(id) => id <= self.trigger.id(),
+            Address::Coord(coord) => !self.open && self.trigger.rect().contains(coord),
+        };
+        if to_trigger {
+            return match self.trigger.handle(tk, addr, event) {
+                Response::Msg(_) => {
+                    let open = !self.open;
+                    self.set_open(tk, open);
+                    Response::None
+                }
+                Response::None => Response::None,
+                Response::Identify(id) => Response::Identify(id),
+                Response::Unhandled(e) => Response::Unhandled(e),
+            };
+        }
+        match addr {
+            Address::Id(id) if id <= self.content.id() => {
+                return self.content.handle(tk, addr, translate(event, d));
+            }
+            _ => (),
+        }
+
+        // Remaining events are addressed to us (while open, all mouse input)
+        let coord = match (addr, &event) {
+            (_, Event::PressStart { coord, .. }) => *coord,
+            (Address::Coord(coord), _) => coord,
+            (Address::Id(_), _) => tk.data().last_mouse_coord(),
+        };
+        if self.open && shown.contains(coord) {
+            let addr = Address::Coord(coord + d);
+            return self.content.handle(tk, addr, translate(event, d));
+        }
+        match event {
+            Event::Identify if self.open => Response::Identify(self.id()),
+            Event::PressStart { source, .. } if self.open && source.is_primary() => {
+                // Dismiss on a click outside the content
+                self.set_open(tk, false);
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
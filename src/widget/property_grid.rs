@@ -0,0 +1,465 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Property grid
+
+use std::any::Any;
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
+
+use crate::draw::Colour;
+use crate::event::{Address, Event, Handler, Manager, Response};
+use crate::geom::Rect;
+use crate::layout::{self, AxisInfo, GridChildInfo, Margins, RulesSetter, RulesSolver, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::widget::{CheckBox, EditBox, Label, SegmentedControl};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// The value of a property, as shown by a [`PropertyGrid`]
+///
+/// The variant determines which editor is used.
+#[derive(Clone, Debug)]
+pub enum PropertyValue {
+    /// A boolean, edited with a check box
+    Bool(bool),
+    /// An integer, edited with a text box
+    Int(i64),
+    /// A floating-point number, edited with a text box
+    Float(f64),
+    /// Some text, edited with a text box
+    Text(String),
+    /// One of a set of named options (see [`PropertySource::choices`]),
+    /// edited with a [`SegmentedControl`]
+    Choice(usize),
+    /// A colour, shown as a swatch and edited as a hex code (`#rrggbb`)
+    Colour(Colour),
+}
+
+/// A set of named properties, as viewed by a [`PropertyGrid`]
+///
+/// Properties are identified by index, from `0` to `len() - 1`.
+///
+/// ```
+/// use kas::widget::{PropertyGrid, PropertySource, PropertyValue};
+///
+/// #[derive(Clone, Debug)]
+/// struct Settings {
+///     visible: bool,
+///     width: i64,
+/// }
+///
+/// impl PropertySource for Settings {
+///     fn len(&self) -> usize {
+///         2
+///     }
+///     fn name(&self, index: usize) -> String {
+///         ["Visible", "Width"][index].to_string()
+///     }
+///     fn get(&self, index: usize) -> PropertyValue {
+///         match index {
+///             0 => PropertyValue::Bool(self.visible),
+///             _ => PropertyValue::Int(self.width),
+///         }
+///     }
+///     fn set(&mut self, index: usize, value: PropertyValue) {
+///         match (index, value) {
+///             (0, PropertyValue::Bool(v)) => self.visible = v,
+///             (1, PropertyValue::Int(v)) => self.width = v.max(0),
+///             _ => (),
+///         }
+///     }
+/// }
+///
+/// let grid = PropertyGrid::new(Settings { visible: true, width: 80 });
+/// assert_eq!(grid.source().width, 80);
+/// ```
+pub trait PropertySource: fmt::Debug {
+    /// Number of properties
+    fn len(&self) -> usize;
+
+    /// True if there are no properties
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Display name of property `index`
+    fn name(&self, index: usize) -> String;
+
+    /// Current value of property `index`
+    fn get(&self, index: usize) -> PropertyValue;
+
+    /// Names of the options of property `index`
+    ///
+    /// This is only used for [`PropertyValue::Choice`] properties. The
+    /// default implementation returns no options.
+    fn choices(&self, index: usize) -> Vec<String> {
+        let _ = index;
+        Vec::new()
+    }
+
+    /// Set property `index`
+    ///
+    /// This is called after the user edits a value. The value always has the
+    /// same variant as that returned by [`PropertySource::get`]; it may be
+    /// adjusted or ignored (e.g. if out of range), in which case the editor is
+    /// not updated until [`PropertyGrid::refresh`] is called.
+    fn set(&mut self, index: usize, value: PropertyValue);
+}
+
+/// Editor message: `None` on invalid input
+type EditorMsg = Option<PropertyValue>;
+
+/// A grid of name / value rows for viewing and editing properties
+///
+/// Each property of a [`PropertySource`] is shown on its own row: its name on
+/// the left and an editor on the right, chosen according to the value's type
+/// (see [`PropertyValue`]). Text-based editors apply values when the enter
+/// key is pressed; invalid input is reverted.
+///
+/// After each edit, the new value is passed to [`PropertySource::set`] and the
+/// property's index is returned as a message.
+#[derive(Clone, Debug)]
+pub struct PropertyGrid<S: PropertySource> {
+    core: CoreData,
+    source: S,
+    labels: Vec<Label>,
+    editors: Vec<Box<dyn Handler<Msg = EditorMsg>>>,
+    data: layout::DynGridStorage,
+}
+
+impl<S: PropertySource> PropertyGrid<S> {
+    /// Construct a grid over the given properties
+    pub fn new(source: S) -> Self {
+        let mut grid = PropertyGrid {
+            core: Default::default(),
+            source,
+            labels: vec![],
+            editors: vec![],
+            data: Default::default(),
+        };
+        grid.build();
+        grid
+    }
+
+    /// Access the properties
+    #[inline]
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Replace the properties
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_source(&mut self, tk: &mut dyn TkWindow, source: S) {
+        self.source = source;
+        self.refresh(tk);
+    }
+
+    /// Update all names and editors from the source
+    ///
+    /// This should be called after the source's properties are changed by
+    /// other means than editing.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn refresh(&mut self, tk: &mut dyn TkWindow) {
+        self.build();
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    fn build(&mut self) {
+        let source = &self.source;
+        let len = source.len();
+        self.labels = (0..len).map(|i| Label::new(source.name(i))).collect();
+        self.editors = (0..len).map(|i| make_editor(source, i)).collect();
+    }
+
+    fn cell(col: usize, row: usize) -> GridChildInfo {
+        GridChildInfo {
+            col,
+            col_end: col + 1,
+            col_span_index: usize::MAX,
+            row,
+            row_end: row + 1,
+            row_span_index: usize::MAX,
+        }
+    }
+}
+
+fn make_editor<S: PropertySource>(source: &S, index: usize) -> Box<dyn Handler<Msg = EditorMsg>> {
+    match source.get(index) {
+        PropertyValue::Bool(v) => Box::new(
+            CheckBox::new("")
+                .state(v)
+                .on_toggle(|v| Some(PropertyValue::Bool(v))),
+        ),
+        PropertyValue::Int(v) => Box::new(
+            EditBox::new(v.to_string())
+                .on_activate(|s| s.trim().parse().ok().map(PropertyValue::Int)),
+        ),
+        PropertyValue::Float(v) => Box::new(
+            EditBox::new(v.to_string())
+                .on_activate(|s| s.trim().parse().ok().map(PropertyValue::Float)),
+        ),
+        PropertyValue::Text(v) => {
+            Box::new(EditBox::new(v).on_activate(|s| Some(PropertyValue::Text(s.to_string()))))
+        }
+        PropertyValue::Choice(v) => Box::new(
+            SegmentedControl::new(source.choices(index))
+                .with_selected(v)
+                .on_select(|v| Some(PropertyValue::Choice(v))),
+        ),
+        PropertyValue::Colour(v) => Box::new(ColourEditor::new(v)),
+    }
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<S: PropertySource + Clone + 'static> WidgetCore for PropertyGrid<S> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "PropertyGrid"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        2 * self.labels.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        match index % 2 {
+            0 => self.labels.get(index / 2).map(|w| w.as_widget()),
+            _ => self.editors.get(index / 2).map(|w| w.as_widget()),
+        }
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        match index % 2 {
+            0 => self.labels.get_mut(index / 2).map(|w| w.as_widget_mut()),
+            _ => self.editors.get_mut(index / 2).map(|w| w.as_widget_mut()),
+        }
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for (label, editor) in self.labels.iter().zip(self.editors.iter()) {
+            label.walk(f);
+            editor.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for (label, editor) in self.labels.iter_mut().zip(self.editors.iter_mut()) {
+            label.walk_mut(f);
+            editor.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+type Solver =
+    layout::GridSolver<Vec<u32>, Vec<u32>, Vec<SizeRules>, Vec<SizeRules>, layout::DynGridStorage>;
+type Setter = layout::GridSetter<Vec<u32>, Vec<u32>, layout::DynGridStorage>;
+
+impl<S: PropertySource + Clone + 'static> Widget for PropertyGrid<S> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let rows = self.labels.len();
+        let mut solver = Solver::new(axis, (2, rows), &mut self.data);
+        let children = self.labels.iter_mut().zip(self.editors.iter_mut());
+        for (row, (label, editor)) in children.enumerate() {
+            solver.for_child(&mut self.data, Self::cell(0, row), |axis| {
+                layout::child_rules(label, size_handle, axis)
+            });
+            solver.for_child(&mut self.data, Self::cell(1, row), |axis| {
+                layout::child_rules(editor, size_handle, axis)
+            });
+        }
+        solver.finish(&mut self.data, iter::empty(), iter::empty())
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let rows = self.labels.len();
+        let mut setter = Setter::new(rect, Margins::ZERO, (2, rows), &mut self.data);
+        let children = self.labels.iter_mut().zip(self.editors.iter_mut());
+        for (row, (label, editor)) in children.enumerate() {
+            label.set_rect(size_handle, setter.child_rect(Self::cell(0, row)));
+            editor.set_rect(size_handle, setter.child_rect(Self::cell(1, row)));
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let target = draw_handle.target_rect();
+        for (label, editor) in self.labels.iter().zip(self.editors.iter()) {
+            if label.rect().intersection(&target).is_some() {
+                layout::draw_child(label, draw_handle, ev_mgr);
+            }
+            if editor.rect().intersection(&target).is_some() {
+                layout::draw_child(editor, draw_handle, ev_mgr);
+            }
+        }
+    }
+}
+
+impl<S: PropertySource + Clone + 'static> Handler for PropertyGrid<S> {
+    type Msg = usize;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<usize> {
+        let mut index = None;
+        match addr {
+            Address::Id(id) => {
+                for (i, (label, editor)) in self.labels.iter_mut().zip(&self.editors).enumerate() {
+                    if id <= label.id() {
+                        let r = label.handle(tk, addr, event);
+                        return r.try_into().unwrap_or_else(|_| unreachable!());
+                    } else if id <= editor.id() {
+                        index = Some(i);
+                        break;
+                    }
+                }
+            }
+            Address::Coord(coord) => {
+                index = self.editors.iter().position(|w| w.rect().contains(coord));
+            }
+        }
+
+        let index = match index {
+            Some(index) => index,
+            None => return Manager::handle_generic(self, tk, event),
+        };
+        match self.editors[index].handle(tk, addr, event) {
+            Response::Msg(Some(value)) => {
+                self.source.set(index, value);
+                Response::Msg(index)
+            }
+            Response::Msg(None) => {
+                // Invalid input: revert
+                self.editors[index] = make_editor(&self.source, index);
+                tk.send_action(TkAction::Reconfigure);
+                Response::None
+            }
+            r => r.try_into().unwrap_or_else(|_| unreachable!()),
+        }
+    }
+}
+
+/// Parse a colour from a hex code: `#rrggbb` (the `#` is optional)
+pub(crate) fn parse_colour(s: &str) -> Option<Colour> {
+    let s = s.trim();
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if s.len() != 6 {
+        return None;
+    }
+    let c = |i: usize| {
+        let v = u8::from_str_radix(s.get(i..i + 2)?, 16).ok()?;
+        Some(v as f32 / 255.0)
+    };
+    Some(Colour::new(c(0)?, c(2)?, c(4)?))
+}
+
+pub(crate) fn format_colour(c: Colour) -> String {
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(c.r), byte(c.g), byte(c.b))
+}
+
+/// A colour sample
+///
+/// This never sends a message; the type `M` is for convenience of the parent.
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub(crate) struct Swatch<M: Clone + fmt::Debug + 'static> {
+    #[core]
+    core: CoreData,
+    colour: Colour,
+    _msg: PhantomData<M>,
+}
+
+impl<M: Clone + fmt::Debug> Swatch<M> {
+    pub(crate) fn new(colour: Colour) -> Self {
+        Swatch {
+            core: Default::default(),
+            colour,
+            _msg: PhantomData,
+        }
+    }
+
+    pub(crate) fn set_colour(&mut self, tk: &mut dyn TkWindow, colour: Colour) {
+        self.colour = colour;
+        tk.redraw(self.id());
+    }
+}
+
+impl<M: Clone + fmt::Debug> Widget for Swatch<M> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        SizeRules::fixed(axis.extract_size(size_handle.swatch()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &Manager) {
+        draw_handle.swatch(self.core.rect, self.colour);
+    }
+}
+
+impl<M: Clone + fmt::Debug> Handler for Swatch<M> {
+    type Msg = M;
+}
+
+/// Colour editor: a swatch and a hex code
+#[widget(layout = horizontal)]
+#[handler(msg = EditorMsg)]
+#[derive(Clone, Debug, Widget)]
+struct ColourEditor {
+    #[core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as kas::LayoutData>::Data,
+    #[widget]
+    swatch: Swatch<EditorMsg>,
+    #[widget(handler = handle_edit)]
+    edit: EditBox<fn(&str) -> Option<Colour>>,
+}
+
+impl ColourEditor {
+    fn new(colour: Colour) -> Self {
+        ColourEditor {
+            core: Default::default(),
+            layout_data: Default::default(),
+            swatch: Swatch::new(colour),
+            edit: EditBox::new(format_colour(colour)).on_activate(parse_colour as fn(&str) -> _),
+        }
+    }
+
+    fn handle_edit(&mut self, tk: &mut dyn TkWindow, msg: Option<Colour>) -> Response<EditorMsg> {
+        if let Some(colour) = msg {
+            self.swatch.set_colour(tk, colour);
+        }
+        Response::Msg(msg.map(PropertyValue::Colour))
+    }
+}
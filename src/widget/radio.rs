@@ -0,0 +1,320 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Radio buttons
+
+use std::cell::Cell;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+use crate::class::{HasBool, HasText};
+use crate::event::{self, Action, Handler, Response, VoidMsg};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+use kas::geom::{Coord, Rect};
+
+#[derive(Debug, Default)]
+struct GroupData {
+    selected: Cell<Option<usize>>,
+    next_index: Cell<usize>,
+}
+
+/// A group of [`RadioButton`]s
+///
+/// This is a lightweight, shared handle: clones refer to the same group. At
+/// most one button of a group is selected at any time; selecting a button
+/// deselects all others. Buttons of a group may be placed anywhere in the
+/// widget tree (within a single window).
+///
+/// Each button is assigned an index on construction, in order: the first
+/// button constructed with this group has index 0, the next 1, and so on.
+#[derive(Clone, Debug, Default)]
+pub struct RadioGroup(Rc<GroupData>);
+
+impl RadioGroup {
+    /// Construct a new group, with nothing selected
+    pub fn new() -> Self {
+        RadioGroup::default()
+    }
+
+    /// Get the index of the selected button, if any
+    #[inline]
+    pub fn selected(&self) -> Option<usize> {
+        self.0.selected.get()
+    }
+
+    /// Set the selected button
+    ///
+    /// Since buttons of the group may be anywhere in the window, this
+    /// triggers a [redraw action](TkWindow::send_action) of the whole window.
+    pub fn set_selected(&self, tk: &mut dyn TkWindow, index: Option<usize>) {
+        self.set(index);
+        tk.send_action(TkAction::Redraw);
+    }
+
+    fn set(&self, index: Option<usize>) {
+        self.0.selected.set(index);
+    }
+
+    fn next_index(&self) -> usize {
+        let index = self.0.next_index.get();
+        self.0.next_index.set(index + 1);
+        index
+    }
+}
+
+/// A radio button with optional label
+///
+/// A radio button represents one of a [`RadioGroup`] of mutually exclusive
+/// options. Activating a button selects it (deselecting others of the same
+/// group); activating a button which is already selected does nothing.
+///
+/// Optionally, a handler may be set via [`RadioButton::on_select`]; this is
+/// called with the button's index within the group when the button becomes
+/// selected, and its result is returned from the event handler. Since all
+/// buttons of a group usually share the same message type, a parent may
+/// handle all of these with a single handler.
+///
+/// Cloning a button (including as part of a cloned parent) yields a button of
+/// the same group with the same index: the clone is selected exactly when the
+/// original is. For an independent set of buttons, construct each with a new
+/// group.
+#[widget]
+#[derive(Clone, Default, Widget)]
+pub struct RadioButton<OS: 'static> {
+    #[core]
+    core: CoreData,
+    box_pos: Coord,
+    text_pos_x: i32,
+    label: String,
+    group: RadioGroup,
+    index: usize,
+    on_select: OS,
+}
+
+impl<H> Debug for RadioButton<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "RadioButton {{ core: {:?}, index: {}, selected: {:?}, label: {:?}, ... }}",
+            self.core,
+            self.index,
+            self.is_selected(),
+            self.label
+        )
+    }
+}
+
+impl<OS: 'static> Widget for RadioButton<OS> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut r = SizeRules::fixed(axis.extract_size(size_handle.radiobox()));
+        if !self.label.is_empty() {
+            if !axis.vertical() {
+                r += SizeRules::fixed(size_handle.outer_margin().0);
+            }
+            r += size_handle.text_bound(&self.label, TextClass::Label, true, axis);
+        }
+        r
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        // We center the box vertically and align to the left
+        let box_size = size_handle.radiobox();
+        let mut pos = rect.pos;
+        let extra_height = rect.size.1 as i32 - box_size.1 as i32;
+        pos.1 += extra_height / 2;
+        self.box_pos = pos;
+        // Text is drawn in the area to the right of this
+        let margin = size_handle.outer_margin().0;
+        self.text_pos_x = pos.0 + (margin + box_size.0) as i32;
+        self.core_data_mut().rect = rect;
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        draw_handle.radiobox(self.box_pos, self.is_selected(), highlights);
+        let mut text_rect = self.core.rect;
+        text_rect.pos.0 = self.text_pos_x;
+        let indent = (self.text_pos_x - self.core.rect.pos.0) as u32;
+        text_rect.size.0 = text_rect.size.0.saturating_sub(indent);
+        if !self.label.is_empty() {
+            let props = TextProperties {
+                class: TextClass::Label,
+                multi_line: true,
+                horiz: Align::Begin,
+                vert: Align::Centre,
+            };
+            draw_handle.text(text_rect, &self.label, props);
+        }
+    }
+}
+
+impl<M, OS: Fn(usize) -> M> RadioButton<OS> {
+    /// Construct a radio button with a given `label` and `group`, which calls
+    /// `f` when selected.
+    ///
+    /// This is a shortcut for `RadioButton::new(label, group).on_select(f)`.
+    ///
+    /// Radio button labels are optional; if no label is desired, use an empty
+    /// string.
+    pub fn new_on<S: Into<String>>(label: S, group: &RadioGroup, f: OS) -> Self {
+        RadioButton {
+            core: Default::default(),
+            box_pos: Default::default(),
+            text_pos_x: 0,
+            label: label.into(),
+            group: group.clone(),
+            index: group.next_index(),
+            on_select: f,
+        }
+    }
+}
+
+impl RadioButton<()> {
+    /// Construct a radio button with a given `label` and `group`.
+    ///
+    /// Radio button labels are optional; if no label is desired, use an empty
+    /// string.
+    pub fn new<S: Into<String>>(label: S, group: &RadioGroup) -> Self {
+        RadioButton {
+            core: Default::default(),
+            box_pos: Default::default(),
+            text_pos_x: 0,
+            label: label.into(),
+            group: group.clone(),
+            index: group.next_index(),
+            on_select: (),
+        }
+    }
+
+    /// Set the event handler to be called on selection.
+    ///
+    /// The closure `f` is called with the button's index when selected, and
+    /// the result of `f` is returned from the event handler.
+    pub fn on_select<M, OS: Fn(usize) -> M>(self, f: OS) -> RadioButton<OS> {
+        RadioButton {
+            core: self.core,
+            box_pos: self.box_pos,
+            text_pos_x: self.text_pos_x,
+            label: self.label,
+            group: self.group,
+            index: self.index,
+            on_select: f,
+        }
+    }
+}
+
+impl<OS: 'static> RadioButton<OS> {
+    /// Select this button initially.
+    pub fn state(mut self, state: bool) -> Self {
+        self.set_state(state);
+        self
+    }
+
+    /// Select or deselect this button.
+    ///
+    /// Note: this does not cause a redraw. Once the window is shown, use
+    /// [`HasBool::set_bool`] or [`RadioGroup::set_selected`] instead.
+    pub fn set_state(&mut self, state: bool) {
+        if state {
+            self.group.set(Some(self.index));
+        } else if self.is_selected() {
+            self.group.set(None);
+        }
+    }
+
+    /// Get the index of this button within its group
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the group
+    #[inline]
+    pub fn group(&self) -> &RadioGroup {
+        &self.group
+    }
+
+    /// True if this button is selected
+    #[inline]
+    pub fn is_selected(&self) -> bool {
+        self.group.selected() == Some(self.index)
+    }
+
+    // Select on activation, returning true if state changed
+    fn select(&mut self, tk: &mut dyn TkWindow) -> bool {
+        if self.is_selected() {
+            return false;
+        }
+        self.group.set_selected(tk, Some(self.index));
+        true
+    }
+}
+
+impl<H> HasBool for RadioButton<H> {
+    fn get_bool(&self) -> bool {
+        self.is_selected()
+    }
+
+    fn set_bool(&mut self, tk: &mut dyn TkWindow, state: bool) {
+        self.set_state(state);
+        tk.send_action(TkAction::Redraw);
+    }
+}
+
+impl<H> HasText for RadioButton<H> {
+    fn get_text(&self) -> &str {
+        &self.label
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.label = text;
+        tk.redraw(self.id());
+    }
+}
+
+impl Handler for RadioButton<()> {
+    type Msg = VoidMsg;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
+        match action {
+            Action::Activate => {
+                self.select(tk);
+                Response::None
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+}
+
+impl<M, H: Fn(usize) -> M> Handler for RadioButton<H> {
+    type Msg = M;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<M> {
+        match action {
+            Action::Activate => match self.select(tk) {
+                true => ((self.on_select)(self.index)).into(),
+                false => Response::None,
+            },
+            a => Response::unhandled_action(a),
+        }
+    }
+}
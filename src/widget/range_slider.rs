@@ -0,0 +1,363 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `RangeSlider` control
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, PressSource, Response,
+};
+use crate::geom::{Coord, Rect};
+use crate::layout::{AxisInfo, Direction, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::widget::slider::fraction;
+use crate::widget::SliderType;
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// A slider with two grips, selecting a sub-range
+///
+/// As with a [`Slider`], values are taken from a fixed range, `min..=max`, in
+/// increments of `step`. The selected sub-range is `lower..=upper`, where
+/// `upper - lower` is at least the minimum gap (see
+/// [`RangeSlider::with_min_gap`]).
+///
+/// Either grip may be dragged; pressing the track moves the nearest grip.
+/// When the slider has key focus, the arrow, page-up/down, home and end keys
+/// move the focussed grip (as for [`Slider`]), and Tab moves focus from the
+/// lower grip to the upper grip (then on to the next widget). Each change is
+/// reported as a message with the new range, `(lower, upper)`.
+///
+/// [`Slider`]: crate::widget::Slider
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct RangeSlider<T: SliderType, D: Direction> {
+    #[core]
+    core: CoreData,
+    direction: D,
+    grip_len: u32,
+    range: (T, T),
+    step: T,
+    min_gap: T,
+    values: (T, T),
+    // Index of the grip with key focus (0: lower, 1: upper)
+    active: usize,
+    hover: Option<usize>,
+    // Press source and grip index
+    press: Option<(PressSource, usize)>,
+    press_offset: i32,
+}
+
+impl<T: SliderType, D: Direction + Default> RangeSlider<T, D> {
+    /// Construct a range slider
+    ///
+    /// Values vary between `min` and `max` in increments of `step`. Initially,
+    /// the whole range is selected and there is no minimum gap.
+    #[inline]
+    pub fn new(min: T, max: T, step: T) -> Self {
+        RangeSlider::new_with_direction(min, max, step, D::default())
+    }
+}
+
+impl<T: SliderType, D: Direction> RangeSlider<T, D> {
+    /// Construct a range slider with the given direction
+    ///
+    /// See [`RangeSlider::new`].
+    pub fn new_with_direction(min: T, max: T, step: T, direction: D) -> Self {
+        assert!(min <= max);
+        assert!(step > T::default());
+        RangeSlider {
+            core: Default::default(),
+            direction,
+            grip_len: 0,
+            range: (min, max),
+            step,
+            min_gap: T::default(),
+            values: (min, max),
+            active: 0,
+            hover: None,
+            press: None,
+            press_offset: 0,
+        }
+    }
+
+    /// Set the minimum gap between the lower and upper values
+    ///
+    /// Requires `gap <= max - min`. The selected range is widened if
+    /// necessary.
+    pub fn with_min_gap(mut self, gap: T) -> Self {
+        let (min, max) = self.range;
+        // max - min may overflow T
+        assert!(gap >= T::default() && gap.to_f64() <= max.to_f64() - min.to_f64());
+        self.min_gap = gap;
+        let (lower, upper) = self.values;
+        self.with_values(lower, upper)
+    }
+
+    /// Set the initial range
+    ///
+    /// Values are clamped to the range and rounded to the nearest step. If
+    /// the gap between values is too small, `upper` is increased (or, if
+    /// that is not possible, `lower` is decreased).
+    pub fn with_values(mut self, lower: T, upper: T) -> Self {
+        self.set_values_inner(lower, upper);
+        self
+    }
+
+    /// Get the selected range, `(lower, upper)`
+    #[inline]
+    pub fn values(&self) -> (T, T) {
+        self.values
+    }
+
+    /// Set the selected range
+    ///
+    /// See [`RangeSlider::with_values`].
+    pub fn set_values(&mut self, tk: &mut dyn TkWindow, lower: T, upper: T) {
+        let old = self.values;
+        self.set_values_inner(lower, upper);
+        if self.values != old {
+            tk.redraw(self.id());
+        }
+    }
+
+    fn set_values_inner(&mut self, lower: T, upper: T) {
+        let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+        let gap = self.min_gap.to_f64();
+        let lower = self.clamp(lower.to_f64(), min, max - gap);
+        let upper = self.clamp(upper.to_f64(), lower.to_f64() + gap, max);
+        let lower = self.clamp(lower.to_f64(), min, upper.to_f64() - gap);
+        self.values = (lower, upper);
+    }
+
+    // clamp to `lo..=hi`, rounding to a step where not at a limit
+    fn clamp(&self, value: f64, lo: f64, hi: f64) -> T {
+        if value.is_nan() || value <= lo {
+            return T::from_f64(lo);
+        } else if value >= hi {
+            return T::from_f64(hi);
+        }
+        let (min, step) = (self.range.0.to_f64(), self.step.to_f64());
+        let value = min + ((value - min) / step).round() * step;
+        T::from_f64(value.max(lo).min(hi))
+    }
+
+    // Set the value of grip `index`; returns true if changed
+    fn set_grip(&mut self, tk: &mut dyn TkWindow, index: usize, value: f64) -> bool {
+        let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+        let gap = self.min_gap.to_f64();
+        let old = self.values;
+        match index {
+            0 => self.values.0 = self.clamp(value, min, old.1.to_f64() - gap),
+            _ => self.values.1 = self.clamp(value, old.0.to_f64() + gap, max),
+        }
+        if self.values != old {
+            tk.redraw(self.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&self, index: usize) -> T {
+        match index {
+            0 => self.values.0,
+            _ => self.values.1,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> u32 {
+        match self.direction.is_vertical() {
+            false => self.core.rect.size.0,
+            true => self.core.rect.size.1,
+        }
+    }
+
+    // translate a value to a grip position in local coordinates
+    fn position(&self, value: T) -> u32 {
+        let len = self.len().saturating_sub(self.grip_len);
+        let frac = fraction(value, self.range);
+        ((frac * len as f64).round() as u32).min(len)
+    }
+
+    // translate a grip position to a value
+    fn position_value(&self, position: i32) -> f64 {
+        let len = self.len().saturating_sub(self.grip_len);
+        let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+        match len {
+            0 => min,
+            len => min + position.max(0).min(len as i32) as f64 / len as f64 * (max - min),
+        }
+    }
+
+    // pointer coordinate and slider start along the slider's axis
+    fn axis_coords(&self, coord: Coord) -> (i32, i32) {
+        match self.direction.is_vertical() {
+            false => (coord.0, self.core.rect.pos.0),
+            true => (coord.1, self.core.rect.pos.1),
+        }
+    }
+
+    // Find the grip under `coord`, preferring the active grip
+    fn grip_at(&self, coord: Coord) -> Option<usize> {
+        let (pointer, offset) = self.axis_coords(coord);
+        let on_grip = |index: usize| {
+            let start = offset + self.position(self.value(index)) as i32;
+            pointer >= start && pointer < start + self.grip_len as i32
+        };
+        let other = 1 - self.active;
+        match (on_grip(self.active), on_grip(other)) {
+            (true, _) => Some(self.active),
+            (false, true) => Some(other),
+            (false, false) => None,
+        }
+    }
+
+    fn changed(&self, changed: bool) -> Response<(T, T)> {
+        match changed {
+            true => Response::Msg(self.values),
+            false => Response::None,
+        }
+    }
+}
+
+impl<T: SliderType, D: Direction> Widget for RangeSlider<T, D> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let (thickness, _, min_len) = size_handle.slider();
+        if self.direction.is_vertical() == axis.vertical() {
+            SizeRules::variable(min_len, min_len)
+        } else {
+            SizeRules::fixed(thickness)
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        let (_, grip_len, _) = size_handle.slider();
+        self.core.rect = rect;
+        self.grip_len = grip_len.min(self.len());
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let dir = self.direction.is_vertical();
+        let hl = ev_mgr.highlight_state(self.id());
+        let pressed = self.press.map(|p| p.1);
+        let grip_hl = |index| HighlightState {
+            hover: hl.hover && (self.hover == Some(index) || pressed == Some(index)),
+            depress: hl.depress && pressed == Some(index),
+            key_focus: hl.key_focus && self.active == index,
+            char_focus: false,
+            error: false,
+        };
+        let pos = (self.position(self.values.0), self.position(self.values.1));
+        let highlights = (grip_hl(0), grip_hl(1));
+        draw_handle.range_slider(self.core.rect, dir, self.grip_len, pos, highlights);
+    }
+}
+
+impl<T: SliderType, D: Direction> Handler for RangeSlider<T, D> {
+    type Msg = (T, T);
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<(T, T)> {
+        match action {
+            Action::NavKey(key) => {
+                let index = self.active;
+                let step = self.step.to_f64();
+                let value = self.value(index).to_f64();
+                let value = match key {
+                    NavKey::Left | NavKey::Up => value - step,
+                    NavKey::Right | NavKey::Down => value + step,
+                    NavKey::PageUp => value - 10.0 * step,
+                    NavKey::PageDown => value + 10.0 * step,
+                    NavKey::Home => self.range.0.to_f64(),
+                    NavKey::End => self.range.1.to_f64(),
+                };
+                let changed = self.set_grip(tk, index, value);
+                self.changed(changed)
+            }
+            Action::NextFocus if self.active == 0 => {
+                self.active = 1;
+                tk.redraw(self.id());
+                Response::None
+            }
+            Action::NextFocus => {
+                // Focus leaves the widget; next time, start from the lower grip
+                self.active = 0;
+                tk.redraw(self.id());
+                Response::unhandled_action(Action::NextFocus)
+            }
+            Action::PreviousFocus if self.active == 1 => {
+                self.active = 0;
+                tk.redraw(self.id());
+                Response::None
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<(T, T)> {
+        match event {
+            Event::Identify => {
+                if let Address::Coord(coord) = addr {
+                    let hover = self.grip_at(coord);
+                    if hover != self.hover {
+                        self.hover = hover;
+                        tk.redraw(self.id());
+                    }
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                let (pointer, offset) = self.axis_coords(coord);
+
+                if let Some(index) = self.grip_at(coord) {
+                    // coord is on a grip
+                    let position = self.position(self.value(index)) as i32;
+                    self.press_offset = position - pointer;
+                    self.press = Some((source, index));
+                    self.active = index;
+                    tk.redraw(self.id());
+                    Response::None
+                } else {
+                    // coord is not on a grip; we move the nearest immediately
+                    self.press_offset = -offset - (self.grip_len / 2) as i32;
+                    let value = self.position_value(pointer + self.press_offset);
+                    let lower = (self.values.0.to_f64() - value).abs();
+                    let upper = (self.values.1.to_f64() - value).abs();
+                    let index = match (lower, upper) {
+                        (l, u) if l < u => 0,
+                        (l, u) if u < l => 1,
+                        // grips coincide: choose by direction of movement
+                        _ => (value > self.values.1.to_f64()) as usize,
+                    };
+                    self.press = Some((source, index));
+                    self.active = index;
+                    tk.redraw(self.id());
+                    let changed = self.set_grip(tk, index, value);
+                    self.changed(changed)
+                }
+            }
+            Event::PressMove { source, coord, .. } => match self.press {
+                Some((s, index)) if s == source => {
+                    let (pointer, _) = self.axis_coords(coord);
+                    let value = self.position_value(pointer + self.press_offset);
+                    let changed = self.set_grip(tk, index, value);
+                    self.changed(changed)
+                }
+                _ => Response::Unhandled(event),
+            },
+            Event::PressEnd { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                self.press = None;
+                tk.redraw(self.id());
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
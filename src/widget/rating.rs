@@ -0,0 +1,208 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Rating` control
+
+use crate::event::{self, Action, Address, Event, Handler, Manager, NavKey, PressSource, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// A rating (star) input
+///
+/// A row of `count` symbols, of which the first `value` are filled. The value
+/// varies from 0 to `count` in whole steps or, optionally, half steps.
+///
+/// While hovered, the value which a click would set is previewed. The value
+/// may also be adjusted with the arrow keys when the widget has key focus
+/// (home and end set the limits). Each change is reported as a message with
+/// the new value.
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Rating {
+    #[core]
+    core: CoreData,
+    count: u32,
+    half_steps: bool,
+    value: f32,
+    preview: Option<f32>,
+    symbol: Size,
+    press: Option<PressSource>,
+}
+
+impl Rating {
+    /// Construct a rating with `count` symbols and value 0
+    pub fn new(count: u32) -> Self {
+        assert!(count > 0);
+        Rating {
+            core: Default::default(),
+            count,
+            half_steps: false,
+            value: 0.0,
+            preview: None,
+            symbol: Size::ZERO,
+            press: None,
+        }
+    }
+
+    /// Allow half steps
+    #[inline]
+    pub fn with_half_steps(mut self, half_steps: bool) -> Self {
+        self.half_steps = half_steps;
+        self
+    }
+
+    /// Set the initial value
+    ///
+    /// The value is clamped to `0..=count` and rounded to the nearest step.
+    #[inline]
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = self.round(value);
+        self
+    }
+
+    /// Get the number of symbols
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Get the current value
+    #[inline]
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Set the value
+    ///
+    /// The value is clamped to `0..=count` and rounded to the nearest step.
+    pub fn set_value(&mut self, tk: &mut dyn TkWindow, value: f32) {
+        let value = self.round(value);
+        if value != self.value {
+            self.value = value;
+            tk.redraw(self.id());
+        }
+    }
+
+    #[inline]
+    fn step(&self) -> f32 {
+        if self.half_steps {
+            0.5
+        } else {
+            1.0
+        }
+    }
+
+    fn round(&self, value: f32) -> f32 {
+        let step = self.step();
+        ((value / step).round() * step)
+            .max(0.0)
+            .min(self.count as f32)
+    }
+
+    // The value set by a click at `coord`: the symbol (or half symbol) under
+    // the cursor and all before it are filled
+    fn value_at(&self, coord: Coord) -> f32 {
+        let x = (coord.0 - self.core.rect.pos.0).max(0) as f32;
+        let width = self.core.rect.size.0 as f32 / self.count as f32;
+        let step = self.step();
+        ((x / width / step).floor() + 1.0) * step
+    }
+
+    fn set_and_changed(&mut self, tk: &mut dyn TkWindow, value: f32) -> bool {
+        let old = self.value;
+        self.set_value(tk, value);
+        self.value != old
+    }
+}
+
+impl Widget for Rating {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.symbol = size_handle.rating_symbol();
+        let size = Size(self.count * self.symbol.0, self.symbol.1);
+        SizeRules::fixed(axis.extract_size(size))
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        // Use only the fixed size, aligned to the start of the rect
+        let size = Size(self.count * self.symbol.0, self.symbol.1).min(rect.size);
+        self.core.rect = Rect {
+            pos: rect.pos,
+            size,
+        };
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let preview = self.preview.filter(|_| highlights.hover);
+        let rect = self.core.rect;
+        draw_handle.rating(rect, self.count, self.value, preview, highlights);
+    }
+}
+
+impl Handler for Rating {
+    type Msg = f32;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<f32> {
+        match action {
+            Action::NavKey(key) => {
+                let value = match key {
+                    NavKey::Left | NavKey::Down => self.value - self.step(),
+                    NavKey::Right | NavKey::Up => self.value + self.step(),
+                    NavKey::Home => 0.0,
+                    NavKey::End => self.count as f32,
+                    key => return Response::unhandled_action(Action::NavKey(key)),
+                };
+                match self.set_and_changed(tk, value) {
+                    true => Response::Msg(self.value),
+                    false => Response::None,
+                }
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<f32> {
+        match event {
+            Event::CursorMove { coord } => {
+                let preview = Some(self.round(self.value_at(coord)));
+                if preview != self.preview {
+                    self.preview = preview;
+                    tk.redraw(self.id());
+                }
+                Response::None
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some(source);
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.press == Some(source) => Response::None,
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press == Some(source) => {
+                self.press = None;
+                if end_id != Some(self.id()) {
+                    return Response::None;
+                }
+                let value = self.value_at(coord);
+                match self.set_and_changed(tk, value) {
+                    true => Response::Msg(self.value),
+                    false => Response::None,
+                }
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
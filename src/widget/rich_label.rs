@@ -0,0 +1,193 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Rich text label
+
+use std::ops::Range;
+
+use super::property_grid::parse_colour;
+use crate::class::HasText;
+use crate::event;
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties, TextStyle};
+use crate::{CoreData, TkAction, TkWindow, Widget};
+
+/// A text label with inline styles
+///
+/// The text is made of runs, each with a [`TextStyle`] (bold, italic, colour
+/// and relative size). It may be constructed from a list of runs or from a
+/// simple markup; see [`RichLabel::from_markup`].
+///
+/// Whether bold and italic faces are available depends on the theme.
+#[widget]
+#[handler]
+#[derive(Clone, Default, Debug, Widget)]
+pub struct RichLabel {
+    #[core]
+    core: CoreData,
+    text: String,
+    runs: Vec<(Range<usize>, TextStyle)>,
+}
+
+impl RichLabel {
+    /// Construct from a list of runs
+    pub fn new<S: AsRef<str>>(runs: &[(S, TextStyle)]) -> Self {
+        let mut label = RichLabel::default();
+        for (text, style) in runs {
+            label.push_run(text.as_ref(), *style);
+        }
+        label
+    }
+
+    /// Construct from markup
+    ///
+    /// The markup is plain text with the following tags:
+    ///
+    /// -   `[b]bold[/b]`
+    /// -   `[i]italic[/i]`
+    /// -   `[colour=#rrggbb]coloured[/colour]`
+    /// -   `[size=1.5]larger[/size]`, where the size is relative
+    ///
+    /// Tags may be nested but must be closed in reverse order; unclosed tags
+    /// apply to the end of the text. Use `[[` for a literal `[`. Anything not
+    /// recognised as a tag is shown literally.
+    pub fn from_markup(markup: &str) -> Self {
+        let mut label = RichLabel::default();
+        label.parse(markup);
+        label
+    }
+
+    /// Replace the text with a list of runs
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_runs<S: AsRef<str>>(&mut self, tk: &mut dyn TkWindow, runs: &[(S, TextStyle)]) {
+        self.text.clear();
+        self.runs.clear();
+        for (text, style) in runs {
+            self.push_run(text.as_ref(), *style);
+        }
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Replace the text with markup
+    ///
+    /// See [`RichLabel::from_markup`]. Triggers a
+    /// [reconfigure action](TkWindow::send_action).
+    pub fn set_markup(&mut self, tk: &mut dyn TkWindow, markup: &str) {
+        self.text.clear();
+        self.runs.clear();
+        self.parse(markup);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Iterate over runs of text and their styles
+    pub fn runs<'a>(&'a self) -> impl Iterator<Item = (&'a str, TextStyle)> + 'a {
+        let text = &self.text;
+        (self.runs.iter()).map(move |(range, style)| (&text[range.clone()], *style))
+    }
+
+    fn push_run(&mut self, text: &str, style: TextStyle) {
+        if text.is_empty() {
+            return;
+        }
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.runs.push((start..self.text.len(), style));
+    }
+
+    fn parse(&mut self, mut markup: &str) {
+        // Open tags, each with the style before it was opened
+        let mut stack: Vec<(&str, TextStyle)> = vec![];
+        let mut style = TextStyle::default();
+        let mut run = String::new();
+
+        while let Some(pos) = markup.find('[') {
+            run.push_str(&markup[..pos]);
+            markup = &markup[pos..];
+            if markup.starts_with("[[") {
+                run.push('[');
+                markup = &markup[2..];
+                continue;
+            }
+            let end = match markup.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = &markup[1..end];
+            let mut next = style;
+            let valid = if let Some(name) = tag.strip_prefix('/') {
+                stack.last().map(|t| t.0) == Some(name)
+            } else if tag == "b" {
+                next.bold = true;
+                true
+            } else if tag == "i" {
+                next.italic = true;
+                true
+            } else if let Some(colour) = tag.strip_prefix("colour=") {
+                next.colour = parse_colour(colour);
+                next.colour.is_some()
+            } else if let Some(size) = tag.strip_prefix("size=") {
+                next.scale = size.trim().parse().ok().filter(|s: &f32| *s > 0.0);
+                next.scale.is_some()
+            } else {
+                false
+            };
+            if !valid {
+                run.push('[');
+                markup = &markup[1..];
+                continue;
+            }
+
+            self.push_run(&run, style);
+            run.clear();
+            if tag.starts_with('/') {
+                style = stack.pop().unwrap().1;
+            } else {
+                let name = tag.split('=').next().unwrap();
+                stack.push((name, style));
+                style = next;
+            }
+            markup = &markup[end + 1..];
+        }
+        run.push_str(markup);
+        self.push_run(&run, style);
+    }
+
+    fn draw_runs(&self) -> Vec<(&str, TextStyle)> {
+        self.runs().collect()
+    }
+}
+
+impl Widget for RichLabel {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        size_handle.text_runs_bound(&self.draw_runs(), TextClass::Label, true, axis)
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let props = TextProperties {
+            class: TextClass::Label,
+            multi_line: true,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        draw_handle.text_runs(self.core.rect, &self.draw_runs(), props);
+    }
+}
+
+impl HasText for RichLabel {
+    /// Get the text, without styles
+    fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Set plain text, replacing all runs
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.runs.clear();
+        self.runs.push((0..text.len(), TextStyle::default()));
+        self.text = text;
+        tk.send_action(TkAction::Reconfigure);
+    }
+}
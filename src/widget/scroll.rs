@@ -10,7 +10,7 @@ use std::fmt::Debug;
 use super::ScrollBar;
 use crate::event::{Action, Address, Event, Handler, Manager, Response, ScrollDelta};
 use crate::geom::{Coord, Rect, Size};
-use crate::layout::{AxisInfo, Horizontal, SizeRules, Vertical};
+use crate::layout::{self, AxisInfo, Horizontal, SizeRules, Vertical};
 use crate::macros::Widget;
 use crate::theme::{DrawHandle, SizeHandle, TextClass};
 use crate::{CoreData, TkWindow, Widget, WidgetCore};
@@ -18,6 +18,9 @@ use crate::{CoreData, TkWindow, Widget, WidgetCore};
 /// A scrollable region
 ///
 /// This region supports scrolling via mouse wheel and drag.
+/// By default the child may scroll on both axes; on an axis which is not
+/// scrollable (see [`ScrollRegion::with_scroll_axes`]) the child is sized to
+/// fit the region as with any other container.
 /// Optionally, it can have scroll bars (see [`ScrollRegion::show_bars`] and
 /// [`ScrollRegion::with_bars`]).
 ///
@@ -36,6 +39,7 @@ pub struct ScrollRegion<W: Widget> {
     scroll_rate: f32,
     auto_bars: bool,
     show_bars: (bool, bool),
+    scroll_axes: (bool, bool),
     #[widget]
     horiz_bar: ScrollBar<Horizontal>,
     #[widget]
@@ -57,6 +61,7 @@ impl<W: Widget> ScrollRegion<W> {
             scroll_rate: 30.0,
             auto_bars: false,
             show_bars: (false, false),
+            scroll_axes: (true, true),
             horiz_bar: ScrollBar::new(),
             vert_bar: ScrollBar::new(),
             child,
@@ -86,6 +91,24 @@ impl<W: Widget> ScrollRegion<W> {
         self.show_bars = (horiz, vert);
     }
 
+    /// Set which axes are scrollable
+    ///
+    /// Content may only exceed the size of the region on scrollable axes. By
+    /// default, both axes are scrollable.
+    #[inline]
+    pub fn with_scroll_axes(mut self, horiz: bool, vert: bool) -> Self {
+        self.scroll_axes = (horiz, vert);
+        self
+    }
+
+    /// Set which axes are scrollable
+    ///
+    /// This requires a reconfigure to take effect.
+    #[inline]
+    pub fn set_scroll_axes(&mut self, horiz: bool, vert: bool) {
+        self.scroll_axes = (horiz, vert);
+    }
+
     /// Access inner widget directly
     #[inline]
     pub fn inner(&self) -> &W {
@@ -127,7 +150,7 @@ impl<W: Widget> ScrollRegion<W> {
 
 impl<W: Widget> Widget for ScrollRegion<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        let mut rules = self.child.size_rules(size_handle, axis);
+        let mut rules = layout::child_rules(&mut self.child, size_handle, axis);
         if !axis.vertical() {
             self.min_child_size.0 = rules.min_size();
         } else {
@@ -135,12 +158,24 @@ impl<W: Widget> Widget for ScrollRegion<W> {
         }
         let line_height = size_handle.line_height(TextClass::Label);
         self.scroll_rate = 3.0 * line_height as f32;
-        rules.reduce_min_to(line_height);
+        // Scrolling over the bars should move at the same rate
+        self.horiz_bar.set_line_step(self.scroll_rate as u32);
+        self.vert_bar.set_line_step(self.scroll_rate as u32);
+        let scrollable = match axis.vertical() {
+            false => self.scroll_axes.0,
+            true => self.scroll_axes.1,
+        };
+        if scrollable {
+            rules.reduce_min_to(line_height);
+        }
 
+        // Bars are sized even when hidden, as required before set_rect
+        let horiz_bar = layout::child_rules(&mut self.horiz_bar, size_handle, axis);
+        let vert_bar = layout::child_rules(&mut self.vert_bar, size_handle, axis);
         if !axis.vertical() && self.show_bars.1 {
-            rules + self.vert_bar.size_rules(size_handle, axis)
+            rules + vert_bar
         } else if axis.vertical() && self.show_bars.0 {
-            rules + self.horiz_bar.size_rules(size_handle, axis)
+            rules + horiz_bar
         } else {
             rules
         }
@@ -152,8 +187,8 @@ impl<W: Widget> Widget for ScrollRegion<W> {
         let mut size = rect.size;
         if self.auto_bars {
             self.show_bars = (
-                self.min_child_size.0 > size.0,
-                self.min_child_size.1 > size.1,
+                self.scroll_axes.0 && self.min_child_size.0 > size.0,
+                self.scroll_axes.1 && self.min_child_size.1 > size.1,
             );
         }
 
@@ -166,7 +201,13 @@ impl<W: Widget> Widget for ScrollRegion<W> {
         }
         self.inner_size = size;
 
-        let child_size = size.max(self.min_child_size);
+        let mut child_size = size.max(self.min_child_size);
+        if !self.scroll_axes.0 {
+            child_size.0 = size.0;
+        }
+        if !self.scroll_axes.1 {
+            child_size.1 = size.1;
+        }
         self.child.set_rect(
             size_handle,
             Rect {
@@ -174,31 +215,29 @@ impl<W: Widget> Widget for ScrollRegion<W> {
                 size: child_size,
             },
         );
-        self.max_offset = Coord::from(child_size) - Coord::from(rect.size);
+        self.max_offset = Coord::from(child_size) - Coord::from(size);
         self.offset = self.offset.max(Coord::ZERO).min(self.max_offset);
 
         if self.show_bars.0 {
             let pos = Coord(pos.0, pos.1 + size.1 as i32);
             let size = Size(size.0, self.horiz_bar.width());
             self.horiz_bar.set_rect(size_handle, Rect { pos, size });
-            self.horiz_bar
-                .set_limits(self.max_offset.0 as u32, rect.size.0);
+            self.horiz_bar.set_limits(self.max_offset.0 as u32, size.0);
         }
         if self.show_bars.1 {
             let pos = Coord(pos.0 + size.0 as i32, pos.1);
             let size = Size(self.vert_bar.width(), size.1);
             self.vert_bar.set_rect(size_handle, Rect { pos, size });
-            self.vert_bar
-                .set_limits(self.max_offset.1 as u32, rect.size.1);
+            self.vert_bar.set_limits(self.max_offset.1 as u32, size.1);
         }
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
         if self.show_bars.0 {
-            self.horiz_bar.draw(draw_handle, ev_mgr);
+            layout::draw_child(&self.horiz_bar, draw_handle, ev_mgr);
         }
         if self.show_bars.1 {
-            self.vert_bar.draw(draw_handle, ev_mgr);
+            layout::draw_child(&self.vert_bar, draw_handle, ev_mgr);
         }
         let rect = Rect {
             pos: self.core.rect.pos,
@@ -289,7 +328,7 @@ impl<W: Widget + Handler> Handler for ScrollRegion<W> {
             Address::Coord(coord) => Address::Coord(coord + self.offset),
         };
         let event = match event {
-            a @ Event::Action(_) | a @ Event::Identify => a,
+            a @ Event::Action(_) | a @ Event::Identify | a @ Event::Key { .. } => a,
             Event::PressStart { source, coord } => Event::PressStart {
                 source,
                 coord: coord + self.offset,
@@ -314,13 +353,24 @@ impl<W: Widget + Handler> Handler for ScrollRegion<W> {
                 end_id,
                 coord: coord + self.offset,
             },
+            Event::CursorMove { coord } => Event::CursorMove {
+                coord: coord + self.offset,
+            },
+            Event::DragHover { payload, coord } => Event::DragHover {
+                payload,
+                coord: coord + self.offset,
+            },
+            Event::Drop { payload, coord } => Event::Drop {
+                payload,
+                coord: coord + self.offset,
+            },
         };
 
         match self.child.handle(tk, addr, event) {
             Response::None => Response::None,
             Response::Unhandled(Event::Action(action)) => unhandled_action(self, tk, action),
             Response::Unhandled(Event::PressStart { source, coord }) if source.is_primary() => {
-                tk.update_data(&mut |data| data.request_press_grab(source, self, coord));
+                tk.data_mut().request_press_grab(source, self, coord);
                 Response::None
             }
             e @ _ => e,
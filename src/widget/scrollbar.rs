@@ -7,7 +7,9 @@
 
 use std::fmt::Debug;
 
-use crate::event::{self, Address, Event, Handler, Manager, PressSource, Response};
+use crate::event::{
+    self, Action, Address, Event, Handler, Manager, PressSource, Response, ScrollDelta,
+};
 use crate::geom::Rect;
 use crate::layout::{AxisInfo, Direction, SizeRules};
 use crate::macros::Widget;
@@ -18,6 +20,11 @@ use crate::{CoreData, TkWindow, Widget, WidgetCore};
 ///
 /// Scroll bars allow user-input of a value between 0 and a defined maximum,
 /// and allow the size of the handle to be specified.
+///
+/// The handle may be dragged, or moved with the mouse wheel or touchpad
+/// while hovered (see [`ScrollBar::set_line_step`]). Scroll events which do
+/// not move the bar (e.g. when already at the limit) are left unhandled, thus
+/// a parent may respond to them instead.
 #[widget]
 #[derive(Clone, Debug, Default, Widget)]
 pub struct ScrollBar<D: Direction> {
@@ -31,6 +38,7 @@ pub struct ScrollBar<D: Direction> {
     handle_value: u32, // contract: > 0
     max_value: u32,
     value: u32,
+    line_step: u32,
     press_source: Option<PressSource>,
     press_offset: i32,
 }
@@ -59,6 +67,7 @@ impl<D: Direction> ScrollBar<D> {
             handle_value: 1,
             max_value: 0,
             value: 0,
+            line_step: 1,
             press_source: None,
             press_offset: 0,
         }
@@ -96,6 +105,25 @@ impl<D: Direction> ScrollBar<D> {
         self.update_handle();
     }
 
+    /// Set the distance moved per line of mouse-wheel scrolling
+    ///
+    /// See [`ScrollBar::set_line_step`].
+    #[inline]
+    pub fn with_line_step(mut self, step: u32) -> Self {
+        self.line_step = step;
+        self
+    }
+
+    /// Set the distance moved per line of mouse-wheel scrolling
+    ///
+    /// This is in the same units as the value. Pixel deltas (as from touchpads)
+    /// are applied directly, assuming the value has units of pixels.
+    /// The default is 1.
+    #[inline]
+    pub fn set_line_step(&mut self, step: u32) {
+        self.line_step = step;
+    }
+
     /// Get the current value
     #[inline]
     pub fn value(&self) -> u32 {
@@ -111,6 +139,27 @@ impl<D: Direction> ScrollBar<D> {
         }
     }
 
+    // Apply a scroll delta, returning true if the value changed
+    fn scroll(&mut self, tk: &mut dyn TkWindow, delta: ScrollDelta) -> bool {
+        // Where the delta is purely vertical, it also moves horizontal bars.
+        let vertical = self.direction.is_vertical();
+        let d = match delta {
+            ScrollDelta::LineDelta(x, y) => {
+                let lines = if vertical || x == 0.0 { -y } else { x };
+                (lines * self.line_step as f32).round() as i64
+            }
+            ScrollDelta::PixelDelta(d) => -(if vertical || d.0 == 0 { d.1 } else { d.0 }) as i64,
+        };
+        let value = (self.value as i64 + d).max(0).min(self.max_value as i64) as u32;
+        if value != self.value {
+            self.value = value;
+            tk.redraw(self.id());
+            true
+        } else {
+            false
+        }
+    }
+
     pub(crate) fn width(&self) -> u32 {
         self.width
     }
@@ -164,6 +213,10 @@ impl<D: Direction> ScrollBar<D> {
 }
 
 impl<D: Direction> Widget for ScrollBar<D> {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let (thickness, _, min_len) = size_handle.scrollbar();
         self.width = thickness;
@@ -199,7 +252,7 @@ impl<D: Direction> Handler for ScrollBar<D> {
                 // does not make sense. Any other gets aborted.
                 // TODO: only if request_press_grab succeeds
                 self.press_source = Some(source);
-                tk.update_data(&mut |data| data.request_press_grab(source, self, coord));
+                tk.data_mut().request_press_grab(source, self, coord);
 
                 // Event delivery implies coord is over the scrollbar.
                 let (pointer, offset) = match self.direction.is_vertical() {
@@ -240,6 +293,10 @@ impl<D: Direction> Handler for ScrollBar<D> {
                 self.press_source = None;
                 Response::None
             }
+            Event::Action(Action::Scroll(delta)) => match self.scroll(tk, delta) {
+                true => Response::Msg(self.value),
+                false => Response::unhandled_action(Action::Scroll(delta)),
+            },
             e @ _ => Manager::handle_generic(self, tk, e),
         }
     }
@@ -0,0 +1,434 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Search entry with suggestions
+
+use std::any::Any;
+
+use crate::class::HasText;
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, Manager, NavKey, Response,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{Align, DrawHandle, SizeHandle, SpanClass, TextClass, TextProperties};
+use crate::widget::EditBox;
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+type SearchEdit = EditBox<fn(&str)>;
+
+/// A search entry with a pop-up list of suggestions
+///
+/// The pop-up lists those suggestions containing the entered text (ignoring
+/// case), in order, and is updated as the text is edited. It is closed while
+/// the text is empty or nothing matches, or by the Escape key.
+///
+/// The up and down arrow keys select a suggestion (the down key also re-opens
+/// the list), as does hovering with the mouse. Clicking a suggestion or
+/// pressing Enter with one selected chooses it: the entered text is replaced
+/// and the suggestion is returned as a message. Pressing Enter without a
+/// selection returns the entered text as a message.
+#[derive(Clone, Debug)]
+pub struct SearchBox {
+    core: CoreData,
+    edit: SearchEdit,
+    suggestions: Vec<String>,
+    // Indices of suggestions matching the text
+    matches: Vec<usize>,
+    // Index into matches
+    selected: Option<usize>,
+    max_shown: usize,
+    row_height: u32,
+    popup: Rect,
+    open: bool,
+}
+
+impl Default for SearchBox {
+    fn default() -> Self {
+        SearchBox::new(Vec::<String>::new())
+    }
+}
+
+impl SearchBox {
+    /// Construct, with the given suggestions
+    pub fn new<S: Into<String>, I: IntoIterator<Item = S>>(suggestions: I) -> Self {
+        let edit: SearchEdit = EditBox::new("").on_activate(|_| ());
+        SearchBox {
+            core: Default::default(),
+            edit,
+            suggestions: suggestions.into_iter().map(|s| s.into()).collect(),
+            matches: vec![],
+            selected: None,
+            max_shown: 8,
+            row_height: 0,
+            popup: Rect::default(),
+            open: false,
+        }
+    }
+
+    /// Set the maximum number of suggestions shown at once (chain style)
+    ///
+    /// The default is 8.
+    pub fn with_max_shown(mut self, max_shown: usize) -> Self {
+        self.max_shown = max_shown;
+        self
+    }
+
+    /// Access the list of suggestions
+    #[inline]
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    /// Replace the list of suggestions
+    ///
+    /// The pop-up is updated if open.
+    pub fn set_suggestions(&mut self, tk: &mut dyn TkWindow, suggestions: Vec<String>) {
+        self.suggestions = suggestions;
+        self.filter();
+        if self.open {
+            self.set_open(tk, !self.matches.is_empty());
+        }
+    }
+
+    /// Iterate over suggestions matching the entered text
+    pub fn matches<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        (self.matches.iter()).map(move |i| self.suggestions[*i].as_str())
+    }
+
+    /// Get the selected suggestion, if any
+    pub fn selected(&self) -> Option<&str> {
+        (self.selected).map(|i| self.suggestions[self.matches[i]].as_str())
+    }
+
+    /// True while the pop-up is open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open or close the pop-up
+    ///
+    /// The pop-up is not opened when no suggestions match.
+    pub fn set_open(&mut self, tk: &mut dyn TkWindow, open: bool) {
+        self.open = open && !self.matches.is_empty();
+        if !self.open {
+            self.selected = None;
+        }
+        self.update_popup_rect();
+        let (id, edit_id) = (self.id(), self.edit.id());
+        if self.open {
+            // The owner of the pop-up receives key focus; we return character
+            // focus to the edit box so that editing continues.
+            let char_focus = tk.data().char_focus(edit_id);
+            let mut data = tk.data_mut();
+            data.set_popup(Some(id));
+            if char_focus {
+                data.set_char_focus(edit_id);
+            }
+        } else if tk.data().popup() == Some(id) {
+            tk.data_mut().set_popup(None);
+        }
+        tk.redraw(id);
+    }
+
+    // Find suggestions matching the text
+    fn filter(&mut self) {
+        let text = self.edit.get_text().to_lowercase();
+        self.matches = match text.is_empty() {
+            true => vec![],
+            false => (self.suggestions.iter().enumerate())
+                .filter(|(_, s)| s.to_lowercase().contains(&text))
+                .map(|(i, _)| i)
+                .collect(),
+        };
+        self.selected = None;
+    }
+
+    fn shown(&self) -> usize {
+        self.matches.len().min(self.max_shown)
+    }
+
+    // Index of the first match shown: the list scrolls to the selection
+    fn first_shown(&self) -> usize {
+        let shown = self.shown();
+        match self.selected {
+            Some(i) if i >= shown => i + 1 - shown,
+            _ => 0,
+        }
+    }
+
+    fn update_popup_rect(&mut self) {
+        let rect = self.core.rect;
+        self.popup = Rect {
+            pos: rect.pos + Coord(0, rect.size.1 as i32),
+            size: Size(rect.size.0, self.row_height * self.shown() as u32),
+        };
+    }
+
+    // Index of the match at `coord`, if within the pop-up
+    fn match_at(&self, coord: Coord) -> Option<usize> {
+        if !self.popup.contains(coord) || self.row_height == 0 {
+            return None;
+        }
+        let row = (coord.1 - self.popup.pos.1) as u32 / self.row_height;
+        Some(self.first_shown() + row as usize).filter(|i| *i < self.matches.len())
+    }
+
+    fn select(&mut self, tk: &mut dyn TkWindow, selected: Option<usize>) {
+        if selected != self.selected {
+            self.selected = selected;
+            tk.redraw(self.id());
+        }
+    }
+
+    // Choose match `index`, returning the suggestion
+    fn choose(&mut self, tk: &mut dyn TkWindow, index: usize) -> Response<String> {
+        let text = self.suggestions[self.matches[index]].clone();
+        self.edit.set_string(tk, text.clone());
+        self.set_open(tk, false);
+        self.filter();
+        Response::Msg(text)
+    }
+
+    fn enter(&mut self, tk: &mut dyn TkWindow) -> Response<String> {
+        match self.selected {
+            Some(index) if self.open => self.choose(tk, index),
+            _ => {
+                self.set_open(tk, false);
+                Response::Msg(self.edit.get_text().to_string())
+            }
+        }
+    }
+
+    fn handle_edit(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<String> {
+        let edit_addr = match addr {
+            Address::Id(_) => Address::Id(self.edit.id()),
+            addr @ Address::Coord(_) => addr,
+        };
+        let old_text = match event {
+            Event::Action(Action::ReceivedCharacter(_)) => Some(self.edit.get_text().to_string()),
+            _ => None,
+        };
+        let r = self.edit.handle(tk, edit_addr, event);
+        if old_text.map(|t| t != self.edit.get_text()).unwrap_or(false) {
+            self.filter();
+            self.set_open(tk, !self.matches.is_empty());
+        }
+        match r.try_into() {
+            Ok(r) => r,
+            // Enter was pressed
+            Err(()) => self.enter(tk),
+        }
+    }
+}
+
+// We implement this manually, because the derive implementation cannot draw
+// the pop-up.
+impl WidgetCore for SearchBox {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "SearchBox"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        1
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        match index {
+            0 => Some(self.edit.as_widget()),
+            _ => None,
+        }
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        match index {
+            0 => Some(self.edit.as_widget_mut()),
+            _ => None,
+        }
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        self.edit.walk(f);
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        self.edit.walk_mut(f);
+        f(self)
+    }
+}
+
+impl Widget for SearchBox {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        if axis.vertical() {
+            let rules = size_handle.text_bound("X", TextClass::Label, false, axis);
+            self.row_height = rules.ideal_size();
+        }
+        layout::child_rules(&mut self.edit, size_handle, axis)
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.edit.set_rect(size_handle, rect);
+        self.update_popup_rect();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        layout::draw_child(&self.edit, draw_handle, ev_mgr);
+        if !self.open || ev_mgr.popup() != Some(self.id()) {
+            return;
+        }
+
+        let props = || TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        draw_handle.overlay(self.popup, &mut |draw_handle| {
+            draw_handle.edit_box(self.popup, HighlightState::default());
+            let first = self.first_shown();
+            for row in 0..self.shown() {
+                let rect = Rect {
+                    pos: self.popup.pos + Coord(0, (row as u32 * self.row_height) as i32),
+                    size: Size(self.popup.size.0, self.row_height),
+                };
+                let index = first + row;
+                if Some(index) == self.selected {
+                    draw_handle.line_highlight(rect, SpanClass::Selected);
+                }
+                let text = &self.suggestions[self.matches[index]];
+                draw_handle.text(rect, text, props());
+            }
+        });
+    }
+}
+
+impl Handler for SearchBox {
+    type Msg = String;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<String> {
+        if self.open && tk.data().popup() != Some(self.id()) {
+            // The pop-up was closed externally (e.g. via Escape)
+            self.open = false;
+            self.selected = None;
+        }
+
+        let to_self = match addr {
+            Address::Id(id) => id == self.id(),
+            Address::Coord(_) => false,
+        };
+        match event {
+            Event::Identify if self.open => {
+                let coord = match addr {
+                    Address::Coord(coord) => coord,
+                    Address::Id(_) => tk.data().last_mouse_coord(),
+                };
+                if let Some(index) = self.match_at(coord) {
+                    self.select(tk, Some(index));
+                    return Response::Identify(self.id());
+                }
+                match self.edit.rect().contains(coord) {
+                    true => Response::Identify(self.edit.id()),
+                    false => Response::Identify(self.id()),
+                }
+            }
+            Event::Action(Action::NavKey(key @ NavKey::Up))
+            | Event::Action(Action::NavKey(key @ NavKey::Down))
+                if self.open || (key == NavKey::Down && !self.matches.is_empty()) =>
+            {
+                if !self.open {
+                    self.set_open(tk, true);
+                }
+                let last = self.matches.len() - 1;
+                let selected = match (key, self.selected) {
+                    (NavKey::Down, None) => Some(0),
+                    (NavKey::Down, Some(i)) => Some((i + 1).min(last)),
+                    (_, Some(0)) | (_, None) => None,
+                    (_, Some(i)) => Some(i - 1),
+                };
+                self.select(tk, selected);
+                Response::None
+            }
+            // Enter while the pop-up has key focus
+            Event::Action(Action::Activate) if to_self => self.enter(tk),
+            Event::PressStart { coord, .. } | Event::PressEnd { coord, .. }
+                if self.open && self.popup.contains(coord) =>
+            {
+                match (event, self.match_at(coord)) {
+                    (Event::PressStart { .. }, Some(index)) => self.choose(tk, index),
+                    _ => Response::None,
+                }
+            }
+            Event::PressStart { coord, .. } if to_self && !self.edit.rect().contains(coord) => {
+                // Dismiss on a click outside the pop-up
+                self.set_open(tk, false);
+                Response::None
+            }
+            Event::Action(Action::NavKey(_))
+            | Event::Action(Action::ReceivedCharacter(_))
+            | Event::Identify
+            | Event::PressStart { .. }
+            | Event::PressMove { .. }
+            | Event::PressEnd { .. } => self.handle_edit(tk, addr, event),
+            e if to_self => Manager::handle_generic(self, tk, e),
+            e => self.handle_edit(tk, addr, e),
+        }
+    }
+}
+
+impl HasText for SearchBox {
+    fn get_text(&self) -> &str {
+        self.edit.get_text()
+    }
+
+    /// Set the entered text
+    ///
+    /// This does not open the pop-up.
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.edit.set_string(tk, text);
+        self.filter();
+        if self.open {
+            self.set_open(tk, !self.matches.is_empty());
+        }
+    }
+}
@@ -0,0 +1,336 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Segmented control
+
+use std::fmt::{self, Debug};
+use std::iter;
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, NavKey, PressSource, Response, VoidMsg,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, Horizontal, Margins, RulesSetter, RulesSolver, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// A row of mutually exclusive options, drawn as adjoined buttons
+///
+/// Adjacent segments share a border (the selected segment is drawn on top).
+/// Exactly one segment is selected (unless there are none). Segments are
+/// selected by clicking, or with the arrow, home and end keys when the
+/// control has key focus. All segments have equal width (that of the widest
+/// label; see [`RowSolver::with_uniform`]).
+///
+/// Optionally, a handler may be set via [`SegmentedControl::on_select`]; this
+/// is called with the segment's index whenever the selection changes, and its
+/// result is returned from the event handler.
+///
+/// [`RowSolver::with_uniform`]: crate::layout::RowSolver::with_uniform
+#[widget]
+#[derive(Clone, Widget)]
+pub struct SegmentedControl<OS: 'static> {
+    #[core]
+    core: CoreData,
+    labels: Vec<String>,
+    rects: Vec<Rect>,
+    data: layout::DynRowStorage,
+    text_margins: (Size, Size),
+    selected: usize,
+    hover: Option<usize>,
+    press: Option<(PressSource, Option<usize>)>,
+    on_select: OS,
+}
+
+impl<OS> Debug for SegmentedControl<OS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SegmentedControl {{ core: {:?}, labels: {:?}, selected: {}, ... }}",
+            self.core, self.labels, self.selected,
+        )
+    }
+}
+
+impl SegmentedControl<()> {
+    /// Construct a control with the given segment labels
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(labels: I) -> Self {
+        SegmentedControl {
+            core: Default::default(),
+            labels: labels.into_iter().map(|s| s.into()).collect(),
+            rects: vec![],
+            data: Default::default(),
+            text_margins: Default::default(),
+            selected: 0,
+            hover: None,
+            press: None,
+            on_select: (),
+        }
+    }
+
+    /// Set the event handler to be called on selection.
+    ///
+    /// The closure `f` is called with the segment's index when selected, and
+    /// the result of `f` is returned from the event handler.
+    pub fn on_select<M, OS: Fn(usize) -> M>(self, f: OS) -> SegmentedControl<OS> {
+        SegmentedControl {
+            core: self.core,
+            labels: self.labels,
+            rects: self.rects,
+            data: self.data,
+            text_margins: self.text_margins,
+            selected: self.selected,
+            hover: self.hover,
+            press: self.press,
+            on_select: f,
+        }
+    }
+}
+
+impl<OS> SegmentedControl<OS> {
+    /// Add a segment (inline)
+    pub fn with_segment<S: Into<String>>(mut self, label: S) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    /// Add a segment
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push_segment<S: Into<String>>(&mut self, tk: &mut dyn TkWindow, label: S) {
+        self.labels.push(label.into());
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Set the selected segment (inline)
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        assert!(index < self.labels.len());
+        self.selected = index;
+        self
+    }
+
+    /// Returns the number of segments
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// True if there are no segments
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Get the index of the selected segment
+    ///
+    /// This is zero if there are no segments.
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Set the selected segment
+    ///
+    /// Panics if `index` is out of bounds. Does not call the selection
+    /// handler.
+    pub fn set_selected(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        assert!(index < self.labels.len());
+        if index != self.selected {
+            self.selected = index;
+            tk.redraw(self.id());
+        }
+    }
+
+    fn segment_at(&self, coord: Coord) -> Option<usize> {
+        self.rects.iter().position(|rect| rect.contains(coord))
+    }
+
+    // Select a segment; returns true if changed
+    fn select(&mut self, tk: &mut dyn TkWindow, index: usize) -> bool {
+        if index != self.selected && index < self.labels.len() {
+            self.selected = index;
+            tk.redraw(self.id());
+            true
+        } else {
+            false
+        }
+    }
+
+    // Handle an event. Returns a selection change as `Ok(index)`, otherwise
+    // a response.
+    fn handle_inner<M>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Result<usize, Response<M>> {
+        match event {
+            Event::Action(Action::NavKey(key)) => {
+                let last = match self.labels.len().checked_sub(1) {
+                    Some(last) => last,
+                    None => return Err(Response::unhandled_action(Action::NavKey(key))),
+                };
+                let index = match key {
+                    NavKey::Left => self.selected.saturating_sub(1),
+                    NavKey::Right => (self.selected + 1).min(last),
+                    NavKey::Home => 0,
+                    NavKey::End => last,
+                    key => return Err(Response::unhandled_action(Action::NavKey(key))),
+                };
+                match self.select(tk, index) {
+                    true => Ok(index),
+                    false => Err(Response::None),
+                }
+            }
+            Event::Action(a) => Err(Response::unhandled_action(a)),
+            Event::Identify => {
+                if let Address::Coord(coord) = addr {
+                    let hover = self.segment_at(coord);
+                    if hover != self.hover {
+                        self.hover = hover;
+                        tk.redraw(self.id());
+                    }
+                }
+                Err(Response::Identify(self.id()))
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.segment_at(coord)));
+                Err(Response::None)
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Err(Response::None)
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.segment_at(coord),
+                    false => None,
+                };
+                match start {
+                    Some(index) if end == start && self.select(tk, index) => Ok(index),
+                    _ => Err(Response::None),
+                }
+            }
+            e => Err(Response::Unhandled(e)),
+        }
+    }
+}
+
+impl<OS: 'static> Widget for SegmentedControl<OS> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        // Segments overlap their predecessor by the left side: this is added
+        // only once, for the whole row.
+        let shared = match axis.vertical() {
+            false => sides.0 .0,
+            true => 0,
+        };
+        let mut solver = layout::RowSolver::<Vec<u32>, _>::new(
+            axis,
+            (Horizontal, self.labels.len()),
+            &mut self.data,
+        )
+        .with_uniform(true);
+        for (n, label) in self.labels.iter().enumerate() {
+            solver.for_child(&mut self.data, n, |axis| {
+                SizeRules::fixed(axis.extract_size(sides.0 + sides.1) - shared)
+                    + size_handle.text_bound(label, TextClass::Button, false, axis)
+            });
+        }
+        let rules = solver.finish(&mut self.data, iter::empty(), iter::empty());
+        rules + SizeRules::fixed(shared)
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.text_margins = size_handle.button_surround();
+        let shared = (self.text_margins.0).0.min(rect.size.0);
+        let inner = Rect {
+            pos: rect.pos + Coord(shared as i32, 0),
+            size: rect.size - Size(shared, 0),
+        };
+        let mut setter = layout::RowSetter::<_, Vec<u32>, _>::new(
+            inner,
+            Margins::ZERO,
+            (Horizontal, self.labels.len()),
+            &mut self.data,
+        );
+        self.rects = (0..self.labels.len())
+            .map(|n| {
+                let mut rect = setter.child_rect(n);
+                rect.pos.0 -= shared as i32;
+                rect.size.0 += shared;
+                rect
+            })
+            .collect();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let props = || TextProperties {
+            class: TextClass::Button,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+        // Draw the selected segment last, so that its borders are on top
+        let order = (0..self.rects.len())
+            .filter(|i| *i != self.selected)
+            .chain(iter::once(self.selected).filter(|i| *i < self.rects.len()));
+        for index in order {
+            let (rect, label) = (&self.rects[index], &self.labels[index]);
+            let selected = index == self.selected;
+            let hl = HighlightState {
+                hover: highlights.hover && self.hover == Some(index),
+                depress: selected,
+                key_focus: highlights.key_focus && selected,
+                char_focus: false,
+                error: false,
+            };
+            draw_handle.button(*rect, hl);
+            let (first, last) = self.text_margins;
+            let text_rect = Rect {
+                pos: rect.pos + first,
+                size: rect.size - (first + last).min(rect.size),
+            };
+            draw_handle.text(text_rect, label, props());
+        }
+    }
+}
+
+impl Handler for SegmentedControl<()> {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<VoidMsg> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(_) => Response::None,
+            Err(r) => r,
+        }
+    }
+}
+
+impl<M, OS: Fn(usize) -> M> Handler for SegmentedControl<OS> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<M> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(index) => ((self.on_select)(index)).into(),
+            Err(r) => r,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Separator` rule
+
+use crate::event;
+use crate::layout::{AxisInfo, Direction, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, Widget};
+
+/// A separator
+///
+/// This is a thin rule, used to visually split groups of widgets, for example
+/// within a [`Column`] or a menu. The direction `D` is that of the rule: use
+/// `Separator<Horizontal>` to split items of a column and
+/// `Separator<Vertical>` to split items of a row.
+///
+/// The rule stretches to fill the available length; its thickness and the
+/// margins on either side are determined by the theme.
+///
+/// [`Column`]: crate::widget::Column
+#[widget]
+#[handler]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Separator<D: Direction> {
+    #[core]
+    core: CoreData,
+    direction: D,
+}
+
+impl<D: Direction + Default> Separator<D> {
+    /// Construct a separator
+    #[inline]
+    pub fn new() -> Self {
+        Separator::new_with_direction(D::default())
+    }
+}
+
+impl<D: Direction> Separator<D> {
+    /// Construct a separator with the given direction
+    ///
+    /// Unlike [`Separator::new`], this is usable in `const` contexts, e.g.
+    /// `Separator::new_with_direction(Horizontal)`.
+    #[inline]
+    pub const fn new_with_direction(direction: D) -> Self {
+        Separator {
+            core: CoreData::new(),
+            direction,
+        }
+    }
+}
+
+impl<D: Direction> Widget for Separator<D> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let (thickness, margin) = size_handle.separator();
+        if self.direction.is_vertical() == axis.vertical() {
+            SizeRules::variable(0, 0)
+        } else {
+            SizeRules::fixed(thickness + 2 * margin)
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        draw_handle.separator(self.core.rect, self.direction.is_vertical());
+    }
+}
@@ -0,0 +1,321 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Shortcut (key combination) capture field
+
+use std::fmt::{self, Debug};
+
+use crate::event::{
+    modifiers_prefix, Action, Address, ElementState, Event, Handler, Manager, ModifiersState,
+    Response, Shortcut, VirtualKeyCode, VoidMsg,
+};
+use crate::geom::{Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+// Text used to size the field
+const SIZE_TEXT: &str = "Ctrl+Alt+Shift+PageDown";
+
+/// A field capturing a key combination, for example to configure shortcuts
+///
+/// When activated (clicked, or with Enter while it has key focus), the field
+/// captures the next key combination pressed, showing held modifiers as they
+/// are pressed. Escape (without modifiers) cancels capture, as does clicking
+/// another control. Keys are received as raw [`Event::Key`] events, thus no
+/// key has its usual effect while capturing.
+///
+/// Combinations marked as reserved (see [`ShortcutEdit::with_reserved`]) are
+/// rejected: the field shows an error and continues capturing.
+///
+/// Optionally, a handler may be set via [`ShortcutEdit::on_change`]; this is
+/// called with each captured shortcut, and its result is returned from the
+/// event handler.
+#[widget]
+#[derive(Clone, Widget)]
+pub struct ShortcutEdit<OS: 'static> {
+    #[core]
+    core: CoreData,
+    shortcut: Option<Shortcut>,
+    reserved: Vec<Shortcut>,
+    capturing: bool,
+    // Modifiers held while capturing
+    held: ModifiersState,
+    // A reserved shortcut, shown until the next key
+    rejected: Option<Shortcut>,
+    surround: (Size, Size),
+    on_change: OS,
+}
+
+impl<OS> Debug for ShortcutEdit<OS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ShortcutEdit {{ core: {:?}, shortcut: {:?}, reserved: {:?}, capturing: {}, ... }}",
+            self.core, self.shortcut, self.reserved, self.capturing,
+        )
+    }
+}
+
+impl ShortcutEdit<()> {
+    /// Construct, with the given shortcut (if any)
+    pub fn new(shortcut: Option<Shortcut>) -> Self {
+        ShortcutEdit {
+            core: Default::default(),
+            shortcut,
+            reserved: vec![],
+            capturing: false,
+            held: ModifiersState::default(),
+            rejected: None,
+            surround: Default::default(),
+            on_change: (),
+        }
+    }
+
+    /// Set the event handler to be called on capture.
+    ///
+    /// The closure `f` is called with the captured shortcut, and the result of
+    /// `f` is returned from the event handler.
+    pub fn on_change<M, OS: Fn(Shortcut) -> M>(self, f: OS) -> ShortcutEdit<OS> {
+        ShortcutEdit {
+            core: self.core,
+            shortcut: self.shortcut,
+            reserved: self.reserved,
+            capturing: self.capturing,
+            held: self.held,
+            rejected: self.rejected,
+            surround: self.surround,
+            on_change: f,
+        }
+    }
+}
+
+impl<OS> ShortcutEdit<OS> {
+    /// Set reserved shortcuts (inline)
+    ///
+    /// These may not be captured.
+    pub fn with_reserved<I: IntoIterator<Item = Shortcut>>(mut self, reserved: I) -> Self {
+        self.reserved = reserved.into_iter().collect();
+        self
+    }
+
+    /// Get the reserved shortcuts
+    #[inline]
+    pub fn reserved(&self) -> &[Shortcut] {
+        &self.reserved
+    }
+
+    /// Set reserved shortcuts
+    ///
+    /// This does not affect the current shortcut.
+    pub fn set_reserved(&mut self, reserved: Vec<Shortcut>) {
+        self.reserved = reserved;
+    }
+
+    /// Get the shortcut, if any
+    #[inline]
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        self.shortcut
+    }
+
+    /// Set or clear the shortcut
+    ///
+    /// This does not call the capture handler.
+    pub fn set_shortcut(&mut self, tk: &mut dyn TkWindow, shortcut: Option<Shortcut>) {
+        self.shortcut = shortcut;
+        tk.redraw(self.id());
+    }
+
+    /// True while capturing a key combination
+    #[inline]
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    fn text(&self) -> String {
+        if let Some(shortcut) = self.rejected {
+            format!("{} is reserved", shortcut)
+        } else if self.capturing {
+            match self.held == ModifiersState::default() {
+                true => "Press a key…".to_string(),
+                false => format!("{}…", modifiers_prefix(self.held)),
+            }
+        } else {
+            match self.shortcut {
+                Some(shortcut) => shortcut.to_string(),
+                None => "None".to_string(),
+            }
+        }
+    }
+
+    fn start_capture(&mut self, tk: &mut dyn TkWindow) {
+        let id = self.id();
+        let mut data = tk.data_mut();
+        if data.request_raw_keys(id) {
+            data.set_char_focus(id);
+            self.capturing = true;
+            self.held = data.modifiers();
+            self.rejected = None;
+        }
+    }
+
+    fn end_capture(&mut self, tk: &mut dyn TkWindow) {
+        if self.capturing {
+            self.capturing = false;
+            self.rejected = None;
+            let id = self.id();
+            let mut data = tk.data_mut();
+            data.release_raw_keys(id);
+            if data.char_focus(id) {
+                data.clear_char_focus();
+            }
+            tk.redraw(id);
+        }
+    }
+
+    // Handle an action. Returns a captured shortcut as `Ok(shortcut)`,
+    // otherwise a response.
+    fn handle_action_inner<M>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        action: Action,
+    ) -> Result<Shortcut, Response<M>> {
+        match action {
+            Action::Activate if !self.capturing => self.start_capture(tk),
+            Action::LostCharFocus(_) => self.end_capture(tk),
+            Action::ReceivedCharacter(_) if self.capturing => (),
+            a => return Err(Response::unhandled_action(a)),
+        }
+        Err(Response::None)
+    }
+
+    // Handle a raw key. Returns a captured shortcut as `Ok(shortcut)`,
+    // otherwise a response.
+    fn handle_key<M>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        vkey: Option<VirtualKeyCode>,
+        state: ElementState,
+    ) -> Result<Shortcut, Response<M>> {
+        let key = match vkey {
+            Some(key) if self.capturing => key,
+            _ => return Err(Response::None),
+        };
+        let pressed = state == ElementState::Pressed;
+        tk.redraw(self.id());
+        if pressed {
+            self.rejected = None;
+        }
+
+        if Shortcut::is_modifier_key(key) {
+            use VirtualKeyCode::*;
+            match key {
+                LShift | RShift => self.held.shift = pressed,
+                LControl | RControl => self.held.ctrl = pressed,
+                LAlt | RAlt => self.held.alt = pressed,
+                _ => self.held.logo = pressed,
+            }
+            return Err(Response::None);
+        }
+        if !pressed {
+            return Err(Response::None);
+        }
+
+        let shortcut = Shortcut::new(key).with_modifiers(self.held);
+        if key == VirtualKeyCode::Escape && self.held == ModifiersState::default() {
+            self.end_capture(tk);
+            Err(Response::None)
+        } else if self.reserved.contains(&shortcut) {
+            self.rejected = Some(shortcut);
+            Err(Response::None)
+        } else {
+            self.shortcut = Some(shortcut);
+            self.end_capture(tk);
+            Ok(shortcut)
+        }
+    }
+
+    fn handle_inner<M>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        event: Event,
+    ) -> Result<Shortcut, Response<M>> {
+        match event {
+            Event::Key { vkey, state, .. } => self.handle_key(tk, vkey, state),
+            Event::Action(action) => self.handle_action_inner(tk, action),
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                Err(Response::None)
+            }
+            Event::PressMove { .. } => Err(Response::None),
+            Event::PressEnd {
+                start_id, end_id, ..
+            } if start_id == Some(self.id()) && start_id == end_id => {
+                self.handle_action_inner(tk, Action::Activate)
+            }
+            Event::Identify => Err(Response::Identify(self.id())),
+            e => Err(Response::Unhandled(e)),
+        }
+    }
+}
+
+impl<OS: 'static> Widget for ShortcutEdit<OS> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.edit_surround();
+        SizeRules::fixed(axis.extract_size(sides.0 + sides.1))
+            + size_handle.text_bound(SIZE_TEXT, TextClass::Edit, false, axis)
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.surround = size_handle.edit_surround();
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let mut highlights = ev_mgr.highlight_state(self.id());
+        highlights.error = self.rejected.is_some();
+        draw_handle.edit_box(self.core.rect, highlights);
+        let (first, last) = self.surround;
+        let rect = self.core.rect;
+        let text_rect = Rect {
+            pos: rect.pos + first,
+            size: rect.size - (first + last).min(rect.size),
+        };
+        let props = TextProperties {
+            class: TextClass::Edit,
+            multi_line: false,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        draw_handle.text(text_rect, &self.text(), props);
+    }
+}
+
+impl Handler for ShortcutEdit<()> {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        match self.handle_inner(tk, event) {
+            Ok(_) => Response::None,
+            Err(r) => r,
+        }
+    }
+}
+
+impl<M, OS: Fn(Shortcut) -> M> Handler for ShortcutEdit<OS> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<M> {
+        match self.handle_inner(tk, event) {
+            Ok(shortcut) => ((self.on_change)(shortcut)).into(),
+            Err(r) => r,
+        }
+    }
+}
@@ -0,0 +1,482 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Sidebar navigation
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, NavKey, PressSource, Response,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// A navigation sidebar with a stack of pages
+///
+/// Entries are listed vertically on the left; the page of the selected entry
+/// is shown in the remaining space. Each entry has an icon (a short text,
+/// typically a single symbol) and a label; when collapsed (see
+/// [`SideNav::set_collapsed`]) only icons are shown.
+///
+/// Entries may be selected by clicking, or with the arrow, home and end keys
+/// when the sidebar has key focus. Pages which are not shown are retained,
+/// thus returning to an entry shows its page as last seen.
+///
+/// Optionally, a handler may be set via [`SideNav::on_select`]; this is
+/// called with the entry's index whenever the selection changes, and its
+/// result is returned from the event handler. Messages from pages are passed
+/// through unchanged.
+#[derive(Clone)]
+pub struct SideNav<W: Widget, OS: 'static> {
+    core: CoreData,
+    entries: Vec<(String, String)>,
+    pages: Vec<W>,
+    selected: usize,
+    collapsed: bool,
+    nav_width: u32,
+    entry_height: u32,
+    margin: u32,
+    press: Option<(PressSource, Option<usize>)>,
+    on_select: OS,
+}
+
+impl<W: Widget, OS> Debug for SideNav<W, OS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SideNav {{ core: {:?}, entries: {:?}, pages: {:?}, selected: {}, collapsed: {}, ... }}",
+            self.core, self.entries, self.pages, self.selected, self.collapsed,
+        )
+    }
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget, OS> WidgetCore for SideNav<W, OS> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "SideNav"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.pages.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.pages.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.pages.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.pages {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.pages {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Default for SideNav<W, ()> {
+    fn default() -> Self {
+        SideNav::new()
+    }
+}
+
+impl<W: Widget> SideNav<W, ()> {
+    /// Construct an empty sidebar
+    pub fn new() -> Self {
+        SideNav {
+            core: Default::default(),
+            entries: vec![],
+            pages: vec![],
+            selected: 0,
+            collapsed: false,
+            nav_width: 0,
+            entry_height: 0,
+            margin: 0,
+            press: None,
+            on_select: (),
+        }
+    }
+
+    /// Set the event handler to be called on selection.
+    ///
+    /// The closure `f` is called with the entry's index when selected, and
+    /// the result of `f` is returned from the event handler.
+    pub fn on_select<M, OS: Fn(usize) -> M>(self, f: OS) -> SideNav<W, OS> {
+        SideNav {
+            core: self.core,
+            entries: self.entries,
+            pages: self.pages,
+            selected: self.selected,
+            collapsed: self.collapsed,
+            nav_width: self.nav_width,
+            entry_height: self.entry_height,
+            margin: self.margin,
+            press: self.press,
+            on_select: f,
+        }
+    }
+}
+
+impl<W: Widget, OS> SideNav<W, OS> {
+    /// Add an entry with the given `icon`, `label` and `page`
+    pub fn with_entry<S: Into<String>, T: Into<String>>(
+        mut self,
+        icon: S,
+        label: T,
+        page: W,
+    ) -> Self {
+        self.entries.push((icon.into(), label.into()));
+        self.pages.push(page);
+        self
+    }
+
+    /// Add an entry with the given `icon`, `label` and `page`
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push_entry<S: Into<String>, T: Into<String>>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        icon: S,
+        label: T,
+        page: W,
+    ) {
+        self.entries.push((icon.into(), label.into()));
+        self.pages.push(page);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Set whether the sidebar is collapsed (shows icons only)
+    #[inline]
+    pub fn with_collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    /// Set whether the sidebar is collapsed (shows icons only)
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if changed.
+    pub fn set_collapsed(&mut self, tk: &mut dyn TkWindow, collapsed: bool) {
+        if collapsed != self.collapsed {
+            self.collapsed = collapsed;
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+
+    /// True if collapsed
+    #[inline]
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Returns the number of entries
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if there are no entries
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the index of the selected entry
+    ///
+    /// This is zero if there are no entries.
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Set the selected entry
+    ///
+    /// Panics if `index` is out of bounds. Does not call the selection
+    /// handler.
+    pub fn set_selected(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        assert!(index < self.entries.len());
+        if index != self.selected {
+            self.selected = index;
+            tk.send_action(TkAction::Redraw);
+        }
+    }
+
+    /// Access a page
+    #[inline]
+    pub fn page(&self, index: usize) -> Option<&W> {
+        self.pages.get(index)
+    }
+
+    /// Access a page mutably
+    #[inline]
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut W> {
+        self.pages.get_mut(index)
+    }
+
+    fn nav_rect(&self) -> Rect {
+        Rect {
+            pos: self.core.rect.pos,
+            size: Size(self.nav_width, self.core.rect.size.1),
+        }
+    }
+
+    fn entry_rect(&self, index: usize) -> Rect {
+        let y = index as u32 * self.entry_height;
+        Rect {
+            pos: self.core.rect.pos + Coord(0, y as i32),
+            size: Size(self.nav_width, self.entry_height),
+        }
+    }
+
+    fn entry_at(&self, coord: Coord) -> Option<usize> {
+        if !self.nav_rect().contains(coord) || self.entry_height == 0 {
+            return None;
+        }
+        let index = (coord.1 - self.core.rect.pos.1) as u32 / self.entry_height;
+        Some(index as usize).filter(|i| *i < self.entries.len())
+    }
+
+    // Select an entry; returns true if changed
+    fn select(&mut self, tk: &mut dyn TkWindow, index: usize) -> bool {
+        if index != self.selected && index < self.entries.len() {
+            self.selected = index;
+            tk.send_action(TkAction::Redraw);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Returns `Some(changed)` if the action is handled
+    fn navigate(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> Option<bool> {
+        let last = self.entries.len().checked_sub(1)?;
+        let index = match key {
+            NavKey::Up => self.selected.saturating_sub(1),
+            NavKey::Down => (self.selected + 1).min(last),
+            NavKey::Home => 0,
+            NavKey::End => last,
+            _ => return None,
+        };
+        Some(self.select(tk, index))
+    }
+}
+
+impl<W: Widget, OS> Widget for SideNav<W, OS> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.entry_height = line_height + 2 * self.margin;
+
+        let mut pages = SizeRules::EMPTY;
+        for page in &mut self.pages {
+            pages = pages.max(layout::child_rules(page, size_handle, axis));
+        }
+
+        if !axis.vertical() {
+            let mut width = 0;
+            for (icon, label) in &self.entries {
+                let mut rules = size_handle.text_bound(icon, TextClass::Label, false, axis);
+                if !self.collapsed {
+                    rules += size_handle.text_bound(label, TextClass::Label, false, axis);
+                    rules = rules + line_height / 2;
+                }
+                width = width.max(rules.min_size());
+            }
+            self.nav_width = width + 2 * self.margin;
+            SizeRules::fixed(self.nav_width) + pages
+        } else {
+            let height = self.entries.len() as u32 * self.entry_height;
+            SizeRules::fixed(height).max(pages)
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let page_rect = Rect {
+            pos: rect.pos + Coord(self.nav_width as i32, 0),
+            size: Size(rect.size.0.saturating_sub(self.nav_width), rect.size.1),
+        };
+        for page in &mut self.pages {
+            page.set_rect(size_handle, page_rect);
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let margin = Coord(self.margin as i32, self.margin as i32);
+        let icon_width = self.entry_height - 2 * self.margin;
+        for (index, (icon, label)) in self.entries.iter().enumerate() {
+            let rect = self.entry_rect(index);
+            if index == self.selected {
+                let hl = HighlightState {
+                    key_focus: highlights.key_focus,
+                    ..HighlightState::default()
+                };
+                draw_handle.button(rect, hl);
+            }
+
+            let mut text_rect = Rect {
+                pos: rect.pos + margin,
+                size: rect.size - Size::uniform(2 * self.margin),
+            };
+            let props = |horiz| TextProperties {
+                class: TextClass::Label,
+                multi_line: false,
+                horiz,
+                vert: Align::Centre,
+            };
+            if self.collapsed {
+                draw_handle.text(text_rect, icon, props(Align::Centre));
+            } else {
+                draw_handle.text(text_rect, icon, props(Align::Begin));
+                let skip = icon_width.min(text_rect.size.0);
+                text_rect.pos.0 += skip as i32;
+                text_rect.size.0 -= skip;
+                draw_handle.text(text_rect, label, props(Align::Begin));
+            }
+        }
+
+        if let Some(page) = self.pages.get(self.selected) {
+            layout::draw_child(page, draw_handle, ev_mgr);
+        }
+    }
+}
+
+impl<W: Widget + Handler, OS> SideNav<W, OS> {
+    // Handle an event. Returns a selection change as `Ok(index)`, otherwise
+    // a response.
+    fn handle_inner(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Result<usize, Response<<W as Handler>::Msg>> {
+        match addr {
+            Address::Id(id) => {
+                for page in &mut self.pages {
+                    if id <= page.id() {
+                        return Err(page.handle(tk, addr, event));
+                    }
+                }
+                debug_assert!(id == self.id(), "Handler::handle: bad WidgetId");
+            }
+            Address::Coord(coord) if !self.nav_rect().contains(coord) => {
+                if let Some(page) = self.pages.get_mut(self.selected) {
+                    return Err(page.handle(tk, addr, event));
+                }
+            }
+            Address::Coord(_) => (),
+        }
+
+        match event {
+            Event::Action(Action::NavKey(key)) => match self.navigate(tk, key) {
+                Some(true) => Ok(self.selected),
+                Some(false) => Err(Response::None),
+                None => Err(Response::unhandled_action(Action::NavKey(key))),
+            },
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.entry_at(coord)));
+                Err(Response::None)
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Err(Response::None)
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.entry_at(coord),
+                    false => None,
+                };
+                match start {
+                    Some(index) if end == start && self.select(tk, index) => Ok(index),
+                    _ => Err(Response::None),
+                }
+            }
+            Event::Identify => Err(Response::Identify(self.id())),
+            e => Err(Response::Unhandled(e)),
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for SideNav<W, ()> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(_) => Response::None,
+            Err(r) => r,
+        }
+    }
+}
+
+impl<W: Widget + Handler, OS: Fn(usize) -> <W as Handler>::Msg> Handler for SideNav<W, OS> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(index) => Response::Msg((self.on_select)(index)),
+            Err(r) => r,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Slider` control
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, Sub};
+
+use crate::event::{self, Action, Address, Event, Handler, Manager, NavKey, PressSource, Response};
+use crate::geom::Rect;
+use crate::layout::{AxisInfo, Direction, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Requirements on type used by [`Slider`]
+///
+/// Implementations are provided for standard numeric types.
+pub trait SliderType:
+    Copy + Debug + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self> + 'static
+{
+    /// Convert to `f64`
+    fn to_f64(self) -> f64;
+
+    /// Convert from `f64`, rounding where necessary
+    fn from_f64(x: f64) -> Self;
+}
+
+macro_rules! impl_slider_int {
+    ($($t:ty),*) => {
+        $(impl SliderType for $t {
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            #[inline]
+            fn from_f64(x: f64) -> Self {
+                x.round() as $t
+            }
+        })*
+    };
+}
+impl_slider_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_slider_float {
+    ($($t:ty),*) => {
+        $(impl SliderType for $t {
+            #[inline]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            #[inline]
+            fn from_f64(x: f64) -> Self {
+                x as $t
+            }
+        })*
+    };
+}
+impl_slider_float!(f32, f64);
+
+/// Get the fraction of the range `min..=max` below `value`
+///
+/// This is computed in `f64` since `max - min` may overflow `T`. Returns
+/// `0.0` where `min == max`.
+pub(crate) fn fraction<T: SliderType>(value: T, (min, max): (T, T)) -> f64 {
+    let (min, max) = (min.to_f64(), max.to_f64());
+    match max - min {
+        span if span > 0.0 => (value.to_f64() - min) / span,
+        _ => 0.0,
+    }
+}
+
+/// A slider
+///
+/// Sliders allow user input of a value from a fixed range, `min..=max`. The
+/// value is always `min + n * step` for some integer `n` (or `max`).
+///
+/// The grip may be dragged, or moved by steps with the arrow keys when the
+/// slider has key focus: left and up decrease the value, right and down
+/// increase it. Page-up and page-down move by ten steps; home and end move to
+/// the limits. Each change is reported as a message with the new value.
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Slider<T: SliderType, D: Direction> {
+    #[core]
+    core: CoreData,
+    direction: D,
+    // Terminology assumes vertical orientation:
+    width: u32,
+    grip_len: u32,
+    range: (T, T),
+    step: T,
+    value: T,
+    press_source: Option<PressSource>,
+    press_offset: i32,
+}
+
+impl<T: SliderType, D: Direction + Default> Slider<T, D> {
+    /// Construct a slider
+    ///
+    /// Values vary between `min` and `max` in increments of `step`. The
+    /// initial value is `min`.
+    #[inline]
+    pub fn new(min: T, max: T, step: T) -> Self {
+        Slider::new_with_direction(min, max, step, D::default())
+    }
+}
+
+impl<T: SliderType, D: Direction> Slider<T, D> {
+    /// Construct a slider with the given direction
+    ///
+    /// Values vary between `min` and `max` in increments of `step`. The
+    /// initial value is `min`.
+    pub fn new_with_direction(min: T, max: T, step: T, direction: D) -> Self {
+        assert!(min <= max);
+        assert!(step > T::default());
+        Slider {
+            core: Default::default(),
+            direction,
+            width: 0,
+            grip_len: 0,
+            range: (min, max),
+            step,
+            value: min,
+            press_source: None,
+            press_offset: 0,
+        }
+    }
+
+    /// Set the initial value
+    #[inline]
+    pub fn with_value(mut self, value: T) -> Self {
+        self.value = self.clamp(value);
+        self
+    }
+
+    /// Get the current value
+    #[inline]
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Set the value
+    ///
+    /// The value is clamped to the range and rounded to the nearest step.
+    pub fn set_value(&mut self, tk: &mut dyn TkWindow, value: T) {
+        let value = self.clamp(value);
+        if value != self.value {
+            self.value = value;
+            tk.redraw(self.id());
+        }
+    }
+
+    // clamp to range and round to a step
+    fn clamp(&self, value: T) -> T {
+        let (min, max) = self.range;
+        // Values not ordered with respect to the range (NaN) go to min
+        if value.partial_cmp(&min) != Some(Ordering::Greater) {
+            return min;
+        } else if value.partial_cmp(&max) != Some(Ordering::Less) {
+            return max;
+        }
+        // Computed in f64 since value - min may overflow
+        let (lo, step) = (min.to_f64(), self.step.to_f64());
+        let value = lo + ((value.to_f64() - lo) / step).round() * step;
+        if value >= max.to_f64() {
+            max
+        } else {
+            T::from_f64(value)
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> u32 {
+        match self.direction.is_vertical() {
+            false => self.core.rect.size.0,
+            true => self.core.rect.size.1,
+        }
+    }
+
+    // translate value to grip position in local coordinates
+    fn position(&self) -> u32 {
+        let len = self.len().saturating_sub(self.grip_len);
+        let frac = fraction(self.value, self.range);
+        ((frac * len as f64).round() as u32).min(len)
+    }
+
+    // true if not equal to old value
+    fn set_position(&mut self, tk: &mut dyn TkWindow, position: u32) -> bool {
+        let len = self.len().saturating_sub(self.grip_len);
+        if len == 0 {
+            return false;
+        }
+        let (min, max) = (self.range.0.to_f64(), self.range.1.to_f64());
+        let frac = position.min(len) as f64 / len as f64;
+        let value = T::from_f64(min + frac * (max - min));
+        self.set_and_changed(tk, value)
+    }
+
+    fn set_and_changed(&mut self, tk: &mut dyn TkWindow, value: T) -> bool {
+        let old = self.value;
+        self.set_value(tk, value);
+        self.value != old
+    }
+
+    // move by `n` steps (negative to decrease)
+    fn step_by(&self, n: i32) -> T {
+        let (min, max) = self.range;
+        // Computed in f64 to avoid overflow (including of unsigned types)
+        let value = self.value.to_f64() + n as f64 * self.step.to_f64();
+        if value <= min.to_f64() {
+            min
+        } else if value >= max.to_f64() {
+            max
+        } else {
+            T::from_f64(value)
+        }
+    }
+}
+
+impl<T: SliderType, D: Direction> Widget for Slider<T, D> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let (thickness, _, min_len) = size_handle.slider();
+        self.width = thickness;
+        if self.direction.is_vertical() == axis.vertical() {
+            SizeRules::variable(min_len, min_len)
+        } else {
+            SizeRules::fixed(thickness)
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        let (thickness, grip_len, _) = size_handle.slider();
+        self.width = thickness;
+        self.core.rect = rect;
+        self.grip_len = grip_len.min(self.len());
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let dir = self.direction.is_vertical();
+        let hl = ev_mgr.highlight_state(self.id());
+        draw_handle.slider(self.core.rect, dir, self.grip_len, self.position(), hl);
+    }
+}
+
+impl<T: SliderType, D: Direction> Handler for Slider<T, D> {
+    type Msg = T;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<T> {
+        match action {
+            Action::NavKey(key) => {
+                let value = match key {
+                    NavKey::Left | NavKey::Up => self.step_by(-1),
+                    NavKey::Right | NavKey::Down => self.step_by(1),
+                    NavKey::PageUp => self.step_by(-10),
+                    NavKey::PageDown => self.step_by(10),
+                    NavKey::Home => self.range.0,
+                    NavKey::End => self.range.1,
+                };
+                match self.set_and_changed(tk, value) {
+                    true => Response::Msg(self.value),
+                    false => Response::None,
+                }
+            }
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<T> {
+        match event {
+            Event::PressStart { source, coord, .. } => {
+                // As with ScrollBar, only a single press is tracked.
+                self.press_source = Some(source);
+                tk.data_mut().request_press_grab(source, self, coord);
+
+                let (pointer, offset) = match self.direction.is_vertical() {
+                    false => (coord.0, self.core.rect.pos.0),
+                    true => (coord.1, self.core.rect.pos.1),
+                };
+                let position = self.position() as i32;
+                let g_start = offset + position;
+
+                if pointer >= g_start && pointer < g_start + self.grip_len as i32 {
+                    // coord is on the grip
+                    self.press_offset = position - pointer;
+                    Response::None
+                } else {
+                    // coord is not on the grip; we move the grip immediately
+                    self.press_offset = -offset - (self.grip_len / 2) as i32;
+                    let position = (pointer + self.press_offset).max(0) as u32;
+                    match self.set_position(tk, position) {
+                        true => Response::Msg(self.value),
+                        false => Response::None,
+                    }
+                }
+            }
+            Event::PressMove { source, coord, .. } if Some(source) == self.press_source => {
+                let pointer = match self.direction.is_vertical() {
+                    false => coord.0,
+                    true => coord.1,
+                };
+                let position = (pointer + self.press_offset).max(0) as u32;
+                match self.set_position(tk, position) {
+                    true => Response::Msg(self.value),
+                    false => Response::None,
+                }
+            }
+            Event::PressEnd { source, .. } if Some(source) == self.press_source => {
+                self.press_source = None;
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
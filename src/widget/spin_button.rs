@@ -0,0 +1,334 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `SpinButton` control
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::class::HasText;
+use crate::event::{
+    self, Action, Address, Event, GrabMode, Handler, HighlightState, Manager, NavKey, PressSource,
+    Response, ScrollDelta,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::widget::{EditBox, SliderType};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Requirements on type used by [`SpinButton`]
+///
+/// This is implemented for every [`SliderType`] which may be parsed and
+/// displayed, including all standard numeric types.
+pub trait SpinType: SliderType + FromStr + Display {}
+
+impl<T: SliderType + FromStr + Display> SpinType for T {}
+
+fn parse<T: SpinType>(text: &str) -> Option<T> {
+    text.trim().parse().ok()
+}
+
+/// Drag distance (in pixels) per step
+const DRAG_STEP: i32 = 8;
+
+/// A numeric entry field with increment and decrement buttons
+///
+/// The value lies in the range `min..=max`. It may be typed, or changed by
+/// `step` via the buttons, the up and down arrow keys or the mouse wheel.
+/// Dragging vertically from a button also changes the value (up to increase);
+/// the cursor is hidden meanwhile and, where the platform supports relative
+/// motion, dragging is not limited by the edges of the screen.
+///
+/// While typing, any text which does not parse to a value within the range is
+/// highlighted as an error; valid text updates the value immediately. On
+/// enter, out-of-range values are clamped and invalid text is reverted to the
+/// current value. Each change is reported as a message with the new value.
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct SpinButton<T: SpinType> {
+    #[core]
+    core: CoreData,
+    #[widget]
+    edit: EditBox<fn(&str) -> Option<T>>,
+    button_rect: Rect,
+    range: (T, T),
+    step: T,
+    value: T,
+    // Source and direction (true if up) of the active press
+    press: Option<(PressSource, bool)>,
+    // Vertical drag distance not yet applied as steps
+    drag: i32,
+}
+
+impl<T: SpinType> SpinButton<T> {
+    /// Construct a spin button
+    ///
+    /// Values vary between `min` and `max`; the buttons change the value by
+    /// `step`. The initial value is `min`.
+    pub fn new(min: T, max: T, step: T) -> Self {
+        assert!(min <= max);
+        assert!(step > T::default());
+        let edit = EditBox::new(min.to_string()).on_activate(parse::<T> as fn(&str) -> Option<T>);
+        SpinButton {
+            core: Default::default(),
+            edit,
+            button_rect: Default::default(),
+            range: (min, max),
+            step,
+            value: min,
+            press: None,
+            drag: 0,
+        }
+    }
+
+    /// Set the initial value
+    ///
+    /// The value is clamped to the range.
+    pub fn with_value(mut self, value: T) -> Self {
+        self.value = self.clamp(value);
+        let text = self.value.to_string();
+        self.edit = EditBox::new(text).on_activate(parse::<T> as fn(&str) -> Option<T>);
+        self
+    }
+
+    /// Get the current value
+    #[inline]
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    /// Set the value
+    ///
+    /// The value is clamped to the range. Any text being edited is replaced.
+    pub fn set_value(&mut self, tk: &mut dyn TkWindow, value: T) {
+        self.value = self.clamp(value);
+        self.edit.set_string(tk, self.value.to_string());
+        self.edit.set_error(tk, false);
+    }
+
+    /// Increase the value by `steps` steps (or decrease if negative)
+    ///
+    /// The result is clamped to the range. Returns true if changed.
+    pub fn step_by(&mut self, tk: &mut dyn TkWindow, steps: i32) -> bool {
+        let value = self.value.to_f64() + steps as f64 * self.step.to_f64();
+        let (min, max) = self.range;
+        let value = T::from_f64(value.max(min.to_f64()).min(max.to_f64()));
+        let changed = value != self.value;
+        self.set_value(tk, value);
+        changed
+    }
+
+    fn clamp(&self, value: T) -> T {
+        let (min, max) = self.range;
+        // Values not ordered with respect to the range (NaN) go to min
+        if value.partial_cmp(&min) != Some(Ordering::Greater) {
+            min
+        } else if value.partial_cmp(&max) != Some(Ordering::Less) {
+            max
+        } else {
+            value
+        }
+    }
+
+    fn in_range(&self, value: T) -> bool {
+        self.range.0 <= value && value <= self.range.1
+    }
+
+    fn step_response(&mut self, tk: &mut dyn TkWindow, steps: i32) -> Response<T> {
+        if self.step_by(tk, steps) {
+            Response::Msg(self.value)
+        } else {
+            Response::None
+        }
+    }
+
+    // Validate text after an edit
+    fn edited(&mut self, tk: &mut dyn TkWindow) -> Response<T> {
+        match parse::<T>(self.edit.get_text()) {
+            Some(value) if self.in_range(value) => {
+                self.edit.set_error(tk, false);
+                if value != self.value {
+                    self.value = value;
+                    return Response::Msg(value);
+                }
+            }
+            _ => self.edit.set_error(tk, true),
+        }
+        Response::None
+    }
+
+    fn edit_response(&mut self, tk: &mut dyn TkWindow, r: Response<Option<T>>) -> Response<T> {
+        match r {
+            Response::Msg(parsed) => {
+                // On enter, clamp or revert
+                let old = self.value;
+                self.set_value(tk, parsed.unwrap_or(old));
+                match self.value != old {
+                    true => Response::Msg(self.value),
+                    false => Response::None,
+                }
+            }
+            Response::Unhandled(Event::Action(Action::NavKey(NavKey::Up))) => {
+                self.step_response(tk, 1)
+            }
+            Response::Unhandled(Event::Action(Action::NavKey(NavKey::Down))) => {
+                self.step_response(tk, -1)
+            }
+            Response::Unhandled(Event::Action(Action::Scroll(delta))) => self.scroll(tk, delta),
+            Response::None => Response::None,
+            Response::Identify(id) => Response::Identify(id),
+            Response::Unhandled(e) => Response::Unhandled(e),
+        }
+    }
+
+    fn scroll(&mut self, tk: &mut dyn TkWindow, delta: ScrollDelta) -> Response<T> {
+        let y = match delta {
+            ScrollDelta::LineDelta(_, y) => y,
+            ScrollDelta::PixelDelta(d) => d.1 as f32,
+        };
+        match y {
+            y if y > 0.0 => self.step_response(tk, 1),
+            y if y < 0.0 => self.step_response(tk, -1),
+            _ => Response::unhandled_action(Action::Scroll(delta)),
+        }
+    }
+
+    // Rects of the up and down buttons
+    fn button_rects(&self) -> (Rect, Rect) {
+        let rect = self.button_rect;
+        let half = rect.size.1 / 2;
+        let up = Rect {
+            pos: rect.pos,
+            size: Size(rect.size.0, half),
+        };
+        let down = Rect {
+            pos: rect.pos + Coord(0, half as i32),
+            size: Size(rect.size.0, rect.size.1 - half),
+        };
+        (up, down)
+    }
+}
+
+impl<T: SpinType> Widget for SpinButton<T> {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let rules = layout::child_rules(&mut self.edit, size_handle, axis);
+        if !axis.vertical() {
+            let margin = size_handle.inner_margin();
+            let button = size_handle.line_height(TextClass::Button);
+            self.button_rect.size.0 = button + 2 * margin.0;
+            rules + self.button_rect.size.0
+        } else {
+            rules
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let button_width = self.button_rect.size.0.min(rect.size.0);
+        let edit_size = Size(rect.size.0 - button_width, rect.size.1);
+        self.edit.set_rect(
+            size_handle,
+            Rect {
+                pos: rect.pos,
+                size: edit_size,
+            },
+        );
+        self.button_rect = Rect {
+            pos: rect.pos + Coord(edit_size.0 as i32, 0),
+            size: Size(button_width, rect.size.1),
+        };
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        layout::draw_child(&self.edit, draw_handle, ev_mgr);
+        let highlights = ev_mgr.highlight_state(self.id());
+        let pressed = self.press.map(|p| p.1);
+        let (up, down) = self.button_rects();
+        for (rect, is_up, label) in [(up, true, "▴"), (down, false, "▾")].iter().cloned() {
+            let hl = HighlightState {
+                hover: highlights.hover && rect.contains(ev_mgr.last_mouse_coord()),
+                depress: pressed == Some(is_up),
+                ..HighlightState::default()
+            };
+            draw_handle.button(rect, hl);
+            let props = TextProperties {
+                class: TextClass::Button,
+                multi_line: false,
+                horiz: Align::Centre,
+                vert: Align::Centre,
+            };
+            draw_handle.text(rect, label, props);
+        }
+    }
+}
+
+impl<T: SpinType> Handler for SpinButton<T> {
+    type Msg = T;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<T> {
+        match action {
+            Action::Scroll(delta) => self.scroll(tk, delta),
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, addr: Address, event: Event) -> Response<T> {
+        let to_edit = match addr {
+            Address::Id(id) => id <= self.edit.id(),
+            Address::Coord(coord) => self.edit.rect().contains(coord),
+        };
+        if to_edit {
+            let edit = match event {
+                Event::Action(Action::ReceivedCharacter(c)) => c != '\r',
+                _ => false,
+            };
+            let r = self.edit.handle(tk, addr, event);
+            return match edit {
+                true => self.edited(tk),
+                false => self.edit_response(tk, r),
+            };
+        }
+
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                let (up, down) = self.button_rects();
+                let is_up = match coord {
+                    coord if up.contains(coord) => true,
+                    coord if down.contains(coord) => false,
+                    _ => return Response::None,
+                };
+                let mode = GrabMode::Relative;
+                tk.data_mut()
+                    .request_press_grab_mode(source, self, coord, mode);
+                self.press = Some((source, is_up));
+                self.drag = 0;
+                tk.redraw(self.id());
+                self.step_response(tk, if is_up { 1 } else { -1 })
+            }
+            Event::PressMove { source, delta, .. } if self.press.map(|p| p.0) == Some(source) => {
+                self.drag -= delta.1;
+                let steps = self.drag / DRAG_STEP;
+                self.drag -= steps * DRAG_STEP;
+                match steps {
+                    0 => Response::None,
+                    steps => self.step_response(tk, steps),
+                }
+            }
+            Event::PressEnd { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                self.press = None;
+                tk.redraw(self.id());
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
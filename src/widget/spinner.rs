@@ -0,0 +1,97 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Activity indicator
+
+use std::time::{Duration, Instant};
+
+use crate::event;
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle, SpinnerStyle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Duration of one revolution
+const PERIOD: Duration = Duration::from_millis(1000);
+
+/// An indeterminate activity indicator
+///
+/// While running, this shows a rotating animation, indicating that work of
+/// unknown duration is in progress. Nothing is drawn while stopped, although
+/// space is still reserved. [`Spinner::new`] constructs a running spinner;
+/// the default value is stopped. The appearance may be chosen with
+/// [`Spinner::with_style`]; by default this is a ring of spokes.
+///
+/// The animation only requests frames while the spinner is drawn (see
+/// [`Manager::request_frame`]), thus it pauses while hidden. Like other
+/// animations, it also pauses while the window is
+/// [idle](crate::event::Manager::is_idle).
+///
+/// [`Manager::request_frame`]: crate::event::Manager::request_frame
+#[widget]
+#[handler]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct Spinner {
+    #[core]
+    core: CoreData,
+    start: Option<Instant>,
+    style: SpinnerStyle,
+}
+
+impl Spinner {
+    /// Construct, running
+    pub fn new() -> Self {
+        Spinner {
+            core: Default::default(),
+            start: Some(Instant::now()),
+            style: SpinnerStyle::default(),
+        }
+    }
+
+    /// Construct a running spinner, drawn as a rotating arc
+    ///
+    /// This is a shortcut for `Spinner::new().with_style(SpinnerStyle::Arc)`.
+    pub fn arc() -> Self {
+        Spinner::new().with_style(SpinnerStyle::Arc)
+    }
+
+    /// Set the style (chain style)
+    #[inline]
+    pub fn with_style(mut self, style: SpinnerStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// True while running
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Start or stop the animation
+    pub fn set_running(&mut self, tk: &mut dyn TkWindow, running: bool) {
+        if running != self.is_running() {
+            self.start = match running {
+                true => Some(Instant::now()),
+                false => None,
+            };
+            tk.redraw(self.id());
+        }
+    }
+}
+
+impl Widget for Spinner {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        SizeRules::fixed(axis.extract_size(size_handle.spinner()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        if let Some(start) = self.start {
+            let phase = (start.elapsed().as_secs_f32() / PERIOD.as_secs_f32()).fract();
+            draw_handle.spinner(self.core.rect, phase, self.style);
+            ev_mgr.request_frame();
+        }
+    }
+}
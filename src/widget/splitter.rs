@@ -0,0 +1,388 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Splitter (paned) containers
+
+use std::any::Any;
+
+use crate::event::{Address, Event, Handler, HighlightState, Manager, PressSource, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, Direction, Horizontal, LayoutOverrides, SizeRules, Vertical};
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// A row of widgets separated by draggable handles
+///
+/// See documentation of [`Splitter`] type.
+pub type RowSplitter<W> = Splitter<Horizontal, W>;
+
+/// A column of widgets separated by draggable handles
+///
+/// See documentation of [`Splitter`] type.
+pub type ColumnSplitter<W> = Splitter<Vertical, W>;
+
+/// A row/column of widgets separated by draggable handles
+///
+/// Children are arranged as in a [`List`], but with a handle between each
+/// pair of adjacent children. Dragging a handle moves the split between its
+/// neighbours, within the limits of their minimum sizes.
+///
+/// Initially, space is allocated as in a [`List`]. Afterwards, the positions
+/// of the handles are kept as [`LayoutOverrides`]: fractions of the available
+/// length (the *split*), thus are preserved proportionally when the splitter
+/// is resized or reconfigured.
+/// The split may be read via [`Splitter::split`] and restored with
+/// [`Splitter::with_split`], for example to persist it between sessions.
+///
+/// [`List`]: crate::widget::List
+#[derive(Clone, Debug)]
+pub struct Splitter<D: Direction, W: Widget> {
+    core: CoreData,
+    widgets: Vec<W>,
+    direction: D,
+    // main-axis size rules of each child
+    rules: Vec<SizeRules>,
+    split: LayoutOverrides,
+    handle_size: u32,
+    // dragged handle: press source, handle index and press offset
+    drag: Option<(PressSource, usize, i32)>,
+    hover: Option<usize>,
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<D: Direction, W: Widget> WidgetCore for Splitter<D, W> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "Splitter"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.widgets.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.widgets {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.widgets {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<D: Direction, W: Widget> Widget for Splitter<D, W> {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.handle_size = size_handle.splitter();
+        if axis.vertical() != self.direction.is_vertical() {
+            return self
+                .widgets
+                .iter_mut()
+                .fold(SizeRules::EMPTY, |rules, child| {
+                    rules.max(layout::child_rules(child, size_handle, axis))
+                });
+        }
+
+        self.rules.clear();
+        let mut rules = SizeRules::EMPTY;
+        for child in &mut self.widgets {
+            let child_rules = layout::child_rules(child, size_handle, axis);
+            self.rules.push(child_rules);
+            rules += child_rules;
+        }
+        let handles = self.widgets.len().saturating_sub(1) as u32;
+        rules + handles * self.handle_size
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.layout(size_handle);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let dragged = self.drag.map(|d| d.1);
+        for (index, child) in self.widgets.iter().enumerate() {
+            layout::draw_child(child, draw_handle, ev_mgr);
+            if index + 1 < self.widgets.len() {
+                let hl = HighlightState {
+                    hover: highlights.hover && self.hover == Some(index),
+                    depress: dragged == Some(index),
+                    ..HighlightState::default()
+                };
+                let rect = self.handle_rect(index);
+                draw_handle.splitter(rect, self.direction.is_vertical(), hl);
+            }
+        }
+    }
+}
+
+impl<D: Direction, W: Widget + Handler> Handler for Splitter<D, W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match addr {
+            Address::Id(id) => {
+                for child in &mut self.widgets {
+                    if id <= child.id() {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+                debug_assert!(id == self.id(), "Handler::handle: bad WidgetId");
+            }
+            Address::Coord(coord) => {
+                for child in &mut self.widgets {
+                    if child.rect().contains(coord) {
+                        return child.handle(tk, addr, event);
+                    }
+                }
+            }
+        }
+
+        // The event is for a handle (or the space between children)
+        match event {
+            Event::Identify => {
+                if let Address::Coord(coord) = addr {
+                    let hover = self.handle_at(coord);
+                    if hover != self.hover {
+                        self.hover = hover;
+                        tk.redraw(self.id());
+                    }
+                }
+                Response::Identify(self.id())
+            }
+            Event::PressStart { source, coord } if source.is_primary() => {
+                match self.handle_at(coord) {
+                    Some(index) => {
+                        tk.data_mut().request_press_grab(source, self, coord);
+                        let start = self.main(self.handle_rect(index).pos);
+                        self.drag = Some((source, index, self.main(coord) - start));
+                        tk.redraw(self.id());
+                        Response::None
+                    }
+                    None => Response::Unhandled(Event::PressStart { source, coord }),
+                }
+            }
+            Event::PressMove { source, coord, .. } => match self.drag {
+                Some((s, index, offset)) if s == source => {
+                    self.drag_handle(tk, index, self.main(coord) - offset);
+                    Response::None
+                }
+                _ => Response::Unhandled(event),
+            },
+            Event::PressEnd { source, .. } if self.drag.map(|d| d.0) == Some(source) => {
+                self.drag = None;
+                tk.redraw(self.id());
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
+
+impl<D: Direction + Default, W: Widget> Splitter<D, W> {
+    /// Construct a new instance
+    ///
+    /// This constructor is available where the direction is determined by the
+    /// type: for `D: Direction + Default`. In other cases, use
+    /// [`Splitter::new_with_direction`].
+    pub fn new(widgets: Vec<W>) -> Self {
+        Splitter::new_with_direction(Default::default(), widgets)
+    }
+}
+
+impl<D: Direction, W: Widget> Splitter<D, W> {
+    /// Construct a new instance with explicit direction
+    pub fn new_with_direction(direction: D, widgets: Vec<W>) -> Self {
+        Splitter {
+            core: Default::default(),
+            widgets,
+            direction,
+            rules: vec![],
+            split: LayoutOverrides::default(),
+            handle_size: 0,
+            drag: None,
+            hover: None,
+        }
+    }
+
+    /// Set the split (inline)
+    ///
+    /// See [`Splitter::split`]. The split is ignored unless it has one entry
+    /// per handle (one less than the number of children).
+    #[inline]
+    pub fn with_split(mut self, split: Vec<f32>) -> Self {
+        self.split = LayoutOverrides::from_ends(split);
+        self
+    }
+
+    /// Get the split
+    ///
+    /// This is the position of the end of each child (except the last), as
+    /// a fraction of the length available to children, in increasing order.
+    /// It is empty until the splitter is first sized, unless set explicitly.
+    #[inline]
+    pub fn split(&self) -> &[f32] {
+        self.split.ends()
+    }
+
+    /// Set the split
+    ///
+    /// See [`Splitter::with_split`].
+    pub fn set_split(&mut self, tk: &mut dyn TkWindow, split: Vec<f32>) {
+        self.split = LayoutOverrides::from_ends(split);
+        tk.with_size_handle(&mut |size_handle| self.layout(size_handle));
+        tk.redraw(self.id());
+    }
+
+    /// Get a reference to the children
+    #[inline]
+    pub fn widgets(&self) -> &[W] {
+        &self.widgets
+    }
+
+    /// Get a mutable reference to the children
+    ///
+    /// Children may be modified but not added or removed.
+    #[inline]
+    pub fn widgets_mut(&mut self) -> &mut [W] {
+        &mut self.widgets
+    }
+
+    // Position of `coord` along the main axis
+    fn main(&self, coord: Coord) -> i32 {
+        match self.direction.is_vertical() {
+            false => coord.0,
+            true => coord.1,
+        }
+    }
+
+    // Length available to children
+    fn content_len(&self) -> u32 {
+        let size = self.core.rect.size;
+        let len = match self.direction.is_vertical() {
+            false => size.0,
+            true => size.1,
+        };
+        let handles = self.widgets.len().saturating_sub(1) as u32;
+        len.saturating_sub(handles * self.handle_size)
+    }
+
+    // Rect of the handle after child `index`
+    fn handle_rect(&self, index: usize) -> Rect {
+        let rect = self.widgets[index].rect();
+        match self.direction.is_vertical() {
+            false => Rect {
+                pos: rect.pos + Coord(rect.size.0 as i32, 0),
+                size: Size(self.handle_size, rect.size.1),
+            },
+            true => Rect {
+                pos: rect.pos + Coord(0, rect.size.1 as i32),
+                size: Size(rect.size.0, self.handle_size),
+            },
+        }
+    }
+
+    // Index of the handle at `coord`, if any
+    fn handle_at(&self, coord: Coord) -> Option<usize> {
+        let handles = self.widgets.len().saturating_sub(1);
+        (0..handles).find(|index| self.handle_rect(*index).contains(coord))
+    }
+
+    // Move handle `index` to start at `pos` (along the main axis)
+    fn drag_handle(&mut self, tk: &mut dyn TkWindow, index: usize, pos: i32) {
+        let start = self.main(self.core.rect.pos) + index as i32 * self.handle_size as i32;
+        let content = self.content_len().max(1) as f32;
+        if self.split.move_end(index, (pos - start) as f32 / content) {
+            tk.with_size_handle(&mut |size_handle| self.layout(size_handle));
+            tk.redraw(self.id());
+        }
+    }
+
+    // Set child rects (and the split, if not yet known)
+    fn layout(&mut self, size_handle: &mut dyn SizeHandle) {
+        let n = self.widgets.len();
+        if n == 0 || self.rules.len() != n {
+            return;
+        }
+        let content = self.content_len();
+        let mut sizes = vec![0; n];
+
+        if !self.split.apply(&mut sizes, &self.rules, content) {
+            // Solve as for a list, then remember the result
+            let total = self.rules.iter().fold(SizeRules::EMPTY, |a, r| a + *r);
+            let mut rules = self.rules.clone();
+            rules.push(total);
+            SizeRules::solve_seq(&mut sizes, &rules, content);
+            self.split.record(&sizes);
+        }
+
+        let rect = self.core.rect;
+        let mut pos = rect.pos;
+        for (child, size) in self.widgets.iter_mut().zip(sizes) {
+            let step = size + self.handle_size;
+            let (child_size, step) = match self.direction.is_vertical() {
+                false => (Size(size, rect.size.1), Coord(step as i32, 0)),
+                true => (Size(rect.size.0, size), Coord(0, step as i32)),
+            };
+            child.set_rect(
+                size_handle,
+                Rect {
+                    pos,
+                    size: child_size,
+                },
+            );
+            pos = pos + step;
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Status bar
+
+use std::time::{Duration, Instant};
+
+use crate::event::{self, Handler, VoidMsg};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// A status bar
+///
+/// This shows a row of permanent sections (e.g. cursor position or encoding),
+/// aligned to the right, and to their left space for transient messages, as
+/// pushed by [`StatusBar::push_message`].
+///
+/// A message is shown until it expires or is replaced by another message.
+/// Expired messages are not drawn, but are only removed (with a redraw) by
+/// [`StatusBar::poll`], which should be called regularly, for example from a
+/// [`Callback::Repeat`] window callback.
+///
+/// [`Callback::Repeat`]: crate::event::Callback::Repeat
+#[widget]
+#[derive(Clone, Debug, Default, Widget)]
+pub struct StatusBar {
+    #[core]
+    core: CoreData,
+    sections: Vec<String>,
+    // Ideal widths of sections, including margins
+    section_widths: Vec<u32>,
+    section_rects: Vec<Rect>,
+    message: Option<(String, Instant)>,
+    message_rect: Rect,
+}
+
+impl StatusBar {
+    /// Construct, without sections
+    pub fn new() -> Self {
+        StatusBar::default()
+    }
+
+    /// Add a permanent section (chain style)
+    ///
+    /// Sections are shown from left to right in the order added.
+    pub fn with_section<S: Into<String>>(mut self, text: S) -> Self {
+        self.sections.push(text.into());
+        self
+    }
+
+    /// Number of permanent sections
+    #[inline]
+    pub fn num_sections(&self) -> usize {
+        self.sections.len()
+    }
+
+    /// Get the text of a section
+    #[inline]
+    pub fn section(&self, index: usize) -> Option<&str> {
+        self.sections.get(index).map(|s| s.as_str())
+    }
+
+    /// Set the text of a section
+    ///
+    /// Sections are sized to fit their text; if the new text is longer, this
+    /// triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_section<S: Into<String>>(&mut self, tk: &mut dyn TkWindow, index: usize, text: S) {
+        let text = text.into();
+        let longer = text.len() > self.sections[index].len();
+        self.sections[index] = text;
+        if longer {
+            tk.send_action(TkAction::Reconfigure);
+        } else {
+            tk.redraw(self.id());
+        }
+    }
+
+    /// Show a transient message for the given `duration`
+    ///
+    /// This replaces any current message.
+    pub fn push_message<S: Into<String>>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        text: S,
+        duration: Duration,
+    ) {
+        self.message = Some((text.into(), Instant::now() + duration));
+        tk.redraw(self.id());
+    }
+
+    /// Remove any current message
+    pub fn clear_message(&mut self, tk: &mut dyn TkWindow) {
+        if self.message.take().is_some() {
+            tk.redraw(self.id());
+        }
+    }
+
+    /// Get the current message, if any (and not expired)
+    pub fn message(&self) -> Option<&str> {
+        match self.message {
+            Some((ref text, expiry)) if expiry > Instant::now() => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Remove the current message if expired
+    ///
+    /// This should be called regularly; no redraw occurs unless a message
+    /// expires.
+    pub fn poll(&mut self, tk: &mut dyn TkWindow) {
+        if self.message.is_some() && self.message().is_none() {
+            self.message = None;
+            tk.redraw(self.id());
+        }
+    }
+}
+
+impl Widget for StatusBar {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let margin = axis.extract_size(size_handle.inner_margin());
+        let text = |text: &str, size_handle: &mut dyn SizeHandle| {
+            size_handle.text_bound(text, TextClass::Label, false, axis) + 2 * margin
+        };
+        // The message area may shrink to nothing
+        let mut rules = SizeRules::variable(0, 0).max(text("", size_handle));
+        if !axis.vertical() {
+            self.section_widths.clear();
+            for section in &self.sections {
+                let section_rules = text(section, size_handle);
+                self.section_widths.push(section_rules.ideal_size());
+                rules += section_rules;
+            }
+        } else {
+            for section in &self.sections {
+                rules = rules.max(text(section, size_handle));
+            }
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let margin = size_handle.inner_margin().0;
+        // Text rects, excluding margins
+        let text_rect = |x: i32, width: u32| Rect {
+            pos: Coord(x + margin as i32, rect.pos.1),
+            size: Size(width.saturating_sub(2 * margin), rect.size.1),
+        };
+
+        // Sections keep their ideal width, from the right
+        let mut right = rect.pos.0 + rect.size.0 as i32;
+        self.section_rects.clear();
+        for width in self.section_widths.iter().rev() {
+            let width = (*width).min((right - rect.pos.0) as u32);
+            right -= width as i32;
+            self.section_rects.insert(0, text_rect(right, width));
+        }
+        self.message_rect = text_rect(rect.pos.0, (right - rect.pos.0) as u32);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
+        let props = |horiz| TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz,
+            vert: Align::Centre,
+        };
+        if let Some(text) = self.message() {
+            draw_handle.text(self.message_rect, text, props(Align::Begin));
+        }
+        for (rect, text) in self.section_rects.iter().zip(&self.sections) {
+            draw_handle.text(*rect, text, props(Align::Centre));
+        }
+    }
+}
+
+impl Handler for StatusBar {
+    type Msg = VoidMsg;
+}
@@ -0,0 +1,328 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! `Switch` control
+
+use std::fmt::{self, Debug};
+use std::time::{Duration, Instant};
+
+use crate::class::{HasBool, HasText};
+use crate::event::{self, Action, Address, Event, Handler, PressSource, Response, VoidMsg};
+use crate::geom::{Rect, Size};
+use crate::layout::{AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+/// Duration of the knob animation
+const ANIMATION: Duration = Duration::from_millis(150);
+
+/// An on/off switch with optional label
+///
+/// This is functionally equivalent to a (two-state) [`CheckBox`], but drawn as
+/// a track with a sliding knob. Activating the switch (by click or with the
+/// keyboard) toggles it; the knob may also be dragged. The knob's movement is
+/// animated.
+///
+/// As with [`CheckBox`], a handler may be set via [`Switch::on_toggle`].
+///
+/// [`CheckBox`]: crate::widget::CheckBox
+#[widget]
+#[derive(Clone, Default, Widget)]
+pub struct Switch<OT: 'static> {
+    #[core]
+    core: CoreData,
+    switch_rect: Rect,
+    text_pos_x: i32,
+    label: String,
+    state: bool,
+    // Animation start time and initial knob position
+    animation: Option<(Instant, f32)>,
+    press: Option<(PressSource, i32)>,
+    drag_pos: Option<f32>,
+    on_toggle: OT,
+}
+
+/// A [`Switch`] reporting its new state as a `bool` message
+///
+/// Construct with [`ToggleSwitch::new_toggle`]. Unlike [`Switch::on_toggle`],
+/// this has a nameable type, and so may be stored in a struct field.
+pub type ToggleSwitch = Switch<fn(bool) -> bool>;
+
+impl<H> Debug for Switch<H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Switch {{ core: {:?}, state: {:?}, label: {:?}, ... }}",
+            self.core, self.state, self.label
+        )
+    }
+}
+
+impl<OT: 'static> Widget for Switch<OT> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut r = SizeRules::fixed(axis.extract_size(size_handle.switch()));
+        if !self.label.is_empty() {
+            if !axis.vertical() {
+                r += SizeRules::fixed(size_handle.outer_margin().0);
+            }
+            r += size_handle.text_bound(&self.label, TextClass::Label, true, axis);
+        }
+        r
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        // We center the switch vertically and align to the left
+        let size = size_handle.switch();
+        let mut pos = rect.pos;
+        let extra_height = rect.size.1 as i32 - size.1 as i32;
+        pos.1 += extra_height / 2;
+        self.switch_rect = Rect { pos, size };
+        // Text is drawn in the area to the right of this
+        let margin = size_handle.outer_margin().0;
+        self.text_pos_x = pos.0 + (margin + size.0) as i32;
+        self.core_data_mut().rect = rect;
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        draw_handle.switch(self.switch_rect, self.knob_pos(), highlights);
+        let mut text_rect = self.core.rect;
+        text_rect.pos.0 = self.text_pos_x;
+        let indent = (self.text_pos_x - self.core.rect.pos.0) as u32;
+        text_rect.size.0 = text_rect.size.0.saturating_sub(indent);
+        if !self.label.is_empty() {
+            let props = TextProperties {
+                class: TextClass::Label,
+                multi_line: true,
+                horiz: Align::Begin,
+                vert: Align::Centre,
+            };
+            draw_handle.text(text_rect, &self.label, props);
+        }
+    }
+}
+
+impl<M, OT: Fn(bool) -> M> Switch<OT> {
+    /// Construct a switch with a given `label` which calls `f` when toggled.
+    ///
+    /// This is a shortcut for `Switch::new(label).on_toggle(f)`.
+    ///
+    /// Switch labels are optional; if no label is desired, use an empty
+    /// string.
+    ///
+    /// The closure `f` is called with the new state of the switch when
+    /// toggled, and the result of `f` is returned from the event handler.
+    pub fn new_on<S: Into<String>>(label: S, f: OT) -> Self {
+        Switch {
+            core: Default::default(),
+            switch_rect: Default::default(),
+            text_pos_x: 0,
+            label: label.into(),
+            state: false,
+            animation: None,
+            press: None,
+            drag_pos: None,
+            on_toggle: f,
+        }
+    }
+}
+
+impl Switch<()> {
+    /// Construct a switch with a given `label`.
+    ///
+    /// Switch labels are optional; if no label is desired, use an empty
+    /// string.
+    pub fn new<S: Into<String>>(label: S) -> Self {
+        Switch {
+            core: Default::default(),
+            switch_rect: Default::default(),
+            text_pos_x: 0,
+            label: label.into(),
+            state: false,
+            animation: None,
+            press: None,
+            drag_pos: None,
+            on_toggle: (),
+        }
+    }
+
+    /// Set the event handler to be called on toggle.
+    ///
+    /// The closure `f` is called with the new state of the switch when
+    /// toggled, and the result of `f` is returned from the event handler.
+    pub fn on_toggle<M, OT: Fn(bool) -> M>(self, f: OT) -> Switch<OT> {
+        Switch {
+            core: self.core,
+            switch_rect: self.switch_rect,
+            text_pos_x: self.text_pos_x,
+            label: self.label,
+            state: self.state,
+            animation: self.animation,
+            press: self.press,
+            drag_pos: self.drag_pos,
+            on_toggle: f,
+        }
+    }
+}
+
+impl ToggleSwitch {
+    /// Construct a toggle switch with a given `label`
+    ///
+    /// Each toggle is reported as a message with the new state.
+    pub fn new_toggle<S: Into<String>>(label: S) -> Self {
+        Switch::new_on(label, |state| state)
+    }
+}
+
+impl<OT: 'static> Switch<OT> {
+    /// Set the initial state of the switch.
+    pub fn set_state(&mut self, state: bool) {
+        self.state = state;
+        self.animation = None;
+    }
+
+    /// Set the initial state of the switch.
+    pub fn state(mut self, state: bool) -> Self {
+        self.set_state(state);
+        self
+    }
+
+    /// Get the state
+    #[inline]
+    pub fn get_state(&self) -> bool {
+        self.state
+    }
+
+    // Current knob position, from 0 (off) to 1 (on)
+    fn knob_pos(&self) -> f32 {
+        let target = if self.state { 1.0 } else { 0.0 };
+        if let Some(pos) = self.drag_pos {
+            return pos;
+        }
+        match self.animation {
+            Some((start, from)) => {
+                let t = start.elapsed().as_secs_f32() / ANIMATION.as_secs_f32();
+                if t >= 1.0 {
+                    target
+                } else {
+                    from + t * (target - from)
+                }
+            }
+            None => target,
+        }
+    }
+
+    // Set the state, animating the knob from its current position
+    fn set_animated(&mut self, tk: &mut dyn TkWindow, state: bool) {
+        self.animation = Some((Instant::now(), self.knob_pos()));
+        self.drag_pos = None;
+        self.state = state;
+        tk.data_mut().request_animation(ANIMATION);
+    }
+
+    // Handle events; returns `Ok(state)` when toggled
+    fn handle_switch<M>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        event: Event,
+    ) -> Result<bool, Response<M>> {
+        match event {
+            Event::Action(Action::Activate) => {
+                self.set_animated(tk, !self.state);
+                Ok(self.state)
+            }
+            Event::Action(a) => Err(Response::unhandled_action(a)),
+            Event::Identify => Err(Response::Identify(self.id())),
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, coord.0));
+                Err(Response::None)
+            }
+            Event::PressMove { source, coord, .. } => match self.press {
+                Some((s, x)) if s == source => {
+                    // Drag the knob once the press has moved far enough
+                    let Size(w, h) = self.switch_rect.size;
+                    let travel = w.saturating_sub(h).max(1) as f32;
+                    let dx = (coord.0 - x) as f32;
+                    if self.drag_pos.is_some() || dx.abs() >= 0.25 * travel {
+                        let base = if self.state { 1.0 } else { 0.0 };
+                        self.drag_pos = Some((base + dx / travel).clamp(0.0, 1.0));
+                        tk.redraw(self.id());
+                    }
+                    Err(Response::None)
+                }
+                _ => Err(Response::Unhandled(event)),
+            },
+            Event::PressEnd { source, end_id, .. } => match self.press {
+                Some((s, _)) if s == source => {
+                    self.press = None;
+                    if let Some(pos) = self.drag_pos {
+                        let state = pos >= 0.5;
+                        let changed = state != self.state;
+                        self.set_animated(tk, state);
+                        if changed {
+                            return Ok(state);
+                        }
+                    } else if end_id == Some(self.id()) {
+                        self.set_animated(tk, !self.state);
+                        return Ok(self.state);
+                    }
+                    Err(Response::None)
+                }
+                _ => Err(Response::Unhandled(event)),
+            },
+            e => Err(Response::Unhandled(e)),
+        }
+    }
+}
+
+impl<H> HasBool for Switch<H> {
+    fn get_bool(&self) -> bool {
+        self.state
+    }
+
+    fn set_bool(&mut self, tk: &mut dyn TkWindow, state: bool) {
+        self.set_state(state);
+        tk.redraw(self.id());
+    }
+}
+
+impl<H> HasText for Switch<H> {
+    fn get_text(&self) -> &str {
+        &self.label
+    }
+
+    fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
+        self.label = text;
+        tk.redraw(self.id());
+    }
+}
+
+impl Handler for Switch<()> {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        match self.handle_switch(tk, event) {
+            Ok(_) => Response::None,
+            Err(r) => r,
+        }
+    }
+}
+
+impl<M, H: Fn(bool) -> M> Handler for Switch<H> {
+    type Msg = M;
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<M> {
+        match self.handle_switch(tk, event) {
+            Ok(state) => ((self.on_toggle)(state)).into(),
+            Err(r) => r,
+        }
+    }
+}
@@ -0,0 +1,595 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Tabbed stack (notebook)
+
+use std::any::Any;
+use std::fmt::{self, Debug};
+
+use crate::event::{
+    self, Action, Address, Event, Handler, HighlightState, NavKey, PressSource, Response,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// Placement of the tab bar of a [`TabbedStack`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TabPosition {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl TabPosition {
+    #[inline]
+    fn is_vertical(self) -> bool {
+        match self {
+            TabPosition::Top | TabPosition::Bottom => false,
+            TabPosition::Left | TabPosition::Right => true,
+        }
+    }
+}
+
+/// A stack of pages with a bar of tabs
+///
+/// One page is shown at a time; clicking a tab shows its page. The tab bar may
+/// be placed on any side (see [`TabbedStack::with_position`]).
+///
+/// With key focus on the tab bar, the arrow, home and end keys switch tabs.
+/// Ctrl+Tab and Ctrl+Shift+Tab switch to the next and previous tab while key
+/// focus is on the tab bar or anywhere within the pages.
+///
+/// Pages may be added and removed at runtime; this triggers a
+/// [reconfigure action](TkWindow::send_action). Pages which are not shown are
+/// retained.
+///
+/// Optionally, a handler may be set via [`TabbedStack::on_select`]; this is
+/// called with the tab's index whenever the selection changes, and its result
+/// is returned from the event handler. Messages from pages are passed through
+/// unchanged.
+#[derive(Clone)]
+pub struct TabbedStack<W: Widget, OS: 'static> {
+    core: CoreData,
+    titles: Vec<String>,
+    pages: Vec<W>,
+    selected: usize,
+    position: TabPosition,
+    tab_widths: Vec<u32>,
+    tab_height: u32,
+    bar_width: u32,
+    surround: (Size, Size),
+    press: Option<(PressSource, Option<usize>)>,
+    on_select: OS,
+}
+
+impl<W: Widget, OS> Debug for TabbedStack<W, OS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TabbedStack {{ core: {:?}, titles: {:?}, pages: {:?}, selected: {}, position: {:?}, ... }}",
+            self.core, self.titles, self.pages, self.selected, self.position,
+        )
+    }
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget, OS> WidgetCore for TabbedStack<W, OS> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "TabbedStack"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.pages.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.pages.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.pages.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.pages {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.pages {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Default for TabbedStack<W, ()> {
+    fn default() -> Self {
+        TabbedStack::new()
+    }
+}
+
+impl<W: Widget> TabbedStack<W, ()> {
+    /// Construct an empty stack
+    pub fn new() -> Self {
+        TabbedStack {
+            core: Default::default(),
+            titles: vec![],
+            pages: vec![],
+            selected: 0,
+            position: TabPosition::default(),
+            tab_widths: vec![],
+            tab_height: 0,
+            bar_width: 0,
+            surround: (Size::ZERO, Size::ZERO),
+            press: None,
+            on_select: (),
+        }
+    }
+
+    /// Set the event handler to be called on selection.
+    ///
+    /// The closure `f` is called with the tab's index when selected, and the
+    /// result of `f` is returned from the event handler.
+    pub fn on_select<M, OS: Fn(usize) -> M>(self, f: OS) -> TabbedStack<W, OS> {
+        TabbedStack {
+            core: self.core,
+            titles: self.titles,
+            pages: self.pages,
+            selected: self.selected,
+            position: self.position,
+            tab_widths: self.tab_widths,
+            tab_height: self.tab_height,
+            bar_width: self.bar_width,
+            surround: self.surround,
+            press: self.press,
+            on_select: f,
+        }
+    }
+}
+
+impl<W: Widget, OS> TabbedStack<W, OS> {
+    /// Add a page with the given tab `title`
+    pub fn with_page<S: Into<String>>(mut self, title: S, page: W) -> Self {
+        self.titles.push(title.into());
+        self.pages.push(page);
+        self
+    }
+
+    /// Set the placement of the tab bar
+    #[inline]
+    pub fn with_position(mut self, position: TabPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set the placement of the tab bar
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action) if changed.
+    pub fn set_position(&mut self, tk: &mut dyn TkWindow, position: TabPosition) {
+        if position != self.position {
+            self.position = position;
+            tk.send_action(TkAction::Reconfigure);
+        }
+    }
+
+    /// Get the number of pages
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// True if there are no pages
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Append a page with the given tab `title`
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push<S: Into<String>>(&mut self, tk: &mut dyn TkWindow, title: S, page: W) {
+        self.titles.push(title.into());
+        self.pages.push(page);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Insert a page at position `index`
+    ///
+    /// Panics if `index > len`. The selected page is unchanged.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn insert<S: Into<String>>(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        index: usize,
+        title: S,
+        page: W,
+    ) {
+        self.titles.insert(index, title.into());
+        self.pages.insert(index, page);
+        if index <= self.selected && self.pages.len() > 1 {
+            self.selected += 1;
+        }
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Remove the page at position `index`
+    ///
+    /// Panics if `index` is out of bounds. If the selected page is removed,
+    /// the next page (or the new last page) is selected.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn remove(&mut self, tk: &mut dyn TkWindow, index: usize) -> W {
+        self.titles.remove(index);
+        let page = self.pages.remove(index);
+        if index < self.selected || self.selected >= self.pages.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        tk.send_action(TkAction::Reconfigure);
+        page
+    }
+
+    /// Get the index of the selected page
+    ///
+    /// This is zero if there are no pages.
+    #[inline]
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Set the selected page
+    ///
+    /// Panics if `index` is out of bounds. Does not call the selection
+    /// handler.
+    pub fn set_selected(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        assert!(index < self.pages.len());
+        self.select(tk, index);
+    }
+
+    /// Access a page
+    #[inline]
+    pub fn page(&self, index: usize) -> Option<&W> {
+        self.pages.get(index)
+    }
+
+    /// Access a page mutably
+    #[inline]
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut W> {
+        self.pages.get_mut(index)
+    }
+
+    /// Get the title of a tab
+    #[inline]
+    pub fn title(&self, index: usize) -> Option<&str> {
+        self.titles.get(index).map(|s| s.as_str())
+    }
+
+    /// Set the title of a tab
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_title<S: Into<String>>(&mut self, tk: &mut dyn TkWindow, index: usize, title: S) {
+        self.titles[index] = title.into();
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    // Size of the bar across its own axis
+    fn bar_size(&self) -> u32 {
+        match self.position.is_vertical() {
+            false => self.tab_height,
+            true => self.bar_width,
+        }
+    }
+
+    fn tab_rect(&self, index: usize) -> Rect {
+        let rect = self.core.rect;
+        let (pos, size) = match self.position.is_vertical() {
+            false => {
+                let x: u32 = self.tab_widths.iter().take(index).sum();
+                let w = self.tab_widths.get(index).cloned().unwrap_or(0);
+                (Coord(x as i32, 0), Size(w, self.tab_height))
+            }
+            true => {
+                let y = index as u32 * self.tab_height;
+                (Coord(0, y as i32), Size(self.bar_width, self.tab_height))
+            }
+        };
+        let pos = match self.position {
+            TabPosition::Top | TabPosition::Left => pos,
+            TabPosition::Bottom => pos + Coord(0, (rect.size.1 - self.tab_height) as i32),
+            TabPosition::Right => pos + Coord((rect.size.0 - self.bar_width) as i32, 0),
+        };
+        Rect {
+            pos: rect.pos + pos,
+            size,
+        }
+    }
+
+    fn page_rect(&self) -> Rect {
+        let rect = self.core.rect;
+        let bar = self.bar_size();
+        match self.position {
+            TabPosition::Top => Rect {
+                pos: rect.pos + Coord(0, bar as i32),
+                size: Size(rect.size.0, rect.size.1.saturating_sub(bar)),
+            },
+            TabPosition::Bottom => Rect {
+                pos: rect.pos,
+                size: Size(rect.size.0, rect.size.1.saturating_sub(bar)),
+            },
+            TabPosition::Left => Rect {
+                pos: rect.pos + Coord(bar as i32, 0),
+                size: Size(rect.size.0.saturating_sub(bar), rect.size.1),
+            },
+            TabPosition::Right => Rect {
+                pos: rect.pos,
+                size: Size(rect.size.0.saturating_sub(bar), rect.size.1),
+            },
+        }
+    }
+
+    fn tab_at(&self, coord: Coord) -> Option<usize> {
+        (0..self.titles.len()).find(|i| self.tab_rect(*i).contains(coord))
+    }
+
+    // Select a page; returns true if changed
+    fn select(&mut self, tk: &mut dyn TkWindow, index: usize) -> bool {
+        if index != self.selected && index < self.pages.len() {
+            self.selected = index;
+            tk.send_action(TkAction::Redraw);
+            true
+        } else {
+            false
+        }
+    }
+
+    // Returns `Some(changed)` if the key is handled
+    fn navigate(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> Option<bool> {
+        let last = self.pages.len().checked_sub(1)?;
+        let (prev, next) = match self.position.is_vertical() {
+            false => (NavKey::Left, NavKey::Right),
+            true => (NavKey::Up, NavKey::Down),
+        };
+        let index = match key {
+            k if k == prev => self.selected.saturating_sub(1),
+            k if k == next => (self.selected + 1).min(last),
+            NavKey::Home => 0,
+            NavKey::End => last,
+            _ => return None,
+        };
+        Some(self.select(tk, index))
+    }
+
+    // Cycle through pages, wrapping around; returns true if changed
+    fn cycle(&mut self, tk: &mut dyn TkWindow, reverse: bool) -> bool {
+        let len = self.pages.len();
+        if len == 0 {
+            return false;
+        }
+        let index = match reverse {
+            false => (self.selected + 1) % len,
+            true => (self.selected + len - 1) % len,
+        };
+        self.select(tk, index)
+    }
+}
+
+impl<W: Widget, OS> Widget for TabbedStack<W, OS> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.surround = size_handle.button_surround();
+        let surround = self.surround.0 + self.surround.1;
+
+        let mut pages = SizeRules::EMPTY;
+        for page in &mut self.pages {
+            pages = pages.max(layout::child_rules(page, size_handle, axis));
+        }
+
+        if !axis.vertical() {
+            self.tab_widths.clear();
+            for title in &self.titles {
+                let rules = size_handle.text_bound(title, TextClass::Button, false, axis);
+                self.tab_widths.push(rules.min_size() + surround.0);
+            }
+            self.bar_width = self.tab_widths.iter().cloned().max().unwrap_or(0);
+        } else {
+            self.tab_height = size_handle.line_height(TextClass::Button) + surround.1;
+        }
+
+        let len = self.titles.len() as u32;
+        match (self.position.is_vertical(), axis.vertical()) {
+            (false, false) => pages.max(SizeRules::fixed(self.tab_widths.iter().sum())),
+            (false, true) => SizeRules::fixed(self.tab_height) + pages,
+            (true, false) => SizeRules::fixed(self.bar_width) + pages,
+            (true, true) => pages.max(SizeRules::fixed(len * self.tab_height)),
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let page_rect = self.page_rect();
+        for page in &mut self.pages {
+            page.set_rect(size_handle, page_rect);
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let pressed = self.press.and_then(|p| p.1);
+        for (index, title) in self.titles.iter().enumerate() {
+            let rect = self.tab_rect(index);
+            // The selected tab is drawn depressed
+            let hl = HighlightState {
+                depress: index == self.selected || pressed == Some(index),
+                key_focus: highlights.key_focus && index == self.selected,
+                ..HighlightState::default()
+            };
+            draw_handle.button(rect, hl);
+            let text_rect = Rect {
+                pos: rect.pos + self.surround.0,
+                size: rect.size - (self.surround.0 + self.surround.1).min(rect.size),
+            };
+            let props = TextProperties {
+                class: TextClass::Button,
+                multi_line: false,
+                horiz: Align::Centre,
+                vert: Align::Centre,
+            };
+            draw_handle.text(text_rect, title, props);
+        }
+
+        if let Some(page) = self.pages.get(self.selected) {
+            layout::draw_child(page, draw_handle, ev_mgr);
+        }
+    }
+}
+
+impl<W: Widget + Handler, OS> TabbedStack<W, OS> {
+    // Handle an event. Returns a selection change as `Ok(index)`, otherwise
+    // a response.
+    fn handle_inner(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Result<usize, Response<<W as Handler>::Msg>> {
+        let event = match addr {
+            Address::Id(id) if id < self.id() => {
+                let r = match self.pages.iter_mut().find(|page| id <= page.id()) {
+                    Some(page) => page.handle(tk, addr, event),
+                    None => Response::Unhandled(event),
+                };
+                match r {
+                    // Ctrl+Tab from within a page
+                    Response::Unhandled(Event::Action(Action::NextTab(reverse))) => {
+                        return match self.cycle(tk, reverse) {
+                            true => Ok(self.selected),
+                            false => Err(Response::None),
+                        };
+                    }
+                    r => return Err(r),
+                }
+            }
+            Address::Coord(coord) if self.page_rect().contains(coord) => {
+                if let Some(page) = self.pages.get_mut(self.selected) {
+                    return Err(page.handle(tk, addr, event));
+                }
+                event
+            }
+            _ => event,
+        };
+
+        match event {
+            Event::Action(Action::NavKey(key)) => match self.navigate(tk, key) {
+                Some(true) => Ok(self.selected),
+                Some(false) => Err(Response::None),
+                None => Err(Response::unhandled_action(Action::NavKey(key))),
+            },
+            Event::Action(Action::NextTab(reverse)) => match self.cycle(tk, reverse) {
+                true => Ok(self.selected),
+                false => Err(Response::None),
+            },
+            Event::PressStart { source, coord } if source.is_primary() => {
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.tab_at(coord)));
+                tk.redraw(self.id());
+                Err(Response::None)
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Err(Response::None)
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                tk.redraw(self.id());
+                let end = match end_id == Some(self.id()) {
+                    true => self.tab_at(coord),
+                    false => None,
+                };
+                match start {
+                    Some(index) if end == start && self.select(tk, index) => Ok(index),
+                    _ => Err(Response::None),
+                }
+            }
+            Event::Identify => Err(Response::Identify(self.id())),
+            e => Err(Response::Unhandled(e)),
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for TabbedStack<W, ()> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(_) => Response::None,
+            Err(r) => r,
+        }
+    }
+}
+
+impl<W: Widget + Handler, OS: Fn(usize) -> <W as Handler>::Msg> Handler for TabbedStack<W, OS> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        match self.handle_inner(tk, addr, event) {
+            Ok(index) => Response::Msg((self.on_select)(index)),
+            Err(r) => r,
+        }
+    }
+}
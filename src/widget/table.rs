@@ -0,0 +1,911 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Virtualised table view
+
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::event::{
+    self, Action, Address, Deferred, Event, Handler, HighlightState, Manager, NavKey, PressSource,
+    Response, ScrollDelta,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{AxisInfo, LayoutOverrides, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TableData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// Maximum time between the clicks of a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+/// Number of rows sampled when measuring column widths
+const SAMPLE_ROWS: usize = 64;
+
+/// Maximum ideal column width, in lines
+const MAX_COL_LINES: u32 = 16;
+
+/// Message type of [`Table`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableMsg<K> {
+    /// The cell at `(row, col)` was selected
+    Select(usize, usize),
+    /// The table was sorted by column `col`, ascending if true
+    ///
+    /// Sorting (see [`Table::set_sort`]) changes only the view, not the data.
+    Sort(usize, bool),
+    /// A cell was edited
+    ///
+    /// The new `value` should be written to the data (e.g. via
+    /// [`Table::modify_data`]); the table does not do this itself.
+    Edit {
+        /// Key of the cell's row
+        key: K,
+        /// Column of the cell
+        col: usize,
+        /// New text
+        value: String,
+    },
+}
+
+// State of an in-place edit
+#[derive(Clone, Debug)]
+struct CellEdit {
+    row: usize,
+    col: usize,
+    text: String,
+}
+
+/// A scrollable table of text cells
+///
+/// Cells of a [`TableData`] model are shown in rows of equal-width columns.
+/// As with [`ListView`], only visible rows are drawn or hit-tested, thus long
+/// tables remain fast.
+///
+/// If the model has column headers (see [`TableData::header`]), these are
+/// shown in a row above the cells, which does not scroll. Clicking a header
+/// sorts the view by that column, or reverses the order if already sorted by
+/// that column; each change is reported with [`TableMsg::Sort`]. Cells which
+/// parse as numbers are compared numerically. Row numbers used by the table's
+/// API (e.g. [`Table::selected`]) are positions in the sorted view; keys
+/// reported by [`TableMsg::Edit`] are those of the model.
+///
+/// Column widths are proportional to the widths of their content, measured
+/// on a sample of rows (which is not updated by [`Table::append_data`]).
+/// Columns may be resized by dragging the border between two columns; such
+/// adjustments are kept as [`LayoutOverrides`], thus are scaled when the
+/// table is resized and persist when the data changes (unless the number of
+/// columns changes). See [`Table::column_split`].
+///
+/// The cursor cell is selected by clicking or with the arrow, page and
+/// home/end keys; each change is reported with [`TableMsg::Select`]. Holding
+/// shift extends a rectangular selection from the previous cell. The copy
+/// shortcut copies the selection to the clipboard, in CSV format.
+///
+/// If editing is enabled (see [`Table::with_editable`]), double-clicking a
+/// cell or pressing F2 replaces the cell's display with an edit box. The
+/// enter key or moving focus elsewhere commits the edit, reported as
+/// [`TableMsg::Edit`]; the escape key cancels it.
+///
+/// [`ListView`]: crate::widget::ListView
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Table<D: TableData + 'static> {
+    #[core]
+    core: CoreData,
+    data: D,
+    editable: bool,
+    anchor: Option<(usize, usize)>,
+    cursor: Option<(usize, usize)>,
+    editing: Option<CellEdit>,
+    col_widths: Vec<u32>,
+    col_min: u32,
+    col_overrides: LayoutOverrides,
+    col_pos: Vec<u32>,
+    // Model row of each view row; empty when unsorted
+    order: Vec<usize>,
+    sort: Option<(usize, bool)>,
+    // Area below the headers
+    body: Rect,
+    header_height: u32,
+    // press source and column of a pressed header
+    header_press: Option<(PressSource, usize)>,
+    row_height: u32,
+    margin: u32,
+    offset: u32,
+    max_offset: u32,
+    press: Option<(PressSource, Option<(usize, usize)>)>,
+    // press source and index of the dragged column border
+    resize: Option<(PressSource, usize)>,
+    last_click: Option<(Instant, (usize, usize))>,
+}
+
+impl<D: TableData> Table<D> {
+    /// Construct a view over `data`
+    ///
+    /// By default, cells are not editable.
+    pub fn new(data: D) -> Self {
+        Table {
+            core: Default::default(),
+            data,
+            editable: false,
+            anchor: None,
+            cursor: None,
+            editing: None,
+            col_widths: vec![],
+            col_min: 0,
+            col_overrides: LayoutOverrides::default(),
+            col_pos: vec![],
+            order: vec![],
+            sort: None,
+            body: Rect::default(),
+            header_height: 0,
+            header_press: None,
+            row_height: 0,
+            margin: 0,
+            offset: 0,
+            max_offset: 0,
+            press: None,
+            resize: None,
+            last_click: None,
+        }
+    }
+
+    /// Set the column split (inline)
+    ///
+    /// See [`Table::column_split`]. The split is ignored unless it has one
+    /// entry per column border (one less than the number of columns).
+    #[inline]
+    pub fn with_column_split(mut self, split: Vec<f32>) -> Self {
+        self.col_overrides = LayoutOverrides::from_ends(split);
+        self
+    }
+
+    /// Get the column split
+    ///
+    /// This is the position of the end of each column (except the last), as
+    /// a fraction of the table's width, in increasing order. It is empty
+    /// unless columns have been resized by the user or set explicitly, and
+    /// may be used to persist column widths between sessions.
+    #[inline]
+    pub fn column_split(&self) -> &[f32] {
+        self.col_overrides.ends()
+    }
+
+    /// Set the column split
+    ///
+    /// See [`Table::with_column_split`]. An empty split restores the default
+    /// column widths.
+    pub fn set_column_split(&mut self, tk: &mut dyn TkWindow, split: Vec<f32>) {
+        self.col_overrides = LayoutOverrides::from_ends(split);
+        self.layout_cols();
+        tk.redraw(self.id());
+    }
+
+    /// Sort by column `col`, ascending if true (inline)
+    ///
+    /// See [`Table::set_sort`].
+    pub fn with_sort(mut self, col: usize, ascending: bool) -> Self {
+        self.sort = Some((col, ascending));
+        self.sort_rows();
+        self
+    }
+
+    /// Get the sort column and order, if sorted
+    #[inline]
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    /// Sort by a column, or (given `None`) restore the model's order
+    ///
+    /// Each sort is a tuple `(col, ascending)`. The sort is stable and is
+    /// updated when the data changes. Changing the sort clears the selection
+    /// and cancels any edit in progress.
+    pub fn set_sort(&mut self, tk: &mut dyn TkWindow, sort: Option<(usize, bool)>) {
+        if sort == self.sort {
+            return;
+        }
+        self.sort = sort;
+        self.sort_rows();
+        self.anchor = None;
+        self.cursor = None;
+        self.cancel_edit(tk);
+        tk.redraw(self.id());
+    }
+
+    /// Enable or disable in-place editing
+    #[inline]
+    pub fn with_editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// True if cells are editable
+    #[inline]
+    pub fn is_editable(&self) -> bool {
+        self.editable
+    }
+
+    /// Enable or disable in-place editing
+    ///
+    /// Disabling editing cancels any edit in progress.
+    pub fn set_editable(&mut self, tk: &mut dyn TkWindow, editable: bool) {
+        self.editable = editable;
+        if !editable {
+            self.cancel_edit(tk);
+        }
+    }
+
+    /// Access the data
+    #[inline]
+    pub fn data(&self) -> &D {
+        &self.data
+    }
+
+    /// Replace the data
+    ///
+    /// The selection is cleared, any edit is cancelled and the view scrolled
+    /// to the top.
+    pub fn set_data(&mut self, tk: &mut dyn TkWindow, data: D) {
+        self.data = data;
+        self.anchor = None;
+        self.cursor = None;
+        self.cancel_edit(tk);
+        self.offset = 0;
+        self.sort_rows();
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Modify the data
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn modify_data<F: FnOnce(&mut D)>(&mut self, tk: &mut dyn TkWindow, f: F) {
+        f(&mut self.data);
+        self.sort_rows();
+        let (rows, cols) = (self.data.rows(), self.data.cols());
+        self.anchor = self.anchor.filter(|c| c.0 < rows && c.1 < cols);
+        self.cursor = self.cursor.filter(|c| c.0 < rows && c.1 < cols);
+        if let Some(ref edit) = self.editing {
+            if edit.row >= rows || edit.col >= cols {
+                self.cancel_edit(tk);
+            }
+        }
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// Append rows to the data
+    ///
+    /// The closure `f` should only append rows (existing rows may not be
+    /// changed or removed). This is cheaper than [`Table::modify_data`]:
+    /// unless the number of columns changes, column widths are not measured
+    /// again and only a redraw is required.
+    pub fn append_data<F: FnOnce(&mut D)>(&mut self, tk: &mut dyn TkWindow, f: F) {
+        let cols = self.data.cols();
+        f(&mut self.data);
+        self.sort_rows();
+        if self.data.cols() != cols {
+            tk.send_action(TkAction::Reconfigure);
+            return;
+        }
+        let content_height = self.data.rows() as u32 * self.row_height;
+        self.max_offset = content_height.saturating_sub(self.body.size.1);
+        tk.redraw(self.id());
+    }
+
+    /// Get the cursor cell, as `(row, col)`
+    #[inline]
+    pub fn selected(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    /// Get the selected range, as inclusive `(first, last)` cell coordinates
+    ///
+    /// Each coordinate is `(row, col)`, with `first <= last` on each axis.
+    pub fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (a, b) = (self.anchor?, self.cursor?);
+        Some(((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1))))
+    }
+
+    /// Get the selected range as CSV text
+    ///
+    /// Fields are separated by commas and rows by line breaks (CRLF); fields
+    /// containing commas, quotes or line breaks are quoted.
+    pub fn selection_to_csv(&self) -> Option<String> {
+        let (first, last) = self.selection()?;
+        let mut csv = String::new();
+        for row in first.0..=last.0 {
+            for col in first.1..=last.1 {
+                if col > first.1 {
+                    csv.push(',');
+                }
+                let field = self.cell(row, col).unwrap_or("");
+                if field.contains([',', '"', '\n', '\r']) {
+                    csv.push('"');
+                    csv.push_str(&field.replace('"', "\"\""));
+                    csv.push('"');
+                } else {
+                    csv.push_str(field);
+                }
+            }
+            csv.push_str("\r\n");
+        }
+        Some(csv)
+    }
+
+    /// True while a cell is being edited
+    #[inline]
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Begin editing the cell at `(row, col)`
+    ///
+    /// Does nothing if editing is disabled or the cell does not exist.
+    pub fn begin_edit(&mut self, tk: &mut dyn TkWindow, row: usize, col: usize) {
+        if !self.editable || row >= self.data.rows() || col >= self.data.cols() {
+            return;
+        }
+        let text = self.cell(row, col).unwrap_or("").to_string();
+        self.editing = Some(CellEdit { row, col, text });
+        self.cursor = Some((row, col));
+        self.ensure_visible(row);
+        tk.defer(Deferred::SetCharFocus(Some(self.id())));
+    }
+
+    /// Cancel any edit in progress
+    pub fn cancel_edit(&mut self, tk: &mut dyn TkWindow) {
+        if self.editing.take().is_some() {
+            tk.data_mut().clear_char_focus();
+        }
+    }
+
+    // Finish any edit in progress, returning the edit message
+    fn commit_edit(&mut self, tk: &mut dyn TkWindow) -> Response<TableMsg<D::Key>> {
+        match self.editing.take() {
+            Some(edit) => {
+                tk.data_mut().clear_char_focus();
+                let key = self.data.key(self.model_row(edit.row));
+                Response::Msg(TableMsg::Edit {
+                    key,
+                    col: edit.col,
+                    value: edit.text,
+                })
+            }
+            None => Response::None,
+        }
+    }
+
+    // Handle a character while editing; returns true on commit
+    fn received_char(&mut self, tk: &mut dyn TkWindow, c: char) -> bool {
+        let edit = match self.editing.as_mut() {
+            Some(edit) => edit,
+            None => return false,
+        };
+        match c {
+            '\u{0D}' /* carriage return */ => return true,
+            '\u{08}' /* backspace */ => {
+                edit.text.pop();
+            }
+            '\u{7f}' /* delete */ => edit.text.clear(),
+            '\u{16}' /* paste */ => {
+                if let Some(content) = tk.get_clipboard() {
+                    // Stop at the first control character, as for EditBox
+                    let end = content
+                        .char_indices()
+                        .find(|(_, c)| c.is_control())
+                        .map(|(i, _)| i)
+                        .unwrap_or(content.len());
+                    edit.text.push_str(&content[0..end]);
+                }
+            }
+            c if c.is_control() => return false,
+            c => edit.text.push(c),
+        }
+        tk.redraw(self.id());
+        false
+    }
+
+    // Model row shown at view `row`
+    fn model_row(&self, row: usize) -> usize {
+        self.order.get(row).cloned().unwrap_or(row)
+    }
+
+    // Text of the cell at view `row`
+    fn cell(&self, row: usize, col: usize) -> Option<&str> {
+        self.data.cell(self.model_row(row), col)
+    }
+
+    // Update the view order from the sort
+    fn sort_rows(&mut self) {
+        self.order.clear();
+        let (col, ascending) = match self.sort {
+            Some(sort) if sort.0 < self.data.cols() => sort,
+            _ => return,
+        };
+        let data = &self.data;
+        self.order.extend(0..data.rows());
+        self.order.sort_by(|a, b| {
+            let ord = compare_cells(data.cell(*a, col), data.cell(*b, col));
+            match ascending {
+                true => ord,
+                false => ord.reverse(),
+            }
+        });
+    }
+
+    // Range of visible rows
+    fn visible(&self) -> (usize, usize) {
+        let h = self.row_height;
+        if h == 0 {
+            return (0, 0);
+        }
+        let first = (self.offset / h) as usize;
+        let last = (self.offset + self.body.size.1).div_ceil(h) as usize;
+        let end = last.min(self.data.rows());
+        (first.min(end), end)
+    }
+
+    // Number of laid-out columns
+    fn cols(&self) -> usize {
+        self.col_pos.len().saturating_sub(1)
+    }
+
+    // Set column positions from widths and overrides
+    fn layout_cols(&mut self) {
+        // Share width between columns in proportion to their ideal widths
+        let width = self.core.rect.size.0;
+        let total = self.col_widths.iter().sum::<u32>().max(1) as u64;
+        let (mut sum, mut pos) = (0, 0);
+        let mut sizes: Vec<u32> = (self.col_widths.iter())
+            .map(|w| {
+                sum += *w as u64;
+                let end = (sum * width as u64 / total) as u32;
+                let size = end - pos;
+                pos = end;
+                size
+            })
+            .collect();
+
+        let rules = vec![SizeRules::fixed(self.col_min); sizes.len()];
+        self.col_overrides.apply(&mut sizes, &rules, width);
+
+        self.col_pos.clear();
+        self.col_pos.push(0);
+        let mut end = 0;
+        for size in sizes {
+            end += size;
+            self.col_pos.push(end);
+        }
+    }
+
+    // Column of the header at `coord`, if any
+    fn header_at(&self, coord: Coord) -> Option<usize> {
+        let rect = self.core.rect;
+        if !rect.contains(coord) || coord.1 >= rect.pos.1 + self.header_height as i32 {
+            return None;
+        }
+        let x = (coord.0 - rect.pos.0) as u32;
+        self.col_pos.windows(2).position(|w| x < w[1])
+    }
+
+    // Index of the column border at `coord`, if any
+    //
+    // Border `i` is at the start of column `i`; the outer borders are excluded.
+    fn border_at(&self, coord: Coord) -> Option<usize> {
+        if !self.core.rect.contains(coord) {
+            return None;
+        }
+        let x = coord.0 - self.core.rect.pos.0;
+        let grip = (self.margin as i32).max(2);
+        (1..self.cols()).find(|i| (x - self.col_pos[*i] as i32).abs() <= grip)
+    }
+
+    // Move column border `index` to `x` (relative to the table)
+    fn drag_border(&mut self, tk: &mut dyn TkWindow, index: usize, x: i32) {
+        let cols = self.cols();
+        if !self.col_overrides.matches(cols) {
+            let sizes: Vec<u32> = self.col_pos.windows(2).map(|w| w[1] - w[0]).collect();
+            self.col_overrides.record(&sizes);
+        }
+        let frac = x as f32 / self.core.rect.size.0.max(1) as f32;
+        if self.col_overrides.move_end(index - 1, frac) {
+            self.layout_cols();
+            tk.redraw(self.id());
+        }
+    }
+
+    // Cell rect, in (unscrolled) content coordinates
+    fn cell_rect(&self, row: usize, col: usize) -> Rect {
+        let (x0, x1) = (self.col_pos[col], self.col_pos[col + 1]);
+        let y = row as u32 * self.row_height;
+        Rect {
+            pos: self.body.pos + Coord(x0 as i32, y as i32),
+            size: Size(x1 - x0, self.row_height),
+        }
+    }
+
+    // Cell at `coord`, if any
+    fn cell_at(&self, coord: Coord) -> Option<(usize, usize)> {
+        if !self.body.contains(coord) || self.row_height == 0 {
+            return None;
+        }
+        let y = (coord.1 - self.body.pos.1) as u32 + self.offset;
+        let x = (coord.0 - self.body.pos.0) as u32;
+        let row = (y / self.row_height) as usize;
+        let col = self.col_pos.windows(2).position(|w| x < w[1])?;
+        Some((row, col)).filter(|c| c.0 < self.data.rows() && c.1 < self.data.cols())
+    }
+
+    // Set offset, returning true if changed
+    fn set_offset(&mut self, offset: u32) -> bool {
+        let offset = offset.min(self.max_offset);
+        if offset != self.offset {
+            self.offset = offset;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ensure_visible(&mut self, row: usize) {
+        let top = row as u32 * self.row_height;
+        let height = self.body.size.1;
+        if top < self.offset {
+            self.set_offset(top);
+        } else if top + self.row_height > self.offset + height {
+            self.set_offset(top + self.row_height - height.min(top + self.row_height));
+        }
+    }
+
+    // Move the cursor, optionally extending the selection
+    fn select(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        cell: (usize, usize),
+        extend: bool,
+    ) -> Response<TableMsg<D::Key>> {
+        tk.redraw(self.id());
+        if !extend || self.anchor.is_none() {
+            self.anchor = Some(cell);
+        }
+        if self.cursor == Some(cell) {
+            return Response::None;
+        }
+        self.cursor = Some(cell);
+        Response::Msg(TableMsg::Select(cell.0, cell.1))
+    }
+
+    fn navigate(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> Response<TableMsg<D::Key>> {
+        let (rows, cols) = (self.data.rows() as i64, self.data.cols() as i64);
+        if rows == 0 || cols == 0 {
+            return Response::unhandled_action(Action::NavKey(key));
+        }
+        let page = (self.body.size.1 / self.row_height.max(1)).max(1) as i64;
+        let (row, col) = match (key, self.cursor.map(|(r, c)| (r as i64, c as i64))) {
+            (NavKey::Home, c) => (0, c.map(|c| c.1).unwrap_or(0)),
+            (NavKey::End, c) => (rows - 1, c.map(|c| c.1).unwrap_or(0)),
+            (_, None) => (0, 0),
+            (NavKey::Up, Some((r, c))) => (r - 1, c),
+            (NavKey::Down, Some((r, c))) => (r + 1, c),
+            (NavKey::Left, Some((r, c))) => (r, c - 1),
+            (NavKey::Right, Some((r, c))) => (r, c + 1),
+            (NavKey::PageUp, Some((r, c))) => (r - page, c),
+            (NavKey::PageDown, Some((r, c))) => (r + page, c),
+        };
+        let row = row.max(0).min(rows - 1) as usize;
+        let col = col.max(0).min(cols - 1) as usize;
+        self.ensure_visible(row);
+        let extend = tk.data().modifiers().shift;
+        self.select(tk, (row, col), extend)
+    }
+}
+
+impl<D: TableData> Widget for Table<D> {
+    fn allow_focus(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let line_height = size_handle.line_height(TextClass::Label);
+        self.margin = size_handle.outer_margin().0;
+        self.row_height = line_height + 2 * self.margin;
+        let data = &self.data;
+        let has_headers = (0..data.cols()).any(|col| data.header(col).is_some());
+        self.header_height = if has_headers { self.row_height } else { 0 };
+
+        // We only measure a sample of rows, since measuring all would be O(n)
+        if !axis.vertical() {
+            let (rows, cols) = (self.data.rows(), self.data.cols());
+            if cols == 0 {
+                self.col_widths.clear();
+                self.col_min = 0;
+                return SizeRules::variable(6 * line_height, 12 * line_height);
+            }
+            let step = rows / SAMPLE_ROWS + 1;
+            let data = &self.data;
+            let (min_width, max_width) = (2 * line_height, MAX_COL_LINES * line_height);
+            self.col_min = min_width + 2 * self.margin;
+            self.col_widths = (0..cols)
+                .map(|col| {
+                    let width = (0..rows)
+                        .step_by(step)
+                        .filter_map(|row| data.cell(row, col))
+                        .map(|text| {
+                            let rules = size_handle.text_bound(text, TextClass::Label, false, axis);
+                            rules.ideal_size()
+                        })
+                        .max()
+                        .unwrap_or(0);
+                    // Allow space for the sort indicator
+                    let header = (data.header(col))
+                        .map(|text| {
+                            let text = format!("{} ▾", text);
+                            let rules =
+                                size_handle.text_bound(&text, TextClass::Label, false, axis);
+                            rules.ideal_size()
+                        })
+                        .unwrap_or(0);
+                    width.max(header).max(min_width).min(max_width) + 2 * self.margin
+                })
+                .collect();
+            let ideal = self.col_widths.iter().sum();
+            SizeRules::variable(cols as u32 * min_width, ideal)
+        } else {
+            let rows = (self.data.rows() as u32).clamp(1, 8);
+            SizeRules::variable(self.row_height, rows * self.row_height) + self.header_height
+        }
+    }
+
+    fn set_rect(&mut self, _size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.layout_cols();
+        let header_height = self.header_height.min(rect.size.1);
+        self.body = Rect {
+            pos: rect.pos + Coord(0, header_height as i32),
+            size: Size(rect.size.0, rect.size.1 - header_height),
+        };
+
+        let content_height = self.data.rows() as u32 * self.row_height;
+        self.max_offset = content_height.saturating_sub(self.body.size.1);
+        self.offset = self.offset.min(self.max_offset);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        let highlights = ev_mgr.highlight_state(self.id());
+        let offset = Coord(0, self.offset as i32);
+        let (start, end) = self.visible();
+        let cols = self.cols();
+        let selection = self.selection();
+        let props = || TextProperties {
+            class: TextClass::Label,
+            multi_line: false,
+            horiz: Align::Begin,
+            vert: Align::Centre,
+        };
+        let inset = |rect: Rect| Rect {
+            pos: rect.pos + Coord(self.margin as i32, self.margin as i32),
+            size: rect.size - Size::uniform(2 * self.margin).min(rect.size),
+        };
+
+        if self.header_height > 0 {
+            let header_height = (self.body.pos.1 - self.core.rect.pos.1) as u32;
+            for col in 0..cols {
+                let (x0, x1) = (self.col_pos[col], self.col_pos[col + 1]);
+                let rect = Rect {
+                    pos: self.core.rect.pos + Coord(x0 as i32, 0),
+                    size: Size(x1 - x0, header_height),
+                };
+                let hl = HighlightState {
+                    depress: self.header_press.map(|p| p.1) == Some(col),
+                    ..HighlightState::default()
+                };
+                draw_handle.button(rect, hl);
+                let mut text = self.data.header(col).unwrap_or("").to_string();
+                match self.sort {
+                    Some((c, true)) if c == col => text.push_str(" ▴"),
+                    Some((c, false)) if c == col => text.push_str(" ▾"),
+                    _ => (),
+                }
+                draw_handle.text(inset(rect), &text, props());
+            }
+        }
+
+        draw_handle.clip_region(self.body, offset, &mut |draw_handle| {
+            for row in start..end {
+                for col in 0..cols {
+                    let rect = self.cell_rect(row, col);
+                    let text_rect = inset(rect);
+
+                    match self.editing {
+                        Some(ref edit) if (edit.row, edit.col) == (row, col) => {
+                            let hl = HighlightState {
+                                char_focus: highlights.char_focus,
+                                ..HighlightState::default()
+                            };
+                            draw_handle.edit_box(rect, hl);
+                            let mut text = edit.text.clone();
+                            if hl.char_focus {
+                                text.push('|');
+                            }
+                            draw_handle.text(text_rect, &text, props());
+                            continue;
+                        }
+                        _ => (),
+                    }
+
+                    let selected = match selection {
+                        Some((a, b)) => a.0 <= row && row <= b.0 && a.1 <= col && col <= b.1,
+                        None => false,
+                    };
+                    if selected {
+                        let hl = HighlightState {
+                            key_focus: highlights.key_focus && self.cursor == Some((row, col)),
+                            ..HighlightState::default()
+                        };
+                        draw_handle.button(rect, hl);
+                    }
+                    if let Some(text) = self.cell(row, col) {
+                        draw_handle.text(text_rect, text, props());
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl<D: TableData> Handler for Table<D> {
+    type Msg = TableMsg<D::Key>;
+
+    fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<Self::Msg> {
+        match action {
+            Action::ReceivedCharacter(c) if self.editing.is_some() => {
+                match self.received_char(tk, c) {
+                    true => self.commit_edit(tk),
+                    false => Response::None,
+                }
+            }
+            Action::LostCharFocus(cancelled) if self.editing.is_some() => {
+                tk.redraw(self.id());
+                match cancelled {
+                    true => {
+                        self.editing = None;
+                        Response::None
+                    }
+                    false => self.commit_edit(tk),
+                }
+            }
+            Action::Copy => match self.selection_to_csv() {
+                Some(csv) => {
+                    tk.set_clipboard(csv);
+                    Response::None
+                }
+                None => Response::unhandled_action(action),
+            },
+            Action::BeginEdit => match self.cursor {
+                Some((row, col)) if self.editable => {
+                    self.begin_edit(tk, row, col);
+                    Response::None
+                }
+                _ => Response::unhandled_action(action),
+            },
+            Action::Scroll(delta) => {
+                let d = match delta {
+                    ScrollDelta::LineDelta(_, y) => -(3.0 * y * self.row_height as f32) as i64,
+                    ScrollDelta::PixelDelta(d) => -d.1 as i64,
+                };
+                let offset = (self.offset as i64 + d).max(0) as u32;
+                if self.set_offset(offset) {
+                    tk.redraw(self.id());
+                    Response::None
+                } else {
+                    Response::unhandled_action(Action::Scroll(delta))
+                }
+            }
+            // Navigation keys are ignored while editing a cell
+            Action::NavKey(_) if self.editing.is_some() => Response::None,
+            Action::NavKey(key) => self.navigate(tk, key),
+            a => Response::unhandled_action(a),
+        }
+    }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<Self::Msg> {
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                if let Some(index) = self.border_at(coord) {
+                    tk.data_mut().request_press_grab(source, self, coord);
+                    self.resize = Some((source, index));
+                    return Response::None;
+                }
+                if let Some(col) = self.header_at(coord) {
+                    tk.data_mut().request_press_grab(source, self, coord);
+                    self.header_press = Some((source, col));
+                    tk.redraw(self.id());
+                    return Response::None;
+                }
+                // Note: this removes character focus, committing any edit
+                tk.data_mut().request_press_grab(source, self, coord);
+                self.press = Some((source, self.cell_at(coord)));
+                Response::None
+            }
+            Event::PressMove { source, coord, .. } if self.resize.map(|r| r.0) == Some(source) => {
+                let index = self.resize.unwrap().1;
+                self.drag_border(tk, index, coord.0 - self.core.rect.pos.0);
+                Response::None
+            }
+            Event::PressEnd { source, .. } if self.resize.map(|r| r.0) == Some(source) => {
+                self.resize = None;
+                Response::None
+            }
+            Event::PressMove { source, .. } if self.header_press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.header_press.map(|p| p.0) == Some(source) => {
+                let col = self.header_press.take().unwrap().1;
+                tk.redraw(self.id());
+                if end_id != Some(self.id()) || self.header_at(coord) != Some(col) {
+                    return Response::None;
+                }
+                let ascending = match self.sort {
+                    Some((c, ascending)) if c == col => !ascending,
+                    _ => true,
+                };
+                self.set_sort(tk, Some((col, ascending)));
+                Response::Msg(TableMsg::Sort(col, ascending))
+            }
+            Event::PressMove { source, .. } if self.press.map(|p| p.0) == Some(source) => {
+                Response::None
+            }
+            Event::PressEnd {
+                source,
+                end_id,
+                coord,
+                ..
+            } if self.press.map(|p| p.0) == Some(source) => {
+                let start = self.press.take().and_then(|p| p.1);
+                let end = match end_id == Some(self.id()) {
+                    true => self.cell_at(coord),
+                    false => None,
+                };
+                let cell = match start {
+                    Some(cell) if end == start => cell,
+                    _ => return Response::None,
+                };
+
+                let extend = tk.data().modifiers().shift;
+                let now = Instant::now();
+                let double = !extend
+                    && match self.last_click {
+                        Some((time, c)) => c == cell && now - time < DOUBLE_CLICK,
+                        None => false,
+                    };
+                if double {
+                    self.last_click = None;
+                    self.begin_edit(tk, cell.0, cell.1);
+                    tk.redraw(self.id());
+                    Response::None
+                } else {
+                    self.last_click = Some((now, cell));
+                    self.select(tk, cell, extend)
+                }
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
+
+// Compare cells, numerically where both parse as numbers
+fn compare_cells(a: Option<&str>, b: Option<&str>) -> Ordering {
+    let number = |s: Option<&str>| s.and_then(|s| s.trim().parse::<f64>().ok());
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.unwrap_or("").cmp(b.unwrap_or("")),
+    }
+}
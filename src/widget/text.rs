@@ -5,35 +5,60 @@
 
 //! Text widgets
 
+use std::borrow::Cow;
 use std::fmt::{self, Debug};
+use std::ops::Range;
 
-use crate::class::{Editable, HasText};
-use crate::event::{self, Action, Handler, Response, VoidMsg};
+use crate::class::{Editable, HasSpans, HasText};
+use crate::event::{
+    self, Action, Address, Deferred, Event, Handler, HighlightState, Manager, NavKey, Response,
+    ScrollDelta, VoidMsg,
+};
 use crate::layout::{AxisInfo, SizeRules};
 use crate::macros::Widget;
-use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
-use crate::{CoreData, TkWindow, Widget, WidgetCore};
-use kas::geom::Rect;
+use crate::theme::{Align, DrawHandle, SizeHandle, SpanClass, TextClass, TextProperties};
+use crate::widget::Builder;
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+use kas::geom::{Coord, Rect, Size};
 
 /// A simple text label
+///
+/// Labels constructed from a `&'static str` (e.g. via `Label::from("text")`)
+/// borrow the string instead of allocating a copy.
+///
+/// By default, long lines are wrapped; see [`Label::builder`].
 #[widget]
 #[handler]
-#[derive(Clone, Default, Debug, Widget)]
+#[derive(Clone, Debug, Widget)]
 pub struct Label {
     #[core]
     core: CoreData,
-    text: String,
+    text: Cow<'static, str>,
+    wrap: bool,
+}
+
+impl Default for Label {
+    fn default() -> Self {
+        Label::new_static("")
+    }
 }
 
 impl Widget for Label {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
-        size_handle.text_bound(&self.text, TextClass::Label, true, axis)
+        size_handle.text_bound(&self.text, TextClass::Label, self.wrap, axis)
+    }
+
+    fn size_rules_both(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+    ) -> Option<(SizeRules, SizeRules)> {
+        Some(size_handle.text_bound_both(&self.text, TextClass::Label, self.wrap))
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::Manager) {
         let props = TextProperties {
             class: TextClass::Label,
-            multi_line: true,
+            multi_line: self.wrap,
             horiz: Align::Begin,
             vert: Align::Centre,
         };
@@ -46,19 +71,70 @@ impl Label {
     pub fn new<T: ToString>(text: T) -> Self {
         Label {
             core: Default::default(),
-            text: text.to_string(),
+            text: Cow::Owned(text.to_string()),
+            wrap: true,
         }
     }
+
+    /// Construct a label borrowing static `text`
+    ///
+    /// This is equivalent to `Label::from(text)`, but usable in `const`
+    /// contexts.
+    pub const fn new_static(text: &'static str) -> Self {
+        Label {
+            core: CoreData::new(),
+            text: Cow::Borrowed(text),
+            wrap: true,
+        }
+    }
+
+    /// Construct a builder, starting from an empty label
+    pub fn builder() -> Builder<Self> {
+        Builder::new(Label::default())
+    }
+
+    /// True if long lines are wrapped
+    #[inline]
+    pub fn is_wrapping(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set whether long lines are wrapped
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn set_wrap(&mut self, tk: &mut dyn TkWindow, wrap: bool) {
+        self.wrap = wrap;
+        tk.send_action(TkAction::Reconfigure);
+    }
+}
+
+impl Builder<Label> {
+    /// Set the text
+    ///
+    /// A `&'static str` is borrowed, not copied.
+    pub fn text<T: Into<Cow<'static, str>>>(mut self, text: T) -> Self {
+        self.0.text = text.into();
+        self
+    }
+
+    /// Set whether long lines are wrapped (default: true)
+    ///
+    /// Without wrapping, the text is drawn on a single line.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.0.wrap = wrap;
+        self
+    }
 }
 
 impl<T> From<T> for Label
 where
-    String: From<T>,
+    Cow<'static, str>: From<T>,
 {
     fn from(text: T) -> Self {
         Label {
             core: Default::default(),
-            text: String::from(text),
+            text: Cow::from(text),
+            wrap: true,
         }
     }
 }
@@ -69,17 +145,17 @@ impl HasText for Label {
     }
 
     fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
-        self.text = text;
+        self.text = Cow::Owned(text);
         tk.redraw(self.id());
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
-enum LastEdit {
+pub(super) enum LastEdit {
     None,
     Insert,
     Backspace,
-    Clear,
+    Delete,
     Paste,
 }
 
@@ -89,7 +165,14 @@ impl Default for LastEdit {
     }
 }
 
-/// An editable, single-line text box.
+/// An editable text box
+///
+/// By default this is a single-line box. In multi-line mode (see
+/// [`EditBox::multi_line`]) it is a simple text editor: the Enter key inserts
+/// a line break and content larger than the box scrolls, following the caret.
+///
+/// The caret may be positioned by clicking and by the arrow, Home, End and
+/// Page keys.
 #[widget]
 #[derive(Clone, Default, Widget)]
 pub struct EditBox<H: 'static> {
@@ -98,9 +181,17 @@ pub struct EditBox<H: 'static> {
     text_rect: Rect,
     editable: bool,
     multi_line: bool,
+    wrap: bool,
     text: String,
+    caret: usize,
+    // Size of the laid-out text and the scroll offset (multi-line mode only)
+    content_size: Size,
+    offset: Coord,
+    scroll_rate: f32,
     old_state: Option<String>,
     last_edit: LastEdit,
+    spans: Vec<(Range<usize>, SpanClass)>,
+    error: bool,
     on_activate: H,
 }
 
@@ -125,6 +216,16 @@ impl<H: 'static> Widget for EditBox<H> {
             + size_handle.text_bound(&self.text, TextClass::Edit, self.multi_line, axis)
     }
 
+    fn size_rules_both(
+        &mut self,
+        size_handle: &mut dyn SizeHandle,
+    ) -> Option<(SizeRules, SizeRules)> {
+        let sides = size_handle.edit_surround();
+        let sides = sides.0 + sides.1;
+        let (w, h) = size_handle.text_bound_both(&self.text, TextClass::Edit, self.multi_line);
+        Some((w + sides.0, h + sides.1))
+    }
+
     fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
         let sides = size_handle.edit_surround();
         self.text_rect = Rect {
@@ -132,47 +233,65 @@ impl<H: 'static> Widget for EditBox<H> {
             size: rect.size - (sides.0 + sides.1),
         };
         self.core_data_mut().rect = rect;
+        self.scroll_rate = 3.0 * size_handle.line_height(TextClass::Edit) as f32;
+        self.update_view(size_handle, false);
     }
 
     fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
-        let highlights = ev_mgr.highlight_state(self.id());
+        let highlights = HighlightState {
+            error: self.error,
+            ..ev_mgr.highlight_state(self.id())
+        };
         draw_handle.edit_box(self.core.rect, highlights);
-        let props = TextProperties {
-            class: TextClass::Edit,
-            multi_line: self.multi_line,
-            horiz: Align::Begin,
-            vert: Align::Begin,
+        let caret = match highlights.char_focus {
+            true => Some(self.caret),
+            false => None,
         };
-        let mut text = &self.text;
-        let mut _string;
-        if highlights.char_focus {
-            _string = self.text.clone();
-            _string.push('|');
-            text = &_string;
+        if self.multi_line {
+            let rect = self.content_rect();
+            draw_handle.clip_region(self.text_rect, self.offset, &mut |draw_handle| {
+                draw_handle.text_caret(rect, &self.text, self.props(), &self.spans, caret)
+            });
+        } else {
+            let props = self.props();
+            draw_handle.text_caret(self.text_rect, &self.text, props, &self.spans, caret);
         }
-        draw_handle.text(self.text_rect, text, props);
     }
 }
 
 impl EditBox<()> {
     /// Construct an `EditBox` with the given inital `text`.
     pub fn new<S: Into<String>>(text: S) -> Self {
+        let text = text.into();
         EditBox {
             core: Default::default(),
             text_rect: Default::default(),
             editable: true,
             multi_line: false,
-            text: text.into(),
+            wrap: true,
+            caret: text.len(),
+            text,
+            content_size: Size::ZERO,
+            offset: Coord::ZERO,
+            scroll_rate: 30.0,
             old_state: None,
             last_edit: LastEdit::None,
+            spans: vec![],
+            error: false,
             on_activate: (),
         }
     }
 
+    /// Construct a builder, starting from an empty, editable box
+    pub fn builder() -> Builder<Self> {
+        Builder::new(EditBox::new(""))
+    }
+
     /// Set the event handler to be called on activation.
     ///
     /// The closure `f` is called when the `EditBox` is activated (when the
-    /// "enter" key is pressed). Its result is returned from the event handler.
+    /// "enter" key is pressed in single-line mode). Its result is returned
+    /// from the event handler.
     ///
     /// Technically, this consumes `self` and reconstructs another `EditBox`
     /// with a different parameterisation.
@@ -182,14 +301,60 @@ impl EditBox<()> {
             text_rect: self.text_rect,
             editable: self.editable,
             multi_line: self.multi_line,
+            wrap: self.wrap,
             text: self.text,
+            caret: self.caret,
+            content_size: self.content_size,
+            offset: self.offset,
+            scroll_rate: self.scroll_rate,
             old_state: self.old_state,
             last_edit: self.last_edit,
+            spans: self.spans,
+            error: self.error,
             on_activate: f,
         }
     }
 }
 
+impl<H: 'static> Builder<EditBox<H>> {
+    /// Set the text
+    ///
+    /// The caret is placed at the end of the text.
+    pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+        self.0.text = text.into();
+        self.0.caret = self.0.text.len();
+        self
+    }
+
+    /// Set whether the text is editable (default: true)
+    pub fn editable(self, editable: bool) -> Self {
+        Builder(self.0.editable(editable))
+    }
+
+    /// Set whether multiple text lines are shown (default: false)
+    ///
+    /// See [`EditBox::multi_line`].
+    pub fn multi_line(self, multi_line: bool) -> Self {
+        Builder(self.0.multi_line(multi_line))
+    }
+
+    /// Set whether long lines are wrapped (default: true)
+    ///
+    /// See [`EditBox::wrap`].
+    pub fn wrap(self, wrap: bool) -> Self {
+        Builder(self.0.wrap(wrap))
+    }
+}
+
+impl Builder<EditBox<()>> {
+    /// Set the event handler to be called on activation
+    ///
+    /// See [`EditBox::on_activate`].
+    pub fn on_activate<R, H: Fn(&str) -> R>(self, f: H) -> Builder<EditBox<H>> {
+        Builder(self.0.on_activate(f))
+    }
+}
+
 impl<H> EditBox<H> {
     /// Set whether this `EditBox` is editable.
     pub fn editable(mut self, editable: bool) -> Self {
@@ -198,19 +363,254 @@ impl<H> EditBox<H> {
     }
 
     /// Set whether this `EditBox` shows multiple text lines
+    ///
+    /// In multi-line mode, the Enter key inserts a line break (thus the
+    /// [`EditBox::on_activate`] handler is not called) and the text scrolls
+    /// when larger than the box.
     pub fn multi_line(mut self, multi_line: bool) -> Self {
         self.multi_line = multi_line;
         self
     }
 
+    /// Set whether long lines are wrapped (multi-line mode only)
+    ///
+    /// By default, lines are wrapped to the width of the box. Otherwise, lines
+    /// are only broken by line breaks in the text, which may scroll
+    /// horizontally.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// True if long lines are wrapped
+    #[inline]
+    pub fn is_wrapping(&self) -> bool {
+        self.wrap
+    }
+
+    /// Set whether long lines are wrapped
+    ///
+    /// See [`EditBox::wrap`].
+    pub fn set_wrap(&mut self, tk: &mut dyn TkWindow, wrap: bool) {
+        if wrap != self.wrap {
+            self.wrap = wrap;
+            self.show_caret(tk);
+        }
+    }
+
+    /// Get the caret position (a byte index into the text)
+    #[inline]
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Set the caret position
+    ///
+    /// The `index` is clamped to the text length and rounded down to a `char`
+    /// boundary. In multi-line mode, this scrolls as necessary to show the
+    /// caret.
+    pub fn set_caret(&mut self, tk: &mut dyn TkWindow, index: usize) {
+        let mut index = index.min(self.text.len());
+        while !self.text.is_char_boundary(index) {
+            index -= 1;
+        }
+        self.caret = index;
+        self.last_edit = LastEdit::None;
+        self.show_caret(tk);
+    }
+
+    /// True if the content is marked as invalid
+    #[inline]
+    pub fn has_error(&self) -> bool {
+        self.error
+    }
+
+    /// Mark the content as invalid (or valid)
+    ///
+    /// Themes draw invalid content with an error highlight. The state is not
+    /// changed by editing; it is up to the owner to validate the text.
+    pub fn set_error(&mut self, tk: &mut dyn TkWindow, error: bool) {
+        if error != self.error {
+            self.error = error;
+            tk.redraw(self.id());
+        }
+    }
+
+    fn props(&self) -> TextProperties {
+        TextProperties {
+            class: TextClass::Edit,
+            multi_line: self.multi_line,
+            horiz: Align::Begin,
+            vert: Align::Begin,
+        }
+    }
+
+    // Rect of the laid-out text, before scrolling
+    fn content_rect(&self) -> Rect {
+        match self.multi_line {
+            false => self.text_rect,
+            true => Rect {
+                pos: self.text_rect.pos,
+                size: self.content_size,
+            },
+        }
+    }
+
+    // Update the content size and scroll offset, optionally scrolling to show
+    // the caret
+    fn update_view(&mut self, size_handle: &mut dyn SizeHandle, show_caret: bool) {
+        if !self.multi_line {
+            return;
+        }
+        let view = self.text_rect.size;
+        let margin = size_handle.inner_margin();
+        let line_height = size_handle.line_height(TextClass::Edit);
+
+        let width = match self.wrap {
+            true => view.0,
+            false => {
+                let axis = AxisInfo::new(false, None);
+                let rules = size_handle.text_bound(&self.text, TextClass::Edit, true, axis);
+                // Leave space for the caret after the longest line
+                view.0.max(rules.ideal_size() + line_height)
+            }
+        };
+        // Measure the height without a bound
+        let rect = Rect {
+            pos: self.text_rect.pos,
+            size: Size(width, i32::MAX as u32),
+        };
+        let len = self.text.len();
+        let end = size_handle.text_caret_rect(rect, &self.text, self.props(), len);
+        let height = (end.pos.1 - rect.pos.1) as u32 + end.size.1 + margin.1;
+        self.content_size = Size(width, view.1.max(height));
+
+        let mut offset = self.offset;
+        if show_caret {
+            let caret = size_handle.text_caret_rect(rect, &self.text, self.props(), self.caret);
+            let pos = caret.pos - rect.pos;
+            let (x0, x1) = (pos.0 - margin.0 as i32, pos.0 + margin.0 as i32);
+            let (y0, y1) = (pos.1, pos.1 + caret.size.1 as i32);
+            offset.0 = offset.0.min(x0).max(x1 - view.0 as i32);
+            offset.1 = offset.1.min(y0).max(y1 - view.1 as i32);
+        }
+        self.offset = self.clamp_offset(offset);
+    }
+
+    fn clamp_offset(&self, offset: Coord) -> Coord {
+        let max = Coord::from(self.content_size) - Coord::from(self.text_rect.size);
+        Coord(offset.0.min(max.0).max(0), offset.1.min(max.1).max(0))
+    }
+
+    fn show_caret(&mut self, tk: &mut dyn TkWindow) {
+        tk.with_size_handle(&mut |size_handle| self.update_view(size_handle, true));
+        tk.redraw(self.id());
+    }
+
+    // Find the caret position nearest `coord`, in scrolled coordinates
+    fn index_at(&self, size_handle: &mut dyn SizeHandle, coord: Coord) -> usize {
+        let rect = self.content_rect();
+        size_handle.text_index_nearest(rect, &self.text, self.props(), coord)
+    }
+
+    fn set_caret_from_coord(&mut self, tk: &mut dyn TkWindow, coord: Coord) {
+        let coord = coord + self.offset;
+        let mut index = self.caret;
+        tk.with_size_handle(&mut |size_handle| index = self.index_at(size_handle, coord));
+        self.set_caret(tk, index);
+    }
+
+    // Move the caret, returning false if the key is not used
+    fn nav_key(&mut self, tk: &mut dyn TkWindow, key: NavKey) -> bool {
+        let index = match key {
+            NavKey::Left => (self.text[..self.caret].char_indices().next_back())
+                .map(|(i, _)| i)
+                .unwrap_or(0),
+            NavKey::Right => (self.text[self.caret..].chars().next())
+                .map(|c| self.caret + c.len_utf8())
+                .unwrap_or(self.caret),
+            NavKey::Home if !self.multi_line => 0,
+            NavKey::End if !self.multi_line => self.text.len(),
+            _ if !self.multi_line => return false,
+            key => {
+                // Find the nearest position on another (visual) line
+                let page = self.text_rect.size.1 as i32;
+                let mut index = self.caret;
+                tk.with_size_handle(&mut |size_handle| {
+                    let rect = self.content_rect();
+                    let props = self.props();
+                    let caret = size_handle.text_caret_rect(rect, &self.text, props, self.caret);
+                    let line = caret.size.1 as i32;
+                    let (x, y) = (caret.pos.0, caret.pos.1 + line / 2);
+                    let coord = match key {
+                        NavKey::Up => Coord(x, y - line),
+                        NavKey::Down => Coord(x, y + line),
+                        NavKey::PageUp => Coord(x, y - page),
+                        NavKey::PageDown => Coord(x, y + page),
+                        NavKey::Home => Coord(rect.pos.0, y),
+                        _ => Coord(rect.pos.0 + rect.size.0 as i32, y),
+                    };
+                    index = self.index_at(size_handle, coord);
+                });
+                index
+            }
+        };
+        self.set_caret(tk, index);
+        true
+    }
+
+    // Scroll, returning false if already at the limit
+    fn scroll(&mut self, tk: &mut dyn TkWindow, delta: ScrollDelta) -> bool {
+        if !self.multi_line {
+            return false;
+        }
+        let d = match delta {
+            ScrollDelta::LineDelta(x, y) => Coord(
+                (-self.scroll_rate * x) as i32,
+                (self.scroll_rate * y) as i32,
+            ),
+            ScrollDelta::PixelDelta(d) => d,
+        };
+        let offset = self.clamp_offset(self.offset - d);
+        if offset == self.offset {
+            return false;
+        }
+        self.offset = offset;
+        tk.redraw(self.id());
+        true
+    }
+
+    // Insert `s` at the caret
+    fn insert(&mut self, s: &str, edit: LastEdit) {
+        if self.last_edit != edit {
+            self.old_state = Some(self.text.clone());
+            self.last_edit = edit;
+        }
+        self.text.insert_str(self.caret, s);
+        self.caret += s.len();
+    }
+
+    // Remove `range` of the text, leaving the caret at its start
+    fn remove(&mut self, range: Range<usize>, edit: LastEdit) {
+        if range.start == range.end {
+            return;
+        }
+        if self.last_edit != edit {
+            self.old_state = Some(self.text.clone());
+            self.last_edit = edit;
+        }
+        self.caret = range.start;
+        self.text.replace_range(range, "");
+    }
+
     fn received_char(&mut self, tk: &mut dyn TkWindow, c: char) -> bool {
         if !self.editable {
             return false;
         }
 
-        // TODO: Text selection and editing (see Unicode std. section 5.11)
+        // TODO: Text selection (see Unicode std. section 5.11)
         // Note that it may make sense to implement text shaping first.
-        // For now we just filter control characters and append the rest.
+        // For now we filter control characters and insert the rest.
         if c < '\u{20}' || (c >= '\u{7f}' && c <= '\u{9f}') {
             match c {
                 '\u{03}' /* copy */ => {
@@ -218,34 +618,40 @@ impl<H> EditBox<H> {
                     tk.set_clipboard(self.text.clone());
                 }
                 '\u{08}' /* backspace */  => {
-                    if self.last_edit != LastEdit::Backspace {
-                        self.old_state = Some(self.text.clone());
-                        self.last_edit = LastEdit::Backspace;
-                    }
-                    self.text.pop();
+                    let start = (self.text[..self.caret].char_indices().next_back())
+                        .map(|(i, _)| i)
+                        .unwrap_or(0);
+                    self.remove(start..self.caret, LastEdit::Backspace);
                 }
                 '\u{09}' /* tab */ => (),
-                '\u{0A}' /* line feed */ => (),
+                '\u{0A}' /* line feed */ | '\u{0D}' /* carriage return (\r) */ => {
+                    if !self.multi_line {
+                        return c == '\r';
+                    }
+                    self.insert("\n", LastEdit::Insert);
+                }
                 '\u{0B}' /* vertical tab */ => (),
                 '\u{0C}' /* form feed */ => (),
-                '\u{0D}' /* carriage return (\r) */ => return true,
                 '\u{16}' /* paste */ => {
-                    if self.last_edit != LastEdit::Paste {
-                        self.old_state = Some(self.text.clone());
-                        self.last_edit = LastEdit::Paste;
-                    }
                     if let Some(content) = tk.get_clipboard() {
                         // We cut the content short on control characters and
-                        // ignore them (preventing line-breaks and ignoring any
-                        // actions such as recursive-paste).
-                        let mut end = content.len();
-                        for (i, b) in content.as_bytes().iter().cloned().enumerate() {
-                            if b < 0x20 || (b >= 0x7f && b <= 0x9f) {
-                                end = i;
+                        // ignore them (preventing line-breaks in single-line
+                        // mode and ignoring any actions such as
+                        // recursive-paste).
+                        let multi_line = self.multi_line;
+                        let mut text = String::with_capacity(content.len());
+                        for c in content.chars() {
+                            if multi_line && (c == '\n' || c == '\r') {
+                                if c == '\n' {
+                                    text.push(c);
+                                }
+                            } else if c < '\u{20}' || ('\u{7f}'..='\u{9f}').contains(&c) {
                                 break;
+                            } else {
+                                text.push(c);
                             }
                         }
-                        self.text.push_str(&content[0..end]);
+                        self.insert(&text, LastEdit::Paste);
                     }
                 }
                 '\u{1A}' /* undo and redo */ => {
@@ -253,27 +659,27 @@ impl<H> EditBox<H> {
                     // NOTE: undo *and* redo shortcuts map to this control char
                     if let Some(state) = self.old_state.as_mut() {
                         std::mem::swap(state, &mut self.text);
+                        self.caret = self.text.len();
                         self.last_edit = LastEdit::None;
                     }
                 }
                 '\u{1B}' /* escape */ => (),
                 '\u{7f}' /* delete */ => {
-                    if self.last_edit != LastEdit::Clear {
-                        self.old_state = Some(self.text.clone());
-                        self.last_edit = LastEdit::Clear;
-                    }
-                    self.text.clear();
+                    let end = (self.text[self.caret..].chars().next())
+                        .map(|c| self.caret + c.len_utf8())
+                        .unwrap_or(self.caret);
+                    self.remove(self.caret..end, LastEdit::Delete);
                 }
                 _ => (),
             };
         } else {
-            if self.last_edit != LastEdit::Insert {
-                self.old_state = Some(self.text.clone());
-                self.last_edit = LastEdit::Insert;
-            }
-            self.text.push(c);
+            let mut buf = [0; 4];
+            self.insert(c.encode_utf8(&mut buf), LastEdit::Insert);
         }
-        tk.redraw(self.id());
+        if c != '\u{03}' {
+            self.spans.clear();
+        }
+        self.show_caret(tk);
         false
     }
 }
@@ -285,6 +691,15 @@ impl<H> HasText for EditBox<H> {
 
     fn set_string(&mut self, tk: &mut dyn TkWindow, text: String) {
         self.text = text;
+        self.caret = self.text.len();
+        self.spans.clear();
+        self.show_caret(tk);
+    }
+}
+
+impl<H> HasSpans for EditBox<H> {
+    fn set_spans(&mut self, tk: &mut dyn TkWindow, spans: Vec<(Range<usize>, SpanClass)>) {
+        self.spans = spans;
         tk.redraw(self.id());
     }
 }
@@ -310,16 +725,27 @@ impl Handler for EditBox<()> {
     fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<VoidMsg> {
         match action {
             Action::Activate => {
-                tk.update_data(&mut |data| data.set_char_focus(self.id()));
+                tk.defer(Deferred::SetCharFocus(Some(self.id())));
                 Response::None
             }
             Action::ReceivedCharacter(c) => {
                 self.received_char(tk, c);
                 Response::None
             }
+            Action::NavKey(key) if self.nav_key(tk, key) => Response::None,
+            Action::Scroll(delta) if self.scroll(tk, delta) => Response::None,
             a @ _ => Response::unhandled_action(a),
         }
     }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<VoidMsg> {
+        if let Event::PressStart { source, coord } = event {
+            if source.is_primary() {
+                self.set_caret_from_coord(tk, coord);
+            }
+        }
+        Manager::handle_generic(self, tk, event)
+    }
 }
 
 impl<M, H: Fn(&str) -> M> Handler for EditBox<H> {
@@ -333,7 +759,7 @@ impl<M, H: Fn(&str) -> M> Handler for EditBox<H> {
     fn handle_action(&mut self, tk: &mut dyn TkWindow, action: Action) -> Response<M> {
         match action {
             Action::Activate => {
-                tk.update_data(&mut |data| data.set_char_focus(self.id()));
+                tk.defer(Deferred::SetCharFocus(Some(self.id())));
                 Response::None
             }
             Action::ReceivedCharacter(c) => {
@@ -343,7 +769,18 @@ impl<M, H: Fn(&str) -> M> Handler for EditBox<H> {
                     Response::None
                 }
             }
+            Action::NavKey(key) if self.nav_key(tk, key) => Response::None,
+            Action::Scroll(delta) if self.scroll(tk, delta) => Response::None,
             a @ _ => Response::unhandled_action(a),
         }
     }
+
+    fn handle(&mut self, tk: &mut dyn TkWindow, _: Address, event: Event) -> Response<M> {
+        if let Event::PressStart { source, coord } = event {
+            if source.is_primary() {
+                self.set_caret_from_coord(tk, coord);
+            }
+        }
+        Manager::handle_generic(self, tk, event)
+    }
 }
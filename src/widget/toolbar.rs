@@ -0,0 +1,359 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Toolbar with overflow menu
+
+use std::any::Any;
+
+use crate::event::{self, Address, Event, Handler, HighlightState, Manager, Response};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::theme::{Align, DrawHandle, SizeHandle, TextClass, TextProperties};
+use crate::{CoreData, TkAction, TkWindow, Widget, WidgetCore};
+
+/// A horizontal toolbar
+///
+/// Items (usually buttons, e.g. [`TextButton`]) are laid out in a row, each
+/// at its ideal width. Where the toolbar is given less than the total width,
+/// as many leading items as fit are shown, followed by an overflow button
+/// ("»"); the remaining items are shown in a pop-up column when this is
+/// clicked. Use [`Toolbar::visible_len`] to find which items are shown in the
+/// row after layout.
+///
+/// Messages from items are returned to the parent. Activating an item in the
+/// overflow menu also closes the menu.
+///
+/// [`TextButton`]: crate::widget::TextButton
+#[derive(Clone, Debug, Default)]
+pub struct Toolbar<W: Widget> {
+    core: CoreData,
+    widgets: Vec<W>,
+    // Ideal widths and heights of items
+    widths: Vec<u32>,
+    heights: Vec<u32>,
+    visible: usize,
+    overflow: Rect,
+    popup: Rect,
+    open: bool,
+}
+
+impl<W: Widget> Toolbar<W> {
+    /// Construct, with the given items
+    pub fn new(widgets: Vec<W>) -> Self {
+        Toolbar {
+            core: Default::default(),
+            widgets,
+            widths: vec![],
+            heights: vec![],
+            visible: 0,
+            overflow: Rect::default(),
+            popup: Rect::default(),
+            open: false,
+        }
+    }
+
+    /// Number of items
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    /// True if there are no items
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Number of items shown in the row
+    ///
+    /// This is determined on layout: items `0..visible_len()` are shown in the
+    /// row while the rest are in the overflow menu.
+    #[inline]
+    pub fn visible_len(&self) -> usize {
+        self.visible
+    }
+
+    /// True if any items are in the overflow menu
+    #[inline]
+    pub fn has_overflow(&self) -> bool {
+        self.visible < self.widgets.len()
+    }
+
+    /// Access an item
+    #[inline]
+    pub fn item(&self, index: usize) -> Option<&W> {
+        self.widgets.get(index)
+    }
+
+    /// Access an item mutably
+    #[inline]
+    pub fn item_mut(&mut self, index: usize) -> Option<&mut W> {
+        self.widgets.get_mut(index)
+    }
+
+    /// Append an item
+    ///
+    /// Triggers a [reconfigure action](TkWindow::send_action).
+    pub fn push(&mut self, tk: &mut dyn TkWindow, widget: W) {
+        self.widgets.push(widget);
+        tk.send_action(TkAction::Reconfigure);
+    }
+
+    /// True while the overflow menu is open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open or close the overflow menu
+    ///
+    /// The menu is not opened when all items fit in the row.
+    pub fn set_open(&mut self, tk: &mut dyn TkWindow, open: bool) {
+        self.open = open && self.has_overflow();
+        let id = self.id();
+        if self.open {
+            tk.data_mut().set_popup(Some(id));
+        } else if tk.data().popup() == Some(id) {
+            tk.data_mut().set_popup(None);
+        }
+        tk.redraw(id);
+    }
+
+    // Number of leading items which fit within `width`
+    fn fit(&self, width: u32) -> usize {
+        let total: u32 = self.widths.iter().sum();
+        if total <= width {
+            return self.widths.len();
+        }
+        let width = width.saturating_sub(self.overflow.size.0);
+        let mut used = 0;
+        for (n, w) in self.widths.iter().enumerate() {
+            used += w;
+            if used > width {
+                return n;
+            }
+        }
+        self.widths.len()
+    }
+}
+
+// We implement this manually, because the derive implementation cannot handle
+// vectors of child widgets.
+impl<W: Widget> WidgetCore for Toolbar<W> {
+    #[inline]
+    fn core_data(&self) -> &CoreData {
+        &self.core
+    }
+    #[inline]
+    fn core_data_mut(&mut self) -> &mut CoreData {
+        &mut self.core
+    }
+
+    #[inline]
+    fn widget_name(&self) -> &'static str {
+        "Toolbar"
+    }
+
+    #[inline]
+    fn as_widget(&self) -> &dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_widget_mut(&mut self) -> &mut dyn Widget {
+        self
+    }
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.widgets.len()
+    }
+    #[inline]
+    fn get(&self, index: usize) -> Option<&dyn Widget> {
+        self.widgets.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Widget> {
+        self.widgets.get_mut(index).map(|w| w.as_widget_mut())
+    }
+
+    fn walk(&self, f: &mut dyn FnMut(&dyn Widget)) {
+        for child in &self.widgets {
+            child.walk(f);
+        }
+        f(self)
+    }
+    fn walk_mut(&mut self, f: &mut dyn FnMut(&mut dyn Widget)) {
+        for child in &mut self.widgets {
+            child.walk_mut(f);
+        }
+        f(self)
+    }
+}
+
+impl<W: Widget> Widget for Toolbar<W> {
+    fn redraw_on_hover(&self) -> bool {
+        true
+    }
+
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let sides = size_handle.button_surround();
+        let button = SizeRules::fixed(axis.extract_size(sides.0 + sides.1))
+            + size_handle.text_bound("»", TextClass::Button, false, axis);
+        let mut rules = SizeRules::EMPTY;
+        let mut sizes = Vec::with_capacity(self.widgets.len());
+        for child in &mut self.widgets {
+            let child_rules = layout::child_rules(child, size_handle, axis);
+            sizes.push(child_rules.ideal_size());
+            rules = match axis.vertical() {
+                false => rules + child_rules,
+                true => rules.max(child_rules),
+            };
+        }
+        if !axis.vertical() {
+            self.widths = sizes;
+            self.overflow.size.0 = button.ideal_size();
+            // We may show only the overflow button
+            let min = match self.widgets.is_empty() {
+                true => 0,
+                false => button.ideal_size(),
+            };
+            SizeRules::variable(min, rules.ideal_size().max(min))
+        } else {
+            self.heights = sizes;
+            rules.max(button)
+        }
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.visible = self.fit(rect.size.0);
+
+        let mut pos = rect.pos;
+        for (child, width) in self.widgets.iter_mut().zip(&self.widths) {
+            let size = Size(*width, rect.size.1);
+            child.set_rect(size_handle, Rect { pos, size });
+            pos.0 += *width as i32;
+        }
+
+        // The overflow button is at the end of the row
+        let right = rect.pos.0 + rect.size.0 as i32;
+        let button_width = self.overflow.size.0.min(rect.size.0);
+        self.overflow = Rect {
+            pos: Coord(right - button_width as i32, rect.pos.1),
+            size: Size(button_width, rect.size.1),
+        };
+
+        // Hidden items are placed in a column below, right-aligned
+        let hidden = self.visible..self.widgets.len();
+        let width = self.widths[hidden.clone()]
+            .iter()
+            .cloned()
+            .max()
+            .unwrap_or(0);
+        let height = self.heights[hidden.clone()].iter().sum();
+        let mut pos = Coord(right - width as i32, rect.pos.1 + rect.size.1 as i32);
+        self.popup = Rect {
+            pos,
+            size: Size(width, height),
+        };
+        for index in hidden {
+            let size = Size(width, self.heights[index]);
+            self.widgets[index].set_rect(size_handle, Rect { pos, size });
+            pos.1 += size.1 as i32;
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        for child in &self.widgets[..self.visible] {
+            layout::draw_child(child, draw_handle, ev_mgr);
+        }
+        if !self.has_overflow() {
+            return;
+        }
+
+        let highlights = ev_mgr.highlight_state(self.id());
+        let hl = HighlightState {
+            hover: highlights.hover && self.overflow.contains(ev_mgr.last_mouse_coord()),
+            depress: self.open,
+            ..HighlightState::default()
+        };
+        draw_handle.button(self.overflow, hl);
+        let props = TextProperties {
+            class: TextClass::Button,
+            multi_line: false,
+            horiz: Align::Centre,
+            vert: Align::Centre,
+        };
+        draw_handle.text(self.overflow, "»", props);
+
+        if self.open && ev_mgr.popup() == Some(self.id()) {
+            draw_handle.overlay(self.popup, &mut |draw_handle| {
+                draw_handle.edit_box(self.popup, HighlightState::default());
+                for child in &self.widgets[self.visible..] {
+                    layout::draw_child(child, draw_handle, ev_mgr);
+                }
+            });
+        }
+    }
+}
+
+impl<W: Widget + Handler> Handler for Toolbar<W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        if self.open && tk.data().popup() != Some(self.id()) {
+            // The pop-up was closed externally (e.g. via Escape)
+            self.open = false;
+        }
+
+        let index = match addr {
+            Address::Id(id) => self.widgets.iter().position(|w| id <= w.id()),
+            Address::Coord(coord) if self.open && self.popup.contains(coord) => {
+                let hidden = &self.widgets[self.visible..];
+                (hidden.iter().position(|w| w.rect().contains(coord))).map(|i| i + self.visible)
+            }
+            Address::Coord(_) if self.open => None,
+            Address::Coord(coord) => {
+                (self.widgets[..self.visible].iter()).position(|w| w.rect().contains(coord))
+            }
+        };
+        if let Some(index) = index {
+            let r = self.widgets[index].handle(tk, addr, event);
+            if let Response::Msg(_) = r {
+                if index >= self.visible {
+                    self.set_open(tk, false);
+                }
+            }
+            return r;
+        }
+
+        match event {
+            Event::PressStart { source, coord } if source.is_primary() => {
+                if self.has_overflow() && self.overflow.contains(coord) {
+                    let open = !self.open;
+                    self.set_open(tk, open);
+                } else if self.open {
+                    // Dismiss on a click outside the menu
+                    self.set_open(tk, false);
+                }
+                Response::None
+            }
+            e => Manager::handle_generic(self, tk, e),
+        }
+    }
+}
@@ -6,34 +6,59 @@
 //! Window widgets
 
 use std::fmt::{self, Debug};
+use std::rc::Rc;
 
-use crate::event::{Address, Callback, Event, Handler, Response, VoidMsg};
-use crate::geom::Size;
-use crate::layout::{self};
+use crate::event::{
+    self, Action, Address, Callback, Event, Handler, Manager, Response, Shortcut, VoidMsg,
+};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
 use crate::macros::Widget;
-use crate::{CoreData, LayoutData, TkWindow, Widget};
+use crate::theme::{DrawHandle, SizeHandle};
+use crate::{CoreData, Modality, TkWindow, Widget, WidgetCore};
+
+// Handler of messages from the content of a window
+type MsgHandler<W> = Rc<dyn Fn(&mut W, &mut dyn TkWindow, <W as Handler>::Msg)>;
+
+// Handler of a window-level shortcut
+type ShortcutFn<W> = &'static dyn Fn(&mut W, &mut dyn TkWindow);
 
 /// The main instantiation of the [`Window`] trait.
-#[widget(layout = single)]
+///
+/// This adapts any widget into a window. Messages returned by the widget are
+/// passed to the handler set by [`Window::on_message`], if any, and otherwise
+/// discarded.
+///
+/// Unless a size is set with [`Window::with_size`], the window initially
+/// takes the ideal size of its content.
+///
+/// [`Window`]: kas::Window
+#[widget]
 #[derive(Widget)]
-pub struct Window<W: Widget + 'static> {
+pub struct Window<W: Widget + Handler + 'static> {
     #[core]
     core: CoreData,
-    #[layout_data]
-    layout_data: <Self as LayoutData>::Data,
     min_size: Size,
+    size: Option<Size>,
+    margin: bool,
+    margin_size: Size,
     title: String,
+    resize_snapshot: bool,
+    modality: Modality,
     #[widget]
     w: W,
     fns: Vec<(Callback, &'static dyn Fn(&mut W, &mut dyn TkWindow))>,
+    shortcuts: Vec<(Shortcut, Action)>,
+    shortcut_fns: Vec<ShortcutFn<W>>,
+    on_msg: Option<MsgHandler<W>>,
 }
 
-impl<W: Widget> Debug for Window<W> {
+impl<W: Widget + Handler> Debug for Window<W> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Window {{ core: {:?}, min_size: {:?}, solver: <omitted>, w: {:?}, fns: [",
-            self.core, self.min_size, self.w
+            "Window {{ core: {:?}, min_size: {:?}, size: {:?}, w: {:?}, fns: [",
+            self.core, self.min_size, self.size, self.w
         )?;
         let mut iter = self.fns.iter();
         if let Some(first) = iter.next() {
@@ -42,36 +67,97 @@ impl<W: Widget> Debug for Window<W> {
                 write!(f, ", ({:?}, <Fn>)", next.0)?;
             }
         }
-        write!(f, "] }}")
+        write!(f, "], ... }}")
     }
 }
 
-impl<W: Widget + Clone> Clone for Window<W> {
+impl<W: Widget + Handler + Clone> Clone for Window<W> {
     fn clone(&self) -> Self {
         Window {
             core: self.core.clone(),
-            layout_data: self.layout_data.clone(),
             min_size: self.min_size,
+            size: self.size,
+            margin: self.margin,
+            margin_size: self.margin_size,
             title: self.title.clone(),
+            resize_snapshot: self.resize_snapshot,
+            modality: self.modality,
             w: self.w.clone(),
             fns: self.fns.clone(),
+            shortcuts: self.shortcuts.clone(),
+            shortcut_fns: self.shortcut_fns.clone(),
+            on_msg: self.on_msg.clone(),
         }
     }
 }
 
-impl<W: Widget> Window<W> {
+impl<W: Widget + Handler> Window<W> {
     /// Create
     pub fn new<T: ToString>(title: T, w: W) -> Window<W> {
         Window {
             core: Default::default(),
-            layout_data: Default::default(),
             min_size: Size::ZERO,
+            size: None,
+            margin: false,
+            margin_size: Size::ZERO,
             title: title.to_string(),
+            resize_snapshot: false,
+            modality: Modality::None,
             w,
             fns: Vec::new(),
+            shortcuts: Vec::new(),
+            shortcut_fns: Vec::new(),
+            on_msg: None,
         }
     }
 
+    /// Set the initial size (chain style)
+    ///
+    /// See [`kas::Window::initial_size`]. By default, the window takes the
+    /// ideal size of its content.
+    pub fn with_size(mut self, size: Size) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Enable or disable the root margin (chain style)
+    ///
+    /// If enabled, the content is surrounded by the theme's outer margin
+    /// (see [`SizeHandle::outer_margin`]). By default this is disabled.
+    pub fn with_margin(mut self, margin: bool) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Enable or disable resize snapshots (chain style)
+    ///
+    /// See [`kas::Window::resize_snapshot`]. By default this is disabled.
+    pub fn with_resize_snapshot(mut self, snapshot: bool) -> Self {
+        self.resize_snapshot = snapshot;
+        self
+    }
+
+    /// Set the modality (chain style)
+    ///
+    /// See [`kas::Window::modality`]. By default the window is not modal.
+    pub fn with_modality(mut self, modality: Modality) -> Self {
+        self.modality = modality;
+        self
+    }
+
+    /// Set the message handler (chain style)
+    ///
+    /// The closure `f` is called with each message returned by the content,
+    /// along with the content itself. Without a handler, messages are
+    /// discarded.
+    pub fn on_message<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut W, &mut dyn TkWindow, W::Msg) + 'static,
+    {
+        self.on_msg = Some(Rc::new(f));
+        self
+    }
+
     /// Add a closure to be called, with a reference to self, on the given
     /// condition. The closure must be passed by reference.
     pub fn add_callback(
@@ -81,9 +167,71 @@ impl<W: Widget> Window<W> {
     ) {
         self.fns.push((condition, f));
     }
+
+    /// Add a closure to be called, with a reference to self, when the given
+    /// keyboard shortcut is pressed. The closure must be passed by reference.
+    ///
+    /// The shortcut applies throughout the window (see
+    /// [`Widget::shortcuts`]); for example the closure may save a document
+    /// on Ctrl+S.
+    pub fn add_shortcut(
+        &mut self,
+        shortcut: Shortcut,
+        f: &'static dyn Fn(&mut W, &mut dyn TkWindow),
+    ) {
+        self.shortcuts.push((shortcut, Action::Shortcut(shortcut)));
+        self.shortcut_fns.push(f);
+    }
+
+    /// Access the content
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.w
+    }
+
+    /// Access the content mutably
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.w
+    }
 }
 
-impl<W: Widget + Handler<Msg = VoidMsg> + 'static> Handler for Window<W> {
+impl<W: Widget + Handler> Widget for Window<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.margin_size = match self.margin {
+            true => size_handle.outer_margin(),
+            false => Size::ZERO,
+        };
+        let margins = 2 * axis.extract_size(self.margin_size);
+        let rules = layout::child_rules(&mut self.w, size_handle, axis) + margins;
+        match axis.vertical() {
+            false => self.min_size.0 = rules.min_size(),
+            true => self.min_size.1 = rules.min_size(),
+        }
+        rules
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        let m = self.margin_size;
+        let pos = rect.pos + Coord::from(m);
+        let size = Size(
+            rect.size.0.saturating_sub(2 * m.0),
+            rect.size.1.saturating_sub(2 * m.1),
+        );
+        self.w.set_rect(size_handle, Rect { pos, size });
+    }
+
+    fn shortcuts(&self) -> &[(Shortcut, Action)] {
+        &self.shortcuts
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &event::Manager) {
+        layout::draw_child(&self.w, draw_handle, ev_mgr);
+    }
+}
+
+impl<W: Widget + Handler + 'static> Handler for Window<W> {
     type Msg = VoidMsg;
 
     fn handle(
@@ -92,16 +240,52 @@ impl<W: Widget + Handler<Msg = VoidMsg> + 'static> Handler for Window<W> {
         addr: Address,
         event: Event,
     ) -> Response<Self::Msg> {
-        // The window itself doesn't handle events, so we can just pass through
-        self.w.handle(tk, addr, event)
+        match (addr, &event) {
+            (Address::Coord(coord), _) if !self.w.rect().contains(coord) => {
+                // The coordinate is within the margin
+                return Manager::handle_generic(self, tk, event);
+            }
+            (Address::Id(id), Event::Action(Action::Shortcut(shortcut))) if id == self.id() => {
+                let index = self.shortcuts.iter().position(|s| s.0 == *shortcut);
+                if let Some(index) = index {
+                    (self.shortcut_fns[index])(&mut self.w, tk);
+                    return Response::None;
+                }
+            }
+            _ => (),
+        }
+        match self.w.handle(tk, addr, event) {
+            Response::None => Response::None,
+            Response::Identify(id) => Response::Identify(id),
+            Response::Unhandled(event) => Response::Unhandled(event),
+            Response::Msg(msg) => {
+                if let Some(f) = self.on_msg.clone() {
+                    f(&mut self.w, tk, msg);
+                }
+                Response::None
+            }
+        }
     }
 }
 
-impl<W: Widget + Handler<Msg = VoidMsg> + 'static> kas::Window for Window<W> {
+impl<W: Widget + Handler + 'static> kas::Window for Window<W> {
     fn title(&self) -> &str {
         &self.title
     }
 
+    fn initial_size(&mut self, tk: &mut dyn TkWindow) -> Option<Size> {
+        if self.size.is_some() {
+            return self.size;
+        }
+        let mut size = Size::ZERO;
+        tk.with_size_handle(&mut |size_handle| {
+            let w = self.size_rules(size_handle, AxisInfo::new(false, None));
+            let h = self.size_rules(size_handle, AxisInfo::new(true, Some(w.ideal_size())));
+            size = Size(w.ideal_size(), h.ideal_size());
+        });
+        Some(size)
+    }
+
     fn resize(&mut self, tk: &mut dyn TkWindow, size: Size) {
         layout::solve(self, tk, size);
     }
@@ -115,4 +299,12 @@ impl<W: Widget + Handler<Msg = VoidMsg> + 'static> kas::Window for Window<W> {
         let cb = &mut self.fns[index].1;
         cb(&mut self.w, tk);
     }
+
+    fn modality(&self) -> Modality {
+        self.modality
+    }
+
+    fn resize_snapshot(&self) -> bool {
+        self.resize_snapshot
+    }
 }
@@ -0,0 +1,311 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Zoomable view
+
+use crate::event::{Action, Address, Event, Handler, Manager, Response, ScrollDelta};
+use crate::geom::{Coord, Rect, Size};
+use crate::layout::{self, AxisInfo, SizeRules};
+use crate::macros::Widget;
+use crate::theme::{DrawHandle, SizeHandle, TextClass};
+use crate::{CoreData, TkWindow, Widget, WidgetCore};
+
+// Scale factor per line of mouse-wheel scrolling
+const WHEEL_FACTOR: f32 = 1.2;
+// Pixel scroll distance equivalent to one line
+const PIXELS_PER_LINE: f32 = 50.0;
+
+/// A zoomable, pannable view
+///
+/// The child is drawn magnified by a `scale` factor about the view's top-left
+/// corner, then translated by `-offset` (in view pixels). Mouse-wheel scrolling
+/// zooms about the mouse cursor, as does a two-finger pinch about its centre,
+/// while dragging pans the view (where not handled by the child). Event coordinates are mapped back to the child's
+/// coordinate space, thus the child need not be aware of the zoom.
+///
+/// The child is sized to fill the view (at a scale of 1). The offset is
+/// limited such that the child covers the view when magnified, or lies
+/// within it when shrunk.
+///
+/// Pop-ups opened by the child are positioned without zoom.
+#[widget]
+#[derive(Clone, Debug, Widget)]
+pub struct Zoom<W: Widget> {
+    #[core]
+    core: CoreData,
+    min_child_size: Size,
+    child_size: Size,
+    scale: f32,
+    scale_limits: (f32, f32),
+    offset: Coord,
+    #[widget]
+    child: W,
+}
+
+impl<W: Widget> Zoom<W> {
+    /// Construct a new zoomable view around a child widget
+    #[inline]
+    pub fn new(child: W) -> Self {
+        Zoom {
+            core: Default::default(),
+            min_child_size: Size::ZERO,
+            child_size: Size::ZERO,
+            scale: 1.0,
+            scale_limits: (0.25, 8.0),
+            offset: Coord::ZERO,
+            child,
+        }
+    }
+
+    /// Set the range of allowed scale factors (inline)
+    ///
+    /// By default this is from 0.25 to 8. Requires `0 < min ≤ 1 ≤ max`.
+    #[inline]
+    pub fn with_scale_limits(mut self, min: f32, max: f32) -> Self {
+        assert!(0.0 < min && min <= 1.0 && 1.0 <= max);
+        self.scale_limits = (min, max);
+        self.scale = self.scale.max(min).min(max);
+        self
+    }
+
+    /// Set the initial scale factor (inline)
+    ///
+    /// This is clamped to the allowed range.
+    #[inline]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale.max(self.scale_limits.0).min(self.scale_limits.1);
+        self
+    }
+
+    /// Access inner widget directly
+    #[inline]
+    pub fn inner(&self) -> &W {
+        &self.child
+    }
+
+    /// Access inner widget directly
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.child
+    }
+
+    /// Get the scale factor
+    #[inline]
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Set the scale factor, zooming about the centre of the view
+    ///
+    /// The scale is clamped to the allowed range (see
+    /// [`Zoom::with_scale_limits`]). Returns true if the scale changed.
+    pub fn set_scale(&mut self, tk: &mut dyn TkWindow, scale: f32) -> bool {
+        let centre = self.centre();
+        self.zoom_about(tk, scale, centre)
+    }
+
+    /// Get the current offset
+    #[inline]
+    pub fn offset(&self) -> Coord {
+        self.offset
+    }
+
+    /// Set the offset
+    ///
+    /// The offset is clamped to the allowed range. Returns true if the offset
+    /// is not identical to the old offset.
+    pub fn set_offset(&mut self, tk: &mut dyn TkWindow, offset: Coord) -> bool {
+        let offset = self.clamp_offset(offset);
+        if offset != self.offset {
+            self.offset = offset;
+            tk.redraw(self.id());
+            return true;
+        }
+        false
+    }
+
+    /// Map a coordinate in the view to the child's coordinate space
+    pub fn to_child(&self, coord: Coord) -> Coord {
+        let pos = self.core.rect.pos;
+        let v = coord + self.offset - pos;
+        let f = |x: i32| (x as f32 / self.scale).round() as i32;
+        pos + Coord(f(v.0), f(v.1))
+    }
+
+    fn centre(&self) -> Coord {
+        let rect = self.core.rect;
+        rect.pos + Coord(rect.size.0 as i32 / 2, rect.size.1 as i32 / 2)
+    }
+
+    fn clamp_offset(&self, offset: Coord) -> Coord {
+        let view = self.core.rect.size;
+        let extra =
+            |child: u32, view: u32| (child as f32 * self.scale).round() as i32 - view as i32;
+        let extra = Coord(
+            extra(self.child_size.0, view.0),
+            extra(self.child_size.1, view.1),
+        );
+        offset
+            .max(extra.min(Coord::ZERO))
+            .min(extra.max(Coord::ZERO))
+    }
+
+    // Set the scale, keeping the content under `coord` fixed
+    fn zoom_about(&mut self, tk: &mut dyn TkWindow, scale: f32, coord: Coord) -> bool {
+        let scale = scale.max(self.scale_limits.0).min(self.scale_limits.1);
+        if scale == self.scale {
+            return false;
+        }
+        // With v = coord - pos, the child point under coord is at
+        // (v + offset) / scale relative to pos; solve for the new offset.
+        let v = coord - self.core.rect.pos;
+        let k = scale / self.scale;
+        let f = |v: i32, off: i32| ((v + off) as f32 * k).round() as i32 - v;
+        self.scale = scale;
+        self.offset = self.clamp_offset(Coord(f(v.0, self.offset.0), f(v.1, self.offset.1)));
+        tk.redraw(self.id());
+        true
+    }
+
+    fn map_event(&self, event: Event) -> Event {
+        let delta = |d: Coord| {
+            let f = |x: i32| (x as f32 / self.scale).round() as i32;
+            Coord(f(d.0), f(d.1))
+        };
+        match event {
+            a @ Event::Action(_) | a @ Event::Identify | a @ Event::Key { .. } => a,
+            Event::PressStart { source, coord } => Event::PressStart {
+                source,
+                coord: self.to_child(coord),
+            },
+            Event::PressMove {
+                source,
+                coord,
+                delta: d,
+            } => Event::PressMove {
+                source,
+                coord: self.to_child(coord),
+                delta: delta(d),
+            },
+            Event::PressEnd {
+                source,
+                start_id,
+                end_id,
+                coord,
+            } => Event::PressEnd {
+                source,
+                start_id,
+                end_id,
+                coord: self.to_child(coord),
+            },
+            Event::CursorMove { coord } => Event::CursorMove {
+                coord: self.to_child(coord),
+            },
+            Event::DragHover { payload, coord } => Event::DragHover {
+                payload,
+                coord: self.to_child(coord),
+            },
+            Event::Drop { payload, coord } => Event::Drop {
+                payload,
+                coord: self.to_child(coord),
+            },
+        }
+    }
+}
+
+impl<W: Widget> Widget for Zoom<W> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let mut rules = layout::child_rules(&mut self.child, size_handle, axis);
+        if !axis.vertical() {
+            self.min_child_size.0 = rules.min_size();
+        } else {
+            self.min_child_size.1 = rules.min_size();
+        }
+        rules.reduce_min_to(size_handle.line_height(TextClass::Label));
+        rules
+    }
+
+    fn set_rect(&mut self, size_handle: &mut dyn SizeHandle, rect: Rect) {
+        self.core.rect = rect;
+        self.child_size = rect.size.max(self.min_child_size);
+        let size = self.child_size;
+        self.child.set_rect(
+            size_handle,
+            Rect {
+                pos: rect.pos,
+                size,
+            },
+        );
+        self.offset = self.clamp_offset(self.offset);
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, ev_mgr: &Manager) {
+        let (rect, offset) = (self.core.rect, self.offset);
+        draw_handle.zoom_region(rect, offset, self.scale, &mut |handle| {
+            self.child.draw(handle, ev_mgr)
+        });
+    }
+}
+
+impl<W: Widget + Handler> Handler for Zoom<W> {
+    type Msg = <W as Handler>::Msg;
+
+    fn handle(
+        &mut self,
+        tk: &mut dyn TkWindow,
+        addr: Address,
+        event: Event,
+    ) -> Response<Self::Msg> {
+        let (addr, focus) = match addr {
+            Address::Id(id) if id == self.id() => {
+                return match event {
+                    Event::PressMove { delta, .. } => {
+                        self.set_offset(tk, self.offset - delta);
+                        Response::None
+                    }
+                    Event::PressEnd { .. } => {
+                        // consume due to request
+                        Response::None
+                    }
+                    e => Response::Unhandled(e),
+                };
+            }
+            Address::Id(id) => (Address::Id(id), None),
+            Address::Coord(coord) => (Address::Coord(self.to_child(coord)), Some(coord)),
+        };
+
+        let event = self.map_event(event);
+        match self.child.handle(tk, addr, event) {
+            Response::Unhandled(Event::Action(Action::Scroll(delta))) => {
+                let lines = match delta {
+                    ScrollDelta::LineDelta(_, y) => y,
+                    ScrollDelta::PixelDelta(d) => d.1 as f32 / PIXELS_PER_LINE,
+                };
+                let coord = focus.unwrap_or(self.centre());
+                let scale = self.scale * WHEEL_FACTOR.powf(lines);
+                if self.zoom_about(tk, scale, coord) {
+                    Response::None
+                } else {
+                    Response::unhandled_action(Action::Scroll(delta))
+                }
+            }
+            Response::Unhandled(Event::Action(Action::Pinch(factor))) => {
+                let coord = focus.unwrap_or(self.centre());
+                if self.zoom_about(tk, self.scale * factor, coord) {
+                    Response::None
+                } else {
+                    Response::unhandled_action(Action::Pinch(factor))
+                }
+            }
+            Response::Unhandled(Event::PressStart { source, coord }) if source.is_primary() => {
+                // Map back to window coordinates for the grab
+                let coord = focus.unwrap_or(coord);
+                tk.data_mut().request_press_grab(source, self, coord);
+                Response::None
+            }
+            r => r,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Conformance tests of the standard widgets, via [`kas::test::check_widget`]
+
+use kas::draw::Colour;
+use kas::event::Handler;
+use kas::geom::{Coord, Size};
+use kas::layout::{Anchor, Horizontal, Vertical};
+use kas::test::check_widget;
+use kas::widget::*;
+use kas::Widget;
+
+fn check<W: Widget + Handler>(name: &str, mut widget: W) {
+    if let Err(violations) = check_widget(&mut widget) {
+        for violation in &violations {
+            eprintln!("{}: {}", name, violation);
+        }
+        panic!("{}: {} violation(s) found", name, violations.len());
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Settings {
+    visible: bool,
+    width: i64,
+}
+
+impl PropertySource for Settings {
+    fn len(&self) -> usize {
+        2
+    }
+    fn name(&self, index: usize) -> String {
+        ["Visible", "Width"][index].to_string()
+    }
+    fn get(&self, index: usize) -> PropertyValue {
+        match index {
+            0 => PropertyValue::Bool(self.visible),
+            _ => PropertyValue::Int(self.width),
+        }
+    }
+    fn set(&mut self, _: usize, _: PropertyValue) {}
+}
+
+#[test]
+fn ranges() {
+    check(
+        "Slider<u8>",
+        Slider::<u8, Horizontal>::new(0, 255, 1).with_value(200),
+    );
+    check("Slider<i8>", Slider::<i8, Horizontal>::new(-100, 100, 1));
+    check(
+        "Slider<i8> max",
+        Slider::<i8, Vertical>::new(-100, 100, 1).with_value(100),
+    );
+    check(
+        "Slider<i64>",
+        Slider::<i64, Horizontal>::new(i64::MIN, i64::MAX, 1),
+    );
+    check(
+        "Slider<f32>",
+        Slider::<f32, Horizontal>::new(-1.0, 1.0, 0.1),
+    );
+    check("Dial<i8>", Dial::<i8>::new(-100, 100, 1).with_value(-100));
+    check("Dial<i64>", Dial::<i64>::new(i64::MIN, i64::MAX, 1));
+    check(
+        "RangeSlider<i8>",
+        RangeSlider::<i8, Horizontal>::new(-100, 100, 1),
+    );
+    check(
+        "RangeSlider<i64>",
+        RangeSlider::<i64, Vertical>::new(i64::MIN, i64::MAX, 1),
+    );
+    check(
+        "SpinButton<i8>",
+        SpinButton::<i8>::new(-100, 100, 1).with_value(-100),
+    );
+    check(
+        "Rating",
+        Rating::new(5).with_half_steps(true).with_value(2.5),
+    );
+    check("LevelMeter", LevelMeter::<Horizontal>::new());
+}
+
+#[test]
+fn controls() {
+    check("Switch", Switch::new("Enable"));
+    check("CheckBox", CheckBox::new("Check"));
+    let group = RadioGroup::new();
+    check("RadioButton", RadioButton::new("Option", &group));
+    check(
+        "SegmentedControl",
+        SegmentedControl::new(vec!["One", "Two"]).with_selected(1),
+    );
+    check("Hyperlink", Hyperlink::new("link", "https://example.com"));
+    check("ShortcutEdit", ShortcutEdit::new(None));
+    check(
+        "ColourPicker",
+        ColourPicker::new(Colour::new(0.2, 0.4, 0.6)),
+    );
+    check("Calendar", Calendar::new());
+    check("DatePicker", DatePicker::new());
+    check("SearchBox", SearchBox::new(vec!["apple", "banana"]));
+    check("Spinner", Spinner::new());
+    check("Separator", Separator::<Horizontal>::new());
+}
+
+#[test]
+fn content() {
+    check("RichLabel", RichLabel::from_markup("some *bold* text"));
+    check("CodeEdit", CodeEdit::new("fn main() {}\n"));
+    check("FindBar", FindBar::new(EditBox::new("find me")));
+    check("CsvView", CsvView::from_str("a,b\n1,2\n", ','));
+    check("DiffView", DiffView::new("a\nb\n", "a\nc\n"));
+    check("LogView", LogView::new(100));
+    check("ListView", ListView::new(vec!["one", "two", "three"]));
+    check("IconView", IconView::new(vec!["one", "two", "three"]));
+    check("Table", Table::new(vec![vec!["a", "b"], vec!["c", "d"]]));
+    check(
+        "PropertyGrid",
+        PropertyGrid::new(Settings {
+            visible: true,
+            width: 80,
+        }),
+    );
+    let points = vec![(0.0, 1.0), (1.0, -1.0), (2.0, 0.5)];
+    let series = Series::line("series", Colour::new(1.0, 0.0, 0.0), points);
+    check("Plot", Plot::new().with_series(series));
+    check("Image", Image::from_rgba(Size(2, 2), vec![255; 16]));
+    check("Image loading", Image::loading(Size(16, 16)));
+    check("Canvas", Canvas::new(Size(16, 16), |_: &mut Painter| ()));
+    check("StatusBar", StatusBar::new().with_section("Ready"));
+}
+
+#[test]
+fn containers() {
+    let label = |text: &'static str| Label::from(text);
+    check(
+        "Expander",
+        Expander::new("More", label("content")).with_expanded(true),
+    );
+    check("GroupBox", GroupBox::new("Group", label("content")));
+    check(
+        "FlowBox",
+        FlowBox::new(vec![label("a"), label("b"), label("c")]),
+    );
+    check("Toolbar", Toolbar::new(vec![label("a"), label("b")]));
+    check("Zoom", Zoom::new(label("zoomed")));
+    check(
+        "Splitter",
+        Splitter::<Horizontal, _>::new(vec![label("a"), label("b")]),
+    );
+    check(
+        "TabbedStack",
+        TabbedStack::new()
+            .with_page("A", label("a"))
+            .with_page("B", label("b")),
+    );
+    check(
+        "SideNav",
+        SideNav::new()
+            .with_entry("*", "A", label("a"))
+            .with_entry("#", "B", label("b")),
+    );
+    check(
+        "DockLayout",
+        DockLayout::new()
+            .with_panel("A", label("a"))
+            .with_docked("B", label("b"), DockEdge::Left),
+    );
+    check(
+        "Overlay",
+        Overlay::new()
+            .with_child(Anchor::FILL, label("Inbox"))
+            .with_child(Anchor::TOP_RIGHT.with_offset(Coord(-2, 2)), label("3")),
+    );
+    check("Popover", Popover::new(label("trigger"), label("content")));
+    let menu = Menu::new("File").with_item(MenuItem::new("Open", 1u32));
+    check("MenuBar", MenuBar::new(vec![menu]));
+}
+
+#[test]
+fn empty() {
+    check("Label", Label::from(""));
+    check("Switch", Switch::new(""));
+    check("RichLabel", RichLabel::from_markup(""));
+    check("CodeEdit", CodeEdit::new(""));
+    check("CsvView", CsvView::from_str("", ','));
+    check("DiffView", DiffView::new("", ""));
+    check("LogView", LogView::new(10));
+    check("ListView", ListView::new(Vec::<String>::new()));
+    check("IconView", IconView::new(Vec::<String>::new()));
+    check("Table", Table::new(Vec::<Vec<String>>::new()));
+    check("Plot", Plot::new());
+    check("Image", Image::from_rgba(Size::ZERO, vec![]));
+    check(
+        "SegmentedControl",
+        SegmentedControl::new(Vec::<String>::new()),
+    );
+    check("SearchBox", SearchBox::new(Vec::<String>::new()));
+    check("StatusBar", StatusBar::new());
+    check("FlowBox", FlowBox::<Label>::new(vec![]));
+    check("Toolbar", Toolbar::<Label>::new(vec![]));
+    check("Splitter", Splitter::<Horizontal, Label>::new(vec![]));
+    check("TabbedStack", TabbedStack::<Label, ()>::new());
+    check("SideNav", SideNav::<Label, ()>::new());
+    check("DockLayout", DockLayout::<Label>::new());
+    check("Overlay", Overlay::<Label>::new());
+    check("MenuBar", MenuBar::<u32>::new(vec![]));
+}